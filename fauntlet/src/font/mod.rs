@@ -60,10 +60,12 @@ impl Hinting {
             Self::Interpreter(target) => HintingOptions {
                 engine: ::skrifa::outline::Engine::Interpreter,
                 target: target.to_skrifa_target(),
+                use_gasp: false,
             },
             Self::Auto(target) => HintingOptions {
                 engine: ::skrifa::outline::Engine::Auto(None),
                 target: target.to_skrifa_target(),
+                use_gasp: false,
             },
         }
     }
@@ -6,6 +6,7 @@ pub mod gpos;
 pub mod gsub;
 pub mod ift;
 pub mod layout;
+pub mod malformed;
 
 pub static CMAP12_FONT1: &[u8] = include_bytes!("../test_data/ttf/cmap12_font1.ttf");
 
@@ -0,0 +1,262 @@
+//! Intentionally malformed tables for robustness tests.
+//!
+//! Each case is identified by name so that the same data can be shared by
+//! tests in different crates. A case consists of one or more raw tables;
+//! [`MalformedCase::font_data`] wraps them in a font along with minimal
+//! (well formed) `head` and `maxp` tables unless the case provides its own.
+
+use read_fonts::{be_buffer, be_buffer_add, test_helpers::BeBuffer, types::Tag};
+use write_fonts::{
+    tables::{head::Head, maxp::Maxp},
+    FontBuilder,
+};
+
+/// A named set of malformed tables.
+pub struct MalformedCase {
+    /// The name used to look up this case.
+    pub name: &'static str,
+    /// A short description of what is wrong with the data.
+    pub description: &'static str,
+    build: fn() -> Vec<(Tag, BeBuffer)>,
+}
+
+impl MalformedCase {
+    /// Returns the raw tables for this case.
+    pub fn tables(&self) -> Vec<(Tag, BeBuffer)> {
+        (self.build)()
+    }
+
+    /// Returns the data for the table with the given tag, if this case
+    /// provides one.
+    pub fn table_data(&self, tag: Tag) -> Option<BeBuffer> {
+        self.tables()
+            .into_iter()
+            .find_map(|(table_tag, data)| (table_tag == tag).then_some(data))
+    }
+
+    /// Returns a font containing the tables for this case.
+    pub fn font_data(&self) -> Vec<u8> {
+        let tables = self.tables();
+        let mut builder = FontBuilder::new();
+        for (tag, data) in &tables {
+            builder.add_raw(*tag, data.as_slice().to_vec());
+        }
+        if !builder.contains(Tag::new(b"head")) {
+            builder
+                .add_table(&Head {
+                    units_per_em: 1000,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        if !builder.contains(Tag::new(b"maxp")) {
+            builder
+                .add_table(&Maxp {
+                    num_glyphs: 4,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        builder.build()
+    }
+}
+
+/// All malformed cases.
+pub static CASES: &[MalformedCase] = &[
+    MalformedCase {
+        name: "cmap_subtable_offset_out_of_bounds",
+        description: "cmap encoding record points past the end of the table",
+        build: cmap_subtable_offset_out_of_bounds,
+    },
+    MalformedCase {
+        name: "cmap_shared_subtable",
+        description: "two cmap encoding records point at the same subtable",
+        build: cmap_shared_subtable,
+    },
+    MalformedCase {
+        name: "cmap4_truncated_arrays",
+        description: "cmap format 4 segment count exceeds the subtable data",
+        build: cmap4_truncated_arrays,
+    },
+    MalformedCase {
+        name: "hhea_truncated",
+        description: "hhea table ends in the middle of the header",
+        build: hhea_truncated,
+    },
+    MalformedCase {
+        name: "hmtx_short",
+        description: "hmtx contains fewer long metrics than declared in hhea",
+        build: hmtx_short,
+    },
+    MalformedCase {
+        name: "glyf_loca_past_end",
+        description: "loca points past the end of the glyf table",
+        build: glyf_loca_past_end,
+    },
+    MalformedCase {
+        name: "glyf_truncated_points",
+        description: "simple glyph declares more points than it contains",
+        build: glyf_truncated_points,
+    },
+    MalformedCase {
+        name: "name_string_out_of_bounds",
+        description: "name record points past the end of string storage",
+        build: name_string_out_of_bounds,
+    },
+];
+
+/// Returns the malformed case with the given name.
+pub fn get(name: &str) -> Option<&'static MalformedCase> {
+    CASES.iter().find(|case| case.name == name)
+}
+
+const CMAP: Tag = Tag::new(b"cmap");
+const HEAD: Tag = Tag::new(b"head");
+const HHEA: Tag = Tag::new(b"hhea");
+const HMTX: Tag = Tag::new(b"hmtx");
+const GLYF: Tag = Tag::new(b"glyf");
+const LOCA: Tag = Tag::new(b"loca");
+const NAME: Tag = Tag::new(b"name");
+
+/// A cmap format 4 subtable mapping [0x41, 0x44] to glyphs [1, 4].
+fn cmap4_subtable(buf: BeBuffer) -> BeBuffer {
+    buf.extend([4u16, 32, 0]) // format, length, language
+        .extend([4u16, 4, 1, 0]) // segCountX2, searchRange, entrySelector, rangeShift
+        .extend([0x44u16, 0xFFFF]) // endCode
+        .push(0u16) // reservedPad
+        .extend([0x41u16, 0xFFFF]) // startCode
+        .extend([-0x40i16, 1]) // idDelta
+        .extend([0u16, 0]) // idRangeOffset
+}
+
+fn cmap_subtable_offset_out_of_bounds() -> Vec<(Tag, BeBuffer)> {
+    let buf = be_buffer! {
+        0u16,               // version
+        1u16,               // numTables
+        [3u16, 1],          // platformID, encodingID
+        {0u32: "offset"}    // subtableOffset
+    };
+    let mut buf = cmap4_subtable(buf.mark("subtable")).mark("end");
+    buf.write_offset_at::<u32>("offset", "end");
+    vec![(CMAP, buf)]
+}
+
+fn cmap_shared_subtable() -> Vec<(Tag, BeBuffer)> {
+    let buf = be_buffer! {
+        0u16,               // version
+        2u16,               // numTables
+        [0u16, 3],          // platformID, encodingID
+        {0u32: "offset0"},  // subtableOffset
+        [3u16, 1],          // platformID, encodingID
+        {0u32: "offset1"}   // subtableOffset
+    };
+    let mut buf = cmap4_subtable(buf.mark("subtable"));
+    buf.write_offset_at::<u32>("offset0", "subtable");
+    buf.write_offset_at::<u32>("offset1", "subtable");
+    vec![(CMAP, buf)]
+}
+
+fn cmap4_truncated_arrays() -> Vec<(Tag, BeBuffer)> {
+    let mut buf = be_buffer! {
+        0u16,               // version
+        1u16,               // numTables
+        [3u16, 1],          // platformID, encodingID
+        {0u32: "offset"}    // subtableOffset
+    }
+    .mark("subtable")
+    .extend([4u16, 32, 0]) // format, length, language
+    .extend([64u16, 64, 5, 0]) // segCountX2 claims 32 segments
+    .extend([0x44u16, 0xFFFF]) // endCode for two segments
+    .push(0u16) // reservedPad
+    .extend([0x41u16, 0xFFFF]); // startCode; no idDelta or idRangeOffset
+    buf.write_offset_at::<u32>("offset", "subtable");
+    vec![(CMAP, buf)]
+}
+
+fn hhea_truncated() -> Vec<(Tag, BeBuffer)> {
+    let buf = be_buffer! {
+        0x00010000u32,      // version
+        [800i16, -200, 0],  // ascender, descender, lineGap
+        1000u16             // advanceWidthMax
+    };
+    vec![(HHEA, buf)]
+}
+
+/// An hhea table declaring the given number of long metrics.
+fn hhea(number_of_long_metrics: u16) -> BeBuffer {
+    BeBuffer::new()
+        .push(0x00010000u32) // version
+        .extend([800i16, -200, 0]) // ascender, descender, lineGap
+        .push(1000u16) // advanceWidthMax
+        .extend([0i16, 0, 1000]) // minLsb, minRsb, xMaxExtent
+        .extend([1i16, 0, 0]) // caretSlopeRise, caretSlopeRun, caretOffset
+        .extend([0i16; 5]) // reserved, metricDataFormat
+        .push(number_of_long_metrics)
+}
+
+fn hmtx_short() -> Vec<(Tag, BeBuffer)> {
+    let hmtx = BeBuffer::new().extend([500u16, 50]);
+    vec![(HHEA, hhea(4)), (HMTX, hmtx)]
+}
+
+/// A head table using short loca offsets.
+fn head_short_loca() -> BeBuffer {
+    let head = Head {
+        units_per_em: 1000,
+        index_to_loc_format: 0,
+        ..Default::default()
+    };
+    BeBuffer::new().extend(write_fonts::dump_table(&head).unwrap())
+}
+
+/// Tables required to load outlines for four glyphs, other than glyf and
+/// loca.
+fn outline_support_tables() -> Vec<(Tag, BeBuffer)> {
+    let hmtx = BeBuffer::new().extend([500u16, 50].repeat(4));
+    vec![(HEAD, head_short_loca()), (HHEA, hhea(4)), (HMTX, hmtx)]
+}
+
+fn glyf_loca_past_end() -> Vec<(Tag, BeBuffer)> {
+    // glyph 0 is empty, glyph 1 has no contours and glyph 2 extends well
+    // past the end of the table
+    let glyf = be_buffer! {
+        0i16,                   // numberOfContours
+        [0i16, 0, 500, 500],    // xMin, yMin, xMax, yMax
+        0u16                    // instructionLength
+    };
+    // short offsets are stored divided by two
+    let loca = BeBuffer::new().extend([0u16, 0, 6, 50, 50]);
+    let mut tables = outline_support_tables();
+    tables.extend([(GLYF, glyf), (LOCA, loca)]);
+    tables
+}
+
+fn glyf_truncated_points() -> Vec<(Tag, BeBuffer)> {
+    let glyf = be_buffer! {
+        1i16,                   // numberOfContours
+        [0i16, 0, 500, 500],    // xMin, yMin, xMax, yMax
+        3u16,                   // endPtsOfContours[0]
+        0u16,                   // instructionLength
+        1u8,                    // flags[0]; the remaining flags and all
+        0u8                     // coordinates are missing
+    };
+    let loca = BeBuffer::new().extend([0u16, 8, 8, 8, 8]);
+    let mut tables = outline_support_tables();
+    tables.extend([(GLYF, glyf), (LOCA, loca)]);
+    tables
+}
+
+fn name_string_out_of_bounds() -> Vec<(Tag, BeBuffer)> {
+    let mut buf = be_buffer! {
+        0u16,                       // version
+        1u16,                       // count
+        {0u16: "storage_offset"},   // storageOffset
+        [3u16, 1, 0x409, 1],        // platformID, encodingID, languageID, nameID
+        100u16,                     // length
+        0u16                        // stringOffset
+    }
+    .mark("storage")
+    .extend([0x41u16, 0x62]); // "Ab" in UTF-16
+    buf.write_offset_at::<u16>("storage_offset", "storage");
+    vec![(NAME, buf)]
+}
@@ -0,0 +1,18 @@
+#![no_main]
+use std::error::Error;
+
+use libfuzzer_sys::fuzz_target;
+use read_fonts::test_helpers::exercise_font;
+
+mod helpers;
+use helpers::*;
+
+fn do_read_things(data: &[u8]) -> Result<(), Box<dyn Error>> {
+    let font = select_font(data)?;
+    exercise_font(&font);
+    Ok(())
+}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = do_read_things(data);
+});
@@ -0,0 +1,693 @@
+//! A client which drives the IFT extension algorithm.
+//!
+//! [`IftClient`] repeatedly selects patches with [`PatchGroup`], fetches them with a user
+//! supplied [`PatchFetcher`] and applies them until the font covers the requested subset
+//! definition. See: <https://w3c.github.io/IFT/Overview.html#extend-font-subset>
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    future::Future,
+};
+
+use read_fonts::FontRef;
+
+#[cfg(feature = "c-brotli")]
+use crate::font_patch::BuiltInBrotliDecoder;
+use crate::{
+    font_patch::{DecompressionLimits, PatchingError, SharedBrotliDecoder},
+    patch_group::{PatchGroup, UriStatus},
+    patchmap::SubsetDefinition,
+};
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Loads the data for patch URIs.
+pub trait PatchFetcher {
+    /// Fetch the patch data located at `uri`.
+    fn fetch(&self, uri: &str) -> impl Future<Output = Result<Vec<u8>, FetchError>>;
+}
+
+/// An error that occurred while fetching a patch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FetchError {
+    /// The fetch failed, but may succeed if retried (for example a network timeout).
+    Transient(String),
+    /// The fetch failed and should not be retried (for example the patch does not exist).
+    Permanent(String),
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FetchError::Transient(msg) => write!(f, "Transient fetch failure: {msg}"),
+            FetchError::Permanent(msg) => write!(f, "Fetch failed: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// The result of a call to [`IftClient::extend`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExtensionOutcome {
+    applied_uris: Vec<String>,
+    failed_uris: BTreeMap<String, FetchError>,
+}
+
+impl ExtensionOutcome {
+    /// The URIs which were applied, in the order they were fetched.
+    pub fn applied_uris(&self) -> &[String] {
+        &self.applied_uris
+    }
+
+    /// The URIs which could not be fetched along with the last error for each.
+    pub fn failed_uris(&self) -> &BTreeMap<String, FetchError> {
+        &self.failed_uris
+    }
+
+    /// Returns true if all needed patches were fetched and applied.
+    ///
+    /// If this is false the font has still been extended as far as possible with the patches
+    /// that could be fetched, but may not fully cover the requested subset definition.
+    pub fn is_complete(&self) -> bool {
+        self.failed_uris.is_empty()
+    }
+}
+
+/// Extends an IFT font by fetching and applying patches.
+///
+/// The client remembers which URIs have been applied and keeps any fetched patch data which
+/// wasn't yet needed, so successive calls to [`IftClient::extend`] don't refetch patches.
+pub struct IftClient<F> {
+    fetcher: F,
+    font: Vec<u8>,
+    patch_data: HashMap<String, UriStatus>,
+    max_attempts: u32,
+    limits: DecompressionLimits,
+    decoder: Box<dyn SharedBrotliDecoder + Send + Sync>,
+}
+
+impl<F: PatchFetcher> IftClient<F> {
+    /// Create a client for the IFT font `font`, which loads patches using `fetcher`.
+    #[cfg(feature = "c-brotli")]
+    pub fn new(font: Vec<u8>, fetcher: F) -> Self {
+        Self::with_decoder(font, fetcher, BuiltInBrotliDecoder)
+    }
+
+    /// Create a client for the IFT font `font`, which loads patches using `fetcher` and
+    /// decompresses them with `decoder`.
+    pub fn with_decoder(
+        font: Vec<u8>,
+        fetcher: F,
+        decoder: impl SharedBrotliDecoder + Send + Sync + 'static,
+    ) -> Self {
+        IftClient {
+            fetcher,
+            font,
+            patch_data: Default::default(),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            limits: Default::default(),
+            decoder: Box::new(decoder),
+        }
+    }
+
+    /// Set the number of times a fetch is attempted before giving up on a URI.
+    ///
+    /// Only [`FetchError::Transient`] failures are retried. Defaults to 3.
+    pub fn set_max_attempts(&mut self, max_attempts: u32) -> &mut Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set caps on how much data applying a single table keyed patch may decompress.
+    ///
+    /// A patch which exceeds the limits fails to apply. Defaults to no limits beyond those
+    /// declared in the patches.
+    pub fn set_decompression_limits(&mut self, limits: DecompressionLimits) -> &mut Self {
+        self.limits = limits;
+        self
+    }
+
+    /// The current bytes of the font.
+    pub fn font(&self) -> &[u8] {
+        &self.font
+    }
+
+    /// Consumes the client, returning the current bytes of the font.
+    pub fn into_font(self) -> Vec<u8> {
+        self.font
+    }
+
+    /// The URIs of the patches which may be needed to extend the font to `subset_definition`,
+    /// across all future rounds of extension, and which the client hasn't applied yet.
+    ///
+    /// These can be fetched ahead of time, for example to warm an HTTP cache. The URIs of the
+    /// next group of patches come first, followed by the other intersecting entries (see
+    /// [`PatchGroup::intersecting_uris`]). Applying an invalidating patch replaces the mapping
+    /// tables, so to find the URIs which follow it the invalidating patches that
+    /// [`IftClient::extend`] would select are fetched and applied to a copy of the font. The
+    /// fetched data is kept for later extension. If one of those fetches fails the URIs after it
+    /// can't be found, and the list stops there.
+    pub async fn preload_uris(
+        &mut self,
+        subset_definition: &SubsetDefinition,
+    ) -> Result<Vec<String>, PatchingError> {
+        let mut font = self.font.clone();
+        // Only the data of the invalidating patch being followed is needed, everything else in
+        // the scratch patch data is applied.
+        let mut scratch_data: HashMap<String, UriStatus> = self
+            .patch_data
+            .iter()
+            .filter(|(_, status)| matches!(status, UriStatus::Applied))
+            .map(|(uri, _)| (uri.clone(), UriStatus::Applied))
+            .collect();
+        let mut seen: HashSet<String> = Default::default();
+        let mut uris: Vec<String> = vec![];
+        loop {
+            let font_ref = FontRef::new(&font).map_err(PatchingError::FontParsingFailed)?;
+            for uri in PatchGroup::intersecting_uris(font_ref.clone(), subset_definition)
+                .map_err(PatchingError::FontParsingFailed)?
+            {
+                if !scratch_data.contains_key(&uri) && seen.insert(uri.clone()) {
+                    uris.push(uri);
+                }
+            }
+
+            // Only invalidating patches change the mapping tables, so once none are selected
+            // every remaining URI has been found.
+            let group = PatchGroup::select_next_patches(font_ref, subset_definition)
+                .map_err(PatchingError::FontParsingFailed)?;
+            let Some(uri) = group
+                .uris()
+                .find(|info| info.is_invalidating() && !scratch_data.contains_key(info.uri()))
+                .map(|info| info.uri().to_string())
+            else {
+                return Ok(uris);
+            };
+
+            let data = match self.patch_data.get(&uri) {
+                Some(UriStatus::Pending(data)) => data.clone(),
+                _ => match self.fetch(&uri).await {
+                    Ok(data) => {
+                        self.patch_data
+                            .insert(uri.clone(), UriStatus::Pending(data.clone()));
+                        data
+                    }
+                    Err(_) => return Ok(uris),
+                },
+            };
+            scratch_data.insert(uri, UriStatus::Pending(data));
+            font = group.apply_next_patches_with_decoder(
+                &mut scratch_data,
+                self.decoder.as_ref(),
+                &self.limits,
+            )?;
+        }
+    }
+
+    /// Extend the font to cover `subset_definition`.
+    ///
+    /// Patches are selected, fetched and applied until no more patches intersect
+    /// `subset_definition`. Fetches which fail are recorded in the returned outcome and the
+    /// remaining patches are still applied, so the font is extended as far as possible.
+    /// URIs which failed are not refetched during the same call.
+    ///
+    /// Returns an error if the font or a fetched patch is malformed, in which case the font
+    /// is left in the state from before the failed patch application.
+    pub async fn extend(
+        &mut self,
+        subset_definition: &SubsetDefinition,
+    ) -> Result<ExtensionOutcome, PatchingError> {
+        let mut outcome = ExtensionOutcome::default();
+        loop {
+            let font = FontRef::new(&self.font).map_err(PatchingError::FontParsingFailed)?;
+            let group = PatchGroup::select_next_patches(font, subset_definition)
+                .map_err(PatchingError::FontParsingFailed)?;
+
+            let uris: Vec<String> = group
+                .uris()
+                .map(|info| info.uri())
+                .filter(|uri| !matches!(self.patch_data.get(*uri), Some(UriStatus::Applied)))
+                .map(|uri| uri.to_string())
+                .collect();
+
+            for uri in &uris {
+                if self.patch_data.contains_key(uri) || outcome.failed_uris.contains_key(uri) {
+                    continue;
+                }
+                match self.fetch(uri).await {
+                    Ok(data) => {
+                        self.patch_data
+                            .insert(uri.clone(), UriStatus::Pending(data));
+                    }
+                    Err(err) => {
+                        outcome.failed_uris.insert(uri.clone(), err);
+                    }
+                }
+            }
+
+            if !uris
+                .iter()
+                .any(|uri| matches!(self.patch_data.get(uri), Some(UriStatus::Pending(_))))
+            {
+                // Every remaining patch in the group failed to fetch, so nothing can be applied
+                // and the font is left as is.
+                return Ok(outcome);
+            }
+
+            // Failed URIs are marked as applied for the duration of patch application so that
+            // they are skipped, allowing the rest of the group to be applied. At least one patch
+            // in the group is pending, so something is always applied.
+            let skipped: Vec<&String> = uris
+                .iter()
+                .filter(|uri| outcome.failed_uris.contains_key(*uri))
+                .collect();
+            for uri in &skipped {
+                self.patch_data.insert((*uri).clone(), UriStatus::Applied);
+            }
+            let result = group.apply_next_patches_with_decoder(
+                &mut self.patch_data,
+                self.decoder.as_ref(),
+                &self.limits,
+            );
+            for uri in &skipped {
+                self.patch_data.remove(*uri);
+            }
+
+            self.font = result?;
+            outcome.applied_uris.extend(
+                uris.into_iter()
+                    .filter(|uri| matches!(self.patch_data.get(uri), Some(UriStatus::Applied))),
+            );
+        }
+    }
+
+    async fn fetch(&self, uri: &str) -> Result<Vec<u8>, FetchError> {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match self.fetcher.fetch(uri).await {
+                Err(FetchError::Transient(_)) if attempts < self.max_attempts => continue,
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "c-brotli"))]
+mod tests {
+    use std::{
+        cell::RefCell,
+        future::ready,
+        pin::pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll, Waker},
+    };
+
+    use super::*;
+    use crate::font_patch::DecodeError;
+    use crate::glyph_keyed::tests::assemble_glyph_keyed_patch;
+    use font_test_data::ift::{
+        glyf_u16_glyph_patches, glyph_keyed_patch_header, table_keyed_format2, table_keyed_patch,
+        test_font_for_patching_with_loca_mod,
+    };
+    use font_types::{Int24, Tag};
+    use read_fonts::tables::ift::{IFTX_TAG, IFT_TAG};
+    use write_fonts::FontBuilder;
+
+    /// Runs a future which never waits to completion.
+    fn block_on<T>(future: impl Future<Output = T>) -> T {
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(result) = future.as_mut().poll(&mut context) {
+                return result;
+            }
+        }
+    }
+
+    /// Serves patches from memory, failing transiently a set number of times per uri.
+    #[derive(Default)]
+    struct TestFetcher {
+        patches: HashMap<String, Vec<u8>>,
+        transient_failures: RefCell<HashMap<String, u32>>,
+        fetches: RefCell<Vec<String>>,
+    }
+
+    impl PatchFetcher for TestFetcher {
+        fn fetch(&self, uri: &str) -> impl Future<Output = Result<Vec<u8>, FetchError>> {
+            self.fetches.borrow_mut().push(uri.to_string());
+            if let Some(remaining) = self.transient_failures.borrow_mut().get_mut(uri) {
+                if *remaining > 0 {
+                    *remaining -= 1;
+                    return ready(Err(FetchError::Transient("timeout".to_string())));
+                }
+            }
+            ready(
+                self.patches
+                    .get(uri)
+                    .cloned()
+                    .ok_or(FetchError::Permanent("not found".to_string())),
+            )
+        }
+    }
+
+    /// A font with two glyph keyed patches, "foo/04" from IFT and "foo/08" from IFTX.
+    fn test_font() -> Vec<u8> {
+        let mut ift_builder = table_keyed_format2();
+        ift_builder.write_at("encoding", 3u8);
+        ift_builder.write_at("compat_id[0]", 6u32);
+        ift_builder.write_at("compat_id[1]", 7u32);
+        ift_builder.write_at("compat_id[2]", 8u32);
+        ift_builder.write_at("compat_id[3]", 9u32);
+
+        let mut iftx_builder = table_keyed_format2();
+        iftx_builder.write_at("encoding", 3u8);
+        iftx_builder.write_at("compat_id[0]", 7u32);
+        iftx_builder.write_at("compat_id[1]", 7u32);
+        iftx_builder.write_at("compat_id[2]", 8u32);
+        iftx_builder.write_at("compat_id[3]", 9u32);
+        iftx_builder.write_at("id_delta", Int24::new(1));
+
+        test_font_for_patching_with_loca_mod(
+            |_| {},
+            HashMap::from([
+                (IFT_TAG, ift_builder.as_slice()),
+                (IFTX_TAG, iftx_builder.as_slice()),
+            ]),
+        )
+    }
+
+    fn test_fetcher() -> TestFetcher {
+        let patch1 =
+            assemble_glyph_keyed_patch(glyph_keyed_patch_header(), glyf_u16_glyph_patches());
+
+        let mut patch2 = glyf_u16_glyph_patches();
+        patch2.write_at("gid_13", 14u16);
+        let mut header = glyph_keyed_patch_header();
+        header.write_at("compatibility_id", 7u32);
+        let patch2 = assemble_glyph_keyed_patch(header, patch2);
+
+        TestFetcher {
+            patches: HashMap::from([
+                ("foo/04".to_string(), patch1.as_slice().to_vec()),
+                ("foo/08".to_string(), patch2.as_slice().to_vec()),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    fn glyf(font: &[u8]) -> Vec<u8> {
+        FontRef::new(font)
+            .unwrap()
+            .table_data(Tag::new(b"glyf"))
+            .unwrap()
+            .as_bytes()
+            .to_vec()
+    }
+
+    fn subset() -> SubsetDefinition {
+        SubsetDefinition::codepoints([5].into_iter().collect())
+    }
+
+    #[test]
+    fn extend_applies_all_patches() {
+        let mut client = IftClient::new(test_font(), test_fetcher());
+        let outcome = block_on(client.extend(&subset())).unwrap();
+
+        assert!(outcome.is_complete());
+        assert_eq!(outcome.applied_uris(), &["foo/04", "foo/08"]);
+        assert_eq!(
+            glyf(client.font()),
+            vec![
+                1, 2, 3, 4, 5, 0, // gid 0
+                6, 7, 8, 0, // gid 1
+                b'a', b'b', b'c', 0, // gid2
+                b'd', b'e', b'f', b'g', // gid 7
+                b'h', b'i', b'j', b'k', b'l', 0, // gid 8 + 9
+                b'm', b'n', // gid 13
+                b'm', b'n', // gid 14
+            ]
+        );
+
+        // Extending again has nothing left to do.
+        let outcome = block_on(client.extend(&subset())).unwrap();
+        assert!(outcome.is_complete());
+        assert!(outcome.applied_uris().is_empty());
+        assert_eq!(client.fetcher.fetches.borrow().len(), 2);
+    }
+
+    #[test]
+    fn preload_uris() {
+        let mut client = IftClient::new(test_font(), test_fetcher());
+        assert_eq!(
+            block_on(client.preload_uris(&subset())).unwrap(),
+            vec!["foo/04", "foo/08"]
+        );
+        // Glyph keyed patches don't need to be fetched to find the other uris.
+        assert!(client.fetcher.fetches.borrow().is_empty());
+
+        block_on(client.extend(&subset())).unwrap();
+        assert!(block_on(client.preload_uris(&subset())).unwrap().is_empty());
+    }
+
+    #[cfg(feature = "encoder")]
+    #[test]
+    fn preload_uris_follows_invalidating_patches() {
+        use crate::encoder::{encode, PatchEncoding, SegmentationPlan};
+        use read_fonts::tables::ift::CompatibilityId;
+        use skrifa::charmap::Charmap;
+
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let codepoints: Vec<u32> = Charmap::new(&font)
+            .mappings()
+            .map(|(codepoint, _)| codepoint)
+            .take(3)
+            .collect();
+        let mut plan = SegmentationPlan::new(SubsetDefinition::codepoints(
+            [codepoints[0]].into_iter().collect(),
+        ));
+        plan.add_segment(SubsetDefinition::codepoints(
+            [codepoints[1]].into_iter().collect(),
+        ))
+        .add_segment(SubsetDefinition::codepoints(
+            [codepoints[2]].into_iter().collect(),
+        ))
+        .set_patch_encoding(PatchEncoding::TableKeyed);
+        let encoded = encode(&font, &plan, "patches/{id}", CompatibilityId::new([1; 16])).unwrap();
+        let fetcher = TestFetcher {
+            patches: encoded.patches().clone().into_iter().collect(),
+            ..Default::default()
+        };
+        let subset = SubsetDefinition::codepoints(codepoints[1..].iter().copied().collect());
+
+        // The initial font only has the patches which load one of the segments, the patch which
+        // then loads the other segment is listed by the font produced by the first patch.
+        let initial_font = FontRef::new(encoded.initial_font()).unwrap();
+        let initial_uris = PatchGroup::intersecting_uris(initial_font, &subset).unwrap();
+        assert_eq!(initial_uris.len(), 2);
+
+        let mut client = IftClient::new(encoded.initial_font().to_vec(), fetcher);
+        let uris = block_on(client.preload_uris(&subset)).unwrap();
+        assert_eq!(uris.len(), 3);
+        assert_eq!(uris[..2], initial_uris);
+        assert!(uris.iter().all(|uri| encoded.patches().contains_key(uri)));
+
+        // The invalidating patches were fetched to follow them, and aren't fetched again.
+        assert_eq!(client.fetcher.fetches.borrow().len(), 2);
+        let outcome = block_on(client.extend(&subset)).unwrap();
+        assert_eq!(outcome.applied_uris().len(), 2);
+        assert_eq!(client.fetcher.fetches.borrow().len(), 2);
+        assert!(block_on(client.preload_uris(&subset)).unwrap().is_empty());
+    }
+
+    /// Delegates to the built in decoder, counting the number of decode calls.
+    struct CountingDecoder(Arc<AtomicUsize>);
+
+    impl SharedBrotliDecoder for CountingDecoder {
+        fn decode(
+            &self,
+            encoded: &[u8],
+            shared_dictionary: Option<&[u8]>,
+            max_uncompressed_length: usize,
+        ) -> Result<Vec<u8>, DecodeError> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            BuiltInBrotliDecoder.decode(encoded, shared_dictionary, max_uncompressed_length)
+        }
+    }
+
+    #[test]
+    fn extend_with_custom_decoder() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let mut client =
+            IftClient::with_decoder(test_font(), test_fetcher(), CountingDecoder(count.clone()));
+        let outcome = block_on(client.extend(&subset())).unwrap();
+
+        assert!(outcome.is_complete());
+        assert_eq!(outcome.applied_uris(), &["foo/04", "foo/08"]);
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn extend_nothing_to_do() {
+        let font = test_font();
+        let mut client = IftClient::new(font.clone(), test_fetcher());
+        let outcome = block_on(client.extend(&SubsetDefinition::codepoints(
+            [0x1234].into_iter().collect(),
+        )))
+        .unwrap();
+        assert!(outcome.is_complete());
+        assert!(outcome.applied_uris().is_empty());
+        assert_eq!(client.into_font(), font);
+    }
+
+    #[test]
+    fn extend_retries_transient_failures() {
+        let fetcher = test_fetcher();
+        fetcher
+            .transient_failures
+            .borrow_mut()
+            .insert("foo/08".to_string(), 2);
+        let mut client = IftClient::new(test_font(), fetcher);
+        let outcome = block_on(client.extend(&subset())).unwrap();
+
+        assert!(outcome.is_complete());
+        assert_eq!(outcome.applied_uris(), &["foo/04", "foo/08"]);
+        assert_eq!(
+            *client.fetcher.fetches.borrow(),
+            vec!["foo/04", "foo/08", "foo/08", "foo/08"]
+        );
+    }
+
+    #[test]
+    fn extend_partial_failure() {
+        let fetcher = test_fetcher();
+        fetcher
+            .transient_failures
+            .borrow_mut()
+            .insert("foo/08".to_string(), 3);
+        let mut client = IftClient::new(test_font(), fetcher);
+        client.set_max_attempts(2);
+        let outcome = block_on(client.extend(&subset())).unwrap();
+
+        assert!(!outcome.is_complete());
+        assert_eq!(outcome.applied_uris(), &["foo/04"]);
+        assert_eq!(
+            outcome.failed_uris(),
+            &BTreeMap::from([(
+                "foo/08".to_string(),
+                FetchError::Transient("timeout".to_string())
+            )])
+        );
+        // the patch which could be fetched was still applied.
+        assert_eq!(
+            glyf(client.font()),
+            vec![
+                1, 2, 3, 4, 5, 0, // gid 0
+                6, 7, 8, 0, // gid 1
+                b'a', b'b', b'c', 0, // gid2
+                b'd', b'e', b'f', b'g', // gid 7
+                b'h', b'i', b'j', b'k', b'l', 0, // gid 8 + 9
+                b'm', b'n', // gid 13
+            ]
+        );
+
+        // A later call tries the failed uri again.
+        let outcome = block_on(client.extend(&subset())).unwrap();
+        assert!(outcome.is_complete());
+        assert_eq!(outcome.applied_uris(), &["foo/08"]);
+    }
+
+    #[test]
+    fn extend_permanent_failure_not_retried() {
+        let mut fetcher = test_fetcher();
+        fetcher.patches.clear();
+        let font = test_font();
+        let mut client = IftClient::new(font.clone(), fetcher);
+        let outcome = block_on(client.extend(&subset())).unwrap();
+
+        assert!(!outcome.is_complete());
+        assert!(outcome.applied_uris().is_empty());
+        assert_eq!(outcome.failed_uris().len(), 2);
+        assert_eq!(*client.fetcher.fetches.borrow(), vec!["foo/04", "foo/08"]);
+        assert_eq!(client.font(), font);
+    }
+
+    #[test]
+    fn extend_invalid_patch() {
+        let mut fetcher = test_fetcher();
+        fetcher.patches.insert("foo/04".to_string(), vec![1, 2, 3]);
+        let font = test_font();
+        let mut client = IftClient::new(font.clone(), fetcher);
+
+        assert!(block_on(client.extend(&subset())).is_err());
+        assert_eq!(client.font(), font);
+    }
+
+    /// A font where both the IFT and IFTX tables map codepoint 5 to a partially invalidating
+    /// table keyed patch, "foo/04" and "foo/08" respectively.
+    fn partial_invalidation_font() -> Vec<u8> {
+        let mut ift_builder = table_keyed_format2();
+        ift_builder.write_at("encoding", 2u8);
+        ift_builder.write_at("compat_id[0]", 6u32);
+
+        let mut iftx_builder = table_keyed_format2();
+        iftx_builder.write_at("encoding", 2u8);
+        iftx_builder.write_at("id_delta", Int24::new(1));
+
+        let mut font_builder = FontBuilder::new();
+        font_builder
+            .add_raw(IFT_TAG, ift_builder.as_slice())
+            .add_raw(IFTX_TAG, iftx_builder.as_slice())
+            .add_raw(Tag::new(b"tab1"), "abcdef\n".as_bytes())
+            .add_raw(Tag::new(b"tab2"), "foobar\n".as_bytes());
+        font_builder.build()
+    }
+
+    #[test]
+    fn extend_only_invalidating_patch_fails() {
+        let mut ift_builder = table_keyed_format2();
+        ift_builder.write_at("encoding", 2u8);
+        let mut font_builder = FontBuilder::new();
+        font_builder
+            .add_raw(IFT_TAG, ift_builder.as_slice())
+            .add_raw(Tag::new(b"tab1"), "abcdef\n".as_bytes());
+        let font = font_builder.build();
+
+        let mut client = IftClient::new(font.clone(), TestFetcher::default());
+        let outcome = block_on(client.extend(&subset())).unwrap();
+
+        assert!(!outcome.is_complete());
+        assert!(outcome.applied_uris().is_empty());
+        assert_eq!(
+            outcome.failed_uris().keys().collect::<Vec<_>>(),
+            vec!["foo/04"]
+        );
+        assert_eq!(client.font(), font);
+    }
+
+    #[test]
+    fn extend_skips_failed_invalidating_patch() {
+        let fetcher = TestFetcher {
+            patches: HashMap::from([(
+                "foo/08".to_string(),
+                table_keyed_patch().as_slice().to_vec(),
+            )]),
+            ..Default::default()
+        };
+        let font = partial_invalidation_font();
+        let mut client = IftClient::new(font.clone(), fetcher);
+        let outcome = block_on(client.extend(&subset())).unwrap();
+
+        assert!(!outcome.is_complete());
+        assert_eq!(outcome.applied_uris(), &["foo/08"]);
+        assert_eq!(
+            outcome.failed_uris().keys().collect::<Vec<_>>(),
+            vec!["foo/04"]
+        );
+        assert_ne!(client.font(), font);
+    }
+}
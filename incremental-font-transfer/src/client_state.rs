@@ -0,0 +1,408 @@
+//! Persistence of IFT client state.
+//!
+//! Running the extension algorithm (<https://w3c.github.io/IFT/Overview.html#extend-font-subset>)
+//! requires a client to remember which patch URIs have already been applied and which subset
+//! definition the font has been extended to cover so far. [`ClientState`] captures this
+//! information along with a hash of the current font bytes so that it can be saved and later
+//! restored, allowing extension to resume across sessions without refetching patches.
+
+use std::collections::{BTreeSet, HashMap};
+
+use font_types::{Fixed, Tag};
+use read_fonts::{collections::IntSet, FontData, ReadError};
+
+use crate::{patch_group::UriStatus, patchmap::SubsetDefinition};
+
+const VERSION: u8 = 1;
+
+/// The state of an IFT client extending a single font.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientState {
+    font_hash: u64,
+    subset_definition: SubsetDefinition,
+    applied_uris: BTreeSet<String>,
+}
+
+impl ClientState {
+    /// Create a new state for a client which currently holds `font_bytes`.
+    pub fn new(font_bytes: &[u8]) -> ClientState {
+        ClientState {
+            font_hash: font_hash(font_bytes),
+            subset_definition: Default::default(),
+            applied_uris: Default::default(),
+        }
+    }
+
+    /// Returns true if `font_bytes` are the font bytes this state was recorded against.
+    pub fn matches_font(&self, font_bytes: &[u8]) -> bool {
+        self.font_hash == font_hash(font_bytes)
+    }
+
+    /// Returns the hash of the font bytes this state was recorded against.
+    pub fn font_hash(&self) -> u64 {
+        self.font_hash
+    }
+
+    /// Record that the client now holds `font_bytes` (for example after applying patches).
+    pub fn set_font(&mut self, font_bytes: &[u8]) {
+        self.font_hash = font_hash(font_bytes);
+    }
+
+    /// The accumulated subset definition the font has been extended to cover.
+    pub fn subset_definition(&self) -> &SubsetDefinition {
+        &self.subset_definition
+    }
+
+    /// Add `subset_definition` to the accumulated subset definition.
+    pub fn add_subset_definition(&mut self, subset_definition: &SubsetDefinition) {
+        self.subset_definition.union(subset_definition);
+    }
+
+    /// Returns an iterator over the URIs which have been applied, in sorted order.
+    pub fn applied_uris(&self) -> impl Iterator<Item = &str> {
+        self.applied_uris.iter().map(|uri| uri.as_str())
+    }
+
+    /// Returns true if `uri` has been recorded as applied.
+    pub fn is_applied(&self, uri: &str) -> bool {
+        self.applied_uris.contains(uri)
+    }
+
+    /// Record all URIs marked as [`UriStatus::Applied`] in `patch_data` as applied.
+    pub fn record_applied(&mut self, patch_data: &HashMap<String, UriStatus>) {
+        self.applied_uris.extend(
+            patch_data
+                .iter()
+                .filter(|(_, status)| **status == UriStatus::Applied)
+                .map(|(uri, _)| uri.clone()),
+        );
+    }
+
+    /// Returns patch data with all applied URIs marked as [`UriStatus::Applied`].
+    ///
+    /// This can be passed to [`crate::patch_group::PatchGroup::apply_next_patches`] so previously
+    /// applied URIs are not refetched.
+    pub fn patch_data(&self) -> HashMap<String, UriStatus> {
+        self.applied_uris
+            .iter()
+            .map(|uri| (uri.clone(), UriStatus::Applied))
+            .collect()
+    }
+
+    /// Serialize this state to bytes.
+    ///
+    /// All values are big endian. The format is:
+    ///
+    /// ```text
+    /// uint8    version
+    /// uint64   font hash
+    /// uint32   codepoints length
+    /// uint8    codepoints[codepoints length]   (IntSet serialization)
+    /// uint16   feature tag count
+    /// Tag      feature tags[feature tag count]
+    /// uint16   axis count
+    /// {
+    ///   Tag    axis tag
+    ///   uint16 segment count
+    ///   Fixed  segments[segment count][2]      (start, end)
+    /// }[axis count]
+    /// uint32   applied uri count
+    /// {
+    ///   uint16 length
+    ///   uint8  utf8 bytes[length]
+    /// }[applied uri count]
+    /// ```
+    ///
+    /// Fails if any of the counts or lengths don't fit in their field, for example if an applied
+    /// uri is longer than 65535 bytes.
+    pub fn serialize(&self) -> Result<Vec<u8>, SerializationError> {
+        let mut out = vec![VERSION];
+        out.extend(self.font_hash.to_be_bytes());
+
+        let codepoints = self.subset_definition.codepoints.serialize();
+        out.extend(checked_len::<u32>(codepoints.len(), "codepoint set")?.to_be_bytes());
+        out.extend(codepoints);
+
+        let feature_tags = &self.subset_definition.feature_tags;
+        out.extend(checked_len::<u16>(feature_tags.len(), "feature tag count")?.to_be_bytes());
+        for tag in feature_tags {
+            out.extend(tag.to_be_bytes());
+        }
+
+        let mut axes: Vec<_> = self.subset_definition.design_space.iter().collect();
+        axes.sort_by_key(|(tag, _)| **tag);
+        out.extend(checked_len::<u16>(axes.len(), "axis count")?.to_be_bytes());
+        for (tag, segments) in axes {
+            out.extend(tag.to_be_bytes());
+            let segment_count = checked_len::<u16>(segments.iter().count(), "segment count")?;
+            out.extend(segment_count.to_be_bytes());
+            for segment in segments.iter() {
+                out.extend(segment.start().to_be_bytes());
+                out.extend(segment.end().to_be_bytes());
+            }
+        }
+
+        let uri_count = checked_len::<u32>(self.applied_uris.len(), "applied uri count")?;
+        out.extend(uri_count.to_be_bytes());
+        for uri in &self.applied_uris {
+            out.extend(checked_len::<u16>(uri.len(), "uri length")?.to_be_bytes());
+            out.extend(uri.as_bytes());
+        }
+
+        Ok(out)
+    }
+
+    /// Restore a state previously produced by [`ClientState::serialize`].
+    pub fn deserialize(data: &[u8]) -> Result<ClientState, ReadError> {
+        let mut reader = Reader {
+            data: FontData::new(data),
+            offset: 0,
+        };
+
+        if reader.read::<u8>()? != VERSION {
+            return Err(ReadError::MalformedData(
+                "Unsupported client state version.",
+            ));
+        }
+        let font_hash = u64::from_be_bytes(
+            reader
+                .read_bytes(8)?
+                .try_into()
+                .map_err(|_| ReadError::OutOfBounds)?,
+        );
+
+        let codepoints_len = reader.read::<u32>()? as usize;
+        let codepoints = IntSet::<u32>::deserialize(reader.read_bytes(codepoints_len)?)
+            .map_err(|_| ReadError::MalformedData("Invalid codepoint set in client state."))?;
+
+        let feature_tag_count = reader.read::<u16>()?;
+        let feature_tags = (0..feature_tag_count)
+            .map(|_| reader.read::<Tag>())
+            .collect::<Result<BTreeSet<_>, _>>()?;
+
+        let axis_count = reader.read::<u16>()?;
+        let mut design_space = HashMap::new();
+        for _ in 0..axis_count {
+            let tag = reader.read::<Tag>()?;
+            let segment_count = reader.read::<u16>()?;
+            let segments = (0..segment_count)
+                .map(|_| Ok(reader.read::<Fixed>()?..=reader.read::<Fixed>()?))
+                .collect::<Result<_, ReadError>>()?;
+            design_space.insert(tag, segments);
+        }
+
+        let uri_count = reader.read::<u32>()?;
+        let mut applied_uris = BTreeSet::new();
+        for _ in 0..uri_count {
+            let len = reader.read::<u16>()? as usize;
+            let uri = std::str::from_utf8(reader.read_bytes(len)?)
+                .map_err(|_| ReadError::MalformedData("Invalid utf8 in client state uri."))?;
+            applied_uris.insert(uri.to_string());
+        }
+
+        if reader.offset != data.len() {
+            return Err(ReadError::MalformedData(
+                "Unexpected trailing data in client state.",
+            ));
+        }
+
+        Ok(ClientState {
+            font_hash,
+            subset_definition: SubsetDefinition::new(codepoints, feature_tags, design_space),
+            applied_uris,
+        })
+    }
+}
+
+/// An error that occurs while serializing a [`ClientState`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SerializationError {
+    /// A count or length is too large to be stored in its field.
+    Overflow(&'static str),
+}
+
+impl std::fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SerializationError::Overflow(field) => {
+                write!(f, "Client state {field} is too large to serialize.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SerializationError {}
+
+fn checked_len<T: TryFrom<usize>>(
+    len: usize,
+    field: &'static str,
+) -> Result<T, SerializationError> {
+    T::try_from(len).map_err(|_| SerializationError::Overflow(field))
+}
+
+struct Reader<'a> {
+    data: FontData<'a>,
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read<T: font_types::Scalar>(&mut self) -> Result<T, ReadError> {
+        let value = self.data.read_at::<T>(self.offset)?;
+        self.offset += std::mem::size_of::<T::Raw>();
+        Ok(value)
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], ReadError> {
+        let bytes = self
+            .data
+            .slice(self.offset..self.offset + len)
+            .ok_or(ReadError::OutOfBounds)?;
+        self.offset += len;
+        Ok(bytes.as_bytes())
+    }
+}
+
+/// 64 bit FNV-1a hash of the font bytes.
+///
+/// This is stable across platforms and releases so it's suitable for persisting.
+fn font_hash(font_bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    font_bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use read_fonts::collections::RangeSet;
+
+    fn test_state() -> ClientState {
+        let mut state = ClientState::new(b"font bytes");
+        let codepoints: IntSet<u32> = [0x41, 0x42, 0x1F600].into();
+        let feature_tags: BTreeSet<Tag> = [Tag::new(b"liga"), Tag::new(b"smcp")].into();
+        let mut design_space: HashMap<Tag, RangeSet<Fixed>> = Default::default();
+        design_space.insert(
+            Tag::new(b"wght"),
+            [
+                Fixed::from_i32(100)..=Fixed::from_i32(200),
+                Fixed::from_i32(700)..=Fixed::from_i32(900),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        design_space.insert(
+            Tag::new(b"wdth"),
+            [Fixed::from_f64(87.5)..=Fixed::from_i32(100)]
+                .into_iter()
+                .collect(),
+        );
+        state.add_subset_definition(&SubsetDefinition::new(
+            codepoints,
+            feature_tags,
+            design_space,
+        ));
+
+        let mut patch_data: HashMap<String, UriStatus> = Default::default();
+        patch_data.insert("//foo.bar/01".to_string(), UriStatus::Applied);
+        patch_data.insert("//foo.bar/02".to_string(), UriStatus::Pending(vec![1, 2]));
+        patch_data.insert("//foo.bar/03".to_string(), UriStatus::Applied);
+        state.record_applied(&patch_data);
+        state
+    }
+
+    #[test]
+    fn round_trip() {
+        let state = test_state();
+        let bytes = state.serialize().unwrap();
+        assert_eq!(ClientState::deserialize(&bytes), Ok(state));
+    }
+
+    #[test]
+    fn round_trip_empty() {
+        let state = ClientState::new(&[]);
+        let bytes = state.serialize().unwrap();
+        assert_eq!(ClientState::deserialize(&bytes), Ok(state));
+    }
+
+    #[test]
+    fn serialize_overflow() {
+        let mut state = ClientState::new(&[]);
+        let long_uri = "a".repeat(u16::MAX as usize + 1);
+        let mut patch_data: HashMap<String, UriStatus> = Default::default();
+        patch_data.insert(long_uri, UriStatus::Applied);
+        state.record_applied(&patch_data);
+        assert_eq!(
+            state.serialize(),
+            Err(SerializationError::Overflow("uri length"))
+        );
+
+        let mut state = ClientState::new(&[]);
+        let feature_tags: BTreeSet<Tag> = (0..=u16::MAX as u32)
+            .map(|i| Tag::from_u32(i + 1))
+            .collect();
+        state.add_subset_definition(&SubsetDefinition::new(
+            Default::default(),
+            feature_tags,
+            Default::default(),
+        ));
+        assert_eq!(
+            state.serialize(),
+            Err(SerializationError::Overflow("feature tag count"))
+        );
+    }
+
+    #[test]
+    fn applied_uris() {
+        let state = test_state();
+        assert_eq!(
+            state.applied_uris().collect::<Vec<_>>(),
+            vec!["//foo.bar/01", "//foo.bar/03"]
+        );
+        assert!(state.is_applied("//foo.bar/01"));
+        assert!(!state.is_applied("//foo.bar/02"));
+
+        let patch_data = state.patch_data();
+        assert_eq!(patch_data.len(), 2);
+        assert_eq!(patch_data.get("//foo.bar/03"), Some(&UriStatus::Applied));
+    }
+
+    #[test]
+    fn font_matching() {
+        let mut state = test_state();
+        assert!(state.matches_font(b"font bytes"));
+        assert!(!state.matches_font(b"other font bytes"));
+
+        state.set_font(b"other font bytes");
+        assert!(state.matches_font(b"other font bytes"));
+        assert!(!state.matches_font(b"font bytes"));
+    }
+
+    #[test]
+    fn accumulates_subset_definition() {
+        let mut state = test_state();
+        state.add_subset_definition(&SubsetDefinition::codepoints([0x43].into()));
+        assert_eq!(
+            state.subset_definition().codepoints,
+            [0x41, 0x42, 0x43, 0x1F600].into()
+        );
+        assert_eq!(state.subset_definition().feature_tags.len(), 2);
+    }
+
+    #[test]
+    fn invalid_data() {
+        let bytes = test_state().serialize().unwrap();
+
+        assert!(ClientState::deserialize(&[]).is_err());
+        assert!(ClientState::deserialize(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut bad_version = bytes.clone();
+        bad_version[0] = 2;
+        assert!(ClientState::deserialize(&bad_version).is_err());
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(ClientState::deserialize(&trailing).is_err());
+    }
+}
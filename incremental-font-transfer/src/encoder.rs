@@ -0,0 +1,1804 @@
+//! Encodes a font into an incremental font transfer font and a set of patches.
+//!
+//! This is the server side half of incremental font transfer: given a complete font and a
+//! segmentation plan it produces the initial font and the patch files that the client
+//! side of this crate consumes.
+//!
+//! The initial font keeps every table of the input font, but the glyph data (glyf and gvar, or
+//! the charstrings in CFF and CFF2) of glyphs which are only needed by segments is removed. The
+//! segments are then loaded by one of two kinds of patches, selected with
+//! [`SegmentationPlan::set_patch_encoding`]:
+//!
+//! * [`PatchEncoding::GlyphKeyed`]: each segment becomes a glyph keyed patch
+//!   (<https://w3c.github.io/IFT/Overview.html#glyph-keyed>) which restores the data for the
+//!   glyphs it needs. The patches can be applied in any order.
+//! * [`PatchEncoding::TableKeyed`]: each combination of loaded segments is a distinct font, and
+//!   fully invalidating table keyed patches
+//!   (<https://w3c.github.io/IFT/Overview.html#table-keyed>) replace the glyph data tables of
+//!   one combination with those of the combination that has one more segment loaded.
+//!
+//! In both cases the patches are listed in a format 2 patch map
+//! (<https://w3c.github.io/IFT/Overview.html#patch-map-format-2>) stored in the 'IFT ' table.
+//!
+//! Glyphs are associated with a segment by mapping its codepoints through the cmap and then
+//! following composite glyph components. Glyphs which aren't reachable from any segment this way
+//! (for example, glyphs only reachable through layout substitutions or CFF seac accents) are kept
+//! in the initial font. Since patches can only replace charstrings at the end of a CFF or CFF2
+//! table, those tables are rewritten so that the CharStrings INDEX is the last data in the table.
+//!
+//! Helpers for generating compatibility ids, and for assigning new ones to the mapping tables of a
+//! font produced by an invalidating patch, are also provided.
+
+use std::collections::{hash_map::RandomState, BTreeMap, BTreeSet};
+use std::hash::BuildHasher;
+use std::io::Write;
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use brotlic::CompressorWriter;
+use read_fonts::{
+    collections::IntSet,
+    tables::{
+        cff::Cff,
+        cff2::Cff2,
+        glyf::{Glyf, Glyph},
+        gvar::Gvar,
+        ift::{
+            CompatibilityId, GlyphKeyedFlags, Ift as ReadIft, TablePatchFlags, IFTX_TAG, IFT_TAG,
+        },
+        loca::Loca,
+        postscript::{
+            dict::{self, Token},
+            Index, Number,
+        },
+    },
+    types::{Int24, Tag, Uint24},
+    FontRef, ReadError, TableProvider, TopLevelTable,
+};
+use skrifa::{charmap::Charmap, GlyphId};
+use write_fonts::{
+    tables::ift::{DesignSpaceSegment, EntryData, GlyphKeyedPatch, Ift, PatchMapFormat2},
+    FontBuilder,
+};
+
+use crate::{
+    font_patch::PatchingError,
+    glyph_keyed::{
+        patch_charstrings, patch_glyf_and_loca, patch_gvar, GlyphDataOffsetArray, GlyphReplacements,
+    },
+    patch_group::Invalidation,
+    patchmap::{IftTableTag, PatchFormat, PatchUri, SubsetDefinition},
+    table_keyed::copy_unprocessed_tables,
+};
+
+/// Format number of fully invalidating table keyed patches.
+///
+/// See: <https://w3c.github.io/IFT/Overview.html#font-patch-formats-summary>
+const TABLE_KEYED_FULL_FORMAT: u8 = 1;
+
+/// Format number of glyph keyed patches.
+///
+/// See: <https://w3c.github.io/IFT/Overview.html#font-patch-formats-summary>
+const GLYPH_KEYED_FORMAT: u8 = 3;
+
+/// The maximum number of segments in a table keyed encoding.
+///
+/// A table keyed encoding has a font for every combination of segments, so the number of
+/// patches grows exponentially with the number of segments.
+pub const MAX_TABLE_KEYED_SEGMENTS: usize = 8;
+
+/// The kind of patches which load the segments of a [`SegmentationPlan`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PatchEncoding {
+    /// One glyph keyed patch per segment, which adds the glyph data of that segment.
+    #[default]
+    GlyphKeyed,
+    /// Fully invalidating table keyed patches between every combination of segments.
+    ///
+    /// Plans using this encoding are limited to [`MAX_TABLE_KEYED_SEGMENTS`] segments.
+    TableKeyed,
+}
+
+/// Describes how a font is split into an initial font and patches.
+#[derive(Debug, Clone, Default)]
+pub struct SegmentationPlan {
+    initial_subset: SubsetDefinition,
+    segments: Vec<SubsetDefinition>,
+    patch_encoding: PatchEncoding,
+}
+
+impl SegmentationPlan {
+    /// Create a plan where the initial font includes the glyphs needed by `initial_subset`.
+    ///
+    /// Only the codepoints of the initial subset are used.
+    pub fn new(initial_subset: SubsetDefinition) -> Self {
+        Self {
+            initial_subset,
+            segments: Default::default(),
+            patch_encoding: Default::default(),
+        }
+    }
+
+    /// Add a segment, which will be loaded by a single patch.
+    ///
+    /// The patch is selected when a client's target subset definition intersects the segment,
+    /// so a segment with feature tags is only loaded when one of those features is requested.
+    pub fn add_segment(&mut self, segment: SubsetDefinition) -> &mut Self {
+        self.segments.push(segment);
+        self
+    }
+
+    /// Set the kind of patches used to load the segments, glyph keyed by default.
+    pub fn set_patch_encoding(&mut self, patch_encoding: PatchEncoding) -> &mut Self {
+        self.patch_encoding = patch_encoding;
+        self
+    }
+
+    /// The segments of this plan, in the order their patch map entries are written.
+    pub fn segments(&self) -> &[SubsetDefinition] {
+        &self.segments
+    }
+
+    /// The kind of patches used to load the segments.
+    pub fn patch_encoding(&self) -> PatchEncoding {
+        self.patch_encoding
+    }
+}
+
+/// The result of encoding a font: the initial font along with the patches it refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedFont {
+    initial_font: Vec<u8>,
+    patches: BTreeMap<String, Vec<u8>>,
+}
+
+impl EncodedFont {
+    /// The bytes of the initial font.
+    pub fn initial_font(&self) -> &[u8] {
+        &self.initial_font
+    }
+
+    /// The patch files, keyed by the URI the initial font uses to refer to them.
+    pub fn patches(&self) -> &BTreeMap<String, Vec<u8>> {
+        &self.patches
+    }
+}
+
+/// An error that occurs while encoding a font.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodingError {
+    FontParsingFailed(ReadError),
+    UnsupportedFont(&'static str),
+    InvalidPlan(&'static str),
+    CompressionFailed,
+    InternalError,
+}
+
+impl From<PatchingError> for EncodingError {
+    fn from(err: PatchingError) -> Self {
+        match err {
+            PatchingError::FontParsingFailed(err) => EncodingError::FontParsingFailed(err),
+            _ => EncodingError::InternalError,
+        }
+    }
+}
+
+impl std::fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EncodingError::FontParsingFailed(err) => {
+                write!(f, "Failed to parse font file: {}", err)
+            }
+            EncodingError::UnsupportedFont(msg) => write!(f, "Unsupported font: '{msg}'"),
+            EncodingError::InvalidPlan(msg) => write!(f, "Invalid segmentation plan: '{msg}'"),
+            EncodingError::CompressionFailed => write!(f, "Brotli compression failed."),
+            EncodingError::InternalError => write!(
+                f,
+                "Internal constraint violated, typically should not happen."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+/// Encode `font` into an initial font and patches according to `plan`.
+///
+/// Patches are given the URIs produced by expanding `uri_template` with their entry ids. For
+/// glyph keyed patches the patch for the nth segment (counting from one) has an id of n; table
+/// keyed patches are numbered consecutively starting from one across all of the fonts produced
+/// by the encoding.
+///
+/// `compatibility_id` is stored in the initial font and in every patch which applies to it. The
+/// fonts produced by table keyed patches use ids derived from it, by adding the set of segments
+/// loaded in the font (as a bit mask) to the last four bytes of the id.
+pub fn encode(
+    font: &FontRef,
+    plan: &SegmentationPlan,
+    uri_template: &str,
+    compatibility_id: CompatibilityId,
+) -> Result<EncodedFont, EncodingError> {
+    if font.table_data(IFT_TAG).is_some() || font.table_data(IFTX_TAG).is_some() {
+        return Err(EncodingError::UnsupportedFont(
+            "Font is already an incremental font.",
+        ));
+    }
+    if uri_template.len() > u16::MAX as usize {
+        return Err(EncodingError::InvalidPlan("URI template is too long."));
+    }
+    let outlines = Outlines::new(font)?;
+
+    let num_glyphs = font
+        .maxp()
+        .map_err(EncodingError::FontParsingFailed)?
+        .num_glyphs();
+    let max_glyph_id = GlyphId::new(num_glyphs.checked_sub(1).ok_or(
+        EncodingError::FontParsingFailed(ReadError::MalformedData("Font has no glyphs.")),
+    )? as u32);
+
+    // Determine which glyphs each segment needs, any glyph needed by the initial subset stays
+    // in the initial font.
+    let charmap = Charmap::new(font);
+    let mut initial_gids =
+        outlines.glyph_closure(&plan.initial_subset.codepoints, &charmap, max_glyph_id)?;
+    initial_gids.insert(GlyphId::new(0));
+
+    let mut segment_gids: Vec<IntSet<GlyphId>> = Vec::with_capacity(plan.segments.len());
+    for segment in plan.segments.iter() {
+        let mut gids = outlines.glyph_closure(&segment.codepoints, &charmap, max_glyph_id)?;
+        gids.subtract(&initial_gids);
+        segment_gids.push(gids);
+    }
+
+    let encoder = Encoder {
+        font,
+        plan,
+        outlines,
+        max_glyph_id,
+        segment_gids,
+        uri_template,
+        compatibility_id,
+    };
+    match plan.patch_encoding {
+        PatchEncoding::GlyphKeyed => encoder.encode_glyph_keyed(),
+        PatchEncoding::TableKeyed => encoder.encode_table_keyed(),
+    }
+}
+
+/// Generate a new random compatibility id.
+///
+/// The id is laid out as a version 4 (random) UUID. The randomness comes from the standard
+/// library's per process hash seeds, which is sufficient to keep ids from different encodings
+/// apart but is not cryptographically secure.
+pub fn new_compatibility_id() -> CompatibilityId {
+    let mut bytes = [0u8; 16];
+    let state = RandomState::new();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    for (index, chunk) in bytes.chunks_exact_mut(8).enumerate() {
+        chunk.copy_from_slice(&state.hash_one((index, now)).to_be_bytes());
+    }
+    // Version 4, RFC 4122 variant.
+    bytes[6] = (bytes[6] & 0x0F) | 0x40;
+    bytes[8] = (bytes[8] & 0x3F) | 0x80;
+    CompatibilityId::new(bytes)
+}
+
+/// Returns a copy of `font` where the compatibility id of the mapping table `tag` (either
+/// 'IFT ' or 'IFTX') is replaced by `compatibility_id`.
+///
+/// All other tables, and the rest of the mapping table, are copied unchanged.
+pub fn set_compatibility_id(
+    font: &FontRef,
+    tag: Tag,
+    compatibility_id: &CompatibilityId,
+) -> Result<Vec<u8>, EncodingError> {
+    let table = match tag {
+        IFT_TAG => font.ift(),
+        IFTX_TAG => font.iftx(),
+        _ => {
+            return Err(EncodingError::UnsupportedFont(
+                "Compatibility ids are only stored in the 'IFT ' and 'IFTX' tables.",
+            ))
+        }
+    }
+    .map_err(EncodingError::FontParsingFailed)?;
+    let range = match &table {
+        ReadIft::Format1(format_1) => format_1.shape().compatibility_id_byte_range(),
+        ReadIft::Format2(format_2) => format_2.shape().compatibility_id_byte_range(),
+    };
+
+    let mut data = font
+        .table_data(tag)
+        .ok_or(EncodingError::InternalError)?
+        .as_bytes()
+        .to_vec();
+    data.get_mut(range)
+        .ok_or(EncodingError::FontParsingFailed(ReadError::OutOfBounds))?
+        .copy_from_slice(compatibility_id.as_slice());
+
+    let mut font_builder = FontBuilder::new();
+    font_builder.add_raw(tag, data);
+    copy_unprocessed_tables(font, BTreeSet::from([tag]), &mut font_builder);
+    Ok(font_builder.build())
+}
+
+/// Assigns fresh compatibility ids to the mapping tables of `font` which are invalidated by a
+/// patch listed in the mapping table `source_table`.
+///
+/// A fully invalidating patch invalidates both the 'IFT ' and 'IFTX' tables, a partially
+/// invalidating one only invalidates `source_table`, and a non invalidating patch leaves the
+/// ids unchanged. Encoders producing an invalidating patch should store the returned ids in the
+/// font the patch produces, so that the patch can't be combined with any patches built against
+/// the font it was applied to.
+///
+/// Returns the updated font along with the new id of each table that was changed. Tables which
+/// aren't present in `font` are skipped.
+pub fn regenerate_compatibility_ids(
+    font: &FontRef,
+    source_table: Tag,
+    invalidation: Invalidation,
+) -> Result<(Vec<u8>, BTreeMap<Tag, CompatibilityId>), EncodingError> {
+    if source_table != IFT_TAG && source_table != IFTX_TAG {
+        return Err(EncodingError::UnsupportedFont(
+            "Compatibility ids are only stored in the 'IFT ' and 'IFTX' tables.",
+        ));
+    }
+    let invalidated: &[Tag] = match invalidation {
+        Invalidation::Full => &[IFT_TAG, IFTX_TAG],
+        Invalidation::Partial => &[source_table],
+        Invalidation::None => &[],
+    };
+
+    let mut font_bytes: Option<Vec<u8>> = None;
+    let mut new_ids = BTreeMap::new();
+    for tag in invalidated {
+        let current = match &font_bytes {
+            Some(bytes) => FontRef::new(bytes).map_err(EncodingError::FontParsingFailed)?,
+            None => font.clone(),
+        };
+        if current.table_data(*tag).is_none() {
+            continue;
+        }
+        let id = new_compatibility_id();
+        font_bytes = Some(set_compatibility_id(&current, *tag, &id)?);
+        new_ids.insert(*tag, id);
+    }
+
+    let font_bytes = font_bytes.unwrap_or_else(|| {
+        let mut font_builder = FontBuilder::new();
+        copy_unprocessed_tables(font, BTreeSet::new(), &mut font_builder);
+        font_builder.build()
+    });
+    Ok((font_bytes, new_ids))
+}
+
+/// The inputs shared by both patch encodings.
+struct Encoder<'a> {
+    font: &'a FontRef<'a>,
+    plan: &'a SegmentationPlan,
+    outlines: Outlines<'a>,
+    max_glyph_id: GlyphId,
+    /// The glyphs loaded by each segment, excluding those in the initial font.
+    segment_gids: Vec<IntSet<GlyphId>>,
+    uri_template: &'a str,
+    compatibility_id: CompatibilityId,
+}
+
+impl Encoder<'_> {
+    /// Produces one glyph keyed patch per segment.
+    fn encode_glyph_keyed(&self) -> Result<EncodedFont, EncodingError> {
+        // The patches contain the original data for each of their glyphs.
+        let sources = self.outlines.glyph_data_sources()?;
+
+        let source_table = IftTableTag::Ift(self.compatibility_id.clone());
+        let mut patches: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+        let mut entries: Vec<EntryData> = vec![];
+        for (index, (segment, gids)) in self
+            .plan
+            .segments
+            .iter()
+            .zip(self.segment_gids.iter())
+            .enumerate()
+        {
+            // Entries don't specify an id delta, so they're implicitly numbered from one.
+            let entry_id = u32::try_from(index + 1)
+                .map_err(|_| EncodingError::InvalidPlan("Too many segments."))?;
+            let uri = self.uri(entry_id, &source_table, PatchFormat::GlyphKeyed);
+            let patch = glyph_keyed_patch(gids, &sources, &self.compatibility_id)?;
+            if patches.insert(uri, patch).is_some() {
+                return Err(EncodingError::InvalidPlan(
+                    "URI template produces the same URI for multiple patches.",
+                ));
+            }
+
+            entries.push(encode_entry(segment)?);
+        }
+
+        if Uint24::checked_new(entries.len() as u32).is_none() {
+            return Err(EncodingError::InvalidPlan("Too many segments."));
+        }
+        let ift = Ift::Format2(
+            PatchMapFormat2::from_entries(
+                self.compatibility_id.clone(),
+                GLYPH_KEYED_FORMAT,
+                self.uri_template,
+                &entries,
+            )
+            .map_err(|_| EncodingError::InternalError)?,
+        );
+
+        // The initial font has the data for all glyphs loaded by patches removed.
+        let mut removed_gids: IntSet<GlyphId> = IntSet::empty();
+        for gids in self.segment_gids.iter() {
+            removed_gids.union(gids);
+        }
+        Ok(EncodedFont {
+            initial_font: self.build_font(&removed_gids, Some(&ift))?,
+            patches,
+        })
+    }
+
+    /// Produces a font for every combination of segments, connected by fully invalidating table
+    /// keyed patches which each load one more segment.
+    ///
+    /// A combination of segments (a state) is identified by a bit mask of the segments it has
+    /// loaded.
+    fn encode_table_keyed(&self) -> Result<EncodedFont, EncodingError> {
+        let segment_count = self.plan.segments.len();
+        if segment_count > MAX_TABLE_KEYED_SEGMENTS {
+            return Err(EncodingError::InvalidPlan(
+                "Too many segments for a table keyed encoding.",
+            ));
+        }
+        let state_count = 1u32 << segment_count;
+        let all_segments = state_count - 1;
+
+        // The entries of each state are numbered consecutively, following those of the
+        // previous state.
+        let mut first_entry_ids: Vec<u32> = Vec::with_capacity(state_count as usize);
+        let mut next_id = 1;
+        for state in 0..state_count {
+            first_entry_ids.push(next_id);
+            next_id += segment_count as u32 - state.count_ones();
+        }
+        let entry_id = |state: u32, segment: usize| {
+            let preceding = (0..segment).filter(|i| state & (1 << i) == 0).count();
+            first_entry_ids[state as usize] + preceding as u32
+        };
+
+        let mut initial_font = vec![];
+        let mut patches: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+        for state in 0..state_count {
+            let state_id = state_compatibility_id(&self.compatibility_id, state);
+            let ift = if state == all_segments {
+                None
+            } else {
+                let mut entries: Vec<EntryData> = vec![];
+                for (index, segment) in self.plan.segments.iter().enumerate() {
+                    if state & (1 << index) != 0 {
+                        continue;
+                    }
+                    let mut entry = encode_entry(segment)?;
+                    if entries.is_empty() && first_entry_ids[state as usize] > 1 {
+                        entry.set_entry_id_delta(Int24::new(
+                            first_entry_ids[state as usize] as i32 - 1,
+                        ));
+                    }
+                    entries.push(entry);
+                }
+                Some(Ift::Format2(
+                    PatchMapFormat2::from_entries(
+                        state_id.clone(),
+                        TABLE_KEYED_FULL_FORMAT,
+                        self.uri_template,
+                        &entries,
+                    )
+                    .map_err(|_| EncodingError::InternalError)?,
+                ))
+            };
+
+            // Glyphs shared between segments are kept if any of them is loaded.
+            let mut removed_gids: IntSet<GlyphId> = IntSet::empty();
+            let mut loaded_gids: IntSet<GlyphId> = IntSet::empty();
+            for (index, gids) in self.segment_gids.iter().enumerate() {
+                if state & (1 << index) == 0 {
+                    removed_gids.union(gids);
+                } else {
+                    loaded_gids.union(gids);
+                }
+            }
+            removed_gids.subtract(&loaded_gids);
+            let state_font = self.build_font(&removed_gids, ift.as_ref())?;
+            if state == 0 {
+                initial_font = state_font;
+                continue;
+            }
+
+            // Each patch replaces the glyph data tables, and the mapping table, of the state it
+            // is applied to with those of this state.
+            let state_font_ref =
+                FontRef::new(&state_font).map_err(EncodingError::FontParsingFailed)?;
+            let tables = self
+                .outlines
+                .tags()
+                .iter()
+                .chain([&IFT_TAG])
+                .map(|tag| (*tag, state_font_ref.table_data(*tag).map(|d| d.as_bytes())))
+                .collect::<Vec<_>>();
+            for segment in (0..segment_count).filter(|i| state & (1 << i) != 0) {
+                let previous = state & !(1 << segment);
+                let previous_id = state_compatibility_id(&self.compatibility_id, previous);
+                let uri = self.uri(
+                    entry_id(previous, segment),
+                    &IftTableTag::Ift(previous_id.clone()),
+                    PatchFormat::TableKeyed {
+                        fully_invalidating: true,
+                    },
+                );
+                let patch = table_keyed_patch(&tables, &previous_id)?;
+                if patches.insert(uri, patch).is_some() {
+                    return Err(EncodingError::InvalidPlan(
+                        "URI template produces the same URI for multiple patches.",
+                    ));
+                }
+            }
+        }
+
+        Ok(EncodedFont {
+            initial_font,
+            patches,
+        })
+    }
+
+    /// The URI of the patch with `entry_id` in the mapping table `source_table`.
+    fn uri(&self, entry_id: u32, source_table: &IftTableTag, format: PatchFormat) -> String {
+        PatchUri::from_index(
+            self.uri_template,
+            entry_id,
+            source_table.clone(),
+            0,
+            format,
+            Default::default(),
+        )
+        .uri_string()
+    }
+
+    /// Builds a copy of the input font where the glyph data of `removed_gids` is removed and
+    /// `ift` is added as the 'IFT ' table.
+    fn build_font(
+        &self,
+        removed_gids: &IntSet<GlyphId>,
+        ift: Option<&Ift>,
+    ) -> Result<Vec<u8>, EncodingError> {
+        let mut font_builder = FontBuilder::new();
+        let mut processed_tables: BTreeSet<Tag> = self.outlines.tags().iter().copied().collect();
+        let removed = GlyphReplacements {
+            data: vec![&[]; removed_gids.len() as usize],
+            gids: removed_gids.clone(),
+        };
+        self.outlines
+            .patch(&removed, self.max_glyph_id, &mut font_builder)?;
+        if let Some(ift) = ift {
+            font_builder.add_raw(
+                IFT_TAG,
+                write_fonts::dump_table(ift).map_err(|_| EncodingError::InternalError)?,
+            );
+            processed_tables.insert(IFT_TAG);
+        }
+        copy_unprocessed_tables(self.font, processed_tables, &mut font_builder);
+        Ok(font_builder.build())
+    }
+}
+
+/// Derives the compatibility id of a table keyed state from the id of the initial font.
+fn state_compatibility_id(compatibility_id: &CompatibilityId, state: u32) -> CompatibilityId {
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(compatibility_id.as_slice());
+    let mut last = [0u8; 4];
+    last.copy_from_slice(&bytes[12..]);
+    let last = u32::from_be_bytes(last).wrapping_add(state);
+    bytes[12..].copy_from_slice(&last.to_be_bytes());
+    CompatibilityId::new(bytes)
+}
+
+/// The tables holding the outlines of the font being encoded.
+enum Outlines<'a> {
+    Glyf {
+        glyf: Glyf<'a>,
+        loca: Loca<'a>,
+        gvar: Option<Gvar<'a>>,
+    },
+    /// A CFF or CFF2 table which has been rewritten to have the CharStrings INDEX at the end.
+    Cff {
+        tag: Tag,
+        table: Vec<u8>,
+        charstrings_offset: usize,
+        is_cff2: bool,
+    },
+}
+
+impl<'a> Outlines<'a> {
+    fn new(font: &FontRef<'a>) -> Result<Self, EncodingError> {
+        if let (Ok(glyf), Ok(loca)) = (font.glyf(), font.loca(None)) {
+            let gvar = match font.gvar() {
+                Ok(gvar) => Some(gvar),
+                Err(ReadError::TableIsMissing(_)) => None,
+                Err(err) => return Err(EncodingError::FontParsingFailed(err)),
+            };
+            return Ok(Outlines::Glyf { glyf, loca, gvar });
+        }
+
+        let (tag, is_cff2) = if font.table_data(Cff2::TAG).is_some() {
+            (Cff2::TAG, true)
+        } else if font.table_data(Cff::TAG).is_some() {
+            (Cff::TAG, false)
+        } else {
+            return Err(EncodingError::UnsupportedFont(
+                "Only fonts with glyf, CFF or CFF2 outlines are supported.",
+            ));
+        };
+        let table = font.table_data(tag).ok_or(EncodingError::InternalError)?;
+        let (table, charstrings_offset) = move_charstrings_to_end(table.as_bytes(), is_cff2)?;
+        Ok(Outlines::Cff {
+            tag,
+            table,
+            charstrings_offset,
+            is_cff2,
+        })
+    }
+
+    /// The tags of the tables which hold glyph data.
+    fn tags(&self) -> Vec<Tag> {
+        match self {
+            Outlines::Glyf { gvar: None, .. } => vec![Glyf::TAG, Loca::TAG],
+            Outlines::Glyf { gvar: Some(_), .. } => vec![Glyf::TAG, Loca::TAG, Gvar::TAG],
+            Outlines::Cff { tag, .. } => vec![*tag],
+        }
+    }
+
+    /// Returns the glyphs reachable from `codepoints` via the cmap and composite glyphs.
+    fn glyph_closure(
+        &self,
+        codepoints: &IntSet<u32>,
+        charmap: &Charmap,
+        max_glyph_id: GlyphId,
+    ) -> Result<IntSet<GlyphId>, EncodingError> {
+        let mut gids: IntSet<GlyphId> = codepoints
+            .iter()
+            .filter_map(|cp| charmap.map(cp))
+            .filter(|gid| *gid <= max_glyph_id)
+            .collect();
+        let Outlines::Glyf { glyf, loca, .. } = self else {
+            return Ok(gids);
+        };
+        let mut pending: Vec<GlyphId> = gids.iter().collect();
+        while let Some(gid) = pending.pop() {
+            let glyph = loca
+                .get_glyf(gid, glyf)
+                .map_err(EncodingError::FontParsingFailed)?;
+            let Some(Glyph::Composite(composite)) = glyph else {
+                continue;
+            };
+            for component in composite.components() {
+                let component_gid: GlyphId = component.glyph.into();
+                if component_gid <= max_glyph_id && gids.insert(component_gid) {
+                    pending.push(component_gid);
+                }
+            }
+        }
+        Ok(gids)
+    }
+
+    /// The per glyph data of each glyph data table, as used in glyph keyed patches.
+    fn glyph_data_sources(&self) -> Result<Vec<GlyphDataSource<'_>>, EncodingError> {
+        match self {
+            Outlines::Glyf { glyf, loca, gvar } => {
+                let mut sources = vec![GlyphDataSource {
+                    tag: Glyf::TAG,
+                    data: glyf.offset_data().as_bytes(),
+                    offsets: Box::new(loca.clone()),
+                }];
+                if let Some(gvar) = gvar {
+                    sources.push(GlyphDataSource {
+                        tag: Gvar::TAG,
+                        data: gvar
+                            .offset_data()
+                            .as_bytes()
+                            .get(gvar.glyph_variation_data_array_offset() as usize..)
+                            .ok_or(EncodingError::FontParsingFailed(ReadError::OutOfBounds))?,
+                        offsets: Box::new(gvar.glyph_variation_data_offsets()),
+                    });
+                }
+                Ok(sources)
+            }
+            Outlines::Cff {
+                tag,
+                table,
+                charstrings_offset,
+                is_cff2,
+            } => {
+                // The charstrings are the last data in the table.
+                let charstrings = Index::new(&table[*charstrings_offset..], *is_cff2)
+                    .map_err(|_| malformed("Failed to parse the CharStrings INDEX."))?;
+                let data_len = charstrings
+                    .offset_for(charstrings.count() as usize)
+                    .ok_or(EncodingError::FontParsingFailed(ReadError::OutOfBounds))?;
+                let data = table
+                    .len()
+                    .checked_sub(data_len)
+                    .and_then(|start| table.get(start..))
+                    .ok_or(EncodingError::FontParsingFailed(ReadError::OutOfBounds))?;
+                Ok(vec![GlyphDataSource {
+                    tag: *tag,
+                    data,
+                    offsets: Box::new(charstrings),
+                }])
+            }
+        }
+    }
+
+    /// Adds the glyph data tables, with the data of each glyph in `replacements` replaced, to
+    /// `font_builder`.
+    fn patch(
+        &self,
+        replacements: &GlyphReplacements,
+        max_glyph_id: GlyphId,
+        font_builder: &mut FontBuilder,
+    ) -> Result<(), EncodingError> {
+        match self {
+            Outlines::Glyf { glyf, loca, gvar } => {
+                patch_glyf_and_loca(
+                    replacements,
+                    glyf.offset_data().as_bytes(),
+                    loca.clone(),
+                    max_glyph_id,
+                    font_builder,
+                )?;
+                if let Some(gvar) = gvar {
+                    patch_gvar(replacements, gvar.clone(), max_glyph_id, font_builder)?;
+                }
+            }
+            Outlines::Cff {
+                tag,
+                table,
+                charstrings_offset,
+                is_cff2,
+            } => patch_charstrings(
+                replacements,
+                *tag,
+                table,
+                *charstrings_offset,
+                *is_cff2,
+                max_glyph_id,
+                font_builder,
+            )?,
+        }
+        Ok(())
+    }
+}
+
+/// Per glyph data from one table of the input font.
+struct GlyphDataSource<'a> {
+    tag: Tag,
+    data: &'a [u8],
+    offsets: Box<dyn GlyphDataOffsetArray + 'a>,
+}
+
+impl<'a> GlyphDataSource<'a> {
+    fn glyph_data(&self, gid: GlyphId) -> Result<&'a [u8], EncodingError> {
+        let index = gid.to_u32() as usize;
+        self.offsets
+            .offset_for(index)
+            .zip(self.offsets.offset_for(index + 1))
+            .and_then(|(start, end)| self.data.get(start..end))
+            .ok_or(EncodingError::FontParsingFailed(ReadError::OutOfBounds))
+    }
+}
+
+fn malformed(message: &'static str) -> EncodingError {
+    EncodingError::FontParsingFailed(ReadError::MalformedData(message))
+}
+
+/// Rewrites a CFF or CFF2 table so that the CharStrings INDEX is the last data in the table.
+///
+/// Glyph keyed patches don't update any offsets in the table, so they can only replace
+/// charstrings which are at the end of it. The Top DICT and the Font DICTs in the FDArray are
+/// rewritten with every offset in the five byte integer encoding, which keeps their sizes
+/// independent of the new offsets; all other data is copied unchanged.
+///
+/// Returns the new table and the offset to the CharStrings INDEX in it.
+fn move_charstrings_to_end(table: &[u8], is_cff2: bool) -> Result<(Vec<u8>, usize), EncodingError> {
+    let header_size = *table
+        .get(2)
+        .ok_or(EncodingError::FontParsingFailed(ReadError::OutOfBounds))?
+        as usize;
+
+    // The Top DICT, and the part of the table which holds it: the Top DICT INDEX for CFF.
+    let (top_dict, top_range) = if is_cff2 {
+        let top_dict_len = table
+            .get(3..5)
+            .map(|len| u16::from_be_bytes([len[0], len[1]]) as usize)
+            .ok_or(EncodingError::FontParsingFailed(ReadError::OutOfBounds))?;
+        let top_range = header_size..header_size + top_dict_len;
+        let top_dict = table
+            .get(top_range.clone())
+            .ok_or(EncodingError::FontParsingFailed(ReadError::OutOfBounds))?;
+        (top_dict, top_range)
+    } else {
+        let name_index = index_range(table, header_size, false)?;
+        let top_range = index_range(table, name_index.end, false)?;
+        let top_index = Index::new(&table[top_range.clone()], false)
+            .map_err(|_| malformed("Failed to parse the Top DICT INDEX."))?;
+        if top_index.count() != 1 {
+            return Err(EncodingError::UnsupportedFont(
+                "Only CFF tables containing a single font are supported.",
+            ));
+        }
+        let top_dict = top_index
+            .get(0)
+            .map_err(|_| malformed("Failed to parse the Top DICT INDEX."))?;
+        (top_dict, top_range)
+    };
+
+    let mut charstrings_offset = None;
+    let mut fd_array_offset = None;
+    let mut private_dicts = vec![];
+    for entry in dict::entries(top_dict, None) {
+        match entry.map_err(|_| malformed("Failed to parse the Top DICT."))? {
+            dict::Entry::CharstringsOffset(offset) => charstrings_offset = Some(offset),
+            dict::Entry::FdArrayOffset(offset) => fd_array_offset = Some(offset),
+            dict::Entry::PrivateDictRange(range) => private_dicts.push(range),
+            _ => {}
+        }
+    }
+    let charstrings = index_range(
+        table,
+        charstrings_offset.ok_or(malformed("Top DICT is missing the CharStrings offset."))?,
+        is_cff2,
+    )?;
+
+    let mut font_dicts: Vec<&[u8]> = vec![];
+    let fd_array = fd_array_offset
+        .map(|offset| index_range(table, offset, is_cff2))
+        .transpose()?;
+    if let Some(fd_array) = &fd_array {
+        let index = Index::new(&table[fd_array.clone()], is_cff2)
+            .map_err(|_| malformed("Failed to parse the FDArray."))?;
+        for i in 0..index.count() as usize {
+            let font_dict = index
+                .get(i)
+                .map_err(|_| malformed("Failed to parse the FDArray."))?;
+            for entry in dict::entries(font_dict, None) {
+                if let dict::Entry::PrivateDictRange(range) =
+                    entry.map_err(|_| malformed("Failed to parse a Font DICT."))?
+                {
+                    private_dicts.push(range);
+                }
+            }
+            font_dicts.push(font_dict);
+        }
+    }
+
+    // The CharStrings INDEX and the FDArray are moved or resized, all other data after the Top
+    // DICT keeps its relative order.
+    let mut replaced = vec![charstrings.clone()];
+    replaced.extend(fd_array.clone());
+    replaced.sort_by_key(|range| range.start);
+    if replaced[0].start < top_range.end
+        || replaced
+            .windows(2)
+            .any(|ranges| ranges[0].end > ranges[1].start)
+    {
+        return Err(EncodingError::UnsupportedFont(
+            "CFF tables with overlapping data are not supported.",
+        ));
+    }
+
+    // Offsets are always written with the same encoding, so the new sizes are known before
+    // any of the offsets are.
+    let new_top_len = rewrite_dict_offsets(top_dict, |offset| offset)?.len();
+    let new_top_range_len = if is_cff2 {
+        new_top_len
+    } else {
+        build_index(&[vec![0; new_top_len]], false)?.len()
+    };
+    let new_fd_array_len = fd_array
+        .as_ref()
+        .map(|_| {
+            let font_dicts = font_dicts
+                .iter()
+                .map(|font_dict| rewrite_dict_offsets(font_dict, |offset| offset))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok::<_, EncodingError>(build_index(&font_dicts, is_cff2)?.len())
+        })
+        .transpose()?;
+    let top_growth = new_top_range_len as isize - top_range.len() as isize;
+    let fd_array_growth = new_fd_array_len
+        .zip(fd_array.as_ref())
+        .map(|(len, range)| len as isize - range.len() as isize)
+        .unwrap_or_default();
+    let new_table_len = (table.len() as isize + top_growth + fd_array_growth) as usize;
+    let new_charstrings_offset = new_table_len - charstrings.len();
+
+    let relocate = |offset: usize| -> usize {
+        if offset == charstrings.start {
+            return new_charstrings_offset;
+        }
+        if offset < top_range.end {
+            // Predefined charsets and encodings are also stored as offsets.
+            return offset;
+        }
+        let mut new_offset = offset as isize + top_growth;
+        if offset >= charstrings.end {
+            new_offset -= charstrings.len() as isize;
+        }
+        if fd_array.as_ref().is_some_and(|range| offset >= range.end) {
+            new_offset += fd_array_growth;
+        }
+        new_offset as usize
+    };
+
+    // Subrs offsets are relative to their Private DICT, and aren't rewritten.
+    for private_dict in private_dicts {
+        let Some(private_data) = table.get(private_dict.clone()) else {
+            return Err(EncodingError::FontParsingFailed(ReadError::OutOfBounds));
+        };
+        for entry in dict::entries(private_data, None) {
+            if let Ok(dict::Entry::SubrsOffset(subrs)) = entry {
+                let start = private_dict.start;
+                if relocate(start + subrs) != relocate(start) + subrs {
+                    return Err(EncodingError::UnsupportedFont(
+                        "Local subroutines must not be separated from their Private DICT.",
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut new_table = Vec::with_capacity(new_table_len);
+    if is_cff2 {
+        new_table.extend_from_slice(&table[..3]);
+        let new_top_len = u16::try_from(new_top_len)
+            .map_err(|_| EncodingError::UnsupportedFont("Top DICT is too large."))?;
+        new_table.extend_from_slice(&new_top_len.to_be_bytes());
+        new_table.extend_from_slice(&table[5..top_range.start]);
+        new_table.extend(rewrite_dict_offsets(top_dict, relocate)?);
+    } else {
+        new_table.extend_from_slice(&table[..top_range.start]);
+        new_table.extend(build_index(
+            &[rewrite_dict_offsets(top_dict, relocate)?],
+            false,
+        )?);
+    }
+    let mut copied_to = top_range.end;
+    for range in replaced {
+        new_table.extend_from_slice(&table[copied_to..range.start]);
+        if Some(&range) == fd_array.as_ref() {
+            let font_dicts = font_dicts
+                .iter()
+                .map(|font_dict| rewrite_dict_offsets(font_dict, relocate))
+                .collect::<Result<Vec<_>, _>>()?;
+            new_table.extend(build_index(&font_dicts, is_cff2)?);
+        }
+        copied_to = range.end;
+    }
+    new_table.extend_from_slice(&table[copied_to..]);
+    new_table.extend_from_slice(&table[charstrings]);
+
+    if new_table.len() != new_table_len {
+        return Err(EncodingError::InternalError);
+    }
+    Ok((new_table, new_charstrings_offset))
+}
+
+/// The range of the table occupied by the INDEX at `offset`.
+fn index_range(table: &[u8], offset: usize, is_cff2: bool) -> Result<Range<usize>, EncodingError> {
+    let index = table
+        .get(offset..)
+        .and_then(|data| Index::new(data, is_cff2).ok())
+        .ok_or(malformed("Failed to parse an INDEX."))?;
+    let size = index
+        .size_in_bytes()
+        .map_err(EncodingError::FontParsingFailed)?;
+    Ok(offset..offset + size)
+}
+
+/// Builds a CFF (16 bit count) or CFF2 (32 bit count) INDEX containing `items`.
+fn build_index(items: &[Vec<u8>], is_cff2: bool) -> Result<Vec<u8>, EncodingError> {
+    let data_len: usize = items.iter().map(|item| item.len()).sum();
+    let mut index = Vec::with_capacity(5 + (items.len() + 1) * 4 + data_len);
+    if is_cff2 {
+        let count = u32::try_from(items.len()).map_err(|_| EncodingError::InternalError)?;
+        index.extend_from_slice(&count.to_be_bytes());
+    } else {
+        let count = u16::try_from(items.len()).map_err(|_| EncodingError::InternalError)?;
+        index.extend_from_slice(&count.to_be_bytes());
+    }
+    if items.is_empty() {
+        return Ok(index);
+    }
+
+    // Offsets are one based.
+    let off_size: usize = match data_len + 1 {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        0x10000..=0xFFFFFF => 3,
+        _ => 4,
+    };
+    index.push(off_size as u8);
+    let mut offset = 1;
+    index.extend_from_slice(&(offset as u32).to_be_bytes()[4 - off_size..]);
+    for item in items {
+        offset += item.len();
+        let offset = u32::try_from(offset).map_err(|_| EncodingError::InternalError)?;
+        index.extend_from_slice(&offset.to_be_bytes()[4 - off_size..]);
+    }
+    for item in items {
+        index.extend_from_slice(item);
+    }
+    Ok(index)
+}
+
+/// Copies `dict` with the operand of each operator which holds an offset from the start of the
+/// table mapped through `relocate` and written as a five byte integer.
+fn rewrite_dict_offsets(
+    dict: &[u8],
+    relocate: impl Fn(usize) -> usize,
+) -> Result<Vec<u8>, EncodingError> {
+    // Escape byte for two byte operators.
+    const ESCAPE: u8 = 12;
+    let mut new_dict = Vec::with_capacity(dict.len());
+    let mut operands: Vec<Range<usize>> = vec![];
+    let mut pos = 0;
+    while pos < dict.len() {
+        let b0 = dict[pos];
+        let len = dict_token_len(&dict[pos..]).ok_or(malformed("Failed to parse a DICT."))?;
+        let token = pos..pos + len;
+        pos += len;
+        if matches!(b0, 28..=30 | 32..=254) {
+            operands.push(token);
+            continue;
+        }
+
+        // charset, Encoding, CharStrings, Private, vstore, FDArray and FDSelect.
+        let is_offset = match b0 {
+            15..=18 | 24 => true,
+            ESCAPE => matches!(dict[token.start + 1], 36 | 37),
+            _ => false,
+        };
+        let offset_operand = if is_offset { operands.pop() } else { None };
+        for operand in operands.drain(..) {
+            new_dict.extend_from_slice(&dict[operand]);
+        }
+        if let Some(operand) = offset_operand {
+            let Some(Ok(Token::Operand(Number::I32(offset)))) = dict::tokens(&dict[operand]).next()
+            else {
+                return Err(malformed("DICT offset is not an integer."));
+            };
+            let offset =
+                usize::try_from(offset).map_err(|_| malformed("DICT offset is negative."))?;
+            let offset =
+                i32::try_from(relocate(offset)).map_err(|_| EncodingError::InternalError)?;
+            new_dict.push(29);
+            new_dict.extend_from_slice(&offset.to_be_bytes());
+        }
+        new_dict.extend_from_slice(&dict[token]);
+    }
+    if !operands.is_empty() {
+        return Err(malformed("DICT ends with an operand."));
+    }
+    Ok(new_dict)
+}
+
+/// The length of the DICT operator or operand at the start of `data`.
+///
+/// See: <https://learn.microsoft.com/en-us/typography/opentype/spec/cff2#table-3-operand-encoding>
+fn dict_token_len(data: &[u8]) -> Option<usize> {
+    let len = match *data.first()? {
+        12 | 247..=254 => 2,
+        28 => 3,
+        29 => 5,
+        // Real numbers end with a nibble of 0xf.
+        30 => {
+            2 + data
+                .get(1..)?
+                .iter()
+                .position(|b| b & 0xF0 == 0xF0 || b & 0x0F == 0x0F)?
+        }
+        31 | 255 => return None,
+        _ => 1,
+    };
+    (len <= data.len()).then_some(len)
+}
+
+/// Builds a brotli compressed glyph keyed patch containing the data of `gids` from each source.
+///
+/// See: <https://w3c.github.io/IFT/Overview.html#glyph-keyed-patch>
+fn glyph_keyed_patch(
+    gids: &IntSet<GlyphId>,
+    sources: &[GlyphDataSource],
+    compatibility_id: &CompatibilityId,
+) -> Result<Vec<u8>, EncodingError> {
+    let glyph_count = gids.len() as usize;
+    let mut glyph_data: Vec<&[u8]> = Vec::with_capacity(glyph_count * sources.len());
+    for source in sources {
+        for gid in gids.iter() {
+            glyph_data.push(source.glyph_data(gid)?);
+        }
+    }
+
+    // glyph count, table count, glyph ids, tables, then one more data offset than there are
+    // data blocks.
+    let header_size = 4 + 1 + glyph_count * 2 + sources.len() * 4 + (glyph_data.len() + 1) * 4;
+    let total_size = header_size + glyph_data.iter().map(|data| data.len()).sum::<usize>();
+    let to_u32 = |value: usize| u32::try_from(value).map_err(|_| EncodingError::InternalError);
+
+    let mut uncompressed: Vec<u8> = Vec::with_capacity(total_size);
+    uncompressed.extend_from_slice(&to_u32(glyph_count)?.to_be_bytes());
+    uncompressed.push(sources.len() as u8);
+    for gid in gids.iter() {
+        let gid = u16::try_from(gid.to_u32()).map_err(|_| EncodingError::InternalError)?;
+        uncompressed.extend_from_slice(&gid.to_be_bytes());
+    }
+    for source in sources {
+        uncompressed.extend_from_slice(&source.tag.to_be_bytes());
+    }
+    let mut offset = header_size;
+    uncompressed.extend_from_slice(&to_u32(offset)?.to_be_bytes());
+    for data in glyph_data.iter() {
+        offset += data.len();
+        uncompressed.extend_from_slice(&to_u32(offset)?.to_be_bytes());
+    }
+    for data in glyph_data {
+        uncompressed.extend_from_slice(data);
+    }
+
+    let patch = GlyphKeyedPatch::new(
+        Tag::new(b"ifgk"),
+        GlyphKeyedFlags::empty(),
+        compatibility_id.clone(),
+        to_u32(uncompressed.len())?,
+        compress(&uncompressed)?,
+    );
+    write_fonts::dump_table(&patch).map_err(|_| EncodingError::InternalError)
+}
+
+/// Builds a table keyed patch which replaces each table with the given data, or removes it
+/// if there is no data.
+///
+/// The table data is compressed without a dictionary, so the patch doesn't depend on the
+/// contents of the font it is applied to.
+///
+/// See: <https://w3c.github.io/IFT/Overview.html#table-keyed>
+fn table_keyed_patch(
+    tables: &[(Tag, Option<&[u8]>)],
+    compatibility_id: &CompatibilityId,
+) -> Result<Vec<u8>, EncodingError> {
+    let to_u32 = |value: usize| u32::try_from(value).map_err(|_| EncodingError::InternalError);
+    let mut table_patches: Vec<Vec<u8>> = Vec::with_capacity(tables.len());
+    for (tag, data) in tables {
+        // tag, flags, max uncompressed length and brotli stream.
+        let mut table_patch = tag.to_be_bytes().to_vec();
+        match data {
+            Some(data) => {
+                table_patch.push(TablePatchFlags::REPLACE_TABLE.bits());
+                table_patch.extend_from_slice(&to_u32(data.len())?.to_be_bytes());
+                table_patch.extend(compress(data)?);
+            }
+            None => {
+                table_patch.push(TablePatchFlags::DROP_TABLE.bits());
+                table_patch.extend_from_slice(&0u32.to_be_bytes());
+            }
+        }
+        table_patches.push(table_patch);
+    }
+
+    // format, reserved, compatibility id, patch count, then one more offset than there are
+    // table patches. The table patches must be in offset order, since the length of each
+    // brotli stream is determined by the following offset.
+    let header_size = 4 + 4 + 16 + 2 + (tables.len() + 1) * 4;
+    let patch_count = u16::try_from(tables.len()).map_err(|_| EncodingError::InternalError)?;
+    let mut patch = Vec::with_capacity(
+        header_size + table_patches.iter().map(|data| data.len()).sum::<usize>(),
+    );
+    patch.extend_from_slice(b"iftk");
+    patch.extend_from_slice(&0u32.to_be_bytes());
+    patch.extend_from_slice(compatibility_id.as_slice());
+    patch.extend_from_slice(&patch_count.to_be_bytes());
+    let mut offset = header_size;
+    patch.extend_from_slice(&to_u32(offset)?.to_be_bytes());
+    for table_patch in table_patches.iter() {
+        offset += table_patch.len();
+        patch.extend_from_slice(&to_u32(offset)?.to_be_bytes());
+    }
+    for table_patch in table_patches {
+        patch.extend(table_patch);
+    }
+    Ok(patch)
+}
+
+fn compress(data: &[u8]) -> Result<Vec<u8>, EncodingError> {
+    let mut compressor = CompressorWriter::new(Vec::new());
+    compressor
+        .write_all(data)
+        .map_err(|_| EncodingError::CompressionFailed)?;
+    compressor
+        .into_inner()
+        .map_err(|_| EncodingError::CompressionFailed)
+}
+
+/// Creates a format 2 patch map entry which matches `segment`.
+///
+/// See: <https://w3c.github.io/IFT/Overview.html#mapping-entry>
+fn encode_entry(segment: &SubsetDefinition) -> Result<EntryData, EncodingError> {
+    let mut entry = EntryData::default();
+
+    if !segment.feature_tags.is_empty() || !segment.design_space.is_empty() {
+        if segment.feature_tags.len() > u8::MAX as usize {
+            return Err(EncodingError::InvalidPlan(
+                "Too many feature tags in a segment.",
+            ));
+        }
+        let design_space = segment
+            .design_space
+            .iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .flat_map(|(tag, ranges)| {
+                ranges
+                    .iter()
+                    .map(|range| DesignSpaceSegment::new(*tag, *range.start(), *range.end()))
+            })
+            .collect::<Vec<_>>();
+        if design_space.len() > u16::MAX as usize {
+            return Err(EncodingError::InvalidPlan(
+                "Too many design space segments.",
+            ));
+        }
+        entry.set_features_and_design_space(
+            segment.feature_tags.iter().copied().collect(),
+            design_space,
+        );
+    }
+    entry.set_codepoints(&segment.codepoints);
+
+    Ok(entry)
+}
+
+#[cfg(all(test, feature = "c-brotli"))]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use skrifa::{
+        instance::{LocationRef, Size},
+        outline::{pen::PathElement, DrawSettings},
+        MetadataProvider,
+    };
+
+    use crate::{
+        patch_group::{PatchGroup, UriStatus},
+        patchmap::intersecting_patches,
+    };
+
+    const URI_TEMPLATE: &str = "patches/{id}.ift_gk";
+
+    fn compat_id() -> CompatibilityId {
+        CompatibilityId::from_u32s([1, 2, 3, 4])
+    }
+
+    /// Splits the codepoints mapped by the test font into an initial set and two segments.
+    fn test_codepoints(font: &FontRef) -> [IntSet<u32>; 3] {
+        let codepoints: Vec<u32> = Charmap::new(font).mappings().map(|(cp, _)| cp).collect();
+        let third = codepoints.len() / 3;
+        [
+            codepoints[..third].iter().copied().collect(),
+            codepoints[third..2 * third].iter().copied().collect(),
+            codepoints[2 * third..].iter().copied().collect(),
+        ]
+    }
+
+    fn gids_for(font: &FontRef, codepoints: &IntSet<u32>) -> IntSet<GlyphId> {
+        let max_glyph_id = GlyphId::new(font.maxp().unwrap().num_glyphs() as u32 - 1);
+        Outlines::new(font)
+            .unwrap()
+            .glyph_closure(codepoints, &Charmap::new(font), max_glyph_id)
+            .unwrap()
+    }
+
+    fn glyf_data(font: &FontRef, gid: GlyphId) -> Vec<u8> {
+        let loca = font.loca(None).unwrap();
+        let glyf = font.table_data(Glyf::TAG).unwrap();
+        let index = gid.to_u32() as usize;
+        let range =
+            loca.get_raw(index).unwrap() as usize..loca.get_raw(index + 1).unwrap() as usize;
+        glyf.as_bytes()[range].to_vec()
+    }
+
+    fn gvar_data(font: &FontRef, gid: GlyphId) -> Vec<u8> {
+        font.gvar()
+            .unwrap()
+            .data_for_gid(gid)
+            .map(|data| data.as_bytes().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Runs the extension algorithm against the encoded patches.
+    fn extend(encoded: &EncodedFont, subset: &SubsetDefinition) -> Vec<u8> {
+        let mut patch_data: HashMap<String, UriStatus> = encoded
+            .patches()
+            .iter()
+            .map(|(uri, data)| (uri.clone(), UriStatus::Pending(data.clone())))
+            .collect();
+        let mut font_bytes = encoded.initial_font().to_vec();
+        loop {
+            let font = FontRef::new(&font_bytes).unwrap();
+            let next_patches = PatchGroup::select_next_patches(font, subset).unwrap();
+            if !next_patches.has_uris() {
+                return font_bytes;
+            }
+            font_bytes = next_patches.apply_next_patches(&mut patch_data).unwrap();
+        }
+    }
+
+    #[test]
+    fn encode_and_extend() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let [initial, segment_1, segment_2] = test_codepoints(&font);
+        let mut plan = SegmentationPlan::new(SubsetDefinition::codepoints(initial.clone()));
+        plan.add_segment(SubsetDefinition::codepoints(segment_1.clone()))
+            .add_segment(SubsetDefinition::codepoints(segment_2.clone()));
+
+        let encoded = encode(&font, &plan, URI_TEMPLATE, compat_id()).unwrap();
+        assert_eq!(encoded.patches().len(), 2);
+
+        let initial_gids = gids_for(&font, &initial);
+        let mut segment_1_gids = gids_for(&font, &segment_1);
+        segment_1_gids.subtract(&initial_gids);
+        let mut segment_2_only_gids = gids_for(&font, &segment_2);
+        segment_2_only_gids.subtract(&initial_gids);
+        segment_2_only_gids.subtract(&segment_1_gids);
+        assert!(!segment_1_gids.is_empty());
+        assert!(!segment_2_only_gids.is_empty());
+        assert!(segment_1_gids
+            .iter()
+            .any(|gid| !gvar_data(&font, gid).is_empty()));
+
+        // Only glyphs needed by the initial subset have data in the initial font.
+        let initial_font = FontRef::new(encoded.initial_font()).unwrap();
+        for gid in initial_gids.iter() {
+            assert_eq!(glyf_data(&initial_font, gid), glyf_data(&font, gid));
+        }
+        for gid in segment_1_gids.iter().chain(segment_2_only_gids.iter()) {
+            assert!(glyf_data(&initial_font, gid).is_empty());
+            assert!(gvar_data(&initial_font, gid).is_empty());
+        }
+
+        // Extending to the first segment restores its glyphs.
+        let extended = extend(&encoded, &SubsetDefinition::codepoints(segment_1));
+        let extended = FontRef::new(&extended).unwrap();
+        for gid in initial_gids.iter().chain(segment_1_gids.iter()) {
+            assert_eq!(glyf_data(&extended, gid), glyf_data(&font, gid));
+            assert_eq!(gvar_data(&extended, gid), gvar_data(&font, gid));
+        }
+        for gid in segment_2_only_gids.iter() {
+            assert!(glyf_data(&extended, gid).is_empty());
+        }
+        assert_eq!(
+            extended.table_data(Tag::new(b"cmap")).unwrap().as_bytes(),
+            font.table_data(Tag::new(b"cmap")).unwrap().as_bytes()
+        );
+    }
+
+    #[test]
+    fn feature_segments() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let [initial, segment_1, _] = test_codepoints(&font);
+        let smcp = Tag::new(b"smcp");
+        let mut plan = SegmentationPlan::new(SubsetDefinition::codepoints(initial));
+        plan.add_segment(SubsetDefinition::new(
+            segment_1.clone(),
+            BTreeSet::from([smcp]),
+            Default::default(),
+        ));
+
+        let encoded = encode(&font, &plan, URI_TEMPLATE, compat_id()).unwrap();
+        let initial_font = FontRef::new(encoded.initial_font()).unwrap();
+
+        let without_feature = SubsetDefinition::codepoints(segment_1.clone());
+        assert!(intersecting_patches(&initial_font, &without_feature)
+            .unwrap()
+            .is_empty());
+
+        let with_feature =
+            SubsetDefinition::new(segment_1, BTreeSet::from([smcp]), Default::default());
+        let patches = intersecting_patches(&initial_font, &with_feature).unwrap();
+        assert_eq!(patches.len(), 1);
+        assert!(encoded.patches().contains_key(&patches[0].uri_string()));
+    }
+
+    fn charstring_data(font: &FontRef, gid: GlyphId) -> Vec<u8> {
+        let (tag, is_cff2) = match font.table_data(Cff2::TAG) {
+            Some(_) => (Cff2::TAG, true),
+            None => (Cff::TAG, false),
+        };
+        let top_dict = if is_cff2 {
+            font.cff2().unwrap().top_dict_data().to_vec()
+        } else {
+            font.cff().unwrap().top_dicts().get(0).unwrap().to_vec()
+        };
+        let offset = dict::entries(&top_dict, None)
+            .find_map(|entry| match entry {
+                Ok(dict::Entry::CharstringsOffset(offset)) => Some(offset),
+                _ => None,
+            })
+            .unwrap();
+        let table = font.table_data(tag).unwrap();
+        Index::new(&table.as_bytes()[offset..], is_cff2)
+            .unwrap()
+            .get(gid.to_u32() as usize)
+            .unwrap()
+            .to_vec()
+    }
+
+    fn draw(font: &FontRef, gid: GlyphId) -> Vec<PathElement> {
+        let mut path = vec![];
+        font.outline_glyphs()
+            .get(gid)
+            .unwrap()
+            .draw(
+                DrawSettings::unhinted(Size::unscaled(), LocationRef::default()),
+                &mut path,
+            )
+            .unwrap();
+        path
+    }
+
+    fn check_charstrings_moved_to_end(font_data: &[u8], tag: Tag) {
+        let font = FontRef::new(font_data).unwrap();
+        let table = font.table_data(tag).unwrap();
+        let is_cff2 = tag == Cff2::TAG;
+        let (new_table, charstrings_offset) =
+            move_charstrings_to_end(table.as_bytes(), is_cff2).unwrap();
+        let charstrings = Index::new(&new_table[charstrings_offset..], is_cff2).unwrap();
+        assert_eq!(
+            charstrings_offset + charstrings.size_in_bytes().unwrap(),
+            new_table.len()
+        );
+
+        let mut font_builder = FontBuilder::new();
+        font_builder.add_raw(tag, new_table);
+        copy_unprocessed_tables(&font, BTreeSet::from([tag]), &mut font_builder);
+        let new_font = font_builder.build();
+        let new_font = FontRef::new(&new_font).unwrap();
+        for gid in 0..font.maxp().unwrap().num_glyphs() {
+            let gid = GlyphId::from(gid);
+            assert_eq!(charstring_data(&new_font, gid), charstring_data(&font, gid));
+            assert_eq!(draw(&new_font, gid), draw(&font, gid));
+        }
+    }
+
+    #[test]
+    fn cff_charstrings_moved_to_end() {
+        check_charstrings_moved_to_end(font_test_data::NOTO_SERIF_DISPLAY_TRIMMED, Cff::TAG);
+    }
+
+    #[test]
+    fn cff2_charstrings_moved_to_end() {
+        check_charstrings_moved_to_end(font_test_data::CANTARELL_VF_TRIMMED, Cff2::TAG);
+    }
+
+    fn check_encode_and_extend_charstrings(font_data: &[u8]) {
+        let font = FontRef::new(font_data).unwrap();
+        let [initial, segment_1, segment_2] = test_codepoints(&font);
+        let mut plan = SegmentationPlan::new(SubsetDefinition::codepoints(initial.clone()));
+        plan.add_segment(SubsetDefinition::codepoints(segment_1.clone()))
+            .add_segment(SubsetDefinition::codepoints(segment_2.clone()));
+        let encoded = encode(&font, &plan, URI_TEMPLATE, compat_id()).unwrap();
+
+        let mut initial_gids = gids_for(&font, &initial);
+        initial_gids.insert(GlyphId::new(0));
+        let mut segment_1_gids = gids_for(&font, &segment_1);
+        segment_1_gids.subtract(&initial_gids);
+        let mut segment_2_gids = gids_for(&font, &segment_2);
+        segment_2_gids.subtract(&initial_gids);
+        assert!(!segment_1_gids.is_empty());
+        assert!(!segment_2_gids.is_empty());
+
+        let initial_font = FontRef::new(encoded.initial_font()).unwrap();
+        for gid in initial_gids.iter() {
+            assert_eq!(
+                charstring_data(&initial_font, gid),
+                charstring_data(&font, gid)
+            );
+        }
+        for gid in segment_1_gids.iter().chain(segment_2_gids.iter()) {
+            assert!(charstring_data(&initial_font, gid).is_empty());
+        }
+
+        let extended = extend(&encoded, &SubsetDefinition::codepoints(segment_1));
+        let extended = FontRef::new(&extended).unwrap();
+        for gid in initial_gids.iter().chain(segment_1_gids.iter()) {
+            assert_eq!(charstring_data(&extended, gid), charstring_data(&font, gid));
+            assert_eq!(draw(&extended, gid), draw(&font, gid));
+        }
+        for gid in segment_2_gids.iter() {
+            assert!(charstring_data(&extended, gid).is_empty());
+        }
+    }
+
+    #[test]
+    fn encode_and_extend_cff() {
+        check_encode_and_extend_charstrings(font_test_data::NOTO_SERIF_DISPLAY_TRIMMED);
+    }
+
+    #[test]
+    fn encode_and_extend_cff2() {
+        check_encode_and_extend_charstrings(font_test_data::CANTARELL_VF_TRIMMED);
+    }
+
+    #[test]
+    fn table_keyed_encode_and_extend() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let [initial, segment_1, segment_2] = test_codepoints(&font);
+        let mut plan = SegmentationPlan::new(SubsetDefinition::codepoints(initial.clone()));
+        plan.add_segment(SubsetDefinition::codepoints(segment_1.clone()))
+            .add_segment(SubsetDefinition::codepoints(segment_2.clone()))
+            .set_patch_encoding(PatchEncoding::TableKeyed);
+
+        let encoded = encode(&font, &plan, URI_TEMPLATE, compat_id()).unwrap();
+        // Two patches from the initial font, and one from each font with one segment loaded.
+        assert_eq!(encoded.patches().len(), 4);
+        let mut all_codepoints = segment_1.clone();
+        all_codepoints.union(&segment_2);
+
+        let initial_font = FontRef::new(encoded.initial_font()).unwrap();
+        assert_eq!(initial_font.ift().unwrap().compatibility_id(), compat_id());
+        let patches = intersecting_patches(
+            &initial_font,
+            &SubsetDefinition::codepoints(all_codepoints.clone()),
+        )
+        .unwrap();
+        assert_eq!(patches.len(), 2);
+        assert!(patches
+            .iter()
+            .all(|patch| encoded.patches().contains_key(&patch.uri_string())));
+
+        let initial_gids = gids_for(&font, &initial);
+        let mut segment_1_gids = gids_for(&font, &segment_1);
+        segment_1_gids.subtract(&initial_gids);
+        let mut segment_2_only_gids = gids_for(&font, &segment_2);
+        segment_2_only_gids.subtract(&initial_gids);
+        segment_2_only_gids.subtract(&segment_1_gids);
+
+        let extended = extend(&encoded, &SubsetDefinition::codepoints(segment_1.clone()));
+        let extended = FontRef::new(&extended).unwrap();
+        for gid in initial_gids.iter().chain(segment_1_gids.iter()) {
+            assert_eq!(glyf_data(&extended, gid), glyf_data(&font, gid));
+            assert_eq!(gvar_data(&extended, gid), gvar_data(&font, gid));
+        }
+        for gid in segment_2_only_gids.iter() {
+            assert!(glyf_data(&extended, gid).is_empty());
+        }
+        // Only the entry for the second segment remains.
+        assert!(
+            intersecting_patches(&extended, &SubsetDefinition::codepoints(segment_1.clone()))
+                .unwrap()
+                .is_empty()
+        );
+        assert_ne!(extended.ift().unwrap().compatibility_id(), compat_id());
+
+        // Loading every segment produces the original glyph data, with no mapping table.
+        let extended = extend(&encoded, &SubsetDefinition::codepoints(all_codepoints));
+        let extended = FontRef::new(&extended).unwrap();
+        assert!(extended.table_data(IFT_TAG).is_none());
+        for tag in [Glyf::TAG, Loca::TAG, Gvar::TAG, Tag::new(b"cmap")] {
+            assert_eq!(
+                extended.table_data(tag).unwrap().as_bytes(),
+                font.table_data(tag).unwrap().as_bytes(),
+                "{tag}"
+            );
+        }
+    }
+
+    #[test]
+    fn table_keyed_too_many_segments() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let mut plan = SegmentationPlan::default();
+        plan.set_patch_encoding(PatchEncoding::TableKeyed);
+        for cp in 0..=MAX_TABLE_KEYED_SEGMENTS as u32 {
+            plan.add_segment(SubsetDefinition::codepoints([cp].into_iter().collect()));
+        }
+        assert_eq!(
+            encode(&font, &plan, URI_TEMPLATE, compat_id()),
+            Err(EncodingError::InvalidPlan(
+                "Too many segments for a table keyed encoding."
+            ))
+        );
+    }
+
+    #[test]
+    fn unsupported_fonts() {
+        let font = FontRef::new(font_test_data::CMAP14_FONT1).unwrap();
+        assert_eq!(
+            encode(
+                &font,
+                &SegmentationPlan::default(),
+                URI_TEMPLATE,
+                compat_id()
+            ),
+            Err(EncodingError::UnsupportedFont(
+                "Only fonts with glyf, CFF or CFF2 outlines are supported."
+            ))
+        );
+
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let encoded = encode(
+            &font,
+            &SegmentationPlan::default(),
+            URI_TEMPLATE,
+            compat_id(),
+        )
+        .unwrap();
+        let initial_font = FontRef::new(encoded.initial_font()).unwrap();
+        assert_eq!(
+            encode(
+                &initial_font,
+                &SegmentationPlan::default(),
+                URI_TEMPLATE,
+                compat_id()
+            ),
+            Err(EncodingError::UnsupportedFont(
+                "Font is already an incremental font."
+            ))
+        );
+    }
+
+    #[test]
+    fn duplicate_uris() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let [initial, segment_1, segment_2] = test_codepoints(&font);
+        let mut plan = SegmentationPlan::new(SubsetDefinition::codepoints(initial));
+        plan.add_segment(SubsetDefinition::codepoints(segment_1))
+            .add_segment(SubsetDefinition::codepoints(segment_2));
+        assert_eq!(
+            encode(&font, &plan, "patch.ift_gk", compat_id()),
+            Err(EncodingError::InvalidPlan(
+                "URI template produces the same URI for multiple patches."
+            ))
+        );
+    }
+
+    /// Encodes the test font, then adds an 'IFTX' table which is a copy of the 'IFT ' table.
+    fn font_with_iftx() -> Vec<u8> {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let encoded = encode(
+            &font,
+            &SegmentationPlan::default(),
+            URI_TEMPLATE,
+            compat_id(),
+        )
+        .unwrap();
+        let initial_font = FontRef::new(encoded.initial_font()).unwrap();
+        let mut font_builder = FontBuilder::new();
+        font_builder.add_raw(
+            IFTX_TAG,
+            initial_font
+                .table_data(IFT_TAG)
+                .unwrap()
+                .as_bytes()
+                .to_vec(),
+        );
+        copy_unprocessed_tables(&initial_font, BTreeSet::new(), &mut font_builder);
+        let font_bytes = font_builder.build();
+        set_compatibility_id(
+            &FontRef::new(&font_bytes).unwrap(),
+            IFTX_TAG,
+            &CompatibilityId::from_u32s([5, 6, 7, 8]),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn new_compatibility_ids() {
+        let id_1 = new_compatibility_id();
+        let id_2 = new_compatibility_id();
+        assert_ne!(id_1, id_2);
+        for id in [id_1, id_2] {
+            assert_eq!(id.as_slice()[6] & 0xF0, 0x40);
+            assert_eq!(id.as_slice()[8] & 0xC0, 0x80);
+        }
+    }
+
+    #[test]
+    fn set_compatibility_ids() {
+        let font_bytes = font_with_iftx();
+        let font = FontRef::new(&font_bytes).unwrap();
+        assert_eq!(font.ift().unwrap().compatibility_id(), compat_id());
+        assert_eq!(
+            font.iftx().unwrap().compatibility_id(),
+            CompatibilityId::from_u32s([5, 6, 7, 8])
+        );
+
+        let new_id = CompatibilityId::from_u32s([9, 10, 11, 12]);
+        let updated = set_compatibility_id(&font, IFT_TAG, &new_id).unwrap();
+        let updated = FontRef::new(&updated).unwrap();
+        assert_eq!(updated.ift().unwrap().compatibility_id(), new_id);
+        assert_eq!(
+            updated.iftx().unwrap().compatibility_id(),
+            CompatibilityId::from_u32s([5, 6, 7, 8])
+        );
+        assert_eq!(
+            updated.table_data(IFT_TAG).unwrap().as_bytes()[21..],
+            font.table_data(IFT_TAG).unwrap().as_bytes()[21..]
+        );
+        assert_eq!(
+            updated.table_data(Tag::new(b"cmap")).unwrap().as_bytes(),
+            font.table_data(Tag::new(b"cmap")).unwrap().as_bytes()
+        );
+
+        assert_eq!(
+            set_compatibility_id(&font, Tag::new(b"cmap"), &new_id),
+            Err(EncodingError::UnsupportedFont(
+                "Compatibility ids are only stored in the 'IFT ' and 'IFTX' tables."
+            ))
+        );
+    }
+
+    #[test]
+    fn regenerate_compatibility_ids_for_invalidation() {
+        let font_bytes = font_with_iftx();
+        let font = FontRef::new(&font_bytes).unwrap();
+        let ift_id = font.ift().unwrap().compatibility_id();
+        let iftx_id = font.iftx().unwrap().compatibility_id();
+
+        let (updated, ids) =
+            regenerate_compatibility_ids(&font, IFT_TAG, Invalidation::None).unwrap();
+        assert!(ids.is_empty());
+        let updated = FontRef::new(&updated).unwrap();
+        assert_eq!(updated.ift().unwrap().compatibility_id(), ift_id);
+        assert_eq!(updated.iftx().unwrap().compatibility_id(), iftx_id);
+
+        let (updated, ids) =
+            regenerate_compatibility_ids(&font, IFTX_TAG, Invalidation::Partial).unwrap();
+        assert_eq!(ids.keys().copied().collect::<Vec<_>>(), vec![IFTX_TAG]);
+        let updated = FontRef::new(&updated).unwrap();
+        assert_eq!(updated.ift().unwrap().compatibility_id(), ift_id);
+        assert_eq!(updated.iftx().unwrap().compatibility_id(), ids[&IFTX_TAG]);
+        assert_ne!(ids[&IFTX_TAG], iftx_id);
+
+        let (updated, ids) =
+            regenerate_compatibility_ids(&font, IFT_TAG, Invalidation::Full).unwrap();
+        assert_eq!(
+            ids.keys().copied().collect::<Vec<_>>(),
+            vec![IFT_TAG, IFTX_TAG]
+        );
+        let updated = FontRef::new(&updated).unwrap();
+        assert_eq!(updated.ift().unwrap().compatibility_id(), ids[&IFT_TAG]);
+        assert_eq!(updated.iftx().unwrap().compatibility_id(), ids[&IFTX_TAG]);
+        assert_ne!(ids[&IFT_TAG], ift_id);
+        assert_ne!(ids[&IFT_TAG], ids[&IFTX_TAG]);
+    }
+
+    #[test]
+    fn regenerate_compatibility_ids_missing_table() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let encoded = encode(
+            &font,
+            &SegmentationPlan::default(),
+            URI_TEMPLATE,
+            compat_id(),
+        )
+        .unwrap();
+        let initial_font = FontRef::new(encoded.initial_font()).unwrap();
+        let (updated, ids) =
+            regenerate_compatibility_ids(&initial_font, IFT_TAG, Invalidation::Full).unwrap();
+        assert_eq!(ids.keys().copied().collect::<Vec<_>>(), vec![IFT_TAG]);
+        let updated = FontRef::new(&updated).unwrap();
+        assert_eq!(updated.ift().unwrap().compatibility_id(), ids[&IFT_TAG]);
+        assert!(updated.table_data(IFTX_TAG).is_none());
+    }
+}
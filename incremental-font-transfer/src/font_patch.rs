@@ -10,19 +10,25 @@
 //!    glyph id + table. The patch inserts these blobs into the table at the location for
 //!    the corresponding glyph id.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use crate::patch_group::PatchInfo;
+use crate::patchmap::IftTableTag;
 
-use crate::glyph_keyed::apply_glyph_keyed_patches;
+use crate::glyph_keyed::{apply_glyph_keyed_patches, mark_applied};
 
-use crate::table_keyed::apply_table_keyed_patch;
+use crate::table_keyed::{apply_table_keyed_patch, copy_unprocessed_tables};
 use font_types::Tag;
-use read_fonts::tables::ift::{CompatibilityId, GlyphKeyedPatch, TableKeyedPatch};
+use read_fonts::tables::ift::{
+    CompatibilityId, GlyphKeyedPatch, TableKeyedPatch, IFTX_TAG, IFT_TAG,
+};
+use write_fonts::FontBuilder;
 
 use read_fonts::{FontData, FontRead, FontRef, ReadError};
 
-use shared_brotli_patch_decoder::DecodeError;
+#[cfg(feature = "c-brotli")]
+pub use shared_brotli_patch_decoder::BuiltInBrotliDecoder;
+pub use shared_brotli_patch_decoder::{DecodeError, SharedBrotliDecoder};
 
 /// A trait for types to which an incremental font transfer patch can be applied.
 ///
@@ -37,6 +43,9 @@ pub(crate) trait IncrementalFontPatchBase {
         &self,
         patch: &PatchInfo,
         patch_data: &[u8],
+        decoder: &dyn SharedBrotliDecoder,
+        limits: &DecompressionLimits,
+        observer: &mut dyn PatchObserver,
     ) -> Result<Vec<u8>, PatchingError>;
 
     /// Apply a set of glyph keyed incremental font patches (<https://w3c.github.io/IFT/Overview.html#font-patch-formats>)
@@ -47,9 +56,67 @@ pub(crate) trait IncrementalFontPatchBase {
     fn apply_glyph_keyed_patches<'a>(
         &self,
         patches: impl Iterator<Item = (&'a PatchInfo, &'a [u8])>,
+        decoder: &dyn SharedBrotliDecoder,
+        observer: &mut dyn PatchObserver,
     ) -> Result<Vec<u8>, PatchingError>;
 }
 
+/// Caps on the amount of data that table keyed patch application may decompress.
+///
+/// These apply in addition to the max uncompressed lengths declared by the patches themselves,
+/// and allow memory constrained clients to reject patches which would decompress to more data
+/// than they are willing to hold. `None` means no additional limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecompressionLimits {
+    /// Maximum decompressed size of any single table.
+    pub max_table_size: Option<usize>,
+    /// Maximum combined decompressed size of all tables in a patch.
+    pub max_total_size: Option<usize>,
+}
+
+impl DecompressionLimits {
+    /// The maximum number of bytes a table may decompress to, given the max length declared by the
+    /// patch and the number of bytes already decompressed for other tables in the patch.
+    pub(crate) fn table_limit(&self, declared_max: usize, decompressed_so_far: usize) -> usize {
+        let table_max = self.max_table_size.unwrap_or(usize::MAX);
+        let total_remaining = self
+            .max_total_size
+            .map(|max| max.saturating_sub(decompressed_so_far))
+            .unwrap_or(usize::MAX);
+        declared_max.min(table_max).min(total_remaining)
+    }
+}
+
+/// Receives progress updates while patches are applied, and may cancel the application.
+///
+/// Applying large patches (for example glyph keyed patches for CJK fonts) can take a while on
+/// slow devices. Cancellation is cooperative: it's checked before each table is patched and once
+/// requested application stops with [`PatchingError::Cancelled`], leaving the font unchanged.
+///
+/// `()` can be used as an observer which ignores progress and never cancels.
+pub trait PatchObserver {
+    /// Called after the table `tag` has been processed.
+    ///
+    /// `processed` is the number of tables processed so far by the current application, out of
+    /// `total`.
+    fn table_processed(&mut self, _tag: Tag, _processed: usize, _total: usize) {}
+
+    /// Returns true if the application should be stopped.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+impl PatchObserver for () {}
+
+/// Returns [`PatchingError::Cancelled`] if `observer` has requested cancellation.
+pub(crate) fn check_cancelled(observer: &dyn PatchObserver) -> Result<(), PatchingError> {
+    if observer.is_cancelled() {
+        return Err(PatchingError::Cancelled);
+    }
+    Ok(())
+}
+
 /// An error that occurs while trying to apply an IFT patch to a font file.
 #[derive(Debug, Clone, PartialEq)]
 pub enum PatchingError {
@@ -61,6 +128,7 @@ pub enum PatchingError {
     EmptyPatchList,
     InternalError,
     MissingPatches,
+    Cancelled,
 }
 
 impl From<DecodeError> for PatchingError {
@@ -105,6 +173,7 @@ impl std::fmt::Display for PatchingError {
                 "Internal constraint violated, typically should not happen."
             ),
             PatchingError::MissingPatches => write!(f, "Not all patch data has been supplied."),
+            PatchingError::Cancelled => write!(f, "Patch application was cancelled."),
         }
     }
 }
@@ -116,6 +185,9 @@ impl IncrementalFontPatchBase for FontRef<'_> {
         &self,
         patch: &PatchInfo,
         patch_data: &[u8],
+        decoder: &dyn SharedBrotliDecoder,
+        limits: &DecompressionLimits,
+        observer: &mut dyn PatchObserver,
     ) -> Result<Vec<u8>, PatchingError> {
         let font_compat_id = patch
             .tag()
@@ -125,6 +197,7 @@ impl IncrementalFontPatchBase for FontRef<'_> {
             return Err(PatchingError::IncompatiblePatch);
         }
 
+        let duplicates = patch.duplicates();
         let patch = TableKeyedPatch::read(FontData::new(patch_data))
             .map_err(PatchingError::PatchParsingFailed)?;
 
@@ -132,12 +205,18 @@ impl IncrementalFontPatchBase for FontRef<'_> {
             return Err(PatchingError::IncompatiblePatch);
         }
 
-        apply_table_keyed_patch(&patch, self)
+        let new_font = apply_table_keyed_patch(&patch, self, decoder, limits, observer)?;
+        if duplicates.is_empty() {
+            return Ok(new_font);
+        }
+        mark_duplicates_applied(duplicates, &new_font)
     }
 
     fn apply_glyph_keyed_patches<'a>(
         &self,
         patches: impl Iterator<Item = (&'a PatchInfo, &'a [u8])>,
+        decoder: &dyn SharedBrotliDecoder,
+        observer: &mut dyn PatchObserver,
     ) -> Result<Vec<u8>, PatchingError> {
         let mut cached_compat_ids: HashMap<Tag, Result<CompatibilityId, PatchingError>> =
             Default::default();
@@ -167,32 +246,64 @@ impl IncrementalFontPatchBase for FontRef<'_> {
             raw_patches.push((patch_info, patch));
         }
 
-        apply_glyph_keyed_patches(&raw_patches, self)
+        apply_glyph_keyed_patches(&raw_patches, self, decoder, observer)
     }
 }
 
+/// Marks entries which share the URI of an applied table keyed patch as applied.
+///
+/// Table keyed patches may replace the mapping tables, so entries are only marked in tables which still have the
+/// compatibility id that the entry was found under.
+fn mark_duplicates_applied(
+    duplicates: &[(IftTableTag, usize)],
+    font_data: &[u8],
+) -> Result<Vec<u8>, PatchingError> {
+    let font = FontRef::new(font_data).map_err(PatchingError::FontParsingFailed)?;
+    let entries = duplicates
+        .iter()
+        .filter(|(tag, _)| {
+            tag.font_compat_id(&font)
+                .is_ok_and(|compat_id| compat_id == *tag.expected_compat_id())
+        })
+        .map(|(tag, bit_index)| (tag, *bit_index));
+    let mut font_builder = FontBuilder::new();
+    mark_applied(&font, entries, &mut font_builder)?;
+    copy_unprocessed_tables(
+        &font,
+        BTreeSet::from([IFT_TAG, IFTX_TAG]),
+        &mut font_builder,
+    );
+
+    Ok(font_builder.build())
+}
+
 impl IncrementalFontPatchBase for &[u8] {
     fn apply_table_keyed_patch(
         &self,
         patch: &PatchInfo,
         patch_data: &[u8],
+        decoder: &dyn SharedBrotliDecoder,
+        limits: &DecompressionLimits,
+        observer: &mut dyn PatchObserver,
     ) -> Result<Vec<u8>, PatchingError> {
         FontRef::new(self)
             .map_err(PatchingError::FontParsingFailed)?
-            .apply_table_keyed_patch(patch, patch_data)
+            .apply_table_keyed_patch(patch, patch_data, decoder, limits, observer)
     }
 
     fn apply_glyph_keyed_patches<'a>(
         &self,
         patches: impl Iterator<Item = (&'a PatchInfo, &'a [u8])>,
+        decoder: &dyn SharedBrotliDecoder,
+        observer: &mut dyn PatchObserver,
     ) -> Result<Vec<u8>, PatchingError> {
         FontRef::new(self)
             .map_err(PatchingError::FontParsingFailed)?
-            .apply_glyph_keyed_patches(patches)
+            .apply_glyph_keyed_patches(patches, decoder, observer)
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "c-brotli"))]
 mod tests {
 
     use std::collections::HashMap;
@@ -204,7 +315,7 @@ mod tests {
     use read_fonts::tables::ift::{CompatibilityId, IFTX_TAG, IFT_TAG};
 
     use crate::{
-        font_patch::PatchingError,
+        font_patch::{BuiltInBrotliDecoder, PatchingError},
         glyph_keyed::tests::assemble_glyph_keyed_patch,
         patchmap::{IftTableTag, PatchFormat::GlyphKeyed, PatchFormat::TableKeyed, PatchUri},
     };
@@ -242,7 +353,13 @@ mod tests {
         let mut patch = table_keyed_patch();
         patch.write_at("compat_id", 2);
         assert_eq!(
-            font.as_slice().apply_table_keyed_patch(&info, &patch),
+            font.as_slice().apply_table_keyed_patch(
+                &info,
+                &patch,
+                &BuiltInBrotliDecoder,
+                &Default::default(),
+                &mut (),
+            ),
             Err(PatchingError::IncompatiblePatch)
         );
     }
@@ -269,7 +386,13 @@ mod tests {
 
         let patch = table_keyed_patch();
         assert_eq!(
-            font.as_slice().apply_table_keyed_patch(&info, &patch),
+            font.as_slice().apply_table_keyed_patch(
+                &info,
+                &patch,
+                &BuiltInBrotliDecoder,
+                &Default::default(),
+                &mut (),
+            ),
             Err(PatchingError::IncompatiblePatch)
         );
     }
@@ -297,7 +420,11 @@ mod tests {
 
         let input = vec![(&info, patch.as_slice())];
         assert_eq!(
-            font.as_slice().apply_glyph_keyed_patches(input.into_iter()),
+            font.as_slice().apply_glyph_keyed_patches(
+                input.into_iter(),
+                &BuiltInBrotliDecoder,
+                &mut ()
+            ),
             Err(PatchingError::IncompatiblePatch)
         );
     }
@@ -330,7 +457,11 @@ mod tests {
 
         let input = vec![(&info, patch.as_slice())];
         assert_eq!(
-            font.as_slice().apply_glyph_keyed_patches(input.into_iter()),
+            font.as_slice().apply_glyph_keyed_patches(
+                input.into_iter(),
+                &BuiltInBrotliDecoder,
+                &mut ()
+            ),
             Err(PatchingError::IncompatiblePatch)
         );
     }
@@ -8,30 +8,41 @@
 //! <https://w3c.github.io/IFT/Overview.html#glyph-keyed>
 use crate::patchmap::IftTableTag;
 use crate::table_keyed::copy_unprocessed_tables;
-use crate::{font_patch::PatchingError, patch_group::PatchInfo};
+use crate::{
+    font_patch::{check_cancelled, PatchObserver, PatchingError},
+    patch_group::PatchInfo,
+};
 
-use font_types::Scalar;
 use read_fonts::tables::ift::{IFTX_TAG, IFT_TAG};
 use read_fonts::{
+    array::ComputedArray,
     collections::IntSet,
     tables::{
+        cff::Cff,
+        cff2::Cff2,
+        glyf::Glyf,
+        gvar::{Gvar, GvarFlags, U16Or32},
         ift::{GlyphKeyedPatch, GlyphPatches},
         loca::Loca,
+        maxp::Maxp,
+        postscript::{dict, Index},
     },
-    types::Tag,
-    FontData, FontRef, ReadError, TableProvider,
+    types::{Tag, Version16Dot16},
+    FontData, FontRead, FontRef, ReadError, TableProvider, TopLevelTable,
 };
 
-use shared_brotli_patch_decoder::shared_brotli_decode;
+use shared_brotli_patch_decoder::SharedBrotliDecoder;
 use skrifa::GlyphId;
 use std::collections::{BTreeSet, HashMap};
 use std::ops::RangeInclusive;
 
-use write_fonts::FontBuilder;
+use write_fonts::{from_obj::ToOwnedTable, FontBuilder};
 
 pub(crate) fn apply_glyph_keyed_patches(
     patches: &[(&PatchInfo, GlyphKeyedPatch<'_>)],
     font: &FontRef,
+    decoder: &dyn SharedBrotliDecoder,
+    observer: &mut dyn PatchObserver,
 ) -> Result<Vec<u8>, PatchingError> {
     let mut decompression_buffer: Vec<Vec<u8>> = Vec::with_capacity(patches.len());
 
@@ -40,13 +51,16 @@ pub(crate) fn apply_glyph_keyed_patches(
             return Err(PatchingError::InvalidPatch("Patch file tag is not 'ifgk'"));
         }
 
+        check_cancelled(observer)?;
+
         decompression_buffer.push(
-            shared_brotli_decode(
-                patch.brotli_stream(),
-                None,
-                patch.max_uncompressed_length() as usize,
-            )
-            .map_err(PatchingError::from)?,
+            decoder
+                .decode(
+                    patch.brotli_stream(),
+                    None,
+                    patch.max_uncompressed_length() as usize,
+                )
+                .map_err(PatchingError::from)?,
         );
     }
 
@@ -71,7 +85,18 @@ pub(crate) fn apply_glyph_keyed_patches(
     let mut processed_tables = BTreeSet::from([IFT_TAG, IFTX_TAG]);
     let mut font_builder = FontBuilder::new();
 
-    for table_tag in table_tag_list(&glyph_patches)? {
+    // All table tags other than the glyph data tables are ignored.
+    let table_tags: Vec<Tag> = table_tag_list(&glyph_patches)?
+        .into_iter()
+        .filter(|tag| GLYPH_DATA_TABLES.contains(tag))
+        .collect();
+    for (processed, table_tag) in table_tags.iter().copied().enumerate() {
+        check_cancelled(observer)?;
+
+        // Merge the individual patches into a list of replacement data for gid.
+        let replacements = dedup_gid_replacement_data(glyph_patches.iter(), table_tag)
+            .map_err(PatchingError::PatchParsingFailed)?;
+
         if table_tag == Tag::new(b"glyf") {
             let (Some(glyf), Ok(loca)) = (font.table_data(Tag::new(b"glyf")), font.loca(None))
             else {
@@ -80,7 +105,7 @@ pub(crate) fn apply_glyph_keyed_patches(
                 ));
             };
             patch_glyf_and_loca(
-                &glyph_patches,
+                &replacements,
                 glyf.as_bytes(),
                 loca,
                 max_glyph_id,
@@ -89,38 +114,105 @@ pub(crate) fn apply_glyph_keyed_patches(
             // glyf patch application also generates a loca table.
             processed_tables.insert(table_tag);
             processed_tables.insert(Tag::new(b"loca"));
-        } else if table_tag == Tag::new(b"CFF ")
-            || table_tag == Tag::new(b"CFF2")
-            || table_tag == Tag::new(b"gvar")
-        {
-            // TODO(garretrieger): add CFF, CFF2, and gvar support as well.
-            return Err(PatchingError::InvalidPatch(
-                "CFF, CFF2, and gvar patches are not yet supported.",
-            ));
-        } else {
-            // All other table tags are ignored.
-            continue;
+
+            if update_max_size_of_instructions(font, &mut font_builder)? {
+                processed_tables.insert(Maxp::TAG);
+            }
+        } else if table_tag == Gvar::TAG {
+            let Some(gvar) = font.table_data(Gvar::TAG) else {
+                return Err(PatchingError::InvalidPatch(
+                    "Trying to patch gvar but base font doesn't have it.",
+                ));
+            };
+            let gvar = Gvar::read(gvar).map_err(PatchingError::FontParsingFailed)?;
+            patch_gvar(&replacements, gvar, max_glyph_id, &mut font_builder)?;
+            processed_tables.insert(table_tag);
+        } else if table_tag == Cff::TAG {
+            let Some(cff) = font.table_data(Cff::TAG) else {
+                return Err(PatchingError::InvalidPatch(
+                    "Trying to patch CFF but base font doesn't have it.",
+                ));
+            };
+            let top_dict = Cff::read(cff)
+                .map_err(PatchingError::FontParsingFailed)?
+                .top_dicts()
+                .get(0)
+                .map_err(|_| {
+                    PatchingError::FontParsingFailed(ReadError::MalformedData(
+                        "CFF table has no Top DICT.",
+                    ))
+                })?;
+            patch_charstrings(
+                &replacements,
+                table_tag,
+                cff.as_bytes(),
+                charstrings_offset(top_dict)?,
+                false,
+                max_glyph_id,
+                &mut font_builder,
+            )?;
+            processed_tables.insert(table_tag);
+        } else if table_tag == Cff2::TAG {
+            let Some(cff2) = font.table_data(Cff2::TAG) else {
+                return Err(PatchingError::InvalidPatch(
+                    "Trying to patch CFF2 but base font doesn't have it.",
+                ));
+            };
+            let top_dict = Cff2::read(cff2)
+                .map_err(PatchingError::FontParsingFailed)?
+                .top_dict_data();
+            patch_charstrings(
+                &replacements,
+                table_tag,
+                cff2.as_bytes(),
+                charstrings_offset(top_dict)?,
+                true,
+                max_glyph_id,
+                &mut font_builder,
+            )?;
+            processed_tables.insert(table_tag);
         }
+
+        observer.table_processed(table_tag, processed + 1, table_tags.len());
     }
 
     // Mark patches applied in IFT and IFTX as needed, copy the modified tables into the font builder.
+    mark_applied(
+        font,
+        patches
+            .iter()
+            .flat_map(|(info, _)| info.application_flags()),
+        &mut font_builder,
+    )?;
+
+    copy_unprocessed_tables(font, processed_tables, &mut font_builder);
+
+    Ok(font_builder.build())
+}
+
+/// Sets the applied bit of each of the given mapping table entries.
+///
+/// Copies of the font's IFT and IFTX tables with the bits set are added to font_builder.
+pub(crate) fn mark_applied<'a>(
+    font: &FontRef,
+    entries: impl Iterator<Item = (&'a IftTableTag, usize)>,
+    font_builder: &mut FontBuilder,
+) -> Result<(), PatchingError> {
     let mut new_itf_data = font
         .table_data(IFT_TAG)
         .map(|data| data.as_bytes().to_vec());
     let mut new_itfx_data = font
         .table_data(IFTX_TAG)
         .map(|data| data.as_bytes().to_vec());
-    for (info, _) in patches {
-        let data = match info.tag() {
+    for (tag, bit_index) in entries {
+        let data = match tag {
             IftTableTag::Ift(_) => new_itf_data.as_mut().ok_or(PatchingError::InternalError)?,
             IftTableTag::Iftx(_) => new_itfx_data.as_mut().ok_or(PatchingError::InternalError)?,
         };
-        let byte_index = info.application_flag_bit_index() / 8;
-        let bit_index = (info.application_flag_bit_index() % 8) as u8;
         let byte = data
-            .get_mut(byte_index)
+            .get_mut(bit_index / 8)
             .ok_or(PatchingError::InternalError)?;
-        *byte |= 1 << bit_index;
+        *byte |= 1 << (bit_index % 8) as u8;
     }
 
     if let Some(data) = new_itf_data {
@@ -130,9 +222,7 @@ pub(crate) fn apply_glyph_keyed_patches(
         font_builder.add_raw(IFTX_TAG, data);
     }
 
-    copy_unprocessed_tables(font, processed_tables, &mut font_builder);
-
-    Ok(font_builder.build())
+    Ok(())
 }
 
 fn table_tag_list(glyph_patches: &[GlyphPatches]) -> Result<BTreeSet<Tag>, PatchingError> {
@@ -156,10 +246,21 @@ fn table_tag_list(glyph_patches: &[GlyphPatches]) -> Result<BTreeSet<Tag>, Patch
         .collect::<BTreeSet<Tag>>())
 }
 
+/// Tables containing per glyph data which glyph keyed patches can modify.
+const GLYPH_DATA_TABLES: [Tag; 4] = [Tag::new(b"glyf"), Gvar::TAG, Cff::TAG, Cff2::TAG];
+
+/// New data for a set of glyphs in a single table.
+pub(crate) struct GlyphReplacements<'a> {
+    /// The glyphs to replace.
+    pub(crate) gids: IntSet<GlyphId>,
+    /// The new data for each glyph in `gids`, in glyph id order.
+    pub(crate) data: Vec<&'a [u8]>,
+}
+
 fn dedup_gid_replacement_data<'a>(
     glyph_patches: impl Iterator<Item = &'a GlyphPatches<'a>>,
     table_tag: Tag,
-) -> Result<(IntSet<GlyphId>, Vec<&'a [u8]>), ReadError> {
+) -> Result<GlyphReplacements<'a>, ReadError> {
     // Since the specification allows us to freely choose patch application order (for groups of glyph keyed patches,
     // see: https://w3c.github.io/IFT/Overview.html#extend-font-subset) if two patches affect the same gid we can choose
     // one arbitrarily to remain applied. In this case we choose the first applied patch for each gid be the one that takes
@@ -192,7 +293,10 @@ fn dedup_gid_replacement_data<'a>(
         deduped.push(data_for_gid.get(&gid).unwrap());
     });
 
-    Ok((gids, deduped))
+    Ok(GlyphReplacements {
+        gids,
+        data: deduped,
+    })
 }
 
 fn retained_glyphs_in_font(
@@ -213,9 +317,63 @@ fn retained_glyphs_in_font(
         })
 }
 
+/// Offsets to the per glyph data of a table which glyph keyed patches can modify.
+pub(crate) trait GlyphDataOffsetArray {
+    /// Returns the offset to the data for the glyph at `index`, relative to the start of the
+    /// glyph data.
+    ///
+    /// There is one more offset than there are glyphs, the final offset marks the end of the data.
+    fn offset_for(&self, index: usize) -> Option<usize>;
+}
+
+impl GlyphDataOffsetArray for Loca<'_> {
+    fn offset_for(&self, index: usize) -> Option<usize> {
+        self.get_raw(index).map(|offset| offset as usize)
+    }
+}
+
+impl GlyphDataOffsetArray for ComputedArray<'_, U16Or32> {
+    fn offset_for(&self, index: usize) -> Option<usize> {
+        self.get(index).ok().map(|offset| offset.get() as usize)
+    }
+}
+
+impl GlyphDataOffsetArray for Index<'_> {
+    fn offset_for(&self, index: usize) -> Option<usize> {
+        self.get_offset(index).ok()
+    }
+}
+
+/// Checks that the offsets for `num_glyphs` glyphs are ascending and within the glyph data.
+fn validate_offsets(
+    offsets: &impl GlyphDataOffsetArray,
+    num_glyphs: usize,
+    data_len: usize,
+    unordered_message: &'static str,
+) -> Result<(), PatchingError> {
+    let mut prev = 0;
+    for index in 0..=num_glyphs {
+        let offset = offsets
+            .offset_for(index)
+            .ok_or(PatchingError::FontParsingFailed(ReadError::OutOfBounds))?;
+        if offset < prev {
+            return Err(PatchingError::FontParsingFailed(ReadError::MalformedData(
+                unordered_message,
+            )));
+        }
+        prev = offset;
+    }
+
+    if prev > data_len {
+        return Err(PatchingError::FontParsingFailed(ReadError::OutOfBounds));
+    }
+
+    Ok(())
+}
+
 fn retained_glyphs_total_size(
     gids: &IntSet<GlyphId>,
-    loca: &Loca,
+    offsets: &impl GlyphDataOffsetArray,
     max_glyph_id: GlyphId,
 ) -> Result<u64, PatchingError> {
     let mut total_size = 0u64;
@@ -223,65 +381,46 @@ fn retained_glyphs_total_size(
         let start = keep_range.start();
         let end = keep_range.end();
 
-        let start_offset = loca
-            .get_raw(start.to_u32() as usize)
-            .ok_or(PatchingError::InvalidPatch("Start loca entry is missing."))?;
-        let end_offset = loca
-            .get_raw(end.to_u32() as usize + 1)
-            .ok_or(PatchingError::InvalidPatch("End loca entry is missing."))?;
-
-        total_size += end_offset
-            .checked_sub(start_offset) // TODO: this can be removed if we pre-verify ascending order
-            .ok_or(PatchingError::FontParsingFailed(ReadError::MalformedData(
-                "loca entries are not in ascending order",
-            )))? as u64;
+        let start_offset =
+            offsets
+                .offset_for(start.to_u32() as usize)
+                .ok_or(PatchingError::InvalidPatch(
+                    "Start offset entry is missing.",
+                ))?;
+        let end_offset = offsets
+            .offset_for(end.to_u32() as usize + 1)
+            .ok_or(PatchingError::InvalidPatch("End offset entry is missing."))?;
+
+        total_size +=
+            end_offset
+                .checked_sub(start_offset)
+                .ok_or(PatchingError::FontParsingFailed(ReadError::MalformedData(
+                    "glyph data offsets are not in ascending order",
+                )))? as u64;
     }
 
     Ok(total_size)
 }
 
-trait LocaOffset {
-    fn write_to(self, dest: &mut [u8]);
-}
-
-impl LocaOffset for u32 {
-    fn write_to(self, dest: &mut [u8]) {
-        let data: [u8; 4] = self.to_raw();
-        dest[..4].copy_from_slice(&data);
-    }
-}
-
-impl LocaOffset for u16 {
-    fn write_to(self, dest: &mut [u8]) {
-        let data: [u8; 2] = (self / 2).to_raw();
-        dest[..2].copy_from_slice(&data);
-    }
-}
-
-fn synthesize_glyf_and_loca<OffsetType: LocaOffset + TryFrom<usize>>(
+/// Writes the retained glyphs from `data` along with the replacement data into `new_data`.
+///
+/// Returns the offset of each glyph's data in `new_data`, followed by the end offset. If
+/// `pad_to_even` is set replacement data is padded to an even length, as required by tables
+/// which use short offsets.
+fn synthesize_glyph_data(
     gids: &IntSet<GlyphId>,
     max_glyph_id: GlyphId,
     replacement_data: &[&[u8]],
-    glyf: &[u8],
-    loca: &Loca<'_>,
-    new_glyf: &mut [u8],
-    new_loca: &mut [u8],
-) -> Result<(), PatchingError> {
-    if !loca.all_offsets_are_ascending() {
-        return Err(PatchingError::FontParsingFailed(ReadError::MalformedData(
-            "loca contains unordered offsets.",
-        )));
-    }
-
+    data: &[u8],
+    offsets: &impl GlyphDataOffsetArray,
+    pad_to_even: bool,
+    new_data: &mut [u8],
+) -> Result<Vec<usize>, PatchingError> {
     let mut replace_it = gids.iter_ranges().peekable();
     let mut keep_it = retained_glyphs_in_font(gids, max_glyph_id).peekable();
     let mut replacement_data_it = replacement_data.iter();
     let mut write_index = 0;
-    let is_short_loca = match loca {
-        Loca::Short(_) => true,
-        Loca::Long(_) => false,
-    };
-    let off_size = std::mem::size_of::<OffsetType>();
+    let mut new_offsets = vec![0usize; max_glyph_id.to_u32() as usize + 2];
 
     loop {
         let (range, replace) = match (replace_it.peek(), keep_it.peek()) {
@@ -308,143 +447,357 @@ fn synthesize_glyf_and_loca<OffsetType: LocaOffset + TryFrom<usize>>(
                     .next()
                     .ok_or(PatchingError::InternalError)?;
 
-                new_glyf
+                new_data
                     .get_mut(write_index..write_index + data.len())
                     .ok_or(PatchingError::InternalError)?
                     .copy_from_slice(data);
 
-                let loca_off: OffsetType = write_index
-                    .try_into()
-                    .map_err(|_| PatchingError::InternalError)?;
-
-                loca_off.write_to(
-                    new_loca
-                        .get_mut(gid * off_size..)
-                        .ok_or(PatchingError::InternalError)?,
-                );
+                *new_offsets
+                    .get_mut(gid)
+                    .ok_or(PatchingError::InternalError)? = write_index;
 
                 write_index += data.len();
-                if is_short_loca {
-                    // Add padding for short loca.
+                if pad_to_even {
                     write_index += data.len() % 2;
                 }
             }
         } else {
-            let start_off = loca.get_raw(start).ok_or(PatchingError::InternalError)? as usize;
-            let end_off = loca.get_raw(end + 1).ok_or(PatchingError::InternalError)? as usize;
+            let start_off = offsets
+                .offset_for(start)
+                .ok_or(PatchingError::InternalError)?;
+            let end_off = offsets
+                .offset_for(end + 1)
+                .ok_or(PatchingError::InternalError)?;
             let len = end_off
                 .checked_sub(start_off)
                 .ok_or(PatchingError::InternalError)?;
-            new_glyf
+            new_data
                 .get_mut(write_index..write_index + len)
                 .ok_or(PatchingError::InternalError)?
                 .copy_from_slice(
-                    glyf.get(start_off..end_off)
+                    data.get(start_off..end_off)
                         .ok_or(PatchingError::InternalError)?,
                 );
 
             for gid in start..=end {
-                let cur_off = loca.get_raw(gid).ok_or(PatchingError::InternalError)? as usize;
-                let new_off = cur_off - start_off + write_index;
-
-                let new_off: OffsetType = new_off
-                    .try_into()
-                    .map_err(|_| PatchingError::InternalError)?;
-                new_off.write_to(
-                    new_loca
-                        .get_mut(gid * off_size..)
-                        .ok_or(PatchingError::InternalError)?,
-                );
+                let cur_off = offsets
+                    .offset_for(gid)
+                    .ok_or(PatchingError::InternalError)?;
+                *new_offsets
+                    .get_mut(gid)
+                    .ok_or(PatchingError::InternalError)? = cur_off
+                    .checked_sub(start_off)
+                    .ok_or(PatchingError::InternalError)?
+                    + write_index;
             }
 
             write_index += len;
         }
     }
 
-    // Write the last loca offset
-    let loca_off: OffsetType = write_index
-        .try_into()
-        .map_err(|_| PatchingError::InternalError)?;
-    loca_off.write_to(
-        new_loca
-            .get_mut(new_loca.len() - 2..)
-            .ok_or(PatchingError::InternalError)?,
-    );
+    // Write the end offset
+    *new_offsets.last_mut().ok_or(PatchingError::InternalError)? = write_index;
+
+    Ok(new_offsets)
+}
+
+/// Replaces the data of all glyphs in `replacements`.
+///
+/// Returns the new glyph data and the offsets to each glyph's data within it.
+fn patch_glyph_data(
+    replacements: &GlyphReplacements,
+    data: &[u8],
+    offsets: &impl GlyphDataOffsetArray,
+    max_glyph_id: GlyphId,
+    pad_to_even: bool,
+) -> Result<(Vec<u8>, Vec<usize>), PatchingError> {
+    let GlyphReplacements {
+        gids,
+        data: replacement_data,
+    } = replacements;
+
+    // Step 1: determine the new total size of the glyph data
+    let mut total_size = retained_glyphs_total_size(gids, offsets, max_glyph_id)?;
+    for data in replacement_data.iter() {
+        let len = data.len() as u64;
+        total_size += len + if pad_to_even { len % 2 } else { 0 };
+    }
 
+    if gids.last().unwrap_or(GlyphId::new(0)) > max_glyph_id {
+        return Err(PatchingError::InvalidPatch(
+            "Patch would add a glyph beyond this fonts maximum.",
+        ));
+    }
+
+    // Step 2: patch together the new glyph data (by copying in ranges of data in the correct order).
+    let mut new_data = vec![0u8; total_size as usize];
+    let new_offsets = synthesize_glyph_data(
+        gids,
+        max_glyph_id,
+        replacement_data,
+        data,
+        offsets,
+        pad_to_even,
+        new_data.as_mut_slice(),
+    )?;
+
+    Ok((new_data, new_offsets))
+}
+
+/// Appends `offsets` to `dest` as either short (stored divided by two) or long offsets.
+fn write_short_or_long_offsets(
+    offsets: &[usize],
+    is_short: bool,
+    dest: &mut Vec<u8>,
+) -> Result<(), PatchingError> {
+    for offset in offsets {
+        if is_short {
+            let offset: u16 = (offset / 2)
+                .try_into()
+                .map_err(|_| PatchingError::InternalError)?;
+            dest.extend_from_slice(&offset.to_be_bytes());
+        } else {
+            let offset: u32 = (*offset)
+                .try_into()
+                .map_err(|_| PatchingError::InternalError)?;
+            dest.extend_from_slice(&offset.to_be_bytes());
+        }
+    }
     Ok(())
 }
 
-fn patch_glyf_and_loca<'a>(
-    glyph_patches: &'a [GlyphPatches<'a>],
+pub(crate) fn patch_glyf_and_loca<'a>(
+    replacements: &GlyphReplacements,
     glyf: &[u8],
     loca: Loca<'a>,
     max_glyph_id: GlyphId,
     font_builder: &mut FontBuilder,
 ) -> Result<(), PatchingError> {
-    // TODO(garretrieger) using traits, generalize this approach to any of the supported table types.
-
     let is_short = match loca {
         Loca::Short(_) => true,
         Loca::Long(_) => false,
     };
 
-    // Step 0: merge the individual patches into a list of replacement data for gid.
+    loca.validate(max_glyph_id.to_u32() + 1, glyf.len())
+        .map_err(PatchingError::FontParsingFailed)?;
+
     // TODO(garretrieger): special case where gids is empty, just returned umodified copy of glyf + loca?
-    let (gids, replacement_data) =
-        dedup_gid_replacement_data(glyph_patches.iter(), Tag::new(b"glyf"))
-            .map_err(PatchingError::PatchParsingFailed)?;
+    let (new_glyf, new_offsets) =
+        patch_glyph_data(replacements, glyf, &loca, max_glyph_id, is_short)?;
 
-    // Step 1: determine the new total size of glyf
-    let mut total_glyf_size = retained_glyphs_total_size(&gids, &loca, max_glyph_id)?;
-    for data in replacement_data.iter() {
-        let len = data.len() as u64;
-        // note: include padding as needed for short loca
-        total_glyf_size += len + if is_short { len % 2 } else { 0 };
+    // TODO(garretrieger): check if loca format will need to switch, if so that's an error.
+    let mut new_loca = Vec::with_capacity(new_offsets.len() * if is_short { 2 } else { 4 });
+    write_short_or_long_offsets(&new_offsets, is_short, &mut new_loca)?;
+
+    // Step 3: add new tables to the output builder
+    font_builder.add_raw(Tag::new(b"glyf"), new_glyf);
+    font_builder.add_raw(Tag::new(b"loca"), new_loca);
+
+    Ok(())
+}
+
+/// Recomputes maxSizeOfInstructions in the font's maxp table from the patched glyf and loca tables
+/// in `font_builder`.
+///
+/// Returns true if an updated maxp table was added to `font_builder`.
+fn update_max_size_of_instructions(
+    font: &FontRef,
+    font_builder: &mut FontBuilder,
+) -> Result<bool, PatchingError> {
+    let maxp = font.maxp().map_err(PatchingError::FontParsingFailed)?;
+    if maxp.version() != Version16Dot16::VERSION_1_0 {
+        return Ok(false);
+    }
+    let (Some(glyf), Some(loca)) = (
+        font_builder.table_data(Glyf::TAG),
+        font_builder.table_data(Loca::TAG),
+    ) else {
+        return Err(PatchingError::InternalError);
+    };
+    let is_long = font
+        .head()
+        .map_err(PatchingError::FontParsingFailed)?
+        .index_to_loc_format()
+        == 1;
+    let loca =
+        Loca::read(FontData::new(loca), is_long).map_err(PatchingError::FontParsingFailed)?;
+    let glyf = Glyf::read(FontData::new(glyf)).map_err(PatchingError::FontParsingFailed)?;
+
+    let mut maxp: write_fonts::tables::maxp::Maxp = maxp.to_owned_table();
+    maxp.recalc_max_size_of_instructions(&loca, &glyf)
+        .map_err(PatchingError::FontParsingFailed)?;
+    font_builder
+        .add_table(&maxp)
+        .map_err(|_| PatchingError::InternalError)?;
+    Ok(true)
+}
+
+pub(crate) fn patch_gvar<'a>(
+    replacements: &GlyphReplacements,
+    gvar: Gvar<'a>,
+    max_glyph_id: GlyphId,
+    font_builder: &mut FontBuilder,
+) -> Result<(), PatchingError> {
+    let gvar_data = gvar.offset_data().as_bytes();
+    let num_glyphs = max_glyph_id.to_u32() as usize + 1;
+    if gvar.glyph_count() as usize != num_glyphs {
+        return Err(PatchingError::FontParsingFailed(ReadError::MalformedData(
+            "gvar glyph count does not match the number of glyphs in the font.",
+        )));
     }
 
-    // TODO(garretrieger): pre-check loca has all ascending offsets.
-    // TODO(garretrieger): check if loca format will need to switch, if so that's an error.
+    let glyph_data = gvar_data
+        .get(gvar.glyph_variation_data_array_offset() as usize..)
+        .ok_or(PatchingError::FontParsingFailed(ReadError::OutOfBounds))?;
+    let offsets = gvar.glyph_variation_data_offsets();
+    validate_offsets(
+        &offsets,
+        num_glyphs,
+        glyph_data.len(),
+        "gvar contains unordered offsets.",
+    )?;
+
+    let is_short = !gvar.flags().contains(GvarFlags::LONG_OFFSETS);
+    let (new_glyph_data, new_offsets) =
+        patch_glyph_data(replacements, glyph_data, &offsets, max_glyph_id, is_short)?;
+
+    // Unlike loca the offset size is stored in gvar itself, so switch to long offsets if the
+    // patched data no longer fits in short offsets.
+    let is_short = is_short && new_glyph_data.len() <= 2 * u16::MAX as usize;
+    let flags = if is_short {
+        gvar.flags()
+    } else {
+        gvar.flags() | GvarFlags::LONG_OFFSETS
+    };
 
-    if gids.last().unwrap_or(GlyphId::new(0)) > max_glyph_id {
-        return Err(PatchingError::InvalidPatch(
-            "Patch would add a glyph beyond this fonts maximum.",
-        ));
+    let shared_tuples_start = gvar.shared_tuples_offset().to_u32() as usize;
+    let shared_tuples_len = gvar.shared_tuple_count() as usize * gvar.axis_count() as usize * 2;
+    let shared_tuples = gvar_data
+        .get(shared_tuples_start..shared_tuples_start + shared_tuples_len)
+        .ok_or(PatchingError::FontParsingFailed(ReadError::OutOfBounds))?;
+
+    // The new table is laid out as: header, offsets, shared tuples, glyph data.
+    const HEADER_SIZE: usize = 20;
+    let shared_tuples_offset = HEADER_SIZE + new_offsets.len() * if is_short { 2 } else { 4 };
+    let data_array_offset = shared_tuples_offset + shared_tuples.len();
+    let to_u32 = |value: usize| -> Result<u32, PatchingError> {
+        value.try_into().map_err(|_| PatchingError::InternalError)
+    };
+
+    let mut new_gvar = Vec::with_capacity(data_array_offset + new_glyph_data.len());
+    // version, axis count and shared tuple count are unchanged.
+    new_gvar.extend_from_slice(&gvar_data[..8]);
+    new_gvar.extend_from_slice(&to_u32(shared_tuples_offset)?.to_be_bytes());
+    new_gvar.extend_from_slice(&gvar.glyph_count().to_be_bytes());
+    new_gvar.extend_from_slice(&flags.bits().to_be_bytes());
+    new_gvar.extend_from_slice(&to_u32(data_array_offset)?.to_be_bytes());
+    write_short_or_long_offsets(&new_offsets, is_short, &mut new_gvar)?;
+    new_gvar.extend_from_slice(shared_tuples);
+    new_gvar.extend_from_slice(&new_glyph_data);
+
+    font_builder.add_raw(Gvar::TAG, new_gvar);
+
+    Ok(())
+}
+
+/// Finds the offset to the CharStrings INDEX in a CFF or CFF2 Top DICT.
+fn charstrings_offset(top_dict: &[u8]) -> Result<usize, PatchingError> {
+    dict::entries(top_dict, None)
+        .find_map(|entry| match entry {
+            Ok(dict::Entry::CharstringsOffset(offset)) => Some(Ok(offset)),
+            Err(_) => Some(Err(ReadError::MalformedData("Failed to parse Top DICT."))),
+            _ => None,
+        })
+        .unwrap_or(Err(ReadError::MalformedData(
+            "Top DICT is missing the CharStrings offset.",
+        )))
+        .map_err(PatchingError::FontParsingFailed)
+}
+
+/// Replaces charstrings in the CFF or CFF2 table `table_data`.
+///
+/// Since no other offsets in the table are updated, the CharStrings INDEX is required to be
+/// the last data in the table.
+pub(crate) fn patch_charstrings(
+    replacements: &GlyphReplacements,
+    table_tag: Tag,
+    table_data: &[u8],
+    charstrings_offset: usize,
+    is_cff2: bool,
+    max_glyph_id: GlyphId,
+    font_builder: &mut FontBuilder,
+) -> Result<(), PatchingError> {
+    let charstrings = table_data
+        .get(charstrings_offset..)
+        .and_then(|data| Index::new(data, is_cff2).ok())
+        .ok_or(PatchingError::FontParsingFailed(ReadError::MalformedData(
+            "Failed to parse the CharStrings INDEX.",
+        )))?;
+
+    let num_glyphs = max_glyph_id.to_u32() as usize + 1;
+    if charstrings.count() as usize != num_glyphs {
+        return Err(PatchingError::FontParsingFailed(ReadError::MalformedData(
+            "CharStrings count does not match the number of glyphs in the font.",
+        )));
     }
 
-    // Step 2: patch together the new glyf (by copying in ranges of data in the correct order).
-    let loca_size = (max_glyph_id.to_u32() as usize + 2) * if is_short { 2 } else { 4 };
-    let mut new_glyf = vec![0u8; total_glyf_size as usize];
-    let mut new_loca = vec![0u8; loca_size];
-    if is_short {
-        synthesize_glyf_and_loca::<u16>(
-            &gids,
-            max_glyph_id,
-            &replacement_data,
-            glyf,
-            &loca,
-            new_glyf.as_mut_slice(),
-            new_loca.as_mut_slice(),
-        )?;
+    let index_end = charstrings_offset
+        + charstrings
+            .size_in_bytes()
+            .map_err(PatchingError::FontParsingFailed)?;
+    if index_end != table_data.len() {
+        return Err(PatchingError::FontParsingFailed(ReadError::MalformedData(
+            "CharStrings INDEX must be at the end of the table.",
+        )));
+    }
+
+    let data_len = charstrings
+        .offset_for(num_glyphs)
+        .ok_or(PatchingError::FontParsingFailed(ReadError::OutOfBounds))?;
+    let glyph_data = &table_data[index_end - data_len..];
+    validate_offsets(
+        &charstrings,
+        num_glyphs,
+        glyph_data.len(),
+        "CharStrings contains unordered offsets.",
+    )?;
+
+    let (new_glyph_data, new_offsets) =
+        patch_glyph_data(replacements, glyph_data, &charstrings, max_glyph_id, false)?;
+
+    // INDEX offsets are one based and use the smallest size that can hold the largest offset.
+    let max_offset = new_glyph_data.len() + 1;
+    let off_size: usize = match max_offset {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        0x10000..=0xFFFFFF => 3,
+        _ => 4,
+    };
+
+    let mut new_table = Vec::with_capacity(
+        index_end + (new_offsets.len() * off_size) + new_glyph_data.len() - data_len,
+    );
+    new_table.extend_from_slice(&table_data[..charstrings_offset]);
+    if is_cff2 {
+        new_table.extend_from_slice(&(num_glyphs as u32).to_be_bytes());
     } else {
-        synthesize_glyf_and_loca::<u32>(
-            &gids,
-            max_glyph_id,
-            &replacement_data,
-            glyf,
-            &loca,
-            new_glyf.as_mut_slice(),
-            new_loca.as_mut_slice(),
-        )?;
+        new_table.extend_from_slice(&(num_glyphs as u16).to_be_bytes());
+    }
+    new_table.push(off_size as u8);
+    for offset in new_offsets {
+        let offset: u32 = (offset + 1)
+            .try_into()
+            .map_err(|_| PatchingError::InternalError)?;
+        new_table.extend_from_slice(&offset.to_be_bytes()[4 - off_size..]);
     }
+    new_table.extend_from_slice(&new_glyph_data);
 
-    // Step 3: add new tables to the output builder
-    font_builder.add_raw(Tag::new(b"glyf"), new_glyf);
-    font_builder.add_raw(Tag::new(b"loca"), new_loca);
+    font_builder.add_raw(table_tag, new_table);
 
     Ok(())
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "c-brotli"))]
 pub(crate) mod tests {
     use std::{
         collections::{BTreeSet, HashMap},
@@ -453,10 +806,16 @@ pub(crate) mod tests {
 
     use brotlic::CompressorWriter;
     use read_fonts::{
-        tables::ift::{CompatibilityId, GlyphKeyedPatch, IFTX_TAG, IFT_TAG},
+        tables::{
+            glyf::Glyf,
+            ift::{CompatibilityId, GlyphKeyedPatch, IFTX_TAG, IFT_TAG},
+            loca::Loca,
+            maxp::Maxp,
+        },
         test_helpers::BeBuffer,
-        FontData, FontRead, ReadError, TableProvider,
+        FontData, FontRead, ReadError, TableProvider, TopLevelTable,
     };
+    use shared_brotli_patch_decoder::BuiltInBrotliDecoder;
 
     use font_test_data::ift::{
         glyf_and_gvar_u16_glyph_patches, glyf_u16_glyph_patches, glyf_u16_glyph_patches_2,
@@ -464,9 +823,10 @@ pub(crate) mod tests {
         test_font_for_patching_with_loca_mod,
     };
     use skrifa::{FontRef, Tag};
+    use write_fonts::{from_obj::ToOwnedTable, FontBuilder};
 
     use crate::{
-        font_patch::PatchingError,
+        font_patch::{PatchObserver, PatchingError},
         glyph_keyed::apply_glyph_keyed_patches,
         patchmap::{PatchFormat, PatchUri},
     };
@@ -534,7 +894,13 @@ pub(crate) mod tests {
 
         let patch_info = patch_info(IFT_TAG, 4);
 
-        let patched = apply_glyph_keyed_patches(&[(&patch_info, patch)], &font).unwrap();
+        let patched = apply_glyph_keyed_patches(
+            &[(&patch_info, patch)],
+            &font,
+            &BuiltInBrotliDecoder,
+            &mut (),
+        )
+        .unwrap();
         let patched = FontRef::new(&patched).unwrap();
 
         // Application bit will be set in the patched font.
@@ -546,6 +912,64 @@ pub(crate) mod tests {
         check_tables_equal(&expected_font, &patched, BTreeSet::default());
     }
 
+    #[derive(Default)]
+    struct TestObserver {
+        progress: Vec<(Tag, usize, usize)>,
+        cancelled: bool,
+    }
+
+    impl PatchObserver for TestObserver {
+        fn table_processed(&mut self, tag: Tag, processed: usize, total: usize) {
+            self.progress.push((tag, processed, total));
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.cancelled
+        }
+    }
+
+    #[test]
+    fn glyph_keyed_progress_and_cancellation() {
+        let patch =
+            assemble_glyph_keyed_patch(glyph_keyed_patch_header(), glyf_u16_glyph_patches());
+        let patch: &[u8] = &patch;
+
+        let font = test_font_for_patching();
+        let font = FontRef::new(&font).unwrap();
+        let patch_info = patch_info(IFT_TAG, 28);
+
+        let mut observer = TestObserver::default();
+        apply_glyph_keyed_patches(
+            &[(
+                &patch_info,
+                GlyphKeyedPatch::read(FontData::new(patch)).unwrap(),
+            )],
+            &font,
+            &BuiltInBrotliDecoder,
+            &mut observer,
+        )
+        .unwrap();
+        assert_eq!(observer.progress, vec![(Tag::new(b"glyf"), 1, 1)]);
+
+        let mut observer = TestObserver {
+            cancelled: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            apply_glyph_keyed_patches(
+                &[(
+                    &patch_info,
+                    GlyphKeyedPatch::read(FontData::new(patch)).unwrap(),
+                )],
+                &font,
+                &BuiltInBrotliDecoder,
+                &mut observer,
+            ),
+            Err(PatchingError::Cancelled)
+        );
+        assert!(observer.progress.is_empty());
+    }
+
     #[test]
     fn basic_glyph_keyed() {
         let patch =
@@ -557,7 +981,13 @@ pub(crate) mod tests {
         let font = FontRef::new(&font).unwrap();
 
         let patch_info = patch_info(IFT_TAG, 28);
-        let patched = apply_glyph_keyed_patches(&[(&patch_info, patch)], &font).unwrap();
+        let patched = apply_glyph_keyed_patches(
+            &[(&patch_info, patch)],
+            &font,
+            &BuiltInBrotliDecoder,
+            &mut (),
+        )
+        .unwrap();
         let patched = FontRef::new(&patched).unwrap();
 
         let new_ift: &[u8] = patched.table_data(IFT_TAG).unwrap().as_bytes();
@@ -628,9 +1058,13 @@ pub(crate) mod tests {
         );
         let font = FontRef::new(&font).unwrap();
 
-        let patched =
-            apply_glyph_keyed_patches(&[(&patch_info_2, patch2), (&patch_info_1, patch1)], &font)
-                .unwrap();
+        let patched = apply_glyph_keyed_patches(
+            &[(&patch_info_2, patch2), (&patch_info_1, patch1)],
+            &font,
+            &BuiltInBrotliDecoder,
+            &mut (),
+        )
+        .unwrap();
         let patched = FontRef::new(&patched).unwrap();
 
         let new_ift: &[u8] = patched.table_data(IFTX_TAG).unwrap().as_bytes();
@@ -696,13 +1130,18 @@ pub(crate) mod tests {
         let font = FontRef::new(&font).unwrap();
 
         assert_eq!(
-            apply_glyph_keyed_patches(&[(&patch_info, patch)], &font),
+            apply_glyph_keyed_patches(
+                &[(&patch_info, patch)],
+                &font,
+                &BuiltInBrotliDecoder,
+                &mut ()
+            ),
             Err(PatchingError::InvalidPatch("Patch file tag is not 'ifgk'"))
         );
     }
 
     #[test]
-    fn glyph_keyed_unsupported_table() {
+    fn glyph_keyed_missing_table() {
         let patch = assemble_glyph_keyed_patch(
             glyph_keyed_patch_header(),
             glyf_and_gvar_u16_glyph_patches(),
@@ -715,13 +1154,288 @@ pub(crate) mod tests {
         let font = FontRef::new(&font).unwrap();
 
         assert_eq!(
-            apply_glyph_keyed_patches(&[(&patch_info, patch)], &font),
+            apply_glyph_keyed_patches(
+                &[(&patch_info, patch)],
+                &font,
+                &BuiltInBrotliDecoder,
+                &mut ()
+            ),
             Err(PatchingError::InvalidPatch(
-                "CFF, CFF2, and gvar patches are not yet supported."
+                "Trying to patch gvar but base font doesn't have it."
             ))
         );
     }
 
+    /// Builds a gvar table with short offsets, one axis and one shared tuple.
+    fn gvar_table(glyph_data: &[&[u8]]) -> Vec<u8> {
+        let shared_tuples_offset = 20 + (glyph_data.len() as u32 + 1) * 2;
+        let mut buf = BeBuffer::new()
+            .extend([1u16, 0, 1, 1]) // version, axis count, shared tuple count
+            .push(shared_tuples_offset)
+            .push(glyph_data.len() as u16)
+            .push(0u16) // flags
+            .push(shared_tuples_offset + 2)
+            .push(0u16);
+        let mut offset = 0;
+        for data in glyph_data {
+            offset += data.len();
+            buf = buf.push((offset / 2) as u16);
+        }
+        buf = buf.push(0x4000u16); // shared tuple
+        for data in glyph_data {
+            buf = buf.extend(data.iter().copied());
+        }
+        buf.as_slice().to_vec()
+    }
+
+    /// Builds a CFF or CFF2 table with the CharStrings INDEX at the end of the table.
+    fn charstrings_table(is_cff2: bool, charstrings: &[&[u8]]) -> Vec<u8> {
+        let mut buf = if is_cff2 {
+            BeBuffer::new()
+                .extend([2u8, 0, 5]) // major, minor, header size
+                .push(6u16) // top dict length
+                .push(29u8) // 5 byte integer operand
+                .push(15u32) // charstrings offset
+                .push(17u8) // CharStrings operator
+                .push(0u32) // global subrs INDEX
+                .push(charstrings.len() as u32)
+        } else {
+            BeBuffer::new()
+                .extend([1u8, 0, 4, 1]) // major, minor, header size, offset size
+                .push(1u16) // name INDEX
+                .extend([1u8, 1, 2, b'a'])
+                .push(1u16) // top dict INDEX
+                .extend([1u8, 1, 7])
+                .push(29u8) // 5 byte integer operand
+                .push(25u32) // charstrings offset
+                .push(17u8) // CharStrings operator
+                .push(0u16) // string INDEX
+                .push(0u16) // global subrs INDEX
+                .push(charstrings.len() as u16)
+        };
+        buf = buf.push(1u8).push(1u8);
+        let mut offset = 1;
+        for data in charstrings {
+            offset += data.len();
+            buf = buf.push(offset as u8);
+        }
+        for data in charstrings {
+            buf = buf.extend(data.iter().copied());
+        }
+        buf.as_slice().to_vec()
+    }
+
+    /// Glyph patches which replace gids 2, 7 and 8 in a single table.
+    fn single_table_glyph_patches(tag: Tag) -> BeBuffer {
+        BeBuffer::new()
+            .push(3u32) // glyph count
+            .push(1u8) // table count
+            .extend([2u16, 7, 8]) // glyph ids
+            .push(tag)
+            .extend([31u32, 33, 36, 37]) // glyph data offsets
+            .extend(*b"abcdef")
+    }
+
+    fn font_with_table(tag: Tag, data: &[u8]) -> Vec<u8> {
+        test_font_for_patching_with_loca_mod(
+            |_| {},
+            HashMap::from([(IFT_TAG, vec![0, 0, 0, 0].as_slice()), (tag, data)]),
+        )
+    }
+
+    #[test]
+    fn glyph_keyed_updates_max_size_of_instructions() {
+        // gid 2 of this font has 11941 bytes of instructions, drop it along with gid 1 from the
+        // base font and restore it with a patch.
+        let source = FontRef::new(font_test_data::TTHINT_SUBSET).unwrap();
+        let loca = source.loca(None).unwrap();
+        let glyf = source.glyf().unwrap().offset_data().as_bytes();
+        let is_long = source.head().unwrap().index_to_loc_format() == 1;
+        let gid_0 = loca.get_raw(0).unwrap() as usize..loca.get_raw(1).unwrap() as usize;
+        let gid_2 = loca.get_raw(2).unwrap() as usize..loca.get_raw(3).unwrap() as usize;
+
+        let mut font_builder = FontBuilder::new();
+        for record in source.table_directory.table_records() {
+            let tag = record.tag();
+            if ![Glyf::TAG, Loca::TAG, Maxp::TAG].contains(&tag) {
+                font_builder.add_raw(tag, source.table_data(tag).unwrap());
+            }
+        }
+        let mut maxp: write_fonts::tables::maxp::Maxp = source.maxp().unwrap().to_owned_table();
+        maxp.max_size_of_instructions = Some(0);
+        font_builder.add_table(&maxp).unwrap();
+        font_builder.add_raw(Glyf::TAG, &glyf[gid_0.clone()]);
+        let loca_data: Vec<u8> = [0, gid_0.len(), gid_0.len(), gid_0.len()]
+            .into_iter()
+            .flat_map(|offset| {
+                if is_long {
+                    (offset as u32).to_be_bytes().to_vec()
+                } else {
+                    ((offset / 2) as u16).to_be_bytes().to_vec()
+                }
+            })
+            .collect();
+        font_builder.add_raw(Loca::TAG, loca_data);
+        font_builder.add_raw(IFT_TAG, vec![0u8, 0, 0, 0]);
+        let font = font_builder.build();
+        let font = FontRef::new(&font).unwrap();
+
+        let mut payload = BeBuffer::new()
+            .push(1u32) // glyph count
+            .push(1u8) // table count
+            .push(2u16) // glyph ids
+            .push(Tag::new(b"glyf")) // tables
+            .push_with_tag(0u32, "gid_2_offset")
+            .push_with_tag(0u32, "end_offset");
+        let offset = payload.offset_for("end_offset") as u32 + 4;
+        payload.write_at("gid_2_offset", offset);
+        payload.write_at("end_offset", offset + gid_2.len() as u32);
+        let payload = payload.extend(glyf[gid_2].iter().copied());
+
+        let patch = assemble_glyph_keyed_patch(glyph_keyed_patch_header(), payload);
+        let patch: &[u8] = &patch;
+        let patch = GlyphKeyedPatch::read(FontData::new(patch)).unwrap();
+        let patch_info = patch_info(IFT_TAG, 0);
+
+        let patched = apply_glyph_keyed_patches(
+            &[(&patch_info, patch)],
+            &font,
+            &BuiltInBrotliDecoder,
+            &mut (),
+        )
+        .unwrap();
+        let patched = FontRef::new(&patched).unwrap();
+
+        let patched_maxp = patched.maxp().unwrap();
+        assert_eq!(patched_maxp.max_size_of_instructions(), Some(11941));
+        assert_eq!(
+            patched_maxp.num_glyphs(),
+            source.maxp().unwrap().num_glyphs()
+        );
+    }
+
+    #[test]
+    fn glyph_keyed_gvar() {
+        let patch = assemble_glyph_keyed_patch(
+            glyph_keyed_patch_header(),
+            glyf_and_gvar_u16_glyph_patches(),
+        );
+        let patch: &[u8] = &patch;
+        let patch = GlyphKeyedPatch::read(FontData::new(patch)).unwrap();
+        let patch_info = patch_info(IFT_TAG, 0);
+
+        let mut glyph_data: Vec<&[u8]> = vec![&[]; 15];
+        glyph_data[0] = &[1, 2];
+        glyph_data[1] = &[3, 4, 5, 6];
+        glyph_data[8] = &[7, 8];
+        let font = font_with_table(Tag::new(b"gvar"), &gvar_table(&glyph_data));
+        let font = FontRef::new(&font).unwrap();
+
+        let patched = apply_glyph_keyed_patches(
+            &[(&patch_info, patch)],
+            &font,
+            &BuiltInBrotliDecoder,
+            &mut (),
+        )
+        .unwrap();
+        let patched = FontRef::new(&patched).unwrap();
+
+        // replacement data is padded for short offsets
+        glyph_data[2] = b"mn";
+        glyph_data[7] = b"opq\0";
+        glyph_data[8] = b"r\0";
+        assert_eq!(
+            patched.table_data(Tag::new(b"gvar")).unwrap().as_bytes(),
+            gvar_table(&glyph_data)
+        );
+        let gvar = patched.gvar().unwrap();
+        assert_eq!(
+            gvar.shared_tuple(0).unwrap().values()[0].get().to_bits(),
+            0x4000
+        );
+
+        check_tables_equal(
+            &font,
+            &patched,
+            [
+                Tag::new(b"glyf"),
+                Tag::new(b"loca"),
+                Tag::new(b"gvar"),
+                IFT_TAG,
+            ]
+            .into(),
+        );
+    }
+
+    fn check_charstrings_patch(tag: Tag, is_cff2: bool) {
+        let patch =
+            assemble_glyph_keyed_patch(glyph_keyed_patch_header(), single_table_glyph_patches(tag));
+        let patch: &[u8] = &patch;
+        let patch = GlyphKeyedPatch::read(FontData::new(patch)).unwrap();
+        let patch_info = patch_info(IFT_TAG, 0);
+
+        let gids: Vec<[u8; 1]> = (0..15u8).map(|gid| [gid]).collect();
+        let mut charstrings: Vec<&[u8]> = gids.iter().map(|data| data.as_slice()).collect();
+        let font = font_with_table(tag, &charstrings_table(is_cff2, &charstrings));
+        let font = FontRef::new(&font).unwrap();
+
+        let patched = apply_glyph_keyed_patches(
+            &[(&patch_info, patch)],
+            &font,
+            &BuiltInBrotliDecoder,
+            &mut (),
+        )
+        .unwrap();
+        let patched = FontRef::new(&patched).unwrap();
+
+        charstrings[2] = b"ab";
+        charstrings[7] = b"cde";
+        charstrings[8] = b"f";
+        assert_eq!(
+            patched.table_data(tag).unwrap().as_bytes(),
+            charstrings_table(is_cff2, &charstrings)
+        );
+        check_tables_equal(&font, &patched, [tag, IFT_TAG].into());
+    }
+
+    #[test]
+    fn glyph_keyed_cff() {
+        check_charstrings_patch(Tag::new(b"CFF "), false);
+    }
+
+    #[test]
+    fn glyph_keyed_cff2() {
+        check_charstrings_patch(Tag::new(b"CFF2"), true);
+    }
+
+    #[test]
+    fn glyph_keyed_cff_charstrings_not_at_end() {
+        let tag = Tag::new(b"CFF ");
+        let patch =
+            assemble_glyph_keyed_patch(glyph_keyed_patch_header(), single_table_glyph_patches(tag));
+        let patch: &[u8] = &patch;
+        let patch = GlyphKeyedPatch::read(FontData::new(patch)).unwrap();
+        let patch_info = patch_info(IFT_TAG, 0);
+
+        let charstrings: Vec<&[u8]> = vec![&[0]; 15];
+        let mut cff = charstrings_table(false, &charstrings);
+        cff.push(0);
+        let font = font_with_table(tag, &cff);
+        let font = FontRef::new(&font).unwrap();
+
+        assert_eq!(
+            apply_glyph_keyed_patches(
+                &[(&patch_info, patch)],
+                &font,
+                &BuiltInBrotliDecoder,
+                &mut ()
+            ),
+            Err(PatchingError::FontParsingFailed(ReadError::MalformedData(
+                "CharStrings INDEX must be at the end of the table."
+            )))
+        );
+    }
+
     #[test]
     fn glyph_keyed_unknown_table() {
         let mut builder = glyf_and_gvar_u16_glyph_patches();
@@ -735,7 +1449,13 @@ pub(crate) mod tests {
         let font = test_font_for_patching();
         let font = FontRef::new(&font).unwrap();
 
-        let patched = apply_glyph_keyed_patches(&[(&patch_info, patch)], &font).unwrap();
+        let patched = apply_glyph_keyed_patches(
+            &[(&patch_info, patch)],
+            &font,
+            &BuiltInBrotliDecoder,
+            &mut (),
+        )
+        .unwrap();
         let patched = FontRef::new(&patched).unwrap();
 
         let new_glyf: &[u8] = patched.table_data(Tag::new(b"glyf")).unwrap().as_bytes();
@@ -795,7 +1515,12 @@ pub(crate) mod tests {
         let font = FontRef::new(&font).unwrap();
 
         assert_eq!(
-            apply_glyph_keyed_patches(&[(&patch_info, patch)], &font),
+            apply_glyph_keyed_patches(
+                &[(&patch_info, patch)],
+                &font,
+                &BuiltInBrotliDecoder,
+                &mut ()
+            ),
             Err(PatchingError::InvalidPatch(
                 "Duplicate or unsorted table tag."
             ))
@@ -815,7 +1540,12 @@ pub(crate) mod tests {
         let font = FontRef::new(&font).unwrap();
 
         assert_eq!(
-            apply_glyph_keyed_patches(&[(&patch_info, patch)], &font),
+            apply_glyph_keyed_patches(
+                &[(&patch_info, patch)],
+                &font,
+                &BuiltInBrotliDecoder,
+                &mut ()
+            ),
             Err(PatchingError::InvalidPatch(
                 "Duplicate or unsorted table tag."
             ))
@@ -835,7 +1565,12 @@ pub(crate) mod tests {
         let font = FontRef::new(&font).unwrap();
 
         assert_eq!(
-            apply_glyph_keyed_patches(&[(&patch_info, patch)], &font),
+            apply_glyph_keyed_patches(
+                &[(&patch_info, patch)],
+                &font,
+                &BuiltInBrotliDecoder,
+                &mut ()
+            ),
             Err(PatchingError::PatchParsingFailed(ReadError::MalformedData(
                 "Glyph IDs are unsorted or duplicated."
             ))),
@@ -855,7 +1590,12 @@ pub(crate) mod tests {
         let font = FontRef::new(&font).unwrap();
 
         assert_eq!(
-            apply_glyph_keyed_patches(&[(&patch_info, patch)], &font),
+            apply_glyph_keyed_patches(
+                &[(&patch_info, patch)],
+                &font,
+                &BuiltInBrotliDecoder,
+                &mut ()
+            ),
             Err(PatchingError::PatchParsingFailed(ReadError::MalformedData(
                 "Glyph IDs are unsorted or duplicated."
             ))),
@@ -876,7 +1616,12 @@ pub(crate) mod tests {
         let font = FontRef::new(&font).unwrap();
 
         assert_eq!(
-            apply_glyph_keyed_patches(&[(&patch_info, patch)], &font),
+            apply_glyph_keyed_patches(
+                &[(&patch_info, patch)],
+                &font,
+                &BuiltInBrotliDecoder,
+                &mut ()
+            ),
             Err(PatchingError::InvalidPatch("Max size exceeded.")),
         );
     }
@@ -894,7 +1639,12 @@ pub(crate) mod tests {
         let font = FontRef::new(&font).unwrap();
 
         assert_eq!(
-            apply_glyph_keyed_patches(&[(&patch_info, patch)], &font),
+            apply_glyph_keyed_patches(
+                &[(&patch_info, patch)],
+                &font,
+                &BuiltInBrotliDecoder,
+                &mut ()
+            ),
             Err(PatchingError::InvalidPatch(
                 "Patch would add a glyph beyond this fonts maximum."
             )),
@@ -923,7 +1673,12 @@ pub(crate) mod tests {
         let font = FontRef::new(&font).unwrap();
 
         assert_eq!(
-            apply_glyph_keyed_patches(&[(&patch_info, patch)], &font),
+            apply_glyph_keyed_patches(
+                &[(&patch_info, patch)],
+                &font,
+                &BuiltInBrotliDecoder,
+                &mut ()
+            ),
             Err(PatchingError::FontParsingFailed(ReadError::MalformedData(
                 "loca contains unordered offsets."
             ))),
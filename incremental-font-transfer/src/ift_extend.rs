@@ -60,7 +60,7 @@ fn main() {
         }
 
         println!("  Selected URIs:");
-        for uri in next_patches.uris() {
+        for uri in next_patches.uris().map(|info| info.uri()) {
             println!("    fetching {}", uri);
             let uri_path = args.font.parent().unwrap().join(uri);
             let patch_bytes = std::fs::read(uri_path.clone()).unwrap_or_else(|e| {
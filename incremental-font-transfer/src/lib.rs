@@ -5,14 +5,23 @@
 //!   <https://w3c.github.io/IFT/Overview.html#font-format-extensions>
 //! - Implementation of parsing and apply incremental font patches:
 //!   <https://w3c.github.io/IFT/Overview.html#font-patch-formats>
+//! - A client which drives the extension algorithm, fetching patches with a user supplied
+//!   async fetcher: <https://w3c.github.io/IFT/Overview.html#extend-font-subset>
+//! - Validation of an incremental font against a set of patch files.
+//! - With the `encoder` feature, encoding a font into an initial font and a set of patches.
 //!
 //! Built on top of the read-fonts crate.
 
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 #![forbid(unsafe_code)]
 
+pub mod client;
+pub mod client_state;
+#[cfg(feature = "encoder")]
+pub mod encoder;
 pub mod font_patch;
 pub mod glyph_keyed;
 pub mod patch_group;
 pub mod patchmap;
 pub mod table_keyed;
+pub mod validate;
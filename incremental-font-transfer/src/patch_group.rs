@@ -3,11 +3,24 @@
 //! This provides methods for selecting a maximal group of patches that are compatible with each other and
 //! additionally methods for applying that group of patches.
 
-use read_fonts::{tables::ift::CompatibilityId, FontRef, ReadError, TableProvider};
-use std::collections::{BTreeMap, HashMap};
+use read_fonts::{
+    tables::ift::CompatibilityId,
+    types::{Fixed, Tag},
+    FontRef, ReadError, TableProvider,
+};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
+};
+
+#[cfg(feature = "c-brotli")]
+use crate::font_patch::BuiltInBrotliDecoder;
 
 use crate::{
-    font_patch::{IncrementalFontPatchBase, PatchingError},
+    font_patch::{
+        DecompressionLimits, IncrementalFontPatchBase, PatchObserver, PatchingError,
+        SharedBrotliDecoder,
+    },
     patchmap::{
         intersecting_patches, IftTableTag, IntersectionInfo, PatchFormat, PatchUri,
         SubsetDefinition,
@@ -31,6 +44,18 @@ impl PatchGroup<'_> {
     pub fn select_next_patches<'b>(
         ift_font: FontRef<'b>,
         subset_definition: &SubsetDefinition,
+    ) -> Result<PatchGroup<'b>, ReadError> {
+        Self::select_next_patches_with_scorer(ift_font, subset_definition, &CoverageScorer)
+    }
+
+    /// Intersect the available and unapplied patches in ift_font against subset_definition, using
+    /// scorer to choose between competing invalidating patches.
+    ///
+    /// Returns a group of patches which would be applied next.
+    pub fn select_next_patches_with_scorer<'b>(
+        ift_font: FontRef<'b>,
+        subset_definition: &SubsetDefinition,
+        scorer: &impl PatchScorer,
     ) -> Result<PatchGroup<'b>, ReadError> {
         let candidates = intersecting_patches(&ift_font, subset_definition)?;
         if candidates.is_empty() {
@@ -48,8 +73,12 @@ impl PatchGroup<'_> {
             return Err(ReadError::ValidationError);
         }
 
-        let compat_group =
-            Self::select_next_patches_from_candidates(candidates, ift_compat_id, iftx_compat_id)?;
+        let compat_group = Self::select_next_patches_from_candidates(
+            candidates,
+            ift_compat_id,
+            iftx_compat_id,
+            scorer,
+        )?;
 
         Ok(PatchGroup {
             font: ift_font,
@@ -57,11 +86,42 @@ impl PatchGroup<'_> {
         })
     }
 
-    /// Returns an iterator over URIs in this group.
-    pub fn uris(&self) -> impl Iterator<Item = &str> {
+    /// Returns the URIs of every unapplied patch in the mapping tables of ift_font which intersects
+    /// subset_definition.
+    ///
+    /// The URIs of the group that [`PatchGroup::select_next_patches`] would return come first,
+    /// followed by those of all other intersecting entries in patch map order, without duplicates.
+    ///
+    /// Only the current mapping tables are consulted: applying an invalidating patch replaces them,
+    /// so the URIs needed after it aren't known until the patch is applied. See
+    /// [`crate::client::IftClient::preload_uris`] for the list across all future rounds.
+    pub fn intersecting_uris(
+        ift_font: FontRef,
+        subset_definition: &SubsetDefinition,
+    ) -> Result<Vec<String>, ReadError> {
+        let group = Self::select_next_patches(ift_font.clone(), subset_definition)?;
+        let mut seen: HashSet<String> = Default::default();
+        let mut uris: Vec<String> = vec![];
+        let all_uris = group.uris().map(|info| info.uri().to_string()).chain(
+            intersecting_patches(&ift_font, subset_definition)?
+                .into_iter()
+                .map(|uri| uri.uri_string()),
+        );
+        for uri in all_uris {
+            if seen.insert(uri.clone()) {
+                uris.push(uri);
+            }
+        }
+        Ok(uris)
+    }
+
+    /// Returns an iterator over the URIs in this group, along with metadata about each patch.
+    ///
+    /// The metadata can be used to prioritize fetching of the patches.
+    pub fn uris(&self) -> impl Iterator<Item = PatchUriInfo<'_>> {
         self.invalidating_patch_iter()
             .chain(self.non_invalidating_patch_iter())
-            .map(|info| info.uri.as_str())
+            .map(|info| PatchUriInfo { info })
     }
 
     /// Returns true if there is at least one uri associated with this group.
@@ -75,10 +135,6 @@ impl PatchGroup<'_> {
         }
     }
 
-    fn next_invalidating_patch(&self) -> Option<&PatchInfo> {
-        self.invalidating_patch_iter().next()
-    }
-
     fn invalidating_patch_iter(&self) -> impl Iterator<Item = &PatchInfo> {
         let full = match &self.patches {
             Some(CompatibleGroup::Full(info)) => Some(&info.0),
@@ -128,6 +184,7 @@ impl PatchGroup<'_> {
         candidates: Vec<PatchUri>,
         ift_compat_id: Option<CompatibilityId>,
         iftx_compat_id: Option<CompatibilityId>,
+        scorer: &impl PatchScorer,
     ) -> Result<CompatibleGroup, ReadError> {
         // Some notes about this implementation:
         // - From candidates we need to form the largest possible group of patches which follow the selection criteria
@@ -137,7 +194,8 @@ impl PatchGroup<'_> {
         //   a compatible group appropriately.
         //
         // - When multiple valid choices exist the specification allows the implementation to take one of it's choosing.
-        //   Here we use a heuristic that tries to select the patch which has the most value to the extension request.
+        //   Here we use a heuristic that tries to select the patch which has the most value to the extension request,
+        //   see PatchScorer.
         //
         // - During selection we need to ensure that there are no PatchInfo's with duplicate URIs. The spec doesn't
         //   require erroring on this case, and it's resolved by:
@@ -156,41 +214,44 @@ impl PatchGroup<'_> {
         } = GroupingByInvalidation::group_patches(candidates, ift_compat_id, iftx_compat_id);
 
         // Step 2 - now make patch selections in priority order: first full invalidation, second partial, lastly none.
-        if let Some(patch) = Self::select_invalidating_candidate(full_invalidation) {
-            // TODO(garretrieger): use a heuristic to select the best patch
+        if let Some(patch) = Self::select_invalidating_candidate(full_invalidation, scorer) {
             return Ok(CompatibleGroup::Full(patch.into()));
         }
 
-        let mut ift_selected_uri: Option<String> = None;
-        let ift_scope =
-            Self::select_invalidating_candidate(partial_invalidation_ift).map(|patch| {
-                ift_selected_uri = Some(patch.patch_info.uri.clone());
-                ScopedGroup::PartialInvalidation(patch.into())
-            });
-
-        let mut iftx_selected_uri: Option<String> = None;
-        let iftx_scope = Self::select_invalidating_candidate(
-            partial_invalidation_iftx.into_iter().filter(|patch| {
-                // TODO(garretrieger): use a heuristic to select the best patch
-                let Some(selected) = &ift_selected_uri else {
-                    return true;
-                };
-                selected != &patch.patch_info.uri
-            }),
-        )
-        .map(|patch| {
-            iftx_selected_uri = Some(patch.patch_info.uri.clone());
-            ScopedGroup::PartialInvalidation(patch.into())
-        });
-
-        // URI's which have been selected for use above should not show up in other selections.
-        if let (Some(uri), None) = (&ift_selected_uri, &iftx_selected_uri) {
-            no_invalidation_iftx.remove(uri);
+        let mut ift_selected =
+            Self::select_invalidating_candidate(partial_invalidation_ift, scorer);
+        let ift_selected_uri = ift_selected
+            .as_ref()
+            .map(|patch| patch.patch_info.uri.clone());
+
+        let (iftx_duplicates, partial_invalidation_iftx): (Vec<_>, Vec<_>) =
+            partial_invalidation_iftx
+                .into_iter()
+                .partition(|patch| Some(&patch.patch_info.uri) == ift_selected_uri.as_ref());
+        let mut iftx_selected =
+            Self::select_invalidating_candidate(partial_invalidation_iftx, scorer);
+
+        // URI's which have been selected for use above should not show up in other selections. The entries
+        // which were dropped are recorded on the selected patch so that they get marked as applied along with it.
+        if let Some(patch) = ift_selected.as_mut() {
+            for duplicate in iftx_duplicates {
+                patch.patch_info.add_duplicate(duplicate.patch_info);
+            }
+            if iftx_selected.is_none() {
+                if let Some(duplicate) = no_invalidation_iftx.remove(&patch.patch_info.uri) {
+                    patch.patch_info.add_duplicate(duplicate.0);
+                }
+            }
         }
-        if let (None, Some(uri)) = (ift_selected_uri, iftx_selected_uri) {
-            no_invalidation_ift.remove(&uri);
+        if let (None, Some(patch)) = (&ift_selected, iftx_selected.as_mut()) {
+            if let Some(duplicate) = no_invalidation_ift.remove(&patch.patch_info.uri) {
+                patch.patch_info.add_duplicate(duplicate.0);
+            }
         }
 
+        let ift_scope = ift_selected.map(|patch| ScopedGroup::PartialInvalidation(patch.into()));
+        let iftx_scope = iftx_selected.map(|patch| ScopedGroup::PartialInvalidation(patch.into()));
+
         match (ift_scope, iftx_scope) {
             (Some(scope1), Some(scope2)) => Ok(CompatibleGroup::Mixed {
                 ift: scope1,
@@ -205,9 +266,12 @@ impl PatchGroup<'_> {
                 iftx: scope2,
             }),
             (None, None) => {
-                // The two groups can't contain any duplicate URIs so remove all URIs in ift from iftx.
-                for uri in no_invalidation_ift.keys() {
-                    no_invalidation_iftx.remove(uri);
+                // The two groups can't contain any duplicate URIs so remove all URIs in ift from iftx, keeping
+                // track of the removed entries so they are marked applied along with the ift copy.
+                for (uri, patch) in no_invalidation_ift.iter_mut() {
+                    if let Some(duplicate) = no_invalidation_iftx.remove(uri) {
+                        patch.0.add_duplicate(duplicate.0);
+                    }
                 }
                 Ok(CompatibleGroup::Mixed {
                     ift: ScopedGroup::NoInvalidation(no_invalidation_ift),
@@ -220,37 +284,91 @@ impl PatchGroup<'_> {
     /// Select an entry from a list of candidate invalidating entries according to the specs selection criteria.
     ///
     /// Context: <https://w3c.github.io/IFT/Overview.html#invalidating-patch-selection>
-    fn select_invalidating_candidate<T>(candidates: T) -> Option<CandidatePatch>
+    fn select_invalidating_candidate<T>(
+        candidates: T,
+        scorer: &impl PatchScorer,
+    ) -> Option<CandidatePatch>
     where
         T: IntoIterator<Item = CandidatePatch>,
     {
         // Note:
         // - As mentioned in the spec we can find at least one entry matching that criteria by finding an entry with the
-        //   largest intersection (since that can't be a strict subset of others).
-        // - Intersection size is tracked in intersection info.
-        // - Ties are broken by entry order, which is also tracked in intersection info.
-        // - So it's sufficient to just find a candidate patch with the largest intersection info, relying on it's
-        //   Ord implementation.
+        //   largest intersection (since that can't be a strict subset of others). The default scorer does this.
+        // - Ties are broken by entry order, which is tracked in intersection info.
         candidates
             .into_iter()
-            .max_by_key(|candidate| candidate.intersection_info.clone())
+            .map(|candidate| (scorer.score(&PatchCandidate::new(&candidate)), candidate))
+            .max_by(|(score_a, a), (score_b, b)| {
+                score_a.cmp(score_b).then_with(|| {
+                    b.intersection_info
+                        .entry_order()
+                        .cmp(&a.intersection_info.entry_order())
+                })
+            })
+            .map(|(_, candidate)| candidate)
     }
 
     /// Attempt to apply the next patch (or patches if non-invalidating) listed in this group.
     ///
     /// Returns the bytes of the updated font.
+    #[cfg(feature = "c-brotli")]
     pub fn apply_next_patches(
         self,
         patch_data: &mut HashMap<String, UriStatus>,
     ) -> Result<Vec<u8>, PatchingError> {
-        if let Some(patch) = self.next_invalidating_patch() {
+        self.apply_next_patches_with_limits(patch_data, &DecompressionLimits::default())
+    }
+
+    /// Attempt to apply the next patch (or patches if non-invalidating) listed in this group, failing if
+    /// decompressing a table keyed patch would exceed limits.
+    ///
+    /// Returns the bytes of the updated font.
+    #[cfg(feature = "c-brotli")]
+    pub fn apply_next_patches_with_limits(
+        self,
+        patch_data: &mut HashMap<String, UriStatus>,
+        limits: &DecompressionLimits,
+    ) -> Result<Vec<u8>, PatchingError> {
+        self.apply_next_patches_with_decoder(patch_data, &BuiltInBrotliDecoder, limits)
+    }
+
+    /// Attempt to apply the next patch (or patches if non-invalidating) listed in this group, using decoder
+    /// to decompress the patches.
+    ///
+    /// Returns the bytes of the updated font.
+    pub fn apply_next_patches_with_decoder(
+        self,
+        patch_data: &mut HashMap<String, UriStatus>,
+        decoder: &dyn SharedBrotliDecoder,
+        limits: &DecompressionLimits,
+    ) -> Result<Vec<u8>, PatchingError> {
+        self.apply_next_patches_with_observer(patch_data, decoder, limits, &mut ())
+    }
+
+    /// Attempt to apply the next patch (or patches if non-invalidating) listed in this group, reporting
+    /// progress to observer.
+    ///
+    /// If observer cancels the application [`PatchingError::Cancelled`] is returned and the entries of
+    /// patch_data are left unchanged.
+    ///
+    /// Returns the bytes of the updated font.
+    pub fn apply_next_patches_with_observer(
+        self,
+        patch_data: &mut HashMap<String, UriStatus>,
+        decoder: &dyn SharedBrotliDecoder,
+        limits: &DecompressionLimits,
+        observer: &mut dyn PatchObserver,
+    ) -> Result<Vec<u8>, PatchingError> {
+        for patch in self.invalidating_patch_iter() {
             let entry = patch_data
                 .get_mut(&patch.uri)
                 .ok_or(PatchingError::MissingPatches)?;
 
             match entry {
                 UriStatus::Pending(patch_data) => {
-                    let r = self.font.apply_table_keyed_patch(patch, patch_data)?;
+                    let r = self
+                        .font
+                        .apply_table_keyed_patch(patch, patch_data, decoder, limits, observer)?;
                     *entry = UriStatus::Applied;
                     return Ok(r);
                 }
@@ -278,7 +396,7 @@ impl PatchGroup<'_> {
             }
 
             self.font
-                .apply_glyph_keyed_patches(accumulated_info.into_iter())?
+                .apply_glyph_keyed_patches(accumulated_info.into_iter(), decoder, observer)?
         };
 
         for info in self.non_invalidating_patch_iter() {
@@ -291,6 +409,91 @@ impl PatchGroup<'_> {
     }
 }
 
+/// A view of an invalidating patch which is being considered for selection.
+///
+/// Details describe how the patch's subset definition intersects the requested subset definition.
+pub struct PatchCandidate<'a> {
+    uri: &'a str,
+    info: &'a IntersectionInfo,
+}
+
+impl<'a> PatchCandidate<'a> {
+    fn new(candidate: &'a CandidatePatch) -> Self {
+        PatchCandidate {
+            uri: &candidate.patch_info.uri,
+            info: &candidate.intersection_info,
+        }
+    }
+
+    /// The resolved URI of the patch.
+    pub fn uri(&self) -> &'a str {
+        self.uri
+    }
+
+    /// Number of requested codepoints covered by the patch.
+    pub fn intersecting_codepoints(&self) -> u64 {
+        self.info.intersecting_codepoints()
+    }
+
+    /// Number of requested layout feature tags covered by the patch.
+    pub fn intersecting_layout_tags(&self) -> usize {
+        self.info.intersecting_layout_tags()
+    }
+
+    /// Total size of the requested design space covered by the patch, per axis.
+    pub fn intersecting_design_space(&self) -> &'a BTreeMap<Tag, Fixed> {
+        self.info.intersecting_design_space()
+    }
+
+    /// Number of codepoints covered by the patch which were not requested.
+    ///
+    /// This is always 0 for patches from format 1 patch maps where the total coverage of an entry
+    /// isn't tracked.
+    pub fn extraneous_codepoints(&self) -> u64 {
+        self.info.extraneous_codepoints()
+    }
+
+    /// Position of the patch's entry within its patch map.
+    pub fn entry_order(&self) -> usize {
+        self.info.entry_order()
+    }
+}
+
+/// Policy for choosing between multiple candidate invalidating patches.
+///
+/// Only one invalidating patch can be applied at a time, the candidate with the highest score is
+/// selected and ties are broken in favour of the lowest entry order.
+///
+/// To remain conformant with the specification a scorer must never rank a candidate whose
+/// intersection is a strict subset of another candidate's intersection above that candidate. See:
+/// <https://w3c.github.io/IFT/Overview.html#invalidating-patch-selection>
+pub trait PatchScorer {
+    type Score: Ord;
+
+    /// Compute the score for a single candidate.
+    fn score(&self, candidate: &PatchCandidate) -> Self::Score;
+}
+
+/// The default [`PatchScorer`].
+///
+/// Prefers patches which cover the most requested codepoints, then layout tags, then design space.
+/// Remaining ties are broken by preferring patches which bring in the fewest unrequested codepoints.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct CoverageScorer;
+
+impl PatchScorer for CoverageScorer {
+    type Score = (u64, usize, BTreeMap<Tag, Fixed>, Reverse<u64>);
+
+    fn score(&self, candidate: &PatchCandidate) -> Self::Score {
+        (
+            candidate.intersecting_codepoints(),
+            candidate.intersecting_layout_tags(),
+            candidate.intersecting_design_space().clone(),
+            Reverse(candidate.extraneous_codepoints()),
+        )
+    }
+}
+
 #[derive(Default)]
 struct GroupingByInvalidation {
     full_invalidation: Vec<CandidatePatch>,
@@ -350,12 +553,75 @@ pub enum UriStatus {
     Pending(Vec<u8>),
 }
 
+/// Describes how much of a font is invalidated by the application of a patch.
+///
+/// See: <https://w3c.github.io/IFT/Overview.html#font-patch-invalidations>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Invalidation {
+    Full,
+    Partial,
+    None,
+}
+
+/// A URI in a [`PatchGroup`] along with metadata about the patch it points to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PatchUriInfo<'a> {
+    info: &'a PatchInfo,
+}
+
+impl<'a> PatchUriInfo<'a> {
+    /// The URI of the patch.
+    pub fn uri(&self) -> &'a str {
+        self.info.uri.as_str()
+    }
+
+    /// The encoding of the patch.
+    pub fn encoding(&self) -> PatchFormat {
+        self.info.encoding
+    }
+
+    /// How much of the font is invalidated by applying the patch.
+    pub fn invalidation(&self) -> Invalidation {
+        match self.info.encoding {
+            PatchFormat::TableKeyed {
+                fully_invalidating: true,
+            } => Invalidation::Full,
+            PatchFormat::TableKeyed {
+                fully_invalidating: false,
+            } => Invalidation::Partial,
+            PatchFormat::GlyphKeyed => Invalidation::None,
+        }
+    }
+
+    /// Returns true if applying the patch invalidates other patches in the font.
+    pub fn is_invalidating(&self) -> bool {
+        self.invalidation() != Invalidation::None
+    }
+
+    /// The tag of the mapping table (IFT or IFTX) which the patch was found in.
+    pub fn source_table(&self) -> Tag {
+        self.info.source_table.tag()
+    }
+
+    /// Total number of codepoints covered by the patch map entry for this patch.
+    ///
+    /// This is `None` when the coverage isn't known, as is the case for format 1 patch maps.
+    pub fn entry_codepoints(&self) -> Option<u64> {
+        self.info.entry_codepoints
+    }
+}
+
 /// Tracks information related to a patch necessary to apply that patch.
 #[derive(PartialEq, Eq, Debug)]
 pub(crate) struct PatchInfo {
     uri: String,
     source_table: IftTableTag,
     application_flag_bit_index: usize,
+    encoding: PatchFormat,
+    entry_codepoints: Option<u64>,
+    // Other mapping table entries with the same URI which were dropped during selection, as
+    // (table, application flag bit index). These are marked applied along with this patch.
+    duplicates: Vec<(IftTableTag, usize)>,
 }
 
 impl PatchInfo {
@@ -363,8 +629,24 @@ impl PatchInfo {
         &self.source_table
     }
 
-    pub(crate) fn application_flag_bit_index(&self) -> usize {
-        self.application_flag_bit_index
+    /// Mapping table entries, from any table, which share this patch's URI and were dropped during selection.
+    pub(crate) fn duplicates(&self) -> &[(IftTableTag, usize)] {
+        &self.duplicates
+    }
+
+    /// All mapping table entries which should be marked applied once this patch has been applied.
+    pub(crate) fn application_flags(&self) -> impl Iterator<Item = (&IftTableTag, usize)> {
+        std::iter::once((&self.source_table, self.application_flag_bit_index)).chain(
+            self.duplicates
+                .iter()
+                .map(|(tag, bit_index)| (tag, *bit_index)),
+        )
+    }
+
+    fn add_duplicate(&mut self, other: PatchInfo) {
+        self.duplicates
+            .push((other.source_table, other.application_flag_bit_index));
+        self.duplicates.extend(other.duplicates);
     }
 }
 
@@ -373,7 +655,10 @@ impl From<PatchUri> for PatchInfo {
         PatchInfo {
             uri: value.uri_string(),
             application_flag_bit_index: value.application_flag_bit_index(),
+            encoding: value.encoding(),
+            entry_codepoints: value.entry_codepoints(),
             source_table: value.source_table(),
+            duplicates: vec![],
         }
     }
 }
@@ -470,7 +755,7 @@ where
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "c-brotli"))]
 mod tests {
     use std::collections::HashMap;
 
@@ -489,7 +774,10 @@ mod tests {
         FontRef,
     };
 
-    use write_fonts::FontBuilder;
+    use write_fonts::{
+        tables::ift::{EntryData, Ift as WriteIft, PatchMapFormat2},
+        FontBuilder,
+    };
 
     const TABLE_1_FINAL_STATE: &[u8] = "hijkabcdeflmnohijkabcdeflmno\n".as_bytes();
     const TABLE_2_FINAL_STATE: &[u8] = "foobarbaz foobarbaz foobarbaz\n".as_bytes();
@@ -657,19 +945,40 @@ mod tests {
         )
     }
 
-    fn patch_info_ift(uri: &str) -> PatchInfo {
+    const FULL: PatchFormat = PatchFormat::TableKeyed {
+        fully_invalidating: true,
+    };
+    const PARTIAL: PatchFormat = PatchFormat::TableKeyed {
+        fully_invalidating: false,
+    };
+    const NONE: PatchFormat = PatchFormat::GlyphKeyed;
+
+    fn patch_info_ift(encoding: PatchFormat, uri: &str) -> PatchInfo {
         PatchInfo {
             uri: uri.to_string(),
             application_flag_bit_index: 42,
             source_table: IftTableTag::Ift(cid_1()),
+            encoding,
+            entry_codepoints: None,
+            duplicates: vec![],
+        }
+    }
+
+    impl PatchInfo {
+        fn with_duplicate(mut self, tag: IftTableTag, application_flag_bit_index: usize) -> Self {
+            self.duplicates.push((tag, application_flag_bit_index));
+            self
         }
     }
 
-    fn patch_info_iftx(uri: &str) -> PatchInfo {
+    fn patch_info_iftx(encoding: PatchFormat, uri: &str) -> PatchInfo {
         PatchInfo {
             uri: uri.to_string(),
             application_flag_bit_index: 42,
             source_table: IftTableTag::Iftx(cid_2()),
+            encoding,
+            entry_codepoints: None,
+            duplicates: vec![],
         }
     }
 
@@ -679,12 +988,13 @@ mod tests {
             vec![p1_full()],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
         assert_eq!(
             group,
-            CompatibleGroup::Full(FullInvalidationPatch(patch_info_ift("//foo.bar/04")))
+            CompatibleGroup::Full(FullInvalidationPatch(patch_info_ift(FULL, "//foo.bar/04")))
         );
 
         let group = PatchGroup::select_next_patches_from_candidates(
@@ -697,12 +1007,13 @@ mod tests {
             ],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
         assert_eq!(
             group,
-            CompatibleGroup::Full(FullInvalidationPatch(patch_info_ift("//foo.bar/04"),))
+            CompatibleGroup::Full(FullInvalidationPatch(patch_info_ift(FULL, "//foo.bar/04"),))
         );
     }
 
@@ -712,12 +1023,110 @@ mod tests {
             vec![full(3, 9), full(1, 7), full(2, 24)],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
+        )
+        .unwrap();
+
+        assert_eq!(
+            group,
+            CompatibleGroup::Full(FullInvalidationPatch(patch_info_ift(FULL, "//foo.bar/08")))
+        );
+    }
+
+    #[test]
+    fn full_invalidation_prefers_least_extraneous() {
+        let with_extraneous = |index, extraneous| {
+            PatchUri::from_index(
+                "//foo.bar/{id}",
+                index,
+                IftTableTag::Ift(cid_1()),
+                42,
+                PatchFormat::TableKeyed {
+                    fully_invalidating: true,
+                },
+                IntersectionInfo::new(10, 0, index as usize).with_extraneous_codepoints(extraneous),
+            )
+        };
+        let group = PatchGroup::select_next_patches_from_candidates(
+            vec![
+                with_extraneous(1, 30),
+                with_extraneous(2, 5),
+                with_extraneous(3, 5),
+            ],
+            Some(cid_1()),
+            Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
+        // 2 and 3 tie, so the lower entry order wins.
         assert_eq!(
             group,
-            CompatibleGroup::Full(FullInvalidationPatch(patch_info_ift("//foo.bar/08")))
+            CompatibleGroup::Full(FullInvalidationPatch(patch_info_ift(FULL, "//foo.bar/08")))
+        );
+    }
+
+    #[test]
+    fn custom_scorer() {
+        // Keeps the default coverage ordering, which is required for conformance, but breaks ties
+        // in favour of the entry which appears last in the patch map.
+        struct PreferLaterEntries;
+
+        impl PatchScorer for PreferLaterEntries {
+            type Score = (u64, usize, BTreeMap<Tag, Fixed>, usize);
+
+            fn score(&self, candidate: &PatchCandidate) -> Self::Score {
+                (
+                    candidate.intersecting_codepoints(),
+                    candidate.intersecting_layout_tags(),
+                    candidate.intersecting_design_space().clone(),
+                    candidate.entry_order(),
+                )
+            }
+        }
+
+        let with_order = |index, codepoints| {
+            PatchUri::from_index(
+                "//foo.bar/{id}",
+                index,
+                IftTableTag::Ift(cid_1()),
+                42,
+                PatchFormat::TableKeyed {
+                    fully_invalidating: true,
+                },
+                IntersectionInfo::new(codepoints, 0, index as usize),
+            )
+        };
+        let candidates = vec![
+            with_order(1, 10),
+            with_order(2, 10),
+            with_order(3, 10),
+            with_order(4, 5),
+        ];
+
+        let group = PatchGroup::select_next_patches_from_candidates(
+            candidates.clone(),
+            Some(cid_1()),
+            Some(cid_2()),
+            &CoverageScorer,
+        )
+        .unwrap();
+        assert_eq!(
+            group,
+            CompatibleGroup::Full(FullInvalidationPatch(patch_info_ift(FULL, "//foo.bar/04")))
+        );
+
+        // 1, 2 and 3 tie on coverage so the latest entry wins, 4 covers less and is never selected.
+        let group = PatchGroup::select_next_patches_from_candidates(
+            candidates,
+            Some(cid_1()),
+            Some(cid_2()),
+            &PreferLaterEntries,
+        )
+        .unwrap();
+        assert_eq!(
+            group,
+            CompatibleGroup::Full(FullInvalidationPatch(patch_info_ift(FULL, "//foo.bar/0C")))
         );
     }
 
@@ -732,6 +1141,7 @@ mod tests {
             ],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
@@ -739,6 +1149,7 @@ mod tests {
             group,
             CompatibleGroup::Mixed {
                 ift: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_ift(
+                    PARTIAL,
                     "//foo.bar/08"
                 ),)),
                 iftx: ScopedGroup::NoInvalidation(BTreeMap::default()),
@@ -754,6 +1165,7 @@ mod tests {
             ],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
@@ -763,6 +1175,7 @@ mod tests {
                 ift: ScopedGroup::NoInvalidation(BTreeMap::default()),
 
                 iftx: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_iftx(
+                    PARTIAL,
                     "//foo.bar/0K"
                 ),)),
             }
@@ -780,6 +1193,7 @@ mod tests {
             ],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
@@ -787,9 +1201,11 @@ mod tests {
             group,
             CompatibleGroup::Mixed {
                 ift: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_ift(
+                    PARTIAL,
                     "//foo.bar/08"
                 ),)),
                 iftx: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_iftx(
+                    PARTIAL,
                     "//foo.bar/0K"
                 ),)),
             }
@@ -803,6 +1219,7 @@ mod tests {
             vec![p2_partial_c1(), p4_no_c1(), p5_no_c2()],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
@@ -810,11 +1227,12 @@ mod tests {
             group,
             CompatibleGroup::Mixed {
                 ift: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_ift(
+                    PARTIAL,
                     "//foo.bar/08"
                 ),)),
                 iftx: ScopedGroup::NoInvalidation(BTreeMap::from([(
                     "//foo.bar/0K".to_string(),
-                    NoInvalidationPatch(patch_info_iftx("//foo.bar/0K"))
+                    NoInvalidationPatch(patch_info_iftx(NONE, "//foo.bar/0K"))
                 )]))
             }
         );
@@ -824,6 +1242,7 @@ mod tests {
             vec![p3_partial_c2(), p4_no_c1(), p5_no_c2()],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
@@ -832,9 +1251,10 @@ mod tests {
             CompatibleGroup::Mixed {
                 ift: ScopedGroup::NoInvalidation(BTreeMap::from([(
                     "//foo.bar/0G".to_string(),
-                    NoInvalidationPatch(patch_info_ift("//foo.bar/0G"))
+                    NoInvalidationPatch(patch_info_ift(NONE, "//foo.bar/0G"))
                 )])),
                 iftx: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_iftx(
+                    PARTIAL,
                     "//foo.bar/0C"
                 ),))
             }
@@ -845,6 +1265,7 @@ mod tests {
             vec![p2_partial_c1(), p4_no_c1()],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
@@ -852,6 +1273,7 @@ mod tests {
             group,
             CompatibleGroup::Mixed {
                 ift: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_ift(
+                    PARTIAL,
                     "//foo.bar/08"
                 ),)),
                 iftx: ScopedGroup::NoInvalidation(BTreeMap::default()),
@@ -863,6 +1285,7 @@ mod tests {
             vec![p3_partial_c2(), p5_no_c2()],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
@@ -871,6 +1294,7 @@ mod tests {
             CompatibleGroup::Mixed {
                 ift: ScopedGroup::NoInvalidation(BTreeMap::default()),
                 iftx: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_iftx(
+                    PARTIAL,
                     "//foo.bar/0C"
                 ),)),
             }
@@ -884,6 +1308,7 @@ mod tests {
             vec![p2_partial_c1(), p4_no_c1(), p5_no_c2()],
             None,
             None,
+            &CoverageScorer,
         )
         .unwrap();
 
@@ -900,6 +1325,7 @@ mod tests {
             vec![p2_partial_c1(), p4_no_c1(), p5_no_c2()],
             Some(cid_1()),
             None,
+            &CoverageScorer,
         )
         .unwrap();
 
@@ -907,6 +1333,7 @@ mod tests {
             group,
             CompatibleGroup::Mixed {
                 ift: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_ift(
+                    PARTIAL,
                     "//foo.bar/08"
                 ),)),
                 iftx: ScopedGroup::NoInvalidation(Default::default()),
@@ -918,6 +1345,7 @@ mod tests {
             vec![p2_partial_c1(), p4_no_c1(), p5_no_c2()],
             None,
             Some(cid_1()),
+            &CoverageScorer,
         )
         .unwrap();
 
@@ -926,6 +1354,7 @@ mod tests {
             CompatibleGroup::Mixed {
                 ift: ScopedGroup::NoInvalidation(Default::default()),
                 iftx: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_ift(
+                    PARTIAL,
                     "//foo.bar/08"
                 ),)),
             }
@@ -939,6 +1368,7 @@ mod tests {
             vec![p4_no_c1(), p4_no_c1()],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
@@ -947,7 +1377,7 @@ mod tests {
             CompatibleGroup::Mixed {
                 ift: ScopedGroup::NoInvalidation(BTreeMap::from([(
                     "//foo.bar/0G".to_string(),
-                    NoInvalidationPatch(patch_info_ift("//foo.bar/0G"))
+                    NoInvalidationPatch(patch_info_ift(NONE, "//foo.bar/0G"))
                 )])),
                 iftx: ScopedGroup::NoInvalidation(BTreeMap::new()),
             }
@@ -958,6 +1388,7 @@ mod tests {
             vec![p4_no_c1(), p4_no_c2(), p5_no_c2()],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
@@ -966,11 +1397,14 @@ mod tests {
             CompatibleGroup::Mixed {
                 ift: ScopedGroup::NoInvalidation(BTreeMap::from([(
                     "//foo.bar/0G".to_string(),
-                    NoInvalidationPatch(patch_info_ift("//foo.bar/0G"))
+                    NoInvalidationPatch(
+                        patch_info_ift(NONE, "//foo.bar/0G")
+                            .with_duplicate(IftTableTag::Iftx(cid_2()), 42)
+                    )
                 )])),
                 iftx: ScopedGroup::NoInvalidation(BTreeMap::from([(
                     "//foo.bar/0K".to_string(),
-                    NoInvalidationPatch(patch_info_iftx("//foo.bar/0K"))
+                    NoInvalidationPatch(patch_info_iftx(NONE, "//foo.bar/0K"))
                 )])),
             }
         );
@@ -980,16 +1414,19 @@ mod tests {
             vec![p2_partial_c1(), p2_partial_c2(), p3_partial_c2()],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
         assert_eq!(
             group,
             CompatibleGroup::Mixed {
-                ift: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_ift(
-                    "//foo.bar/08"
-                ))),
+                ift: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(
+                    patch_info_ift(PARTIAL, "//foo.bar/08")
+                        .with_duplicate(IftTableTag::Iftx(cid_2()), 42)
+                )),
                 iftx: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_iftx(
+                    PARTIAL,
                     "//foo.bar/0C"
                 ))),
             }
@@ -1000,18 +1437,20 @@ mod tests {
             vec![p2_partial_c1(), p2_no_c2(), p5_no_c2()],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
         assert_eq!(
             group,
             CompatibleGroup::Mixed {
-                ift: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_ift(
-                    "//foo.bar/08"
-                ))),
+                ift: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(
+                    patch_info_ift(PARTIAL, "//foo.bar/08")
+                        .with_duplicate(IftTableTag::Iftx(cid_2()), 42)
+                )),
                 iftx: ScopedGroup::NoInvalidation(BTreeMap::from([(
                     "//foo.bar/0K".to_string(),
-                    NoInvalidationPatch(patch_info_iftx("//foo.bar/0K"))
+                    NoInvalidationPatch(patch_info_iftx(NONE, "//foo.bar/0K"))
                 )])),
             }
         );
@@ -1020,6 +1459,7 @@ mod tests {
             vec![p3_partial_c2(), p3_no_c1(), p4_no_c1()],
             Some(cid_1()),
             Some(cid_2()),
+            &CoverageScorer,
         )
         .unwrap();
 
@@ -1028,20 +1468,25 @@ mod tests {
             CompatibleGroup::Mixed {
                 ift: ScopedGroup::NoInvalidation(BTreeMap::from([(
                     "//foo.bar/0G".to_string(),
-                    NoInvalidationPatch(patch_info_ift("//foo.bar/0G"))
+                    NoInvalidationPatch(patch_info_ift(NONE, "//foo.bar/0G"))
                 )])),
-                iftx: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(patch_info_iftx(
-                    "//foo.bar/0C"
-                ))),
+                iftx: ScopedGroup::PartialInvalidation(PartialInvalidationPatch(
+                    patch_info_iftx(PARTIAL, "//foo.bar/0C")
+                        .with_duplicate(IftTableTag::Ift(cid_1()), 42)
+                )),
             }
         );
     }
 
     fn create_group_for(uris: Vec<PatchUri>) -> PatchGroup<'static> {
         let data = FontRef::new(font_test_data::CMAP12_FONT1).unwrap();
-        let group =
-            PatchGroup::select_next_patches_from_candidates(uris, Some(cid_1()), Some(cid_2()))
-                .unwrap();
+        let group = PatchGroup::select_next_patches_from_candidates(
+            uris,
+            Some(cid_1()),
+            Some(cid_2()),
+            &CoverageScorer,
+        )
+        .unwrap();
 
         PatchGroup {
             font: data,
@@ -1060,53 +1505,161 @@ mod tests {
     #[test]
     fn uris() {
         let g = create_group_for(vec![]);
-        assert_eq!(g.uris().collect::<Vec<&str>>(), Vec::<&str>::default());
+        assert_eq!(
+            g.uris().map(|info| info.uri()).collect::<Vec<&str>>(),
+            Vec::<&str>::default()
+        );
         assert!(!g.has_uris());
 
         let g = empty_group();
-        assert_eq!(g.uris().collect::<Vec<&str>>(), Vec::<&str>::default());
+        assert_eq!(
+            g.uris().map(|info| info.uri()).collect::<Vec<&str>>(),
+            Vec::<&str>::default()
+        );
         assert!(!g.has_uris());
 
         let g = create_group_for(vec![p1_full()]);
-        assert_eq!(g.uris().collect::<Vec<&str>>(), vec!["//foo.bar/04"],);
+        assert_eq!(
+            g.uris().map(|info| info.uri()).collect::<Vec<&str>>(),
+            vec!["//foo.bar/04"],
+        );
         assert!(g.has_uris());
 
         let g = create_group_for(vec![p2_partial_c1(), p3_partial_c2()]);
         assert_eq!(
-            g.uris().collect::<Vec<&str>>(),
+            g.uris().map(|info| info.uri()).collect::<Vec<&str>>(),
             vec!["//foo.bar/08", "//foo.bar/0C"]
         );
         assert!(g.has_uris());
 
         let g = create_group_for(vec![p2_partial_c1()]);
-        assert_eq!(g.uris().collect::<Vec<&str>>(), vec!["//foo.bar/08",],);
+        assert_eq!(
+            g.uris().map(|info| info.uri()).collect::<Vec<&str>>(),
+            vec!["//foo.bar/08",],
+        );
         assert!(g.has_uris());
 
         let g = create_group_for(vec![p3_partial_c2()]);
-        assert_eq!(g.uris().collect::<Vec<&str>>(), vec!["//foo.bar/0C"],);
+        assert_eq!(
+            g.uris().map(|info| info.uri()).collect::<Vec<&str>>(),
+            vec!["//foo.bar/0C"],
+        );
         assert!(g.has_uris());
 
         let g = create_group_for(vec![p2_partial_c1(), p4_no_c2(), p5_no_c2()]);
         assert_eq!(
-            g.uris().collect::<Vec<&str>>(),
+            g.uris().map(|info| info.uri()).collect::<Vec<&str>>(),
             vec!["//foo.bar/08", "//foo.bar/0G", "//foo.bar/0K"],
         );
         assert!(g.has_uris());
 
         let g = create_group_for(vec![p3_partial_c2(), p4_no_c1()]);
         assert_eq!(
-            g.uris().collect::<Vec<&str>>(),
+            g.uris().map(|info| info.uri()).collect::<Vec<&str>>(),
             vec!["//foo.bar/0C", "//foo.bar/0G"],
         );
 
         let g = create_group_for(vec![p4_no_c1(), p5_no_c2()]);
         assert_eq!(
-            g.uris().collect::<Vec<&str>>(),
+            g.uris().map(|info| info.uri()).collect::<Vec<&str>>(),
             vec!["//foo.bar/0G", "//foo.bar/0K"],
         );
         assert!(g.has_uris());
     }
 
+    #[test]
+    fn uris_metadata() {
+        let g = create_group_for(vec![p1_full()]);
+        let info = g.uris().next().unwrap();
+        assert_eq!(
+            info.encoding(),
+            PatchFormat::TableKeyed {
+                fully_invalidating: true
+            }
+        );
+        assert_eq!(info.invalidation(), Invalidation::Full);
+        assert!(info.is_invalidating());
+        assert_eq!(info.source_table(), IFT_TAG);
+        assert_eq!(info.entry_codepoints(), None);
+
+        let g = create_group_for(vec![p2_partial_c1(), p4_no_c2()]);
+        let infos: Vec<_> = g
+            .uris()
+            .map(|info| (info.invalidation(), info.source_table()))
+            .collect();
+        assert_eq!(
+            infos,
+            vec![
+                (Invalidation::Partial, IFT_TAG),
+                (Invalidation::None, IFTX_TAG),
+            ]
+        );
+        assert!(!g.uris().nth(1).unwrap().is_invalidating());
+    }
+
+    #[test]
+    fn uris_entry_codepoints() {
+        let font = base_font(Some(table_keyed_format2()), None);
+        let font = FontRef::new(&font).unwrap();
+
+        let s = SubsetDefinition::codepoints([5].into_iter().collect());
+        let g = PatchGroup::select_next_patches(font, &s).unwrap();
+        let counts: Vec<Option<u64>> = g.uris().map(|info| info.entry_codepoints()).collect();
+        assert_eq!(counts, vec![Some(18)]);
+    }
+
+    /// A format 2 mapping table with one entry per codepoint set, using uri_template.
+    fn format2_table(
+        compat_id: CompatibilityId,
+        patch_format: u8,
+        uri_template: &str,
+        codepoints: &[&[u32]],
+    ) -> Vec<u8> {
+        let entries: Vec<EntryData> = codepoints
+            .iter()
+            .map(|codepoints| {
+                let mut entry = EntryData::default();
+                entry.set_codepoints(&codepoints.iter().copied().collect());
+                entry
+            })
+            .collect();
+        write_fonts::dump_table(&WriteIft::Format2(
+            PatchMapFormat2::from_entries(compat_id, patch_format, uri_template, &entries).unwrap(),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn intersecting_uris() {
+        let mut font_builder = FontBuilder::new();
+        font_builder.add_raw(
+            IFT_TAG,
+            format2_table(cid_1(), 1, "ift/{id}", &[&[1], &[2], &[3]]),
+        );
+        font_builder.add_raw(
+            IFTX_TAG,
+            format2_table(cid_2(), 3, "iftx/{id}", &[&[4], &[1, 5]]),
+        );
+        let font = font_builder.build();
+        let font = FontRef::new(&font).unwrap();
+
+        let s = SubsetDefinition::codepoints([2, 3, 5].into_iter().collect());
+        let g = PatchGroup::select_next_patches(font.clone(), &s).unwrap();
+        assert_eq!(
+            g.uris().map(|info| info.uri()).collect::<Vec<_>>(),
+            vec!["ift/08"]
+        );
+
+        // The next group is listed first, followed by every other intersecting entry.
+        assert_eq!(
+            PatchGroup::intersecting_uris(font.clone(), &s).unwrap(),
+            vec!["ift/08", "ift/0C", "iftx/08"]
+        );
+
+        let s = SubsetDefinition::codepoints([7].into_iter().collect());
+        assert!(PatchGroup::intersecting_uris(font, &s).unwrap().is_empty());
+    }
+
     #[test]
     fn select_next_patches_no_intersection() {
         let font = base_font(Some(table_keyed_format2()), None);
@@ -1116,7 +1669,10 @@ mod tests {
         let g = PatchGroup::select_next_patches(font, &s).unwrap();
 
         assert!(!g.has_uris());
-        assert_eq!(g.uris().collect::<Vec<&str>>(), Vec::<&str>::default());
+        assert_eq!(
+            g.uris().map(|info| info.uri()).collect::<Vec<&str>>(),
+            Vec::<&str>::default()
+        );
 
         assert_eq!(
             g.apply_next_patches(&mut Default::default()),
@@ -1168,6 +1724,89 @@ mod tests {
         )
     }
 
+    /// Records progress updates, and cancels after a set number of tables have been processed.
+    #[derive(Default)]
+    struct RecordingObserver {
+        progress: Vec<(Tag, usize, usize)>,
+        cancel_after: Option<usize>,
+    }
+
+    impl PatchObserver for RecordingObserver {
+        fn table_processed(&mut self, tag: Tag, processed: usize, total: usize) {
+            self.progress.push((tag, processed, total));
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.cancel_after
+                .is_some_and(|cancel_after| self.progress.len() >= cancel_after)
+        }
+    }
+
+    #[test]
+    fn apply_patches_reports_progress() {
+        let font = base_font(Some(table_keyed_format2()), None);
+        let font = FontRef::new(&font).unwrap();
+
+        let s = SubsetDefinition::codepoints([5].into_iter().collect());
+        let g = PatchGroup::select_next_patches(font, &s).unwrap();
+        let mut patch_data = HashMap::from([(
+            "foo/04".to_string(),
+            UriStatus::Pending(table_keyed_patch().as_slice().to_vec()),
+        )]);
+
+        let mut observer = RecordingObserver::default();
+        g.apply_next_patches_with_observer(
+            &mut patch_data,
+            &BuiltInBrotliDecoder,
+            &Default::default(),
+            &mut observer,
+        )
+        .unwrap();
+
+        assert_eq!(
+            observer.progress,
+            vec![
+                (Tag::new(b"tab1"), 1, 3),
+                (Tag::new(b"tab2"), 2, 3),
+                (Tag::new(b"tab3"), 3, 3),
+            ]
+        );
+        assert_eq!(patch_data["foo/04"], UriStatus::Applied);
+    }
+
+    #[test]
+    fn apply_patches_cancelled() {
+        let font = base_font(Some(table_keyed_format2()), None);
+        let font = FontRef::new(&font).unwrap();
+
+        let s = SubsetDefinition::codepoints([5].into_iter().collect());
+        let g = PatchGroup::select_next_patches(font, &s).unwrap();
+        let mut patch_data = HashMap::from([(
+            "foo/04".to_string(),
+            UriStatus::Pending(table_keyed_patch().as_slice().to_vec()),
+        )]);
+
+        let mut observer = RecordingObserver {
+            cancel_after: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            g.apply_next_patches_with_observer(
+                &mut patch_data,
+                &BuiltInBrotliDecoder,
+                &Default::default(),
+                &mut observer,
+            ),
+            Err(PatchingError::Cancelled)
+        );
+
+        assert_eq!(observer.progress, vec![(Tag::new(b"tab1"), 1, 3)]);
+        assert_eq!(
+            patch_data["foo/04"],
+            UriStatus::Pending(table_keyed_patch().as_slice().to_vec())
+        );
+    }
+
     #[test]
     fn apply_patches_one_partial_invalidation() {
         let mut buffer = table_keyed_format2();
@@ -1423,6 +2062,93 @@ mod tests {
         assert!(!g.has_uris());
     }
 
+    #[test]
+    fn apply_patches_duplicate_uri_across_tables() {
+        let mut ift_builder = table_keyed_format2();
+        ift_builder.write_at("encoding", 3u8);
+        ift_builder.write_at("compat_id[0]", 6u32);
+        ift_builder.write_at("compat_id[1]", 7u32);
+        ift_builder.write_at("compat_id[2]", 8u32);
+        ift_builder.write_at("compat_id[3]", 9u32);
+
+        // Same entries as IFT, so foo/04 is listed in both tables.
+        let mut iftx_builder = table_keyed_format2();
+        iftx_builder.write_at("encoding", 3u8);
+        iftx_builder.write_at("compat_id[0]", 7u32);
+        iftx_builder.write_at("compat_id[1]", 7u32);
+        iftx_builder.write_at("compat_id[2]", 8u32);
+        iftx_builder.write_at("compat_id[3]", 9u32);
+
+        let font = test_font_for_patching_with_loca_mod(
+            |_| {},
+            HashMap::from([
+                (IFT_TAG, ift_builder.as_slice()),
+                (IFTX_TAG, iftx_builder.as_slice()),
+            ]),
+        );
+        let font = FontRef::new(font.as_slice()).unwrap();
+
+        let s = SubsetDefinition::codepoints([5].into_iter().collect());
+        let g = PatchGroup::select_next_patches(font, &s).unwrap();
+        assert_eq!(
+            g.uris().map(|info| info.uri()).collect::<Vec<&str>>(),
+            vec!["foo/04"]
+        );
+
+        let patch =
+            assemble_glyph_keyed_patch(glyph_keyed_patch_header(), glyf_u16_glyph_patches());
+        let mut patch_data = HashMap::from([(
+            "foo/04".to_string(),
+            UriStatus::Pending(patch.as_slice().to_vec()),
+        )]);
+
+        let new_font = g.apply_next_patches(&mut patch_data).unwrap();
+        let new_font = FontRef::new(&new_font).unwrap();
+
+        // The single fetch marks the entries in both tables as applied.
+        let g = PatchGroup::select_next_patches(new_font, &s).unwrap();
+        assert!(!g.has_uris());
+    }
+
+    #[test]
+    fn apply_patches_partial_invalidation_duplicate_uri() {
+        let mut ift_buffer = table_keyed_format2();
+        ift_buffer.write_at("encoding", 2u8);
+
+        // IFTX lists the same URI as a glyph keyed patch.
+        let mut iftx_buffer = table_keyed_format2();
+        iftx_buffer.write_at("compat_id[0]", 2u32);
+        iftx_buffer.write_at("encoding", 3u8);
+
+        let font = base_font(Some(ift_buffer), Some(iftx_buffer));
+        let font = FontRef::new(&font).unwrap();
+
+        let s = SubsetDefinition::codepoints([5].into_iter().collect());
+        assert!(intersecting_patches(&font, &s)
+            .unwrap()
+            .into_iter()
+            .any(|uri| matches!(uri.source_table(), IftTableTag::Iftx(_))));
+
+        let g = PatchGroup::select_next_patches(font, &s).unwrap();
+        let mut patch_data = HashMap::from([(
+            "foo/04".to_string(),
+            UriStatus::Pending(table_keyed_patch().as_slice().to_vec()),
+        )]);
+
+        let new_font = g.apply_next_patches(&mut patch_data).unwrap();
+        let new_font = FontRef::new(&new_font).unwrap();
+
+        assert_eq!(
+            new_font.table_data(Tag::new(b"tab1")).unwrap().as_bytes(),
+            TABLE_1_FINAL_STATE,
+        );
+        // The patch didn't touch IFTX so the duplicate entry there is now marked applied.
+        assert!(!intersecting_patches(&new_font, &s)
+            .unwrap()
+            .into_iter()
+            .any(|uri| matches!(uri.source_table(), IftTableTag::Iftx(_))));
+    }
+
     #[test]
     fn tables_have_same_compat_id() {
         let ift_buffer = table_keyed_format2();
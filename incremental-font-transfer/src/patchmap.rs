@@ -20,7 +20,7 @@ use data_encoding_macro::new_encoding;
 use read_fonts::{
     collections::{IntSet, RangeSet},
     tables::ift::{
-        CompatibilityId, EntryData, EntryFormatFlags, EntryMapRecord, Ift, PatchMapFormat1,
+        CompatibilityId, EntryData, EntryFormatFlags, FeatureMap, Ift, PatchMapFormat1,
         PatchMapFormat2, IFTX_TAG, IFT_TAG,
     },
     types::{Offset32, Uint24},
@@ -49,6 +49,50 @@ pub fn intersecting_patches(
     Ok(result)
 }
 
+/// Find every patch listed in the mapping tables of the font which hasn't been applied yet,
+/// regardless of the subset definition it's associated with.
+///
+/// Format 1 entries which are only reachable through the feature map are included if they map
+/// from at least one entry of the glyph map.
+pub fn unapplied_patches(font: &FontRef) -> Result<Vec<PatchUri>, ReadError> {
+    let mut result: Vec<PatchUri> = vec![];
+
+    for (tag, table) in IftTableTag::tables_in(font) {
+        match &table {
+            Ift::Format1(format_1) => {
+                let glyph_count = format_1.glyph_count().to_u32();
+                let feature_tags = format_1
+                    .feature_map()
+                    .and_then(Result::ok)
+                    .map(|feature_map| {
+                        feature_map
+                            .feature_records()
+                            .iter()
+                            .filter_map(|record| record.ok().map(|record| record.feature_tag()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                add_format1_patches(font, &tag, format_1, &mut result, |map, _| {
+                    // Every glyph, codepoints aren't recorded so any value will do.
+                    let gids = (0..glyph_count).map(|gid| (0, gid));
+                    let mut entries = Default::default();
+                    intersect_format1_glyph_map_inner::<false>(map, gids, &mut entries)?;
+                    intersect_format1_feature_map::<false>(map, &feature_tags, &mut entries)?;
+                    Ok(entries)
+                })?;
+            }
+            Ift::Format2(format_2) => result.extend(
+                decode_format2_entries(&tag, format_2)?
+                    .into_iter()
+                    .filter(|entry| !entry.ignored)
+                    .map(|entry| entry.uri),
+            ),
+        }
+    }
+
+    Ok(result)
+}
+
 fn add_intersecting_patches(
     font: &FontRef,
     source_table: IftTableTag,
@@ -78,6 +122,38 @@ fn add_intersecting_format1_patches(
     codepoints: &IntSet<u32>,
     features: &BTreeSet<Tag>,
     patches: &mut Vec<PatchUri>,
+) -> Result<(), ReadError> {
+    let charmap = Charmap::new(font);
+    add_format1_patches(
+        font,
+        source_table,
+        map,
+        patches,
+        |map, record_intersection| {
+            if record_intersection {
+                intersect_format1_glyph_and_feature_map::<true>(&charmap, map, codepoints, features)
+            } else {
+                intersect_format1_glyph_and_feature_map::<false>(
+                    &charmap, map, codepoints, features,
+                )
+            }
+        },
+    )
+}
+
+/// Adds the unapplied entries of a format 1 patch map which are selected by `collect_entries`.
+///
+/// `collect_entries` is passed the map and whether the intersection of each entry should be
+/// recorded, which is only needed for invalidating patches.
+fn add_format1_patches(
+    font: &FontRef,
+    source_table: &IftTableTag,
+    map: &PatchMapFormat1,
+    patches: &mut Vec<PatchUri>,
+    collect_entries: impl FnOnce(
+        &PatchMapFormat1,
+        bool,
+    ) -> Result<BTreeMap<u16, SubsetDefinition>, ReadError>,
 ) -> Result<(), ReadError> {
     // Step 0: Top Level Field Validation
     let maxp = font.maxp()?;
@@ -104,12 +180,7 @@ fn add_intersecting_format1_patches(
     let encoding = PatchFormat::from_format_number(map.patch_format())?;
 
     // Step 1: Collect the glyph and feature map entries.
-    let charmap = Charmap::new(font);
-    let entries = if PatchFormat::is_invalidating_format(map.patch_format()) {
-        intersect_format1_glyph_and_feature_map::<true>(&charmap, map, codepoints, features)?
-    } else {
-        intersect_format1_glyph_and_feature_map::<false>(&charmap, map, codepoints, features)?
-    };
+    let entries = collect_entries(map, PatchFormat::is_invalidating_format(map.patch_format()))?;
 
     // Step 2: produce final output.
     let applied_entries_start_bit_index = map.shape().applied_entries_bitmap_byte_range().start * 8;
@@ -129,6 +200,9 @@ fn add_intersecting_format1_patches(
                     if PatchFormat::is_invalidating_format(map.patch_format()) {
                         IntersectionInfo::from_subset(
                             subset_def,
+                            // Format 1 entries are defined by glyph mappings, so the full codepoint
+                            // coverage of an entry isn't readily available.
+                            None,
                             // For format 1 the entry index is the "order",
                             // see: https://w3c.github.io/IFT/Overview.html#font-patch-invalidations
                             index.into(),
@@ -222,87 +296,141 @@ fn intersect_format1_feature_map<const RECORD_INTERSECTION: bool>(
     let Some(feature_map) = map.feature_map() else {
         return Ok(());
     };
-    let feature_map = feature_map?;
-
-    let max_entry_index = map.max_entry_index();
-    let max_glyph_map_entry_index = map.max_glyph_map_entry_index();
-    let field_width = if max_entry_index < 256 { 1u16 } else { 2u16 };
-
-    // We need to check up front there is enough data for all of the listed entry records, this
-    // isn't checked by the read_fonts generated code. Specification requires the operation to fail
-    // up front if the data is too short.
-    if feature_map.entry_records_size(max_entry_index)? > feature_map.entry_map_data().len() {
-        return Err(ReadError::OutOfBounds);
+    // The entry map is always decoded, since the specification requires the operation to fail
+    // if the feature map is malformed even when no features are requested.
+    let entry_map = FeatureEntryMap::new(map, &feature_map?)?;
+    if features.is_empty() {
+        return Ok(());
     }
 
-    let mut tag_it = features.iter();
-    let mut record_it = feature_map.feature_records().iter();
-
-    let mut next_tag = tag_it.next();
-    let mut next_record = record_it.next();
-    let mut cumulative_entry_map_count = 0;
     let mut largest_tag: Option<Tag> = None;
-    loop {
-        let Some((tag, record)) = next_tag.zip(next_record.clone()) else {
-            break;
-        };
-        let record = record?;
-
-        if *tag > record.feature_tag() {
-            cumulative_entry_map_count += record.entry_map_count().get();
-            next_record = record_it.next();
+    for feature in entry_map.features.iter() {
+        // Feature records are required to be sorted by tag, any which are out of order or
+        // duplicated are skipped.
+        if largest_tag.is_some_and(|largest_tag| feature.tag <= largest_tag) {
             continue;
         }
+        largest_tag = Some(feature.tag);
 
-        if let Some(largest_tag) = largest_tag {
-            if *tag <= largest_tag {
-                // Out of order or duplicate tag, skip this record.
-                next_tag = tag_it.next();
-                continue;
-            }
-        }
-
-        largest_tag = Some(*tag);
-
-        let entry_count = record.entry_map_count().get();
-        if *tag < record.feature_tag() {
-            next_tag = tag_it.next();
+        if !features.contains(&feature.tag) {
             continue;
         }
 
-        for i in 0..entry_count {
-            let index = i + cumulative_entry_map_count;
-            let byte_index = (index * field_width * 2) as usize;
-            let data = FontData::new(&feature_map.entry_map_data()[byte_index..]);
-            let mapped_entry_index = record.first_new_entry_index().get() + i;
-            let record = EntryMapRecord::read(data, max_entry_index)?;
-            let first = record.first_entry_index().get();
-            let last = record.last_entry_index().get();
-            if first > last
-                || first > max_glyph_map_entry_index
-                || last > max_glyph_map_entry_index
-                || mapped_entry_index <= max_glyph_map_entry_index
-                || mapped_entry_index > max_entry_index
-            {
-                // Invalid, continue on
-                continue;
-            }
-
+        for (mapped_entry_index, intersection) in entry_map.mappings(feature) {
             // If any entries exist which intersect the range of this record add all of their subset defs
             // to the new entry.
             merge_intersecting_entries::<RECORD_INTERSECTION>(
-                first..=last,
+                intersection,
                 mapped_entry_index,
-                *tag,
+                feature.tag,
                 entries,
             );
         }
-        next_tag = tag_it.next();
     }
 
     Ok(())
 }
 
+/// The entry map records of a format 1 feature map, decoded and indexed by feature record.
+///
+/// See: <https://w3c.github.io/IFT/Overview.html#feature-map>
+struct FeatureEntryMap<'a> {
+    entry_map_data: &'a [u8],
+    max_entry_index: u16,
+    max_glyph_map_entry_index: u16,
+    features: Vec<FeatureEntryRecords>,
+}
+
+/// The location of the entry map records belonging to a single feature record.
+struct FeatureEntryRecords {
+    tag: Tag,
+    first_new_entry_index: u16,
+    entry_map_count: u16,
+    /// Index of the first entry map record for this feature within the entry map data.
+    first_record: usize,
+}
+
+impl<'a> FeatureEntryMap<'a> {
+    fn new(map: &PatchMapFormat1, feature_map: &FeatureMap<'a>) -> Result<Self, ReadError> {
+        let mut features = vec![];
+        let mut record_count = 0usize;
+        for record in feature_map.feature_records().iter() {
+            let record = record?;
+            let entry_map_count = record.entry_map_count().get();
+            features.push(FeatureEntryRecords {
+                tag: record.feature_tag(),
+                first_new_entry_index: record.first_new_entry_index().get(),
+                entry_map_count,
+                first_record: record_count,
+            });
+            record_count += entry_map_count as usize;
+        }
+
+        let entry_map = FeatureEntryMap {
+            entry_map_data: feature_map.entry_map_data(),
+            max_entry_index: map.max_entry_index(),
+            max_glyph_map_entry_index: map.max_glyph_map_entry_index(),
+            features,
+        };
+
+        // The read_fonts generated code doesn't check that there is enough data for all of the
+        // listed entry records, so do that here.
+        if record_count * entry_map.record_size() > entry_map.entry_map_data.len() {
+            return Err(ReadError::OutOfBounds);
+        }
+
+        Ok(entry_map)
+    }
+
+    /// Entry indices are stored as u8 when the max entry index fits in one, otherwise as u16.
+    fn entry_index_size(&self) -> usize {
+        if self.max_entry_index < 256 {
+            1
+        } else {
+            2
+        }
+    }
+
+    fn record_size(&self) -> usize {
+        self.entry_index_size() * 2
+    }
+
+    fn entry_index(&self, byte_index: usize) -> Option<u16> {
+        let data = FontData::new(self.entry_map_data);
+        if self.entry_index_size() == 1 {
+            data.read_at::<u8>(byte_index).ok().map(u16::from)
+        } else {
+            data.read_at::<u16>(byte_index).ok()
+        }
+    }
+
+    /// Returns the valid mappings of `feature` as pairs of the mapped entry index and the range of
+    /// entry indices it applies to.
+    ///
+    /// Records that have an empty range, refer to entries outside of the glyph map, or map to an
+    /// entry index which isn't in (max glyph map entry index, max entry index] are skipped.
+    fn mappings<'b>(
+        &'b self,
+        feature: &'b FeatureEntryRecords,
+    ) -> impl Iterator<Item = (u16, RangeInclusive<u16>)> + 'b {
+        (0..feature.entry_map_count).filter_map(move |i| {
+            let byte_index = (feature.first_record + i as usize) * self.record_size();
+            let first = self.entry_index(byte_index)?;
+            let last = self.entry_index(byte_index + self.entry_index_size())?;
+            // A mapped index which overflows is necessarily larger than the max entry index.
+            let mapped_entry_index = feature.first_new_entry_index.checked_add(i)?;
+            if first > last
+                || last > self.max_glyph_map_entry_index
+                || mapped_entry_index <= self.max_glyph_map_entry_index
+                || mapped_entry_index > self.max_entry_index
+            {
+                return None;
+            }
+            Some((mapped_entry_index, first..=last))
+        })
+    }
+}
+
 fn merge_intersecting_entries<const RECORD_INTERSECTION: bool>(
     intersection: RangeInclusive<u16>,
     mapped_entry_index: u16,
@@ -347,11 +475,14 @@ fn add_intersecting_format2_patches(
             continue;
         }
 
+        let entry_codepoints = e.subset_definition.codepoints.len();
+        e.uri.entry_codepoints = Some(entry_codepoints);
         if e.uri.encoding().is_invalidating() {
             // for invalidating keyed patches we need to record information about intersection size to use later
             // for patch selection.
             e.uri.intersection_info = IntersectionInfo::from_subset(
                 e.subset_definition.intersection(subset_definition),
+                Some(entry_codepoints),
                 order,
             );
         }
@@ -590,7 +721,7 @@ pub enum PatchFormat {
 }
 
 impl PatchFormat {
-    fn is_invalidating(&self) -> bool {
+    pub(crate) fn is_invalidating(&self) -> bool {
         matches!(self, PatchFormat::TableKeyed { .. })
     }
 
@@ -672,8 +803,9 @@ impl IftTableTag {
 /// Stores a template and the arguments used to instantiate it. See:
 /// <https://w3c.github.io/IFT/Overview.html#uri-templates> for details on the template format.
 ///
-/// The input to the template expansion can be either a numeric index or a string id. Currently only
-/// the numeric index is supported.
+/// The input to the template expansion can be either a numeric index or a string id. Both are
+/// supported, and are encoded as base32hex (`id`, `d1` - `d4`) and base64url (`id64`) during
+/// expansion.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct PatchUri {
     template: String, // TODO(garretrieger): Make this a reference?
@@ -682,6 +814,7 @@ pub struct PatchUri {
     source_table: IftTableTag,
     application_flag_bit_index: usize,
     intersection_info: IntersectionInfo,
+    entry_codepoints: Option<u64>,
 }
 
 /// Stores information on the intersection which lead to the selection of this patch.
@@ -693,6 +826,7 @@ pub(crate) struct IntersectionInfo {
     intersecting_codepoints: u64,
     intersecting_layout_tags: usize,
     intersecting_design_space: BTreeMap<Tag, Fixed>,
+    extraneous_codepoints: u64,
     entry_order: usize,
 }
 
@@ -727,6 +861,12 @@ impl Ord for IntersectionInfo {
             Ordering::Equal => {}
             ord => return ord,
         }
+        // Any candidate with the largest intersection satisfies the spec, so among those prefer the
+        // one that brings in the fewest codepoints which weren't requested.
+        match self.extraneous_codepoints.cmp(&other.extraneous_codepoints) {
+            Ordering::Equal => {}
+            ord => return ord.reverse(),
+        }
 
         // We select the largest intersection info, and the spec requires in ties that the lowest entry order
         // is selected. So reverse the ordering of comparing entry_order.
@@ -785,6 +925,11 @@ impl PatchUri {
         self.application_flag_bit_index
     }
 
+    /// Total number of codepoints covered by the entry this patch came from, if known.
+    pub(crate) fn entry_codepoints(&self) -> Option<u64> {
+        self.entry_codepoints
+    }
+
     fn count_leading_zeroes(id: &[u8]) -> usize {
         let mut leading_bytes = 0;
         for b in id {
@@ -819,20 +964,54 @@ impl PatchUri {
             application_flag_bit_index,
             encoding,
             intersection_info,
+            entry_codepoints: None,
         }
     }
 }
 
 impl IntersectionInfo {
-    fn from_subset(value: SubsetDefinition, order: usize) -> Self {
+    /// Create intersection info from the intersection of an entry with the target subset definition.
+    ///
+    /// `entry_codepoints` is the total number of codepoints covered by the entry, or `None` if that
+    /// isn't known.
+    fn from_subset(value: SubsetDefinition, entry_codepoints: Option<u64>, order: usize) -> Self {
+        let intersecting_codepoints = value.codepoints.len();
         IntersectionInfo {
-            intersecting_codepoints: value.codepoints.len(),
+            intersecting_codepoints,
             intersecting_layout_tags: value.feature_tags.len(),
             intersecting_design_space: Self::design_space_size(value.design_space),
+            extraneous_codepoints: entry_codepoints
+                .map(|count| count.saturating_sub(intersecting_codepoints))
+                .unwrap_or_default(),
             entry_order: order,
         }
     }
 
+    /// Number of requested codepoints covered by the entry.
+    pub(crate) fn intersecting_codepoints(&self) -> u64 {
+        self.intersecting_codepoints
+    }
+
+    /// Number of requested layout feature tags covered by the entry.
+    pub(crate) fn intersecting_layout_tags(&self) -> usize {
+        self.intersecting_layout_tags
+    }
+
+    /// Total size of the requested design space covered by the entry, per axis.
+    pub(crate) fn intersecting_design_space(&self) -> &BTreeMap<Tag, Fixed> {
+        &self.intersecting_design_space
+    }
+
+    /// Number of codepoints covered by the entry which were not requested.
+    pub(crate) fn extraneous_codepoints(&self) -> u64 {
+        self.extraneous_codepoints
+    }
+
+    /// Order of the entry in the patch map.
+    pub(crate) fn entry_order(&self) -> usize {
+        self.entry_order
+    }
+
     fn design_space_size(value: HashMap<Tag, RangeSet<Fixed>>) -> BTreeMap<Tag, Fixed> {
         value
             .into_iter()
@@ -851,9 +1030,9 @@ impl IntersectionInfo {
 /// Stores a description of a font subset over codepoints, feature tags, and design space.
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct SubsetDefinition {
-    codepoints: IntSet<u32>,
-    feature_tags: BTreeSet<Tag>,
-    design_space: HashMap<Tag, RangeSet<Fixed>>,
+    pub(crate) codepoints: IntSet<u32>,
+    pub(crate) feature_tags: BTreeSet<Tag>,
+    pub(crate) design_space: HashMap<Tag, RangeSet<Fixed>>,
 }
 
 impl SubsetDefinition {
@@ -877,7 +1056,7 @@ impl SubsetDefinition {
         }
     }
 
-    fn union(&mut self, other: &SubsetDefinition) {
+    pub(crate) fn union(&mut self, other: &SubsetDefinition) {
         self.codepoints.union(&other.codepoints);
         other.feature_tags.iter().for_each(|t| {
             self.feature_tags.insert(*t);
@@ -1031,10 +1210,16 @@ mod tests {
                 intersecting_codepoints: codepoints,
                 intersecting_layout_tags: features,
                 intersecting_design_space: Default::default(),
+                extraneous_codepoints: 0,
                 entry_order: order,
             }
         }
 
+        pub(crate) fn with_extraneous_codepoints(mut self, count: u64) -> Self {
+            self.extraneous_codepoints = count;
+            self
+        }
+
         pub(crate) fn from_design_space<const N: usize>(
             codepoints: u64,
             features: usize,
@@ -1045,6 +1230,7 @@ mod tests {
                 intersecting_codepoints: codepoints,
                 intersecting_layout_tags: features,
                 intersecting_design_space: BTreeMap::from(design_space),
+                extraneous_codepoints: 0,
                 entry_order: order,
             }
         }
@@ -1065,8 +1251,14 @@ mod tests {
                 application_flag_bit_index,
                 encoding,
                 intersection_info: Default::default(),
+                entry_codepoints: None,
             }
         }
+
+        fn with_entry_codepoints(mut self, entry_codepoints: Option<u64>) -> PatchUri {
+            self.entry_codepoints = entry_codepoints;
+            self
+        }
     }
 
     fn compat_id() -> CompatibilityId {
@@ -1148,6 +1340,11 @@ mod tests {
             ),
         )
         .unwrap();
+        // Entry coverage is checked separately by format_2_patch_map_entry_codepoints.
+        let patches: Vec<PatchUri> = patches
+            .into_iter()
+            .map(|uri| uri.with_entry_codepoints(None))
+            .collect();
 
         let expected: Vec<PatchUri> = expected_entries
             .iter()
@@ -1185,6 +1382,11 @@ mod tests {
             ),
         )
         .unwrap();
+        // Entry coverage is checked separately by format_2_patch_map_entry_codepoints.
+        let patches: Vec<PatchUri> = patches
+            .into_iter()
+            .map(|uri| uri.with_entry_codepoints(None))
+            .collect();
 
         let expected: Vec<PatchUri> = expected_entries
             .iter()
@@ -1491,6 +1693,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unapplied_patches_format_1() {
+        let font_bytes = create_ift_font(
+            FontRef::new(test_data::ift::IFT_BASE).unwrap(),
+            Some(&feature_map_format1()),
+            None,
+        );
+        let font = FontRef::new(&font_bytes).unwrap();
+
+        // Entries mapped from the glyph map by any of the features are included.
+        let uris: Vec<PatchUri> = unapplied_patches(&font).unwrap();
+        let expected: Vec<PatchUri> = [0x50, 0x51, 0x12c, 0x12d, 0x180, 0x181, 0x190]
+            .map(f1)
+            .into_iter()
+            .map(
+                |ExpectedEntry {
+                     index,
+                     application_bit_index,
+                 }| {
+                    PatchUri::from_index(
+                        "ABCDEFɤ",
+                        index,
+                        IftTableTag::Ift(compat_id()),
+                        application_bit_index,
+                        PatchFormat::GlyphKeyed,
+                        Default::default(),
+                    )
+                },
+            )
+            .collect();
+        assert_eq!(uris, expected);
+    }
+
     fn patch_with_intersection(
         applied_entries_start: usize,
         index: u32,
@@ -1748,6 +1983,227 @@ mod tests {
         .is_err());
     }
 
+    /// A format 1 feature record along with its entry map records as (first, last) pairs.
+    struct TestFeatureRecord {
+        tag: Tag,
+        first_new_entry_index: u16,
+        mappings: Vec<(u16, u16)>,
+    }
+
+    /// Builds a format 1 mapping table with an empty glyph map and the given feature map.
+    fn format1_with_feature_map(
+        max_entry_index: u16,
+        max_glyph_map_entry_index: u16,
+        records: &[TestFeatureRecord],
+    ) -> Vec<u8> {
+        let push_index = |data: &mut Vec<u8>, value: u16| {
+            if max_entry_index < 256 {
+                data.push(value as u8);
+            } else {
+                data.extend_from_slice(&value.to_be_bytes());
+            }
+        };
+
+        let mut data = vec![1u8];
+        data.extend_from_slice(&0u32.to_be_bytes());
+        data.extend_from_slice(compat_id().as_slice());
+        data.extend_from_slice(&max_entry_index.to_be_bytes());
+        data.extend_from_slice(&max_glyph_map_entry_index.to_be_bytes());
+        data.extend_from_slice(&[0, 0, 0]); // glyph count
+        let bitmap_len = (max_entry_index as usize + 8) / 8;
+        // Offsets of the glyph map and feature map, the glyph map only has a first mapped glyph.
+        let glyph_map_offset = 1 + 4 + 16 + 2 + 2 + 3 + 4 + 4 + bitmap_len + 2 + 1;
+        data.extend_from_slice(&(glyph_map_offset as u32).to_be_bytes());
+        data.extend_from_slice(&(glyph_map_offset as u32 + 2).to_be_bytes());
+        data.resize(data.len() + bitmap_len, 0);
+        data.extend_from_slice(&0u16.to_be_bytes()); // uri template length
+        data.push(1); // patch format
+        data.extend_from_slice(&0u16.to_be_bytes()); // first mapped glyph
+
+        data.extend_from_slice(&(records.len() as u16).to_be_bytes());
+        for record in records {
+            data.extend_from_slice(&record.tag.to_be_bytes());
+            push_index(&mut data, record.first_new_entry_index);
+            push_index(&mut data, record.mappings.len() as u16);
+        }
+        for (first, last) in records.iter().flat_map(|record| record.mappings.iter()) {
+            push_index(&mut data, *first);
+            push_index(&mut data, *last);
+        }
+        data
+    }
+
+    /// Straightforward implementation of the format 1 feature map intersection to compare
+    /// against, see: <https://w3c.github.io/IFT/Overview.html#patch-map-format-1>
+    fn reference_feature_map_intersection(
+        max_entry_index: u16,
+        max_glyph_map_entry_index: u16,
+        records: &[TestFeatureRecord],
+        features: &BTreeSet<Tag>,
+        glyph_map_entries: &BTreeMap<u16, SubsetDefinition>,
+    ) -> BTreeMap<u16, SubsetDefinition> {
+        let mut result = glyph_map_entries.clone();
+        let mut previous_tags: Vec<Tag> = vec![];
+        for record in records {
+            let in_order = previous_tags.iter().all(|tag| *tag < record.tag);
+            previous_tags.push(record.tag);
+            if !in_order || !features.contains(&record.tag) {
+                continue;
+            }
+            for (i, (first, last)) in record.mappings.iter().enumerate() {
+                let mapped = record.first_new_entry_index as u32 + i as u32;
+                if first > last
+                    || *last > max_glyph_map_entry_index
+                    || mapped <= max_glyph_map_entry_index as u32
+                    || mapped > max_entry_index as u32
+                {
+                    continue;
+                }
+                for (index, subset_def) in glyph_map_entries.iter() {
+                    if index < first || index > last {
+                        continue;
+                    }
+                    let merged = result.entry(mapped as u16).or_default();
+                    merged.union(subset_def);
+                    merged.feature_tags.insert(record.tag);
+                }
+            }
+        }
+        result
+    }
+
+    /// Small deterministic xorshift generator for the randomized tests.
+    struct TestRng(u64);
+
+    impl TestRng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, bound: u32) -> u32 {
+            (self.next() % bound as u64) as u32
+        }
+    }
+
+    #[test]
+    fn format_1_feature_map_matches_reference() {
+        let tags = [b"aalt", b"dlig", b"liga", b"smcp"].map(Tag::new);
+        let mut rng = TestRng(0x9E37_79B9_7F4A_7C15);
+        for _ in 0..500 {
+            let max_entry_index = if rng.below(2) == 0 {
+                rng.below(256) as u16
+            } else {
+                256 + rng.below(u16::MAX as u32 - 255) as u16
+            };
+            let max_glyph_map_entry_index = rng.below(max_entry_index as u32 + 1) as u16;
+            // Values which go slightly beyond the valid ranges, including ones that overflow when
+            // a record's mapping index is added.
+            let index_near = |rng: &mut TestRng, value: u16| {
+                let mut value = value.saturating_sub(2).saturating_add(rng.below(5) as u16);
+                if max_entry_index < 256 {
+                    value = value.min(255);
+                }
+                value
+            };
+
+            let records: Vec<TestFeatureRecord> = (0..rng.below(6))
+                .map(|_| {
+                    let first_new_entry_index = match rng.below(3) {
+                        0 => index_near(&mut rng, max_glyph_map_entry_index),
+                        1 => index_near(&mut rng, max_entry_index),
+                        _ => rng.below(max_entry_index as u32 + 1) as u16,
+                    };
+                    let mappings = (0..rng.below(4))
+                        .map(|_| {
+                            let first = rng.below(max_glyph_map_entry_index as u32 + 1) as u16;
+                            let first = index_near(&mut rng, first);
+                            let last = first.saturating_add(rng.below(3) as u16);
+                            let last = index_near(&mut rng, last);
+                            (first, last)
+                        })
+                        .collect();
+                    TestFeatureRecord {
+                        tag: tags[rng.below(tags.len() as u32) as usize],
+                        first_new_entry_index,
+                        mappings,
+                    }
+                })
+                .collect();
+            let features: BTreeSet<Tag> =
+                tags.iter().copied().filter(|_| rng.below(2) == 0).collect();
+            let glyph_map_entries: BTreeMap<u16, SubsetDefinition> = (0..rng.below(8))
+                .map(|_| {
+                    let index = rng.below(max_glyph_map_entry_index as u32 + 1) as u16;
+                    (
+                        index,
+                        SubsetDefinition::codepoints(IntSet::from([index as u32])),
+                    )
+                })
+                .collect();
+
+            let data =
+                format1_with_feature_map(max_entry_index, max_glyph_map_entry_index, &records);
+            let map = PatchMapFormat1::read(FontData::new(&data)).unwrap();
+            let mut entries = glyph_map_entries.clone();
+            intersect_format1_feature_map::<true>(&map, &features, &mut entries).unwrap();
+
+            assert_eq!(
+                entries,
+                reference_feature_map_intersection(
+                    max_entry_index,
+                    max_glyph_map_entry_index,
+                    &records,
+                    &features,
+                    &glyph_map_entries,
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn format_1_feature_map_mapped_entry_index_overflow() {
+        let records = [TestFeatureRecord {
+            tag: Tag::new(b"liga"),
+            first_new_entry_index: u16::MAX - 1,
+            mappings: vec![(0, 1), (0, 1), (0, 1)],
+        }];
+        let data = format1_with_feature_map(u16::MAX, 300, &records);
+        let map = PatchMapFormat1::read(FontData::new(&data)).unwrap();
+        let mut entries = BTreeMap::from([(1, SubsetDefinition::codepoints(IntSet::from([1])))]);
+        intersect_format1_feature_map::<true>(
+            &map,
+            &BTreeSet::from([Tag::new(b"liga")]),
+            &mut entries,
+        )
+        .unwrap();
+
+        let mut expected = SubsetDefinition::codepoints(IntSet::from([1]));
+        expected.feature_tags.insert(Tag::new(b"liga"));
+        assert_eq!(
+            entries.keys().copied().collect::<Vec<_>>(),
+            vec![1, u16::MAX - 1, u16::MAX]
+        );
+        assert_eq!(entries[&u16::MAX], expected);
+    }
+
+    #[test]
+    fn format_1_feature_map_truncated_entry_map_data() {
+        let records = [TestFeatureRecord {
+            tag: Tag::new(b"liga"),
+            first_new_entry_index: 301,
+            mappings: vec![(0, 1), (2, 3)],
+        }];
+        let data = format1_with_feature_map(400, 300, &records);
+        let map = PatchMapFormat1::read(FontData::new(&data[..data.len() - 1])).unwrap();
+        assert_eq!(
+            intersect_format1_feature_map::<true>(&map, &BTreeSet::new(), &mut BTreeMap::new()),
+            Err(ReadError::OutOfBounds)
+        );
+    }
+
     #[test]
     fn format_2_patch_map_codepoints_only() {
         let font_bytes = create_ift_font(
@@ -1929,8 +2385,10 @@ mod tests {
             vec![patch_with_intersection(
                 map.offset_for("entries[1]") * 8 + 4,
                 2,
-                IntersectionInfo::new(2, 1, 1),
-            ),]
+                // entry 2 has 18 codepoints, of which 2 were requested.
+                IntersectionInfo::new(2, 1, 1).with_extraneous_codepoints(16),
+            )
+            .with_entry_codepoints(Some(18)),]
         );
 
         // Case 2
@@ -1954,8 +2412,10 @@ mod tests {
                 patch_with_intersection(
                     map.offset_for("entries[1]") * 8 + 4,
                     2,
-                    IntersectionInfo::new(2, 1, 1),
-                ),
+                    // entry 2 has 18 codepoints, of which 2 were requested.
+                    IntersectionInfo::new(2, 1, 1).with_extraneous_codepoints(16),
+                )
+                .with_entry_codepoints(Some(18)),
                 patch_with_intersection(
                     map.offset_for("entries[2]") * 8 + 3,
                     3,
@@ -1964,12 +2424,34 @@ mod tests {
                         1,
                         [(Tag::new(b"wght"), Fixed::from_i32(195))],
                         2
-                    ),
-                ),
+                    )
+                    .with_extraneous_codepoints(15),
+                )
+                .with_entry_codepoints(Some(18)),
             ]
         );
     }
 
+    #[test]
+    fn format_2_patch_map_entry_codepoints() {
+        let font_bytes = create_ift_font(
+            FontRef::new(test_data::ift::IFT_BASE).unwrap(),
+            Some(&copy_indices_format2()),
+            None,
+        );
+        let font = FontRef::new(&font_bytes).unwrap();
+
+        let patches = intersecting_patches(
+            &font,
+            &SubsetDefinition::new(IntSet::from([0x05]), BTreeSet::new(), HashMap::new()),
+        )
+        .unwrap();
+
+        // entry 5 copies entry 1 ([5..22]), entry 9 additionally adds [100..117].
+        let counts: Vec<Option<u64>> = patches.iter().map(|uri| uri.entry_codepoints()).collect();
+        assert_eq!(counts, vec![Some(18), Some(36)]);
+    }
+
     #[test]
     fn format_2_patch_map_copy_indices() {
         let font_bytes = create_ift_font(
@@ -2264,6 +2746,13 @@ mod tests {
 
         assert_eq!(v3.cmp(&v5), Ordering::Less);
         assert_eq!(v5.cmp(&v3), Ordering::Greater);
+
+        // fewer extraneous codepoints is preferred over entry order
+        let v6 = IntersectionInfo::new(6, 1, 1).with_extraneous_codepoints(5);
+        let v7 = IntersectionInfo::new(6, 1, 2).with_extraneous_codepoints(4);
+        assert_eq!(v6.cmp(&v7), Ordering::Less);
+        assert_eq!(v7.cmp(&v6), Ordering::Greater);
+        assert_eq!(v7.cmp(&v5), Ordering::Less);
     }
 
     #[test]
@@ -8,18 +8,21 @@
 //!
 use std::collections::BTreeSet;
 
-use crate::font_patch::PatchingError;
+use crate::font_patch::{check_cancelled, DecompressionLimits, PatchObserver, PatchingError};
 use read_fonts::{
     tables::ift::{TableKeyedPatch, TablePatch, TablePatchFlags},
     types::Tag,
     FontRef, ReadError,
 };
-use shared_brotli_patch_decoder::shared_brotli_decode;
+use shared_brotli_patch_decoder::SharedBrotliDecoder;
 use write_fonts::FontBuilder;
 
 pub(crate) fn apply_table_keyed_patch(
     patch: &TableKeyedPatch<'_>,
     font: &FontRef,
+    decoder: &dyn SharedBrotliDecoder,
+    limits: &DecompressionLimits,
+    observer: &mut dyn PatchObserver,
 ) -> Result<Vec<u8>, PatchingError> {
     if patch.format() != Tag::new(b"iftk") {
         return Err(PatchingError::InvalidPatch("Patch file tag is not 'iftk'"));
@@ -29,13 +32,10 @@ pub(crate) fn apply_table_keyed_patch(
     const STREAM_START: u32 = 9;
     let mut font_builder = FontBuilder::new();
     let mut processed_tables = BTreeSet::<Tag>::new();
-    // TODO(garretrieger): enforce a max combined size of all decoded tables? say something in the spec about this?
-    for (i, table_patch) in patch
-        .patches()
-        .iter()
-        .take(patch.patches_count() as usize)
-        .enumerate()
-    {
+    let mut decompressed_size = 0;
+    let table_count = patch.patches_count() as usize;
+    for (i, table_patch) in patch.patches().iter().take(table_count).enumerate() {
+        check_cancelled(observer)?;
         let next = i + 1;
 
         let table_patch = table_patch.map_err(PatchingError::PatchParsingFailed)?;
@@ -64,17 +64,32 @@ pub(crate) fn apply_table_keyed_patch(
         let tag = table_patch.tag();
         if !processed_tables.insert(tag) {
             // Table has already been processed.
+            observer.table_processed(tag, next, table_count);
             continue;
         }
 
         if table_patch.flags().contains(TablePatchFlags::DROP_TABLE) {
             // Table will not be copied, skip any further processing.
+            observer.table_processed(tag, next, table_count);
             continue;
         }
 
         let replacement = table_patch.flags().contains(TablePatchFlags::REPLACE_TABLE);
-        let new_table = apply_table_patch(font, table_patch, stream_length, replacement)?;
+        let max_size = limits.table_limit(
+            table_patch.max_uncompressed_length() as usize,
+            decompressed_size,
+        );
+        let new_table = apply_table_patch(
+            font,
+            table_patch,
+            stream_length,
+            replacement,
+            decoder,
+            max_size,
+        )?;
+        decompressed_size += new_table.len();
         font_builder.add_raw(tag, new_table);
+        observer.table_processed(tag, next, table_count);
     }
 
     copy_unprocessed_tables(font, processed_tables, &mut font_builder);
@@ -87,6 +102,8 @@ fn apply_table_patch(
     table_patch: TablePatch,
     stream_length: u32,
     replacement: bool,
+    decoder: &dyn SharedBrotliDecoder,
+    max_size: usize,
 ) -> Result<Vec<u8>, PatchingError> {
     let stream_length = stream_length as usize;
     let base_data = font.table_data(table_patch.tag());
@@ -98,17 +115,13 @@ fn apply_table_patch(
         ));
     };
     let r = match (base_data, replacement) {
-        (Some(base_data), false) => shared_brotli_decode(
-            stream,
-            Some(base_data.as_bytes()),
-            table_patch.max_uncompressed_length() as usize,
-        ),
+        (Some(base_data), false) => decoder.decode(stream, Some(base_data.as_bytes()), max_size),
         (None, false) => {
             return Err(PatchingError::InvalidPatch(
                 "Trying to patch a base table that doesn't exist.",
             ))
         }
-        _ => shared_brotli_decode(stream, None, table_patch.max_uncompressed_length() as usize),
+        _ => decoder.decode(stream, None, max_size),
     };
 
     r.map_err(PatchingError::from)
@@ -130,7 +143,7 @@ pub(crate) fn copy_unprocessed_tables<'a>(
         });
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "c-brotli"))]
 mod tests {
     use super::*;
     use font_test_data::ift::{noop_table_keyed_patch, table_keyed_patch};
@@ -139,6 +152,7 @@ mod tests {
     use read_fonts::FontRead;
     use read_fonts::FontRef;
     use read_fonts::ReadError;
+    use shared_brotli_patch_decoder::BuiltInBrotliDecoder;
     use write_fonts::FontBuilder;
 
     const IFT_TABLE: &[u8] = b"IFT PATCH MAP";
@@ -163,7 +177,13 @@ mod tests {
         let patch = TableKeyedPatch::read(FontData::new(&patch_data)).unwrap();
         let font = test_font();
         let font = FontRef::new(font.as_slice()).unwrap();
-        let r = apply_table_keyed_patch(&patch, &font);
+        let r = apply_table_keyed_patch(
+            &patch,
+            &font,
+            &BuiltInBrotliDecoder,
+            &Default::default(),
+            &mut (),
+        );
 
         let font = r.unwrap();
         let font = FontRef::new(&font).unwrap();
@@ -185,13 +205,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn table_keyed_patch_decompression_limits() {
+        let patch_data = table_keyed_patch();
+        let patch = TableKeyedPatch::read(FontData::new(&patch_data)).unwrap();
+        let font = test_font();
+        let font = FontRef::new(font.as_slice()).unwrap();
+
+        // tab1 decompresses to 29 bytes and tab2 to 30 bytes.
+        let limits = DecompressionLimits {
+            max_table_size: Some(30),
+            max_total_size: Some(59),
+        };
+        assert!(
+            apply_table_keyed_patch(&patch, &font, &BuiltInBrotliDecoder, &limits, &mut ()).is_ok()
+        );
+
+        let limits = DecompressionLimits {
+            max_table_size: Some(29),
+            max_total_size: None,
+        };
+        assert_eq!(
+            apply_table_keyed_patch(&patch, &font, &BuiltInBrotliDecoder, &limits, &mut ()),
+            Err(PatchingError::InvalidPatch("Max size exceeded."))
+        );
+
+        let limits = DecompressionLimits {
+            max_table_size: None,
+            max_total_size: Some(58),
+        };
+        assert_eq!(
+            apply_table_keyed_patch(&patch, &font, &BuiltInBrotliDecoder, &limits, &mut ()),
+            Err(PatchingError::InvalidPatch("Max size exceeded."))
+        );
+    }
+
     #[test]
     fn noop_table_keyed_patch_test() {
         let patch_data = noop_table_keyed_patch();
         let patch = TableKeyedPatch::read(FontData::new(&patch_data)).unwrap();
         let font = test_font();
         let font = FontRef::new(font.as_slice()).unwrap();
-        let r = apply_table_keyed_patch(&patch, &font);
+        let r = apply_table_keyed_patch(
+            &patch,
+            &font,
+            &BuiltInBrotliDecoder,
+            &Default::default(),
+            &mut (),
+        );
 
         let font = r.unwrap();
         let font = FontRef::new(&font).unwrap();
@@ -220,7 +281,13 @@ mod tests {
 
         assert_eq!(
             Err(PatchingError::InvalidPatch("Patch file tag is not 'iftk'")),
-            apply_table_keyed_patch(&patch, &font)
+            apply_table_keyed_patch(
+                &patch,
+                &font,
+                &BuiltInBrotliDecoder,
+                &Default::default(),
+                &mut ()
+            )
         );
     }
 
@@ -233,7 +300,13 @@ mod tests {
         let patch = TableKeyedPatch::read(FontData::new(&patch_data)).unwrap();
         let font = test_font();
         let font = FontRef::new(font.as_slice()).unwrap();
-        let r = apply_table_keyed_patch(&patch, &font);
+        let r = apply_table_keyed_patch(
+            &patch,
+            &font,
+            &BuiltInBrotliDecoder,
+            &Default::default(),
+            &mut (),
+        );
 
         let font = r.unwrap();
         let font = FontRef::new(&font).unwrap();
@@ -278,7 +351,13 @@ mod tests {
             Err(PatchingError::InvalidPatch(
                 "Patch offsets are not in sorted order."
             )),
-            apply_table_keyed_patch(&patch, &font)
+            apply_table_keyed_patch(
+                &patch,
+                &font,
+                &BuiltInBrotliDecoder,
+                &Default::default(),
+                &mut ()
+            )
         );
     }
 
@@ -295,7 +374,13 @@ mod tests {
 
         assert_eq!(
             Err(PatchingError::PatchParsingFailed(ReadError::OutOfBounds)),
-            apply_table_keyed_patch(&patch, &font)
+            apply_table_keyed_patch(
+                &patch,
+                &font,
+                &BuiltInBrotliDecoder,
+                &Default::default(),
+                &mut ()
+            )
         );
     }
 
@@ -309,7 +394,13 @@ mod tests {
         let font = FontRef::new(font.as_slice()).unwrap();
 
         // When DROP and REPLACE are both set DROP takes priority.
-        let r = apply_table_keyed_patch(&patch, &font);
+        let r = apply_table_keyed_patch(
+            &patch,
+            &font,
+            &BuiltInBrotliDecoder,
+            &Default::default(),
+            &mut (),
+        );
 
         let font = r.unwrap();
         let font = FontRef::new(&font).unwrap();
@@ -345,7 +436,13 @@ mod tests {
             Err(PatchingError::InvalidPatch(
                 "Trying to patch a base table that doesn't exist."
             )),
-            apply_table_keyed_patch(&patch, &font)
+            apply_table_keyed_patch(
+                &patch,
+                &font,
+                &BuiltInBrotliDecoder,
+                &Default::default(),
+                &mut ()
+            )
         );
     }
 
@@ -358,7 +455,13 @@ mod tests {
         let font = test_font();
         let font = FontRef::new(font.as_slice()).unwrap();
 
-        let r = apply_table_keyed_patch(&patch, &font);
+        let r = apply_table_keyed_patch(
+            &patch,
+            &font,
+            &BuiltInBrotliDecoder,
+            &Default::default(),
+            &mut (),
+        );
 
         let font = r.unwrap();
         let font = FontRef::new(&font).unwrap();
@@ -393,7 +496,13 @@ mod tests {
         let font = test_font();
         let font = FontRef::new(font.as_slice()).unwrap();
 
-        let r = apply_table_keyed_patch(&patch, &font);
+        let r = apply_table_keyed_patch(
+            &patch,
+            &font,
+            &BuiltInBrotliDecoder,
+            &Default::default(),
+            &mut (),
+        );
 
         let font = r.unwrap();
         let font = FontRef::new(&font).unwrap();
@@ -430,7 +539,13 @@ mod tests {
 
         assert_eq!(
             Err(PatchingError::InvalidPatch("Max size exceeded.")),
-            apply_table_keyed_patch(&patch, &font)
+            apply_table_keyed_patch(
+                &patch,
+                &font,
+                &BuiltInBrotliDecoder,
+                &Default::default(),
+                &mut ()
+            )
         );
     }
 }
@@ -0,0 +1,449 @@
+//! Checks that an incremental font and a set of patch files are consistent with each other.
+//!
+//! This is intended for encoders and servers, so that problems with a font and its patches are
+//! found before the files are served to clients.
+
+use std::collections::HashMap;
+
+use read_fonts::{
+    tables::{
+        glyf::Glyf,
+        ift::{GlyphKeyedPatch, GlyphPatches, Ift, TableKeyedPatch, IFTX_TAG, IFT_TAG},
+    },
+    types::Tag,
+    FontData, FontRead, FontRef, ReadError, TableProvider, TopLevelTable,
+};
+
+#[cfg(feature = "c-brotli")]
+use crate::font_patch::BuiltInBrotliDecoder;
+use crate::{
+    font_patch::{IncrementalFontPatchBase, PatchingError, SharedBrotliDecoder},
+    patch_group::PatchInfo,
+    patchmap::{unapplied_patches, PatchFormat},
+};
+
+/// A problem found while validating an incremental font and its patches.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationIssue {
+    /// A patch map entry refers to a URI which has no patch file.
+    MissingPatch(String),
+    /// The compatibility id of a patch doesn't match the mapping table which refers to it.
+    IncompatiblePatch(String),
+    /// A patch file could not be parsed or decompressed.
+    MalformedPatch { uri: String, error: PatchingError },
+    /// A glyph keyed patch has data for a glyph id which isn't in the font.
+    InvalidGlyphId { uri: String, gid: u32 },
+    /// Applying a set of patches failed.
+    ApplicationFailed(PatchingError),
+    /// The initial font, or a font produced by applying patches, is malformed.
+    MalformedFont(ReadError),
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ValidationIssue::MissingPatch(uri) => write!(f, "No patch file for '{uri}'."),
+            ValidationIssue::IncompatiblePatch(uri) => write!(
+                f,
+                "Compatibility ID of patch '{uri}' does not match the font."
+            ),
+            ValidationIssue::MalformedPatch { uri, error } => {
+                write!(f, "Patch '{uri}' is malformed: {error}")
+            }
+            ValidationIssue::InvalidGlyphId { uri, gid } => {
+                write!(
+                    f,
+                    "Patch '{uri}' has data for glyph {gid} which is not in the font."
+                )
+            }
+            ValidationIssue::ApplicationFailed(err) => {
+                write!(f, "Failed to apply patches: {err}")
+            }
+            ValidationIssue::MalformedFont(err) => write!(f, "Font is malformed: {err}"),
+        }
+    }
+}
+
+/// Validate the incremental font `font` against a set of patch files keyed by URI.
+///
+/// See [`validate_with_decoder`].
+#[cfg(feature = "c-brotli")]
+pub fn validate(font: &[u8], patches: &HashMap<String, Vec<u8>>) -> Vec<ValidationIssue> {
+    validate_with_decoder(font, patches, &BuiltInBrotliDecoder)
+}
+
+/// Validate the incremental font `font` against a set of patch files keyed by URI, using
+/// decoder to decompress the patches.
+///
+/// Every entry of the IFT and IFTX mapping tables is checked, regardless of which subset
+/// definitions would select it:
+/// - every referenced URI has a patch file,
+/// - each patch has the compatibility id of the mapping table that refers to it,
+/// - glyph keyed patches only have data for glyph ids which are in the font,
+/// - the initial font and every font produced by applying patches is well formed.
+///
+/// Invalidating patches replace the mapping tables, so each one is applied and the font it
+/// produces is validated in turn. This covers every font reachable from the initial font, with
+/// each invalidating patch followed once. The non invalidating patches of a font are applied
+/// together to check the result. Patches with problems aren't applied.
+///
+/// Returns the problems found, which is empty if the font and patches are consistent.
+pub fn validate_with_decoder(
+    font: &[u8],
+    patches: &HashMap<String, Vec<u8>>,
+    decoder: &dyn SharedBrotliDecoder,
+) -> Vec<ValidationIssue> {
+    let mut issues = vec![];
+    // Whether each URI seen so far refers to a valid patch.
+    let mut checked_uris: HashMap<String, bool> = Default::default();
+    let mut fonts = vec![font.to_vec()];
+
+    while let Some(font_bytes) = fonts.pop() {
+        let font = match FontRef::new(&font_bytes).and_then(|font| {
+            check_font(&font)?;
+            Ok(font)
+        }) {
+            Ok(font) => font,
+            Err(err) => {
+                issues.push(ValidationIssue::MalformedFont(err));
+                continue;
+            }
+        };
+
+        let entries = match unapplied_patches(&font) {
+            Ok(entries) => entries,
+            Err(err) => {
+                issues.push(ValidationIssue::MalformedFont(err));
+                continue;
+            }
+        };
+
+        let mut non_invalidating: Vec<(PatchInfo, &[u8])> = vec![];
+        for entry in entries {
+            let uri = entry.uri_string();
+            let encoding = entry.encoding();
+            let info = PatchInfo::from(entry);
+            let source_table = info.tag().tag();
+            let first_visit = !checked_uris.contains_key(&uri);
+            let Some(data) = patches.get(&uri) else {
+                if first_visit {
+                    issues.push(ValidationIssue::MissingPatch(uri.clone()));
+                    checked_uris.insert(uri, false);
+                }
+                continue;
+            };
+            if first_visit {
+                let issue_count = issues.len();
+                check_patch(
+                    &font,
+                    &uri,
+                    encoding,
+                    source_table,
+                    data,
+                    decoder,
+                    &mut issues,
+                );
+                checked_uris.insert(uri.clone(), issues.len() == issue_count);
+            }
+            if !checked_uris[&uri] {
+                continue;
+            }
+
+            if !encoding.is_invalidating() {
+                non_invalidating.push((info, data));
+            } else if first_visit {
+                match font.apply_table_keyed_patch(
+                    &info,
+                    data,
+                    decoder,
+                    &Default::default(),
+                    &mut (),
+                ) {
+                    Ok(new_font) => fonts.push(new_font),
+                    Err(err) => issues.push(ValidationIssue::ApplicationFailed(err)),
+                }
+            }
+        }
+
+        if non_invalidating.is_empty() {
+            continue;
+        }
+        let patches = non_invalidating.iter().map(|(info, data)| (info, *data));
+        match font.apply_glyph_keyed_patches(patches, decoder, &mut ()) {
+            Ok(new_font) => {
+                if let Err(err) = FontRef::new(&new_font).and_then(|font| check_font(&font)) {
+                    issues.push(ValidationIssue::MalformedFont(err));
+                }
+            }
+            Err(err) => issues.push(ValidationIssue::ApplicationFailed(err)),
+        }
+    }
+
+    issues
+}
+
+/// Checks that the tables of `font` needed for patch selection and application can be parsed.
+fn check_font(font: &FontRef) -> Result<(), ReadError> {
+    for tag in [IFT_TAG, IFTX_TAG] {
+        if font.table_data(tag).is_some() {
+            mapping_table(font, tag)?;
+        }
+    }
+
+    // Glyph keyed patches replace glyph data using loca, so it must describe valid ranges of glyf.
+    let num_glyphs = font.maxp()?.num_glyphs() as usize;
+    if let Some(glyf) = font.table_data(Glyf::TAG) {
+        let loca = font.loca(None)?;
+        for gid in 0..num_glyphs {
+            let (Some(start), Some(end)) = (loca.get_raw(gid), loca.get_raw(gid + 1)) else {
+                return Err(ReadError::OutOfBounds);
+            };
+            if start > end || end as usize > glyf.len() {
+                return Err(ReadError::MalformedData(
+                    "loca offsets are out of order or outside of glyf.",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_patch(
+    font: &FontRef,
+    uri: &str,
+    encoding: PatchFormat,
+    source_table: Tag,
+    data: &[u8],
+    decoder: &dyn SharedBrotliDecoder,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let uri = uri.to_string();
+    let malformed = |error: PatchingError| ValidationIssue::MalformedPatch {
+        uri: uri.clone(),
+        error,
+    };
+
+    let font_compat_id = match mapping_table(font, source_table) {
+        Ok(table) => table.compatibility_id(),
+        Err(err) => {
+            issues.push(ValidationIssue::MalformedFont(err));
+            return;
+        }
+    };
+
+    match encoding {
+        PatchFormat::TableKeyed { .. } => {
+            match TableKeyedPatch::read(FontData::new(data)) {
+                Ok(patch) if patch.compatibility_id() != font_compat_id => {
+                    issues.push(ValidationIssue::IncompatiblePatch(uri))
+                }
+                Ok(_) => {}
+                Err(err) => issues.push(malformed(PatchingError::PatchParsingFailed(err))),
+            };
+        }
+        PatchFormat::GlyphKeyed => {
+            let patch = match GlyphKeyedPatch::read(FontData::new(data)) {
+                Ok(patch) => patch,
+                Err(err) => {
+                    issues.push(malformed(PatchingError::PatchParsingFailed(err)));
+                    return;
+                }
+            };
+            if patch.compatibility_id() != font_compat_id {
+                issues.push(ValidationIssue::IncompatiblePatch(uri));
+                return;
+            }
+
+            let decoded = match decoder.decode(
+                patch.brotli_stream(),
+                None,
+                patch.max_uncompressed_length() as usize,
+            ) {
+                Ok(decoded) => decoded,
+                Err(err) => {
+                    issues.push(malformed(err.into()));
+                    return;
+                }
+            };
+            let glyph_patches = match GlyphPatches::read(FontData::new(&decoded), patch.flags()) {
+                Ok(glyph_patches) => glyph_patches,
+                Err(err) => {
+                    issues.push(malformed(PatchingError::PatchParsingFailed(err)));
+                    return;
+                }
+            };
+
+            let num_glyphs = match font.maxp() {
+                Ok(maxp) => maxp.num_glyphs() as u32,
+                Err(err) => {
+                    issues.push(ValidationIssue::MalformedFont(err));
+                    return;
+                }
+            };
+            for gid in glyph_patches.glyph_ids().iter() {
+                match gid {
+                    Ok(gid) if gid.get() >= num_glyphs => {
+                        issues.push(ValidationIssue::InvalidGlyphId {
+                            uri: uri.clone(),
+                            gid: gid.get(),
+                        })
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        issues.push(malformed(PatchingError::PatchParsingFailed(err)));
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn mapping_table<'a>(font: &FontRef<'a>, tag: Tag) -> Result<Ift<'a>, ReadError> {
+    if tag == IFTX_TAG {
+        font.iftx()
+    } else {
+        font.ift()
+    }
+}
+
+#[cfg(all(test, feature = "c-brotli"))]
+mod tests {
+    use super::*;
+    use crate::glyph_keyed::tests::assemble_glyph_keyed_patch;
+    use font_test_data::ift::{
+        glyf_u16_glyph_patches, glyph_keyed_patch_header, table_keyed_format2,
+        test_font_for_patching_with_loca_mod,
+    };
+    use read_fonts::types::Int24;
+
+    /// A font with two glyph keyed patches, "foo/04" from IFT and "foo/08" from IFTX.
+    fn test_font() -> Vec<u8> {
+        let mut ift_builder = table_keyed_format2();
+        ift_builder.write_at("encoding", 3u8);
+        ift_builder.write_at("compat_id[0]", 6u32);
+        ift_builder.write_at("compat_id[1]", 7u32);
+        ift_builder.write_at("compat_id[2]", 8u32);
+        ift_builder.write_at("compat_id[3]", 9u32);
+
+        let mut iftx_builder = table_keyed_format2();
+        iftx_builder.write_at("encoding", 3u8);
+        iftx_builder.write_at("compat_id[0]", 7u32);
+        iftx_builder.write_at("compat_id[1]", 7u32);
+        iftx_builder.write_at("compat_id[2]", 8u32);
+        iftx_builder.write_at("compat_id[3]", 9u32);
+        iftx_builder.write_at("id_delta", Int24::new(1));
+
+        test_font_for_patching_with_loca_mod(
+            |_| {},
+            HashMap::from([
+                (IFT_TAG, ift_builder.as_slice()),
+                (IFTX_TAG, iftx_builder.as_slice()),
+            ]),
+        )
+    }
+
+    /// Patches for test_font(), the second patch has the given compat id and last glyph id.
+    fn test_patches(patch2_compat_id: u32, patch2_last_gid: u16) -> HashMap<String, Vec<u8>> {
+        let patch1 =
+            assemble_glyph_keyed_patch(glyph_keyed_patch_header(), glyf_u16_glyph_patches());
+
+        let mut patch2 = glyf_u16_glyph_patches();
+        patch2.write_at("gid_13", patch2_last_gid);
+        let mut header = glyph_keyed_patch_header();
+        header.write_at("compatibility_id", patch2_compat_id);
+        let patch2 = assemble_glyph_keyed_patch(header, patch2);
+
+        HashMap::from([
+            ("foo/04".to_string(), patch1.as_slice().to_vec()),
+            ("foo/08".to_string(), patch2.as_slice().to_vec()),
+        ])
+    }
+
+    #[test]
+    fn consistent_font_and_patches() {
+        assert_eq!(validate(&test_font(), &test_patches(7, 14)), vec![]);
+    }
+
+    #[test]
+    fn missing_patch() {
+        let mut patches = test_patches(7, 14);
+        patches.remove("foo/08");
+        assert_eq!(
+            validate(&test_font(), &patches),
+            vec![ValidationIssue::MissingPatch("foo/08".to_string())]
+        );
+    }
+
+    #[test]
+    fn incompatible_patch() {
+        assert_eq!(
+            validate(&test_font(), &test_patches(6, 14)),
+            vec![ValidationIssue::IncompatiblePatch("foo/08".to_string())]
+        );
+    }
+
+    #[test]
+    fn invalid_glyph_id() {
+        assert_eq!(
+            validate(&test_font(), &test_patches(7, 1000)),
+            vec![ValidationIssue::InvalidGlyphId {
+                uri: "foo/08".to_string(),
+                gid: 1000
+            }]
+        );
+    }
+
+    #[test]
+    fn malformed_patch() {
+        let mut patches = test_patches(7, 14);
+        patches.insert("foo/04".to_string(), vec![1, 2, 3]);
+        assert!(matches!(
+            validate(&test_font(), &patches).as_slice(),
+            [ValidationIssue::MalformedPatch { uri, .. }] if uri == "foo/04"
+        ));
+    }
+
+    #[cfg(feature = "encoder")]
+    #[test]
+    fn every_entry_is_checked() {
+        use crate::{
+            encoder::{encode, PatchEncoding, SegmentationPlan},
+            patchmap::SubsetDefinition,
+        };
+        use read_fonts::tables::ift::CompatibilityId;
+        use skrifa::charmap::Charmap;
+
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let mut plan = SegmentationPlan::default();
+        for codepoint in Charmap::new(&font).mappings().map(|(cp, _)| cp).take(2) {
+            plan.add_segment(SubsetDefinition::codepoints(
+                [codepoint].into_iter().collect(),
+            ));
+        }
+        plan.set_patch_encoding(PatchEncoding::TableKeyed);
+        let encoded = encode(&font, &plan, "patches/{id}", CompatibilityId::new([1; 16])).unwrap();
+        let patches: HashMap<String, Vec<u8>> = encoded.patches().clone().into_iter().collect();
+        assert_eq!(patches.len(), 4);
+        assert_eq!(validate(encoded.initial_font(), &patches), vec![]);
+
+        // Extension only follows one of the two patches in the initial font, but the fonts
+        // produced by both are checked.
+        for uri in patches.keys() {
+            let mut patches = patches.clone();
+            patches.remove(uri);
+            assert_eq!(
+                validate(encoded.initial_font(), &patches),
+                vec![ValidationIssue::MissingPatch(uri.clone())]
+            );
+        }
+    }
+
+    #[test]
+    fn malformed_font() {
+        assert!(matches!(
+            validate(&[0, 1, 2, 3], &test_patches(7, 14)).as_slice(),
+            [ValidationIssue::MalformedFont(_)]
+        ));
+    }
+}
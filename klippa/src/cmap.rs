@@ -0,0 +1,95 @@
+//! impl subset() for cmap table
+
+use crate::{serialize::Serializer, Plan, Subset, SubsetError};
+use write_fonts::{
+    read::{tables::cmap::Cmap, FontRef, TopLevelTable},
+    tables::cmap as write_cmap,
+    FontBuilder,
+};
+
+// reference: subset() for cmap in harfbuzz
+// https://github.com/harfbuzz/harfbuzz/blob/main/src/hb-ot-cmap-table.hh
+impl Subset for Cmap<'_> {
+    fn subset(
+        &self,
+        plan: &Plan,
+        _font: &FontRef,
+        s: &mut Serializer,
+        _builder: &mut FontBuilder,
+    ) -> Result<(), SubsetError> {
+        // Only the unicode mappings are retained: format 14 variation
+        // sequences are dropped, since they may refer to glyphs that are not
+        // in the plan.
+        let mappings = plan
+            .unicode_to_new_gid_list
+            .iter()
+            .filter_map(|(cp, old_gid)| {
+                let new_gid = plan.glyph_map.get(old_gid)?;
+                Some((char::from_u32(*cp)?, *new_gid))
+            });
+        let cmap = write_cmap::Cmap::from_mappings(mappings)
+            .map_err(|_| SubsetError::SubsetTableError(Cmap::TAG))?;
+
+        let data =
+            write_fonts::dump_table(&cmap).map_err(|_| SubsetError::SubsetTableError(Cmap::TAG))?;
+        s.embed_bytes(&data)
+            .map_err(|_| SubsetError::SubsetTableError(Cmap::TAG))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use skrifa::MetadataProvider;
+    use write_fonts::{
+        read::{
+            collections::IntSet,
+            tables::cmap::{CmapSubtable, PlatformId},
+            types::GlyphId,
+            FontRef, TableProvider,
+        },
+        tables::cmap::Cmap,
+        FontBuilder,
+    };
+
+    #[test]
+    fn drop_variation_sequences() {
+        let font = FontRef::new(include_bytes!(
+            "../test-data/fonts/Comfortaa-Regular-new.ttf"
+        ))
+        .unwrap();
+        let mappings = font
+            .charmap()
+            .mappings()
+            .filter_map(|(cp, gid)| Some((char::from_u32(cp)?, GlyphId::new(gid.to_u32()))))
+            .collect::<Vec<_>>();
+        let alternate = font.charmap().map('b').unwrap();
+        let cmap = Cmap::from_mappings_and_variation_sequences(
+            mappings,
+            [('a', '\u{FE00}', GlyphId::new(alternate.to_u32()))],
+        )
+        .unwrap();
+        let font_data = FontBuilder::new()
+            .add_table(&cmap)
+            .unwrap()
+            .copy_missing_tables(font)
+            .build();
+        let font = FontRef::new(&font_data).unwrap();
+        assert!(font.charmap().map_variant('a', '\u{FE00}').is_some());
+
+        let mut unicodes = IntSet::empty();
+        unicodes.insert('a' as u32);
+        let data = crate::subset(&font, &unicodes, &IntSet::empty(), &IntSet::empty()).unwrap();
+        let subset_font = FontRef::new(&data).unwrap();
+        let subset_cmap = subset_font.cmap().unwrap();
+
+        assert!(subset_cmap.map_codepoint('a').is_some());
+        assert!(subset_cmap.encoding_records().iter().all(|rec| {
+            (rec.platform_id() != PlatformId::Unicode || rec.encoding_id() != 5)
+                && !matches!(
+                    rec.subtable(subset_cmap.offset_data()),
+                    Ok(CmapSubtable::Format14(_))
+                )
+        }));
+    }
+}
@@ -0,0 +1,56 @@
+//! impl subset() for GDEF table
+
+use crate::{
+    layout::{subset_class_def, subset_coverage, subset_coverage_items},
+    serialize::Serializer,
+    Plan, Subset, SubsetError,
+};
+use write_fonts::{
+    from_obj::ToOwnedTable,
+    read::{tables::gdef::Gdef, FontRef, TopLevelTable},
+    tables::gdef as write_gdef,
+    FontBuilder,
+};
+
+// reference: subset() for GDEF in harfbuzz
+// https://github.com/harfbuzz/harfbuzz/blob/main/src/OT/Layout/GDEF/GDEF.hh
+impl Subset for Gdef<'_> {
+    fn subset(
+        &self,
+        plan: &Plan,
+        _font: &FontRef,
+        s: &mut Serializer,
+        _builder: &mut FontBuilder,
+    ) -> Result<(), SubsetError> {
+        let mut gdef: write_gdef::Gdef = self.to_owned_table();
+        if let Some(class_def) = gdef.glyph_class_def.as_mut() {
+            subset_class_def(class_def, plan);
+        }
+        if let Some(attach_list) = gdef.attach_list.as_mut() {
+            let attach_points = std::mem::take(&mut attach_list.attach_points);
+            attach_list.attach_points =
+                subset_coverage_items(&mut attach_list.coverage, attach_points, plan, Some);
+        }
+        if let Some(lig_caret_list) = gdef.lig_caret_list.as_mut() {
+            let lig_glyphs = std::mem::take(&mut lig_caret_list.lig_glyphs);
+            lig_caret_list.lig_glyphs =
+                subset_coverage_items(&mut lig_caret_list.coverage, lig_glyphs, plan, Some);
+        }
+        if let Some(class_def) = gdef.mark_attach_class_def.as_mut() {
+            subset_class_def(class_def, plan);
+        }
+        // mark glyph sets are referenced by index from lookups, so empty sets are kept
+        if let Some(mark_glyph_sets) = gdef.mark_glyph_sets_def.as_mut() {
+            for coverage in mark_glyph_sets.coverages.iter_mut() {
+                subset_coverage(coverage, plan);
+            }
+        }
+        //TODO: subset the item variation store
+
+        let data =
+            write_fonts::dump_table(&gdef).map_err(|_| SubsetError::SubsetTableError(Gdef::TAG))?;
+        s.embed_bytes(&data)
+            .map_err(|_| SubsetError::SubsetTableError(Gdef::TAG))?;
+        Ok(())
+    }
+}
@@ -1,7 +1,289 @@
 //! impl subset() for GPOS table
 
-use crate::{NameIdClosure, Plan};
-use write_fonts::read::tables::gpos::Gpos;
+use crate::{
+    layout::{
+        new_gid16, subset_class_def_compact, subset_coverage, subset_coverage_items,
+        subset_feature_list, subset_feature_variations, subset_lookups, subset_script_list,
+        SubsetLayout,
+    },
+    serialize::Serializer,
+    NameIdClosure, Plan, Subset, SubsetError,
+};
+use fnv::FnvHashMap;
+use write_fonts::{
+    from_obj::ToOwnedTable,
+    read::{tables::gpos::Gpos, FontRef, TopLevelTable},
+    tables::gpos::{
+        self as write_gpos, CursivePosFormat1, ExtensionPosFormat1, ExtensionSubtable, MarkArray,
+        MarkBasePosFormat1, MarkLigPosFormat1, MarkMarkPosFormat1, PairPos, PositionChainContext,
+        PositionLookup, PositionSequenceContext, SinglePos,
+    },
+    FontBuilder, NullableOffsetMarker,
+};
+
+// reference: subset() for GPOS in harfbuzz
+// https://github.com/harfbuzz/harfbuzz/blob/main/src/hb-ot-layout-gpos-table.hh
+impl Subset for Gpos<'_> {
+    fn subset(
+        &self,
+        plan: &Plan,
+        _font: &FontRef,
+        s: &mut Serializer,
+        _builder: &mut FontBuilder,
+    ) -> Result<(), SubsetError> {
+        let mut gpos: write_gpos::Gpos = self.to_owned_table();
+        subset_script_list(&mut gpos.script_list, &plan.gpos_features);
+        subset_feature_list(
+            &mut gpos.feature_list,
+            &plan.gpos_features,
+            &plan.gpos_lookups,
+        );
+        if let Some(feature_variations) = gpos.feature_variations.as_mut() {
+            subset_feature_variations(feature_variations, &plan.gpos_features, &plan.gpos_lookups);
+        }
+        let lookups = std::mem::take(&mut gpos.lookup_list.lookups);
+        gpos.lookup_list.lookups = subset_lookups(lookups, plan, &plan.gpos_lookups);
+
+        let data =
+            write_fonts::dump_table(&gpos).map_err(|_| SubsetError::SubsetTableError(Gpos::TAG))?;
+        s.embed_bytes(&data)
+            .map_err(|_| SubsetError::SubsetTableError(Gpos::TAG))?;
+        Ok(())
+    }
+}
+
+impl SubsetLayout for PositionLookup {
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        match self {
+            PositionLookup::Single(lookup) => lookup.subset_layout(plan, lookup_map),
+            PositionLookup::Pair(lookup) => lookup.subset_layout(plan, lookup_map),
+            PositionLookup::Cursive(lookup) => lookup.subset_layout(plan, lookup_map),
+            PositionLookup::MarkToBase(lookup) => lookup.subset_layout(plan, lookup_map),
+            PositionLookup::MarkToLig(lookup) => lookup.subset_layout(plan, lookup_map),
+            PositionLookup::MarkToMark(lookup) => lookup.subset_layout(plan, lookup_map),
+            PositionLookup::Contextual(lookup) => lookup.subset_layout(plan, lookup_map),
+            PositionLookup::ChainContextual(lookup) => lookup.subset_layout(plan, lookup_map),
+            PositionLookup::Extension(lookup) => lookup.subset_layout(plan, lookup_map),
+        }
+    }
+
+    fn collect_nested_lookups(&self, lookups: &mut Vec<u16>) {
+        match self {
+            PositionLookup::Contextual(lookup) => lookup.collect_nested_lookups(lookups),
+            PositionLookup::ChainContextual(lookup) => lookup.collect_nested_lookups(lookups),
+            PositionLookup::Extension(lookup) => lookup.collect_nested_lookups(lookups),
+            _ => (),
+        }
+    }
+}
+
+impl SubsetLayout for ExtensionSubtable {
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        match self {
+            ExtensionSubtable::Single(table) => table.subset_layout(plan, lookup_map),
+            ExtensionSubtable::Pair(table) => table.subset_layout(plan, lookup_map),
+            ExtensionSubtable::Cursive(table) => table.subset_layout(plan, lookup_map),
+            ExtensionSubtable::MarkToBase(table) => table.subset_layout(plan, lookup_map),
+            ExtensionSubtable::MarkToLig(table) => table.subset_layout(plan, lookup_map),
+            ExtensionSubtable::MarkToMark(table) => table.subset_layout(plan, lookup_map),
+            ExtensionSubtable::Contextual(table) => table.subset_layout(plan, lookup_map),
+            ExtensionSubtable::ChainContextual(table) => table.subset_layout(plan, lookup_map),
+        }
+    }
+
+    fn collect_nested_lookups(&self, lookups: &mut Vec<u16>) {
+        match self {
+            ExtensionSubtable::Contextual(table) => table.collect_nested_lookups(lookups),
+            ExtensionSubtable::ChainContextual(table) => table.collect_nested_lookups(lookups),
+            _ => (),
+        }
+    }
+}
+
+impl<T: SubsetLayout> SubsetLayout for ExtensionPosFormat1<T> {
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        self.extension.subset_layout(plan, lookup_map)
+    }
+
+    fn collect_nested_lookups(&self, lookups: &mut Vec<u16>) {
+        self.extension.collect_nested_lookups(lookups)
+    }
+}
+
+impl SubsetLayout for PositionSequenceContext {
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        (**self).subset_layout(plan, lookup_map)
+    }
+
+    fn collect_nested_lookups(&self, lookups: &mut Vec<u16>) {
+        (**self).collect_nested_lookups(lookups)
+    }
+}
+
+impl SubsetLayout for PositionChainContext {
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        (**self).subset_layout(plan, lookup_map)
+    }
+
+    fn collect_nested_lookups(&self, lookups: &mut Vec<u16>) {
+        (**self).collect_nested_lookups(lookups)
+    }
+}
+
+impl SubsetLayout for SinglePos {
+    fn subset_layout(&mut self, plan: &Plan, _lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        match self {
+            SinglePos::Format1(table) => subset_coverage(&mut table.coverage, plan),
+            SinglePos::Format2(table) => {
+                let value_records = std::mem::take(&mut table.value_records);
+                table.value_records =
+                    subset_coverage_items(&mut table.coverage, value_records, plan, Some);
+                !table.value_records.is_empty()
+            }
+        }
+    }
+}
+
+impl SubsetLayout for PairPos {
+    fn subset_layout(&mut self, plan: &Plan, _lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        match self {
+            PairPos::Format1(table) => {
+                let pair_sets = std::mem::take(&mut table.pair_sets);
+                table.pair_sets =
+                    subset_coverage_items(&mut table.coverage, pair_sets, plan, |mut pair_set| {
+                        pair_set.pair_value_records.retain_mut(|record| {
+                            let Some(second_glyph) = new_gid16(plan, record.second_glyph) else {
+                                return false;
+                            };
+                            record.second_glyph = second_glyph;
+                            true
+                        });
+                        pair_set
+                            .pair_value_records
+                            .sort_by_key(|record| record.second_glyph);
+                        (!pair_set.pair_value_records.is_empty()).then_some(pair_set)
+                    });
+                !table.pair_sets.is_empty()
+            }
+            PairPos::Format2(table) => {
+                // class counts are derived from the class defs, so classes are
+                // renumbered to stay contiguous and records are filtered to match
+                let class1s = subset_class_def_compact(&mut table.class_def1, plan);
+                let class2s = subset_class_def_compact(&mut table.class_def2, plan);
+                let class1_records = std::mem::take(&mut table.class1_records);
+                table.class1_records = class1s
+                    .iter()
+                    .filter_map(|class1| class1_records.get(*class1 as usize).cloned())
+                    .map(|mut class1_record| {
+                        class1_record.class2_records = class2s
+                            .iter()
+                            .filter_map(|class2| {
+                                class1_record.class2_records.get(*class2 as usize).cloned()
+                            })
+                            .collect();
+                        class1_record
+                    })
+                    .collect();
+                subset_coverage(&mut table.coverage, plan)
+            }
+        }
+    }
+}
+
+impl SubsetLayout for CursivePosFormat1 {
+    fn subset_layout(&mut self, plan: &Plan, _lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        let records = std::mem::take(&mut self.entry_exit_record);
+        self.entry_exit_record = subset_coverage_items(&mut self.coverage, records, plan, Some);
+        !self.entry_exit_record.is_empty()
+    }
+}
+
+/// Subset the mark coverage and mark array of a mark attachment subtable.
+///
+/// Mark classes are renumbered so that they stay contiguous, returns the old
+/// class of each new class.
+fn subset_marks(
+    coverage: &mut write_fonts::tables::layout::CoverageTable,
+    mark_array: &mut MarkArray,
+    plan: &Plan,
+) -> Vec<u16> {
+    let mark_records = std::mem::take(&mut mark_array.mark_records);
+    mark_array.mark_records = subset_coverage_items(coverage, mark_records, plan, Some);
+    let mut classes: Vec<u16> = mark_array
+        .mark_records
+        .iter()
+        .map(|record| record.mark_class)
+        .collect();
+    classes.sort_unstable();
+    classes.dedup();
+    for record in mark_array.mark_records.iter_mut() {
+        record.mark_class = classes
+            .binary_search(&record.mark_class)
+            .unwrap_or_default() as u16;
+    }
+    classes
+}
+
+/// Keep the anchors of the retained mark classes
+fn subset_anchors<T: Clone>(
+    anchors: &[NullableOffsetMarker<T>],
+    classes: &[u16],
+) -> Vec<NullableOffsetMarker<T>> {
+    classes
+        .iter()
+        .map(|class| anchors.get(*class as usize).cloned().unwrap_or_default())
+        .collect()
+}
+
+impl SubsetLayout for MarkBasePosFormat1 {
+    fn subset_layout(&mut self, plan: &Plan, _lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        let classes = subset_marks(&mut self.mark_coverage, &mut self.mark_array, plan);
+        let base_records = std::mem::take(&mut self.base_array.base_records);
+        self.base_array.base_records =
+            subset_coverage_items(&mut self.base_coverage, base_records, plan, |mut record| {
+                record.base_anchors = subset_anchors(&record.base_anchors, &classes);
+                Some(record)
+            });
+        !classes.is_empty() && !self.base_array.base_records.is_empty()
+    }
+}
+
+impl SubsetLayout for MarkLigPosFormat1 {
+    fn subset_layout(&mut self, plan: &Plan, _lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        let classes = subset_marks(&mut self.mark_coverage, &mut self.mark_array, plan);
+        let ligature_attaches = std::mem::take(&mut self.ligature_array.ligature_attaches);
+        self.ligature_array.ligature_attaches = subset_coverage_items(
+            &mut self.ligature_coverage,
+            ligature_attaches,
+            plan,
+            |mut attach| {
+                for component in attach.component_records.iter_mut() {
+                    component.ligature_anchors =
+                        subset_anchors(&component.ligature_anchors, &classes);
+                }
+                Some(attach)
+            },
+        );
+        !classes.is_empty() && !self.ligature_array.ligature_attaches.is_empty()
+    }
+}
+
+impl SubsetLayout for MarkMarkPosFormat1 {
+    fn subset_layout(&mut self, plan: &Plan, _lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        let classes = subset_marks(&mut self.mark1_coverage, &mut self.mark1_array, plan);
+        let mark2_records = std::mem::take(&mut self.mark2_array.mark2_records);
+        self.mark2_array.mark2_records = subset_coverage_items(
+            &mut self.mark2_coverage,
+            mark2_records,
+            plan,
+            |mut record| {
+                record.mark2_anchors = subset_anchors(&record.mark2_anchors, &classes);
+                Some(record)
+            },
+        );
+        !classes.is_empty() && !self.mark2_array.mark2_records.is_empty()
+    }
+}
 
 impl NameIdClosure for Gpos<'_> {
     //TODO: support instancing: collect from feature substitutes if exist
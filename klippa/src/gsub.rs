@@ -1,7 +1,242 @@
 //! impl subset() for GSUB table
 
-use crate::{NameIdClosure, Plan};
-use write_fonts::read::tables::gsub::Gsub;
+use crate::{
+    layout::{
+        new_gid16, subset_coverage, subset_coverage_items, subset_feature_list,
+        subset_feature_variations, subset_glyph_sequence, subset_lookups, subset_script_list,
+        SubsetLayout,
+    },
+    serialize::Serializer,
+    NameIdClosure, Plan, Subset, SubsetError,
+};
+use fnv::FnvHashMap;
+use write_fonts::{
+    from_obj::ToOwnedTable,
+    read::{tables::gsub::Gsub, FontRef, TopLevelTable},
+    tables::gsub::{
+        self as write_gsub, AlternateSubstFormat1, ExtensionSubstFormat1, ExtensionSubtable,
+        LigatureSubstFormat1, MultipleSubstFormat1, ReverseChainSingleSubstFormat1, SingleSubst,
+        SingleSubstFormat1, SingleSubstFormat2, SubstitutionChainContext, SubstitutionLookup,
+        SubstitutionSequenceContext,
+    },
+    types::GlyphId16,
+    FontBuilder,
+};
+
+// reference: subset() for GSUB in harfbuzz
+// https://github.com/harfbuzz/harfbuzz/blob/main/src/hb-ot-layout-gsub-table.hh
+impl Subset for Gsub<'_> {
+    fn subset(
+        &self,
+        plan: &Plan,
+        _font: &FontRef,
+        s: &mut Serializer,
+        _builder: &mut FontBuilder,
+    ) -> Result<(), SubsetError> {
+        let mut gsub: write_gsub::Gsub = self.to_owned_table();
+        subset_script_list(&mut gsub.script_list, &plan.gsub_features);
+        subset_feature_list(
+            &mut gsub.feature_list,
+            &plan.gsub_features,
+            &plan.gsub_lookups,
+        );
+        if let Some(feature_variations) = gsub.feature_variations.as_mut() {
+            subset_feature_variations(feature_variations, &plan.gsub_features, &plan.gsub_lookups);
+        }
+        let lookups = std::mem::take(&mut gsub.lookup_list.lookups);
+        gsub.lookup_list.lookups = subset_lookups(lookups, plan, &plan.gsub_lookups);
+
+        let data =
+            write_fonts::dump_table(&gsub).map_err(|_| SubsetError::SubsetTableError(Gsub::TAG))?;
+        s.embed_bytes(&data)
+            .map_err(|_| SubsetError::SubsetTableError(Gsub::TAG))?;
+        Ok(())
+    }
+}
+
+impl SubsetLayout for SubstitutionLookup {
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        match self {
+            SubstitutionLookup::Single(lookup) => lookup.subset_layout(plan, lookup_map),
+            SubstitutionLookup::Multiple(lookup) => lookup.subset_layout(plan, lookup_map),
+            SubstitutionLookup::Alternate(lookup) => lookup.subset_layout(plan, lookup_map),
+            SubstitutionLookup::Ligature(lookup) => lookup.subset_layout(plan, lookup_map),
+            SubstitutionLookup::Contextual(lookup) => lookup.subset_layout(plan, lookup_map),
+            SubstitutionLookup::ChainContextual(lookup) => lookup.subset_layout(plan, lookup_map),
+            SubstitutionLookup::Extension(lookup) => lookup.subset_layout(plan, lookup_map),
+            SubstitutionLookup::Reverse(lookup) => lookup.subset_layout(plan, lookup_map),
+        }
+    }
+
+    fn collect_nested_lookups(&self, lookups: &mut Vec<u16>) {
+        match self {
+            SubstitutionLookup::Contextual(lookup) => lookup.collect_nested_lookups(lookups),
+            SubstitutionLookup::ChainContextual(lookup) => lookup.collect_nested_lookups(lookups),
+            SubstitutionLookup::Extension(lookup) => lookup.collect_nested_lookups(lookups),
+            _ => (),
+        }
+    }
+}
+
+impl SubsetLayout for ExtensionSubtable {
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        match self {
+            ExtensionSubtable::Single(table) => table.subset_layout(plan, lookup_map),
+            ExtensionSubtable::Multiple(table) => table.subset_layout(plan, lookup_map),
+            ExtensionSubtable::Alternate(table) => table.subset_layout(plan, lookup_map),
+            ExtensionSubtable::Ligature(table) => table.subset_layout(plan, lookup_map),
+            ExtensionSubtable::Contextual(table) => table.subset_layout(plan, lookup_map),
+            ExtensionSubtable::ChainContextual(table) => table.subset_layout(plan, lookup_map),
+            ExtensionSubtable::Reverse(table) => table.subset_layout(plan, lookup_map),
+        }
+    }
+
+    fn collect_nested_lookups(&self, lookups: &mut Vec<u16>) {
+        match self {
+            ExtensionSubtable::Contextual(table) => table.collect_nested_lookups(lookups),
+            ExtensionSubtable::ChainContextual(table) => table.collect_nested_lookups(lookups),
+            _ => (),
+        }
+    }
+}
+
+impl<T: SubsetLayout> SubsetLayout for ExtensionSubstFormat1<T> {
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        self.extension.subset_layout(plan, lookup_map)
+    }
+
+    fn collect_nested_lookups(&self, lookups: &mut Vec<u16>) {
+        self.extension.collect_nested_lookups(lookups)
+    }
+}
+
+impl SubsetLayout for SubstitutionSequenceContext {
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        (**self).subset_layout(plan, lookup_map)
+    }
+
+    fn collect_nested_lookups(&self, lookups: &mut Vec<u16>) {
+        (**self).collect_nested_lookups(lookups)
+    }
+}
+
+impl SubsetLayout for SubstitutionChainContext {
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        (**self).subset_layout(plan, lookup_map)
+    }
+
+    fn collect_nested_lookups(&self, lookups: &mut Vec<u16>) {
+        (**self).collect_nested_lookups(lookups)
+    }
+}
+
+impl SubsetLayout for SingleSubst {
+    fn subset_layout(&mut self, plan: &Plan, _lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        let substitutions: Vec<(GlyphId16, GlyphId16)> = match self {
+            SingleSubst::Format1(table) => table
+                .coverage
+                .iter()
+                .map(|gid| {
+                    // addition of the delta is modulo 65536
+                    let target = gid.to_u16().wrapping_add_signed(table.delta_glyph_id);
+                    (gid, GlyphId16::new(target))
+                })
+                .collect(),
+            SingleSubst::Format2(table) => table
+                .coverage
+                .iter()
+                .zip(table.substitute_glyph_ids.iter().copied())
+                .collect(),
+        };
+        let mut substitutions: Vec<(GlyphId16, GlyphId16)> = substitutions
+            .into_iter()
+            .filter_map(|(gid, target)| Some((new_gid16(plan, gid)?, new_gid16(plan, target)?)))
+            .collect();
+        substitutions.sort_unstable();
+
+        let delta = |(gid, target): &(GlyphId16, GlyphId16)| {
+            target.to_u16().wrapping_sub(gid.to_u16()) as i16
+        };
+        let Some(first_delta) = substitutions.first().map(delta) else {
+            return false;
+        };
+        let coverage = substitutions.iter().map(|(gid, _)| *gid).collect();
+        *self = if substitutions.iter().all(|sub| delta(sub) == first_delta) {
+            SingleSubst::Format1(SingleSubstFormat1::new(coverage, first_delta))
+        } else {
+            let targets = substitutions.iter().map(|(_, target)| *target).collect();
+            SingleSubst::Format2(SingleSubstFormat2::new(coverage, targets))
+        };
+        true
+    }
+}
+
+impl SubsetLayout for MultipleSubstFormat1 {
+    fn subset_layout(&mut self, plan: &Plan, _lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        let sequences = std::mem::take(&mut self.sequences);
+        self.sequences =
+            subset_coverage_items(&mut self.coverage, sequences, plan, |mut sequence| {
+                sequence.substitute_glyph_ids =
+                    subset_glyph_sequence(&sequence.substitute_glyph_ids, plan)?;
+                Some(sequence)
+            });
+        !self.sequences.is_empty()
+    }
+}
+
+impl SubsetLayout for AlternateSubstFormat1 {
+    fn subset_layout(&mut self, plan: &Plan, _lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        let alternate_sets = std::mem::take(&mut self.alternate_sets);
+        self.alternate_sets =
+            subset_coverage_items(&mut self.coverage, alternate_sets, plan, |mut set| {
+                set.alternate_glyph_ids = set
+                    .alternate_glyph_ids
+                    .iter()
+                    .filter_map(|gid| new_gid16(plan, *gid))
+                    .collect();
+                (!set.alternate_glyph_ids.is_empty()).then_some(set)
+            });
+        !self.alternate_sets.is_empty()
+    }
+}
+
+impl SubsetLayout for LigatureSubstFormat1 {
+    fn subset_layout(&mut self, plan: &Plan, _lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        let ligature_sets = std::mem::take(&mut self.ligature_sets);
+        self.ligature_sets =
+            subset_coverage_items(&mut self.coverage, ligature_sets, plan, |mut set| {
+                set.ligatures.retain_mut(|ligature| {
+                    let (Some(ligature_glyph), Some(components)) = (
+                        new_gid16(plan, ligature.ligature_glyph),
+                        subset_glyph_sequence(&ligature.component_glyph_ids, plan),
+                    ) else {
+                        return false;
+                    };
+                    ligature.ligature_glyph = ligature_glyph;
+                    ligature.component_glyph_ids = components;
+                    true
+                });
+                (!set.ligatures.is_empty()).then_some(set)
+            });
+        !self.ligature_sets.is_empty()
+    }
+}
+
+impl SubsetLayout for ReverseChainSingleSubstFormat1 {
+    fn subset_layout(&mut self, plan: &Plan, _lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        let substitutes = std::mem::take(&mut self.substitute_glyph_ids);
+        self.substitute_glyph_ids =
+            subset_coverage_items(&mut self.coverage, substitutes, plan, |gid| {
+                new_gid16(plan, gid)
+            });
+        self.backtrack_coverages
+            .iter_mut()
+            .chain(self.lookahead_coverages.iter_mut())
+            .fold(!self.substitute_glyph_ids.is_empty(), |all, coverage| {
+                subset_coverage(coverage, plan) && all
+            })
+    }
+}
 
 impl NameIdClosure for Gsub<'_> {
     //TODO: support instancing: collect from feature substitutes if exist
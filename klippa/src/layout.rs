@@ -1,11 +1,21 @@
 //! impl subset() for layout common tables
 
 use crate::{NameIdClosure, Plan};
-use write_fonts::read::{
+use fnv::FnvHashMap;
+use write_fonts::{
+    read::{
+        collections::IntSet,
+        tables::layout::{
+            CharacterVariantParams, Feature, FeatureParams, SizeParams, StylisticSetParams,
+        },
+        types::NameId,
+    },
     tables::layout::{
-        CharacterVariantParams, Feature, FeatureParams, SizeParams, StylisticSetParams,
+        self as write_layout, ChainedSequenceContext, ClassDef, CoverageTable, FeatureList,
+        FeatureVariations, LangSys, Lookup, ScriptList, SequenceContext, SequenceLookupRecord,
     },
-    types::NameId,
+    types::{GlyphId, GlyphId16, Tag},
+    OffsetMarker,
 };
 
 impl NameIdClosure for StylisticSetParams<'_> {
@@ -53,3 +63,394 @@ impl NameIdClosure for Feature<'_> {
         }
     }
 }
+
+/// Subsetting of GSUB/GPOS lookups and their subtables
+pub(crate) trait SubsetLayout {
+    /// Remap glyphs and nested lookup indices to the subset font.
+    ///
+    /// Returns `false` if nothing is left after subsetting, in which case the
+    /// subtable should be dropped.
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool;
+
+    /// Collect the indices of lookups referenced by nested lookup records.
+    fn collect_nested_lookups(&self, _lookups: &mut Vec<u16>) {}
+}
+
+/// Compute the old->new feature and lookup index maps of a GSUB or GPOS table.
+///
+/// Features are retained if their tag is in `layout_features`, lookups if they are
+/// referenced by a retained feature, directly or through contextual lookups.
+pub(crate) fn layout_feature_lookup_maps<T: SubsetLayout>(
+    feature_list: &FeatureList,
+    feature_variations: Option<&FeatureVariations>,
+    lookups: &[OffsetMarker<T>],
+    layout_features: &IntSet<Tag>,
+) -> (FnvHashMap<u16, u16>, FnvHashMap<u16, u16>) {
+    let feature_indices: IntSet<u16> = feature_list
+        .feature_records
+        .iter()
+        .zip(0u16..)
+        .filter(|(record, _)| layout_features.contains(record.feature_tag))
+        .map(|(_, i)| i)
+        .collect();
+
+    let mut stack: Vec<u16> = feature_list
+        .feature_records
+        .iter()
+        .zip(0u16..)
+        .filter(|(_, i)| feature_indices.contains(*i))
+        .flat_map(|(record, _)| record.feature.lookup_list_indices.iter().copied())
+        .collect();
+    let substitutions = feature_variations
+        .into_iter()
+        .flat_map(|variations| variations.feature_variation_records.iter())
+        .filter_map(|record| record.feature_table_substitution.as_ref())
+        .flat_map(|table| table.substitutions.iter())
+        .filter(|substitution| feature_indices.contains(substitution.feature_index));
+    for substitution in substitutions {
+        stack.extend(substitution.alternate_feature.lookup_list_indices.iter());
+    }
+
+    let mut lookup_indices = IntSet::empty();
+    while let Some(lookup_index) = stack.pop() {
+        let Some(lookup) = lookups.get(lookup_index as usize) else {
+            continue;
+        };
+        if lookup_indices.insert(lookup_index) {
+            lookup.collect_nested_lookups(&mut stack);
+        }
+    }
+    let remap = |indices: IntSet<u16>| indices.iter().zip(0u16..).collect();
+    (remap(feature_indices), remap(lookup_indices))
+}
+
+/// Drop features which are not retained from all language systems
+pub(crate) fn subset_script_list(script_list: &mut ScriptList, feature_map: &FnvHashMap<u16, u16>) {
+    for script_record in script_list.script_records.iter_mut() {
+        let script = &mut script_record.script;
+        if let Some(lang_sys) = script.default_lang_sys.as_mut() {
+            subset_lang_sys(lang_sys, feature_map);
+        }
+        for lang_sys_record in script.lang_sys_records.iter_mut() {
+            subset_lang_sys(&mut lang_sys_record.lang_sys, feature_map);
+        }
+    }
+}
+
+fn subset_lang_sys(lang_sys: &mut LangSys, feature_map: &FnvHashMap<u16, u16>) {
+    lang_sys.required_feature_index = feature_map
+        .get(&lang_sys.required_feature_index)
+        .copied()
+        .unwrap_or(0xFFFF);
+    lang_sys.feature_indices = lang_sys
+        .feature_indices
+        .iter()
+        .filter_map(|i| feature_map.get(i).copied())
+        .collect();
+}
+
+pub(crate) fn subset_feature_list(
+    feature_list: &mut FeatureList,
+    feature_map: &FnvHashMap<u16, u16>,
+    lookup_map: &FnvHashMap<u16, u16>,
+) {
+    let feature_records = std::mem::take(&mut feature_list.feature_records);
+    feature_list.feature_records = feature_records
+        .into_iter()
+        .zip(0u16..)
+        .filter(|(_, i)| feature_map.contains_key(i))
+        .map(|(mut record, _)| {
+            subset_feature(&mut record.feature, lookup_map);
+            record
+        })
+        .collect();
+}
+
+pub(crate) fn subset_feature_variations(
+    feature_variations: &mut FeatureVariations,
+    feature_map: &FnvHashMap<u16, u16>,
+    lookup_map: &FnvHashMap<u16, u16>,
+) {
+    let tables = feature_variations
+        .feature_variation_records
+        .iter_mut()
+        .filter_map(|record| record.feature_table_substitution.as_mut());
+    for table in tables {
+        table.substitutions.retain_mut(|substitution| {
+            let Some(feature_index) = feature_map.get(&substitution.feature_index) else {
+                return false;
+            };
+            substitution.feature_index = *feature_index;
+            subset_feature(&mut substitution.alternate_feature, lookup_map);
+            true
+        });
+    }
+}
+
+fn subset_feature(feature: &mut write_layout::Feature, lookup_map: &FnvHashMap<u16, u16>) {
+    feature.lookup_list_indices = feature
+        .lookup_list_indices
+        .iter()
+        .filter_map(|i| lookup_map.get(i).copied())
+        .collect();
+}
+
+/// Retain the lookups in `lookup_map` and subset each of them
+pub(crate) fn subset_lookups<T: SubsetLayout>(
+    lookups: Vec<OffsetMarker<T>>,
+    plan: &Plan,
+    lookup_map: &FnvHashMap<u16, u16>,
+) -> Vec<OffsetMarker<T>> {
+    // lookup_map preserves the order of lookups, so no sorting is needed
+    lookups
+        .into_iter()
+        .zip(0u16..)
+        .filter(|(_, i)| lookup_map.contains_key(i))
+        .map(|(mut lookup, _)| {
+            lookup.subset_layout(plan, lookup_map);
+            lookup
+        })
+        .collect()
+}
+
+impl<T: SubsetLayout> SubsetLayout for Lookup<T> {
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        self.subtables
+            .retain_mut(|subtable| subtable.subset_layout(plan, lookup_map));
+        // empty lookups are kept, so lookup indices computed by the plan stay valid
+        true
+    }
+
+    fn collect_nested_lookups(&self, lookups: &mut Vec<u16>) {
+        for subtable in self.subtables.iter() {
+            subtable.collect_nested_lookups(lookups);
+        }
+    }
+}
+
+/// Return the new glyph id of `gid`, if it is retained
+pub(crate) fn new_gid16(plan: &Plan, gid: GlyphId16) -> Option<GlyphId16> {
+    plan.glyph_map
+        .get(&GlyphId::from(gid))
+        .and_then(|new_gid| GlyphId16::try_from(*new_gid).ok())
+}
+
+/// Remap a sequence of glyphs, returns `None` if any of them is not retained
+pub(crate) fn subset_glyph_sequence(glyphs: &[GlyphId16], plan: &Plan) -> Option<Vec<GlyphId16>> {
+    glyphs.iter().map(|gid| new_gid16(plan, *gid)).collect()
+}
+
+/// Remap a coverage table, returns `false` if no glyph is retained
+pub(crate) fn subset_coverage(coverage: &mut CoverageTable, plan: &Plan) -> bool {
+    *coverage = coverage
+        .iter()
+        .filter_map(|gid| new_gid16(plan, gid))
+        .collect();
+    !coverage.is_empty()
+}
+
+/// Remap a coverage table along with an array of items indexed by coverage index.
+///
+/// Items of glyphs which are not retained, or for which `subset_item` returns
+/// `None`, are dropped. Returns the retained items in the new coverage order.
+pub(crate) fn subset_coverage_items<T>(
+    coverage: &mut CoverageTable,
+    items: Vec<T>,
+    plan: &Plan,
+    mut subset_item: impl FnMut(T) -> Option<T>,
+) -> Vec<T> {
+    let mut retained: Vec<(GlyphId16, T)> = coverage
+        .iter()
+        .zip(items)
+        .filter_map(|(gid, item)| Some((new_gid16(plan, gid)?, subset_item(item)?)))
+        .collect();
+    retained.sort_unstable_by_key(|(gid, _)| *gid);
+    *coverage = retained.iter().map(|(gid, _)| *gid).collect();
+    retained.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Remap the glyphs of a class def, classes are left untouched
+pub(crate) fn subset_class_def(class_def: &mut ClassDef, plan: &Plan) {
+    *class_def = class_def
+        .iter()
+        .filter_map(|(gid, class)| Some((new_gid16(plan, gid)?, class)))
+        .collect();
+}
+
+/// Remap the glyphs of a class def and renumber its classes so that they are
+/// contiguous, returns the old class of each new class (including class 0).
+pub(crate) fn subset_class_def_compact(class_def: &mut ClassDef, plan: &Plan) -> Vec<u16> {
+    let mapped: Vec<(GlyphId16, u16)> = class_def
+        .iter()
+        .filter_map(|(gid, class)| Some((new_gid16(plan, gid)?, class)))
+        .filter(|(_, class)| *class != 0)
+        .collect();
+    let mut old_classes: Vec<u16> = mapped.iter().map(|(_, class)| *class).collect();
+    old_classes.push(0);
+    old_classes.sort_unstable();
+    old_classes.dedup();
+    *class_def = mapped
+        .into_iter()
+        .map(|(gid, class)| {
+            let new_class = old_classes.binary_search(&class).unwrap_or_default();
+            (gid, new_class as u16)
+        })
+        .collect();
+    old_classes
+}
+
+fn subset_seq_lookup_records(
+    records: &mut Vec<SequenceLookupRecord>,
+    lookup_map: &FnvHashMap<u16, u16>,
+) {
+    records.retain_mut(|record| match lookup_map.get(&record.lookup_list_index) {
+        Some(new_index) => {
+            record.lookup_list_index = *new_index;
+            true
+        }
+        None => false,
+    });
+}
+
+fn collect_seq_lookup_records(records: &[SequenceLookupRecord], lookups: &mut Vec<u16>) {
+    lookups.extend(records.iter().map(|record| record.lookup_list_index));
+}
+
+impl SubsetLayout for SequenceContext {
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        match self {
+            SequenceContext::Format1(table) => {
+                let rule_sets = std::mem::take(&mut table.seq_rule_sets);
+                table.seq_rule_sets =
+                    subset_coverage_items(&mut table.coverage, rule_sets, plan, |mut rule_set| {
+                        if let Some(rule_set) = rule_set.as_mut() {
+                            rule_set.seq_rules.retain_mut(|rule| {
+                                let Some(input) = subset_glyph_sequence(&rule.input_sequence, plan)
+                                else {
+                                    return false;
+                                };
+                                rule.input_sequence = input;
+                                subset_seq_lookup_records(&mut rule.seq_lookup_records, lookup_map);
+                                true
+                            });
+                        }
+                        Some(rule_set)
+                    });
+                !table.seq_rule_sets.is_empty()
+            }
+            SequenceContext::Format2(table) => {
+                subset_class_def(&mut table.class_def, plan);
+                let rules = table
+                    .class_seq_rule_sets
+                    .iter_mut()
+                    .filter_map(|rule_set| rule_set.as_mut())
+                    .flat_map(|rule_set| rule_set.class_seq_rules.iter_mut());
+                for rule in rules {
+                    subset_seq_lookup_records(&mut rule.seq_lookup_records, lookup_map);
+                }
+                subset_coverage(&mut table.coverage, plan)
+            }
+            SequenceContext::Format3(table) => {
+                subset_seq_lookup_records(&mut table.seq_lookup_records, lookup_map);
+                table
+                    .coverages
+                    .iter_mut()
+                    .all(|coverage| subset_coverage(coverage, plan))
+            }
+        }
+    }
+
+    fn collect_nested_lookups(&self, lookups: &mut Vec<u16>) {
+        match self {
+            SequenceContext::Format1(table) => table
+                .seq_rule_sets
+                .iter()
+                .filter_map(|rule_set| rule_set.as_ref())
+                .flat_map(|rule_set| rule_set.seq_rules.iter())
+                .for_each(|rule| collect_seq_lookup_records(&rule.seq_lookup_records, lookups)),
+            SequenceContext::Format2(table) => table
+                .class_seq_rule_sets
+                .iter()
+                .filter_map(|rule_set| rule_set.as_ref())
+                .flat_map(|rule_set| rule_set.class_seq_rules.iter())
+                .for_each(|rule| collect_seq_lookup_records(&rule.seq_lookup_records, lookups)),
+            SequenceContext::Format3(table) => {
+                collect_seq_lookup_records(&table.seq_lookup_records, lookups)
+            }
+        }
+    }
+}
+
+impl SubsetLayout for ChainedSequenceContext {
+    fn subset_layout(&mut self, plan: &Plan, lookup_map: &FnvHashMap<u16, u16>) -> bool {
+        match self {
+            ChainedSequenceContext::Format1(table) => {
+                let rule_sets = std::mem::take(&mut table.chained_seq_rule_sets);
+                table.chained_seq_rule_sets =
+                    subset_coverage_items(&mut table.coverage, rule_sets, plan, |mut rule_set| {
+                        if let Some(rule_set) = rule_set.as_mut() {
+                            rule_set.chained_seq_rules.retain_mut(|rule| {
+                                let (Some(backtrack), Some(input), Some(lookahead)) = (
+                                    subset_glyph_sequence(&rule.backtrack_sequence, plan),
+                                    subset_glyph_sequence(&rule.input_sequence, plan),
+                                    subset_glyph_sequence(&rule.lookahead_sequence, plan),
+                                ) else {
+                                    return false;
+                                };
+                                rule.backtrack_sequence = backtrack;
+                                rule.input_sequence = input;
+                                rule.lookahead_sequence = lookahead;
+                                subset_seq_lookup_records(&mut rule.seq_lookup_records, lookup_map);
+                                true
+                            });
+                        }
+                        Some(rule_set)
+                    });
+                !table.chained_seq_rule_sets.is_empty()
+            }
+            ChainedSequenceContext::Format2(table) => {
+                subset_class_def(&mut table.backtrack_class_def, plan);
+                subset_class_def(&mut table.input_class_def, plan);
+                subset_class_def(&mut table.lookahead_class_def, plan);
+                let rules = table
+                    .chained_class_seq_rule_sets
+                    .iter_mut()
+                    .filter_map(|rule_set| rule_set.as_mut())
+                    .flat_map(|rule_set| rule_set.chained_class_seq_rules.iter_mut());
+                for rule in rules {
+                    subset_seq_lookup_records(&mut rule.seq_lookup_records, lookup_map);
+                }
+                subset_coverage(&mut table.coverage, plan)
+            }
+            ChainedSequenceContext::Format3(table) => {
+                subset_seq_lookup_records(&mut table.seq_lookup_records, lookup_map);
+                table
+                    .backtrack_coverages
+                    .iter_mut()
+                    .chain(table.input_coverages.iter_mut())
+                    .chain(table.lookahead_coverages.iter_mut())
+                    .all(|coverage| subset_coverage(coverage, plan))
+            }
+        }
+    }
+
+    fn collect_nested_lookups(&self, lookups: &mut Vec<u16>) {
+        match self {
+            ChainedSequenceContext::Format1(table) => table
+                .chained_seq_rule_sets
+                .iter()
+                .filter_map(|rule_set| rule_set.as_ref())
+                .flat_map(|rule_set| rule_set.chained_seq_rules.iter())
+                .for_each(|rule| collect_seq_lookup_records(&rule.seq_lookup_records, lookups)),
+            ChainedSequenceContext::Format2(table) => table
+                .chained_class_seq_rule_sets
+                .iter()
+                .filter_map(|rule_set| rule_set.as_ref())
+                .flat_map(|rule_set| rule_set.chained_class_seq_rules.iter())
+                .for_each(|rule| collect_seq_lookup_records(&rule.seq_lookup_records, lookups)),
+            ChainedSequenceContext::Format3(table) => {
+                collect_seq_lookup_records(&table.seq_lookup_records, lookups)
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 //! try to define Subset trait so I can add methods for Hmtx
 //! TODO: make it generic for all tables
+mod cmap;
 mod cpal;
 mod fvar;
+mod gdef;
 mod glyf_loca;
 mod gpos;
 mod gsub;
@@ -17,18 +19,25 @@ mod post;
 mod serialize;
 mod stat;
 pub use parsing_util::{
-    parse_drop_tables, parse_name_ids, parse_name_languages, parse_unicodes, populate_gids,
+    parse_drop_tables, parse_layout_features, parse_name_ids, parse_name_languages, parse_unicodes,
+    populate_gids,
 };
 
+use std::collections::HashSet;
+
 use fnv::FnvHashMap;
+use layout::layout_feature_lookup_maps;
 use serialize::Serializer;
 use skrifa::MetadataProvider;
 use thiserror::Error;
+use write_fonts::from_obj::ToOwnedTable;
 use write_fonts::read::{
     collections::{int_set::Domain, IntSet},
     tables::{
         cff::Cff,
         cff2::Cff2,
+        cmap::Cmap,
+        gdef::Gdef,
         glyf::{Glyf, Glyph},
         gpos::Gpos,
         gsub::Gsub,
@@ -42,8 +51,7 @@ use write_fonts::read::{
     types::NameId,
     FontRef, TableProvider, TopLevelTable,
 };
-use write_fonts::types::GlyphId;
-use write_fonts::types::Tag;
+use write_fonts::types::{GlyphId, GlyphId16, Tag};
 use write_fonts::{tables::hhea::Hhea, tables::hmtx::Hmtx, tables::maxp::Maxp, FontBuilder};
 
 const MAX_COMPOSITE_OPERATIONS_PER_GLYPH: u8 = 64;
@@ -53,6 +61,92 @@ const MAX_NESTING_LEVEL: u8 = 64;
 // See <https://github.com/googlefonts/fontations/issues/997>
 const MAX_GID: GlyphId = GlyphId::new(0xFFFFFF);
 
+/// Layout features retained by default.
+///
+/// Ported from HB: <https://github.com/harfbuzz/harfbuzz/blob/main/src/hb-subset-input.cc>
+pub const DEFAULT_LAYOUT_FEATURES: [Tag; 66] = [
+    //default shaper: common
+    Tag::new(b"rvrn"),
+    Tag::new(b"ccmp"),
+    Tag::new(b"liga"),
+    Tag::new(b"locl"),
+    Tag::new(b"mark"),
+    Tag::new(b"mkmk"),
+    Tag::new(b"rlig"),
+    //fractions
+    Tag::new(b"frac"),
+    Tag::new(b"numr"),
+    Tag::new(b"dnom"),
+    //horizontal
+    Tag::new(b"calt"),
+    Tag::new(b"clig"),
+    Tag::new(b"curs"),
+    Tag::new(b"kern"),
+    Tag::new(b"rclt"),
+    //vertical
+    Tag::new(b"valt"),
+    Tag::new(b"vert"),
+    Tag::new(b"vkrn"),
+    Tag::new(b"vrt2"),
+    //ltr
+    Tag::new(b"ltra"),
+    Tag::new(b"ltrm"),
+    //rtl
+    Tag::new(b"rtla"),
+    Tag::new(b"rtlm"),
+    //random
+    Tag::new(b"rand"),
+    //justify
+    Tag::new(b"jalt"),
+    //East Asian spacing
+    Tag::new(b"chws"),
+    Tag::new(b"vchw"),
+    Tag::new(b"halt"),
+    Tag::new(b"vhal"),
+    //private
+    Tag::new(b"Harf"),
+    Tag::new(b"HARF"),
+    Tag::new(b"Buzz"),
+    Tag::new(b"BUZZ"),
+    //arabic
+    Tag::new(b"init"),
+    Tag::new(b"medi"),
+    Tag::new(b"fina"),
+    Tag::new(b"isol"),
+    Tag::new(b"med2"),
+    Tag::new(b"fin2"),
+    Tag::new(b"fin3"),
+    Tag::new(b"cswh"),
+    Tag::new(b"mset"),
+    Tag::new(b"stch"),
+    //hangul
+    Tag::new(b"ljmo"),
+    Tag::new(b"vjmo"),
+    Tag::new(b"tjmo"),
+    //tibetan
+    Tag::new(b"abvs"),
+    Tag::new(b"blws"),
+    Tag::new(b"abvm"),
+    Tag::new(b"blwm"),
+    //indic
+    Tag::new(b"nukt"),
+    Tag::new(b"akhn"),
+    Tag::new(b"rphf"),
+    Tag::new(b"rkrf"),
+    Tag::new(b"pref"),
+    Tag::new(b"blwf"),
+    Tag::new(b"half"),
+    Tag::new(b"abvf"),
+    Tag::new(b"pstf"),
+    Tag::new(b"cfar"),
+    Tag::new(b"vatu"),
+    Tag::new(b"cjct"),
+    Tag::new(b"pres"),
+    Tag::new(b"psts"),
+    Tag::new(b"haln"),
+    Tag::new(b"dist"),
+];
+
 #[derive(Clone, Copy, Debug)]
 pub struct SubsetFlags(u16);
 
@@ -95,13 +189,17 @@ impl SubsetFlags {
     pub const SUBSET_FLAGS_NO_PRUNE_UNICODE_RANGES: Self = Self(0x0100);
 
     //If set don't perform glyph closure on layout substitution rules (GSUB)
-    //This flag is UNIMPLEMENTED yet
     pub const SUBSET_FLAGS_NO_LAYOUT_CLOSURE: Self = Self(0x0200);
 
     //If set perform IUP delta optimization on the remaining gvar table's deltas.
     //This flag is UNIMPLEMENTED yet
     pub const SUBSET_FLAGS_OPTIMIZE_IUP_DELTAS: Self = Self(0x0400);
 
+    //If set maxSizeOfInstructions in maxp is recalculated from the retained glyphs, otherwise it
+    //is copied from the source font like harfbuzz does.
+    //This flag has no harfbuzz equivalent, so it uses the top bit to stay clear of new ones.
+    pub const SUBSET_FLAGS_RECALC_MAX_SIZE_OF_INSTRUCTIONS: Self = Self(0x8000);
+
     /// Returns `true` if all of the flags in `other` are contained within `self`.
     #[inline]
     pub const fn contains(&self, other: Self) -> bool {
@@ -167,9 +265,13 @@ pub struct Plan {
     name_ids: IntSet<NameId>,
     name_languages: IntSet<u16>,
 
+    layout_features: IntSet<Tag>,
     //old->new feature index map
     gsub_features: FnvHashMap<u16, u16>,
     gpos_features: FnvHashMap<u16, u16>,
+    //old->new lookup index map
+    gsub_lookups: FnvHashMap<u16, u16>,
+    gpos_lookups: FnvHashMap<u16, u16>,
 
     //old->new colrv1 layer index map
     colrv1_layers: FnvHashMap<u32, u32>,
@@ -178,6 +280,7 @@ pub struct Plan {
 }
 
 impl Plan {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         input_gids: &IntSet<GlyphId>,
         input_unicodes: &IntSet<u32>,
@@ -186,6 +289,7 @@ impl Plan {
         drop_tables: &IntSet<Tag>,
         name_ids: &IntSet<NameId>,
         name_languages: &IntSet<u16>,
+        layout_features: &IntSet<Tag>,
     ) -> Self {
         let mut this = Plan {
             font_num_glyphs: get_font_num_glyphs(font),
@@ -193,10 +297,12 @@ impl Plan {
             drop_tables: drop_tables.clone(),
             name_ids: name_ids.clone(),
             name_languages: name_languages.clone(),
+            layout_features: layout_features.clone(),
             ..Default::default()
         };
 
         this.populate_unicodes_to_retain(input_gids, input_unicodes, font);
+        this.populate_layout_features(font);
         this.populate_gids_to_retain(font);
         this.create_old_gid_to_new_gid_map();
 
@@ -284,6 +390,16 @@ impl Plan {
         cmap.closure_glyphs(&self.unicodes, &mut self.glyphset_gsub);
         remove_invalid_gids(&mut self.glyphset_gsub, self.font_num_glyphs);
 
+        //glyph closure for GSUB
+        if !self
+            .subset_flags
+            .contains(SubsetFlags::SUBSET_FLAGS_NO_LAYOUT_CLOSURE)
+            && !self.drop_tables.contains(Gsub::TAG)
+        {
+            self.gsub_closure(font);
+            remove_invalid_gids(&mut self.glyphset_gsub, self.font_num_glyphs);
+        }
+
         //skip glyph closure for MATH table, it's not supported yet
 
         //glyph closure for COLR
@@ -314,6 +430,48 @@ impl Plan {
         self.nameid_closure(font);
     }
 
+    fn populate_layout_features(&mut self, font: &FontRef) {
+        if !self.drop_tables.contains(Gsub::TAG) {
+            if let Ok(gsub) = font.gsub() {
+                let gsub: write_fonts::tables::gsub::Gsub = gsub.to_owned_table();
+                (self.gsub_features, self.gsub_lookups) = layout_feature_lookup_maps(
+                    &gsub.feature_list,
+                    gsub.feature_variations.as_ref(),
+                    &gsub.lookup_list.lookups,
+                    &self.layout_features,
+                );
+            }
+        }
+
+        if !self.drop_tables.contains(Gpos::TAG) {
+            if let Ok(gpos) = font.gpos() {
+                let gpos: write_fonts::tables::gpos::Gpos = gpos.to_owned_table();
+                (self.gpos_features, self.gpos_lookups) = layout_feature_lookup_maps(
+                    &gpos.feature_list,
+                    gpos.feature_variations.as_ref(),
+                    &gpos.lookup_list.lookups,
+                    &self.layout_features,
+                );
+            }
+        }
+    }
+
+    fn gsub_closure(&mut self, font: &FontRef) {
+        let Ok(gsub) = font.gsub() else {
+            return;
+        };
+        let lookups: HashSet<u16> = self.gsub_lookups.keys().copied().collect();
+        let glyphs = self
+            .glyphset_gsub
+            .iter()
+            .filter_map(|gid| GlyphId16::try_from(gid).ok())
+            .collect();
+        if let Ok(glyphs) = gsub.closure_glyphs_for_lookups(glyphs, &lookups) {
+            self.glyphset_gsub
+                .extend_unsorted(glyphs.into_iter().map(GlyphId::from));
+        }
+    }
+
     fn create_old_gid_to_new_gid_map(&mut self) {
         let pop = self.glyphset.len();
         self.glyph_map.reserve(pop as usize);
@@ -517,6 +675,35 @@ pub trait Subset {
     ) -> Result<(), SubsetError>;
 }
 
+/// Subset a font, retaining the given codepoints, glyphs and layout features.
+///
+/// This is a convenience wrapper around [`Plan::new`] and [`subset_font`], using the
+/// default subset flags and keeping the default name ids and name languages.
+pub fn subset(
+    font: &FontRef,
+    unicodes: &IntSet<u32>,
+    gids: &IntSet<GlyphId>,
+    layout_features: &IntSet<Tag>,
+) -> Result<Vec<u8>, SubsetError> {
+    // default values: https://github.com/harfbuzz/harfbuzz/blob/main/src/hb-subset-input.cc#L43
+    let mut name_ids = IntSet::<NameId>::empty();
+    name_ids.insert_range(NameId::from(0)..=NameId::from(6));
+    let mut name_languages = IntSet::<u16>::empty();
+    name_languages.insert(0x0409);
+
+    let plan = Plan::new(
+        gids,
+        unicodes,
+        font,
+        SubsetFlags::default(),
+        &IntSet::empty(),
+        &name_ids,
+        &name_languages,
+        layout_features,
+    );
+    subset_font(font, &plan)
+}
+
 pub fn subset_font(font: &FontRef, plan: &Plan) -> Result<Vec<u8>, SubsetError> {
     let mut builder = FontBuilder::default();
 
@@ -530,20 +717,21 @@ pub fn subset_font(font: &FontRef, plan: &Plan) -> Result<Vec<u8>, SubsetError>
         match tag {
             Head::TAG => {
                 if font.glyf().is_err() {
-                    subset(tag, font, plan, &mut builder, table_len)?;
+                    add_subset_table(tag, font, plan, &mut builder, table_len)?;
                 }
             }
             //Skip, handled by glyf
             Loca::TAG => continue,
             //Skip, handled by Hmtx
             Hhea::TAG => continue,
-            _ => subset(tag, font, plan, &mut builder, table_len)?,
+            _ => add_subset_table(tag, font, plan, &mut builder, table_len)?,
         }
     }
+    maxp::update_max_size_of_instructions(plan, &mut builder)?;
     Ok(builder.build())
 }
 
-fn subset<'a>(
+fn add_subset_table<'a>(
     table_tag: Tag,
     font: &FontRef<'a>,
     plan: &Plan,
@@ -601,11 +789,31 @@ fn subset_table<'a>(
     s: &mut Serializer,
 ) -> Result<(), SubsetError> {
     match tag {
+        Cmap::TAG => font
+            .cmap()
+            .map_err(|_| SubsetError::SubsetTableError(Cmap::TAG))?
+            .subset(plan, font, s, builder),
+
+        Gdef::TAG => font
+            .gdef()
+            .map_err(|_| SubsetError::SubsetTableError(Gdef::TAG))?
+            .subset(plan, font, s, builder),
+
         Glyf::TAG => font
             .glyf()
             .map_err(|_| SubsetError::SubsetTableError(Glyf::TAG))?
             .subset(plan, font, s, builder),
 
+        Gpos::TAG => font
+            .gpos()
+            .map_err(|_| SubsetError::SubsetTableError(Gpos::TAG))?
+            .subset(plan, font, s, builder),
+
+        Gsub::TAG => font
+            .gsub()
+            .map_err(|_| SubsetError::SubsetTableError(Gsub::TAG))?
+            .subset(plan, font, s, builder),
+
         Gvar::TAG => font
             .gvar()
             .map_err(|_| SubsetError::SubsetTableError(Gvar::TAG))?
@@ -799,4 +1007,143 @@ mod test {
         assert!(plan.glyphset.contains(GlyphId::new(4)));
         assert!(plan.glyphset.contains(GlyphId::new(7)));
     }
+
+    fn comfortaa_plan(flags: SubsetFlags, layout_features: &IntSet<Tag>) -> Plan {
+        let font = FontRef::new(include_bytes!(
+            "../test-data/fonts/Comfortaa-Regular-new.ttf"
+        ))
+        .unwrap();
+        let mut unicodes = IntSet::empty();
+        unicodes.insert(0x66_u32);
+        unicodes.insert(0x69_u32);
+        let mut name_ids = IntSet::empty();
+        name_ids.insert(NameId::new(1));
+        let mut name_languages = IntSet::empty();
+        name_languages.insert(0x409);
+        Plan::new(
+            &IntSet::empty(),
+            &unicodes,
+            &font,
+            flags,
+            &IntSet::empty(),
+            &name_ids,
+            &name_languages,
+            layout_features,
+        )
+    }
+
+    #[test]
+    fn populate_gids_with_gsub_closure() {
+        let features = DEFAULT_LAYOUT_FEATURES.into_iter().collect();
+        let plan = comfortaa_plan(SubsetFlags::default(), &features);
+        let no_closure = comfortaa_plan(SubsetFlags::SUBSET_FLAGS_NO_LAYOUT_CLOSURE, &features);
+        assert!(plan.glyphset.len() > no_closure.glyphset.len());
+        assert!(no_closure
+            .glyphset
+            .iter()
+            .all(|gid| plan.glyphset.contains(gid)));
+    }
+
+    #[test]
+    fn layout_features_filter_lookups() {
+        let plan = comfortaa_plan(SubsetFlags::default(), &IntSet::empty());
+        assert!(plan.gsub_lookups.is_empty());
+        assert!(plan.gpos_lookups.is_empty());
+        let no_closure = comfortaa_plan(
+            SubsetFlags::SUBSET_FLAGS_NO_LAYOUT_CLOSURE,
+            &DEFAULT_LAYOUT_FEATURES.into_iter().collect(),
+        );
+        assert_eq!(plan.glyphset, no_closure.glyphset);
+
+        let mut features = IntSet::empty();
+        features.insert(Tag::new(b"kern"));
+        let plan = comfortaa_plan(SubsetFlags::default(), &features);
+        assert!(plan.gsub_lookups.is_empty());
+        assert_eq!(plan.gpos_lookups.len(), 1);
+    }
+
+    #[test]
+    fn subset_layout_tables() {
+        let font = FontRef::new(include_bytes!(
+            "../test-data/fonts/Comfortaa-Regular-new.ttf"
+        ))
+        .unwrap();
+        let mut unicodes = IntSet::empty();
+        unicodes.insert_range(0x61_u32..=0x7a_u32);
+        let mut features = IntSet::empty();
+        features.insert(Tag::new(b"kern"));
+        features.insert(Tag::new(b"liga"));
+        features.insert(Tag::new(b"mark"));
+
+        let data = subset(&font, &unicodes, &IntSet::empty(), &features).unwrap();
+        let subset_font = FontRef::new(&data).unwrap();
+        let num_glyphs = subset_font.maxp().unwrap().num_glyphs();
+
+        let cmap = subset_font.cmap().unwrap();
+        for cp in 0x61_u32..=0x7a_u32 {
+            let gid = cmap.map_codepoint(cp).unwrap();
+            assert!(gid.to_u32() < num_glyphs as u32);
+        }
+
+        let gsub = subset_font.gsub().unwrap();
+        let gsub_tags: Vec<_> = gsub
+            .feature_list()
+            .unwrap()
+            .feature_records()
+            .iter()
+            .map(|r| r.feature_tag())
+            .collect();
+        assert!(!gsub_tags.is_empty());
+        assert!(gsub_tags.iter().all(|tag| *tag == Tag::new(b"liga")));
+
+        let gpos = subset_font.gpos().unwrap();
+        let gpos_tags: Vec<_> = gpos
+            .feature_list()
+            .unwrap()
+            .feature_records()
+            .iter()
+            .map(|r| r.feature_tag())
+            .collect();
+        assert!(gpos_tags.contains(&Tag::new(b"kern")));
+        assert!(gpos_tags.contains(&Tag::new(b"mark")));
+        assert!(!gpos_tags.contains(&Tag::new(b"mkmk")));
+        assert!(subset_font.gdef().is_ok());
+    }
+
+    fn tthint_subset(flags: SubsetFlags) -> Maxp {
+        let font = FontRef::new(font_test_data::TTHINT_SUBSET).unwrap();
+        let mut gids = IntSet::empty();
+        gids.insert(GlyphId::new(2));
+        let plan = Plan::new(
+            &gids,
+            &IntSet::empty(),
+            &font,
+            flags,
+            &IntSet::empty(),
+            &IntSet::empty(),
+            &IntSet::empty(),
+            &IntSet::empty(),
+        );
+        let data = subset_font(&font, &plan).unwrap();
+        let maxp = FontRef::new(&data).unwrap().maxp().unwrap();
+        write_fonts::from_obj::ToOwnedTable::to_owned_table(&maxp)
+    }
+
+    #[test]
+    fn recalc_max_size_of_instructions() {
+        let font = FontRef::new(font_test_data::TTHINT_SUBSET).unwrap();
+        let source = font.maxp().unwrap().max_size_of_instructions();
+
+        let maxp = tthint_subset(SubsetFlags::default());
+        assert_eq!(maxp.max_size_of_instructions, source);
+
+        let maxp = tthint_subset(SubsetFlags::SUBSET_FLAGS_RECALC_MAX_SIZE_OF_INSTRUCTIONS);
+        assert_eq!(maxp.max_size_of_instructions, Some(11941));
+
+        let maxp = tthint_subset(
+            SubsetFlags::SUBSET_FLAGS_RECALC_MAX_SIZE_OF_INSTRUCTIONS
+                | SubsetFlags::SUBSET_FLAGS_NO_HINTING,
+        );
+        assert_eq!(maxp.max_size_of_instructions, Some(0));
+    }
 }
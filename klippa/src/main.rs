@@ -6,8 +6,8 @@
 
 use clap::Parser;
 use klippa::{
-    parse_drop_tables, parse_name_ids, parse_name_languages, parse_unicodes, populate_gids,
-    subset_font, Plan, SubsetFlags,
+    parse_drop_tables, parse_layout_features, parse_name_ids, parse_name_languages, parse_unicodes,
+    populate_gids, subset_font, Plan, SubsetFlags, DEFAULT_LAYOUT_FEATURES,
 };
 use write_fonts::read::{collections::IntSet, types::NameId, FontRef};
 
@@ -44,6 +44,10 @@ struct Args {
     #[arg(long)]
     name_languages: Option<String>,
 
+    /// List of OpenType layout feature tags that will be retained
+    #[arg(long)]
+    layout_features: Option<String>,
+
     /// drop hints
     #[arg(long)]
     no_hinting: bool,
@@ -88,6 +92,10 @@ struct Args {
     #[arg(long)]
     optimize: bool,
 
+    /// Recalculate the maxp maxSizeOfInstructions from the retained glyphs
+    #[arg(long)]
+    recalc_max_size_of_instructions: bool,
+
     ///run subsetter N times
     #[arg(short, long)]
     num_iterations: Option<u32>,
@@ -155,6 +163,17 @@ fn main() {
         }
     };
 
+    let layout_features = match &args.layout_features {
+        Some(layout_features_input) => match parse_layout_features(layout_features_input) {
+            Ok(layout_features) => layout_features,
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        },
+        None => DEFAULT_LAYOUT_FEATURES.into_iter().collect(),
+    };
+
     let mut output_bytes = Vec::new();
     for _ in 0..args.num_iterations.unwrap_or(1) {
         let plan = Plan::new(
@@ -165,6 +184,7 @@ fn main() {
             &drop_tables,
             &name_ids,
             &name_languages,
+            &layout_features,
         );
         match subset_font(&font, &plan) {
             Ok(out) => {
@@ -224,5 +244,9 @@ fn parse_subset_flags(args: &Args) -> SubsetFlags {
     if args.optimize {
         flags |= SubsetFlags::SUBSET_FLAGS_OPTIMIZE_IUP_DELTAS;
     }
+
+    if args.recalc_max_size_of_instructions {
+        flags |= SubsetFlags::SUBSET_FLAGS_RECALC_MAX_SIZE_OF_INSTRUCTIONS;
+    }
     flags
 }
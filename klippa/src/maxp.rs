@@ -1,7 +1,11 @@
 //! impl subset() for maxp
 use crate::{serialize::Serializer, Plan, Subset, SubsetError, SubsetFlags};
 use write_fonts::{
-    read::{tables::maxp::Maxp, FontRef, TopLevelTable},
+    from_obj::ToOwnedTable,
+    read::{
+        tables::{glyf::Glyf, head::Head, loca::Loca, maxp::Maxp},
+        FontData, FontRead, FontRef, TopLevelTable,
+    },
     types::Version16Dot16,
     FontBuilder,
 };
@@ -61,3 +65,50 @@ impl Subset for Maxp<'_> {
         Ok(())
     }
 }
+
+/// Recompute maxSizeOfInstructions from the subset glyf table, which may be smaller than in the
+/// source font once glyphs have been dropped.
+///
+/// Only done when requested with [`SubsetFlags::SUBSET_FLAGS_RECALC_MAX_SIZE_OF_INSTRUCTIONS`].
+pub(crate) fn update_max_size_of_instructions(
+    plan: &Plan,
+    builder: &mut FontBuilder,
+) -> Result<(), SubsetError> {
+    // without hinting the field has already been zeroed by Maxp::subset
+    if !plan
+        .subset_flags
+        .contains(SubsetFlags::SUBSET_FLAGS_RECALC_MAX_SIZE_OF_INSTRUCTIONS)
+        || plan
+            .subset_flags
+            .contains(SubsetFlags::SUBSET_FLAGS_NO_HINTING)
+    {
+        return Ok(());
+    }
+    let (Some(maxp), Some(head), Some(loca), Some(glyf)) = (
+        builder.table_data(Maxp::TAG),
+        builder.table_data(Head::TAG),
+        builder.table_data(Loca::TAG),
+        builder.table_data(Glyf::TAG),
+    ) else {
+        return Ok(());
+    };
+    let maxp =
+        Maxp::read(FontData::new(maxp)).map_err(|_| SubsetError::SubsetTableError(Maxp::TAG))?;
+    if maxp.version() != Version16Dot16::VERSION_1_0 {
+        return Ok(());
+    }
+    let head =
+        Head::read(FontData::new(head)).map_err(|_| SubsetError::SubsetTableError(Head::TAG))?;
+    let loca = Loca::read(FontData::new(loca), head.index_to_loc_format() == 1)
+        .map_err(|_| SubsetError::SubsetTableError(Loca::TAG))?;
+    let glyf =
+        Glyf::read(FontData::new(glyf)).map_err(|_| SubsetError::SubsetTableError(Glyf::TAG))?;
+
+    let mut maxp: write_fonts::tables::maxp::Maxp = maxp.to_owned_table();
+    maxp.recalc_max_size_of_instructions(&loca, &glyf)
+        .map_err(|_| SubsetError::SubsetTableError(Glyf::TAG))?;
+    builder
+        .add_table(&maxp)
+        .map_err(|_| SubsetError::SubsetTableError(Maxp::TAG))?;
+    Ok(())
+}
@@ -89,6 +89,13 @@ pub fn parse_drop_tables(input_str: &str) -> Result<IntSet<Tag>, SubsetError> {
     })
 }
 
+//parse input layout_features string, which is a comma/whitespace-separated list of OpenType layout feature tags that will be retained
+pub fn parse_layout_features(input_str: &str) -> Result<IntSet<Tag>, SubsetError> {
+    parse_list(input_str, |raw| {
+        Tag::new_checked(raw.as_bytes()).map_err(|_| SubsetError::InvalidTag(raw.to_owned()))
+    })
+}
+
 //parse input name_IDs string, which is a comma/whitespace-separated list of nameIDs that will be retained
 pub fn parse_name_ids(input_str: &str) -> Result<IntSet<NameId>, SubsetError> {
     parse_list(input_str, |raw| {
@@ -158,6 +165,20 @@ fn test_parse_drop_tables() {
     assert!(output.is_empty());
 }
 
+#[test]
+fn test_parse_layout_features() {
+    let output = parse_layout_features("liga,kern smcp").unwrap();
+    assert_eq!(output.len(), 3);
+    assert!(output.contains(Tag::new(b"liga")));
+    assert!(output.contains(Tag::new(b"kern")));
+    assert!(output.contains(Tag::new(b"smcp")));
+
+    let output = parse_layout_features("*").unwrap();
+    assert!(output.contains(Tag::new(b"ss01")));
+
+    assert!(parse_layout_features("toolong").is_err());
+}
+
 #[test]
 fn test_parse_name_ids() {
     let input = "7,8,9";
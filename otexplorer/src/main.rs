@@ -1,12 +1,16 @@
 //! Print the contents of font tables.
 //!
 //! This accepts command line arguments similar to what is present in ttx,
-//! although it does not produce xml output.
+//! although it does not produce xml output. Passing `--json` produces a
+//! machine-readable dump instead.
 
 use std::{collections::HashSet, str::FromStr};
 
 use font_types::Tag;
-use read_fonts::{traversal::SomeTable, FileRef, FontRef, ReadError, TableProvider, TopLevelTable};
+use read_fonts::{
+    traversal::{some_table, SomeTable},
+    FileRef, FontRef,
+};
 
 mod print;
 mod query;
@@ -33,7 +37,11 @@ fn main() -> Result<(), Error> {
     }
 
     let filter = TableFilter::from_args(&args)?;
-    print_tables(&font, &filter);
+    if args.json {
+        print_tables_json(&font, &filter);
+    } else {
+        print_tables(&font, &filter);
+    }
     Ok(())
 }
 
@@ -75,6 +83,30 @@ fn print_tables(font: &FontRef, filter: &TableFilter) {
     }
 }
 
+/// Print the selected tables as a JSON object keyed by tag.
+fn print_tables_json(font: &FontRef, filter: &TableFilter) {
+    let mut json = String::from("{");
+    for (i, tag) in font
+        .table_directory
+        .table_records()
+        .iter()
+        .map(|rec| rec.tag())
+        .filter(|tag| filter.should_print(*tag))
+        .enumerate()
+    {
+        if i != 0 {
+            json.push(',');
+        }
+        json.push_str(&format!("\n{:?}: ", tag.to_string()));
+        match some_table(font, tag) {
+            Ok(table) => json.push_str(&table.to_json()),
+            Err(err) => json.push_str(&format!("{{\"$error\": {:?}}}", err.to_string())),
+        }
+    }
+    json.push_str("\n}");
+    println!("{json}");
+}
+
 fn get_offset_width(font: &FontRef) -> usize {
     // pick how much padding we use for offsets based on the max offset in directory
     let max_off = font
@@ -96,38 +128,8 @@ fn hex_width(val: u32) -> usize {
     }
 }
 
-/// Given a font and a tag, return the appropriate table as a [`dyn SomeTable`][SomeTable].
-fn get_some_table<'a>(
-    font: &FontRef<'a>,
-    tag: Tag,
-) -> Result<Box<dyn SomeTable<'a> + 'a>, ReadError> {
-    use read_fonts::tables;
-    match tag {
-        tables::gpos::Gpos::TAG => font.gpos().map(|x| Box::new(x) as _),
-        tables::gsub::Gsub::TAG => font.gsub().map(|x| Box::new(x) as _),
-        tables::cmap::Cmap::TAG => font.cmap().map(|x| Box::new(x) as _),
-        tables::fvar::Fvar::TAG => font.fvar().map(|x| Box::new(x) as _),
-        tables::avar::Avar::TAG => font.avar().map(|x| Box::new(x) as _),
-        tables::gdef::Gdef::TAG => font.gdef().map(|x| Box::new(x) as _),
-        tables::glyf::Glyf::TAG => font.glyf().map(|x| Box::new(x) as _),
-        tables::head::Head::TAG => font.head().map(|x| Box::new(x) as _),
-        tables::hhea::Hhea::TAG => font.hhea().map(|x| Box::new(x) as _),
-        tables::hmtx::Hmtx::TAG => font.hmtx().map(|x| Box::new(x) as _),
-        tables::loca::Loca::TAG => font.loca(None).map(|x| Box::new(x) as _),
-        tables::maxp::Maxp::TAG => font.maxp().map(|x| Box::new(x) as _),
-        tables::name::Name::TAG => font.name().map(|x| Box::new(x) as _),
-        tables::post::Post::TAG => font.post().map(|x| Box::new(x) as _),
-        tables::colr::Colr::TAG => font.colr().map(|x| Box::new(x) as _),
-        tables::stat::Stat::TAG => font.stat().map(|x| Box::new(x) as _),
-        tables::vhea::Vhea::TAG => font.vhea().map(|x| Box::new(x) as _),
-        tables::vmtx::Vmtx::TAG => font.vmtx().map(|x| Box::new(x) as _),
-        tables::svg::Svg::TAG => font.svg().map(|x| Box::new(x) as _),
-        _ => Err(ReadError::TableIsMissing(tag)),
-    }
-}
-
 fn print_table(font: &FontRef, tag: Tag) {
-    match get_some_table(font, tag) {
+    match some_table(font, tag) {
         Ok(table) => fancy_print_table(&table).unwrap(),
         Err(err) => println!("{tag}: Error '{err}'"),
     }
@@ -233,6 +235,8 @@ mod flags {
             optional -q, --query query: Query
             optional -t, --tables include: String
             optional -x, --exclude exclude: String
+            /// Print the tables as JSON
+            optional --json
         }
     }
 }
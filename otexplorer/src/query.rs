@@ -21,7 +21,7 @@ pub enum QueryElement {
 }
 
 pub fn print_query(font: &FontRef, query: &Query) -> Result<(), String> {
-    let table = match super::get_some_table(font, query.tag) {
+    let table = match read_fonts::traversal::some_table(font, query.tag) {
         Ok(table) => table,
         Err(err) => return Err(err.to_string()),
     };
@@ -134,6 +134,30 @@ pub fn iteration_benchmark(c: &mut Criterion) {
     }
 }
 
+pub fn set_algebra_benchmark(c: &mut Criterion) {
+    let inputs = set_parameters();
+
+    for input in inputs {
+        let a = random_set(input.set_size, input.max_value());
+        let b = random_set(input.set_size, input.max_value());
+        c.bench_with_input(
+            BenchmarkId::new("BM_SetIntersect", &input),
+            &(&a, &b),
+            |bench, (a, b)| bench.iter(|| black_box(*a & *b)),
+        );
+        c.bench_with_input(
+            BenchmarkId::new("BM_SetSubtract", &input),
+            &(&a, &b),
+            |bench, (a, b)| bench.iter(|| black_box(*a - *b)),
+        );
+        c.bench_with_input(
+            BenchmarkId::new("BM_SetIntersectsSet", &input),
+            &(&a, &b),
+            |bench, (a, b)| bench.iter(|| black_box(a.intersects_set(b))),
+        );
+    }
+}
+
 criterion_group!(
     benches,
     insert_benchmark,
@@ -141,5 +165,6 @@ criterion_group!(
     lookup_random_benchmark,
     lookup_ordered_benchmark,
     iteration_benchmark,
+    set_algebra_benchmark,
 );
 criterion_main!(benches);
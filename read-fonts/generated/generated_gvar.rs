@@ -171,7 +171,7 @@ impl<'a> SomeTable<'a> for Gvar<'a> {
             )),
             7usize => Some(Field::new(
                 "glyph_variation_data_offsets",
-                traversal::FieldType::Unknown,
+                self.traverse_glyph_variation_data(),
             )),
             _ => None,
         }
@@ -9,7 +9,6 @@ use crate::codegen_prelude::*;
 #[derive(Debug, Clone, Copy)]
 #[doc(hidden)]
 pub struct HdmxMarker {
-    num_glyphs: u16,
     records_byte_len: usize,
 }
 
@@ -40,36 +39,19 @@ impl TopLevelTable for Hdmx<'_> {
     const TAG: Tag = Tag::new(b"hdmx");
 }
 
-impl ReadArgs for Hdmx<'_> {
-    type Args = u16;
-}
-
-impl<'a> FontReadWithArgs<'a> for Hdmx<'a> {
-    fn read_with_args(data: FontData<'a>, args: &u16) -> Result<Self, ReadError> {
-        let num_glyphs = *args;
+impl<'a> FontRead<'a> for Hdmx<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
         let mut cursor = data.cursor();
         cursor.advance::<u16>();
         let num_records: u16 = cursor.read()?;
-        cursor.advance::<u32>();
+        let size_device_record: u32 = cursor.read()?;
         let records_byte_len = (num_records as usize)
-            .checked_mul(<DeviceRecord as ComputeSize>::compute_size(&num_glyphs)?)
+            .checked_mul(<DeviceRecord as ComputeSize>::compute_size(
+                &size_device_record,
+            )?)
             .ok_or(ReadError::OutOfBounds)?;
         cursor.advance_by(records_byte_len);
-        cursor.finish(HdmxMarker {
-            num_glyphs,
-            records_byte_len,
-        })
-    }
-}
-
-impl<'a> Hdmx<'a> {
-    /// A constructor that requires additional arguments.
-    ///
-    /// This type requires some external state in order to be
-    /// parsed.
-    pub fn read(data: FontData<'a>, num_glyphs: u16) -> Result<Self, ReadError> {
-        let args = num_glyphs;
-        Self::read_with_args(data, &args)
+        cursor.finish(HdmxMarker { records_byte_len })
     }
 }
 
@@ -99,11 +81,9 @@ impl<'a> Hdmx<'a> {
     /// Array of device records.
     pub fn records(&self) -> ComputedArray<'a, DeviceRecord<'a>> {
         let range = self.shape.records_byte_range();
-        self.data.read_with_args(range, &self.num_glyphs()).unwrap()
-    }
-
-    pub(crate) fn num_glyphs(&self) -> u16 {
-        self.shape.num_glyphs
+        self.data
+            .read_with_args(range, &self.size_device_record())
+            .unwrap()
     }
 }
 
@@ -144,7 +124,11 @@ pub struct DeviceRecord<'a> {
     pub pixel_size: u8,
     /// Maximum width.
     pub max_width: u8,
-    /// Array of glyphs (numgGlyphs is from the 'maxp' table).
+    /// Array of widths, indexed by glyph id (numGlyphs is from the
+    /// 'maxp' table).
+    ///
+    /// Records are padded to a multiple of four bytes and this array
+    /// includes any trailing padding.
     pub widths: &'a [u8],
 }
 
@@ -159,20 +143,24 @@ impl<'a> DeviceRecord<'a> {
         self.max_width
     }
 
-    /// Array of glyphs (numgGlyphs is from the 'maxp' table).
+    /// Array of widths, indexed by glyph id (numGlyphs is from the
+    /// 'maxp' table).
+    ///
+    /// Records are padded to a multiple of four bytes and this array
+    /// includes any trailing padding.
     pub fn widths(&self) -> &'a [u8] {
         self.widths
     }
 }
 
 impl ReadArgs for DeviceRecord<'_> {
-    type Args = u16;
+    type Args = u32;
 }
 
 impl ComputeSize for DeviceRecord<'_> {
     #[allow(clippy::needless_question_mark)]
-    fn compute_size(args: &u16) -> Result<usize, ReadError> {
-        let num_glyphs = *args;
+    fn compute_size(args: &u32) -> Result<usize, ReadError> {
+        let size_device_record = *args;
         let mut result = 0usize;
         result = result
             .checked_add(u8::RAW_BYTE_LEN)
@@ -182,7 +170,7 @@ impl ComputeSize for DeviceRecord<'_> {
             .ok_or(ReadError::OutOfBounds)?;
         result = result
             .checked_add(
-                (num_glyphs as usize)
+                (transforms::subtract(size_device_record, 2_usize))
                     .checked_mul(u8::RAW_BYTE_LEN)
                     .ok_or(ReadError::OutOfBounds)?,
             )
@@ -192,13 +180,13 @@ impl ComputeSize for DeviceRecord<'_> {
 }
 
 impl<'a> FontReadWithArgs<'a> for DeviceRecord<'a> {
-    fn read_with_args(data: FontData<'a>, args: &u16) -> Result<Self, ReadError> {
+    fn read_with_args(data: FontData<'a>, args: &u32) -> Result<Self, ReadError> {
         let mut cursor = data.cursor();
-        let num_glyphs = *args;
+        let size_device_record = *args;
         Ok(Self {
             pixel_size: cursor.read()?,
             max_width: cursor.read()?,
-            widths: cursor.read_array(num_glyphs as usize)?,
+            widths: cursor.read_array(transforms::subtract(size_device_record, 2_usize))?,
         })
     }
 }
@@ -209,8 +197,8 @@ impl<'a> DeviceRecord<'a> {
     ///
     /// This type requires some external state in order to be
     /// parsed.
-    pub fn read(data: FontData<'a>, num_glyphs: u16) -> Result<Self, ReadError> {
-        let args = num_glyphs;
+    pub fn read(data: FontData<'a>, size_device_record: u32) -> Result<Self, ReadError> {
+        let args = size_device_record;
         Self::read_with_args(data, &args)
     }
 }
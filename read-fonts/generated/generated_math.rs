@@ -0,0 +1,1973 @@
+// THIS FILE IS AUTOGENERATED.
+// Any changes to this file will be overwritten.
+// For more information about how codegen works, see font-codegen/README.md
+
+#[allow(unused_imports)]
+use crate::codegen_prelude::*;
+
+/// The [Mathematical Typesetting](https://learn.microsoft.com/en-us/typography/opentype/spec/math) table
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct MathMarker {}
+
+impl MathMarker {
+    pub fn major_version_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn minor_version_byte_range(&self) -> Range<usize> {
+        let start = self.major_version_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn math_constants_offset_byte_range(&self) -> Range<usize> {
+        let start = self.minor_version_byte_range().end;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+
+    pub fn math_glyph_info_offset_byte_range(&self) -> Range<usize> {
+        let start = self.math_constants_offset_byte_range().end;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+
+    pub fn math_variants_offset_byte_range(&self) -> Range<usize> {
+        let start = self.math_glyph_info_offset_byte_range().end;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+}
+
+impl TopLevelTable for Math<'_> {
+    /// `MATH`
+    const TAG: Tag = Tag::new(b"MATH");
+}
+
+impl<'a> FontRead<'a> for Math<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        cursor.advance::<u16>();
+        cursor.advance::<u16>();
+        cursor.advance::<Offset16>();
+        cursor.advance::<Offset16>();
+        cursor.advance::<Offset16>();
+        cursor.finish(MathMarker {})
+    }
+}
+
+/// The [Mathematical Typesetting](https://learn.microsoft.com/en-us/typography/opentype/spec/math) table
+pub type Math<'a> = TableRef<'a, MathMarker>;
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> Math<'a> {
+    /// Major version of the MATH table, = 1.
+    pub fn major_version(&self) -> u16 {
+        let range = self.shape.major_version_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Minor version of the MATH table, = 0.
+    pub fn minor_version(&self) -> u16 {
+        let range = self.shape.minor_version_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Offset to MathConstants table, from the beginning of MATH table.
+    pub fn math_constants_offset(&self) -> Offset16 {
+        let range = self.shape.math_constants_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`math_constants_offset`][Self::math_constants_offset].
+    pub fn math_constants(&self) -> Result<MathConstants<'a>, ReadError> {
+        let data = self.data;
+        self.math_constants_offset().resolve(data)
+    }
+
+    /// Offset to MathGlyphInfo table, from the beginning of MATH table.
+    pub fn math_glyph_info_offset(&self) -> Offset16 {
+        let range = self.shape.math_glyph_info_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`math_glyph_info_offset`][Self::math_glyph_info_offset].
+    pub fn math_glyph_info(&self) -> Result<MathGlyphInfo<'a>, ReadError> {
+        let data = self.data;
+        self.math_glyph_info_offset().resolve(data)
+    }
+
+    /// Offset to MathVariants table, from the beginning of MATH table.
+    pub fn math_variants_offset(&self) -> Offset16 {
+        let range = self.shape.math_variants_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`math_variants_offset`][Self::math_variants_offset].
+    pub fn math_variants(&self) -> Result<MathVariants<'a>, ReadError> {
+        let data = self.data;
+        self.math_variants_offset().resolve(data)
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeTable<'a> for Math<'a> {
+    fn type_name(&self) -> &str {
+        "Math"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new("major_version", self.major_version())),
+            1usize => Some(Field::new("minor_version", self.minor_version())),
+            2usize => Some(Field::new(
+                "math_constants_offset",
+                FieldType::offset(self.math_constants_offset(), self.math_constants()),
+            )),
+            3usize => Some(Field::new(
+                "math_glyph_info_offset",
+                FieldType::offset(self.math_glyph_info_offset(), self.math_glyph_info()),
+            )),
+            4usize => Some(Field::new(
+                "math_variants_offset",
+                FieldType::offset(self.math_variants_offset(), self.math_variants()),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+#[allow(clippy::needless_lifetimes)]
+impl<'a> std::fmt::Debug for Math<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// [MathValueRecord](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathvaluerecord)
+#[derive(Clone, Debug, Copy, bytemuck :: AnyBitPattern)]
+#[repr(C)]
+#[repr(packed)]
+pub struct MathValueRecord {
+    /// The X or Y value in design units.
+    pub value: BigEndian<FWord>,
+    /// Offset to the device table, from the beginning of parent table.
+    /// May be NULL. Suggested format for device table is 1.
+    pub device_offset: BigEndian<Nullable<Offset16>>,
+}
+
+impl MathValueRecord {
+    /// The X or Y value in design units.
+    pub fn value(&self) -> FWord {
+        self.value.get()
+    }
+
+    /// Offset to the device table, from the beginning of parent table.
+    /// May be NULL. Suggested format for device table is 1.
+    pub fn device_offset(&self) -> Nullable<Offset16> {
+        self.device_offset.get()
+    }
+
+    /// Offset to the device table, from the beginning of parent table.
+    /// May be NULL. Suggested format for device table is 1.
+    ///
+    /// The `data` argument should be retrieved from the parent table
+    /// By calling its `offset_data` method.
+    pub fn device<'a>(
+        &self,
+        data: FontData<'a>,
+    ) -> Option<Result<DeviceOrVariationIndex<'a>, ReadError>> {
+        self.device_offset().resolve(data)
+    }
+}
+
+impl FixedSize for MathValueRecord {
+    const RAW_BYTE_LEN: usize = FWord::RAW_BYTE_LEN + Offset16::RAW_BYTE_LEN;
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeRecord<'a> for MathValueRecord {
+    fn traverse(self, data: FontData<'a>) -> RecordResolver<'a> {
+        RecordResolver {
+            name: "MathValueRecord",
+            get_field: Box::new(move |idx, _data| match idx {
+                0usize => Some(Field::new("value", self.value())),
+                1usize => Some(Field::new(
+                    "device_offset",
+                    FieldType::offset(self.device_offset(), self.device(_data)),
+                )),
+                _ => None,
+            }),
+            data,
+        }
+    }
+}
+
+/// [MathConstants](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathconstants-table) table
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct MathConstantsMarker {
+    math_value_records_byte_len: usize,
+}
+
+impl MathConstantsMarker {
+    pub fn script_percent_scale_down_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + i16::RAW_BYTE_LEN
+    }
+
+    pub fn script_script_percent_scale_down_byte_range(&self) -> Range<usize> {
+        let start = self.script_percent_scale_down_byte_range().end;
+        start..start + i16::RAW_BYTE_LEN
+    }
+
+    pub fn delimited_sub_formula_min_height_byte_range(&self) -> Range<usize> {
+        let start = self.script_script_percent_scale_down_byte_range().end;
+        start..start + UfWord::RAW_BYTE_LEN
+    }
+
+    pub fn display_operator_min_height_byte_range(&self) -> Range<usize> {
+        let start = self.delimited_sub_formula_min_height_byte_range().end;
+        start..start + UfWord::RAW_BYTE_LEN
+    }
+
+    pub fn math_value_records_byte_range(&self) -> Range<usize> {
+        let start = self.display_operator_min_height_byte_range().end;
+        start..start + self.math_value_records_byte_len
+    }
+
+    pub fn radical_degree_bottom_raise_percent_byte_range(&self) -> Range<usize> {
+        let start = self.math_value_records_byte_range().end;
+        start..start + i16::RAW_BYTE_LEN
+    }
+}
+
+impl<'a> FontRead<'a> for MathConstants<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        cursor.advance::<i16>();
+        cursor.advance::<i16>();
+        cursor.advance::<UfWord>();
+        cursor.advance::<UfWord>();
+        let math_value_records_byte_len = (51_usize)
+            .checked_mul(MathValueRecord::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(math_value_records_byte_len);
+        cursor.advance::<i16>();
+        cursor.finish(MathConstantsMarker {
+            math_value_records_byte_len,
+        })
+    }
+}
+
+/// [MathConstants](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathconstants-table) table
+pub type MathConstants<'a> = TableRef<'a, MathConstantsMarker>;
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> MathConstants<'a> {
+    /// Percentage of scaling down for level 1 superscripts and
+    /// subscripts. Suggested value: 80%.
+    pub fn script_percent_scale_down(&self) -> i16 {
+        let range = self.shape.script_percent_scale_down_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Percentage of scaling down for level 2 (scriptScript)
+    /// superscripts and subscripts. Suggested value: 60%.
+    pub fn script_script_percent_scale_down(&self) -> i16 {
+        let range = self.shape.script_script_percent_scale_down_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Minimum height required for a delimited expression (contained
+    /// within parentheses, etc.) to be treated as a sub-formula.
+    pub fn delimited_sub_formula_min_height(&self) -> UfWord {
+        let range = self.shape.delimited_sub_formula_min_height_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Minimum height of n-ary operators (such as integral and
+    /// summation) for formulas in display mode (that is, appearing as
+    /// standalone page elements, not embedded inline within text).
+    pub fn display_operator_min_height(&self) -> UfWord {
+        let range = self.shape.display_operator_min_height_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Values for the constants from mathLeading through
+    /// radicalKernAfterDegree, in the order given in the specification.
+    pub fn math_value_records(&self) -> &'a [MathValueRecord] {
+        let range = self.shape.math_value_records_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+
+    /// Height of the bottom of the radical degree, if such is present,
+    /// in proportion to the height (ascender + descender) of the
+    /// radical sign.
+    pub fn radical_degree_bottom_raise_percent(&self) -> i16 {
+        let range = self.shape.radical_degree_bottom_raise_percent_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeTable<'a> for MathConstants<'a> {
+    fn type_name(&self) -> &str {
+        "MathConstants"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new(
+                "script_percent_scale_down",
+                self.script_percent_scale_down(),
+            )),
+            1usize => Some(Field::new(
+                "script_script_percent_scale_down",
+                self.script_script_percent_scale_down(),
+            )),
+            2usize => Some(Field::new(
+                "delimited_sub_formula_min_height",
+                self.delimited_sub_formula_min_height(),
+            )),
+            3usize => Some(Field::new(
+                "display_operator_min_height",
+                self.display_operator_min_height(),
+            )),
+            4usize => Some(Field::new(
+                "math_value_records",
+                traversal::FieldType::array_of_records(
+                    stringify!(MathValueRecord),
+                    self.math_value_records(),
+                    self.offset_data(),
+                ),
+            )),
+            5usize => Some(Field::new(
+                "radical_degree_bottom_raise_percent",
+                self.radical_degree_bottom_raise_percent(),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+#[allow(clippy::needless_lifetimes)]
+impl<'a> std::fmt::Debug for MathConstants<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// [MathGlyphInfo](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathglyphinfo-table) table
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct MathGlyphInfoMarker {}
+
+impl MathGlyphInfoMarker {
+    pub fn math_italics_correction_info_offset_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+
+    pub fn math_top_accent_attachment_offset_byte_range(&self) -> Range<usize> {
+        let start = self.math_italics_correction_info_offset_byte_range().end;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+
+    pub fn extended_shape_coverage_offset_byte_range(&self) -> Range<usize> {
+        let start = self.math_top_accent_attachment_offset_byte_range().end;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+
+    pub fn math_kern_info_offset_byte_range(&self) -> Range<usize> {
+        let start = self.extended_shape_coverage_offset_byte_range().end;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+}
+
+impl<'a> FontRead<'a> for MathGlyphInfo<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        cursor.advance::<Offset16>();
+        cursor.advance::<Offset16>();
+        cursor.advance::<Offset16>();
+        cursor.advance::<Offset16>();
+        cursor.finish(MathGlyphInfoMarker {})
+    }
+}
+
+/// [MathGlyphInfo](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathglyphinfo-table) table
+pub type MathGlyphInfo<'a> = TableRef<'a, MathGlyphInfoMarker>;
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> MathGlyphInfo<'a> {
+    /// Offset to MathItalicsCorrectionInfo table, from the beginning of
+    /// the MathGlyphInfo table.
+    pub fn math_italics_correction_info_offset(&self) -> Nullable<Offset16> {
+        let range = self.shape.math_italics_correction_info_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`math_italics_correction_info_offset`][Self::math_italics_correction_info_offset].
+    pub fn math_italics_correction_info(
+        &self,
+    ) -> Option<Result<MathItalicsCorrectionInfo<'a>, ReadError>> {
+        let data = self.data;
+        self.math_italics_correction_info_offset().resolve(data)
+    }
+
+    /// Offset to MathTopAccentAttachment table, from the beginning of
+    /// the MathGlyphInfo table.
+    pub fn math_top_accent_attachment_offset(&self) -> Nullable<Offset16> {
+        let range = self.shape.math_top_accent_attachment_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`math_top_accent_attachment_offset`][Self::math_top_accent_attachment_offset].
+    pub fn math_top_accent_attachment(
+        &self,
+    ) -> Option<Result<MathTopAccentAttachment<'a>, ReadError>> {
+        let data = self.data;
+        self.math_top_accent_attachment_offset().resolve(data)
+    }
+
+    /// Offset to ExtendedShapes coverage table, from the beginning of
+    /// the MathGlyphInfo table. When the glyph to the left or right of
+    /// a box is an extended shape variant, the (ink) box should be
+    /// used for vertical positioning purposes, not the default
+    /// position defined by values in MathConstants table. May be NULL.
+    pub fn extended_shape_coverage_offset(&self) -> Nullable<Offset16> {
+        let range = self.shape.extended_shape_coverage_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`extended_shape_coverage_offset`][Self::extended_shape_coverage_offset].
+    pub fn extended_shape_coverage(&self) -> Option<Result<CoverageTable<'a>, ReadError>> {
+        let data = self.data;
+        self.extended_shape_coverage_offset().resolve(data)
+    }
+
+    /// Offset to MathKernInfo table, from the beginning of the
+    /// MathGlyphInfo table.
+    pub fn math_kern_info_offset(&self) -> Nullable<Offset16> {
+        let range = self.shape.math_kern_info_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`math_kern_info_offset`][Self::math_kern_info_offset].
+    pub fn math_kern_info(&self) -> Option<Result<MathKernInfo<'a>, ReadError>> {
+        let data = self.data;
+        self.math_kern_info_offset().resolve(data)
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeTable<'a> for MathGlyphInfo<'a> {
+    fn type_name(&self) -> &str {
+        "MathGlyphInfo"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new(
+                "math_italics_correction_info_offset",
+                FieldType::offset(
+                    self.math_italics_correction_info_offset(),
+                    self.math_italics_correction_info(),
+                ),
+            )),
+            1usize => Some(Field::new(
+                "math_top_accent_attachment_offset",
+                FieldType::offset(
+                    self.math_top_accent_attachment_offset(),
+                    self.math_top_accent_attachment(),
+                ),
+            )),
+            2usize => Some(Field::new(
+                "extended_shape_coverage_offset",
+                FieldType::offset(
+                    self.extended_shape_coverage_offset(),
+                    self.extended_shape_coverage(),
+                ),
+            )),
+            3usize => Some(Field::new(
+                "math_kern_info_offset",
+                FieldType::offset(self.math_kern_info_offset(), self.math_kern_info()),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+#[allow(clippy::needless_lifetimes)]
+impl<'a> std::fmt::Debug for MathGlyphInfo<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// [MathItalicsCorrectionInfo](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathitalicscorrectioninfo-table) table
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct MathItalicsCorrectionInfoMarker {
+    italics_correction_byte_len: usize,
+}
+
+impl MathItalicsCorrectionInfoMarker {
+    pub fn italics_correction_coverage_offset_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+
+    pub fn italics_correction_count_byte_range(&self) -> Range<usize> {
+        let start = self.italics_correction_coverage_offset_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn italics_correction_byte_range(&self) -> Range<usize> {
+        let start = self.italics_correction_count_byte_range().end;
+        start..start + self.italics_correction_byte_len
+    }
+}
+
+impl<'a> FontRead<'a> for MathItalicsCorrectionInfo<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        cursor.advance::<Offset16>();
+        let italics_correction_count: u16 = cursor.read()?;
+        let italics_correction_byte_len = (italics_correction_count as usize)
+            .checked_mul(MathValueRecord::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(italics_correction_byte_len);
+        cursor.finish(MathItalicsCorrectionInfoMarker {
+            italics_correction_byte_len,
+        })
+    }
+}
+
+/// [MathItalicsCorrectionInfo](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathitalicscorrectioninfo-table) table
+pub type MathItalicsCorrectionInfo<'a> = TableRef<'a, MathItalicsCorrectionInfoMarker>;
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> MathItalicsCorrectionInfo<'a> {
+    /// Offset to Coverage table, from the beginning of
+    /// MathItalicsCorrectionInfo table.
+    pub fn italics_correction_coverage_offset(&self) -> Offset16 {
+        let range = self.shape.italics_correction_coverage_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`italics_correction_coverage_offset`][Self::italics_correction_coverage_offset].
+    pub fn italics_correction_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        let data = self.data;
+        self.italics_correction_coverage_offset().resolve(data)
+    }
+
+    /// Number of italics correction values. Should coincide with the
+    /// number of covered glyphs.
+    pub fn italics_correction_count(&self) -> u16 {
+        let range = self.shape.italics_correction_count_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Array of MathValueRecords defining italics correction values
+    /// for each covered glyph.
+    pub fn italics_correction(&self) -> &'a [MathValueRecord] {
+        let range = self.shape.italics_correction_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeTable<'a> for MathItalicsCorrectionInfo<'a> {
+    fn type_name(&self) -> &str {
+        "MathItalicsCorrectionInfo"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new(
+                "italics_correction_coverage_offset",
+                FieldType::offset(
+                    self.italics_correction_coverage_offset(),
+                    self.italics_correction_coverage(),
+                ),
+            )),
+            1usize => Some(Field::new(
+                "italics_correction_count",
+                self.italics_correction_count(),
+            )),
+            2usize => Some(Field::new(
+                "italics_correction",
+                traversal::FieldType::array_of_records(
+                    stringify!(MathValueRecord),
+                    self.italics_correction(),
+                    self.offset_data(),
+                ),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+#[allow(clippy::needless_lifetimes)]
+impl<'a> std::fmt::Debug for MathItalicsCorrectionInfo<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// [MathTopAccentAttachment](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathtopaccentattachment-table) table
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct MathTopAccentAttachmentMarker {
+    top_accent_attachment_byte_len: usize,
+}
+
+impl MathTopAccentAttachmentMarker {
+    pub fn top_accent_coverage_offset_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+
+    pub fn top_accent_attachment_count_byte_range(&self) -> Range<usize> {
+        let start = self.top_accent_coverage_offset_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn top_accent_attachment_byte_range(&self) -> Range<usize> {
+        let start = self.top_accent_attachment_count_byte_range().end;
+        start..start + self.top_accent_attachment_byte_len
+    }
+}
+
+impl<'a> FontRead<'a> for MathTopAccentAttachment<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        cursor.advance::<Offset16>();
+        let top_accent_attachment_count: u16 = cursor.read()?;
+        let top_accent_attachment_byte_len = (top_accent_attachment_count as usize)
+            .checked_mul(MathValueRecord::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(top_accent_attachment_byte_len);
+        cursor.finish(MathTopAccentAttachmentMarker {
+            top_accent_attachment_byte_len,
+        })
+    }
+}
+
+/// [MathTopAccentAttachment](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathtopaccentattachment-table) table
+pub type MathTopAccentAttachment<'a> = TableRef<'a, MathTopAccentAttachmentMarker>;
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> MathTopAccentAttachment<'a> {
+    /// Offset to Coverage table, from the beginning of
+    /// MathTopAccentAttachment table.
+    pub fn top_accent_coverage_offset(&self) -> Offset16 {
+        let range = self.shape.top_accent_coverage_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`top_accent_coverage_offset`][Self::top_accent_coverage_offset].
+    pub fn top_accent_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        let data = self.data;
+        self.top_accent_coverage_offset().resolve(data)
+    }
+
+    /// Number of top accent attachment point values. Must be the same
+    /// as the number of glyph IDs referenced in the Coverage table.
+    pub fn top_accent_attachment_count(&self) -> u16 {
+        let range = self.shape.top_accent_attachment_count_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Array of MathValueRecords defining top accent attachment points
+    /// for each covered glyph.
+    pub fn top_accent_attachment(&self) -> &'a [MathValueRecord] {
+        let range = self.shape.top_accent_attachment_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeTable<'a> for MathTopAccentAttachment<'a> {
+    fn type_name(&self) -> &str {
+        "MathTopAccentAttachment"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new(
+                "top_accent_coverage_offset",
+                FieldType::offset(
+                    self.top_accent_coverage_offset(),
+                    self.top_accent_coverage(),
+                ),
+            )),
+            1usize => Some(Field::new(
+                "top_accent_attachment_count",
+                self.top_accent_attachment_count(),
+            )),
+            2usize => Some(Field::new(
+                "top_accent_attachment",
+                traversal::FieldType::array_of_records(
+                    stringify!(MathValueRecord),
+                    self.top_accent_attachment(),
+                    self.offset_data(),
+                ),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+#[allow(clippy::needless_lifetimes)]
+impl<'a> std::fmt::Debug for MathTopAccentAttachment<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// [MathKernInfo](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathkerninfo-table) table
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct MathKernInfoMarker {
+    math_kern_info_records_byte_len: usize,
+}
+
+impl MathKernInfoMarker {
+    pub fn math_kern_coverage_offset_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+
+    pub fn math_kern_count_byte_range(&self) -> Range<usize> {
+        let start = self.math_kern_coverage_offset_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn math_kern_info_records_byte_range(&self) -> Range<usize> {
+        let start = self.math_kern_count_byte_range().end;
+        start..start + self.math_kern_info_records_byte_len
+    }
+}
+
+impl<'a> FontRead<'a> for MathKernInfo<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        cursor.advance::<Offset16>();
+        let math_kern_count: u16 = cursor.read()?;
+        let math_kern_info_records_byte_len = (math_kern_count as usize)
+            .checked_mul(MathKernInfoRecord::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(math_kern_info_records_byte_len);
+        cursor.finish(MathKernInfoMarker {
+            math_kern_info_records_byte_len,
+        })
+    }
+}
+
+/// [MathKernInfo](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathkerninfo-table) table
+pub type MathKernInfo<'a> = TableRef<'a, MathKernInfoMarker>;
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> MathKernInfo<'a> {
+    /// Offset to Coverage table, from the beginning of the
+    /// MathKernInfo table.
+    pub fn math_kern_coverage_offset(&self) -> Offset16 {
+        let range = self.shape.math_kern_coverage_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`math_kern_coverage_offset`][Self::math_kern_coverage_offset].
+    pub fn math_kern_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        let data = self.data;
+        self.math_kern_coverage_offset().resolve(data)
+    }
+
+    /// Number of MathKernInfoRecords. Must be the same as the number
+    /// of glyph IDs referenced in the Coverage table.
+    pub fn math_kern_count(&self) -> u16 {
+        let range = self.shape.math_kern_count_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Array of MathKernInfoRecords, one for each covered glyph.
+    pub fn math_kern_info_records(&self) -> &'a [MathKernInfoRecord] {
+        let range = self.shape.math_kern_info_records_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeTable<'a> for MathKernInfo<'a> {
+    fn type_name(&self) -> &str {
+        "MathKernInfo"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new(
+                "math_kern_coverage_offset",
+                FieldType::offset(self.math_kern_coverage_offset(), self.math_kern_coverage()),
+            )),
+            1usize => Some(Field::new("math_kern_count", self.math_kern_count())),
+            2usize => Some(Field::new(
+                "math_kern_info_records",
+                traversal::FieldType::array_of_records(
+                    stringify!(MathKernInfoRecord),
+                    self.math_kern_info_records(),
+                    self.offset_data(),
+                ),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+#[allow(clippy::needless_lifetimes)]
+impl<'a> std::fmt::Debug for MathKernInfo<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// [MathKernInfoRecord](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathkerninforecord)
+#[derive(Clone, Debug, Copy, bytemuck :: AnyBitPattern)]
+#[repr(C)]
+#[repr(packed)]
+pub struct MathKernInfoRecord {
+    /// Offset to MathKern table for top right corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    pub top_right_math_kern_offset: BigEndian<Nullable<Offset16>>,
+    /// Offset to MathKern table for the top left corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    pub top_left_math_kern_offset: BigEndian<Nullable<Offset16>>,
+    /// Offset to MathKern table for bottom right corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    pub bottom_right_math_kern_offset: BigEndian<Nullable<Offset16>>,
+    /// Offset to MathKern table for bottom left corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    pub bottom_left_math_kern_offset: BigEndian<Nullable<Offset16>>,
+}
+
+impl MathKernInfoRecord {
+    /// Offset to MathKern table for top right corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    pub fn top_right_math_kern_offset(&self) -> Nullable<Offset16> {
+        self.top_right_math_kern_offset.get()
+    }
+
+    /// Offset to MathKern table for top right corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    ///
+    /// The `data` argument should be retrieved from the parent table
+    /// By calling its `offset_data` method.
+    pub fn top_right_math_kern<'a>(
+        &self,
+        data: FontData<'a>,
+    ) -> Option<Result<MathKern<'a>, ReadError>> {
+        self.top_right_math_kern_offset().resolve(data)
+    }
+
+    /// Offset to MathKern table for the top left corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    pub fn top_left_math_kern_offset(&self) -> Nullable<Offset16> {
+        self.top_left_math_kern_offset.get()
+    }
+
+    /// Offset to MathKern table for the top left corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    ///
+    /// The `data` argument should be retrieved from the parent table
+    /// By calling its `offset_data` method.
+    pub fn top_left_math_kern<'a>(
+        &self,
+        data: FontData<'a>,
+    ) -> Option<Result<MathKern<'a>, ReadError>> {
+        self.top_left_math_kern_offset().resolve(data)
+    }
+
+    /// Offset to MathKern table for bottom right corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    pub fn bottom_right_math_kern_offset(&self) -> Nullable<Offset16> {
+        self.bottom_right_math_kern_offset.get()
+    }
+
+    /// Offset to MathKern table for bottom right corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    ///
+    /// The `data` argument should be retrieved from the parent table
+    /// By calling its `offset_data` method.
+    pub fn bottom_right_math_kern<'a>(
+        &self,
+        data: FontData<'a>,
+    ) -> Option<Result<MathKern<'a>, ReadError>> {
+        self.bottom_right_math_kern_offset().resolve(data)
+    }
+
+    /// Offset to MathKern table for bottom left corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    pub fn bottom_left_math_kern_offset(&self) -> Nullable<Offset16> {
+        self.bottom_left_math_kern_offset.get()
+    }
+
+    /// Offset to MathKern table for bottom left corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    ///
+    /// The `data` argument should be retrieved from the parent table
+    /// By calling its `offset_data` method.
+    pub fn bottom_left_math_kern<'a>(
+        &self,
+        data: FontData<'a>,
+    ) -> Option<Result<MathKern<'a>, ReadError>> {
+        self.bottom_left_math_kern_offset().resolve(data)
+    }
+}
+
+impl FixedSize for MathKernInfoRecord {
+    const RAW_BYTE_LEN: usize = Offset16::RAW_BYTE_LEN
+        + Offset16::RAW_BYTE_LEN
+        + Offset16::RAW_BYTE_LEN
+        + Offset16::RAW_BYTE_LEN;
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeRecord<'a> for MathKernInfoRecord {
+    fn traverse(self, data: FontData<'a>) -> RecordResolver<'a> {
+        RecordResolver {
+            name: "MathKernInfoRecord",
+            get_field: Box::new(move |idx, _data| match idx {
+                0usize => Some(Field::new(
+                    "top_right_math_kern_offset",
+                    FieldType::offset(
+                        self.top_right_math_kern_offset(),
+                        self.top_right_math_kern(_data),
+                    ),
+                )),
+                1usize => Some(Field::new(
+                    "top_left_math_kern_offset",
+                    FieldType::offset(
+                        self.top_left_math_kern_offset(),
+                        self.top_left_math_kern(_data),
+                    ),
+                )),
+                2usize => Some(Field::new(
+                    "bottom_right_math_kern_offset",
+                    FieldType::offset(
+                        self.bottom_right_math_kern_offset(),
+                        self.bottom_right_math_kern(_data),
+                    ),
+                )),
+                3usize => Some(Field::new(
+                    "bottom_left_math_kern_offset",
+                    FieldType::offset(
+                        self.bottom_left_math_kern_offset(),
+                        self.bottom_left_math_kern(_data),
+                    ),
+                )),
+                _ => None,
+            }),
+            data,
+        }
+    }
+}
+
+/// [MathKern](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathkern-table) table
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct MathKernMarker {
+    correction_height_byte_len: usize,
+    kern_values_byte_len: usize,
+}
+
+impl MathKernMarker {
+    pub fn height_count_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn correction_height_byte_range(&self) -> Range<usize> {
+        let start = self.height_count_byte_range().end;
+        start..start + self.correction_height_byte_len
+    }
+
+    pub fn kern_values_byte_range(&self) -> Range<usize> {
+        let start = self.correction_height_byte_range().end;
+        start..start + self.kern_values_byte_len
+    }
+}
+
+impl<'a> FontRead<'a> for MathKern<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        let height_count: u16 = cursor.read()?;
+        let correction_height_byte_len = (height_count as usize)
+            .checked_mul(MathValueRecord::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(correction_height_byte_len);
+        let kern_values_byte_len = (transforms::add(height_count, 1_usize))
+            .checked_mul(MathValueRecord::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(kern_values_byte_len);
+        cursor.finish(MathKernMarker {
+            correction_height_byte_len,
+            kern_values_byte_len,
+        })
+    }
+}
+
+/// [MathKern](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathkern-table) table
+pub type MathKern<'a> = TableRef<'a, MathKernMarker>;
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> MathKern<'a> {
+    /// Number of heights at which the kern value changes.
+    pub fn height_count(&self) -> u16 {
+        let range = self.shape.height_count_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Array of correction heights, in design units, sorted from
+    /// lowest to highest.
+    pub fn correction_height(&self) -> &'a [MathValueRecord] {
+        let range = self.shape.correction_height_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+
+    /// Array of kerning values for different height ranges. Negative
+    /// values are used to move glyphs closer to each other.
+    pub fn kern_values(&self) -> &'a [MathValueRecord] {
+        let range = self.shape.kern_values_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeTable<'a> for MathKern<'a> {
+    fn type_name(&self) -> &str {
+        "MathKern"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new("height_count", self.height_count())),
+            1usize => Some(Field::new(
+                "correction_height",
+                traversal::FieldType::array_of_records(
+                    stringify!(MathValueRecord),
+                    self.correction_height(),
+                    self.offset_data(),
+                ),
+            )),
+            2usize => Some(Field::new(
+                "kern_values",
+                traversal::FieldType::array_of_records(
+                    stringify!(MathValueRecord),
+                    self.kern_values(),
+                    self.offset_data(),
+                ),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+#[allow(clippy::needless_lifetimes)]
+impl<'a> std::fmt::Debug for MathKern<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// [MathVariants](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathvariants-table) table
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct MathVariantsMarker {
+    vert_glyph_construction_offsets_byte_len: usize,
+    horiz_glyph_construction_offsets_byte_len: usize,
+}
+
+impl MathVariantsMarker {
+    pub fn min_connector_overlap_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + UfWord::RAW_BYTE_LEN
+    }
+
+    pub fn vert_glyph_coverage_offset_byte_range(&self) -> Range<usize> {
+        let start = self.min_connector_overlap_byte_range().end;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+
+    pub fn horiz_glyph_coverage_offset_byte_range(&self) -> Range<usize> {
+        let start = self.vert_glyph_coverage_offset_byte_range().end;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+
+    pub fn vert_glyph_count_byte_range(&self) -> Range<usize> {
+        let start = self.horiz_glyph_coverage_offset_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn horiz_glyph_count_byte_range(&self) -> Range<usize> {
+        let start = self.vert_glyph_count_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn vert_glyph_construction_offsets_byte_range(&self) -> Range<usize> {
+        let start = self.horiz_glyph_count_byte_range().end;
+        start..start + self.vert_glyph_construction_offsets_byte_len
+    }
+
+    pub fn horiz_glyph_construction_offsets_byte_range(&self) -> Range<usize> {
+        let start = self.vert_glyph_construction_offsets_byte_range().end;
+        start..start + self.horiz_glyph_construction_offsets_byte_len
+    }
+}
+
+impl<'a> FontRead<'a> for MathVariants<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        cursor.advance::<UfWord>();
+        cursor.advance::<Offset16>();
+        cursor.advance::<Offset16>();
+        let vert_glyph_count: u16 = cursor.read()?;
+        let horiz_glyph_count: u16 = cursor.read()?;
+        let vert_glyph_construction_offsets_byte_len = (vert_glyph_count as usize)
+            .checked_mul(Offset16::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(vert_glyph_construction_offsets_byte_len);
+        let horiz_glyph_construction_offsets_byte_len = (horiz_glyph_count as usize)
+            .checked_mul(Offset16::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(horiz_glyph_construction_offsets_byte_len);
+        cursor.finish(MathVariantsMarker {
+            vert_glyph_construction_offsets_byte_len,
+            horiz_glyph_construction_offsets_byte_len,
+        })
+    }
+}
+
+/// [MathVariants](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathvariants-table) table
+pub type MathVariants<'a> = TableRef<'a, MathVariantsMarker>;
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> MathVariants<'a> {
+    /// Minimum overlap of connecting glyphs during glyph construction,
+    /// in design units.
+    pub fn min_connector_overlap(&self) -> UfWord {
+        let range = self.shape.min_connector_overlap_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Offset to Coverage table, from the beginning of the
+    /// MathVariants table.
+    pub fn vert_glyph_coverage_offset(&self) -> Nullable<Offset16> {
+        let range = self.shape.vert_glyph_coverage_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`vert_glyph_coverage_offset`][Self::vert_glyph_coverage_offset].
+    pub fn vert_glyph_coverage(&self) -> Option<Result<CoverageTable<'a>, ReadError>> {
+        let data = self.data;
+        self.vert_glyph_coverage_offset().resolve(data)
+    }
+
+    /// Offset to Coverage table, from the beginning of the
+    /// MathVariants table.
+    pub fn horiz_glyph_coverage_offset(&self) -> Nullable<Offset16> {
+        let range = self.shape.horiz_glyph_coverage_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`horiz_glyph_coverage_offset`][Self::horiz_glyph_coverage_offset].
+    pub fn horiz_glyph_coverage(&self) -> Option<Result<CoverageTable<'a>, ReadError>> {
+        let data = self.data;
+        self.horiz_glyph_coverage_offset().resolve(data)
+    }
+
+    /// Number of glyphs for which information is provided for
+    /// vertically growing variants. Must be the same as the number of
+    /// glyph IDs referenced in the vertical Coverage table.
+    pub fn vert_glyph_count(&self) -> u16 {
+        let range = self.shape.vert_glyph_count_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Number of glyphs for which information is provided for
+    /// horizontally growing variants. Must be the same as the number
+    /// of glyph IDs referenced in the horizontal Coverage table.
+    pub fn horiz_glyph_count(&self) -> u16 {
+        let range = self.shape.horiz_glyph_count_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Array of offsets to MathGlyphConstruction tables, from the
+    /// beginning of the MathVariants table, for shapes growing in the
+    /// vertical direction.
+    pub fn vert_glyph_construction_offsets(&self) -> &'a [BigEndian<Offset16>] {
+        let range = self.shape.vert_glyph_construction_offsets_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+
+    /// A dynamically resolving wrapper for [`vert_glyph_construction_offsets`][Self::vert_glyph_construction_offsets].
+    pub fn vert_glyph_constructions(
+        &self,
+    ) -> ArrayOfOffsets<'a, MathGlyphConstruction<'a>, Offset16> {
+        let data = self.data;
+        let offsets = self.vert_glyph_construction_offsets();
+        ArrayOfOffsets::new(offsets, data, ())
+    }
+
+    /// Array of offsets to MathGlyphConstruction tables, from the
+    /// beginning of the MathVariants table, for shapes growing in the
+    /// horizontal direction.
+    pub fn horiz_glyph_construction_offsets(&self) -> &'a [BigEndian<Offset16>] {
+        let range = self.shape.horiz_glyph_construction_offsets_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+
+    /// A dynamically resolving wrapper for [`horiz_glyph_construction_offsets`][Self::horiz_glyph_construction_offsets].
+    pub fn horiz_glyph_constructions(
+        &self,
+    ) -> ArrayOfOffsets<'a, MathGlyphConstruction<'a>, Offset16> {
+        let data = self.data;
+        let offsets = self.horiz_glyph_construction_offsets();
+        ArrayOfOffsets::new(offsets, data, ())
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeTable<'a> for MathVariants<'a> {
+    fn type_name(&self) -> &str {
+        "MathVariants"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new(
+                "min_connector_overlap",
+                self.min_connector_overlap(),
+            )),
+            1usize => Some(Field::new(
+                "vert_glyph_coverage_offset",
+                FieldType::offset(
+                    self.vert_glyph_coverage_offset(),
+                    self.vert_glyph_coverage(),
+                ),
+            )),
+            2usize => Some(Field::new(
+                "horiz_glyph_coverage_offset",
+                FieldType::offset(
+                    self.horiz_glyph_coverage_offset(),
+                    self.horiz_glyph_coverage(),
+                ),
+            )),
+            3usize => Some(Field::new("vert_glyph_count", self.vert_glyph_count())),
+            4usize => Some(Field::new("horiz_glyph_count", self.horiz_glyph_count())),
+            5usize => Some({
+                let data = self.data;
+                Field::new(
+                    "vert_glyph_construction_offsets",
+                    FieldType::array_of_offsets(
+                        better_type_name::<MathGlyphConstruction>(),
+                        self.vert_glyph_construction_offsets(),
+                        move |off| {
+                            let target = off.get().resolve::<MathGlyphConstruction>(data);
+                            FieldType::offset(off.get(), target)
+                        },
+                    ),
+                )
+            }),
+            6usize => Some({
+                let data = self.data;
+                Field::new(
+                    "horiz_glyph_construction_offsets",
+                    FieldType::array_of_offsets(
+                        better_type_name::<MathGlyphConstruction>(),
+                        self.horiz_glyph_construction_offsets(),
+                        move |off| {
+                            let target = off.get().resolve::<MathGlyphConstruction>(data);
+                            FieldType::offset(off.get(), target)
+                        },
+                    ),
+                )
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+#[allow(clippy::needless_lifetimes)]
+impl<'a> std::fmt::Debug for MathVariants<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// [MathGlyphConstruction](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathglyphconstruction-table) table
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct MathGlyphConstructionMarker {
+    math_glyph_variant_records_byte_len: usize,
+}
+
+impl MathGlyphConstructionMarker {
+    pub fn glyph_assembly_offset_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+
+    pub fn variant_count_byte_range(&self) -> Range<usize> {
+        let start = self.glyph_assembly_offset_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn math_glyph_variant_records_byte_range(&self) -> Range<usize> {
+        let start = self.variant_count_byte_range().end;
+        start..start + self.math_glyph_variant_records_byte_len
+    }
+}
+
+impl<'a> FontRead<'a> for MathGlyphConstruction<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        cursor.advance::<Offset16>();
+        let variant_count: u16 = cursor.read()?;
+        let math_glyph_variant_records_byte_len = (variant_count as usize)
+            .checked_mul(MathGlyphVariantRecord::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(math_glyph_variant_records_byte_len);
+        cursor.finish(MathGlyphConstructionMarker {
+            math_glyph_variant_records_byte_len,
+        })
+    }
+}
+
+/// [MathGlyphConstruction](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathglyphconstruction-table) table
+pub type MathGlyphConstruction<'a> = TableRef<'a, MathGlyphConstructionMarker>;
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> MathGlyphConstruction<'a> {
+    /// Offset to the GlyphAssembly table for this shape, from the
+    /// beginning of the MathGlyphConstruction table. May be NULL.
+    pub fn glyph_assembly_offset(&self) -> Nullable<Offset16> {
+        let range = self.shape.glyph_assembly_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`glyph_assembly_offset`][Self::glyph_assembly_offset].
+    pub fn glyph_assembly(&self) -> Option<Result<GlyphAssembly<'a>, ReadError>> {
+        let data = self.data;
+        self.glyph_assembly_offset().resolve(data)
+    }
+
+    /// Count of glyph growing variants for this glyph.
+    pub fn variant_count(&self) -> u16 {
+        let range = self.shape.variant_count_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// MathGlyphVariantRecords for alternative variants of the glyphs.
+    pub fn math_glyph_variant_records(&self) -> &'a [MathGlyphVariantRecord] {
+        let range = self.shape.math_glyph_variant_records_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeTable<'a> for MathGlyphConstruction<'a> {
+    fn type_name(&self) -> &str {
+        "MathGlyphConstruction"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new(
+                "glyph_assembly_offset",
+                FieldType::offset(self.glyph_assembly_offset(), self.glyph_assembly()),
+            )),
+            1usize => Some(Field::new("variant_count", self.variant_count())),
+            2usize => Some(Field::new(
+                "math_glyph_variant_records",
+                traversal::FieldType::array_of_records(
+                    stringify!(MathGlyphVariantRecord),
+                    self.math_glyph_variant_records(),
+                    self.offset_data(),
+                ),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+#[allow(clippy::needless_lifetimes)]
+impl<'a> std::fmt::Debug for MathGlyphConstruction<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// [MathGlyphVariantRecord](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathglyphvariantrecord)
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, bytemuck :: AnyBitPattern)]
+#[repr(C)]
+#[repr(packed)]
+pub struct MathGlyphVariantRecord {
+    /// Glyph ID for the variant.
+    pub variant_glyph: BigEndian<GlyphId16>,
+    /// Advance width/height, in design units, of the variant, in the
+    /// direction of requested glyph extension.
+    pub advance_measurement: BigEndian<UfWord>,
+}
+
+impl MathGlyphVariantRecord {
+    /// Glyph ID for the variant.
+    pub fn variant_glyph(&self) -> GlyphId16 {
+        self.variant_glyph.get()
+    }
+
+    /// Advance width/height, in design units, of the variant, in the
+    /// direction of requested glyph extension.
+    pub fn advance_measurement(&self) -> UfWord {
+        self.advance_measurement.get()
+    }
+}
+
+impl FixedSize for MathGlyphVariantRecord {
+    const RAW_BYTE_LEN: usize = GlyphId16::RAW_BYTE_LEN + UfWord::RAW_BYTE_LEN;
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeRecord<'a> for MathGlyphVariantRecord {
+    fn traverse(self, data: FontData<'a>) -> RecordResolver<'a> {
+        RecordResolver {
+            name: "MathGlyphVariantRecord",
+            get_field: Box::new(move |idx, _data| match idx {
+                0usize => Some(Field::new("variant_glyph", self.variant_glyph())),
+                1usize => Some(Field::new(
+                    "advance_measurement",
+                    self.advance_measurement(),
+                )),
+                _ => None,
+            }),
+            data,
+        }
+    }
+}
+
+/// [GlyphAssembly](https://learn.microsoft.com/en-us/typography/opentype/spec/math#glyphassembly-table) table
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct GlyphAssemblyMarker {
+    part_records_byte_len: usize,
+}
+
+impl GlyphAssemblyMarker {
+    pub fn italics_correction_value_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + FWord::RAW_BYTE_LEN
+    }
+
+    pub fn italics_correction_device_offset_byte_range(&self) -> Range<usize> {
+        let start = self.italics_correction_value_byte_range().end;
+        start..start + Offset16::RAW_BYTE_LEN
+    }
+
+    pub fn part_count_byte_range(&self) -> Range<usize> {
+        let start = self.italics_correction_device_offset_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn part_records_byte_range(&self) -> Range<usize> {
+        let start = self.part_count_byte_range().end;
+        start..start + self.part_records_byte_len
+    }
+}
+
+impl<'a> FontRead<'a> for GlyphAssembly<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        cursor.advance::<FWord>();
+        cursor.advance::<Offset16>();
+        let part_count: u16 = cursor.read()?;
+        let part_records_byte_len = (part_count as usize)
+            .checked_mul(GlyphPart::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(part_records_byte_len);
+        cursor.finish(GlyphAssemblyMarker {
+            part_records_byte_len,
+        })
+    }
+}
+
+/// [GlyphAssembly](https://learn.microsoft.com/en-us/typography/opentype/spec/math#glyphassembly-table) table
+pub type GlyphAssembly<'a> = TableRef<'a, GlyphAssemblyMarker>;
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> GlyphAssembly<'a> {
+    /// Value of the italics correction of this GlyphAssembly. Should
+    /// not depend on the assembly size.
+    pub fn italics_correction_value(&self) -> FWord {
+        let range = self.shape.italics_correction_value_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Offset to the device table for the italics correction, from the
+    /// beginning of the GlyphAssembly table. May be NULL.
+    pub fn italics_correction_device_offset(&self) -> Nullable<Offset16> {
+        let range = self.shape.italics_correction_device_offset_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Attempt to resolve [`italics_correction_device_offset`][Self::italics_correction_device_offset].
+    pub fn italics_correction_device(
+        &self,
+    ) -> Option<Result<DeviceOrVariationIndex<'a>, ReadError>> {
+        let data = self.data;
+        self.italics_correction_device_offset().resolve(data)
+    }
+
+    /// Number of parts in this assembly.
+    pub fn part_count(&self) -> u16 {
+        let range = self.shape.part_count_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Array of GlyphPart records, from left to right (for assemblies
+    /// that extend horizontally) or bottom to top (for assemblies that
+    /// extend vertically).
+    pub fn part_records(&self) -> &'a [GlyphPart] {
+        let range = self.shape.part_records_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeTable<'a> for GlyphAssembly<'a> {
+    fn type_name(&self) -> &str {
+        "GlyphAssembly"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new(
+                "italics_correction_value",
+                self.italics_correction_value(),
+            )),
+            1usize => Some(Field::new(
+                "italics_correction_device_offset",
+                FieldType::offset(
+                    self.italics_correction_device_offset(),
+                    self.italics_correction_device(),
+                ),
+            )),
+            2usize => Some(Field::new("part_count", self.part_count())),
+            3usize => Some(Field::new(
+                "part_records",
+                traversal::FieldType::array_of_records(
+                    stringify!(GlyphPart),
+                    self.part_records(),
+                    self.offset_data(),
+                ),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+#[allow(clippy::needless_lifetimes)]
+impl<'a> std::fmt::Debug for GlyphAssembly<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// [GlyphPart](https://learn.microsoft.com/en-us/typography/opentype/spec/math#glyphpart-record)
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, bytemuck :: AnyBitPattern)]
+#[repr(C)]
+#[repr(packed)]
+pub struct GlyphPart {
+    /// Glyph ID for the part.
+    pub glyph_id: BigEndian<GlyphId16>,
+    /// Advance width/ height, in design units, of the straight bar
+    /// connector material at the start of the glyph in the direction
+    /// of the extension (the left end for horizontal extension, the
+    /// bottom end for vertical extension).
+    pub start_connector_length: BigEndian<UfWord>,
+    /// Advance width/ height, in design units, of the straight bar
+    /// connector material at the end of the glyph in the direction of
+    /// the extension (the right end for horizontal extension, the top
+    /// end for vertical extension).
+    pub end_connector_length: BigEndian<UfWord>,
+    /// Full advance width/height for this part in the direction of the
+    /// extension, in design units.
+    pub full_advance: BigEndian<UfWord>,
+    /// Part qualifiers.
+    pub part_flags: BigEndian<GlyphPartFlags>,
+}
+
+impl GlyphPart {
+    /// Glyph ID for the part.
+    pub fn glyph_id(&self) -> GlyphId16 {
+        self.glyph_id.get()
+    }
+
+    /// Advance width/ height, in design units, of the straight bar
+    /// connector material at the start of the glyph in the direction
+    /// of the extension (the left end for horizontal extension, the
+    /// bottom end for vertical extension).
+    pub fn start_connector_length(&self) -> UfWord {
+        self.start_connector_length.get()
+    }
+
+    /// Advance width/ height, in design units, of the straight bar
+    /// connector material at the end of the glyph in the direction of
+    /// the extension (the right end for horizontal extension, the top
+    /// end for vertical extension).
+    pub fn end_connector_length(&self) -> UfWord {
+        self.end_connector_length.get()
+    }
+
+    /// Full advance width/height for this part in the direction of the
+    /// extension, in design units.
+    pub fn full_advance(&self) -> UfWord {
+        self.full_advance.get()
+    }
+
+    /// Part qualifiers.
+    pub fn part_flags(&self) -> GlyphPartFlags {
+        self.part_flags.get()
+    }
+}
+
+impl FixedSize for GlyphPart {
+    const RAW_BYTE_LEN: usize = GlyphId16::RAW_BYTE_LEN
+        + UfWord::RAW_BYTE_LEN
+        + UfWord::RAW_BYTE_LEN
+        + UfWord::RAW_BYTE_LEN
+        + GlyphPartFlags::RAW_BYTE_LEN;
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeRecord<'a> for GlyphPart {
+    fn traverse(self, data: FontData<'a>) -> RecordResolver<'a> {
+        RecordResolver {
+            name: "GlyphPart",
+            get_field: Box::new(move |idx, _data| match idx {
+                0usize => Some(Field::new("glyph_id", self.glyph_id())),
+                1usize => Some(Field::new(
+                    "start_connector_length",
+                    self.start_connector_length(),
+                )),
+                2usize => Some(Field::new(
+                    "end_connector_length",
+                    self.end_connector_length(),
+                )),
+                3usize => Some(Field::new("full_advance", self.full_advance())),
+                4usize => Some(Field::new("part_flags", self.part_flags())),
+                _ => None,
+            }),
+            data,
+        }
+    }
+}
+
+/// [GlyphPart](https://learn.microsoft.com/en-us/typography/opentype/spec/math#glyphpart-record) flags
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, bytemuck :: AnyBitPattern)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(transparent)]
+pub struct GlyphPartFlags {
+    bits: u16,
+}
+
+impl GlyphPartFlags {
+    /// If set, the part can be skipped or repeated.
+    pub const EXTENDER_FLAG: Self = Self { bits: 0x0001 };
+}
+
+impl GlyphPartFlags {
+    ///  Returns an empty set of flags.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self { bits: 0 }
+    }
+
+    /// Returns the set containing all flags.
+    #[inline]
+    pub const fn all() -> Self {
+        Self {
+            bits: Self::EXTENDER_FLAG.bits,
+        }
+    }
+
+    /// Returns the raw value of the flags currently stored.
+    #[inline]
+    pub const fn bits(&self) -> u16 {
+        self.bits
+    }
+
+    /// Convert from underlying bit representation, unless that
+    /// representation contains bits that do not correspond to a flag.
+    #[inline]
+    pub const fn from_bits(bits: u16) -> Option<Self> {
+        if (bits & !Self::all().bits()) == 0 {
+            Some(Self { bits })
+        } else {
+            None
+        }
+    }
+
+    /// Convert from underlying bit representation, dropping any bits
+    /// that do not correspond to flags.
+    #[inline]
+    pub const fn from_bits_truncate(bits: u16) -> Self {
+        Self {
+            bits: bits & Self::all().bits,
+        }
+    }
+
+    /// Returns `true` if no flags are currently stored.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.bits() == Self::empty().bits()
+    }
+
+    /// Returns `true` if there are flags common to both `self` and `other`.
+    #[inline]
+    pub const fn intersects(&self, other: Self) -> bool {
+        !(Self {
+            bits: self.bits & other.bits,
+        })
+        .is_empty()
+    }
+
+    /// Returns `true` if all of the flags in `other` are contained within `self`.
+    #[inline]
+    pub const fn contains(&self, other: Self) -> bool {
+        (self.bits & other.bits) == other.bits
+    }
+
+    /// Inserts the specified flags in-place.
+    #[inline]
+    pub fn insert(&mut self, other: Self) {
+        self.bits |= other.bits;
+    }
+
+    /// Removes the specified flags in-place.
+    #[inline]
+    pub fn remove(&mut self, other: Self) {
+        self.bits &= !other.bits;
+    }
+
+    /// Toggles the specified flags in-place.
+    #[inline]
+    pub fn toggle(&mut self, other: Self) {
+        self.bits ^= other.bits;
+    }
+
+    /// Returns the intersection between the flags in `self` and
+    /// `other`.
+    ///
+    /// Specifically, the returned set contains only the flags which are
+    /// present in *both* `self` *and* `other`.
+    ///
+    /// This is equivalent to using the `&` operator (e.g.
+    /// [`ops::BitAnd`]), as in `flags & other`.
+    ///
+    /// [`ops::BitAnd`]: https://doc.rust-lang.org/std/ops/trait.BitAnd.html
+    #[inline]
+    #[must_use]
+    pub const fn intersection(self, other: Self) -> Self {
+        Self {
+            bits: self.bits & other.bits,
+        }
+    }
+
+    /// Returns the union of between the flags in `self` and `other`.
+    ///
+    /// Specifically, the returned set contains all flags which are
+    /// present in *either* `self` *or* `other`, including any which are
+    /// present in both.
+    ///
+    /// This is equivalent to using the `|` operator (e.g.
+    /// [`ops::BitOr`]), as in `flags | other`.
+    ///
+    /// [`ops::BitOr`]: https://doc.rust-lang.org/std/ops/trait.BitOr.html
+    #[inline]
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        Self {
+            bits: self.bits | other.bits,
+        }
+    }
+
+    /// Returns the difference between the flags in `self` and `other`.
+    ///
+    /// Specifically, the returned set contains all flags present in
+    /// `self`, except for the ones present in `other`.
+    ///
+    /// It is also conceptually equivalent to the "bit-clear" operation:
+    /// `flags & !other` (and this syntax is also supported).
+    ///
+    /// This is equivalent to using the `-` operator (e.g.
+    /// [`ops::Sub`]), as in `flags - other`.
+    ///
+    /// [`ops::Sub`]: https://doc.rust-lang.org/std/ops/trait.Sub.html
+    #[inline]
+    #[must_use]
+    pub const fn difference(self, other: Self) -> Self {
+        Self {
+            bits: self.bits & !other.bits,
+        }
+    }
+}
+
+impl std::ops::BitOr for GlyphPartFlags {
+    type Output = Self;
+
+    /// Returns the union of the two sets of flags.
+    #[inline]
+    fn bitor(self, other: GlyphPartFlags) -> Self {
+        Self {
+            bits: self.bits | other.bits,
+        }
+    }
+}
+
+impl std::ops::BitOrAssign for GlyphPartFlags {
+    /// Adds the set of flags.
+    #[inline]
+    fn bitor_assign(&mut self, other: Self) {
+        self.bits |= other.bits;
+    }
+}
+
+impl std::ops::BitXor for GlyphPartFlags {
+    type Output = Self;
+
+    /// Returns the left flags, but with all the right flags toggled.
+    #[inline]
+    fn bitxor(self, other: Self) -> Self {
+        Self {
+            bits: self.bits ^ other.bits,
+        }
+    }
+}
+
+impl std::ops::BitXorAssign for GlyphPartFlags {
+    /// Toggles the set of flags.
+    #[inline]
+    fn bitxor_assign(&mut self, other: Self) {
+        self.bits ^= other.bits;
+    }
+}
+
+impl std::ops::BitAnd for GlyphPartFlags {
+    type Output = Self;
+
+    /// Returns the intersection between the two sets of flags.
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        Self {
+            bits: self.bits & other.bits,
+        }
+    }
+}
+
+impl std::ops::BitAndAssign for GlyphPartFlags {
+    /// Disables all flags disabled in the set.
+    #[inline]
+    fn bitand_assign(&mut self, other: Self) {
+        self.bits &= other.bits;
+    }
+}
+
+impl std::ops::Sub for GlyphPartFlags {
+    type Output = Self;
+
+    /// Returns the set difference of the two sets of flags.
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self {
+            bits: self.bits & !other.bits,
+        }
+    }
+}
+
+impl std::ops::SubAssign for GlyphPartFlags {
+    /// Disables all flags enabled in the set.
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.bits &= !other.bits;
+    }
+}
+
+impl std::ops::Not for GlyphPartFlags {
+    type Output = Self;
+
+    /// Returns the complement of this set of flags.
+    #[inline]
+    fn not(self) -> Self {
+        Self { bits: !self.bits } & Self::all()
+    }
+}
+
+impl std::fmt::Debug for GlyphPartFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let members: &[(&str, Self)] = &[("EXTENDER_FLAG", Self::EXTENDER_FLAG)];
+        let mut first = true;
+        for (name, value) in members {
+            if self.contains(*value) {
+                if !first {
+                    f.write_str(" | ")?;
+                }
+                first = false;
+                f.write_str(name)?;
+            }
+        }
+        if first {
+            f.write_str("(empty)")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Binary for GlyphPartFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Binary::fmt(&self.bits, f)
+    }
+}
+
+impl std::fmt::Octal for GlyphPartFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Octal::fmt(&self.bits, f)
+    }
+}
+
+impl std::fmt::LowerHex for GlyphPartFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::LowerHex::fmt(&self.bits, f)
+    }
+}
+
+impl std::fmt::UpperHex for GlyphPartFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::UpperHex::fmt(&self.bits, f)
+    }
+}
+
+impl font_types::Scalar for GlyphPartFlags {
+    type Raw = <u16 as font_types::Scalar>::Raw;
+    fn to_raw(self) -> Self::Raw {
+        self.bits().to_raw()
+    }
+    fn from_raw(raw: Self::Raw) -> Self {
+        let t = <u16>::from_raw(raw);
+        Self::from_bits_truncate(t)
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> From<GlyphPartFlags> for FieldType<'a> {
+    fn from(src: GlyphPartFlags) -> FieldType<'a> {
+        src.bits().into()
+    }
+}
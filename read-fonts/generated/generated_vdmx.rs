@@ -0,0 +1,369 @@
+// THIS FILE IS AUTOGENERATED.
+// Any changes to this file will be overwritten.
+// For more information about how codegen works, see font-codegen/README.md
+
+#[allow(unused_imports)]
+use crate::codegen_prelude::*;
+
+/// The [VDMX (Vertical Device Metrics)](https://learn.microsoft.com/en-us/typography/opentype/spec/vdmx) table
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct VdmxMarker {
+    ratio_ranges_byte_len: usize,
+    vdmx_group_offsets_byte_len: usize,
+}
+
+impl VdmxMarker {
+    pub fn version_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn num_recs_byte_range(&self) -> Range<usize> {
+        let start = self.version_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn num_ratios_byte_range(&self) -> Range<usize> {
+        let start = self.num_recs_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn ratio_ranges_byte_range(&self) -> Range<usize> {
+        let start = self.num_ratios_byte_range().end;
+        start..start + self.ratio_ranges_byte_len
+    }
+
+    pub fn vdmx_group_offsets_byte_range(&self) -> Range<usize> {
+        let start = self.ratio_ranges_byte_range().end;
+        start..start + self.vdmx_group_offsets_byte_len
+    }
+}
+
+impl TopLevelTable for Vdmx<'_> {
+    /// `VDMX`
+    const TAG: Tag = Tag::new(b"VDMX");
+}
+
+impl<'a> FontRead<'a> for Vdmx<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        cursor.advance::<u16>();
+        cursor.advance::<u16>();
+        let num_ratios: u16 = cursor.read()?;
+        let ratio_ranges_byte_len = (num_ratios as usize)
+            .checked_mul(RatioRange::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(ratio_ranges_byte_len);
+        let vdmx_group_offsets_byte_len = (num_ratios as usize)
+            .checked_mul(Offset16::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(vdmx_group_offsets_byte_len);
+        cursor.finish(VdmxMarker {
+            ratio_ranges_byte_len,
+            vdmx_group_offsets_byte_len,
+        })
+    }
+}
+
+/// The [VDMX (Vertical Device Metrics)](https://learn.microsoft.com/en-us/typography/opentype/spec/vdmx) table
+pub type Vdmx<'a> = TableRef<'a, VdmxMarker>;
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> Vdmx<'a> {
+    /// Version number (0 or 1).
+    pub fn version(&self) -> u16 {
+        let range = self.shape.version_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Number of VDMX groups present
+    pub fn num_recs(&self) -> u16 {
+        let range = self.shape.num_recs_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Number of aspect ratio groupings
+    pub fn num_ratios(&self) -> u16 {
+        let range = self.shape.num_ratios_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Ratio record array.
+    pub fn ratio_ranges(&self) -> &'a [RatioRange] {
+        let range = self.shape.ratio_ranges_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+
+    /// Offsets from start of this table to the VDMXGroup table for a
+    /// corresponding RatioRange record.
+    pub fn vdmx_group_offsets(&self) -> &'a [BigEndian<Offset16>] {
+        let range = self.shape.vdmx_group_offsets_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+
+    /// A dynamically resolving wrapper for [`vdmx_group_offsets`][Self::vdmx_group_offsets].
+    pub fn vdmx_groups(&self) -> ArrayOfOffsets<'a, VdmxGroup<'a>, Offset16> {
+        let data = self.data;
+        let offsets = self.vdmx_group_offsets();
+        ArrayOfOffsets::new(offsets, data, ())
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeTable<'a> for Vdmx<'a> {
+    fn type_name(&self) -> &str {
+        "Vdmx"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new("version", self.version())),
+            1usize => Some(Field::new("num_recs", self.num_recs())),
+            2usize => Some(Field::new("num_ratios", self.num_ratios())),
+            3usize => Some(Field::new(
+                "ratio_ranges",
+                traversal::FieldType::array_of_records(
+                    stringify!(RatioRange),
+                    self.ratio_ranges(),
+                    self.offset_data(),
+                ),
+            )),
+            4usize => Some({
+                let data = self.data;
+                Field::new(
+                    "vdmx_group_offsets",
+                    FieldType::array_of_offsets(
+                        better_type_name::<VdmxGroup>(),
+                        self.vdmx_group_offsets(),
+                        move |off| {
+                            let target = off.get().resolve::<VdmxGroup>(data);
+                            FieldType::offset(off.get(), target)
+                        },
+                    ),
+                )
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+#[allow(clippy::needless_lifetimes)]
+impl<'a> std::fmt::Debug for Vdmx<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// Ratio ranges specify a range of aspect ratios
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, bytemuck :: AnyBitPattern)]
+#[repr(C)]
+#[repr(packed)]
+pub struct RatioRange {
+    /// Character set (0 for no subset, 1 for the Windows ANSI subset).
+    pub b_char_set: u8,
+    /// Value to use for x-Ratio
+    pub x_ratio: u8,
+    /// Starting y-Ratio value.
+    pub y_start_ratio: u8,
+    /// Ending y-Ratio value.
+    pub y_end_ratio: u8,
+}
+
+impl RatioRange {
+    /// Character set (0 for no subset, 1 for the Windows ANSI subset).
+    pub fn b_char_set(&self) -> u8 {
+        self.b_char_set
+    }
+
+    /// Value to use for x-Ratio
+    pub fn x_ratio(&self) -> u8 {
+        self.x_ratio
+    }
+
+    /// Starting y-Ratio value.
+    pub fn y_start_ratio(&self) -> u8 {
+        self.y_start_ratio
+    }
+
+    /// Ending y-Ratio value.
+    pub fn y_end_ratio(&self) -> u8 {
+        self.y_end_ratio
+    }
+}
+
+impl FixedSize for RatioRange {
+    const RAW_BYTE_LEN: usize =
+        u8::RAW_BYTE_LEN + u8::RAW_BYTE_LEN + u8::RAW_BYTE_LEN + u8::RAW_BYTE_LEN;
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeRecord<'a> for RatioRange {
+    fn traverse(self, data: FontData<'a>) -> RecordResolver<'a> {
+        RecordResolver {
+            name: "RatioRange",
+            get_field: Box::new(move |idx, _data| match idx {
+                0usize => Some(Field::new("b_char_set", self.b_char_set())),
+                1usize => Some(Field::new("x_ratio", self.x_ratio())),
+                2usize => Some(Field::new("y_start_ratio", self.y_start_ratio())),
+                3usize => Some(Field::new("y_end_ratio", self.y_end_ratio())),
+                _ => None,
+            }),
+            data,
+        }
+    }
+}
+
+/// A group of vertical metrics records for a range of sizes.
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct VdmxGroupMarker {
+    entries_byte_len: usize,
+}
+
+impl VdmxGroupMarker {
+    pub fn recs_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + u16::RAW_BYTE_LEN
+    }
+
+    pub fn startsz_byte_range(&self) -> Range<usize> {
+        let start = self.recs_byte_range().end;
+        start..start + u8::RAW_BYTE_LEN
+    }
+
+    pub fn endsz_byte_range(&self) -> Range<usize> {
+        let start = self.startsz_byte_range().end;
+        start..start + u8::RAW_BYTE_LEN
+    }
+
+    pub fn entries_byte_range(&self) -> Range<usize> {
+        let start = self.endsz_byte_range().end;
+        start..start + self.entries_byte_len
+    }
+}
+
+impl<'a> FontRead<'a> for VdmxGroup<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        let recs: u16 = cursor.read()?;
+        cursor.advance::<u8>();
+        cursor.advance::<u8>();
+        let entries_byte_len = (recs as usize)
+            .checked_mul(VTable::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(entries_byte_len);
+        cursor.finish(VdmxGroupMarker { entries_byte_len })
+    }
+}
+
+/// A group of vertical metrics records for a range of sizes.
+pub type VdmxGroup<'a> = TableRef<'a, VdmxGroupMarker>;
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> VdmxGroup<'a> {
+    /// Number of height records in this group
+    pub fn recs(&self) -> u16 {
+        let range = self.shape.recs_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Starting yPelHeight
+    pub fn startsz(&self) -> u8 {
+        let range = self.shape.startsz_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Ending yPelHeight
+    pub fn endsz(&self) -> u8 {
+        let range = self.shape.endsz_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// The VDMX records
+    pub fn entries(&self) -> &'a [VTable] {
+        let range = self.shape.entries_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeTable<'a> for VdmxGroup<'a> {
+    fn type_name(&self) -> &str {
+        "VdmxGroup"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new("recs", self.recs())),
+            1usize => Some(Field::new("startsz", self.startsz())),
+            2usize => Some(Field::new("endsz", self.endsz())),
+            3usize => Some(Field::new(
+                "entries",
+                traversal::FieldType::array_of_records(
+                    stringify!(VTable),
+                    self.entries(),
+                    self.offset_data(),
+                ),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "experimental_traverse")]
+#[allow(clippy::needless_lifetimes)]
+impl<'a> std::fmt::Debug for VdmxGroup<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// The maximum and minimum extents for a single size.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, bytemuck :: AnyBitPattern)]
+#[repr(C)]
+#[repr(packed)]
+pub struct VTable {
+    /// yPelHeight to which values apply.
+    pub y_pel_height: BigEndian<u16>,
+    /// Maximum value (in pels) for this yPelHeight.
+    pub y_max: BigEndian<i16>,
+    /// Minimum value (in pels) for this yPelHeight.
+    pub y_min: BigEndian<i16>,
+}
+
+impl VTable {
+    /// yPelHeight to which values apply.
+    pub fn y_pel_height(&self) -> u16 {
+        self.y_pel_height.get()
+    }
+
+    /// Maximum value (in pels) for this yPelHeight.
+    pub fn y_max(&self) -> i16 {
+        self.y_max.get()
+    }
+
+    /// Minimum value (in pels) for this yPelHeight.
+    pub fn y_min(&self) -> i16 {
+        self.y_min.get()
+    }
+}
+
+impl FixedSize for VTable {
+    const RAW_BYTE_LEN: usize = u16::RAW_BYTE_LEN + i16::RAW_BYTE_LEN + i16::RAW_BYTE_LEN;
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> SomeRecord<'a> for VTable {
+    fn traverse(self, data: FontData<'a>) -> RecordResolver<'a> {
+        RecordResolver {
+            name: "VTable",
+            get_field: Box::new(move |idx, _data| match idx {
+                0usize => Some(Field::new("y_pel_height", self.y_pel_height())),
+                1usize => Some(Field::new("y_max", self.y_max())),
+                2usize => Some(Field::new("y_min", self.y_min())),
+                _ => None,
+            }),
+            data,
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Verification of table checksums.
+//!
+//! Every table record in a font stores a checksum of the table data and the
+//! `head` table stores an adjustment value that causes the checksum of the
+//! entire font to equal a known constant. Neither is used when reading a
+//! font, but mismatches usually indicate corruption or a broken build
+//! pipeline.
+//!
+//! See the [table directory](https://learn.microsoft.com/en-us/typography/opentype/spec/otff#calculating-checksums)
+//! and [head](https://learn.microsoft.com/en-us/typography/opentype/spec/head)
+//! specifications for details.
+
+use types::{Tag, TTC_HEADER_TAG};
+
+use crate::{FontRef, RawTable, TableProvider};
+
+const HEAD: Tag = Tag::new(b"head");
+
+/// The value that the checksum of an entire font should equal.
+const CHECKSUM_MAGIC: u32 = 0xB1B0AFBA;
+
+/// Computes the checksum of the given data, as if it were padded with zeros
+/// to a multiple of four bytes.
+pub fn compute(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// A table with a checksum that does not match its data.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct TableChecksumMismatch {
+    /// Tag of the table.
+    pub tag: Tag,
+    /// Checksum stored in the table record.
+    pub stored: u32,
+    /// Checksum computed from the table data.
+    pub computed: u32,
+}
+
+/// A `head.checkSumAdjustment` value that does not match the font data.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ChecksumAdjustmentMismatch {
+    /// Value stored in the `head` table.
+    pub stored: u32,
+    /// Value computed from the font data.
+    pub computed: u32,
+}
+
+/// The result of verifying the checksums of a font.
+#[cfg(feature = "std")]
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct ChecksumReport {
+    /// Tables with checksums that do not match their data, in directory
+    /// order.
+    ///
+    /// Tables with records that extend beyond the end of the font data are
+    /// not included. These are available from
+    /// [`FontRef::out_of_bounds_tables`].
+    pub tables: Vec<TableChecksumMismatch>,
+    /// The mismatched checksum adjustment, if any.
+    ///
+    /// This is only verified for fonts that contain a `head` table and are
+    /// not part of a collection, as the spec does not define the value for
+    /// fonts in a collection.
+    pub adjustment: Option<ChecksumAdjustmentMismatch>,
+}
+
+#[cfg(feature = "std")]
+impl ChecksumReport {
+    /// Returns true if no mismatches were found.
+    pub fn is_valid(&self) -> bool {
+        self.tables.is_empty() && self.adjustment.is_none()
+    }
+}
+
+/// Verifies the table checksums and checksum adjustment of the given font.
+#[cfg(feature = "std")]
+pub fn verify(font: &FontRef) -> ChecksumReport {
+    ChecksumReport {
+        tables: table_mismatches(font).collect(),
+        adjustment: adjustment_mismatch(font),
+    }
+}
+
+/// Returns an iterator over the tables with checksums that do not match
+/// their data.
+///
+/// See [`ChecksumReport::tables`] for details.
+pub fn table_mismatches<'a>(
+    font: &FontRef<'a>,
+) -> impl Iterator<Item = TableChecksumMismatch> + 'a + Clone {
+    font.tables().filter_map(|table| {
+        let computed = table_checksum(&table)?;
+        (computed != table.checksum()).then(|| TableChecksumMismatch {
+            tag: table.tag(),
+            stored: table.checksum(),
+            computed,
+        })
+    })
+}
+
+/// Returns the mismatched checksum adjustment, if any.
+///
+/// See [`ChecksumReport::adjustment`] for details.
+pub fn adjustment_mismatch(font: &FontRef) -> Option<ChecksumAdjustmentMismatch> {
+    if font.data.read_at::<Tag>(0).ok() == Some(TTC_HEADER_TAG) {
+        return None;
+    }
+    let stored = font.head().ok()?.checksum_adjustment();
+    // The font checksum is the sum of the checksums of the table directory
+    // and each table, computed with an adjustment of zero.
+    let directory = font.table_directory.offset_data().as_bytes();
+    let directory_len = font.table_directory.shape().table_records_byte_range().end;
+    let directory_checksum = compute(directory.get(..directory_len)?);
+    let font_checksum = font
+        .tables()
+        .filter_map(|table| table_checksum(&table))
+        .fold(directory_checksum, u32::wrapping_add);
+    let computed = CHECKSUM_MAGIC.wrapping_sub(font_checksum);
+    (computed != stored).then_some(ChecksumAdjustmentMismatch { stored, computed })
+}
+
+/// Computes the checksum for a table, treating the checksum adjustment of
+/// the `head` table as zero.
+fn table_checksum(table: &RawTable) -> Option<u32> {
+    let data = table.data()?.as_bytes();
+    let mut checksum = compute(data);
+    if table.tag() == HEAD {
+        if let Some(adjustment) = data.get(8..12) {
+            checksum = checksum.wrapping_sub(u32::from_be_bytes(adjustment.try_into().ok()?));
+        }
+    }
+    Some(checksum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileRef, FontRef};
+
+    /// Returns the offset and length of the given table.
+    fn table_range(font_data: &[u8], tag: Tag) -> core::ops::Range<usize> {
+        let font = FontRef::new(font_data).unwrap();
+        let table = font.tables().find(|table| table.tag() == tag).unwrap();
+        let start = table.offset() as usize;
+        start..start + table.length() as usize
+    }
+
+    #[test]
+    fn compute_padded() {
+        assert_eq!(compute(&[]), 0);
+        assert_eq!(compute(&[1, 2, 3, 4, 5]), 0x01020304 + 0x05000000);
+        assert_eq!(compute(&[0xFF; 8]), 0xFFFFFFFE);
+    }
+
+    #[test]
+    fn valid_font() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let report = verify(&font);
+        assert!(report.is_valid(), "{report:?}");
+    }
+
+    #[test]
+    fn corrupt_table() {
+        let mut data = font_test_data::VAZIRMATN_VAR.to_vec();
+        let glyf = table_range(&data, Tag::new(b"glyf"));
+        data[glyf.start] ^= 0x10;
+        let font = FontRef::new(&data).unwrap();
+        let report = verify(&font);
+        let stored = font
+            .tables()
+            .find(|table| table.tag() == Tag::new(b"glyf"))
+            .unwrap()
+            .checksum();
+        assert_eq!(
+            report.tables,
+            [TableChecksumMismatch {
+                tag: Tag::new(b"glyf"),
+                stored,
+                computed: stored.wrapping_add(0x10000000),
+            }]
+        );
+        // the stored checksum doesn't change so the adjustment no longer
+        // matches either
+        let adjustment = report.adjustment.unwrap();
+        assert_eq!(
+            adjustment.computed,
+            adjustment.stored.wrapping_sub(0x10000000)
+        );
+    }
+
+    #[test]
+    fn corrupt_adjustment() {
+        let mut data = font_test_data::VAZIRMATN_VAR.to_vec();
+        let head = table_range(&data, HEAD);
+        let adjustment = &mut data[head.start + 8..head.start + 12];
+        let stored = u32::from_be_bytes(adjustment.try_into().unwrap());
+        adjustment.copy_from_slice(&(stored + 1).to_be_bytes());
+        let font = FontRef::new(&data).unwrap();
+        let report = verify(&font);
+        // the adjustment is excluded from the head table checksum
+        assert!(report.tables.is_empty());
+        assert_eq!(
+            report.adjustment,
+            Some(ChecksumAdjustmentMismatch {
+                stored: stored + 1,
+                computed: stored,
+            })
+        );
+    }
+
+    #[test]
+    fn collection_skips_adjustment() {
+        let file = FileRef::new(font_test_data::ttc::TTC).unwrap();
+        for font in file.fonts() {
+            assert!(adjustment_mismatch(&font.unwrap()).is_none());
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Computing the glyph closure of a font
+//!
+//! When subsetting, the set of glyphs to retain must include not only the
+//! glyphs mapped from the requested codepoints, but also any glyphs that can
+//! be reached from them via substitution or that are referenced as components
+//! of composite glyphs.
+
+use font_types::{GlyphId, GlyphId16};
+
+use crate::{
+    collections::IntSet, FontRef, GlyphIdValidator, InvalidGlyphId, ReadError, TableProvider,
+};
+
+/// An error that occurs while computing a glyph closure.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClosureError {
+    /// A table could not be read.
+    Read(ReadError),
+    /// A glyph identifier was out of range with [`GlyphIdPolicy::Error`].
+    ///
+    /// [`GlyphIdPolicy::Error`]: crate::GlyphIdPolicy::Error
+    InvalidGlyphId(InvalidGlyphId),
+}
+
+impl From<ReadError> for ClosureError {
+    fn from(err: ReadError) -> Self {
+        ClosureError::Read(err)
+    }
+}
+
+impl From<InvalidGlyphId> for ClosureError {
+    fn from(err: InvalidGlyphId) -> Self {
+        ClosureError::InvalidGlyphId(err)
+    }
+}
+
+impl std::fmt::Display for ClosureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClosureError::Read(err) => err.fmt(f),
+            ClosureError::InvalidGlyphId(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ClosureError {}
+
+/// Update the set of glyphs with all glyphs reachable via `GSUB`
+/// substitutions, `COLR` paint graphs and layers and composite glyph
+/// components in `glyf`.
+///
+/// The closures are computed in that order so that, for example, components
+/// of any glyphs produced by substitution are also retained. Tables that are
+/// not present in the font are skipped.
+///
+/// The policy of the given validator is applied to the glyphs added by each
+/// step.
+pub fn glyph_closure(
+    font: &FontRef,
+    glyph_set: &mut IntSet<GlyphId>,
+    validator: &GlyphIdValidator,
+) -> Result<(), ClosureError> {
+    if let Ok(gsub) = font.gsub() {
+        let glyphs = glyph_set
+            .iter()
+            .filter_map(|gid| GlyphId16::try_from(gid).ok())
+            .collect();
+        let closure = gsub.closure_glyphs(glyphs)?;
+        glyph_set.extend_unsorted(closure.into_iter().map(GlyphId::from));
+        validator.validate_set(glyph_set)?;
+    }
+    if let Ok(colr) = font.colr() {
+        let mut layer_indices = IntSet::empty();
+        let mut palette_indices = IntSet::empty();
+        let mut variation_indices = IntSet::empty();
+        let mut delta_set_indices = IntSet::empty();
+        colr.v1_closure(
+            glyph_set,
+            &mut layer_indices,
+            &mut palette_indices,
+            &mut variation_indices,
+            &mut delta_set_indices,
+        );
+        let mut v0_glyphs = IntSet::empty();
+        colr.v0_closure_glyphs(glyph_set, &mut v0_glyphs);
+        glyph_set.union(&v0_glyphs);
+        validator.validate_set(glyph_set)?;
+    }
+    if let (Ok(loca), Ok(glyf)) = (font.loca(None), font.glyf()) {
+        glyf.closure_glyphs(&loca, glyph_set, validator)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GlyphIdPolicy;
+    use font_test_data::closure as test_data;
+
+    fn validator(font: &FontRef) -> GlyphIdValidator {
+        GlyphIdValidator::from_font(font, GlyphIdPolicy::Error).unwrap()
+    }
+
+    #[test]
+    fn gsub_closure() {
+        let font = FontRef::new(test_data::SIMPLE).unwrap();
+        // this font only contains a GSUB table so there is no maxp
+        let validator = GlyphIdValidator::new(11, GlyphIdPolicy::Error);
+        // glyph 0 is 'a'
+        let mut glyph_set: IntSet<GlyphId> = [GlyphId::new(0)].into();
+        glyph_closure(&font, &mut glyph_set, &validator).unwrap();
+        // a, b, c, d, A, a_a, a.1, a.2, a.3
+        assert_eq!(
+            glyph_set.iter().collect::<Vec<_>>(),
+            (0..9).map(GlyphId::new).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn composite_closure() {
+        let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
+        let mut glyph_set: IntSet<GlyphId> = [GlyphId::new(5)].into();
+        glyph_closure(&font, &mut glyph_set, &validator(&font)).unwrap();
+        assert_eq!(
+            glyph_set.iter().collect::<Vec<_>>(),
+            [GlyphId::new(1), GlyphId::new(5)]
+        );
+    }
+
+    #[test]
+    fn colr_closure() {
+        let font = FontRef::new(font_test_data::COLRV0V1_VARIABLE).unwrap();
+        let mut glyph_set: IntSet<GlyphId> = [GlyphId::new(168)].into();
+        glyph_closure(&font, &mut glyph_set, &validator(&font)).unwrap();
+        for gid in [5, 168, 170, 171, 172, 173, 174, 175, 176] {
+            assert!(glyph_set.contains(GlyphId::new(gid)));
+        }
+    }
+
+    #[test]
+    fn colr_closure_clamped() {
+        let font = FontRef::new(font_test_data::COLRV0V1_VARIABLE).unwrap();
+        // treat all layer glyphs other than 5 as out of range
+        let validator = GlyphIdValidator::new(169, GlyphIdPolicy::Clamp);
+        let mut glyph_set: IntSet<GlyphId> = [GlyphId::new(168)].into();
+        glyph_closure(&font, &mut glyph_set, &validator).unwrap();
+        assert!(glyph_set.contains(GlyphId::NOTDEF));
+        assert!(glyph_set.contains(GlyphId::new(5)));
+        assert_eq!(glyph_set.last(), Some(GlyphId::new(168)));
+        assert_eq!(validator.out_of_range_count(), 7);
+    }
+
+    #[test]
+    fn colr_closure_error() {
+        let font = FontRef::new(font_test_data::COLRV0V1_VARIABLE).unwrap();
+        let validator = GlyphIdValidator::new(169, GlyphIdPolicy::Error);
+        let mut glyph_set: IntSet<GlyphId> = [GlyphId::new(168)].into();
+        let Err(ClosureError::InvalidGlyphId(err)) =
+            glyph_closure(&font, &mut glyph_set, &validator)
+        else {
+            panic!("expected an out of range glyph");
+        };
+        assert!(err.glyph_id().to_u32() >= 169);
+    }
+}
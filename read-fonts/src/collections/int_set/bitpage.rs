@@ -184,6 +184,22 @@ impl BitPage {
         a.process(b, |a, b| a & !b)
     }
 
+    /// Returns true if `a` and `b` have at least one member in common.
+    pub(crate) fn intersects(a: &BitPage, b: &BitPage) -> bool {
+        a.storage
+            .iter()
+            .zip(b.storage.iter())
+            .any(|(a, b)| a & b != 0)
+    }
+
+    /// Returns true if `a` has at least one member which is not in `b`.
+    pub(crate) fn has_members_not_in(a: &BitPage, b: &BitPage) -> bool {
+        a.storage
+            .iter()
+            .zip(b.storage.iter())
+            .any(|(a, b)| a & !b != 0)
+    }
+
     fn process<Op>(&self, other: &BitPage, op: Op) -> BitPage
     where
         Op: Fn(Element, Element) -> Element,
@@ -787,6 +803,31 @@ mod test {
         assert_eq!(BitPage::intersect(&c, &b), d);
     }
 
+    #[test]
+    fn intersects() {
+        let a = BitPage::new_zeroes();
+        let b = BitPage::from_iter([32, 400]);
+        let c = BitPage::from_iter([32, 200]);
+        let d = BitPage::from_iter([200]);
+
+        assert!(!BitPage::intersects(&a, &b));
+        assert!(!BitPage::intersects(&b, &a));
+        assert!(BitPage::intersects(&b, &c));
+        assert!(!BitPage::intersects(&b, &d));
+    }
+
+    #[test]
+    fn has_members_not_in() {
+        let a = BitPage::new_zeroes();
+        let b = BitPage::from_iter([32, 400]);
+        let c = BitPage::from_iter([32, 200, 400]);
+
+        assert!(!BitPage::has_members_not_in(&a, &b));
+        assert!(BitPage::has_members_not_in(&b, &a));
+        assert!(!BitPage::has_members_not_in(&b, &c));
+        assert!(BitPage::has_members_not_in(&c, &b));
+    }
+
     #[test]
     fn subtract() {
         let a = BitPage::new_zeroes();
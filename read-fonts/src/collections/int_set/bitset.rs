@@ -203,6 +203,56 @@ impl BitSet {
         self.process(|a, b| BitPage::subtract(b, a), other);
     }
 
+    /// Returns true if self and other have at least one member in common.
+    ///
+    /// Only pages present in both sets are compared, so this never visits individual members.
+    pub(crate) fn intersects(&self, other: &BitSet) -> bool {
+        self.any_page_pair(other, false, BitPage::intersects)
+    }
+
+    /// Returns true if self has at least one member which is not in other.
+    pub(crate) fn has_members_not_in(&self, other: &BitSet) -> bool {
+        self.any_page_pair(other, true, BitPage::has_members_not_in)
+    }
+
+    /// Walks the pages of self and other in major value order, returning true if `pred` is true
+    /// for any pair of pages with the same major value.
+    ///
+    /// Pages of self that have no counterpart in other satisfy the check when `unpaired_left` is
+    /// true and they are non-empty.
+    fn any_page_pair<Pred>(&self, other: &BitSet, unpaired_left: bool, pred: Pred) -> bool
+    where
+        Pred: Fn(&BitPage, &BitPage) -> bool,
+    {
+        let mut a_pages = self.iter_pages().peekable();
+        let mut b_pages = other.iter_pages().peekable();
+        while let Some((a_major, a_page)) = a_pages.peek().copied() {
+            let Some((b_major, b_page)) = b_pages.peek().copied() else {
+                return unpaired_left && a_pages.any(|(_, page)| !page.is_empty());
+            };
+
+            match a_major.cmp(&b_major) {
+                Ordering::Equal => {
+                    if pred(a_page, b_page) {
+                        return true;
+                    }
+                    a_pages.next();
+                    b_pages.next();
+                }
+                Ordering::Less => {
+                    if unpaired_left && !a_page.is_empty() {
+                        return true;
+                    }
+                    a_pages.next();
+                }
+                Ordering::Greater => {
+                    b_pages.next();
+                }
+            }
+        }
+        false
+    }
+
     pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = u32> + '_ {
         self.iter_non_empty_pages().flat_map(|(major, page)| {
             let base = Self::major_start(major);
@@ -1154,6 +1204,42 @@ mod test {
         check_process([5, 1000], [5], [1000], |a, b| a.subtract(b));
     }
 
+    #[test]
+    fn intersects() {
+        let check = |a: &[u32], b: &[u32], expected: bool| {
+            let a: BitSet = a.iter().copied().collect();
+            let b: BitSet = b.iter().copied().collect();
+            assert_eq!(a.intersects(&b), expected, "{a:?} intersects {b:?}");
+            assert_eq!(b.intersects(&a), expected, "{b:?} intersects {a:?}");
+        };
+        check(&[], &[], false);
+        check(&[], &[5], false);
+        check(&[1, 5, 9], &[5, 7], true);
+        check(&[1, 5, 9], &[4, 7], false);
+        check(&[1, 1000, 2000], &[1001, 5000], false);
+        check(&[1, 1000, 2000], &[3, 2000], true);
+    }
+
+    #[test]
+    fn has_members_not_in() {
+        let check = |a: &[u32], b: &[u32], expected: bool| {
+            let a: BitSet = a.iter().copied().collect();
+            let b: BitSet = b.iter().copied().collect();
+            assert_eq!(a.has_members_not_in(&b), expected, "{a:?} - {b:?}");
+        };
+        check(&[], &[5], false);
+        check(&[5], &[], true);
+        check(&[5, 1000], &[5, 1000, 2000], false);
+        check(&[5, 1000], &[5], true);
+        check(&[5, 3000], &[5, 1000], true);
+
+        // empty pages on the left hand side don't count as members.
+        let mut a: BitSet = [5, 3000].into_iter().collect();
+        a.remove(3000);
+        let b: BitSet = [5].into_iter().collect();
+        assert!(!a.has_members_not_in(&b));
+    }
+
     #[test]
     fn reversed_subtract() {
         check_process([], [5], [5], |a, b| a.reversed_subtract(b));
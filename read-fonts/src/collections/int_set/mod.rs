@@ -21,6 +21,7 @@ mod bitpage;
 mod bitset;
 mod input_bit_stream;
 mod output_bit_stream;
+pub mod serialization;
 pub mod sparse_bit_set;
 
 use bitset::BitSet;
@@ -313,6 +314,19 @@ impl<T: Domain> IntSet<T> {
         }
     }
 
+    /// Sets the members of this set to the members of self which are not in other.
+    pub fn subtract(&mut self, other: &IntSet<T>) {
+        match (&mut self.0, &other.0) {
+            (Membership::Inclusive(a), Membership::Inclusive(b)) => a.subtract(b),
+            (Membership::Inclusive(a), Membership::Exclusive(b)) => a.intersect(b),
+            (Membership::Exclusive(a), Membership::Inclusive(b)) => a.union(b),
+            (Membership::Exclusive(a), Membership::Exclusive(b)) => {
+                a.reversed_subtract(b);
+                self.invert();
+            }
+        }
+    }
+
     /// Returns true if this set contains at least one element in 'range'.
     pub fn intersects_range(&self, range: RangeInclusive<T>) -> bool {
         let domain_min = T::ordered_values()
@@ -343,8 +357,18 @@ impl<T: Domain> IntSet<T> {
 
     /// Returns true if this set contains at least one element in 'other'.
     pub fn intersects_set(&self, other: &IntSet<T>) -> bool {
-        // Iterate the smaller set and check for member ship in the larger set
-        // Estimate the true size as the number of pages.
+        // Whenever at least one side is inclusive membership can be checked page by page.
+        match (&self.0, &other.0) {
+            (Membership::Inclusive(a), Membership::Inclusive(b)) => return a.intersects(b),
+            (Membership::Inclusive(a), Membership::Exclusive(b))
+            | (Membership::Exclusive(b), Membership::Inclusive(a)) => {
+                return a.has_members_not_in(b)
+            }
+            (Membership::Exclusive(_), Membership::Exclusive(_)) => {}
+        }
+
+        // Both sets are inverted: iterate the smaller set and check for membership in the larger
+        // set. Estimate the true size as the number of pages.
         let (a, b) = match (&self.0, &other.0) {
             (
                 Membership::Inclusive(us) | Membership::Exclusive(us),
@@ -590,6 +614,30 @@ impl<T: Domain, const N: usize> From<[T; N]> for IntSet<T> {
     }
 }
 
+macro_rules! set_op_impls {
+    ($op:ident, $op_fn:ident, $assign:ident, $assign_fn:ident, $method:ident) => {
+        impl<T: Domain> std::ops::$op<&IntSet<T>> for &IntSet<T> {
+            type Output = IntSet<T>;
+
+            fn $op_fn(self, rhs: &IntSet<T>) -> IntSet<T> {
+                let mut out = IntSet(self.0.clone(), PhantomData);
+                out.$method(rhs);
+                out
+            }
+        }
+
+        impl<T: Domain> std::ops::$assign<&IntSet<T>> for IntSet<T> {
+            fn $assign_fn(&mut self, rhs: &IntSet<T>) {
+                self.$method(rhs);
+            }
+        }
+    };
+}
+
+set_op_impls!(BitOr, bitor, BitOrAssign, bitor_assign, union);
+set_op_impls!(BitAnd, bitand, BitAndAssign, bitand_assign, intersect);
+set_op_impls!(Sub, sub, SubAssign, sub_assign, subtract);
+
 impl<T> Display for IntSet<T>
 where
     T: Domain + Display,
@@ -2034,16 +2082,88 @@ mod test {
         );
     }
 
+    fn check_subtract(a: &SetOpInput, b: &SetOpInput) {
+        let x = 13;
+        let mut set_a = a.to_set(x);
+        let set_b = b.to_set(x);
+
+        let should_contain_x = a.has_x && !b.has_x;
+        set_a.subtract(&set_b);
+
+        assert_eq!(
+            set_a.contains(x),
+            should_contain_x,
+            "{}",
+            set_operation_test_message(a, b, "subtract", should_contain_x)
+        );
+    }
+
+    fn check_intersects_set(a: &SetOpInput, b: &SetOpInput) {
+        let x = 13;
+        let set_a = a.to_set(x);
+        let set_b = b.to_set(x);
+
+        // Inverted sets share all values other than x.
+        let expected = (a.has_x && b.has_x) || (a.inverted && b.inverted);
+        assert_eq!(
+            set_a.intersects_set(&set_b),
+            expected,
+            "{}",
+            set_operation_test_message(a, b, "intersects", expected)
+        );
+    }
+
     #[test]
     fn set_operations() {
         for a in SetOpInput::get_all_inputs() {
             for b in SetOpInput::get_all_inputs() {
                 check_union(&a, &b);
                 check_intersect(&a, &b);
+                check_subtract(&a, &b);
+                check_intersects_set(&a, &b);
             }
         }
     }
 
+    #[test]
+    fn set_operators() {
+        let a = IntSet::from([1u32, 5, 6, 1000]);
+        let b = IntSet::from([5u32, 7, 1000, 2000]);
+
+        assert_eq!(&a | &b, IntSet::from([1u32, 5, 6, 7, 1000, 2000]));
+        assert_eq!(&a & &b, IntSet::from([5u32, 1000]));
+        assert_eq!(&a - &b, IntSet::from([1u32, 6]));
+        assert_eq!(&b - &a, IntSet::from([7u32, 2000]));
+
+        let mut not_b = b.clone();
+        not_b.invert();
+        assert_eq!(&a - &not_b, &a & &b);
+        assert_eq!(&not_b - &a, {
+            let mut expected = &a | &b;
+            expected.invert();
+            expected
+        });
+
+        let mut c = a.clone();
+        c |= &b;
+        c -= &IntSet::from([6u32, 7]);
+        c &= &IntSet::from([1u32, 6, 7, 2000]);
+        assert_eq!(c, IntSet::from([1u32, 2000]));
+    }
+
+    #[test]
+    fn subtract_inverted() {
+        let mut a = IntSet::<u32>::all();
+        a.remove_range(10..=20);
+        let mut b = IntSet::<u32>::all();
+        b.remove_range(0..=15);
+
+        // (all - [10, 20]) - (all - [0, 15]) = [0, 9]
+        a.subtract(&b);
+        assert!(!a.is_inverted());
+        assert_eq!(a.iter_ranges().collect::<Vec<_>>(), vec![0..=9]);
+    }
+
     #[test]
     fn inverted() {
         let mut set = IntSet::<u32>::empty();
@@ -0,0 +1,228 @@
+//! A compact, versioned binary serialization of [`IntSet`]'s.
+//!
+//! This is intended to provide a canonical format for persisting sets (for example
+//! glyph coverage of cached shaping results, or IFT client state) and reloading
+//! them later.
+//!
+//! The format is:
+//!
+//! | Size     | Field                                                              |
+//! |----------|--------------------------------------------------------------------|
+//! | 1 byte   | format version, currently [`SERIALIZATION_VERSION`]                |
+//! | 1 byte   | flags, bit 0 is set if the set is inverted. Other bits must be 0.  |
+//! | variable | [sparse bit set] encoding of the underlying `u32` values           |
+//!
+//! For inverted sets the sparse bit set contains the values which are *not* members of
+//! the set.
+//!
+//! [sparse bit set]: https://w3c.github.io/IFT/Overview.html#sparse-bit-set-decoding
+
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+
+use super::{BitSet, Domain, InDomain, IntSet, Membership};
+
+/// The current version of the serialization format.
+pub const SERIALIZATION_VERSION: u8 = 1;
+
+const INVERTED_FLAG: u8 = 0b0000_0001;
+
+/// An error that occurred while deserializing an [`IntSet`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeserializationError {
+    /// The data was too short to contain a header.
+    MissingHeader,
+    /// The data was written by an unsupported version of the format.
+    UnsupportedVersion(u8),
+    /// The header contained flag bits which are not defined.
+    InvalidFlags(u8),
+    /// The sparse bit set encoding was malformed or had trailing data.
+    InvalidBitSet,
+    /// The encoded set contained a value outside of the domain of the set type.
+    ValueOutOfDomain(u32),
+}
+
+impl Error for DeserializationError {}
+
+impl fmt::Display for DeserializationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingHeader => write!(f, "The input data was too short to contain a header."),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported serialization format version {version}.")
+            }
+            Self::InvalidFlags(flags) => write!(f, "Invalid header flags 0x{flags:02x}."),
+            Self::InvalidBitSet => write!(f, "The encoded sparse bit set is invalid."),
+            Self::ValueOutOfDomain(value) => {
+                write!(f, "Encoded value {value} is not in the domain of the set.")
+            }
+        }
+    }
+}
+
+impl<T: Domain> IntSet<T> {
+    /// Serialize this set to a compact, versioned binary format.
+    ///
+    /// The set can be restored with [`IntSet::deserialize`]. See the
+    /// [module docs](self) for a description of the format.
+    pub fn serialize(&self) -> Vec<u8> {
+        let (flags, bitset) = match &self.0 {
+            Membership::Inclusive(s) => (0, s),
+            Membership::Exclusive(s) => (INVERTED_FLAG, s),
+        };
+        let values = IntSet::<u32>::from_bitset(bitset.clone());
+        let mut out = vec![SERIALIZATION_VERSION, flags];
+        out.extend(values.to_sparse_bit_set());
+        out
+    }
+
+    /// Restore a set previously written by [`IntSet::serialize`].
+    pub fn deserialize(data: &[u8]) -> Result<IntSet<T>, DeserializationError> {
+        let [version, flags, bit_set @ ..] = data else {
+            return Err(DeserializationError::MissingHeader);
+        };
+        if *version != SERIALIZATION_VERSION {
+            return Err(DeserializationError::UnsupportedVersion(*version));
+        }
+        if flags & !INVERTED_FLAG != 0 {
+            return Err(DeserializationError::InvalidFlags(*flags));
+        }
+
+        let (values, remaining) = IntSet::<u32>::from_sparse_bit_set_bounded(bit_set, 0, u32::MAX)
+            .map_err(|_| DeserializationError::InvalidBitSet)?;
+        if !remaining.is_empty() {
+            return Err(DeserializationError::InvalidBitSet);
+        }
+        let bitset = match values.0 {
+            Membership::Inclusive(s) => s,
+            Membership::Exclusive(_) => unreachable!("decoded sets are always inclusive"),
+        };
+        check_domain::<T>(&bitset)?;
+
+        let membership = if flags & INVERTED_FLAG != 0 {
+            Membership::Exclusive(bitset)
+        } else {
+            Membership::Inclusive(bitset)
+        };
+        Ok(IntSet(membership, PhantomData::<T>))
+    }
+}
+
+fn check_domain<T: Domain>(bitset: &BitSet) -> Result<(), DeserializationError> {
+    if T::is_continuous() {
+        let max = T::ordered_values().next_back().unwrap_or_default();
+        return match bitset.iter().next_back() {
+            Some(last) if last > max => Err(DeserializationError::ValueOutOfDomain(last)),
+            _ => Ok(()),
+        };
+    }
+    // for sparse domains check that every value round trips through T
+    match bitset
+        .iter()
+        .find(|v| T::from_u32(InDomain(*v)).to_u32() != *v)
+    {
+        Some(v) => Err(DeserializationError::ValueOutOfDomain(v)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use font_types::{GlyphId, GlyphId16};
+
+    #[test]
+    fn round_trip_empty() {
+        let set = IntSet::<u32>::empty();
+        let bytes = set.serialize();
+        assert_eq!(&bytes[..2], &[SERIALIZATION_VERSION, 0]);
+        assert_eq!(IntSet::<u32>::deserialize(&bytes).unwrap(), set);
+    }
+
+    #[test]
+    fn round_trip_u32() {
+        let mut set: IntSet<u32> = [2, 33, 323, 70_000, u32::MAX].into();
+        set.insert_range(1000..=5000);
+        let bytes = set.serialize();
+        assert_eq!(IntSet::<u32>::deserialize(&bytes).unwrap(), set);
+    }
+
+    #[test]
+    fn round_trip_glyph_ids() {
+        let mut set: IntSet<GlyphId> = [GlyphId::new(5), GlyphId::new(100_000)].into();
+        set.insert_range(GlyphId::new(20)..=GlyphId::new(300));
+        let bytes = set.serialize();
+        assert_eq!(IntSet::<GlyphId>::deserialize(&bytes).unwrap(), set);
+    }
+
+    #[test]
+    fn round_trip_inverted() {
+        let mut set: IntSet<GlyphId16> = [GlyphId16::new(7), GlyphId16::new(8)].into();
+        set.invert();
+        let bytes = set.serialize();
+        assert_eq!(bytes[1], INVERTED_FLAG);
+
+        let restored = IntSet::<GlyphId16>::deserialize(&bytes).unwrap();
+        assert!(restored.is_inverted());
+        assert!(!restored.contains(GlyphId16::new(7)));
+        assert!(restored.contains(GlyphId16::new(9)));
+        assert_eq!(restored, set);
+    }
+
+    #[test]
+    fn compact() {
+        let mut set = IntSet::<u32>::empty();
+        set.insert_range(0..=1023);
+        assert_eq!(set.serialize().len(), 2 + set.to_sparse_bit_set().len());
+        assert!(set.serialize().len() < 8);
+    }
+
+    #[test]
+    fn invalid_header() {
+        assert_eq!(
+            IntSet::<u32>::deserialize(&[]),
+            Err(DeserializationError::MissingHeader)
+        );
+        assert_eq!(
+            IntSet::<u32>::deserialize(&[SERIALIZATION_VERSION]),
+            Err(DeserializationError::MissingHeader)
+        );
+        assert_eq!(
+            IntSet::<u32>::deserialize(&[2, 0, 0]),
+            Err(DeserializationError::UnsupportedVersion(2))
+        );
+        assert_eq!(
+            IntSet::<u32>::deserialize(&[SERIALIZATION_VERSION, 0b10, 0]),
+            Err(DeserializationError::InvalidFlags(0b10))
+        );
+    }
+
+    #[test]
+    fn invalid_bit_set() {
+        let set: IntSet<u32> = [2, 33, 323].into();
+        let bytes = set.serialize();
+        assert_eq!(
+            IntSet::<u32>::deserialize(&bytes[..bytes.len() - 1]),
+            Err(DeserializationError::InvalidBitSet)
+        );
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            IntSet::<u32>::deserialize(&trailing),
+            Err(DeserializationError::InvalidBitSet)
+        );
+    }
+
+    #[test]
+    fn out_of_domain() {
+        let set: IntSet<u32> = [5, 70_000].into();
+        let bytes = set.serialize();
+        assert_eq!(
+            IntSet::<GlyphId16>::deserialize(&bytes),
+            Err(DeserializationError::ValueOutOfDomain(70_000))
+        );
+        assert!(IntSet::<GlyphId>::deserialize(&bytes).is_ok());
+    }
+}
@@ -0,0 +1,191 @@
+//! Handling of glyph identifiers that exceed the glyph count of a font.
+//!
+//! Many tables contain glyph identifiers that are not constrained by the
+//! binary format to be less than `maxp.numGlyphs`. A [`GlyphIdValidator`]
+//! applies a [`GlyphIdPolicy`] selected by the caller so that read paths
+//! which produce glyph identifiers handle these consistently.
+
+use core::cell::Cell;
+
+use types::GlyphId;
+
+use crate::{ReadError, TableProvider};
+
+/// Policy for handling glyph identifiers that are out of range for a font.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GlyphIdPolicy {
+    /// Out of range glyph identifiers produce an [`InvalidGlyphId`] error.
+    Error,
+    /// Out of range glyph identifiers are replaced with
+    /// [`GlyphId::NOTDEF`].
+    #[default]
+    Clamp,
+    /// Out of range glyph identifiers are passed through unchanged and
+    /// recorded so they can be detected with
+    /// [`GlyphIdValidator::out_of_range_count`].
+    Flag,
+}
+
+/// An error for a glyph identifier that is out of range for a font, produced
+/// by [`GlyphIdPolicy::Error`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InvalidGlyphId(GlyphId);
+
+impl InvalidGlyphId {
+    /// Returns the out of range glyph identifier.
+    pub fn glyph_id(&self) -> GlyphId {
+        self.0
+    }
+}
+
+impl std::fmt::Display for InvalidGlyphId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Glyph identifier {} is out of range", self.0.to_u32())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidGlyphId {}
+
+/// Applies a [`GlyphIdPolicy`] to glyph identifiers read from a font.
+#[derive(Clone, Debug)]
+pub struct GlyphIdValidator {
+    num_glyphs: u32,
+    policy: GlyphIdPolicy,
+    out_of_range_count: Cell<u32>,
+}
+
+impl GlyphIdValidator {
+    /// Creates a new validator for a font with the given number of glyphs.
+    pub fn new(num_glyphs: u32, policy: GlyphIdPolicy) -> Self {
+        Self {
+            num_glyphs,
+            policy,
+            out_of_range_count: Cell::new(0),
+        }
+    }
+
+    /// Creates a new validator using the glyph count from the `maxp` table
+    /// of the given font.
+    pub fn from_font<'a>(
+        font: &impl TableProvider<'a>,
+        policy: GlyphIdPolicy,
+    ) -> Result<Self, ReadError> {
+        Ok(Self::new(font.maxp()?.num_glyphs() as u32, policy))
+    }
+
+    /// Returns the number of glyphs in the font.
+    pub fn num_glyphs(&self) -> u32 {
+        self.num_glyphs
+    }
+
+    /// Returns the policy applied to out of range glyph identifiers.
+    pub fn policy(&self) -> GlyphIdPolicy {
+        self.policy
+    }
+
+    /// Returns true if the glyph identifier is in range for the font.
+    pub fn is_valid(&self, glyph_id: GlyphId) -> bool {
+        glyph_id.to_u32() < self.num_glyphs
+    }
+
+    /// Returns the number of out of range glyph identifiers that have been
+    /// encountered by this validator.
+    pub fn out_of_range_count(&self) -> u32 {
+        self.out_of_range_count.get()
+    }
+
+    /// Applies the policy to the given glyph identifier.
+    pub fn validate(&self, glyph_id: GlyphId) -> Result<GlyphId, InvalidGlyphId> {
+        if self.is_valid(glyph_id) {
+            return Ok(glyph_id);
+        }
+        self.out_of_range_count
+            .set(self.out_of_range_count.get().saturating_add(1));
+        match self.policy {
+            GlyphIdPolicy::Error => Err(InvalidGlyphId(glyph_id)),
+            GlyphIdPolicy::Clamp => Ok(GlyphId::NOTDEF),
+            GlyphIdPolicy::Flag => Ok(glyph_id),
+        }
+    }
+
+    /// Applies the policy to every glyph identifier in the set.
+    ///
+    /// With [`GlyphIdPolicy::Clamp`], out of range identifiers are removed
+    /// and replaced with [`GlyphId::NOTDEF`].
+    #[cfg(feature = "std")]
+    pub fn validate_set(
+        &self,
+        glyph_set: &mut crate::collections::IntSet<GlyphId>,
+    ) -> Result<(), InvalidGlyphId> {
+        let Some(last) = glyph_set.last() else {
+            return Ok(());
+        };
+        if self.is_valid(last) {
+            return Ok(());
+        }
+        let first_invalid = GlyphId::new(self.num_glyphs);
+        let mut invalid = glyph_set.clone();
+        if let Some(last_valid) = self.num_glyphs.checked_sub(1) {
+            invalid.remove_range(GlyphId::NOTDEF..=GlyphId::new(last_valid));
+        }
+        let invalid_count = u32::try_from(invalid.len()).unwrap_or(u32::MAX);
+        self.out_of_range_count
+            .set(self.out_of_range_count.get().saturating_add(invalid_count));
+        match self.policy {
+            GlyphIdPolicy::Error => return Err(InvalidGlyphId(last)),
+            GlyphIdPolicy::Clamp => {
+                glyph_set.remove_range(first_invalid..=GlyphId::new(u32::MAX));
+                glyph_set.insert(GlyphId::NOTDEF);
+            }
+            GlyphIdPolicy::Flag => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        let error = GlyphIdValidator::new(10, GlyphIdPolicy::Error);
+        assert_eq!(error.validate(GlyphId::new(9)), Ok(GlyphId::new(9)));
+        assert_eq!(
+            error.validate(GlyphId::new(10)),
+            Err(InvalidGlyphId(GlyphId::new(10)))
+        );
+        let clamp = GlyphIdValidator::new(10, GlyphIdPolicy::Clamp);
+        assert_eq!(clamp.validate(GlyphId::new(20)), Ok(GlyphId::NOTDEF));
+        let flag = GlyphIdValidator::new(10, GlyphIdPolicy::Flag);
+        assert_eq!(flag.validate(GlyphId::new(5)), Ok(GlyphId::new(5)));
+        assert_eq!(flag.out_of_range_count(), 0);
+        assert_eq!(flag.validate(GlyphId::new(20)), Ok(GlyphId::new(20)));
+        assert_eq!(flag.out_of_range_count(), 1);
+    }
+
+    #[test]
+    fn validate_set() {
+        use crate::collections::IntSet;
+        let glyphs: IntSet<GlyphId> = [1, 5, 10, 12].map(GlyphId::new).into();
+
+        let error = GlyphIdValidator::new(10, GlyphIdPolicy::Error);
+        assert_eq!(
+            error.validate_set(&mut glyphs.clone()),
+            Err(InvalidGlyphId(GlyphId::new(12)))
+        );
+        assert_eq!(error.out_of_range_count(), 2);
+
+        let clamp = GlyphIdValidator::new(10, GlyphIdPolicy::Clamp);
+        let mut clamped = glyphs.clone();
+        clamp.validate_set(&mut clamped).unwrap();
+        assert_eq!(clamped, [0, 1, 5].map(GlyphId::new).into());
+
+        let flag = GlyphIdValidator::new(10, GlyphIdPolicy::Flag);
+        let mut flagged = glyphs.clone();
+        flag.validate_set(&mut flagged).unwrap();
+        assert_eq!(flagged, glyphs);
+        assert_eq!(flag.out_of_range_count(), 2);
+    }
+}
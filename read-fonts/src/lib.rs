@@ -70,17 +70,29 @@ extern crate std;
 extern crate core as std;
 
 pub mod array;
+pub mod checksum;
+#[cfg(feature = "std")]
+pub mod closure;
 #[cfg(feature = "std")]
 pub mod collections;
 mod font_data;
+mod glyph_id_policy;
 mod offset;
 mod offset_array;
 mod read;
+#[cfg(feature = "std")]
+pub mod sanitize;
+#[cfg(any(feature = "woff", feature = "woff2"))]
+mod sfnt_writer;
 mod table_provider;
 mod table_ref;
 pub mod tables;
 #[cfg(feature = "experimental_traverse")]
 pub mod traversal;
+#[cfg(feature = "woff")]
+pub mod woff;
+#[cfg(feature = "woff2")]
+pub mod woff2;
 
 #[cfg(any(test, feature = "codegen_test"))]
 pub mod codegen_test;
@@ -90,11 +102,16 @@ pub mod codegen_test;
 #[cfg(feature = "std")]
 pub mod test_helpers;
 
+#[cfg(test)]
+#[path = "tests/robustness.rs"]
+mod robustness_tests;
+
 pub use font_data::FontData;
+pub use glyph_id_policy::{GlyphIdPolicy, GlyphIdValidator, InvalidGlyphId};
 pub use offset::{Offset, ResolveNullableOffset, ResolveOffset};
 pub use offset_array::{ArrayOfNullableOffsets, ArrayOfOffsets};
 pub use read::{ComputeSize, FontRead, FontReadWithArgs, ReadArgs, ReadError, VarSize};
-pub use table_provider::{TableProvider, TopLevelTable};
+pub use table_provider::{TableOverlay, TableProvider, TopLevelTable};
 pub use table_ref::TableRef;
 
 /// Public re-export of the font-types crate.
@@ -310,18 +327,94 @@ impl<'a> FontRef<'a> {
         }
     }
 
+    /// Returns an iterator over the fonts in the given font or font
+    /// collection file.
+    ///
+    /// This yields a single font for ttf/otf files and each font in the
+    /// collection for ttc files. If the data is neither, the iterator yields
+    /// a single error.
+    pub fn fonts(
+        data: &'a [u8],
+    ) -> impl Iterator<Item = Result<FontRef<'a>, ReadError>> + 'a + Clone {
+        let (file, error) = match FileRef::new(data) {
+            Ok(file) => (Some(file), None),
+            Err(e) => (None, Some(Err(e))),
+        };
+        file.into_iter().flat_map(|file| file.fonts()).chain(error)
+    }
+
     /// Returns the data for the table with the specified tag, if present.
     pub fn table_data(&self, tag: Tag) -> Option<FontData<'a>> {
-        self.table_directory
-            .table_records()
+        self.table_record(tag)
+            .and_then(|record| record_data(self.data, record))
+    }
+
+    /// Returns true if the table with the specified tag is present in both
+    /// fonts and refers to the same data.
+    ///
+    /// Fonts in a collection frequently share tables. This can be used to
+    /// avoid processing the same table more than once when working with
+    /// all of the fonts in a collection.
+    pub fn shares_table(&self, other: &FontRef, tag: Tag) -> bool {
+        let (Some(record), Some(other_record)) = (self.table_record(tag), other.table_record(tag))
+        else {
+            return false;
+        };
+        std::ptr::eq(self.data.as_bytes(), other.data.as_bytes())
+            && record.offset() == other_record.offset()
+            && record.length() == other_record.length()
+    }
+
+    fn table_record(&self, tag: Tag) -> Option<&'a TableRecord> {
+        let records = self.table_directory.table_records();
+        records
             .binary_search_by(|rec| rec.tag.get().cmp(&tag))
             .ok()
-            .and_then(|idx| self.table_directory.table_records().get(idx))
-            .and_then(|record| {
-                let start = Offset32::new(record.offset()).non_null()?;
-                let len = record.length() as usize;
-                self.data.slice(start..start + len)
+            .and_then(|idx| records.get(idx))
+    }
+
+    /// Returns an iterator over all tables in the table directory, in
+    /// directory order.
+    ///
+    /// Unlike [`FontRef::table_data`], this yields every record, including
+    /// those with offsets or lengths that fall outside of the font data.
+    pub fn tables(&self) -> impl Iterator<Item = RawTable<'a>> + 'a + Clone {
+        let data = self.data;
+        self.table_directory
+            .table_records()
+            .iter()
+            .map(move |record| RawTable {
+                record: *record,
+                data: record_data(data, record),
+            })
+    }
+
+    /// Returns an iterator over the tags of all tables with records that
+    /// extend beyond the end of the font data.
+    pub fn out_of_bounds_tables(&self) -> impl Iterator<Item = Tag> + 'a + Clone {
+        let data_len = self.data.len() as u64;
+        self.table_directory
+            .table_records()
+            .iter()
+            .filter(move |record| record_range(record).end > data_len)
+            .map(|record| record.tag())
+    }
+
+    /// Returns an iterator over pairs of tags for tables whose records
+    /// overlap.
+    ///
+    /// Empty tables never overlap. This compares every pair of records so it
+    /// is quadratic in the number of tables.
+    pub fn overlapping_tables(&self) -> impl Iterator<Item = (Tag, Tag)> + 'a + Clone {
+        let records = self.table_directory.table_records();
+        records.iter().enumerate().flat_map(move |(i, a)| {
+            let a_range = record_range(a);
+            records[i + 1..].iter().filter_map(move |b| {
+                let b_range = record_range(b);
+                (a_range.start < b_range.end && b_range.start < a_range.end)
+                    .then(|| (a.tag(), b.tag()))
             })
+        })
     }
 
     fn with_table_directory(
@@ -341,6 +434,54 @@ impl<'a> FontRef<'a> {
     }
 }
 
+/// A table in a font along with the metadata from its table record.
+#[derive(Clone)]
+pub struct RawTable<'a> {
+    record: TableRecord,
+    data: Option<FontData<'a>>,
+}
+
+impl<'a> RawTable<'a> {
+    /// Returns the table tag.
+    pub fn tag(&self) -> Tag {
+        self.record.tag()
+    }
+
+    /// Returns the checksum stored in the table record.
+    pub fn checksum(&self) -> u32 {
+        self.record.checksum()
+    }
+
+    /// Returns the offset of the table from the beginning of the font data.
+    pub fn offset(&self) -> u32 {
+        self.record.offset()
+    }
+
+    /// Returns the length of the table in bytes.
+    pub fn length(&self) -> u32 {
+        self.record.length()
+    }
+
+    /// Returns the table data.
+    ///
+    /// This is `None` if the offset is null or if the record extends beyond
+    /// the end of the font data.
+    pub fn data(&self) -> Option<FontData<'a>> {
+        self.data
+    }
+}
+
+fn record_data<'a>(data: FontData<'a>, record: &TableRecord) -> Option<FontData<'a>> {
+    let start = Offset32::new(record.offset()).non_null()?;
+    let len = record.length() as usize;
+    data.slice(start..start.checked_add(len)?)
+}
+
+fn record_range(record: &TableRecord) -> core::ops::Range<u64> {
+    let start = record.offset() as u64;
+    start..start + record.length() as u64
+}
+
 impl<'a> TableProvider<'a> for FontRef<'a> {
     fn data_for_tag(&self, tag: Tag) -> Option<FontData<'a>> {
         self.table_data(tag)
@@ -351,13 +492,88 @@ impl<'a> TableProvider<'a> for FontRef<'a> {
 mod tests {
     use font_test_data::{ttc::TTC, AHEM};
 
-    use crate::FileRef;
+    use crate::{test_helpers::BeBuffer, FileRef, FontRef, TT_SFNT_VERSION};
+    use types::Tag;
 
     #[test]
     fn file_ref_non_collection() {
         assert!(matches!(FileRef::new(AHEM), Ok(FileRef::Font(_))));
     }
 
+    #[test]
+    fn raw_tables() {
+        let font = FontRef::new(AHEM).unwrap();
+        let tables: Vec<_> = font.tables().collect();
+        assert_eq!(tables.len(), font.table_directory.num_tables() as usize);
+        for table in &tables {
+            assert_eq!(
+                font.table_data(table.tag()).map(|data| data.as_bytes()),
+                table.data().map(|data| data.as_bytes())
+            );
+            assert_eq!(table.data().unwrap().len(), table.length() as usize);
+        }
+        assert_eq!(font.out_of_bounds_tables().count(), 0);
+        assert_eq!(font.overlapping_tables().count(), 0);
+    }
+
+    #[test]
+    fn bad_table_records() {
+        let mut buf = BeBuffer::new()
+            .push(TT_SFNT_VERSION)
+            .extend([4u16, 0, 0, 0]);
+        // (tag, offset, length)
+        let records = [
+            (b"aaaa", 76u32, 8u32),
+            (b"bbbb", 80, 8),
+            (b"cccc", 88, 0),
+            (b"dddd", 88, 16),
+        ];
+        for (tag, offset, length) in records {
+            buf = buf.push(Tag::new(tag)).extend([0u32, offset, length]);
+        }
+        let buf = buf.extend([0u8; 20]);
+        let font = FontRef::new(buf.as_slice()).unwrap();
+        assert_eq!(
+            font.overlapping_tables().collect::<Vec<_>>(),
+            [(Tag::new(b"aaaa"), Tag::new(b"bbbb"))]
+        );
+        assert_eq!(
+            font.out_of_bounds_tables().collect::<Vec<_>>(),
+            [Tag::new(b"dddd")]
+        );
+        let tables: Vec<_> = font.tables().collect();
+        assert_eq!(tables[1].offset(), 80);
+        assert_eq!(tables[1].length(), 8);
+        assert!(tables[1].data().is_some());
+        assert!(tables[3].data().is_none());
+    }
+
+    #[test]
+    fn fonts_iter() {
+        assert_eq!(FontRef::fonts(AHEM).count(), 1);
+        assert!(FontRef::fonts(AHEM).all(|font| font.is_ok()));
+        let fonts = FontRef::fonts(TTC).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(fonts.len(), 2);
+        let mut bad = FontRef::fonts(&[0u8; 4]);
+        assert!(bad.next().unwrap().is_err());
+        assert!(bad.next().is_none());
+    }
+
+    #[test]
+    fn collection_shared_tables() {
+        let first = FontRef::from_index(TTC, 0).unwrap();
+        let second = FontRef::from_index(TTC, 1).unwrap();
+        // all tables in this collection are shared
+        for table in first.tables() {
+            assert!(first.shares_table(&second, table.tag()));
+        }
+        assert!(!first.shares_table(&second, Tag::new(b"GSUB")));
+        // identical data in a different file is not shared
+        let copy = TTC.to_vec();
+        let second_copy = FontRef::from_index(&copy, 1).unwrap();
+        assert!(!first.shares_table(&second_copy, Tag::new(b"glyf")));
+    }
+
     #[test]
     fn file_ref_collection() {
         let Ok(FileRef::Collection(collection)) = FileRef::new(TTC) else {
@@ -0,0 +1,535 @@
+//! Strict structural validation of fonts.
+//!
+//! The parsers in this crate are lazy and tolerant: malformed data is only
+//! detected when it is accessed and is generally reported as an error at
+//! that point. Consumers that handle untrusted fonts often prefer to reject
+//! bad data up front, as done by the [OpenType Sanitizer]. The [`sanitize`]
+//! function performs a set of checks over the whole font and either rejects
+//! it or returns the set of tables that passed.
+//!
+//! The following are checked:
+//!
+//! * table records are sorted, within bounds and do not overlap
+//! * the `head` and `maxp` tables are present and well formed
+//! * the `hhea` and `hmtx` tables agree with the glyph count
+//! * the `loca` offsets are ascending and within the bounds of `glyf`
+//! * the ranges in each `cmap` subtable are ordered and do not overlap
+//! * all lookup and feature indices in `GSUB` and `GPOS` are in range and
+//!   contextual lookups do not recursively reference themselves
+//!
+//! Failures in the layout tables cause only those tables to be dropped.
+//! All other failures cause the font to be rejected.
+//!
+//! [OpenType Sanitizer]: https://github.com/khaledhosny/ots
+
+use types::Tag;
+
+use crate::{
+    tables::{
+        cmap::CmapSubtable,
+        gpos::PositionSubtables,
+        gsub::SubstitutionSubtables,
+        layout::{ChainedSequenceContext, FeatureList, ScriptList, SequenceContext},
+    },
+    FontData, FontRef, ReadError, TableOverlay, TableProvider,
+};
+
+const HEAD_MAGIC_NUMBER: u32 = 0x5F0F3CF5;
+
+/// The reason that a font or table was rejected by [`sanitize`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum SanitizeError {
+    /// A required table is missing.
+    MissingTable(Tag),
+    /// The record for the table extends beyond the end of the font data.
+    OutOfBounds(Tag),
+    /// The records for the two tables overlap.
+    Overlapping(Tag, Tag),
+    /// The table failed a consistency check.
+    Invalid {
+        /// Tag of the table.
+        tag: Tag,
+        /// Description of the failed check.
+        reason: &'static str,
+    },
+    /// The table could not be parsed.
+    Read {
+        /// Tag of the table.
+        tag: Tag,
+        /// The underlying error.
+        error: ReadError,
+    },
+}
+
+impl SanitizeError {
+    /// Returns the tag of the table that failed validation.
+    ///
+    /// For overlapping tables, this is the first of the two tags.
+    pub fn tag(&self) -> Tag {
+        match self {
+            Self::MissingTable(tag)
+            | Self::OutOfBounds(tag)
+            | Self::Overlapping(tag, _)
+            | Self::Invalid { tag, .. }
+            | Self::Read { tag, .. } => *tag,
+        }
+    }
+}
+
+/// The tables of a font that passed [`sanitize`].
+#[derive(Clone)]
+pub struct SanitizedFont<'a> {
+    tables: Vec<(Tag, FontData<'a>)>,
+    dropped: Vec<(Tag, Option<FontData<'a>>)>,
+    errors: Vec<SanitizeError>,
+}
+
+impl<'a> SanitizedFont<'a> {
+    /// Returns the tables that passed validation, sorted by tag.
+    pub fn tables(&self) -> &[(Tag, FontData<'a>)] {
+        &self.tables
+    }
+
+    /// Returns the reasons for each table that was dropped.
+    pub fn dropped(&self) -> &[SanitizeError] {
+        &self.errors
+    }
+
+    /// Returns a table provider for the given font that hides the dropped
+    /// tables.
+    pub fn overlay<P: TableProvider<'a>>(&'a self, font: P) -> TableOverlay<'a, P> {
+        TableOverlay::new(font, &self.dropped)
+    }
+}
+
+/// Validates the structure of the given font.
+///
+/// Returns the tables that passed validation or the reason that the font
+/// was rejected. See the [module documentation](self) for details.
+pub fn sanitize<'a>(font: &FontRef<'a>) -> Result<SanitizedFont<'a>, SanitizeError> {
+    check_table_directory(font)?;
+    let num_glyphs = check_head_and_maxp(font)?;
+    check_hmtx(font, num_glyphs)?;
+    check_glyf_and_loca(font, num_glyphs)?;
+    check_cmap(font)?;
+    let mut errors = Vec::new();
+    if let Err(error) = check_gsub(font) {
+        errors.push(error);
+    }
+    if let Err(error) = check_gpos(font) {
+        errors.push(error);
+    }
+    let is_dropped = |tag| errors.iter().any(|error| error.tag() == tag);
+    let tables = font
+        .tables()
+        .filter(|table| !is_dropped(table.tag()))
+        .filter_map(|table| Some((table.tag(), table.data()?)))
+        .collect();
+    let dropped = errors.iter().map(|error| (error.tag(), None)).collect();
+    Ok(SanitizedFont {
+        tables,
+        dropped,
+        errors,
+    })
+}
+
+fn check_table_directory(font: &FontRef) -> Result<(), SanitizeError> {
+    let records = font.table_directory.table_records();
+    if let Some(pair) = records
+        .windows(2)
+        .find(|pair| pair[0].tag() >= pair[1].tag())
+    {
+        return Err(SanitizeError::Invalid {
+            tag: pair[1].tag(),
+            reason: "table records are not sorted by tag",
+        });
+    }
+    if let Some(tag) = font.out_of_bounds_tables().next() {
+        return Err(SanitizeError::OutOfBounds(tag));
+    }
+    if let Some((a, b)) = font.overlapping_tables().next() {
+        return Err(SanitizeError::Overlapping(a, b));
+    }
+    Ok(())
+}
+
+fn check_head_and_maxp(font: &FontRef) -> Result<u16, SanitizeError> {
+    let head = read_required(Tag::new(b"head"), font.head())?;
+    let invalid_head = |reason| SanitizeError::Invalid {
+        tag: Tag::new(b"head"),
+        reason,
+    };
+    if head.magic_number() != HEAD_MAGIC_NUMBER {
+        return Err(invalid_head("invalid magic number"));
+    }
+    if !(16..=16384).contains(&head.units_per_em()) {
+        return Err(invalid_head("units per em out of range"));
+    }
+    if !(0..=1).contains(&head.index_to_loc_format()) {
+        return Err(invalid_head("invalid loca format"));
+    }
+    let maxp = read_required(Tag::new(b"maxp"), font.maxp())?;
+    Ok(maxp.num_glyphs())
+}
+
+fn check_hmtx(font: &FontRef, num_glyphs: u16) -> Result<(), SanitizeError> {
+    let tag = Tag::new(b"hmtx");
+    let has_hmtx = font.table_data(tag).is_some();
+    let Some(hhea) = read_optional(Tag::new(b"hhea"), font.hhea())? else {
+        return if has_hmtx {
+            Err(SanitizeError::MissingTable(Tag::new(b"hhea")))
+        } else {
+            Ok(())
+        };
+    };
+    let num_long_metrics = hhea.number_of_long_metrics();
+    if num_long_metrics > num_glyphs || (num_long_metrics == 0 && num_glyphs != 0) {
+        return Err(SanitizeError::Invalid {
+            tag: Tag::new(b"hhea"),
+            reason: "number of long metrics is inconsistent with glyph count",
+        });
+    }
+    read_required(tag, font.hmtx())?;
+    Ok(())
+}
+
+fn check_glyf_and_loca(font: &FontRef, num_glyphs: u16) -> Result<(), SanitizeError> {
+    let (glyf_tag, loca_tag) = (Tag::new(b"glyf"), Tag::new(b"loca"));
+    let glyf = font.table_data(glyf_tag);
+    let loca = read_optional(loca_tag, font.loca(None))?;
+    match (glyf, loca) {
+        (Some(glyf), Some(loca)) => loca
+            .validate(num_glyphs as u32, glyf.len())
+            .map_err(|error| match error {
+                ReadError::MalformedData(reason) => SanitizeError::Invalid {
+                    tag: loca_tag,
+                    reason,
+                },
+                error => SanitizeError::Read {
+                    tag: loca_tag,
+                    error,
+                },
+            }),
+        (Some(_), None) => Err(SanitizeError::MissingTable(loca_tag)),
+        (None, Some(_)) => Err(SanitizeError::MissingTable(glyf_tag)),
+        (None, None) => Ok(()),
+    }
+}
+
+fn check_cmap(font: &FontRef) -> Result<(), SanitizeError> {
+    let tag = Tag::new(b"cmap");
+    let Some(cmap) = read_optional(tag, font.cmap())? else {
+        return Ok(());
+    };
+    let invalid = |reason| SanitizeError::Invalid { tag, reason };
+    for record in cmap.encoding_records() {
+        let subtable = record
+            .subtable(cmap.offset_data())
+            .map_err(|error| SanitizeError::Read { tag, error })?;
+        let ranges_are_ordered = match &subtable {
+            CmapSubtable::Format4(cmap4) => {
+                if cmap4.end_code().last().map(|end| end.get()) != Some(0xFFFF) {
+                    return Err(invalid("format 4 subtable is missing final segment"));
+                }
+                ranges_are_ordered(
+                    cmap4
+                        .start_code()
+                        .iter()
+                        .zip(cmap4.end_code())
+                        .map(|(start, end)| (start.get() as u32, end.get() as u32)),
+                )
+            }
+            CmapSubtable::Format12(cmap12) => ranges_are_ordered(
+                cmap12
+                    .groups()
+                    .iter()
+                    .map(|group| (group.start_char_code(), group.end_char_code())),
+            ),
+            CmapSubtable::Format13(cmap13) => ranges_are_ordered(
+                cmap13
+                    .groups()
+                    .iter()
+                    .map(|group| (group.start_char_code(), group.end_char_code())),
+            ),
+            _ => true,
+        };
+        if !ranges_are_ordered {
+            return Err(invalid("subtable ranges are unordered or overlapping"));
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if each range is non-empty and starts after the end of the
+/// previous range.
+fn ranges_are_ordered(ranges: impl Iterator<Item = (u32, u32)>) -> bool {
+    let mut prev_end = None;
+    for (start, end) in ranges {
+        if start > end || prev_end.is_some_and(|prev_end| start <= prev_end) {
+            return false;
+        }
+        prev_end = Some(end);
+    }
+    true
+}
+
+fn check_gsub(font: &FontRef) -> Result<(), SanitizeError> {
+    let tag = Tag::new(b"GSUB");
+    let Some(gsub) = read_optional(tag, font.gsub())? else {
+        return Ok(());
+    };
+    let read_err = |error| SanitizeError::Read { tag, error };
+    let nested = gsub
+        .lookup_list()
+        .and_then(|list| {
+            list.lookups()
+                .iter()
+                .map(|lookup| {
+                    let mut nested = Vec::new();
+                    match lookup?.subtables()? {
+                        SubstitutionSubtables::Contextual(subtables) => {
+                            for subtable in subtables.iter() {
+                                add_context_lookups(&subtable?, &mut nested)?;
+                            }
+                        }
+                        SubstitutionSubtables::ChainContextual(subtables) => {
+                            for subtable in subtables.iter() {
+                                add_chain_context_lookups(&subtable?, &mut nested)?;
+                            }
+                        }
+                        _ => {}
+                    }
+                    Ok(nested)
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(read_err)?;
+    let script_list = gsub.script_list().map_err(read_err)?;
+    let feature_list = gsub.feature_list().map_err(read_err)?;
+    check_layout(tag, &script_list, &feature_list, &nested)
+}
+
+fn check_gpos(font: &FontRef) -> Result<(), SanitizeError> {
+    let tag = Tag::new(b"GPOS");
+    let Some(gpos) = read_optional(tag, font.gpos())? else {
+        return Ok(());
+    };
+    let read_err = |error| SanitizeError::Read { tag, error };
+    let nested = gpos
+        .lookup_list()
+        .and_then(|list| {
+            list.lookups()
+                .iter()
+                .map(|lookup| {
+                    let mut nested = Vec::new();
+                    match lookup?.subtables()? {
+                        PositionSubtables::Contextual(subtables) => {
+                            for subtable in subtables.iter() {
+                                add_context_lookups(&subtable?, &mut nested)?;
+                            }
+                        }
+                        PositionSubtables::ChainContextual(subtables) => {
+                            for subtable in subtables.iter() {
+                                add_chain_context_lookups(&subtable?, &mut nested)?;
+                            }
+                        }
+                        _ => {}
+                    }
+                    Ok(nested)
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(read_err)?;
+    let script_list = gpos.script_list().map_err(read_err)?;
+    let feature_list = gpos.feature_list().map_err(read_err)?;
+    check_layout(tag, &script_list, &feature_list, &nested)
+}
+
+/// Checks the feature and lookup indices of a layout table.
+///
+/// The `nested` slice contains the lookups referenced by each contextual
+/// lookup in the lookup list.
+fn check_layout(
+    tag: Tag,
+    script_list: &ScriptList,
+    feature_list: &FeatureList,
+    nested: &[Vec<u16>],
+) -> Result<(), SanitizeError> {
+    let read_err = |error| SanitizeError::Read { tag, error };
+    let invalid = |reason| SanitizeError::Invalid { tag, reason };
+    let feature_count = feature_list.feature_count();
+    for script in script_list.iter() {
+        let script = script.map_err(read_err)?;
+        let default_lang_sys = script.default_lang_sys().transpose().map_err(read_err)?;
+        let lang_systems = script
+            .lang_sys_iter()
+            .map(|lang_sys| lang_sys.map(|lang_sys| lang_sys.element))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(read_err)?;
+        for lang_sys in default_lang_sys.iter().chain(&lang_systems) {
+            let required = lang_sys.required_feature_index();
+            if (required != 0xFFFF && required >= feature_count)
+                || lang_sys
+                    .feature_indices()
+                    .iter()
+                    .any(|index| index.get() >= feature_count)
+            {
+                return Err(invalid("feature index out of range"));
+            }
+        }
+    }
+    let lookup_count = nested.len();
+    for feature in feature_list.iter() {
+        let feature = feature.map_err(read_err)?;
+        if feature
+            .lookup_list_indices()
+            .iter()
+            .any(|index| index.get() as usize >= lookup_count)
+        {
+            return Err(invalid("lookup index out of range"));
+        }
+    }
+    if nested
+        .iter()
+        .flatten()
+        .any(|index| *index as usize >= lookup_count)
+    {
+        return Err(invalid("nested lookup index out of range"));
+    }
+    if has_cycle(nested) {
+        return Err(invalid("contextual lookups contain a cycle"));
+    }
+    Ok(())
+}
+
+fn add_context_lookups(context: &SequenceContext, lookups: &mut Vec<u16>) -> Result<(), ReadError> {
+    match context {
+        SequenceContext::Format1(table) => {
+            for rule_set in table.seq_rule_sets().iter().flatten() {
+                for rule in rule_set?.seq_rules().iter() {
+                    let rule = rule?;
+                    let records = rule.seq_lookup_records().iter();
+                    lookups.extend(records.map(|rec| rec.lookup_list_index()));
+                }
+            }
+        }
+        SequenceContext::Format2(table) => {
+            for rule_set in table.class_seq_rule_sets().iter().flatten() {
+                for rule in rule_set?.class_seq_rules().iter() {
+                    let rule = rule?;
+                    let records = rule.seq_lookup_records().iter();
+                    lookups.extend(records.map(|rec| rec.lookup_list_index()));
+                }
+            }
+        }
+        SequenceContext::Format3(table) => {
+            let records = table.seq_lookup_records().iter();
+            lookups.extend(records.map(|rec| rec.lookup_list_index()));
+        }
+    }
+    Ok(())
+}
+
+fn add_chain_context_lookups(
+    context: &ChainedSequenceContext,
+    lookups: &mut Vec<u16>,
+) -> Result<(), ReadError> {
+    match context {
+        ChainedSequenceContext::Format1(table) => {
+            for rule_set in table.chained_seq_rule_sets().iter().flatten() {
+                for rule in rule_set?.chained_seq_rules().iter() {
+                    let rule = rule?;
+                    let records = rule.seq_lookup_records().iter();
+                    lookups.extend(records.map(|rec| rec.lookup_list_index()));
+                }
+            }
+        }
+        ChainedSequenceContext::Format2(table) => {
+            for rule_set in table.chained_class_seq_rule_sets().iter().flatten() {
+                for rule in rule_set?.chained_class_seq_rules().iter() {
+                    let rule = rule?;
+                    let records = rule.seq_lookup_records().iter();
+                    lookups.extend(records.map(|rec| rec.lookup_list_index()));
+                }
+            }
+        }
+        ChainedSequenceContext::Format3(table) => {
+            let records = table.seq_lookup_records().iter();
+            lookups.extend(records.map(|rec| rec.lookup_list_index()));
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if the graph of nested lookups contains a cycle.
+///
+/// All indices must be in range.
+fn has_cycle(nested: &[Vec<u16>]) -> bool {
+    #[derive(Copy, Clone, PartialEq)]
+    enum State {
+        Unvisited,
+        InProgress,
+        Done,
+    }
+    let mut states = vec![State::Unvisited; nested.len()];
+    // Iterative depth first search with a stack of (lookup, next child)
+    let mut stack = Vec::new();
+    for root in 0..nested.len() {
+        if states[root] != State::Unvisited {
+            continue;
+        }
+        states[root] = State::InProgress;
+        stack.push((root, 0));
+        while let Some((lookup, child_ix)) = stack.last_mut() {
+            let Some(child) = nested[*lookup].get(*child_ix).map(|ix| *ix as usize) else {
+                states[*lookup] = State::Done;
+                stack.pop();
+                continue;
+            };
+            *child_ix += 1;
+            match states[child] {
+                State::InProgress => return true,
+                State::Unvisited => {
+                    states[child] = State::InProgress;
+                    stack.push((child, 0));
+                }
+                State::Done => {}
+            }
+        }
+    }
+    false
+}
+
+fn read_required<T>(tag: Tag, result: Result<T, ReadError>) -> Result<T, SanitizeError> {
+    result.map_err(|error| match error {
+        ReadError::TableIsMissing(_) => SanitizeError::MissingTable(tag),
+        error => SanitizeError::Read { tag, error },
+    })
+}
+
+fn read_optional<T>(tag: Tag, result: Result<T, ReadError>) -> Result<Option<T>, SanitizeError> {
+    match result {
+        Ok(table) => Ok(Some(table)),
+        Err(ReadError::TableIsMissing(_)) => Ok(None),
+        Err(error) => Err(SanitizeError::Read { tag, error }),
+    }
+}
+
+impl std::fmt::Display for SanitizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingTable(tag) => write!(f, "missing required '{tag}' table"),
+            Self::OutOfBounds(tag) => write!(f, "'{tag}' table is out of bounds"),
+            Self::Overlapping(a, b) => write!(f, "'{a}' and '{b}' tables overlap"),
+            Self::Invalid { tag, reason } => write!(f, "invalid '{tag}' table: {reason}"),
+            Self::Read { tag, error } => write!(f, "failed to read '{tag}' table: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SanitizeError {}
+
+#[cfg(test)]
+#[path = "tests/sanitize.rs"]
+mod tests;
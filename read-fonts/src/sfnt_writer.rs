@@ -0,0 +1,88 @@
+//! Assembling sfnt fonts from decoded table data.
+//!
+//! This is used by the web font decoders, which recover the tables of a
+//! font but not the original table directory.
+
+use types::Tag;
+
+use crate::checksum::compute as checksum;
+
+const HEAD: Tag = Tag::new(b"head");
+
+/// Assembles the final font from the given tables.
+pub(crate) fn build_sfnt(flavor: u32, mut tables: Vec<(Tag, Vec<u8>)>) -> Vec<u8> {
+    const TABLE_RECORD_LEN: usize = 16;
+    tables.sort_by_key(|(tag, _)| *tag);
+    let num_tables = tables.len() as u16;
+    let entry_selector = num_tables.checked_ilog2().unwrap_or_default() as u16;
+    let search_range = (1u16 << entry_selector).wrapping_mul(16);
+    let range_shift = num_tables.wrapping_mul(16).wrapping_sub(search_range);
+    let mut font = Vec::new();
+    font.extend(flavor.to_be_bytes());
+    for value in [num_tables, search_range, entry_selector, range_shift] {
+        font.extend(value.to_be_bytes());
+    }
+    let mut offset = font.len() + tables.len() * TABLE_RECORD_LEN;
+    let mut head_offset = None;
+    for (tag, data) in &mut tables {
+        if *tag == HEAD && data.len() >= 12 {
+            // checksumAdjustment is computed with a value of zero
+            data[8..12].fill(0);
+            head_offset = Some(offset);
+        }
+        font.extend(tag.to_be_bytes());
+        font.extend(checksum(data).to_be_bytes());
+        font.extend((offset as u32).to_be_bytes());
+        font.extend((data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in &tables {
+        font.extend(data);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+    if let Some(head_offset) = head_offset {
+        let adjustment = 0xB1B0AFBAu32.wrapping_sub(checksum(&font));
+        font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    font
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{FontRef, TableProvider};
+
+    /// Checks the table checksums and checksum adjustment of a font.
+    pub(crate) fn assert_valid_checksums(font_data: &[u8]) {
+        let font = FontRef::new(font_data).unwrap();
+        let report = crate::checksum::verify(&font);
+        assert!(report.is_valid(), "{report:?}");
+        // the report skips fonts without a head table
+        assert!(font.head().is_ok());
+    }
+
+    #[test]
+    fn build_font() {
+        let head = [0u8; 54];
+        let tables = vec![
+            (Tag::new(b"zzzz"), vec![1, 2, 3]),
+            (HEAD, head.to_vec()),
+            (Tag::new(b"aaaa"), vec![4; 8]),
+        ];
+        let data = build_sfnt(types::TT_SFNT_VERSION, tables);
+        assert_valid_checksums(&data);
+        let font = FontRef::new(&data).unwrap();
+        let directory = &font.table_directory;
+        assert_eq!(directory.num_tables(), 3);
+        assert_eq!(directory.search_range(), 32);
+        assert_eq!(directory.entry_selector(), 1);
+        assert_eq!(directory.range_shift(), 16);
+        let tags = font.tables().map(|table| table.tag()).collect::<Vec<_>>();
+        assert_eq!(tags, [Tag::new(b"aaaa"), HEAD, Tag::new(b"zzzz")]);
+        assert_eq!(
+            font.table_data(Tag::new(b"zzzz")).unwrap().as_bytes(),
+            [1, 2, 3]
+        );
+        assert_eq!(font.out_of_bounds_tables().count(), 0);
+    }
+}
@@ -49,9 +49,7 @@ pub trait TableProvider<'a> {
     }
 
     fn hdmx(&self) -> Result<tables::hdmx::Hdmx<'a>, ReadError> {
-        let num_glyphs = self.maxp().map(|maxp| maxp.num_glyphs())?;
-        let data = self.expect_data_for_tag(tables::hdmx::Hdmx::TAG)?;
-        tables::hdmx::Hdmx::read(data, num_glyphs)
+        self.expect_table()
     }
 
     fn vmtx(&self) -> Result<tables::vmtx::Vmtx<'a>, ReadError> {
@@ -148,10 +146,46 @@ pub trait TableProvider<'a> {
         self.expect_table()
     }
 
+    fn base(&self) -> Result<tables::base::Base<'a>, ReadError> {
+        self.expect_table()
+    }
+
+    fn math(&self) -> Result<tables::math::Math<'a>, ReadError> {
+        self.expect_table()
+    }
+
     fn feat(&self) -> Result<tables::feat::Feat<'a>, ReadError> {
         self.expect_table()
     }
 
+    fn dsig(&self) -> Result<tables::dsig::Dsig<'a>, ReadError> {
+        self.expect_table()
+    }
+
+    fn trak(&self) -> Result<tables::trak::Trak<'a>, ReadError> {
+        self.expect_table()
+    }
+
+    fn vdmx(&self) -> Result<tables::vdmx::Vdmx<'a>, ReadError> {
+        self.expect_table()
+    }
+
+    fn ltsh(&self) -> Result<tables::ltsh::Ltsh<'a>, ReadError> {
+        self.expect_table()
+    }
+
+    fn pclt(&self) -> Result<tables::pclt::Pclt<'a>, ReadError> {
+        self.expect_table()
+    }
+
+    fn kern(&self) -> Result<tables::kern::Kern<'a>, ReadError> {
+        self.expect_table()
+    }
+
+    fn kerx(&self) -> Result<tables::kerx::Kerx<'a>, ReadError> {
+        self.expect_table()
+    }
+
     fn ltag(&self) -> Result<tables::ltag::Ltag<'a>, ReadError> {
         self.expect_table()
     }
@@ -218,6 +252,69 @@ pub trait TableProvider<'a> {
     }
 }
 
+/// A [`TableProvider`] that overlays replacement table data on top of
+/// another provider.
+///
+/// This allows evaluating a font with some of its tables swapped out (for
+/// example, to compare the result of two different `GPOS` tables) without
+/// rebuilding the font. Each override is a tag paired with the replacement
+/// data, or with `None` to hide the table entirely. Tables without an
+/// override are served by the base provider.
+///
+/// # Examples
+///
+/// ```
+/// # use read_fonts::{FontData, FontRef, TableOverlay, TableProvider, types::Tag};
+/// # fn test(font: FontRef, new_gpos: &[u8]) {
+/// let overrides = [
+///     (Tag::new(b"GPOS"), Some(FontData::new(new_gpos))),
+///     (Tag::new(b"kern"), None),
+/// ];
+/// let font = TableOverlay::new(font, &overrides);
+/// assert!(font.kern().is_err());
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TableOverlay<'a, P> {
+    base: P,
+    overrides: &'a [(Tag, Option<FontData<'a>>)],
+}
+
+impl<'a, P: TableProvider<'a>> TableOverlay<'a, P> {
+    /// Creates a new overlay serving the given overrides in place of the
+    /// tables of `base`.
+    ///
+    /// If a tag appears more than once, the first entry is used.
+    pub fn new(base: P, overrides: &'a [(Tag, Option<FontData<'a>>)]) -> Self {
+        Self { base, overrides }
+    }
+
+    /// Returns the underlying provider.
+    pub fn base(&self) -> &P {
+        &self.base
+    }
+
+    /// Returns true if the table with the given tag is overridden.
+    pub fn is_overridden(&self, tag: Tag) -> bool {
+        self.overrides
+            .iter()
+            .any(|(override_tag, _)| *override_tag == tag)
+    }
+}
+
+impl<'a, P: TableProvider<'a>> TableProvider<'a> for TableOverlay<'a, P> {
+    fn data_for_tag(&self, tag: Tag) -> Option<FontData<'a>> {
+        match self
+            .overrides
+            .iter()
+            .find(|(override_tag, _)| *override_tag == tag)
+        {
+            Some((_, data)) => *data,
+            None => self.base.data_for_tag(tag),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -269,4 +366,34 @@ mod tests {
         assert_eq!(hmtx.h_metrics().len(), 1);
         assert_eq!(hmtx.left_side_bearings().len(), 2);
     }
+
+    #[test]
+    fn overlay_tables() {
+        let font = crate::FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let maxp = [0u8, 0, 0x50, 0, 0, 3];
+        let overrides = [
+            (Tag::new(b"maxp"), Some(FontData::new(&maxp))),
+            (Tag::new(b"STAT"), None),
+        ];
+        let overlay = TableOverlay::new(font.clone(), &overrides);
+        assert!(overlay.is_overridden(Tag::new(b"STAT")));
+        assert!(!overlay.is_overridden(Tag::new(b"head")));
+        assert_eq!(overlay.maxp().unwrap().num_glyphs(), 3);
+        assert!(font.stat().is_ok());
+        assert!(matches!(
+            overlay.stat(),
+            Err(ReadError::TableIsMissing(tag)) if tag == Tag::new(b"STAT")
+        ));
+        // everything else comes from the base font
+        assert_eq!(
+            overlay.head().unwrap().units_per_em(),
+            font.head().unwrap().units_per_em()
+        );
+        assert_eq!(
+            overlay
+                .data_for_tag(Tag::new(b"glyf"))
+                .map(|data| data.len()),
+            font.data_for_tag(Tag::new(b"glyf")).map(|data| data.len())
+        );
+    }
 }
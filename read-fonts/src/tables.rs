@@ -13,6 +13,7 @@ pub mod cmap;
 pub mod colr;
 pub mod cpal;
 pub mod cvar;
+pub mod dsig;
 pub mod ebdt;
 pub mod eblc;
 pub mod feat;
@@ -29,21 +30,28 @@ pub mod hhea;
 pub mod hmtx;
 pub mod hvar;
 pub mod ift;
+pub mod kern;
+pub mod kerx;
 pub mod layout;
 pub mod loca;
 pub mod ltag;
+pub mod ltsh;
+pub mod math;
 pub mod maxp;
 pub mod meta;
 pub mod mvar;
 pub mod name;
 pub mod os2;
+pub mod pclt;
 pub mod post;
 pub mod postscript;
 pub mod sbix;
 pub mod stat;
 pub mod svg;
+pub mod trak;
 pub mod varc;
 pub mod variations;
+pub mod vdmx;
 pub mod vhea;
 pub mod vmtx;
 pub mod vorg;
@@ -176,6 +176,7 @@ impl Lookup<'_> {
     }
 }
 
+#[derive(Clone)]
 pub struct TypedLookup<'a, T> {
     lookup: Lookup<'a>,
     _marker: std::marker::PhantomData<fn() -> T>,
@@ -295,6 +296,7 @@ where
     const RAW_BYTE_LEN: usize = u16::RAW_BYTE_LEN + u16::RAW_BYTE_LEN + T::RAW_BYTE_LEN;
 }
 
+#[derive(Clone)]
 pub struct StateTable<'a> {
     header: StateHeader<'a>,
 }
@@ -373,6 +375,7 @@ impl<'a> SomeTable<'a> for StateTable<'a> {
     }
 }
 
+#[derive(Clone)]
 pub struct ExtendedStateTable<'a, T = ()> {
     header: StxHeader<'a>,
     _marker: std::marker::PhantomData<fn() -> T>,
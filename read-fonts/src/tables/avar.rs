@@ -7,27 +7,47 @@ include!("../../generated/generated_avar.rs");
 impl SegmentMaps<'_> {
     /// Applies the piecewise linear mapping to the specified coordinate.
     pub fn apply(&self, coord: Fixed) -> Fixed {
-        let mut prev = AxisValueMap {
-            from_coordinate: Default::default(),
-            to_coordinate: Default::default(),
+        self.map(coord, false)
+    }
+
+    /// Applies the inverse of the piecewise linear mapping to the specified
+    /// coordinate.
+    ///
+    /// This converts a coordinate produced by [`apply`](Self::apply) back to
+    /// the default normalized coordinate. If multiple coordinates map to the
+    /// same value, the smallest is returned.
+    pub fn apply_inverse(&self, coord: Fixed) -> Fixed {
+        self.map(coord, true)
+    }
+
+    fn map(&self, coord: Fixed, inverse: bool) -> Fixed {
+        let endpoints = |map: &AxisValueMap| {
+            let (from, to) = (
+                map.from_coordinate().to_fixed(),
+                map.to_coordinate().to_fixed(),
+            );
+            if inverse {
+                (to, from)
+            } else {
+                (from, to)
+            }
         };
+        let mut prev = (Fixed::ZERO, Fixed::ZERO);
         for (i, axis_value_map) in self.axis_value_maps().iter().enumerate() {
             use core::cmp::Ordering::*;
-            let from = axis_value_map.from_coordinate().to_fixed();
+            let (from, to) = endpoints(axis_value_map);
             match from.cmp(&coord) {
-                Equal => return axis_value_map.to_coordinate().to_fixed(),
+                Equal => return to,
                 Greater => {
                     if i == 0 {
                         return coord;
                     }
-                    let to = axis_value_map.to_coordinate().to_fixed();
-                    let prev_from = prev.from_coordinate().to_fixed();
-                    let prev_to = prev.to_coordinate().to_fixed();
+                    let (prev_from, prev_to) = prev;
                     return prev_to + (to - prev_to).mul_div(coord - prev_from, from - prev_from);
                 }
                 _ => {}
             }
-            prev = *axis_value_map;
+            prev = (from, to);
         }
         coord
     }
@@ -159,6 +179,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn piecewise_linear_inverse() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let avar = font.avar().unwrap();
+        let segment_map = avar.axis_segment_maps().get(0).unwrap().unwrap();
+        for coord in [-1.0, -0.8, -0.5, -0.1, 0.0, 0.25, 0.5, 0.9, 1.0] {
+            let coord = Fixed::from_f64(coord);
+            let inverse = segment_map.apply_inverse(segment_map.apply(coord));
+            assert!((inverse - coord).abs() <= Fixed::from_bits(2), "{coord}");
+        }
+        // outside of the mapped range, the coordinate is unchanged
+        assert_eq!(
+            segment_map.apply_inverse(Fixed::from_f64(1.5)),
+            Fixed::from_f64(1.5)
+        );
+    }
+
     #[test]
     fn avar2() {
         let font = FontRef::new(font_test_data::AVAR2_CHECKER).unwrap();
@@ -4,6 +4,32 @@ use super::{layout::DeviceOrVariationIndex, variations::ItemVariationStore};
 
 include!("../../generated/generated_base.rs");
 
+impl BaseTagList<'_> {
+    /// Returns the index of the baseline with the given tag.
+    pub fn index_for_tag(&self, tag: Tag) -> Option<u16> {
+        self.baseline_tags()
+            .iter()
+            .position(|baseline_tag| baseline_tag.get() == tag)
+            .map(|index| index as u16)
+    }
+}
+
+impl<'a> BaseScriptList<'a> {
+    /// Returns the index of the script with the given tag.
+    pub fn index_for_tag(&self, tag: Tag) -> Option<u16> {
+        self.base_script_records()
+            .binary_search_by_key(&tag, |rec| rec.base_script_tag())
+            .map(|index| index as u16)
+            .ok()
+    }
+
+    /// Returns the script with the given tag.
+    pub fn base_script(&self, tag: Tag) -> Option<Result<BaseScript<'a>, ReadError>> {
+        let index = self.index_for_tag(tag)?;
+        Some(self.base_script_records()[index as usize].base_script(self.offset_data()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use font_types::MajorMinor;
@@ -50,5 +76,9 @@ mod tests {
             base_script.base_script_records()[3].base_script_tag(),
             Tag::new(b"latn")
         );
+        assert_eq!(base_tag.index_for_tag(Tag::new(b"romn")), Some(2));
+        assert_eq!(base_tag.index_for_tag(Tag::new(b"math")), None);
+        assert_eq!(base_script.index_for_tag(Tag::new(b"devn")), Some(1));
+        assert_eq!(base_script.index_for_tag(Tag::new(b"grek")), None);
     }
 }
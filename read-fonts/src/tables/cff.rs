@@ -2,7 +2,7 @@
 
 include!("../../generated/generated_cff.rs");
 
-use super::postscript::{Index1, Latin1String, StringId};
+use super::postscript::{dict, Charset, Error, Index1, Latin1String, StringId};
 
 /// The [Compact Font Format](https://learn.microsoft.com/en-us/typography/opentype/spec/cff) table.
 #[derive(Clone)]
@@ -79,6 +79,43 @@ impl<'a> Cff<'a> {
     pub fn global_subrs(&self) -> Index1<'a> {
         self.global_subrs.clone()
     }
+
+    /// Returns the character set associated with the top dict at the given
+    /// index.
+    ///
+    /// Returns `Ok(None)` if the top dict does not contain a reference to
+    /// the charstrings, in which case the number of glyphs is unknown.
+    ///
+    /// See "Charsets" at <https://adobe-type-tools.github.io/font-tech-notes/pdfs/5176.CFF.pdf#page=21>
+    pub fn charset(&self, top_dict_index: usize) -> Result<Option<Charset<'a>>, Error> {
+        let top_dict = self.top_dicts.get(top_dict_index)?;
+        let offset_data = self.offset_data();
+        let mut charset_offset = None;
+        let mut num_glyphs = None;
+        for entry in dict::entries(top_dict, None) {
+            match entry? {
+                dict::Entry::Charset(offset) => {
+                    charset_offset = Some(offset);
+                }
+                dict::Entry::CharstringsOffset(offset) => {
+                    let data = offset_data
+                        .split_off(offset)
+                        .ok_or(ReadError::OutOfBounds)?;
+                    num_glyphs = Some(Index1::read(data)?.count() as u32);
+                }
+                _ => {}
+            }
+        }
+        let Some(num_glyphs) = num_glyphs else {
+            return Ok(None);
+        };
+        // The charset defaults to ISOAdobe (offset 0) if not specified
+        Ok(Some(Charset::new(
+            offset_data,
+            charset_offset.unwrap_or_default(),
+            num_glyphs,
+        )?))
+    }
 }
 
 impl TopLevelTable for Cff<'_> {
@@ -117,6 +154,19 @@ mod tests {
     use super::*;
     use crate::{tables::postscript::StringId, FontRef, TableProvider};
 
+    #[test]
+    fn charset_names() {
+        let font = FontRef::new(font_test_data::NOTO_SERIF_DISPLAY_TRIMMED).unwrap();
+        let cff = font.cff().unwrap();
+        let charset = cff.charset(0).unwrap().unwrap();
+        let names: Vec<_> = charset
+            .iter()
+            .map(|(_, sid)| cff.string(sid).unwrap().to_string())
+            .collect();
+        assert_eq!(charset.num_glyphs(), 5);
+        assert_eq!(names, [".notdef", "i", "j", "k", "l"]);
+    }
+
     #[test]
     fn read_noto_serif_display_cff() {
         let font = FontRef::new(font_test_data::NOTO_SERIF_DISPLAY_TRIMMED).unwrap();
@@ -4,6 +4,7 @@ include!("../../generated/generated_cmap.rs");
 
 #[cfg(feature = "std")]
 use crate::collections::IntSet;
+use crate::{GlyphIdValidator, InvalidGlyphId};
 use std::ops::{Range, RangeInclusive};
 
 /// Result of mapping a codepoint with a variation selector.
@@ -33,6 +34,7 @@ impl Cmap<'_> {
                 if let Some(gid) = match subtable {
                     CmapSubtable::Format4(format4) => format4.map_codepoint(codepoint),
                     CmapSubtable::Format12(format12) => format12.map_codepoint(codepoint),
+                    CmapSubtable::Format13(format13) => format13.map_codepoint(codepoint),
                     _ => None,
                 } {
                     return Some(gid);
@@ -42,6 +44,18 @@ impl Cmap<'_> {
         None
     }
 
+    /// Map a codepoint to a nominal glyph identifier, applying the policy
+    /// of the given validator to the result.
+    pub fn map_codepoint_validated(
+        &self,
+        codepoint: impl Into<u32>,
+        validator: &GlyphIdValidator,
+    ) -> Result<Option<GlyphId>, InvalidGlyphId> {
+        self.map_codepoint(codepoint)
+            .map(|gid| validator.validate(gid))
+            .transpose()
+    }
+
     #[cfg(feature = "std")]
     pub fn closure_glyphs(&self, unicodes: &IntSet<u32>, glyph_set: &mut IntSet<GlyphId>) {
         for record in self.encoding_records() {
@@ -301,6 +315,89 @@ impl Iterator for Cmap12Iter<'_> {
     }
 }
 
+impl<'a> Cmap13<'a> {
+    /// Maps a codepoint to a nominal glyph identifier.
+    pub fn map_codepoint(&self, codepoint: impl Into<u32>) -> Option<GlyphId> {
+        let codepoint = codepoint.into();
+        let groups = self.groups();
+        let index = groups
+            .binary_search_by(|group| {
+                if codepoint < group.start_char_code() {
+                    core::cmp::Ordering::Greater
+                } else if codepoint > group.end_char_code() {
+                    core::cmp::Ordering::Less
+                } else {
+                    core::cmp::Ordering::Equal
+                }
+            })
+            .ok()?;
+        Some(GlyphId::new(groups.get(index)?.glyph_id()))
+    }
+
+    /// Returns an iterator over all (codepoint, glyph identifier) pairs
+    /// in the subtable.
+    pub fn iter(&self) -> Cmap13Iter<'a> {
+        Cmap13Iter::new(self.clone())
+    }
+
+    /// Returns the codepoint range and glyph id for the group at the given
+    /// index.
+    fn group(&self, index: usize) -> Option<(RangeInclusive<u32>, GlyphId)> {
+        let group = self.groups().get(index)?;
+        // Limit to the valid range of Unicode characters, as for format 12
+        let end_code = group.end_char_code().min(char::MAX as u32);
+        Some((
+            group.start_char_code()..=end_code,
+            GlyphId::new(group.glyph_id()),
+        ))
+    }
+}
+
+/// Iterator over all (codepoint, glyph identifier) pairs in
+/// the subtable.
+#[derive(Clone)]
+pub struct Cmap13Iter<'a> {
+    subtable: Cmap13<'a>,
+    cur_group: Option<(RangeInclusive<u32>, GlyphId)>,
+    cur_group_ix: usize,
+}
+
+impl<'a> Cmap13Iter<'a> {
+    fn new(subtable: Cmap13<'a>) -> Self {
+        let cur_group = subtable.group(0);
+        Self {
+            subtable,
+            cur_group,
+            cur_group_ix: 0,
+        }
+    }
+}
+
+impl Iterator for Cmap13Iter<'_> {
+    type Item = (u32, GlyphId);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (range, glyph_id) = self.cur_group.as_mut()?;
+            // Groups explicitly mapped to 0 are skipped entirely
+            if *glyph_id != GlyphId::NOTDEF {
+                if let Some(codepoint) = range.next() {
+                    return Some((codepoint, *glyph_id));
+                }
+            }
+            let prev_end = *range.end();
+            self.cur_group_ix += 1;
+            let (mut next_range, next_glyph_id) = self.subtable.group(self.cur_group_ix)?;
+            // As with format 12, clamp overlapping groups so that we generate
+            // at most char::MAX + 1 results
+            if *next_range.start() <= prev_end {
+                next_range = prev_end.saturating_add(1)..=*next_range.end();
+            }
+            self.cur_group = Some((next_range, next_glyph_id));
+        }
+    }
+}
+
 impl<'a> Cmap14<'a> {
     /// Maps a codepoint and variation selector to a nominal glyph identifier.
     pub fn map_variant(
@@ -544,6 +641,30 @@ mod tests {
         assert_eq!(cmap.map_codepoint('B'), None);
     }
 
+    #[test]
+    fn map_codepoints_validated() {
+        use crate::GlyphIdPolicy;
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let cmap = font.cmap().unwrap();
+        // only glyphs 0 and 1 are in range
+        let error = GlyphIdValidator::new(2, GlyphIdPolicy::Error);
+        assert_eq!(
+            cmap.map_codepoint_validated('A', &error),
+            Ok(Some(GlyphId::new(1)))
+        );
+        assert_eq!(
+            cmap.map_codepoint_validated('À', &error)
+                .map_err(|e| e.glyph_id()),
+            Err(GlyphId::new(2))
+        );
+        assert_eq!(cmap.map_codepoint_validated('B', &error), Ok(None));
+        let clamp = GlyphIdValidator::new(2, GlyphIdPolicy::Clamp);
+        assert_eq!(
+            cmap.map_codepoint_validated('À', &clamp),
+            Ok(Some(GlyphId::NOTDEF))
+        );
+    }
+
     #[test]
     fn map_variants() {
         use super::MapVariant::*;
@@ -709,6 +830,58 @@ mod tests {
         assert!(cmap12.iter().count() <= char::MAX as usize + 1);
     }
 
+    fn cmap13_data() -> BeBuffer {
+        be_buffer! {
+            13u16,      // format
+            0u16,       // reserved, set to 0
+            0u32,       // length, ignored
+            0u32,       // language, ignored
+            3u32,       // numGroups
+            // groups: [startCode, endCode, glyphID]
+            [0x20u32, 0x22, 1], // group 0
+            [0x30u32, 0x31, 0], // group 1
+            [0x10000u32, 0x10001, 2] // group 2
+        }
+    }
+
+    #[test]
+    fn cmap13_map_codepoint() {
+        let data = cmap13_data();
+        let cmap13 = Cmap13::read(data.font_data()).unwrap();
+        assert_eq!(cmap13.map_codepoint(0x1Fu32), None);
+        assert_eq!(cmap13.map_codepoint(0x20u32), Some(GlyphId::new(1)));
+        assert_eq!(cmap13.map_codepoint(0x22u32), Some(GlyphId::new(1)));
+        assert_eq!(cmap13.map_codepoint(0x30u32), Some(GlyphId::NOTDEF));
+        assert_eq!(cmap13.map_codepoint(0x10001u32), Some(GlyphId::new(2)));
+        assert_eq!(cmap13.map_codepoint(0x10002u32), None);
+    }
+
+    #[test]
+    fn cmap13_iter() {
+        let data = cmap13_data();
+        let cmap13 = Cmap13::read(data.font_data()).unwrap();
+        let mappings = cmap13
+            .iter()
+            .map(|(cp, gid)| (cp, gid.to_u32()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            mappings,
+            [(0x20, 1), (0x21, 1), (0x22, 1), (0x10000, 2), (0x10001, 2)]
+        );
+    }
+
+    #[test]
+    fn cmap13_iter_avoid_overflow() {
+        let data = be_buffer! {
+            13u16, 0u16, 0u32, 0u32,
+            2u32,
+            [0u32, 16777215, 1],
+            [255u32, 0xFFFFFFFF, 2]
+        };
+        let cmap13 = Cmap13::read(data.font_data()).unwrap();
+        assert!(cmap13.iter().count() <= char::MAX as usize + 1);
+    }
+
     #[test]
     fn cmap14_iter() {
         let font = FontRef::new(font_test_data::CMAP14_FONT1).unwrap();
@@ -766,4 +939,37 @@ mod tests {
             cmap4.iter().map(|(cp, _)| cp).collect::<Vec<_>>()
         );
     }
+
+    #[test]
+    fn malformed_cmaps() {
+        use font_test_data::malformed;
+        let tag = Cmap::TAG;
+        let data = malformed::get("cmap_subtable_offset_out_of_bounds")
+            .and_then(|case| case.table_data(tag))
+            .unwrap();
+        let cmap = Cmap::read(FontData::new(data.as_slice())).unwrap();
+        assert!(cmap.encoding_records()[0]
+            .subtable(cmap.offset_data())
+            .is_err());
+        assert_eq!(cmap.map_codepoint('A'), None);
+
+        let data = malformed::get("cmap_shared_subtable")
+            .and_then(|case| case.table_data(tag))
+            .unwrap();
+        let cmap = Cmap::read(FontData::new(data.as_slice())).unwrap();
+        for record in cmap.encoding_records() {
+            let Ok(CmapSubtable::Format4(subtable)) = record.subtable(cmap.offset_data()) else {
+                panic!("expected a format 4 subtable");
+            };
+            assert_eq!(subtable.map_codepoint('D'), Some(GlyphId::new(4)));
+        }
+
+        let data = malformed::get("cmap4_truncated_arrays")
+            .and_then(|case| case.table_data(tag))
+            .unwrap();
+        let cmap = Cmap::read(FontData::new(data.as_slice())).unwrap();
+        assert!(cmap.encoding_records()[0]
+            .subtable(cmap.offset_data())
+            .is_err());
+    }
 }
@@ -0,0 +1,58 @@
+//! The [DSIG (Digital Signature Table)](https://docs.microsoft.com/en-us/typography/opentype/spec/dsig) table
+
+include!("../../generated/generated_dsig.rs");
+
+impl<'a> Dsig<'a> {
+    /// Returns `true` if the table does not contain any signatures.
+    ///
+    /// Empty tables are commonly included as placeholders for legacy
+    /// environments that require the table to be present.
+    pub fn is_empty(&self) -> bool {
+        self.num_signatures() == 0
+    }
+
+    /// Returns an iterator over the signature blocks in the table.
+    pub fn signature_blocks(
+        &self,
+    ) -> impl Iterator<Item = Result<SignatureBlockFormat1<'a>, ReadError>> + '_ {
+        let data = self.offset_data();
+        self.signature_records()
+            .iter()
+            .map(move |record| record.signature_block(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::BeBuffer;
+
+    #[test]
+    fn empty() {
+        let buf = BeBuffer::new().push(1u32).extend([0u16, 0]);
+        let dsig = Dsig::read(buf.font_data()).unwrap();
+        assert!(dsig.is_empty());
+        assert!(dsig.signature_records().is_empty());
+    }
+
+    #[test]
+    fn signature() {
+        let buf = BeBuffer::new()
+            .push(1u32)
+            .extend([1u16, PermissionFlags::CANNOT_BE_RESIGNED.bits()])
+            .extend([1u32, 11, 20])
+            .extend([0u16, 0])
+            .push(3u32)
+            .extend([1u8, 2, 3]);
+        let dsig = Dsig::read(buf.font_data()).unwrap();
+        assert!(!dsig.is_empty());
+        assert!(dsig.flags().contains(PermissionFlags::CANNOT_BE_RESIGNED));
+        let record = &dsig.signature_records()[0];
+        assert_eq!(record.format(), 1);
+        assert_eq!(record.length(), 11);
+        let block = record.signature_block(dsig.offset_data()).unwrap();
+        assert_eq!(block.signature(), [1, 2, 3]);
+        let blocks = dsig.signature_blocks().collect::<Result<Vec<_>, _>>();
+        assert_eq!(blocks.unwrap()[0].signature(), [1, 2, 3]);
+    }
+}
@@ -102,13 +102,11 @@ impl<'a> Fvar<'a> {
                     inner: i as u16,
                 })
             };
-            if var_index.is_none() {
+            let Some(var_index) = var_index else {
                 continue;
-            }
+            };
             if let Some(Ok(varstore)) = var_store.as_ref() {
-                if let Ok(delta) =
-                    varstore.compute_float_delta(var_index.unwrap(), normalized_coords)
-                {
+                if let Ok(delta) = varstore.compute_float_delta(var_index, normalized_coords) {
                     new_coords[i] = F2Dot14::from_f32((*v).apply_float_delta(delta))
                         .clamp(F2Dot14::MIN, F2Dot14::MAX);
                 }
@@ -116,9 +114,69 @@ impl<'a> Fvar<'a> {
         }
         normalized_coords.copy_from_slice(new_coords);
     }
+
+    /// Converts normalized coordinates in axis list order to user space
+    /// coordinates.
+    ///
+    /// Stores the resulting user coordinates in the given slice.
+    ///
+    /// * Normalized coordinates are clamped to the range [-1.0, 1.0].
+    /// * If no normalized coordinate for an axis is provided, the associated
+    ///   user coordinate is set to the default value of the axis.
+    /// * If the length of `user_coords` is smaller than the number of axes,
+    ///   axes at out of bounds indices are ignored. If the length is larger,
+    ///   the excess entries are left unchanged.
+    ///
+    /// If the [`Avar`] table is provided, the inverse of its segment maps is
+    /// applied before conversion. The variations introduced in version 2 of
+    /// that table cannot be inverted and are ignored.
+    pub fn normalized_to_user(
+        &self,
+        avar: Option<&Avar>,
+        normalized_coords: &[F2Dot14],
+        user_coords: &mut [Fixed],
+    ) {
+        let axes = self.axes().unwrap_or_default();
+        let avar_mappings = avar.map(|avar| avar.axis_segment_maps());
+        for (i, (axis, user_coord)) in axes.iter().zip(user_coords).enumerate() {
+            let coord = normalized_coords
+                .get(i)
+                .copied()
+                .unwrap_or_default()
+                .to_fixed();
+            let coord = avar_mappings
+                .as_ref()
+                .and_then(|mappings| mappings.get(i).transpose().ok())
+                .flatten()
+                .map(|mapping| mapping.apply_inverse(coord))
+                .unwrap_or(coord);
+            *user_coord = axis.denormalize(coord);
+        }
+    }
 }
 
 impl VariationAxisRecord {
+    /// Returns the user space value for the given normalized coordinate.
+    ///
+    /// This is the inverse of [`normalize`](Self::normalize), apart from
+    /// clamping and rounding. The coordinate is clamped to the range
+    /// [-1.0, 1.0].
+    pub fn denormalize(&self, coord: Fixed) -> Fixed {
+        let min_value = self.min_value();
+        let default_value = self.default_value();
+        // Make sure max is >= min, matching normalize.
+        let max_value = self.max_value().max(min_value);
+        let coord = coord.clamp(-Fixed::ONE, Fixed::ONE);
+        let range = if coord < Fixed::ZERO {
+            default_value.saturating_sub(min_value)
+        } else {
+            max_value.saturating_sub(default_value)
+        };
+        default_value
+            .saturating_add(range * coord)
+            .clamp(min_value, max_value)
+    }
+
     /// Returns a normalized coordinate for the given value.
     pub fn normalize(&self, mut value: Fixed) -> Fixed {
         use core::cmp::Ordering::*;
@@ -196,6 +254,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn denormalize() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let fvar = font.fvar().unwrap();
+        let axis = fvar.axes().unwrap().first().unwrap();
+        let coords = [-2.0, -1.0, -0.5, 0.0, 0.5, 1.0, 2.0];
+        let expected = [100.0, 100.0, 250.0, 400.0, 650.0, 900.0, 900.0];
+        for (coord, expected) in coords.into_iter().zip(expected) {
+            assert_eq!(
+                axis.denormalize(Fixed::from_f64(coord)),
+                Fixed::from_f64(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn normalized_to_user_round_trip() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let fvar = font.fvar().unwrap();
+        let avar = font.avar().ok();
+        let wght = Tag::new(b"wght");
+        for value in [100.0, 250.0, 400.0, 555.0, 700.0, 900.0] {
+            let mut normalized = [F2Dot14::ZERO];
+            fvar.user_to_normalized(
+                avar.as_ref(),
+                [(wght, Fixed::from_f64(value))],
+                &mut normalized,
+            );
+            let mut user = [Fixed::ZERO];
+            fvar.normalized_to_user(avar.as_ref(), &normalized, &mut user);
+            // normalized coordinates have limited precision
+            assert!((user[0].to_f64() - value).abs() < 0.05, "{value}");
+        }
+        // missing coordinates map to the default
+        let mut user = [Fixed::ZERO, Fixed::ONE];
+        fvar.normalized_to_user(avar.as_ref(), &[], &mut user);
+        assert_eq!(user, [Fixed::from_f64(400.0), Fixed::ONE]);
+    }
+
     #[test]
     fn normalize_overflow() {
         // From: https://bugs.chromium.org/p/oss-fuzz/issues/detail?id=69787
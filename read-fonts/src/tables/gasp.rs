@@ -2,6 +2,53 @@
 
 include!("../../generated/generated_gasp.rs");
 
+impl Gasp<'_> {
+    /// Returns the desired rasterizer behavior for the given size in pixels
+    /// per em.
+    ///
+    /// The symmetric flags are only meaningful in version 1 tables and are
+    /// cleared for earlier versions.
+    ///
+    /// Returns `None` if no range covers the given size.
+    pub fn behavior(&self, ppem: u16) -> Option<GaspRangeBehavior> {
+        let range = self
+            .gasp_ranges()
+            .iter()
+            .find(|range| ppem <= range.range_max_ppem())?;
+        let behavior = range.range_gasp_behavior();
+        if self.version() == 0 {
+            Some(behavior & (GaspRangeBehavior::GASP_GRIDFIT | GaspRangeBehavior::GASP_DOGRAY))
+        } else {
+            Some(behavior)
+        }
+    }
+}
+
+impl GaspRangeBehavior {
+    /// Returns `true` if glyphs should be grid-fitted.
+    pub fn gridfit(self) -> bool {
+        self.contains(Self::GASP_GRIDFIT)
+    }
+
+    /// Returns `true` if glyphs should be rendered with grayscale
+    /// anti-aliasing.
+    pub fn do_gray(self) -> bool {
+        self.contains(Self::GASP_DOGRAY)
+    }
+
+    /// Returns `true` if glyphs should be grid-fitted with ClearType
+    /// symmetric smoothing.
+    pub fn symmetric_gridfit(self) -> bool {
+        self.contains(Self::GASP_SYMMETRIC_GRIDFIT)
+    }
+
+    /// Returns `true` if glyphs should be smoothed along multiple axes
+    /// with ClearType.
+    pub fn symmetric_smoothing(self) -> bool {
+        self.contains(Self::GASP_SYMMETRIC_SMOOTHING)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -41,4 +88,44 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn behavior_lookup() {
+        let make_gasp = |version: u16| {
+            BeBuffer::new()
+                .extend([version, 3])
+                .push(8u16)
+                .push(GaspRangeBehavior::GASP_DOGRAY)
+                .push(16u16)
+                .push(GaspRangeBehavior::GASP_GRIDFIT | GaspRangeBehavior::GASP_SYMMETRIC_GRIDFIT)
+                .push(u16::MAX)
+                .push(GaspRangeBehavior::all())
+        };
+        let buf = make_gasp(1);
+        let gasp = Gasp::read(buf.font_data()).unwrap();
+        let small = gasp.behavior(8).unwrap();
+        assert!(small.do_gray() && !small.gridfit());
+        let medium = gasp.behavior(9).unwrap();
+        assert!(medium.gridfit() && medium.symmetric_gridfit() && !medium.do_gray());
+        let large = gasp.behavior(100).unwrap();
+        assert!(large.symmetric_smoothing());
+        // symmetric flags are ignored in version 0
+        let buf = make_gasp(0);
+        let gasp = Gasp::read(buf.font_data()).unwrap();
+        assert_eq!(
+            gasp.behavior(u16::MAX),
+            Some(GaspRangeBehavior::GASP_GRIDFIT | GaspRangeBehavior::GASP_DOGRAY)
+        );
+        assert!(!gasp.behavior(16).unwrap().symmetric_gridfit());
+    }
+
+    #[test]
+    fn behavior_out_of_range() {
+        let buf = BeBuffer::new()
+            .extend([1u16, 1, 20])
+            .push(GaspRangeBehavior::GASP_GRIDFIT);
+        let gasp = Gasp::read(buf.font_data()).unwrap();
+        assert!(gasp.behavior(20).is_some());
+        assert_eq!(gasp.behavior(21), None);
+    }
 }
@@ -4,7 +4,7 @@
 
 pub use super::layout::{
     ChainedSequenceContext, ClassDef, CoverageTable, Device, DeviceOrVariationIndex, FeatureList,
-    FeatureVariations, Lookup, LookupList, ScriptList, SequenceContext,
+    FeatureVariations, Lookup, LookupFlag, LookupList, ScriptList, SequenceContext,
 };
 
 use super::variations::ItemVariationStore;
@@ -14,3 +14,106 @@ use super::variations::ItemVariationStore;
 mod tests;
 
 include!("../../generated/generated_gdef.rs");
+
+/// Determines which glyphs are skipped by a lookup during matching.
+///
+/// This combines the [`LookupFlag`] and mark filtering set of a lookup with
+/// the glyph classes, mark attachment classes and mark glyph sets defined
+/// in `GDEF`, following the rules in the
+/// [lookup flag](https://learn.microsoft.com/en-us/typography/opentype/spec/chapter2#lookupFlag)
+/// documentation.
+///
+/// Glyphs without a glyph class are never skipped.
+#[derive(Clone, Default)]
+pub struct LookupGlyphFilter<'a> {
+    lookup_flag: LookupFlag,
+    glyph_classes: Option<ClassDef<'a>>,
+    mark_attach_classes: Option<ClassDef<'a>>,
+    mark_filtering_set: Option<CoverageTable<'a>>,
+}
+
+impl<'a> LookupGlyphFilter<'a> {
+    /// Creates a new filter for a lookup with the given flag and mark
+    /// filtering set.
+    ///
+    /// The mark filtering set is ignored unless the
+    /// [`USE_MARK_FILTERING_SET`](LookupFlag::USE_MARK_FILTERING_SET) flag is
+    /// set. Missing or malformed `GDEF` data is treated as absent.
+    pub fn new(
+        gdef: Option<&Gdef<'a>>,
+        lookup_flag: LookupFlag,
+        mark_filtering_set: Option<u16>,
+    ) -> Self {
+        let Some(gdef) = gdef else {
+            return Self {
+                lookup_flag,
+                ..Default::default()
+            };
+        };
+        let mark_filtering_set = mark_filtering_set
+            .filter(|_| lookup_flag.contains(LookupFlag::USE_MARK_FILTERING_SET))
+            .and_then(|index| {
+                gdef.mark_glyph_sets_def()?
+                    .ok()?
+                    .coverages()
+                    .get(index as usize)
+                    .ok()
+            });
+        Self {
+            lookup_flag,
+            glyph_classes: gdef.glyph_class_def().and_then(Result::ok),
+            mark_attach_classes: gdef.mark_attach_class_def().and_then(Result::ok),
+            mark_filtering_set,
+        }
+    }
+
+    /// Returns the lookup flag used by this filter.
+    pub fn lookup_flag(&self) -> LookupFlag {
+        self.lookup_flag
+    }
+
+    /// Returns the `GDEF` glyph class of the given glyph, if it has one.
+    pub fn glyph_class(&self, glyph_id: impl Into<GlyphId>) -> Option<GlyphClassDef> {
+        let glyph_id: GlyphId16 = glyph_id.into().try_into().ok()?;
+        match self.glyph_classes.as_ref()?.get(glyph_id) {
+            0 => None,
+            class => Some(GlyphClassDef::new(class)),
+        }
+    }
+
+    /// Returns `true` if the given glyph should be skipped when matching.
+    pub fn should_skip(&self, glyph_id: impl Into<GlyphId>) -> bool {
+        let glyph_id = glyph_id.into();
+        let flag = self.lookup_flag;
+        match self.glyph_class(glyph_id) {
+            Some(GlyphClassDef::Base) => flag.contains(LookupFlag::IGNORE_BASE_GLYPHS),
+            Some(GlyphClassDef::Ligature) => flag.contains(LookupFlag::IGNORE_LIGATURES),
+            Some(GlyphClassDef::Mark) => self.should_skip_mark(glyph_id),
+            _ => false,
+        }
+    }
+
+    fn should_skip_mark(&self, glyph_id: GlyphId) -> bool {
+        let flag = self.lookup_flag;
+        if flag.contains(LookupFlag::IGNORE_MARKS) {
+            return true;
+        }
+        if flag.contains(LookupFlag::USE_MARK_FILTERING_SET) {
+            // a missing set covers no glyphs
+            return self
+                .mark_filtering_set
+                .as_ref()
+                .and_then(|set| set.get(glyph_id))
+                .is_none();
+        }
+        if let Some(class) = flag.mark_attachment_class() {
+            let attach_class = GlyphId16::try_from(glyph_id)
+                .ok()
+                .zip(self.mark_attach_classes.as_ref())
+                .map(|(gid, classes)| classes.get(gid))
+                .unwrap_or_default();
+            return attach_class != class;
+        }
+        false
+    }
+}
@@ -1,11 +1,15 @@
 //! The [glyf (Glyph Data)](https://docs.microsoft.com/en-us/typography/opentype/spec/glyf) table
 
 pub mod bytecode;
+#[cfg(feature = "std")]
+mod closure;
 
 use bytemuck::AnyBitPattern;
 use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Sub};
 use types::{F26Dot6, Point};
 
+use super::loca::Loca;
+
 include!("../../generated/generated_glyf.rs");
 
 /// Marker bits for point flags that are set during variation delta
@@ -179,6 +183,61 @@ pub trait PointCoord:
     fn midpoint(self, other: Self) -> Self;
 }
 
+impl<'a> Glyph<'a> {
+    /// Returns the TrueType interpreter instructions for this glyph.
+    ///
+    /// Returns `None` for a composite glyph without instructions.
+    pub fn instructions(&self) -> Option<&'a [u8]> {
+        match self {
+            Self::Simple(glyph) => Some(glyph.instructions()),
+            Self::Composite(glyph) => glyph.instructions(),
+        }
+    }
+}
+
+/// Summary of the size of the TrueType instructions in a glyf table.
+///
+/// This can be used to compute the `maxSizeOfInstructions` field of the
+/// [maxp](super::maxp) table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InstructionStats {
+    /// Size in bytes of the largest instruction sequence of any glyph.
+    pub max_size: u16,
+    /// The first glyph with instructions of `max_size` bytes, or `None` if
+    /// no glyph has instructions.
+    pub max_size_glyph: Option<GlyphId>,
+    /// Total size in bytes of the instructions of all glyphs.
+    pub total_size: u64,
+    /// Number of glyphs with a non-empty instruction sequence.
+    pub num_instructed_glyphs: u32,
+}
+
+impl InstructionStats {
+    /// Computes instruction statistics for all glyphs in the given tables.
+    pub fn new(loca: &Loca, glyf: &Glyf) -> Result<Self, ReadError> {
+        let mut stats = Self::default();
+        for gid in 0..loca.len() as u32 {
+            let gid = GlyphId::new(gid);
+            let Some(instructions) = loca
+                .get_glyf(gid, glyf)?
+                .and_then(|glyph| glyph.instructions())
+                .filter(|instructions| !instructions.is_empty())
+            else {
+                continue;
+            };
+            // instruction lengths are encoded as uint16
+            let size = instructions.len() as u16;
+            if stats.max_size_glyph.is_none() || size > stats.max_size {
+                stats.max_size = size;
+                stats.max_size_glyph = Some(gid);
+            }
+            stats.total_size += size as u64;
+            stats.num_instructed_glyphs += 1;
+        }
+        Ok(stats)
+    }
+}
+
 impl<'a> SimpleGlyph<'a> {
     /// Returns the total number of points.
     pub fn num_points(&self) -> usize {
@@ -865,6 +924,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn instruction_stats() {
+        let font = FontRef::new(font_test_data::TTHINT_SUBSET).unwrap();
+        let loca = font.loca(None).unwrap();
+        let glyf = font.glyf().unwrap();
+        let stats = InstructionStats::new(&loca, &glyf).unwrap();
+        let sizes: Vec<_> = all_glyphs(font_test_data::TTHINT_SUBSET)
+            .map(|glyph| glyph.and_then(|g| g.instructions()).map_or(0, |i| i.len()))
+            .collect();
+        assert_eq!(sizes, [0, 12233, 11941]);
+        assert_eq!(
+            stats,
+            InstructionStats {
+                max_size: 12233,
+                max_size_glyph: Some(GlyphId::new(1)),
+                total_size: 12233 + 11941,
+                num_instructed_glyphs: 2,
+            }
+        );
+    }
+
     // Test helper to enumerate all TrueType glyphs in the given font
     fn all_glyphs(font_data: &[u8]) -> impl Iterator<Item = Option<Glyph>> {
         let font = FontRef::new(font_data).unwrap();
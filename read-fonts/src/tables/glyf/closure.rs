@@ -0,0 +1,122 @@
+//! Computing the closure over composite glyphs
+//!
+//! This means taking a set of glyphs and updating it to include any
+//! components referenced by composite glyphs in the set, recursively.
+
+use font_types::GlyphId;
+
+use crate::{collections::IntSet, tables::loca::Loca, GlyphIdValidator, InvalidGlyphId};
+
+use super::{Glyf, Glyph};
+
+/// Maximum nesting depth of composite glyphs.
+const MAX_NESTING_LEVEL: usize = 64;
+
+/// Limits the total number of glyphs visited per input glyph to bound the
+/// cost of processing malicious fonts.
+const MAX_OPERATIONS_PER_GLYPH: usize = 64;
+
+impl Glyf<'_> {
+    /// Update the set of glyphs with all components reachable from
+    /// composite glyphs in the set.
+    ///
+    /// Component glyph identifiers are checked with the given validator.
+    /// Glyphs that fail to load are ignored.
+    pub fn closure_glyphs(
+        &self,
+        loca: &Loca,
+        glyph_set: &mut IntSet<GlyphId>,
+        validator: &GlyphIdValidator,
+    ) -> Result<(), InvalidGlyphId> {
+        let mut operations_remaining = (glyph_set.len() as usize)
+            .max(1)
+            .saturating_mul(MAX_OPERATIONS_PER_GLYPH);
+        // (glyph, depth)
+        let mut stack: Vec<(GlyphId, usize)> = glyph_set.iter().map(|gid| (gid, 0)).collect();
+        while let Some((gid, depth)) = stack.pop() {
+            if depth > MAX_NESTING_LEVEL || operations_remaining == 0 {
+                continue;
+            }
+            operations_remaining -= 1;
+            let Ok(Some(Glyph::Composite(glyph))) = loca.get_glyf(gid, self) else {
+                continue;
+            };
+            for component in glyph.components() {
+                let component_gid = validator.validate(component.glyph.into())?;
+                if glyph_set.insert(component_gid) {
+                    stack.push((component_gid, depth + 1));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FontRef, GlyphIdPolicy, TableProvider};
+
+    fn validator(font: &FontRef, policy: GlyphIdPolicy) -> GlyphIdValidator {
+        GlyphIdValidator::from_font(font, policy).unwrap()
+    }
+
+    #[test]
+    fn composite_closure() {
+        let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
+        let loca = font.loca(None).unwrap();
+        let glyf = font.glyf().unwrap();
+        let mut glyph_set: IntSet<GlyphId> = [GlyphId::new(5)].into();
+        glyf.closure_glyphs(
+            &loca,
+            &mut glyph_set,
+            &validator(&font, GlyphIdPolicy::Error),
+        )
+        .unwrap();
+        assert_eq!(
+            glyph_set.iter().collect::<Vec<_>>(),
+            [GlyphId::new(1), GlyphId::new(5)]
+        );
+    }
+
+    #[test]
+    fn simple_glyphs_unchanged() {
+        let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
+        let loca = font.loca(None).unwrap();
+        let glyf = font.glyf().unwrap();
+        let mut glyph_set: IntSet<GlyphId> = [GlyphId::new(1), GlyphId::new(2)].into();
+        glyf.closure_glyphs(
+            &loca,
+            &mut glyph_set,
+            &validator(&font, GlyphIdPolicy::Error),
+        )
+        .unwrap();
+        assert_eq!(glyph_set.len(), 2);
+    }
+
+    #[test]
+    fn out_of_range_components() {
+        let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
+        let loca = font.loca(None).unwrap();
+        let glyf = font.glyf().unwrap();
+        // with a glyph count of 1, the component reference from glyph 5 to
+        // glyph 1 is out of range
+        let glyph_set: IntSet<GlyphId> = [GlyphId::new(5)].into();
+        let error = GlyphIdValidator::new(1, GlyphIdPolicy::Error);
+        assert_eq!(
+            glyf.closure_glyphs(&loca, &mut glyph_set.clone(), &error)
+                .map_err(|e| e.glyph_id()),
+            Err(GlyphId::new(1))
+        );
+        let clamp = GlyphIdValidator::new(1, GlyphIdPolicy::Clamp);
+        let mut clamped = glyph_set.clone();
+        glyf.closure_glyphs(&loca, &mut clamped, &clamp).unwrap();
+        assert_eq!(clamped, [GlyphId::NOTDEF, GlyphId::new(5)].into());
+        let flag = GlyphIdValidator::new(1, GlyphIdPolicy::Flag);
+        let mut flagged = glyph_set.clone();
+        glyf.closure_glyphs(&loca, &mut flagged, &flag).unwrap();
+        assert_eq!(flagged, [GlyphId::new(1), GlyphId::new(5)].into());
+        // glyph 5 references glyph 1 twice
+        assert_eq!(flag.out_of_range_count(), 2);
+    }
+}
@@ -12,7 +12,10 @@ pub use super::layout::{
     ClassDef, CoverageTable, Device, DeviceOrVariationIndex, FeatureList, FeatureVariations,
     Lookup, ScriptList,
 };
-use super::layout::{ExtensionLookup, LookupFlag, Subtables};
+use super::{
+    gdef::{Gdef, LookupGlyphFilter},
+    layout::{ExtensionLookup, LookupFlag, Subtables},
+};
 pub use value_record::ValueRecord;
 
 #[cfg(test)]
@@ -88,6 +91,12 @@ impl<'a> PositionLookup<'a> {
         self.of_unit_type().mark_filtering_set()
     }
 
+    /// Returns a filter that determines which glyphs this lookup skips,
+    /// using the glyph classes and mark sets in the given `GDEF` table.
+    pub fn glyph_filter(&self, gdef: Option<&Gdef<'a>>) -> LookupGlyphFilter<'a> {
+        LookupGlyphFilter::new(gdef, self.lookup_flag(), self.mark_filtering_set())
+    }
+
     /// Return the subtables for this lookup.
     ///
     /// This method handles both extension and non-extension lookups, and saves
@@ -6,7 +6,10 @@ pub use super::layout::{
     ChainedSequenceContext, ClassDef, CoverageTable, Device, FeatureList, FeatureVariations,
     Lookup, LookupList, ScriptList, SequenceContext,
 };
-use super::layout::{ExtensionLookup, LookupFlag, Subtables};
+use super::{
+    gdef::{Gdef, LookupGlyphFilter},
+    layout::{ExtensionLookup, LookupFlag, Subtables},
+};
 
 #[cfg(feature = "std")]
 mod closure;
@@ -62,6 +65,12 @@ impl<'a> SubstitutionLookup<'a> {
         self.of_unit_type().mark_filtering_set()
     }
 
+    /// Returns a filter that determines which glyphs this lookup skips,
+    /// using the glyph classes and mark sets in the given `GDEF` table.
+    pub fn glyph_filter(&self, gdef: Option<&Gdef<'a>>) -> LookupGlyphFilter<'a> {
+        LookupGlyphFilter::new(gdef, self.lookup_flag(), self.mark_filtering_set())
+    }
+
     /// Return the subtables for this lookup.
     ///
     /// This method handles both extension and non-extension lookups, and saves
@@ -52,8 +52,35 @@ impl Gsub<'_> {
         Ok(glyphs)
     }
 
+    /// Return the set of glyphs reachable from the input set via the given lookups.
+    ///
+    /// Unlike [`closure_glyphs`][Self::closure_glyphs], the feature list is not
+    /// consulted: only the lookups whose index is in `lookups` are applied. Nested
+    /// lookups of contextual lookups must be included in `lookups` to participate.
+    pub fn closure_glyphs_for_lookups(
+        &self,
+        mut glyphs: HashSet<GlyphId16>,
+        lookups: &HashSet<u16>,
+    ) -> Result<HashSet<GlyphId16>, ReadError> {
+        loop {
+            let prev_glyph_count = glyphs.len();
+            self.apply_lookups_once(&mut glyphs, lookups)?;
+            if glyphs.len() == prev_glyph_count {
+                return Ok(glyphs);
+            }
+        }
+    }
+
     fn closure_glyphs_once(&self, glyphs: &mut HashSet<GlyphId16>) -> Result<(), ReadError> {
         let lookups_to_use = self.find_reachable_lookups(glyphs)?;
+        self.apply_lookups_once(glyphs, &lookups_to_use)
+    }
+
+    fn apply_lookups_once(
+        &self,
+        glyphs: &mut HashSet<GlyphId16>,
+        lookups_to_use: &HashSet<u16>,
+    ) -> Result<(), ReadError> {
         let lookup_list = self.lookup_list()?;
         for (i, lookup) in lookup_list.lookups().iter().enumerate() {
             if !lookups_to_use.contains(&(i as u16)) {
@@ -585,6 +612,26 @@ mod tests {
         assert_closure_result!(glyph_map, result, &["a", "b", "c", "d"]);
     }
 
+    #[test]
+    fn closure_for_lookups() {
+        // test input is font-test-data/test_data/fea/recursive_closure.fea
+        let gsub = get_gsub(test_data::RECURSIVE);
+        let glyph_map = GlyphMap::new(test_data::RECURSIVE_GLYPHS);
+        let input: HashSet<_> = [glyph_map.get_gid("a").unwrap()].into_iter().collect();
+
+        // only lookups 'one' and 'two'
+        let result = gsub
+            .closure_glyphs_for_lookups(input.clone(), &[1, 2].into_iter().collect())
+            .unwrap();
+        assert_closure_result!(glyph_map, result, &["a", "b", "c"]);
+
+        // lookup 'two' alone is never triggered by 'a'
+        let result = gsub
+            .closure_glyphs_for_lookups(input, &[2].into_iter().collect())
+            .unwrap();
+        assert_closure_result!(glyph_map, result, &["a"]);
+    }
+
     #[test]
     fn contextual_lookups() {
         let gsub = get_gsub(test_data::CONTEXTUAL);
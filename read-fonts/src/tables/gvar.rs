@@ -77,6 +77,55 @@ impl<'a> Gvar<'a> {
         GlyphVariationData::new(data, axis_count, shared_tuples)
     }
 
+    /// Returns the shared tuple at the given index.
+    ///
+    /// Tuple variation headers refer to these by index rather than
+    /// embedding their own peak tuple.
+    pub fn shared_tuple(&self, index: u16) -> Result<Tuple<'a>, ReadError> {
+        self.shared_tuples()?.tuples().get(index as usize)
+    }
+
+    /// Returns the number of tuple variation tables for the given glyph.
+    ///
+    /// Glyphs without variation data have a count of zero.
+    pub fn glyph_tuple_count(&self, gid: GlyphId) -> Result<u16, ReadError> {
+        let data = self.data_for_gid(gid)?;
+        if data.is_empty() {
+            return Ok(0);
+        }
+        let header = GlyphVariationDataHeader::read(data)?;
+        Ok(header.tuple_variation_count().count())
+    }
+
+    /// Computes summary statistics for the variation data of all glyphs
+    /// in the table.
+    pub fn statistics(&self) -> Result<GvarStatistics, ReadError> {
+        let mut stats = GvarStatistics {
+            glyph_count: self.glyph_count(),
+            shared_tuple_count: self.shared_tuple_count(),
+            ..Default::default()
+        };
+        for gid in 0..self.glyph_count() {
+            let gid = GlyphId::from(gid);
+            let data_len = self.data_for_gid(gid)?.len() as u32;
+            let tuple_count = self.glyph_tuple_count(gid)?;
+            if tuple_count != 0 {
+                stats.glyphs_with_variations += 1;
+            }
+            stats.total_tuples += tuple_count as u32;
+            stats.max_tuples_per_glyph = stats.max_tuples_per_glyph.max(tuple_count);
+            stats.total_data_bytes += data_len;
+        }
+        Ok(stats)
+    }
+
+    #[cfg(feature = "experimental_traverse")]
+    fn traverse_glyph_variation_data(&self) -> traversal::FieldType<'a> {
+        let gvar = self.clone();
+        let glyph_count = self.glyph_count() as usize;
+        traversal::FieldType::Array(Box::new(GlyphVariationDataArray { gvar, glyph_count }))
+    }
+
     /// Returns the phantom point deltas for the given variation coordinates
     /// and glyph identifier.
     ///
@@ -117,6 +166,68 @@ impl<'a> Gvar<'a> {
     }
 }
 
+/// Summary statistics for the glyph variations table.
+///
+/// See [`Gvar::statistics`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct GvarStatistics {
+    /// Number of glyphs covered by the table.
+    pub glyph_count: u16,
+    /// Number of tuples in the shared tuple array.
+    pub shared_tuple_count: u16,
+    /// Number of glyphs with at least one tuple variation table.
+    pub glyphs_with_variations: u16,
+    /// Total number of tuple variation tables across all glyphs.
+    pub total_tuples: u32,
+    /// Largest number of tuple variation tables for a single glyph.
+    pub max_tuples_per_glyph: u16,
+    /// Total size in bytes of the variation data for all glyphs.
+    pub total_data_bytes: u32,
+}
+
+/// Per glyph summary of variation data, used for traversal.
+#[cfg(feature = "experimental_traverse")]
+struct GlyphVariationDataArray<'a> {
+    gvar: Gvar<'a>,
+    glyph_count: usize,
+}
+
+#[cfg(feature = "experimental_traverse")]
+impl<'a> traversal::SomeArray<'a> for GlyphVariationDataArray<'a> {
+    fn type_name(&self) -> &str {
+        "GlyphVariationData"
+    }
+
+    fn len(&self) -> usize {
+        self.glyph_count
+    }
+
+    fn get(&self, idx: usize) -> Option<FieldType<'a>> {
+        if idx >= self.glyph_count {
+            return None;
+        }
+        let gid = GlyphId::new(idx as u32);
+        let offset = self
+            .gvar
+            .glyph_variation_data_offsets()
+            .get(idx)
+            .ok()?
+            .get();
+        let byte_len = self.gvar.data_for_gid(gid).ok()?.len() as u32;
+        let tuple_count = self.gvar.glyph_tuple_count(gid).ok()?;
+        Some(FieldType::Record(traversal::RecordResolver {
+            name: "GlyphVariationData",
+            get_field: Box::new(move |idx, _| match idx {
+                0 => Some(Field::new("offset", offset)),
+                1 => Some(Field::new("tuple_count", tuple_count)),
+                2 => Some(Field::new("byte_len", byte_len)),
+                _ => None,
+            }),
+            data: self.gvar.offset_data(),
+        }))
+    }
+}
+
 impl<'a> GlyphVariationData<'a> {
     pub(crate) fn new(
         data: FontData<'a>,
@@ -484,6 +595,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn statistics() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let gvar = font.gvar().unwrap();
+        assert_eq!(
+            gvar.statistics().unwrap(),
+            GvarStatistics {
+                glyph_count: 4,
+                shared_tuple_count: 2,
+                glyphs_with_variations: 3,
+                total_tuples: 6,
+                max_tuples_per_glyph: 2,
+                total_data_bytes: 158,
+            }
+        );
+        // .notdef has no variation data
+        assert_eq!(gvar.glyph_tuple_count(GlyphId::NOTDEF).unwrap(), 0);
+        for gid in 1..4u32 {
+            let gid = GlyphId::new(gid);
+            let tuples = gvar.glyph_variation_data(gid).unwrap().tuples().count();
+            assert_eq!(gvar.glyph_tuple_count(gid).unwrap() as usize, tuples);
+        }
+        assert!(gvar.glyph_tuple_count(GlyphId::new(4)).is_err());
+    }
+
+    #[test]
+    fn shared_tuple() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let gvar = font.gvar().unwrap();
+        let values = |ix| {
+            gvar.shared_tuple(ix)
+                .unwrap()
+                .values()
+                .iter()
+                .map(|v| v.get().to_f32())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(0), [1.0]);
+        assert_eq!(values(1), [-1.0]);
+        assert!(gvar.shared_tuple(2).is_err());
+    }
+
     fn compute_phantom_deltas(
         font: &FontRef,
         coords: &[f32],
@@ -34,7 +34,7 @@ mod tests {
     #[test]
     fn read_hdmx() {
         let buf = make_hdmx();
-        let hdmx = Hdmx::read(buf.font_data(), 2).unwrap();
+        let hdmx = Hdmx::read(buf.font_data()).unwrap();
         assert_eq!(hdmx.version(), 0);
         assert_eq!(hdmx.num_records(), 3);
         assert_eq!(hdmx.size_device_record(), 4);
@@ -67,7 +67,7 @@ mod tests {
     #[test]
     fn find_by_size() {
         let buf = make_hdmx();
-        let hdmx = Hdmx::read(buf.font_data(), 2).unwrap();
+        let hdmx = Hdmx::read(buf.font_data()).unwrap();
         assert_eq!(hdmx.record_for_size(8).unwrap().pixel_size, 8);
         assert_eq!(hdmx.record_for_size(16).unwrap().pixel_size, 16);
         assert_eq!(hdmx.record_for_size(32).unwrap().pixel_size, 32);
@@ -76,6 +76,22 @@ mod tests {
         assert!(hdmx.record_for_size(72).is_none());
     }
 
+    #[test]
+    fn padded_records() {
+        let buf = be_buffer! {
+            0u16,           // version
+            2u16,           // num_records
+            8u32,           // size_device_record
+            // 2 records [pixel_size, max_width, width0..width2, padding]
+            [9u8, 11, 9, 10, 11, 0, 0, 0],
+            [12u8, 14, 12, 13, 14, 0, 0, 0]
+        };
+        let hdmx = Hdmx::read(buf.font_data()).unwrap();
+        let record = hdmx.record_for_size(12).unwrap();
+        assert_eq!(record.max_width, 14);
+        assert_eq!(&record.widths[..3], &[12, 13, 14]);
+    }
+
     fn make_hdmx() -> BeBuffer {
         be_buffer! {
             0u16,           // version
@@ -0,0 +1,443 @@
+//! The [kerning](https://learn.microsoft.com/en-us/typography/opentype/spec/kern) table.
+//!
+//! This supports both the OpenType and the
+//! [Apple](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kern.html)
+//! variants of the table header.
+
+include!("../../generated/generated_kern.rs");
+
+/// The [kerning](https://learn.microsoft.com/en-us/typography/opentype/spec/kern) table.
+#[derive(Clone)]
+pub enum Kern<'a> {
+    /// A table with the OpenType header.
+    Ot(OtKern<'a>),
+    /// A table with the Apple header.
+    Aat(AatKern<'a>),
+}
+
+impl TopLevelTable for Kern<'_> {
+    const TAG: Tag = Tag::new(b"kern");
+}
+
+impl<'a> FontRead<'a> for Kern<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        // The OpenType version is a u16 set to 0 while the Apple version is
+        // a 16.16 fixed point value of 1.0
+        let version = data.read_at::<u16>(0)?;
+        match version {
+            0 => Ok(Self::Ot(OtKern::read(data)?)),
+            1 => Ok(Self::Aat(AatKern::read(data)?)),
+            _ => Err(ReadError::InvalidFormat(version as _)),
+        }
+    }
+}
+
+impl<'a> Kern<'a> {
+    /// Returns an iterator over all of the subtables in this table.
+    pub fn subtables(&self) -> Subtables<'a> {
+        let (data, remaining, is_aat) = match self {
+            Self::Ot(kern) => (kern.subtable_data(), kern.n_tables() as u32, false),
+            Self::Aat(kern) => (kern.subtable_data(), kern.n_tables(), true),
+        };
+        Subtables {
+            data: FontData::new(data),
+            remaining,
+            is_aat,
+        }
+    }
+
+    /// Returns the accumulated horizontal kerning adjustment for the given
+    /// pair of glyphs.
+    ///
+    /// This only considers horizontal subtables that are not cross-stream,
+    /// minimum or variation subtables. Subtables with the override flag set
+    /// replace the value accumulated so far.
+    pub fn kerning(&self, left: GlyphId, right: GlyphId) -> i32 {
+        let mut value = 0;
+        for subtable in self.subtables().filter_map(|subtable| subtable.ok()) {
+            if !subtable.is_horizontal()
+                || subtable.is_cross_stream()
+                || subtable.is_minimum()
+                || subtable.is_variable()
+            {
+                continue;
+            }
+            if let Some(kerning) = subtable.kerning(left, right) {
+                if subtable.is_override() {
+                    value = kerning;
+                } else {
+                    value += kerning;
+                }
+            }
+        }
+        value
+    }
+}
+
+/// Iterator over the subtables of a `kern` table.
+#[derive(Clone)]
+pub struct Subtables<'a> {
+    data: FontData<'a>,
+    remaining: u32,
+    is_aat: bool,
+}
+
+impl<'a> Iterator for Subtables<'a> {
+    type Item = Result<Subtable<'a>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let len = if self.is_aat {
+            self.data.read_at::<u32>(0).map(|len| len as usize)
+        } else if self.remaining == 0 {
+            // The length field of OpenType subtables is only 16 bits and
+            // frequently overflows for large format 0 subtables so we
+            // let the final subtable consume the remaining data
+            Ok(self.data.len())
+        } else {
+            self.data.read_at::<u16>(2).map(|len| len as usize)
+        };
+        let result = len.and_then(|len| {
+            let data = self.data.slice(..len).ok_or(ReadError::OutOfBounds)?;
+            self.data = self.data.split_off(len).unwrap_or_default();
+            if self.is_aat {
+                AatSubtable::read(data).map(Subtable::Aat)
+            } else {
+                OtSubtable::read(data).map(Subtable::Ot)
+            }
+        });
+        if result.is_err() {
+            self.remaining = 0;
+        }
+        Some(result)
+    }
+}
+
+/// A subtable in a `kern` table.
+#[derive(Clone)]
+pub enum Subtable<'a> {
+    /// A subtable from a table with the OpenType header.
+    Ot(OtSubtable<'a>),
+    /// A subtable from a table with the Apple header.
+    Aat(AatSubtable<'a>),
+}
+
+impl<'a> Subtable<'a> {
+    /// Returns the format of the subtable.
+    pub fn format(&self) -> u8 {
+        match self {
+            Self::Ot(subtable) => (subtable.coverage() >> 8) as u8,
+            Self::Aat(subtable) => subtable.coverage() as u8,
+        }
+    }
+
+    /// True if the subtable contains horizontal kerning values.
+    pub fn is_horizontal(&self) -> bool {
+        match self {
+            Self::Ot(subtable) => subtable.coverage() & 0x0001 != 0,
+            Self::Aat(subtable) => subtable.coverage() & 0x8000 == 0,
+        }
+    }
+
+    /// True if the subtable contains minimum values rather than kerning
+    /// values.
+    ///
+    /// This is only defined for the OpenType header.
+    pub fn is_minimum(&self) -> bool {
+        match self {
+            Self::Ot(subtable) => subtable.coverage() & 0x0002 != 0,
+            Self::Aat(_) => false,
+        }
+    }
+
+    /// True if the kerning is perpendicular to the flow of the text.
+    pub fn is_cross_stream(&self) -> bool {
+        match self {
+            Self::Ot(subtable) => subtable.coverage() & 0x0004 != 0,
+            Self::Aat(subtable) => subtable.coverage() & 0x4000 != 0,
+        }
+    }
+
+    /// True if the values in this subtable replace the accumulated value
+    /// rather than being added to it.
+    ///
+    /// This is only defined for the OpenType header.
+    pub fn is_override(&self) -> bool {
+        match self {
+            Self::Ot(subtable) => subtable.coverage() & 0x0008 != 0,
+            Self::Aat(_) => false,
+        }
+    }
+
+    /// True if the subtable contains variation values.
+    ///
+    /// This is only defined for the Apple header.
+    pub fn is_variable(&self) -> bool {
+        match self {
+            Self::Ot(_) => false,
+            Self::Aat(subtable) => subtable.coverage() & 0x2000 != 0,
+        }
+    }
+
+    /// Returns the format specific subtable.
+    pub fn kind(&self) -> Result<SubtableKind<'a>, ReadError> {
+        let (data, header_len) = match self {
+            Self::Ot(subtable) => (
+                subtable.offset_data(),
+                subtable.shape.data_byte_range().start,
+            ),
+            Self::Aat(subtable) => (
+                subtable.offset_data(),
+                subtable.shape.data_byte_range().start,
+            ),
+        };
+        match self.format() {
+            0 => {
+                let data = data.split_off(header_len).ok_or(ReadError::OutOfBounds)?;
+                Ok(SubtableKind::Format0(Subtable0::read(data)?))
+            }
+            2 => Ok(SubtableKind::Format2(Subtable2::read(data, header_len)?)),
+            format => Err(ReadError::InvalidFormat(format as _)),
+        }
+    }
+
+    /// Returns the kerning value for the given pair of glyphs, if
+    /// present in this subtable.
+    pub fn kerning(&self, left: GlyphId, right: GlyphId) -> Option<i32> {
+        match self.kind().ok()? {
+            SubtableKind::Format0(subtable) => subtable.kerning(left, right),
+            SubtableKind::Format2(subtable) => subtable.kerning(left, right),
+        }
+    }
+}
+
+/// The format specific data of a `kern` subtable.
+#[derive(Clone)]
+pub enum SubtableKind<'a> {
+    /// Ordered list of kerning pairs.
+    Format0(Subtable0<'a>),
+    /// Two-dimensional array of kerning values indexed by glyph class.
+    Format2(Subtable2<'a>),
+}
+
+impl Subtable0<'_> {
+    /// Returns the kerning value for the given pair of glyphs, if present.
+    pub fn kerning(&self, left: GlyphId, right: GlyphId) -> Option<i32> {
+        let left: GlyphId16 = left.try_into().ok()?;
+        let right: GlyphId16 = right.try_into().ok()?;
+        let key = |left: GlyphId16, right: GlyphId16| (left.to_u32() << 16) | right.to_u32();
+        let pairs = self.pairs();
+        let index = pairs
+            .binary_search_by_key(&key(left, right), |pair| key(pair.left(), pair.right()))
+            .ok()?;
+        Some(pairs[index].value() as i32)
+    }
+}
+
+/// The type 2 `kern` subtable.
+#[derive(Clone)]
+pub struct Subtable2<'a> {
+    /// Subtable data, including the header. All offsets are relative
+    /// to the start of this data.
+    data: FontData<'a>,
+    row_width: u16,
+    left_offset_table: Subtable2ClassTable<'a>,
+    right_offset_table: Subtable2ClassTable<'a>,
+    array_offset: usize,
+}
+
+impl<'a> Subtable2<'a> {
+    fn read(data: FontData<'a>, header_len: usize) -> Result<Self, ReadError> {
+        let row_width = data.read_at::<u16>(header_len)?;
+        let class_table = |pos| {
+            let offset = data.read_at::<u16>(header_len + pos)? as usize;
+            Subtable2ClassTable::read(data.split_off(offset).ok_or(ReadError::OutOfBounds)?)
+        };
+        let left_offset_table = class_table(2)?;
+        let right_offset_table = class_table(4)?;
+        let array_offset = data.read_at::<u16>(header_len + 6)? as usize;
+        Ok(Self {
+            data,
+            row_width,
+            left_offset_table,
+            right_offset_table,
+            array_offset,
+        })
+    }
+
+    /// Returns the width, in bytes, of a row in the kerning value array.
+    pub fn row_width(&self) -> u16 {
+        self.row_width
+    }
+
+    /// Returns the class table for the left glyph of a pair.
+    pub fn left_offset_table(&self) -> &Subtable2ClassTable<'a> {
+        &self.left_offset_table
+    }
+
+    /// Returns the class table for the right glyph of a pair.
+    pub fn right_offset_table(&self) -> &Subtable2ClassTable<'a> {
+        &self.right_offset_table
+    }
+
+    /// Returns the kerning value for the given pair of glyphs, if present.
+    pub fn kerning(&self, left: GlyphId, right: GlyphId) -> Option<i32> {
+        // Left values are pre-multiplied by the row width and include the
+        // offset to the kerning array, so the sum of the two values is the
+        // offset of the kerning value from the start of the subtable
+        let left_offset = self.left_offset_table.value(left).unwrap_or(0) as usize;
+        let right_offset = self.right_offset_table.value(right).unwrap_or(0) as usize;
+        let offset = left_offset + right_offset;
+        if offset < self.array_offset {
+            return None;
+        }
+        self.data
+            .read_at::<i16>(offset)
+            .ok()
+            .map(|value| value as i32)
+    }
+}
+
+impl Subtable2ClassTable<'_> {
+    /// Returns the class value for the given glyph, if it is covered by
+    /// this table.
+    pub fn value(&self, glyph_id: GlyphId) -> Option<u16> {
+        let index = glyph_id.to_u32().checked_sub(self.first_glyph().to_u32())?;
+        self.offsets().get(index as usize).map(|value| value.get())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::BeBuffer;
+
+    /// Format 0 subtable data with pairs (1, 2) => -50, (1, 3) => -60
+    /// and (4, 5) => 70.
+    fn format0_data(buf: BeBuffer) -> BeBuffer {
+        buf.extend([3u16, 12, 1, 6])
+            .extend([1u16, 2])
+            .push(-50i16)
+            .extend([1u16, 3])
+            .push(-60i16)
+            .extend([4u16, 5])
+            .push(70i16)
+    }
+
+    /// Format 2 subtable data with glyphs 10, 11 on the left and 20, 21 on
+    /// the right. The header length is needed to compute offsets.
+    fn format2_data(buf: BeBuffer, header_len: u16) -> BeBuffer {
+        let left = header_len + 8;
+        let right = left + 8;
+        let array = right + 8;
+        buf.extend([4u16, left, right, array])
+            // left class table
+            .extend([10u16, 2, array, array + 4])
+            // right class table
+            .extend([20u16, 2, 0, 2])
+            .extend([-10i16, -20, -30, -40])
+    }
+
+    fn ot_kern() -> BeBuffer {
+        let buf = BeBuffer::new().extend([0u16, 3]);
+        // format 0
+        let buf = format0_data(buf.extend([0u16, 32, 0x0001]));
+        // format 0 cross-stream
+        let buf = format0_data(buf.extend([0u16, 32, 0x0005]));
+        // format 2, override
+        format2_data(buf.extend([0u16, 38, 0x0209]), 6)
+    }
+
+    fn aat_kern() -> BeBuffer {
+        let buf = BeBuffer::new().push(MajorMinor::VERSION_1_0).push(3u32);
+        // format 0
+        let buf = format0_data(buf.push(34u32).extend([0x0000u16, 0]));
+        // format 0 vertical
+        let buf = format0_data(buf.push(34u32).extend([0x8000u16, 0]));
+        // format 2
+        format2_data(buf.push(40u32).extend([0x0002u16, 0]), 8)
+    }
+
+    #[test]
+    fn ot_subtables() {
+        let buf = ot_kern();
+        let kern = Kern::read(buf.font_data()).unwrap();
+        assert!(matches!(kern, Kern::Ot(_)));
+        let subtables = kern.subtables().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(subtables.len(), 3);
+        assert_eq!(
+            subtables.iter().map(|s| s.format()).collect::<Vec<_>>(),
+            [0, 0, 2]
+        );
+        assert!(subtables.iter().all(|s| s.is_horizontal()));
+        assert!(subtables[1].is_cross_stream());
+        assert!(subtables[2].is_override());
+        let [left, right] = [1, 2].map(GlyphId::new);
+        assert_eq!(subtables[0].kerning(left, right), Some(-50));
+        assert_eq!(
+            subtables[0].kerning(GlyphId::new(4), GlyphId::new(5)),
+            Some(70)
+        );
+        assert_eq!(subtables[0].kerning(right, left), None);
+        let SubtableKind::Format2(format2) = subtables[2].kind().unwrap() else {
+            panic!("expected format 2 subtable");
+        };
+        assert_eq!(format2.row_width(), 4);
+        for (left, right, value) in [(10, 20, -10), (10, 21, -20), (11, 20, -30), (11, 21, -40)] {
+            assert_eq!(
+                format2.kerning(GlyphId::new(left), GlyphId::new(right)),
+                Some(value)
+            );
+        }
+        assert_eq!(format2.kerning(GlyphId::new(12), GlyphId::new(20)), None);
+    }
+
+    #[test]
+    fn aat_subtables() {
+        let buf = aat_kern();
+        let kern = Kern::read(buf.font_data()).unwrap();
+        assert!(matches!(kern, Kern::Aat(_)));
+        let subtables = kern.subtables().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(subtables.len(), 3);
+        assert!(subtables[0].is_horizontal());
+        assert!(!subtables[1].is_horizontal());
+        assert_eq!(subtables[2].format(), 2);
+        assert_eq!(
+            subtables[2].kerning(GlyphId::new(11), GlyphId::new(21)),
+            Some(-40)
+        );
+    }
+
+    #[test]
+    fn pair_kerning() {
+        for buf in [ot_kern(), aat_kern()] {
+            let kern = Kern::read(buf.font_data()).unwrap();
+            // cross-stream and vertical subtables are ignored
+            assert_eq!(kern.kerning(GlyphId::new(1), GlyphId::new(3)), -60);
+            assert_eq!(kern.kerning(GlyphId::new(10), GlyphId::new(21)), -20);
+            assert_eq!(kern.kerning(GlyphId::new(2), GlyphId::new(1)), 0);
+        }
+    }
+
+    #[test]
+    fn invalid_version() {
+        let buf = BeBuffer::new().extend([2u16, 0]);
+        assert!(Kern::read(buf.font_data()).is_err());
+    }
+
+    #[test]
+    fn truncated_subtable() {
+        let buf = BeBuffer::new()
+            .push(MajorMinor::VERSION_1_0)
+            .push(1u32)
+            .push(100u32)
+            .extend([0u16, 0]);
+        let kern = Kern::read(buf.font_data()).unwrap();
+        let mut subtables = kern.subtables();
+        assert!(subtables.next().unwrap().is_err());
+        assert!(subtables.next().is_none());
+    }
+}
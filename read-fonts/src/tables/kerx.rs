@@ -0,0 +1,529 @@
+//! The [extended kerning](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kerx.html) table.
+
+use super::aat::{ExtendedStateTable, LookupU16, LookupU32};
+
+include!("../../generated/generated_kerx.rs");
+
+impl<'a> Kerx<'a> {
+    /// Returns an iterator over all of the subtables in this table.
+    pub fn subtables(&self) -> Subtables<'a> {
+        Subtables {
+            data: FontData::new(self.subtable_data()),
+            remaining: self.n_tables(),
+        }
+    }
+}
+
+/// Iterator over the subtables of a `kerx` table.
+#[derive(Clone)]
+pub struct Subtables<'a> {
+    data: FontData<'a>,
+    remaining: u32,
+}
+
+impl<'a> Iterator for Subtables<'a> {
+    type Item = Result<Subtable<'a>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let result = self.data.read_at::<u32>(0).and_then(|len| {
+            let len = len as usize;
+            let data = self.data.slice(..len).ok_or(ReadError::OutOfBounds)?;
+            self.data = self.data.split_off(len).unwrap_or_default();
+            Subtable::read(data)
+        });
+        if result.is_err() {
+            self.remaining = 0;
+        }
+        Some(result)
+    }
+}
+
+impl<'a> Subtable<'a> {
+    /// Set if the table has vertical kerning values.
+    pub const VERTICAL: u32 = 0x80000000;
+    /// Set if the table has cross-stream kerning values.
+    pub const CROSS_STREAM: u32 = 0x40000000;
+    /// Set if the table has variation kerning values.
+    pub const VARIATION: u32 = 0x20000000;
+    /// Set if glyphs are processed from last to first.
+    pub const PROCESS_DIRECTION: u32 = 0x10000000;
+
+    /// Returns the format of the subtable.
+    pub fn format(&self) -> u8 {
+        self.coverage() as u8
+    }
+
+    /// True if the subtable contains horizontal kerning values.
+    pub fn is_horizontal(&self) -> bool {
+        self.coverage() & Self::VERTICAL == 0
+    }
+
+    /// True if the kerning is perpendicular to the flow of the text.
+    pub fn is_cross_stream(&self) -> bool {
+        self.coverage() & Self::CROSS_STREAM != 0
+    }
+
+    /// True if the subtable contains variation kerning values.
+    pub fn is_variable(&self) -> bool {
+        self.coverage() & Self::VARIATION != 0
+    }
+
+    /// True if glyphs should be processed from last to first by the
+    /// state machine.
+    pub fn is_reverse(&self) -> bool {
+        self.coverage() & Self::PROCESS_DIRECTION != 0
+    }
+
+    /// Returns the format specific subtable.
+    pub fn kind(&self) -> Result<SubtableKind<'a>, ReadError> {
+        let data = self.offset_data();
+        let header_len = self.shape.data_byte_range().start;
+        let body = data.split_off(header_len).ok_or(ReadError::OutOfBounds)?;
+        Ok(match self.format() {
+            0 => SubtableKind::Format0(Subtable0::read(body)?),
+            1 => SubtableKind::Format1(Subtable1::read(body)?),
+            2 => SubtableKind::Format2(Subtable2::read(data, header_len)?),
+            4 => SubtableKind::Format4(Subtable4::read(body)?),
+            6 => SubtableKind::Format6(Subtable6::read(data, header_len)?),
+            format => return Err(ReadError::InvalidFormat(format as _)),
+        })
+    }
+
+    /// Returns the kerning value for the given pair of glyphs, if
+    /// present in this subtable.
+    ///
+    /// This is only available for the formats that do not require a state
+    /// machine (0, 2 and 6).
+    pub fn kerning(&self, left: GlyphId, right: GlyphId) -> Option<i32> {
+        match self.kind().ok()? {
+            SubtableKind::Format0(subtable) => subtable.kerning(left, right),
+            SubtableKind::Format2(subtable) => subtable.kerning(left, right),
+            SubtableKind::Format6(subtable) => subtable.kerning(left, right),
+            _ => None,
+        }
+    }
+}
+
+/// The format specific data of a `kerx` subtable.
+#[derive(Clone)]
+pub enum SubtableKind<'a> {
+    /// Ordered list of kerning pairs.
+    Format0(Subtable0<'a>),
+    /// State table for contextual kerning.
+    Format1(Subtable1<'a>),
+    /// Two-dimensional array of kerning values indexed by glyph class.
+    Format2(Subtable2<'a>),
+    /// State table for control or anchor point positioning.
+    Format4(Subtable4<'a>),
+    /// Two-dimensional array of kerning values indexed by row and column.
+    Format6(Subtable6<'a>),
+}
+
+impl Subtable0<'_> {
+    /// Returns the kerning value for the given pair of glyphs, if present.
+    pub fn kerning(&self, left: GlyphId, right: GlyphId) -> Option<i32> {
+        let left: GlyphId16 = left.try_into().ok()?;
+        let right: GlyphId16 = right.try_into().ok()?;
+        let key = |left: GlyphId16, right: GlyphId16| (left.to_u32() << 16) | right.to_u32();
+        let pairs = self.pairs();
+        let index = pairs
+            .binary_search_by_key(&key(left, right), |pair| key(pair.left(), pair.right()))
+            .ok()?;
+        Some(pairs[index].value() as i32)
+    }
+}
+
+/// The type 1 `kerx` subtable.
+///
+/// Each entry in the state table carries an index into the array of
+/// kerning values, or `0xFFFF` if there is no kerning action.
+#[derive(Clone)]
+pub struct Subtable1<'a> {
+    state_table: ExtendedStateTable<'a, BigEndian<u16>>,
+    values: &'a [BigEndian<i16>],
+}
+
+impl<'a> Subtable1<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let state_table = ExtendedStateTable::read(data)?;
+        // The value table offset follows the 16 byte extended state
+        // table header and is relative to the start of that header
+        let values_offset = data.read_at::<u32>(16)? as usize;
+        let values = read_array_to_end(
+            data.split_off(values_offset)
+                .ok_or(ReadError::OutOfBounds)?,
+        )?;
+        Ok(Self {
+            state_table,
+            values,
+        })
+    }
+
+    /// Returns the state table that drives the kerning actions.
+    pub fn state_table(&self) -> &ExtendedStateTable<'a, BigEndian<u16>> {
+        &self.state_table
+    }
+
+    /// Returns the array of kerning values referenced by state table
+    /// entries.
+    pub fn values(&self) -> &'a [BigEndian<i16>] {
+        self.values
+    }
+}
+
+/// The type 2 `kerx` subtable.
+#[derive(Clone)]
+pub struct Subtable2<'a> {
+    row_width: u32,
+    left_offset_table: LookupU16<'a>,
+    right_offset_table: LookupU16<'a>,
+    array: &'a [BigEndian<i16>],
+}
+
+impl<'a> Subtable2<'a> {
+    fn read(data: FontData<'a>, header_len: usize) -> Result<Self, ReadError> {
+        // All offsets are relative to the start of the subtable, including
+        // the header
+        let read_offset = |pos| {
+            let offset = data.read_at::<u32>(header_len + pos)? as usize;
+            data.split_off(offset).ok_or(ReadError::OutOfBounds)
+        };
+        Ok(Self {
+            row_width: data.read_at::<u32>(header_len)?,
+            left_offset_table: LookupU16::read(read_offset(4)?)?,
+            right_offset_table: LookupU16::read(read_offset(8)?)?,
+            array: read_array_to_end(read_offset(12)?)?,
+        })
+    }
+
+    /// Returns the width, in bytes, of a row in the kerning value array.
+    pub fn row_width(&self) -> u32 {
+        self.row_width
+    }
+
+    /// Returns the class lookup table for the left glyph of a pair.
+    pub fn left_offset_table(&self) -> &LookupU16<'a> {
+        &self.left_offset_table
+    }
+
+    /// Returns the class lookup table for the right glyph of a pair.
+    pub fn right_offset_table(&self) -> &LookupU16<'a> {
+        &self.right_offset_table
+    }
+
+    /// Returns the kerning value for the given pair of glyphs, if present.
+    pub fn kerning(&self, left: GlyphId, right: GlyphId) -> Option<i32> {
+        let left: u16 = left.to_u32().try_into().ok()?;
+        let right: u16 = right.to_u32().try_into().ok()?;
+        // Unlike the `kern` table, the sum of the class values is an index
+        // into the kerning array rather than a byte offset
+        let left_index = self.left_offset_table.value(left).unwrap_or(0) as usize;
+        let right_index = self.right_offset_table.value(right).unwrap_or(0) as usize;
+        self.array
+            .get(left_index + right_index)
+            .map(|value| value.get() as i32)
+    }
+}
+
+/// The type 4 `kerx` subtable.
+///
+/// Each entry in the state table carries an index into the action array,
+/// or `0xFFFF` if there is no action.
+#[derive(Clone)]
+pub struct Subtable4<'a> {
+    state_table: ExtendedStateTable<'a, BigEndian<u16>>,
+    flags: u32,
+    actions: Subtable4Actions<'a>,
+}
+
+impl<'a> Subtable4<'a> {
+    /// Mask for the action type in the flags field.
+    pub const ACTION_TYPE: u32 = 0xC0000000;
+    /// Mask for the offset to the action array in the flags field.
+    pub const OFFSET: u32 = 0x00FFFFFF;
+
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let state_table = ExtendedStateTable::read(data)?;
+        let flags = data.read_at::<u32>(16)?;
+        let action_data = data
+            .split_off((flags & Self::OFFSET) as usize)
+            .ok_or(ReadError::OutOfBounds)?;
+        let actions = match (flags & Self::ACTION_TYPE) >> 30 {
+            0 => Subtable4Actions::ControlPoints(read_array_to_end(action_data)?),
+            1 => Subtable4Actions::AnchorPoints(read_array_to_end(action_data)?),
+            2 => Subtable4Actions::ControlPointCoords(read_array_to_end(action_data)?),
+            action_type => return Err(ReadError::InvalidFormat(action_type as _)),
+        };
+        Ok(Self {
+            state_table,
+            flags,
+            actions,
+        })
+    }
+
+    /// Returns the state table that drives the positioning actions.
+    pub fn state_table(&self) -> &ExtendedStateTable<'a, BigEndian<u16>> {
+        &self.state_table
+    }
+
+    /// Returns the raw flags value containing the action type and the
+    /// offset to the action array.
+    pub fn flags(&self) -> u32 {
+        self.flags
+    }
+
+    /// Returns the positioning actions referenced by state table entries.
+    pub fn actions(&self) -> &Subtable4Actions<'a> {
+        &self.actions
+    }
+}
+
+/// Positioning actions for a type 4 `kerx` subtable.
+#[derive(Clone)]
+pub enum Subtable4Actions<'a> {
+    /// Pairs of control point indices for the marked and current glyphs.
+    ControlPoints(&'a [BigEndian<u16>]),
+    /// Pairs of anchor point indices (from the `ankr` table) for the
+    /// marked and current glyphs.
+    AnchorPoints(&'a [BigEndian<u16>]),
+    /// Sets of four coordinates: the x and y of the marked glyph followed
+    /// by the x and y of the current glyph.
+    ControlPointCoords(&'a [BigEndian<i16>]),
+}
+
+/// The type 6 `kerx` subtable.
+#[derive(Clone)]
+pub struct Subtable6<'a> {
+    flags: u32,
+    row_count: u16,
+    column_count: u16,
+    kind: Subtable6Kind<'a>,
+}
+
+#[derive(Clone)]
+enum Subtable6Kind<'a> {
+    Short {
+        rows: LookupU16<'a>,
+        columns: LookupU16<'a>,
+        array: &'a [BigEndian<i16>],
+    },
+    Long {
+        rows: LookupU32<'a>,
+        columns: LookupU32<'a>,
+        array: &'a [BigEndian<i32>],
+    },
+}
+
+impl<'a> Subtable6<'a> {
+    /// Set if the lookup values and kerning values are 32-bit.
+    pub const VALUES_ARE_LONG: u32 = 0x00000001;
+
+    fn read(data: FontData<'a>, header_len: usize) -> Result<Self, ReadError> {
+        let flags = data.read_at::<u32>(header_len)?;
+        let row_count = data.read_at::<u16>(header_len + 4)?;
+        let column_count = data.read_at::<u16>(header_len + 6)?;
+        // All offsets are relative to the start of the subtable, including
+        // the header
+        let read_offset = |pos| {
+            let offset = data.read_at::<u32>(header_len + pos)? as usize;
+            data.split_off(offset).ok_or(ReadError::OutOfBounds)
+        };
+        let kind = if flags & Self::VALUES_ARE_LONG != 0 {
+            Subtable6Kind::Long {
+                rows: LookupU32::read(read_offset(8)?)?,
+                columns: LookupU32::read(read_offset(12)?)?,
+                array: read_array_to_end(read_offset(16)?)?,
+            }
+        } else {
+            Subtable6Kind::Short {
+                rows: LookupU16::read(read_offset(8)?)?,
+                columns: LookupU16::read(read_offset(12)?)?,
+                array: read_array_to_end(read_offset(16)?)?,
+            }
+        };
+        Ok(Self {
+            flags,
+            row_count,
+            column_count,
+            kind,
+        })
+    }
+
+    /// Returns the flags for this subtable.
+    pub fn flags(&self) -> u32 {
+        self.flags
+    }
+
+    /// Returns the number of rows in the kerning value array.
+    pub fn row_count(&self) -> u16 {
+        self.row_count
+    }
+
+    /// Returns the number of columns in the kerning value array.
+    pub fn column_count(&self) -> u16 {
+        self.column_count
+    }
+
+    /// Returns the kerning value for the given pair of glyphs, if present.
+    pub fn kerning(&self, left: GlyphId, right: GlyphId) -> Option<i32> {
+        let left: u16 = left.to_u32().try_into().ok()?;
+        let right: u16 = right.to_u32().try_into().ok()?;
+        match &self.kind {
+            Subtable6Kind::Short {
+                rows,
+                columns,
+                array,
+            } => {
+                let index = rows.value(left).unwrap_or(0) as usize
+                    + columns.value(right).unwrap_or(0) as usize;
+                array.get(index).map(|value| value.get() as i32)
+            }
+            Subtable6Kind::Long {
+                rows,
+                columns,
+                array,
+            } => {
+                let index = (rows.value(left).unwrap_or(0) as usize)
+                    .checked_add(columns.value(right).unwrap_or(0) as usize)?;
+                array.get(index).map(|value| value.get())
+            }
+        }
+    }
+}
+
+/// Reads as many elements of `T` as fit in the given data.
+fn read_array_to_end<T: bytemuck::AnyBitPattern + FixedSize>(
+    data: FontData<'_>,
+) -> Result<&'_ [T], ReadError> {
+    let len = data.len() / T::RAW_BYTE_LEN * T::RAW_BYTE_LEN;
+    data.read_array(0..len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::BeBuffer;
+
+    /// Format 8 lookup table mapping glyphs starting at `first` to the
+    /// given values.
+    fn lookup(buf: BeBuffer, first: u16, values: &[u16]) -> BeBuffer {
+        buf.extend([8u16, first, values.len() as u16])
+            .extend(values.iter().copied())
+    }
+
+    fn kerx() -> BeBuffer {
+        let buf = BeBuffer::new().extend([2u16, 0]).push(4u32);
+        // format 0 with pairs (1, 2) => -50 and (4, 5) => 70
+        let buf = buf
+            .extend([40u32, 0, 0])
+            .extend([2u32, 12, 1, 0])
+            .extend([1u16, 2])
+            .push(-50i16)
+            .extend([4u16, 5])
+            .push(70i16);
+        // format 0, vertical
+        let buf = buf
+            .extend([34u32, Subtable::VERTICAL, 0])
+            .extend([1u32, 6, 0, 0])
+            .extend([1u16, 2])
+            .push(100i16);
+        // format 2 with glyphs 10, 11 on the left and 20, 21 on the right
+        let buf = buf.extend([56u32, 2, 0]).extend([4u32, 28, 38, 48]);
+        let buf = lookup(buf, 10, &[0, 2]);
+        let buf = lookup(buf, 20, &[0, 1]);
+        let buf = buf.extend([-10i16, -20, -30, -40]);
+        // format 6 with the same glyphs
+        let buf = buf
+            .extend([60u32, 6, 0])
+            .push(0u32)
+            .extend([2u16, 2])
+            .extend([32u32, 42, 52]);
+        let buf = lookup(buf, 10, &[0, 2]);
+        let buf = lookup(buf, 20, &[0, 1]);
+        buf.extend([5i16, 6, 7, 8])
+    }
+
+    #[test]
+    fn subtables() {
+        let buf = kerx();
+        let kerx = Kerx::read(buf.font_data()).unwrap();
+        let subtables = kerx.subtables().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            subtables.iter().map(|s| s.format()).collect::<Vec<_>>(),
+            [0, 0, 2, 6]
+        );
+        assert!(subtables[0].is_horizontal());
+        assert!(!subtables[1].is_horizontal());
+        let gid = GlyphId::new;
+        assert_eq!(subtables[0].kerning(gid(1), gid(2)), Some(-50));
+        assert_eq!(subtables[0].kerning(gid(4), gid(5)), Some(70));
+        assert_eq!(subtables[0].kerning(gid(2), gid(1)), None);
+        assert_eq!(subtables[1].kerning(gid(1), gid(2)), Some(100));
+        for (left, right, format2, format6) in [
+            (10, 20, -10, 5),
+            (10, 21, -20, 6),
+            (11, 20, -30, 7),
+            (11, 21, -40, 8),
+        ] {
+            assert_eq!(subtables[2].kerning(gid(left), gid(right)), Some(format2));
+            assert_eq!(subtables[3].kerning(gid(left), gid(right)), Some(format6));
+        }
+        let SubtableKind::Format6(format6) = subtables[3].kind().unwrap() else {
+            panic!("expected format 6 subtable");
+        };
+        assert_eq!(format6.row_count(), 2);
+        assert_eq!(format6.column_count(), 2);
+    }
+
+    /// Extended state table with 5 classes where glyph 10 has class 4 and
+    /// (state 0, class 4) selects entry 1, which refers to action index 0.
+    ///
+    /// The state table header is followed by the `extra` field and the
+    /// data at offset 60 is given by `tail`.
+    fn state_table_subtable(format: u32, extra: u32, tail: &[u16]) -> BeBuffer {
+        let len = 12 + 60 + tail.len() as u32 * 2;
+        let buf = BeBuffer::new()
+            .extend([len, format, 0])
+            .extend([5u32, 20, 28, 48, extra]);
+        let buf = lookup(buf, 10, &[4]);
+        let buf = buf.extend([0u16, 0, 0, 0, 1, 0, 0, 0, 0, 0]);
+        let buf = buf.extend([0u16, 0, 0xFFFF, 0, 0, 0]);
+        buf.extend(tail.iter().copied())
+    }
+
+    #[test]
+    fn state_table_formats() {
+        let buf = state_table_subtable(1, 60, &[(-25i16) as u16, 0]);
+        let subtable = Subtable::read(buf.font_data()).unwrap();
+        let SubtableKind::Format1(format1) = subtable.kind().unwrap() else {
+            panic!("expected format 1 subtable");
+        };
+        let state_table = format1.state_table();
+        let class = state_table.class(GlyphId16::new(10)).unwrap();
+        assert_eq!(class, 4);
+        let entry = state_table.entry(0, class).unwrap();
+        assert_eq!(entry.payload.get(), 0);
+        assert_eq!(format1.values()[0].get(), -25);
+        assert_eq!(state_table.entry(0, 1).unwrap().payload.get(), 0xFFFF);
+
+        let buf = state_table_subtable(4, (1 << 30) | 60, &[1, 2]);
+        let subtable = Subtable::read(buf.font_data()).unwrap();
+        let SubtableKind::Format4(format4) = subtable.kind().unwrap() else {
+            panic!("expected format 4 subtable");
+        };
+        let Subtable4Actions::AnchorPoints(points) = format4.actions() else {
+            panic!("expected anchor point actions");
+        };
+        assert_eq!(points[0].get(), 1);
+        assert_eq!(points[1].get(), 2);
+        let class = format4.state_table().class(GlyphId16::new(10)).unwrap();
+        assert_eq!(
+            format4.state_table().entry(0, class).unwrap().payload.get(),
+            0
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! OpenType Layout common table formats
 
 mod feature;
+mod feature_variations;
 mod lookup_flag;
 mod script;
 
@@ -1,8 +1,32 @@
 //! Additional support for working with OpenType features.
 
-use super::{Feature, FeatureList, ReadError, TaggedElement};
+use super::{Feature, FeatureList, ReadError, Tag, TaggedElement};
 
 impl<'a> FeatureList<'a> {
+    /// Returns the index of the first feature with the given tag.
+    ///
+    /// Feature records are required to be sorted by tag but, unlike scripts,
+    /// multiple features may share the same tag. Use
+    /// [`LangSys::feature_index_for_tag`](super::LangSys::feature_index_for_tag)
+    /// to find the feature that applies to a particular language system.
+    pub fn index_for_tag(&self, tag: Tag) -> Option<u16> {
+        let records = self.feature_records();
+        let index = records.partition_point(|rec| rec.feature_tag() < tag);
+        records
+            .get(index)
+            .filter(|rec| rec.feature_tag() == tag)
+            .map(|_| index as u16)
+    }
+
+    /// Returns the first feature with the given tag.
+    ///
+    /// See [`index_for_tag`](Self::index_for_tag) for details on how
+    /// duplicate tags are handled.
+    pub fn feature_by_tag(&self, tag: Tag) -> Option<Result<Feature<'a>, ReadError>> {
+        let index = self.index_for_tag(tag)?;
+        Some(self.feature_records()[index as usize].feature(self.offset_data()))
+    }
+
     /// Returns the tag and feature at the given index.
     pub fn get(&self, index: u16) -> Result<TaggedElement<Feature<'a>>, ReadError> {
         self.feature_records()
@@ -15,6 +39,14 @@ impl<'a> FeatureList<'a> {
                 ))
             })
     }
+
+    /// Returns an iterator over all of the features and their tags.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = Result<TaggedElement<Feature<'a>>, ReadError>> + 'a + Clone {
+        let list = self.clone();
+        (0..self.feature_count()).map(move |index| list.get(index))
+    }
 }
 
 #[cfg(test)]
@@ -30,4 +62,43 @@ mod tests {
         assert_eq!(feature_list.get(1).unwrap().tag, Tag::new(b"liga"));
         assert!(feature_list.get(2).is_err());
     }
+
+    #[test]
+    fn feature_list_by_tag() {
+        let font = FontRef::new(font_test_data::NOTOSERIF_AUTOHINT_SHAPING).unwrap();
+        let gsub = font.gsub().unwrap();
+        let feature_list = gsub.feature_list().unwrap();
+        assert_eq!(feature_list.index_for_tag(Tag::new(b"c2sc")), Some(0));
+        assert_eq!(feature_list.index_for_tag(Tag::new(b"liga")), Some(1));
+        assert_eq!(feature_list.index_for_tag(Tag::new(b"kern")), None);
+        let liga = feature_list.feature_by_tag(Tag::new(b"liga")).unwrap();
+        let lookups = liga.unwrap().lookup_list_indices().to_vec();
+        assert_eq!(lookups.iter().map(|ix| ix.get()).collect::<Vec<_>>(), [1]);
+        assert!(feature_list.feature_by_tag(Tag::new(b"zzzz")).is_none());
+    }
+
+    #[test]
+    fn feature_list_duplicate_tags() {
+        use super::FeatureList;
+        use crate::{test_helpers::BeBuffer, FontRead};
+        // two 'liga' features pointing at different feature tables
+        let data = BeBuffer::new()
+            .push(3u16)
+            .push(Tag::new(b"c2sc"))
+            .push(20u16)
+            .push(Tag::new(b"liga"))
+            .push(26u16)
+            .push(Tag::new(b"liga"))
+            .push(20u16)
+            // feature tables: params offset, lookup count, lookup indices
+            .extend([0u16, 1, 0])
+            .extend([0u16, 1, 1]);
+        let feature_list = FeatureList::read(data.font_data()).unwrap();
+        assert_eq!(feature_list.index_for_tag(Tag::new(b"liga")), Some(1));
+        let liga = feature_list
+            .feature_by_tag(Tag::new(b"liga"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(liga.lookup_list_indices()[0].get(), 1);
+    }
 }
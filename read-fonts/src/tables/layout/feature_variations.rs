@@ -0,0 +1,241 @@
+//! Evaluation of feature variations.
+//!
+//! See <https://learn.microsoft.com/en-us/typography/opentype/spec/chapter2#featurevariations-table>
+
+use super::{
+    Condition, ConditionSet, DeltaSetIndex, F2Dot14, Feature, FeatureTableSubstitution,
+    FeatureVariations, ReadError,
+};
+use crate::tables::variations::ItemVariationStore;
+
+impl<'a> FeatureVariations<'a> {
+    /// Returns the index of the first feature variation record with a
+    /// condition set that matches the given normalized coordinates.
+    ///
+    /// The item variation store from the `GDEF` table is required to
+    /// evaluate conditions on variable values. If it is not provided, the
+    /// default values of those conditions are used.
+    pub fn find_index(
+        &self,
+        coords: &[F2Dot14],
+        var_store: Option<&ItemVariationStore>,
+    ) -> Option<u32> {
+        let data = self.offset_data();
+        self.feature_variation_records()
+            .iter()
+            .position(|record| match record.condition_set(data) {
+                // a null condition set matches all instances
+                None => true,
+                Some(Ok(condition_set)) => condition_set.evaluate(coords, var_store),
+                Some(Err(_)) => false,
+            })
+            .map(|index| index as u32)
+    }
+
+    /// Returns the feature table substitutions that apply at the given
+    /// normalized coordinates.
+    ///
+    /// This is the substitution table for the first record with a matching
+    /// condition set or `None` if no record matches.
+    pub fn substitutions_at(
+        &self,
+        coords: &[F2Dot14],
+        var_store: Option<&ItemVariationStore>,
+    ) -> Result<Option<FeatureTableSubstitution<'a>>, ReadError> {
+        let Some(index) = self.find_index(coords, var_store) else {
+            return Ok(None);
+        };
+        self.feature_variation_records()
+            .get(index as usize)
+            .ok_or(ReadError::OutOfBounds)?
+            .feature_table_substitution(self.offset_data())
+            .transpose()
+    }
+}
+
+impl ConditionSet<'_> {
+    /// Returns true if all conditions in the set are satisfied at the given
+    /// normalized coordinates.
+    ///
+    /// An empty condition set is always satisfied. Conditions that fail to
+    /// load are treated as unsatisfied.
+    pub fn evaluate(&self, coords: &[F2Dot14], var_store: Option<&ItemVariationStore>) -> bool {
+        self.conditions().iter().all(|condition| {
+            condition
+                .map(|condition| condition.evaluate(coords, var_store))
+                .unwrap_or_default()
+        })
+    }
+}
+
+impl Condition<'_> {
+    /// Returns true if the condition is satisfied at the given normalized
+    /// coordinates.
+    ///
+    /// Nested conditions that fail to load are treated as unsatisfied.
+    pub fn evaluate(&self, coords: &[F2Dot14], var_store: Option<&ItemVariationStore>) -> bool {
+        self.evaluate_rec(coords, var_store, 0)
+    }
+
+    fn evaluate_rec(
+        &self,
+        coords: &[F2Dot14],
+        var_store: Option<&ItemVariationStore>,
+        depth: usize,
+    ) -> bool {
+        // Bound the nesting of boolean conditions to avoid stack overflows
+        // on malicious fonts
+        const MAX_NESTING_DEPTH: usize = 64;
+        if depth > MAX_NESTING_DEPTH {
+            return false;
+        }
+        let eval_nested = |condition: Result<Condition, ReadError>| {
+            condition
+                .map(|condition| condition.evaluate_rec(coords, var_store, depth + 1))
+                .unwrap_or_default()
+        };
+        match self {
+            Self::Format1AxisRange(condition) => {
+                let coord = coords
+                    .get(condition.axis_index() as usize)
+                    .copied()
+                    .unwrap_or_default();
+                coord >= condition.filter_range_min_value()
+                    && coord <= condition.filter_range_max_value()
+            }
+            Self::Format2VariableValue(condition) => {
+                let var_index = condition.var_index();
+                let delta = var_store
+                    .and_then(|store| {
+                        store
+                            .compute_delta(
+                                DeltaSetIndex {
+                                    outer: (var_index >> 16) as u16,
+                                    inner: var_index as u16,
+                                },
+                                coords,
+                            )
+                            .ok()
+                    })
+                    .unwrap_or_default();
+                condition.default_value() as i32 + delta > 0
+            }
+            Self::Format3And(condition) => condition.conditions().iter().all(eval_nested),
+            Self::Format4Or(condition) => condition.conditions().iter().any(eval_nested),
+            Self::Format5Negate(condition) => !eval_nested(condition.condition()),
+        }
+    }
+}
+
+impl<'a> FeatureTableSubstitution<'a> {
+    /// Returns the alternate feature table for the feature at the given
+    /// index in the feature list, if it is substituted.
+    pub fn alternate_feature(&self, feature_index: u16) -> Option<Feature<'a>> {
+        let records = self.substitutions();
+        let index = records
+            .binary_search_by_key(&feature_index, |record| record.feature_index())
+            .ok()?;
+        records[index].alternate_feature(self.offset_data()).ok()
+    }
+
+    /// Returns an iterator over the indices of the substituted features and
+    /// their alternate feature tables.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, Result<Feature<'a>, ReadError>)> + 'a + Clone {
+        let data = self.offset_data();
+        self.substitutions()
+            .iter()
+            .map(move |record| (record.feature_index(), record.alternate_feature(data)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FontRead, FontRef, TableProvider};
+
+    fn coords(value: f32) -> [F2Dot14; 1] {
+        [F2Dot14::from_f32(value)]
+    }
+
+    #[test]
+    fn find_matching_record() {
+        let font = FontRef::new(font_test_data::closure::VARIATIONS_CLOSURE).unwrap();
+        let gsub = font.gsub().unwrap();
+        let feature_variations = gsub.feature_variations().unwrap().unwrap();
+        // the single condition set is wght in [0.625, 0.875]
+        for (value, expected) in [
+            (0.0, None),
+            (0.5, None),
+            (0.625, Some(0)),
+            (0.75, Some(0)),
+            (0.875, Some(0)),
+            (1.0, None),
+        ] {
+            assert_eq!(
+                feature_variations.find_index(&coords(value), None),
+                expected,
+                "{value}"
+            );
+        }
+        assert!(feature_variations
+            .substitutions_at(&coords(0.0), None)
+            .unwrap()
+            .is_none());
+        let substitutions = feature_variations
+            .substitutions_at(&coords(0.75), None)
+            .unwrap()
+            .unwrap();
+        let feature = substitutions.alternate_feature(0).unwrap();
+        let lookups = feature
+            .lookup_list_indices()
+            .iter()
+            .map(|index| index.get())
+            .collect::<Vec<_>>();
+        assert_eq!(lookups, [0, 1]);
+        assert!(substitutions.alternate_feature(1).is_none());
+        assert_eq!(substitutions.iter().count(), 1);
+    }
+
+    // Condition format 1 on axis 0 with range [0.5, 1.0]
+    fn axis_range_condition() -> [u8; 8] {
+        [0, 1, 0, 0, 0x20, 0x00, 0x40, 0x00]
+    }
+
+    #[test]
+    fn boolean_conditions() {
+        use crate::FontData;
+        let range = axis_range_condition();
+        // format 5, offset24 to the nested condition
+        let mut negate = vec![0, 5, 0, 0, 5];
+        negate.extend(range);
+        // format 3/4, count of 2, offset24s to the nested conditions where
+        // the second is the negation of the first
+        let mut and = vec![0, 3, 2, 0, 0, 9, 0, 0, 17];
+        and.extend(range);
+        and.extend(&negate);
+        let mut or = and.clone();
+        or[1] = 4;
+        let [range, negate, and, or] = [range.as_slice(), &negate, &and, &or]
+            .map(|data| Condition::read(FontData::new(data)).unwrap());
+        for value in [0.0, 0.25, 0.5, 0.75, 1.0] {
+            let coords = coords(value);
+            let in_range = value >= 0.5;
+            assert_eq!(range.evaluate(&coords, None), in_range);
+            assert_eq!(negate.evaluate(&coords, None), !in_range);
+            assert!(!and.evaluate(&coords, None));
+            assert!(or.evaluate(&coords, None));
+        }
+    }
+
+    #[test]
+    fn variable_value_condition_default() {
+        use crate::FontData;
+        // format 2 with default value and variation index
+        let enabled = [0, 2, 0, 1, 0, 0, 0, 0];
+        let disabled = [0, 2, 0, 0, 0, 0, 0, 0];
+        for (data, expected) in [(enabled, true), (disabled, false)] {
+            let condition = Condition::read(FontData::new(&data)).unwrap();
+            assert_eq!(condition.evaluate(&[], None), expected);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! Additional support for working with OpenType scripts and language systems.
 
-use super::{FeatureList, LangSys, ReadError, Script, ScriptList, Tag, TaggedElement};
+use super::{Feature, FeatureList, LangSys, ReadError, Script, ScriptList, Tag, TaggedElement};
 use std::ops::Deref;
 
 /// A script chosen from a set of candidate tags.
@@ -25,6 +25,12 @@ impl<'a> ScriptList<'a> {
             .ok()
     }
 
+    /// Returns the script with the given tag.
+    pub fn script_by_tag(&self, tag: Tag) -> Option<Result<Script<'a>, ReadError>> {
+        let index = self.index_for_tag(tag)?;
+        Some(self.script_records()[index as usize].script(self.offset_data()))
+    }
+
     /// Returns the tag and script at the given index.
     pub fn get(&self, index: u16) -> Result<TaggedElement<Script<'a>>, ReadError> {
         self.script_records()
@@ -38,6 +44,14 @@ impl<'a> ScriptList<'a> {
             })
     }
 
+    /// Returns an iterator over all of the scripts and their tags.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = Result<TaggedElement<Script<'a>>, ReadError>> + 'a + Clone {
+        let list = self.clone();
+        (0..self.script_count()).map(move |index| list.get(index))
+    }
+
     /// Finds the first available script that matches one of the given tags.
     ///
     /// When none of the requested scripts are available, then `DFLT`, `dflt`
@@ -90,6 +104,15 @@ impl<'a> Script<'a> {
             .ok()
     }
 
+    /// Returns the language system with the given tag.
+    ///
+    /// This does not consider the default language system which is
+    /// available from the `default_lang_sys` method.
+    pub fn lang_sys_by_tag(&self, tag: Tag) -> Option<Result<LangSys<'a>, ReadError>> {
+        let index = self.lang_sys_index_for_tag(tag)?;
+        Some(self.lang_sys_records()[index as usize].lang_sys(self.offset_data()))
+    }
+
     /// Returns the language system with the given index.
     pub fn lang_sys(&self, index: u16) -> Result<TaggedElement<LangSys<'a>>, ReadError> {
         self.lang_sys_records()
@@ -102,9 +125,37 @@ impl<'a> Script<'a> {
                 ))
             })
     }
+
+    /// Returns an iterator over all of the language systems and their tags.
+    ///
+    /// This does not include the default language system which is available
+    /// from the `default_lang_sys` method.
+    pub fn lang_sys_iter(
+        &self,
+    ) -> impl Iterator<Item = Result<TaggedElement<LangSys<'a>>, ReadError>> + 'a + Clone {
+        let script = self.clone();
+        (0..self.lang_sys_count()).map(move |index| script.lang_sys(index))
+    }
 }
 
-impl LangSys<'_> {
+impl<'a> LangSys<'a> {
+    /// Returns an iterator over the features referenced by the language
+    /// system along with their indices in the specified feature list.
+    ///
+    /// The required feature, if any, is yielded first.
+    pub fn features(
+        &self,
+        list: &FeatureList<'a>,
+    ) -> impl Iterator<Item = Result<(u16, TaggedElement<Feature<'a>>), ReadError>> + 'a + Clone
+    {
+        let list = list.clone();
+        let required = Some(self.required_feature_index()).filter(|index| *index != 0xFFFF);
+        required
+            .into_iter()
+            .chain(self.feature_indices().iter().map(|index| index.get()))
+            .map(move |index| Ok((index, list.get(index)?)))
+    }
+
     /// If the language system references a feature with the given tag,
     /// returns the index of that feature in the specified feature list.
     ///
@@ -247,6 +298,92 @@ mod tests {
         }
     }
 
+    #[test]
+    fn script_and_lang_sys_by_tag() {
+        let font = FontRef::new(font_test_data::NOTOSERIFHEBREW_AUTOHINT_METRICS).unwrap();
+        let scripts = font.gsub().unwrap().script_list().unwrap();
+        let hebr = scripts.script_by_tag(Tag::new(b"hebr")).unwrap().unwrap();
+        let expected = scripts.get(3).unwrap();
+        assert_eq!(
+            hebr.offset_data().as_bytes(),
+            expected.offset_data().as_bytes()
+        );
+        assert!(scripts.script_by_tag(Tag::new(b"arab")).is_none());
+        for script in scripts.iter() {
+            let script = script.unwrap();
+            for lang_sys in script.lang_sys_iter() {
+                let lang_sys = lang_sys.unwrap();
+                let by_tag = script.lang_sys_by_tag(lang_sys.tag).unwrap().unwrap();
+                assert_eq!(
+                    by_tag.offset_data().as_bytes(),
+                    lang_sys.offset_data().as_bytes()
+                );
+            }
+            assert!(script.lang_sys_by_tag(Tag::new(b"XXX ")).is_none());
+        }
+    }
+
+    #[test]
+    fn iter_scripts_and_features() {
+        let font = FontRef::new(font_test_data::NOTOSERIF_AUTOHINT_SHAPING).unwrap();
+        let gsub = font.gsub().unwrap();
+        let feature_list = gsub.feature_list().unwrap();
+        let scripts = gsub
+            .script_list()
+            .unwrap()
+            .iter()
+            .map(|script| script.unwrap())
+            .collect::<Vec<_>>();
+        let tags = scripts.iter().map(|script| script.tag).collect::<Vec<_>>();
+        assert_eq!(tags, [b"DFLT", b"cyrl", b"grek", b"latn"].map(Tag::new));
+        for script in &scripts {
+            assert_eq!(script.lang_sys_iter().count(), 0);
+            let lang_sys = script.default_lang_sys().unwrap().unwrap();
+            let features = lang_sys
+                .features(&feature_list)
+                .map(|feature| {
+                    let (index, feature) = feature.unwrap();
+                    let lookups = feature
+                        .lookup_list_indices()
+                        .iter()
+                        .map(|index| index.get())
+                        .collect::<Vec<_>>();
+                    (index, feature.tag, lookups)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                features,
+                [
+                    (0, Tag::new(b"c2sc"), vec![0]),
+                    (1, Tag::new(b"liga"), vec![1])
+                ]
+            );
+        }
+        let feature_tags = feature_list
+            .iter()
+            .map(|feature| feature.unwrap().tag)
+            .collect::<Vec<_>>();
+        assert_eq!(feature_tags, [Tag::new(b"c2sc"), Tag::new(b"liga")]);
+    }
+
+    #[test]
+    fn lang_sys_required_feature() {
+        use crate::{test_helpers::BeBuffer, FontRead};
+        let font = FontRef::new(font_test_data::NOTOSERIF_AUTOHINT_SHAPING).unwrap();
+        let feature_list = font.gsub().unwrap().feature_list().unwrap();
+        // lookup order, required feature index, feature count and indices
+        let data = BeBuffer::new().extend([0u16, 1, 1, 0]);
+        let lang_sys = LangSys::read(data.font_data()).unwrap();
+        let features = lang_sys
+            .features(&feature_list)
+            .map(|feature| {
+                let (index, feature) = feature.unwrap();
+                (index, feature.tag)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(features, [(1, Tag::new(b"liga")), (0, Tag::new(b"c2sc"))]);
+    }
+
     #[test]
     fn simple_script_tag_from_unicode() {
         let unicode_tags = [b"Cyrl", b"Grek", b"Hebr", b"Latn"];
@@ -54,6 +54,39 @@ impl<'a> Loca<'a> {
         }
     }
 
+    /// Checks that the table is consistent with the given glyph count and
+    /// `glyf` table length.
+    ///
+    /// This verifies that there is an offset for each glyph plus a final
+    /// offset marking the end of the last glyph, that all offsets are in
+    /// ascending order and that no glyph extends beyond the end of the
+    /// `glyf` table.
+    ///
+    /// The other methods on this type tolerate malformed tables, so this
+    /// is intended for code that produces new `glyf` and `loca` tables from
+    /// existing ones and would otherwise need to repeat these checks.
+    pub fn validate(&self, num_glyphs: u32, glyf_len: usize) -> Result<(), ReadError> {
+        if self.len() < num_glyphs as usize {
+            return Err(ReadError::MalformedData(
+                "loca has fewer offsets than glyphs.",
+            ));
+        }
+        if !self.all_offsets_are_ascending() {
+            return Err(ReadError::MalformedData("loca contains unordered offsets."));
+        }
+        // Offsets are ascending so we only need to check the last one
+        // that refers to a glyph.
+        let end = self
+            .get_raw(num_glyphs as usize)
+            .ok_or(ReadError::OutOfBounds)?;
+        if end as usize > glyf_len {
+            return Err(ReadError::MalformedData(
+                "loca offsets extend beyond the end of glyf.",
+            ));
+        }
+        Ok(())
+    }
+
     /// Attempt to return the offset for a given glyph id.
     pub fn get_raw(&self, idx: usize) -> Option<u32> {
         match self {
@@ -139,7 +172,7 @@ impl<'a> std::fmt::Debug for Loca<'a> {
 mod tests {
     use types::Scalar;
 
-    use crate::test_helpers::BeBuffer;
+    use crate::{test_helpers::BeBuffer, ReadError};
 
     use super::Loca;
 
@@ -188,4 +221,32 @@ mod tests {
         check_loca_sorting(&[2, 1, 3], false);
         check_loca_sorting(&[1, 2, 3, 2, 7], false);
     }
+
+    #[test]
+    fn validate() {
+        let (bytes, is_long) = to_loca_bytes(&[0u32, 10, 10, 24]);
+        let loca = Loca::read(bytes.font_data(), is_long).unwrap();
+        assert_eq!(loca.validate(3, 24), Ok(()));
+        // fewer glyphs than offsets and trailing glyf data are fine
+        assert_eq!(loca.validate(2, 32), Ok(()));
+        assert_eq!(
+            loca.validate(4, 24),
+            Err(ReadError::MalformedData(
+                "loca has fewer offsets than glyphs."
+            ))
+        );
+        assert_eq!(
+            loca.validate(3, 20),
+            Err(ReadError::MalformedData(
+                "loca offsets extend beyond the end of glyf."
+            ))
+        );
+        // short offsets are scaled by two
+        let (bytes, is_long) = to_loca_bytes(&[0u16, 5, 3]);
+        let loca = Loca::read(bytes.font_data(), is_long).unwrap();
+        assert_eq!(
+            loca.validate(2, 10),
+            Err(ReadError::MalformedData("loca contains unordered offsets."))
+        );
+    }
 }
@@ -0,0 +1,30 @@
+//! The [LTSH (Linear Threshold)](https://learn.microsoft.com/en-us/typography/opentype/spec/ltsh) table
+
+include!("../../generated/generated_ltsh.rs");
+
+impl Ltsh<'_> {
+    /// Returns the vertical pel height at which the given glyph can be
+    /// assumed to scale linearly.
+    ///
+    /// A value of 1 indicates that the glyph does not scale linearly at
+    /// any size.
+    pub fn y_pel(&self, glyph_id: GlyphId) -> Option<u8> {
+        self.y_pels().get(glyph_id.to_u32() as usize).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::BeBuffer;
+
+    #[test]
+    fn y_pels() {
+        let buf = BeBuffer::new().extend([0u16, 3]).extend([1u8, 12, 255]);
+        let ltsh = Ltsh::read(buf.font_data()).unwrap();
+        assert_eq!(ltsh.num_glyphs(), 3);
+        assert_eq!(ltsh.y_pel(GlyphId::new(0)), Some(1));
+        assert_eq!(ltsh.y_pel(GlyphId::new(2)), Some(255));
+        assert_eq!(ltsh.y_pel(GlyphId::new(3)), None);
+    }
+}
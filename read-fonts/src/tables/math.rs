@@ -0,0 +1,378 @@
+//! The [MATH](https://learn.microsoft.com/en-us/typography/opentype/spec/math) table
+
+pub use super::layout::{CoverageTable, DeviceOrVariationIndex};
+
+include!("../../generated/generated_math.rs");
+
+/// Generates named accessors for the entries of the
+/// [`MathConstants::math_value_records`] array.
+macro_rules! math_value_accessors {
+    ($($(#[$doc:meta])* $name:ident = $index:literal,)*) => {
+        impl MathConstants<'_> {
+            $(
+                $(#[$doc])*
+                pub fn $name(&self) -> MathValueRecord {
+                    self.math_value_records()[$index]
+                }
+            )*
+        }
+    };
+}
+
+math_value_accessors! {
+    /// White space to be left between math formulae to ensure proper line
+    /// spacing.
+    math_leading = 0,
+    /// Axis height of the font.
+    axis_height = 1,
+    /// Maximum (ink) height of accent base that does not require raising the
+    /// accents.
+    accent_base_height = 2,
+    /// Maximum (ink) height of accent base that does not require flattening
+    /// the accents.
+    flattened_accent_base_height = 3,
+    /// The standard shift down applied to subscript elements.
+    subscript_shift_down = 4,
+    /// Maximum allowed height of the (ink) top of subscripts that does not
+    /// require moving subscripts further down.
+    subscript_top_max = 5,
+    /// Minimum allowed drop of the baseline of subscripts relative to the
+    /// (ink) bottom of the base.
+    subscript_baseline_drop_min = 6,
+    /// Standard shift up applied to superscript elements.
+    superscript_shift_up = 7,
+    /// Standard shift of superscripts relative to the base, in cramped style.
+    superscript_shift_up_cramped = 8,
+    /// Minimum allowed height of the (ink) bottom of superscripts that does
+    /// not require moving subscripts further up.
+    superscript_bottom_min = 9,
+    /// Maximum allowed drop of the baseline of superscripts relative to the
+    /// (ink) top of the base.
+    superscript_baseline_drop_max = 10,
+    /// Minimum gap between the superscript and subscript ink.
+    sub_superscript_gap_min = 11,
+    /// The maximum level to which the (ink) bottom of superscript can be
+    /// pushed to increase the gap between superscript and subscript, before
+    /// subscript starts being moved down.
+    superscript_bottom_max_with_subscript = 12,
+    /// Extra white space to be added after each subscript and superscript.
+    space_after_script = 13,
+    /// Minimum gap between the (ink) bottom of the upper limit, and the (ink)
+    /// top of the base operator.
+    upper_limit_gap_min = 14,
+    /// Minimum distance between baseline of upper limit and (ink) top of the
+    /// base operator.
+    upper_limit_baseline_rise_min = 15,
+    /// Minimum gap between (ink) top of the lower limit, and (ink) bottom of
+    /// the base operator.
+    lower_limit_gap_min = 16,
+    /// Minimum distance between baseline of the lower limit and (ink) bottom
+    /// of the base operator.
+    lower_limit_baseline_drop_min = 17,
+    /// Standard shift up applied to the top element of a stack.
+    stack_top_shift_up = 18,
+    /// Standard shift up applied to the top element of a stack in display
+    /// style.
+    stack_top_display_style_shift_up = 19,
+    /// Standard shift down applied to the bottom element of a stack.
+    stack_bottom_shift_down = 20,
+    /// Standard shift down applied to the bottom element of a stack in
+    /// display style.
+    stack_bottom_display_style_shift_down = 21,
+    /// Minimum gap between (ink) bottom of the top element of a stack, and
+    /// the (ink) top of the bottom element.
+    stack_gap_min = 22,
+    /// Minimum gap between (ink) bottom of the top element of a stack, and
+    /// the (ink) top of the bottom element in display style.
+    stack_display_style_gap_min = 23,
+    /// Standard shift up applied to the top element of the stretch stack.
+    stretch_stack_top_shift_up = 24,
+    /// Standard shift down applied to the bottom element of the stretch
+    /// stack.
+    stretch_stack_bottom_shift_down = 25,
+    /// Minimum gap between the ink of the stretched element, and the (ink)
+    /// bottom of the element above.
+    stretch_stack_gap_above_min = 26,
+    /// Minimum gap between the ink of the stretched element, and the (ink)
+    /// top of the element below.
+    stretch_stack_gap_below_min = 27,
+    /// Standard shift up applied to the numerator.
+    fraction_numerator_shift_up = 28,
+    /// Standard shift up applied to the numerator in display style.
+    fraction_numerator_display_style_shift_up = 29,
+    /// Standard shift down applied to the denominator.
+    fraction_denominator_shift_down = 30,
+    /// Standard shift down applied to the denominator in display style.
+    fraction_denominator_display_style_shift_down = 31,
+    /// Minimum tolerated gap between the (ink) bottom of the numerator and
+    /// the ink of the fraction bar.
+    fraction_numerator_gap_min = 32,
+    /// Minimum tolerated gap between the (ink) bottom of the numerator and
+    /// the ink of the fraction bar in display style.
+    fraction_num_display_style_gap_min = 33,
+    /// Thickness of the fraction bar.
+    fraction_rule_thickness = 34,
+    /// Minimum tolerated gap between the (ink) top of the denominator and the
+    /// ink of the fraction bar.
+    fraction_denominator_gap_min = 35,
+    /// Minimum tolerated gap between the (ink) top of the denominator and the
+    /// ink of the fraction bar in display style.
+    fraction_denom_display_style_gap_min = 36,
+    /// Horizontal distance between the top and bottom elements of a skewed
+    /// fraction.
+    skewed_fraction_horizontal_gap = 37,
+    /// Vertical distance between the ink of the top and bottom elements of a
+    /// skewed fraction.
+    skewed_fraction_vertical_gap = 38,
+    /// Distance between the overbar and the (ink) top of he base.
+    overbar_vertical_gap = 39,
+    /// Thickness of overbar.
+    overbar_rule_thickness = 40,
+    /// Extra white space reserved above the overbar.
+    overbar_extra_ascender = 41,
+    /// Distance between underbar and (ink) bottom of the base.
+    underbar_vertical_gap = 42,
+    /// Thickness of underbar.
+    underbar_rule_thickness = 43,
+    /// Extra white space reserved below the underbar.
+    underbar_extra_descender = 44,
+    /// Space between the (ink) top of the expression and the bar over it.
+    radical_vertical_gap = 45,
+    /// Space between the (ink) top of the expression and the bar over it in
+    /// display style.
+    radical_display_style_vertical_gap = 46,
+    /// Thickness of the radical rule.
+    radical_rule_thickness = 47,
+    /// Extra white space reserved above the radical.
+    radical_extra_ascender = 48,
+    /// Extra horizontal kern before the degree of a radical, if such is
+    /// present.
+    radical_kern_before_degree = 49,
+    /// Negative kern after the degree of a radical, if such is present.
+    radical_kern_after_degree = 50,
+}
+
+impl GlyphAssembly<'_> {
+    /// Italics correction of this assembly.
+    ///
+    /// Device offsets in the result are relative to this table.
+    pub fn italics_correction(&self) -> MathValueRecord {
+        let start = self.shape.italics_correction_value_byte_range().start;
+        let end = self.shape.italics_correction_device_offset_byte_range().end;
+        self.data.read_array::<MathValueRecord>(start..end).unwrap()[0]
+    }
+}
+
+impl MathGlyphInfo<'_> {
+    /// Returns `true` if the given glyph is an extended shape.
+    pub fn is_extended_shape(&self, glyph_id: GlyphId) -> bool {
+        self.extended_shape_coverage()
+            .and_then(|coverage| coverage.ok())
+            .and_then(|coverage| coverage.get(glyph_id))
+            .is_some()
+    }
+}
+
+impl MathItalicsCorrectionInfo<'_> {
+    /// Returns the italics correction for the given glyph.
+    ///
+    /// Device offsets in the result are relative to this table.
+    pub fn get(&self, glyph_id: GlyphId) -> Option<MathValueRecord> {
+        let index = self.italics_correction_coverage().ok()?.get(glyph_id)?;
+        self.italics_correction().get(index as usize).copied()
+    }
+}
+
+impl MathTopAccentAttachment<'_> {
+    /// Returns the top accent attachment point for the given glyph.
+    ///
+    /// Device offsets in the result are relative to this table.
+    pub fn get(&self, glyph_id: GlyphId) -> Option<MathValueRecord> {
+        let index = self.top_accent_coverage().ok()?.get(glyph_id)?;
+        self.top_accent_attachment().get(index as usize).copied()
+    }
+}
+
+impl MathKernInfo<'_> {
+    /// Returns the kerning record for the given glyph.
+    ///
+    /// Offsets in the result are relative to this table.
+    pub fn get(&self, glyph_id: GlyphId) -> Option<MathKernInfoRecord> {
+        let index = self.math_kern_coverage().ok()?.get(glyph_id)?;
+        self.math_kern_info_records().get(index as usize).copied()
+    }
+}
+
+impl MathKern<'_> {
+    /// Returns the kern value for the given correction height, in design
+    /// units.
+    ///
+    /// This ignores any device adjustments to the correction heights.
+    /// Device offsets in the result are relative to this table.
+    pub fn get(&self, height: i32) -> Option<MathValueRecord> {
+        let index = self
+            .correction_height()
+            .iter()
+            .position(|correction| height < correction.value().to_i16() as i32)
+            .unwrap_or(self.height_count() as usize);
+        self.kern_values().get(index).copied()
+    }
+}
+
+impl<'a> MathVariants<'a> {
+    /// Returns the construction for a glyph that grows in the vertical
+    /// direction.
+    pub fn vert_glyph_construction(
+        &self,
+        glyph_id: GlyphId,
+    ) -> Option<Result<MathGlyphConstruction<'a>, ReadError>> {
+        let index = self.vert_glyph_coverage()?.ok()?.get(glyph_id)?;
+        Some(self.vert_glyph_constructions().get(index as usize))
+    }
+
+    /// Returns the construction for a glyph that grows in the horizontal
+    /// direction.
+    pub fn horiz_glyph_construction(
+        &self,
+        glyph_id: GlyphId,
+    ) -> Option<Result<MathGlyphConstruction<'a>, ReadError>> {
+        let index = self.horiz_glyph_coverage()?.ok()?.get(glyph_id)?;
+        Some(self.horiz_glyph_constructions().get(index as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::BeBuffer;
+
+    const NUM_VALUE_RECORDS: u16 = 51;
+    const CONSTANTS_LEN: u16 = 8 + NUM_VALUE_RECORDS * 4 + 2;
+    const GLYPH_INFO_OFFSET: u16 = 10 + CONSTANTS_LEN;
+    const VARIANTS_OFFSET: u16 = GLYPH_INFO_OFFSET + 66;
+
+    fn coverage(buf: BeBuffer, glyphs: &[u16]) -> BeBuffer {
+        buf.push(1u16)
+            .push(glyphs.len() as u16)
+            .extend(glyphs.iter().copied())
+    }
+
+    fn math_table() -> BeBuffer {
+        let buf =
+            BeBuffer::new()
+                .extend([1u16, 0])
+                .extend([10u16, GLYPH_INFO_OFFSET, VARIANTS_OFFSET]);
+        // MathConstants: each value record is 10 * (index + 1)
+        let buf = buf.push(80i16).push(60i16).extend([1500u16, 1300]);
+        let buf = (1..=NUM_VALUE_RECORDS).fold(buf, |buf, i| buf.push(i as i16 * 10).push(0u16));
+        let buf = buf.push(60i16);
+        // MathGlyphInfo: italics at 8, no top accent, extended shapes
+        // at 28, kern info at 34
+        let buf = buf.extend([8u16, 0, 28, 34]);
+        // MathItalicsCorrectionInfo
+        let buf = buf.extend([12u16, 2]).extend([30i16, 0, -20, 0]);
+        let buf = coverage(buf, &[3, 5]);
+        // extended shape coverage
+        let buf = coverage(buf, &[7]);
+        // MathKernInfo with a single top right kern
+        let buf = buf.extend([12u16, 1]).extend([18u16, 0, 0, 0]);
+        let buf = coverage(buf, &[3]);
+        let buf = buf
+            .push(1u16)
+            .extend([100i16, 0])
+            .extend([-10i16, 0, 20, 0]);
+        // MathVariants with one vertical construction
+        let buf = buf.push(50u16).extend([12u16, 0, 1, 0, 18]);
+        let buf = coverage(buf, &[10]);
+        // MathGlyphConstruction: assembly at 12, two variants
+        let buf = buf.extend([12u16, 2]).extend([11u16, 1200, 12, 1800]);
+        // GlyphAssembly with two parts
+        buf.extend([5i16, 0])
+            .push(2u16)
+            .extend([13u16, 0, 100, 500, 0])
+            .extend([14u16, 100, 100, 400, 1])
+    }
+
+    #[test]
+    fn constants() {
+        let buf = math_table();
+        let math = Math::read(buf.font_data()).unwrap();
+        assert_eq!(math.major_version(), 1);
+        let constants = math.math_constants().unwrap();
+        assert_eq!(constants.script_percent_scale_down(), 80);
+        assert_eq!(constants.script_script_percent_scale_down(), 60);
+        assert_eq!(constants.delimited_sub_formula_min_height().to_u16(), 1500);
+        assert_eq!(constants.display_operator_min_height().to_u16(), 1300);
+        assert_eq!(constants.math_leading().value().to_i16(), 10);
+        assert_eq!(constants.axis_height().value().to_i16(), 20);
+        assert_eq!(constants.radical_kern_after_degree().value().to_i16(), 510);
+        assert_eq!(constants.radical_degree_bottom_raise_percent(), 60);
+        assert!(constants
+            .axis_height()
+            .device(constants.offset_data())
+            .is_none());
+    }
+
+    #[test]
+    fn glyph_info() {
+        let buf = math_table();
+        let math = Math::read(buf.font_data()).unwrap();
+        let glyph_info = math.math_glyph_info().unwrap();
+        let italics = glyph_info.math_italics_correction_info().unwrap().unwrap();
+        assert_eq!(italics.get(GlyphId::new(3)).unwrap().value().to_i16(), 30);
+        assert_eq!(italics.get(GlyphId::new(5)).unwrap().value().to_i16(), -20);
+        assert!(italics.get(GlyphId::new(4)).is_none());
+        assert!(glyph_info.math_top_accent_attachment().is_none());
+        assert!(glyph_info.is_extended_shape(GlyphId::new(7)));
+        assert!(!glyph_info.is_extended_shape(GlyphId::new(3)));
+
+        let kern_info = glyph_info.math_kern_info().unwrap().unwrap();
+        let record = kern_info.get(GlyphId::new(3)).unwrap();
+        assert!(record.top_left_math_kern(kern_info.offset_data()).is_none());
+        let kern = record
+            .top_right_math_kern(kern_info.offset_data())
+            .unwrap()
+            .unwrap();
+        assert_eq!(kern.get(50).unwrap().value().to_i16(), -10);
+        assert_eq!(kern.get(100).unwrap().value().to_i16(), 20);
+        assert!(kern_info.get(GlyphId::new(5)).is_none());
+    }
+
+    #[test]
+    fn variants() {
+        let buf = math_table();
+        let math = Math::read(buf.font_data()).unwrap();
+        let variants = math.math_variants().unwrap();
+        assert_eq!(variants.min_connector_overlap().to_u16(), 50);
+        assert!(variants
+            .horiz_glyph_construction(GlyphId::new(10))
+            .is_none());
+        let construction = variants
+            .vert_glyph_construction(GlyphId::new(10))
+            .unwrap()
+            .unwrap();
+        let sizes = construction
+            .math_glyph_variant_records()
+            .iter()
+            .map(|rec| {
+                (
+                    rec.variant_glyph().to_u16(),
+                    rec.advance_measurement().to_u16(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, [(11, 1200), (12, 1800)]);
+        let assembly = construction.glyph_assembly().unwrap().unwrap();
+        assert_eq!(assembly.italics_correction().value().to_i16(), 5);
+        let parts = assembly.part_records();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].glyph_id().to_u16(), 13);
+        assert_eq!(parts[1].full_advance().to_u16(), 400);
+        assert!(!parts[0]
+            .part_flags()
+            .contains(GlyphPartFlags::EXTENDER_FLAG));
+        assert!(parts[1]
+            .part_flags()
+            .contains(GlyphPartFlags::EXTENDER_FLAG));
+    }
+}
@@ -29,6 +29,40 @@ impl<'a> FontReadWithArgs<'a> for Metadata<'a> {
     }
 }
 
+impl<'a> Meta<'a> {
+    /// Returns the metadata associated with the given tag, if present.
+    pub fn metadata(&self, tag: Tag) -> Option<Result<Metadata<'a>, ReadError>> {
+        self.data_maps()
+            .iter()
+            .find(|record| record.tag() == tag)
+            .map(|record| record.data(self.offset_data()))
+    }
+
+    /// Returns an iterator over the languages and scripts that the font
+    /// was primarily designed for, from the 'dlng' metadata.
+    pub fn design_languages(&self) -> impl Iterator<Item = ScriptLangTag<'a>> + 'a {
+        self.script_lang_tags(DLNG)
+    }
+
+    /// Returns an iterator over the languages and scripts that the font
+    /// is capable of supporting, from the 'slng' metadata.
+    pub fn supported_languages(&self) -> impl Iterator<Item = ScriptLangTag<'a>> + 'a {
+        self.script_lang_tags(SLNG)
+    }
+
+    /// Malformed or empty entries are skipped.
+    fn script_lang_tags(&self, tag: Tag) -> impl Iterator<Item = ScriptLangTag<'a>> + 'a {
+        let tags = match self.metadata(tag) {
+            Some(Ok(Metadata::ScriptLangTags(tags))) => Some(tags.iter()),
+            _ => None,
+        };
+        tags.into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .filter(|tag| !tag.as_str().is_empty())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ScriptLangTag<'a>(&'a str);
 
@@ -59,8 +93,7 @@ impl VarSize for ScriptLangTag<'_> {
         if bytes.is_empty() {
             return None;
         }
-        let end = data
-            .as_bytes()
+        let end = bytes
             .iter()
             .position(|b| *b == b',')
             .map(|pos| pos + 1) // include comma
@@ -113,4 +146,22 @@ mod tests {
             &["latn"]
         ));
     }
+
+    #[test]
+    fn language_accessors() {
+        let table = Meta::read(test_data::SIMPLE_META_TABLE.into()).unwrap();
+        let design = table.design_languages().collect::<Vec<_>>();
+        assert_eq!(design, ["en-latn", "latn"]);
+        let supported = table.supported_languages().collect::<Vec<_>>();
+        assert_eq!(supported, ["latn"]);
+        assert!(table.metadata(Tag::new(b"appl")).is_none());
+    }
+
+    #[test]
+    fn script_lang_tags_of_differing_lengths() {
+        let data = FontData::new(b"en-latn,ja, fr,,zh-Hant");
+        let tags = VarLenArray::<ScriptLangTag>::read(data).unwrap();
+        let tags = tags.iter().map(|x| x.unwrap()).collect::<Vec<_>>();
+        assert_eq!(tags, ["en-latn", "ja", "fr", "", "zh-Hant"]);
+    }
 }
@@ -211,7 +211,10 @@ impl MacRomanMapping {
             raw as char
         } else {
             let idx = raw - Self::START_REMAP;
-            char::from_u32(MAC_ROMAN_DECODE[idx as usize] as u32).unwrap()
+            MAC_ROMAN_DECODE
+                .get(idx as usize)
+                .and_then(|c| char::from_u32(*c as u32))
+                .unwrap_or(char::REPLACEMENT_CHARACTER)
         }
     }
 
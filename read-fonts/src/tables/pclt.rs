@@ -0,0 +1,57 @@
+//! The [PCLT (PCL 5)](https://learn.microsoft.com/en-us/typography/opentype/spec/pclt) table
+
+include!("../../generated/generated_pclt.rs");
+
+impl<'a> Pclt<'a> {
+    /// Returns the typeface name as a string, with trailing padding removed.
+    ///
+    /// Returns `None` if the name contains non-ASCII bytes.
+    pub fn typeface_name(&self) -> Option<&'a str> {
+        padded_ascii(self.typeface())
+    }
+
+    /// Returns the six character font file name as a string, with trailing
+    /// padding removed.
+    ///
+    /// Returns `None` if the name contains non-ASCII bytes.
+    pub fn file_name_str(&self) -> Option<&'a str> {
+        padded_ascii(self.file_name())
+    }
+}
+
+fn padded_ascii(bytes: &[u8]) -> Option<&str> {
+    if !bytes.is_ascii() {
+        return None;
+    }
+    std::str::from_utf8(bytes)
+        .ok()
+        .map(|s| s.trim_end_matches([' ', '\0']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::BeBuffer;
+
+    #[test]
+    fn read_pclt() {
+        let buf = BeBuffer::new()
+            .push(Version16Dot16::VERSION_1_0)
+            .push(0x8000_0001u32)
+            .extend([300u16, 1100, 0, 4, 1400, 0x0155])
+            .extend(*b"Courier\0\0\0\0\0\0\0\0\0")
+            .extend([0xFFu8; 8])
+            .extend(*b"CR0R00")
+            .extend([0i8, 0])
+            .extend([0x40u8, 0]);
+        let pclt = Pclt::read(buf.font_data()).unwrap();
+        assert_eq!(pclt.version(), Version16Dot16::VERSION_1_0);
+        assert_eq!(pclt.font_number(), 0x8000_0001);
+        assert_eq!(pclt.pitch(), 300);
+        assert_eq!(pclt.cap_height(), 1400);
+        assert_eq!(pclt.typeface_name(), Some("Courier"));
+        assert_eq!(pclt.file_name_str(), Some("CR0R00"));
+        assert_eq!(pclt.character_complement(), [0xFF; 8]);
+        assert_eq!(pclt.serif_style(), 0x40);
+    }
+}
@@ -7,12 +7,12 @@ impl<'a> Post<'a> {
     pub fn num_names(&self) -> usize {
         match self.version() {
             Version16Dot16::VERSION_1_0 => DEFAULT_GLYPH_NAMES.len(),
-            Version16Dot16::VERSION_2_0 => self.num_glyphs().unwrap() as usize,
+            Version16Dot16::VERSION_2_0 => self.num_glyphs().unwrap_or_default() as usize,
             _ => 0,
         }
     }
 
-    pub fn glyph_name(&self, glyph_id: GlyphId16) -> Option<&str> {
+    pub fn glyph_name(&self, glyph_id: GlyphId16) -> Option<&'a str> {
         let glyph_id = glyph_id.to_u16() as usize;
         match self.version() {
             Version16Dot16::VERSION_1_0 => DEFAULT_GLYPH_NAMES.get(glyph_id).copied(),
@@ -22,7 +22,7 @@ impl<'a> Post<'a> {
                     return DEFAULT_GLYPH_NAMES.get(idx).copied();
                 }
                 let idx = idx - DEFAULT_GLYPH_NAMES.len();
-                match self.string_data().unwrap().get(idx) {
+                match self.string_data()?.get(idx) {
                     Some(Ok(s)) => Some(s.0),
                     _ => None,
                 }
@@ -73,11 +73,10 @@ impl<'a> FontRead<'a> for PString<'a> {
             .get(1..len as usize + 1)
             .ok_or(ReadError::OutOfBounds)?;
 
-        if pstring.is_ascii() {
-            Ok(PString(std::str::from_utf8(pstring).unwrap()))
-        } else {
+        match std::str::from_utf8(pstring) {
+            Ok(s) if s.is_ascii() => Ok(PString(s)),
             //FIXME not really sure how we want to handle this?
-            Err(ReadError::MalformedData("Must be valid ascii"))
+            _ => Err(ReadError::MalformedData("Must be valid ascii")),
         }
     }
 }
@@ -3,6 +3,7 @@
 use std::fmt;
 
 mod blend;
+mod charset;
 mod fd_select;
 mod index;
 mod stack;
@@ -14,6 +15,7 @@ pub mod dict;
 include!("../../generated/generated_postscript.rs");
 
 pub use blend::BlendState;
+pub use charset::{Charset, CharsetIter, CharsetKind};
 pub use index::Index;
 pub use stack::{Number, Stack};
 pub use string::{Latin1String, StringId, STANDARD_STRINGS};
@@ -32,6 +34,7 @@ pub enum Error {
     InvalidDictOperator(u8),
     InvalidCharstringOperator(u8),
     CharstringNestingDepthLimitExceeded,
+    CharstringOperatorLimitExceeded,
     MissingSubroutines,
     MissingBlendState,
     MissingPrivateDict,
@@ -88,6 +91,13 @@ impl fmt::Display for Error {
                     charstring::NESTING_DEPTH_LIMIT
                 )
             }
+            Self::CharstringOperatorLimitExceeded => {
+                write!(
+                    f,
+                    "exceeded operator limit {} while evaluating a charstring",
+                    charstring::OPERATOR_LIMIT
+                )
+            }
             Self::MissingSubroutines => {
                 write!(
                     f,
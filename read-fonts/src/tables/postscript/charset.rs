@@ -0,0 +1,287 @@
+//! Parsing for CFF charsets.
+//!
+//! See "Charsets" at <https://adobe-type-tools.github.io/font-tech-notes/pdfs/5176.CFF.pdf#page=21>
+
+use types::GlyphId;
+
+use super::StringId;
+use crate::{
+    tables::cff::{CharsetFormat0, CharsetFormat1, CharsetFormat2, CustomCharset},
+    FontData, FontRead, ReadError,
+};
+
+/// Character set for mapping from glyph to string identifiers.
+///
+/// See "Charsets" at <https://adobe-type-tools.github.io/font-tech-notes/pdfs/5176.CFF.pdf#page=21>
+#[derive(Clone)]
+pub struct Charset<'a> {
+    kind: CharsetKind<'a>,
+    num_glyphs: u32,
+}
+
+impl<'a> Charset<'a> {
+    /// Creates a new charset from the given `CFF` table data, the value
+    /// of the `charset` operator in the top DICT and the number of glyphs
+    /// in the font.
+    ///
+    /// Offsets 0, 1 and 2 select the predefined ISOAdobe, Expert and
+    /// ExpertSubset charsets respectively.
+    pub fn new(
+        cff_data: FontData<'a>,
+        charset_offset: usize,
+        num_glyphs: u32,
+    ) -> Result<Self, ReadError> {
+        let kind = match charset_offset {
+            0 => CharsetKind::IsoAdobe,
+            1 => CharsetKind::Expert,
+            2 => CharsetKind::ExpertSubset,
+            _ => {
+                let data = cff_data
+                    .split_off(charset_offset)
+                    .ok_or(ReadError::OutOfBounds)?;
+                CharsetKind::Custom(CustomCharset::read(data)?)
+            }
+        };
+        Ok(Self { kind, num_glyphs })
+    }
+
+    /// Returns the underlying type of the charset.
+    pub fn kind(&self) -> &CharsetKind<'a> {
+        &self.kind
+    }
+
+    /// Returns the number of glyphs covered by the charset.
+    pub fn num_glyphs(&self) -> u32 {
+        self.num_glyphs
+    }
+
+    /// Returns the string identifier for the given glyph identifier.
+    pub fn string_id(&self, glyph_id: GlyphId) -> Result<StringId, ReadError> {
+        let gid = glyph_id.to_u32();
+        if gid >= self.num_glyphs {
+            return Err(ReadError::OutOfBounds);
+        }
+        // .notdef is always the first glyph and is implicit in the
+        // encoded charsets
+        if gid == 0 {
+            return Ok(StringId::new(0));
+        }
+        match &self.kind {
+            CharsetKind::IsoAdobe => {
+                // The ISOAdobe charset maps glyph identifiers directly to the
+                // first 229 string identifiers.
+                if gid <= 228 {
+                    Ok(StringId::new(gid as u16))
+                } else {
+                    Err(ReadError::OutOfBounds)
+                }
+            }
+            CharsetKind::Expert => EXPERT_CHARSET
+                .get(gid as usize)
+                .copied()
+                .map(StringId::new)
+                .ok_or(ReadError::OutOfBounds),
+            CharsetKind::ExpertSubset => EXPERT_SUBSET_CHARSET
+                .get(gid as usize)
+                .copied()
+                .map(StringId::new)
+                .ok_or(ReadError::OutOfBounds),
+            CharsetKind::Custom(custom) => match custom {
+                CustomCharset::Format0(fmt) => fmt.string_id(gid),
+                CustomCharset::Format1(fmt) => fmt.string_id(gid),
+                CustomCharset::Format2(fmt) => fmt.string_id(gid),
+            },
+        }
+    }
+
+    /// Returns the glyph identifier for the given string identifier.
+    ///
+    /// This performs a linear search over the charset.
+    pub fn glyph_id(&self, string_id: StringId) -> Result<GlyphId, ReadError> {
+        self.iter()
+            .find(|(_, sid)| *sid == string_id)
+            .map(|(gid, _)| gid)
+            .ok_or(ReadError::OutOfBounds)
+    }
+
+    /// Returns an iterator over all of the glyph and string identifier
+    /// mappings in the charset.
+    pub fn iter(&self) -> CharsetIter<'a> {
+        CharsetIter {
+            charset: self.clone(),
+            cur: 0,
+        }
+    }
+}
+
+/// Predefined and custom character sets.
+#[derive(Clone)]
+pub enum CharsetKind<'a> {
+    IsoAdobe,
+    Expert,
+    ExpertSubset,
+    Custom(CustomCharset<'a>),
+}
+
+/// Iterator over the glyph and string identifier mappings in a charset.
+#[derive(Clone)]
+pub struct CharsetIter<'a> {
+    charset: Charset<'a>,
+    cur: u32,
+}
+
+impl Iterator for CharsetIter<'_> {
+    type Item = (GlyphId, StringId);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let gid = GlyphId::new(self.cur);
+        let sid = self.charset.string_id(gid).ok()?;
+        self.cur += 1;
+        Some((gid, sid))
+    }
+}
+
+impl CharsetFormat0<'_> {
+    fn string_id(&self, gid: u32) -> Result<StringId, ReadError> {
+        self.glyph()
+            .get(gid as usize - 1)
+            .map(|sid| StringId::new(sid.get()))
+            .ok_or(ReadError::OutOfBounds)
+    }
+}
+
+impl CharsetFormat1<'_> {
+    fn string_id(&self, gid: u32) -> Result<StringId, ReadError> {
+        // skip .notdef
+        let mut first_gid = 1;
+        for range in self.ranges() {
+            let count = range.n_left() as u32 + 1;
+            if gid < first_gid + count {
+                let sid = range.first() as u32 + (gid - first_gid);
+                return sid_from_u32(sid);
+            }
+            first_gid += count;
+        }
+        Err(ReadError::OutOfBounds)
+    }
+}
+
+impl CharsetFormat2<'_> {
+    fn string_id(&self, gid: u32) -> Result<StringId, ReadError> {
+        // skip .notdef
+        let mut first_gid = 1;
+        for range in self.ranges() {
+            let count = range.n_left() as u32 + 1;
+            if gid < first_gid + count {
+                let sid = range.first() as u32 + (gid - first_gid);
+                return sid_from_u32(sid);
+            }
+            first_gid += count;
+        }
+        Err(ReadError::OutOfBounds)
+    }
+}
+
+fn sid_from_u32(sid: u32) -> Result<StringId, ReadError> {
+    u16::try_from(sid)
+        .map(StringId::new)
+        .map_err(|_| ReadError::MalformedData("charset string id out of range"))
+}
+
+/// See "Expert" charset at <https://adobe-type-tools.github.io/font-tech-notes/pdfs/5176.CFF.pdf#page=47>
+#[rustfmt::skip]
+const EXPERT_CHARSET: [u16; 166] = [
+      0,    1,  229,  230,  231,  232,  233,  234,  235,  236,  237,  238,   13,   14,   15,   99,
+    239,  240,  241,  242,  243,  244,  245,  246,  247,  248,   27,   28,  249,  250,  251,  252,
+    253,  254,  255,  256,  257,  258,  259,  260,  261,  262,  263,  264,  265,  266,  109,  110,
+    267,  268,  269,  270,  271,  272,  273,  274,  275,  276,  277,  278,  279,  280,  281,  282,
+    283,  284,  285,  286,  287,  288,  289,  290,  291,  292,  293,  294,  295,  296,  297,  298,
+    299,  300,  301,  302,  303,  304,  305,  306,  307,  308,  309,  310,  311,  312,  313,  314,
+    315,  316,  317,  318,  158,  155,  163,  319,  320,  321,  322,  323,  324,  325,  326,  150,
+    164,  169,  327,  328,  329,  330,  331,  332,  333,  334,  335,  336,  337,  338,  339,  340,
+    341,  342,  343,  344,  345,  346,  347,  348,  349,  350,  351,  352,  353,  354,  355,  356,
+    357,  358,  359,  360,  361,  362,  363,  364,  365,  366,  367,  368,  369,  370,  371,  372,
+    373,  374,  375,  376,  377,  378,
+];
+
+/// See "Expert Subset" charset at <https://adobe-type-tools.github.io/font-tech-notes/pdfs/5176.CFF.pdf#page=49>
+#[rustfmt::skip]
+const EXPERT_SUBSET_CHARSET: [u16; 87] = [
+      0,    1,  231,  232,  235,  236,  237,  238,   13,   14,   15,   99,  239,  240,  241,  242,
+    243,  244,  245,  246,  247,  248,   27,   28,  249,  250,  251,  253,  254,  255,  256,  257,
+    258,  259,  260,  261,  262,  263,  264,  265,  266,  109,  110,  267,  268,  269,  270,  272,
+    300,  301,  302,  305,  314,  315,  158,  155,  163,  320,  321,  322,  323,  324,  325,  326,
+    150,  164,  169,  327,  328,  329,  330,  331,  332,  333,  334,  335,  336,  337,  338,  339,
+    340,  341,  342,  343,  344,  345,  346,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::BeBuffer;
+
+    // For each charset format, map glyphs 1..=6 to string ids
+    // 10, 11, 12, 400, 401, 402
+    fn custom_charsets() -> [BeBuffer; 3] {
+        let format0 = BeBuffer::new()
+            .push(0u8)
+            .extend([10u16, 11, 12, 400, 401, 402]);
+        let format1 = BeBuffer::new()
+            .push(1u8)
+            .push(10u16)
+            .push(2u8)
+            .push(400u16)
+            .push(2u8);
+        let format2 = BeBuffer::new()
+            .push(2u8)
+            .push(10u16)
+            .push(2u16)
+            .push(400u16)
+            .push(2u16);
+        [format0, format1, format2]
+    }
+
+    #[test]
+    fn custom_charset_mappings() {
+        let expected = [0u16, 10, 11, 12, 400, 401, 402];
+        for buf in custom_charsets() {
+            // offset the charset data to avoid the predefined charset offsets
+            let data = BeBuffer::new().extend([0u8; 4]).extend(buf.iter().copied());
+            let charset = Charset::new(data.font_data(), 4, 7).unwrap();
+            let mappings: Vec<_> = charset.iter().map(|(_, sid)| sid.to_u16()).collect();
+            assert_eq!(mappings, expected);
+            assert_eq!(
+                charset.glyph_id(StringId::new(401)).unwrap(),
+                GlyphId::new(5)
+            );
+            assert!(charset.string_id(GlyphId::new(7)).is_err());
+            assert!(charset.glyph_id(StringId::new(13)).is_err());
+        }
+    }
+
+    #[test]
+    fn predefined_charsets() {
+        let data = FontData::new(&[]);
+        let iso_adobe = Charset::new(data, 0, 300).unwrap();
+        assert_eq!(
+            iso_adobe.string_id(GlyphId::new(228)).unwrap(),
+            StringId::new(228)
+        );
+        assert!(iso_adobe.string_id(GlyphId::new(229)).is_err());
+        assert_eq!(iso_adobe.iter().count(), 229);
+
+        let expert = Charset::new(data, 1, 300).unwrap();
+        assert_eq!(expert.iter().count(), 166);
+        assert_eq!(
+            expert.string_id(GlyphId::new(12)).unwrap(),
+            StringId::new(13)
+        );
+
+        let expert_subset = Charset::new(data, 2, 300).unwrap();
+        assert_eq!(expert_subset.iter().count(), 87);
+        assert_eq!(
+            expert_subset.string_id(GlyphId::new(86)).unwrap(),
+            StringId::new(346)
+        );
+    }
+}
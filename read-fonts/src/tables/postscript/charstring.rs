@@ -12,6 +12,14 @@ use crate::{
 /// <https://adobe-type-tools.github.io/font-tech-notes/pdfs/5177.Type2.pdf#page=33>
 pub const NESTING_DEPTH_LIMIT: u32 = 10;
 
+/// Maximum number of operators that may be evaluated for a single
+/// charstring, including those in called subroutines.
+///
+/// Subroutines may call other subroutines multiple times, so the nesting
+/// depth limit alone does not bound the evaluation time. This matches the
+/// limit used by HarfBuzz.
+pub const OPERATOR_LIMIT: u32 = 10_000;
+
 /// Trait for processing commands resulting from charstring evaluation.
 ///
 /// During processing, the path construction operators (see "4.1 Path
@@ -79,6 +87,7 @@ struct Evaluator<'a, S> {
     y: Fixed,
     stack: Stack,
     stack_ix: usize,
+    operator_count: u32,
 }
 
 impl<'a, S> Evaluator<'a, S>
@@ -103,6 +112,7 @@ where
             x: Fixed::ZERO,
             y: Fixed::ZERO,
             stack_ix: 0,
+            operator_count: 0,
         }
     }
 
@@ -126,6 +136,10 @@ where
                     self.stack.push(num)?;
                 }
                 _ => {
+                    self.operator_count += 1;
+                    if self.operator_count > OPERATOR_LIMIT {
+                        return Err(Error::CharstringOperatorLimitExceeded);
+                    }
                     let operator = Operator::read(&mut cursor, b0)?;
                     if !self.evaluate_operator(operator, &mut cursor, nesting_depth)? {
                         break;
@@ -736,6 +750,40 @@ mod tests {
         assert_eq!(&commands.0, expected);
     }
 
+    #[test]
+    fn operator_limit() {
+        // Each global subroutine calls the next one four times, which
+        // stays within the nesting depth limit but requires an
+        // exponential number of operators
+        const N_SUBRS: u8 = 10;
+        let bias = 107;
+        let subrs = (0..N_SUBRS)
+            .map(|i| {
+                if i == N_SUBRS - 1 {
+                    vec![11]
+                } else {
+                    let index = (i as i32 + 1 - bias + 139) as u8;
+                    [index, 29].repeat(4).into_iter().chain([11]).collect()
+                }
+            })
+            .collect::<Vec<Vec<u8>>>();
+        let mut index_bytes = vec![0, N_SUBRS, 1, 1];
+        let mut offset = 1;
+        for subr in &subrs {
+            offset += subr.len() as u8;
+            index_bytes.push(offset);
+        }
+        index_bytes.extend(subrs.concat());
+        let global_subrs = Index::new(&index_bytes, false).unwrap();
+        let charstring = [(-bias + 139) as u8, 29, 14];
+        let mut commands = CaptureCommandSink::default();
+        let result = evaluate(&charstring, global_subrs, None, None, &mut commands);
+        assert!(matches!(
+            result,
+            Err(Error::CharstringOperatorLimitExceeded)
+        ));
+    }
+
     #[test]
     fn all_path_ops() {
         // This charstring was manually constructed in
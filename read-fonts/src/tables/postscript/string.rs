@@ -61,6 +61,11 @@ impl<'a> Latin1String<'a> {
         Self { chars }
     }
 
+    /// Returns the underlying bytes of the string.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.chars
+    }
+
     /// Returns an iterator over the characters of the string.
     ///
     /// This simply converts each byte to `char`.
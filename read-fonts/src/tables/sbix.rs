@@ -2,6 +2,54 @@
 
 include!("../../generated/generated_sbix.rs");
 
+/// Maximum number of `dupe` or `flip` references followed when resolving
+/// glyph data.
+const MAX_REFERENCE_DEPTH: usize = 64;
+
+impl GlyphData<'_> {
+    /// Graphic type for records that reuse the graphic of another glyph.
+    pub const DUPE: Tag = Tag::new(b"dupe");
+    /// Graphic type for records that reuse the graphic of another glyph,
+    /// mirrored horizontally.
+    ///
+    /// This is an Apple extension that is not part of the OpenType
+    /// specification.
+    pub const FLIP: Tag = Tag::new(b"flip");
+    /// Graphic type for PNG images.
+    pub const PNG: Tag = Tag::new(b"png ");
+    /// Graphic type for JPEG images.
+    pub const JPG: Tag = Tag::new(b"jpg ");
+    /// Graphic type for TIFF images.
+    pub const TIFF: Tag = Tag::new(b"tiff");
+
+    /// Returns the glyph identifier referenced by a `dupe` or `flip`
+    /// record, or `None` for any other graphic type.
+    pub fn referenced_glyph_id(&self) -> Option<GlyphId16> {
+        let graphic_type = self.graphic_type();
+        if graphic_type != Self::DUPE && graphic_type != Self::FLIP {
+            return None;
+        }
+        FontData::new(self.data())
+            .read_at::<u16>(0)
+            .ok()
+            .map(GlyphId16::new)
+    }
+}
+
+/// Glyph data after following `dupe` and `flip` references.
+#[derive(Clone)]
+pub struct ResolvedGlyphData<'a> {
+    /// The record containing the actual graphic.
+    ///
+    /// Origin offsets and the graphic type should be taken from this record.
+    pub glyph_data: GlyphData<'a>,
+    /// The glyph that owns [`Self::glyph_data`].
+    pub source_glyph_id: GlyphId,
+    /// True if an odd number of `flip` references were followed, meaning
+    /// the graphic should be mirrored horizontally.
+    pub flipped: bool,
+}
+
 impl<'a> Strike<'a> {
     pub fn glyph_data(&self, glyph_id: GlyphId) -> Result<Option<GlyphData<'a>>, ReadError> {
         let offsets = self.glyph_data_offsets();
@@ -21,12 +69,143 @@ impl<'a> Strike<'a> {
             .ok_or(ReadError::OutOfBounds)?;
         Ok(Some(GlyphData::read(data)?))
     }
+
+    /// Returns the glyph data for the given glyph, following any `dupe` or
+    /// `flip` references to the record containing the actual graphic.
+    ///
+    /// Returns an error if a reference points to an empty glyph or if the
+    /// references form a cycle.
+    pub fn resolved_glyph_data(
+        &self,
+        glyph_id: GlyphId,
+    ) -> Result<Option<ResolvedGlyphData<'a>>, ReadError> {
+        let Some(mut glyph_data) = self.glyph_data(glyph_id)? else {
+            return Ok(None);
+        };
+        let mut source_glyph_id = glyph_id;
+        let mut flipped = false;
+        for _ in 0..MAX_REFERENCE_DEPTH {
+            let Some(reference) = glyph_data.referenced_glyph_id() else {
+                return Ok(Some(ResolvedGlyphData {
+                    glyph_data,
+                    source_glyph_id,
+                    flipped,
+                }));
+            };
+            flipped ^= glyph_data.graphic_type() == GlyphData::FLIP;
+            source_glyph_id = reference.into();
+            glyph_data = self
+                .glyph_data(source_glyph_id)?
+                .ok_or(ReadError::MalformedData(
+                    "sbix glyph data references an empty glyph",
+                ))?;
+        }
+        Err(ReadError::MalformedData(
+            "sbix glyph data references exceed maximum depth",
+        ))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tables::sbix::Sbix;
+    use crate::tables::sbix::{GlyphData, Sbix, Strike};
     use crate::test_helpers::BeBuffer;
+    use crate::{FontData, ReadError};
+    use font_types::{GlyphId, Tag};
+
+    /// Builds a strike with one glyph data record per entry.
+    fn make_strike(records: &[Option<(Tag, &[u8])>]) -> BeBuffer {
+        let num_glyphs = records.len() as u32;
+        let mut offset = 4 + (num_glyphs + 1) * 4;
+        let mut buf = BeBuffer::new().push(16u16).push(72u16);
+        for record in records {
+            buf = buf.push(offset);
+            if let Some((_, data)) = record {
+                offset += 8 + data.len() as u32;
+            }
+        }
+        buf = buf.push(offset);
+        for (x, (tag, data)) in records.iter().flatten().enumerate() {
+            buf = buf
+                .push(x as i16)
+                .push(-(x as i16))
+                .push(*tag)
+                .extend(data.iter().copied());
+        }
+        buf
+    }
+
+    #[test]
+    fn resolve_dupe_and_flip() {
+        let buf = make_strike(&[
+            Some((GlyphData::PNG, b"\x89PNG")),
+            Some((GlyphData::DUPE, &[0, 0])),
+            Some((GlyphData::FLIP, &[0, 1])),
+            Some((GlyphData::FLIP, &[0, 2])),
+            None,
+        ]);
+        let strike = Strike::read(FontData::new(buf.as_slice()), 5).unwrap();
+
+        let plain = strike
+            .resolved_glyph_data(GlyphId::new(0))
+            .unwrap()
+            .unwrap();
+        assert_eq!(plain.source_glyph_id, GlyphId::new(0));
+        assert!(!plain.flipped);
+        assert_eq!(plain.glyph_data.data(), b"\x89PNG");
+
+        let dupe = strike
+            .resolved_glyph_data(GlyphId::new(1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(dupe.source_glyph_id, GlyphId::new(0));
+        assert!(!dupe.flipped);
+        assert_eq!(dupe.glyph_data.graphic_type(), GlyphData::PNG);
+
+        let flip = strike
+            .resolved_glyph_data(GlyphId::new(2))
+            .unwrap()
+            .unwrap();
+        assert_eq!(flip.source_glyph_id, GlyphId::new(0));
+        assert!(flip.flipped);
+
+        // Two flips cancel out
+        let flip_flip = strike
+            .resolved_glyph_data(GlyphId::new(3))
+            .unwrap()
+            .unwrap();
+        assert!(!flip_flip.flipped);
+
+        // The unresolved record still exposes the raw type and reference
+        let raw = strike.glyph_data(GlyphId::new(2)).unwrap().unwrap();
+        assert_eq!(raw.graphic_type(), GlyphData::FLIP);
+        assert_eq!(raw.referenced_glyph_id(), Some(1.into()));
+        assert_eq!((raw.origin_offset_x(), raw.origin_offset_y()), (2, -2));
+
+        assert!(strike
+            .resolved_glyph_data(GlyphId::new(4))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn resolve_bad_references() {
+        let buf = make_strike(&[
+            Some((GlyphData::DUPE, &[0, 1])),
+            Some((GlyphData::DUPE, &[0, 0])),
+            Some((GlyphData::DUPE, &[0, 3])),
+            None,
+        ]);
+        let strike = Strike::read(FontData::new(buf.as_slice()), 4).unwrap();
+        assert!(matches!(
+            strike.resolved_glyph_data(GlyphId::new(0)),
+            Err(ReadError::MalformedData(_))
+        ));
+        assert!(matches!(
+            strike.resolved_glyph_data(GlyphId::new(2)),
+            Err(ReadError::MalformedData(_))
+        ));
+    }
 
     #[test]
     fn sbix_strikes_count_overflow_table() {
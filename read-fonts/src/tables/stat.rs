@@ -2,11 +2,67 @@
 
 include!("../../generated/generated_stat.rs");
 
+impl<'a> Stat<'a> {
+    /// Returns the index of the design axis with the given tag.
+    pub fn design_axis_index(&self, tag: Tag) -> Option<u16> {
+        self.design_axes()
+            .ok()?
+            .iter()
+            .position(|axis| axis.axis_tag() == tag)
+            .map(|ix| ix as u16)
+    }
+
+    /// Returns an iterator over all axis value tables, skipping any that
+    /// are malformed.
+    pub fn axis_value_tables(&self) -> impl Iterator<Item = AxisValue<'a>> + 'a {
+        self.offset_to_axis_values()
+            .and_then(Result::ok)
+            .into_iter()
+            .flat_map(|array| {
+                let values = array.axis_values();
+                (0..values.len()).filter_map(move |ix| values.get(ix).ok())
+            })
+    }
+}
+
+impl AxisValue<'_> {
+    /// Returns the design axis index for single axis value tables (formats
+    /// 1, 2 and 3) or `None` for multi-axis tables (format 4).
+    pub fn axis_index(&self) -> Option<u16> {
+        match self {
+            Self::Format1(value) => Some(value.axis_index()),
+            Self::Format2(value) => Some(value.axis_index()),
+            Self::Format3(value) => Some(value.axis_index()),
+            Self::Format4(_) => None,
+        }
+    }
+
+    /// Returns `true` if this table provides a value for the design axis
+    /// with the given index.
+    pub fn covers_axis(&self, axis_index: u16) -> bool {
+        match self {
+            Self::Format4(value) => value.value_for_axis(axis_index).is_some(),
+            _ => self.axis_index() == Some(axis_index),
+        }
+    }
+}
+
+impl AxisValueFormat4<'_> {
+    /// Returns the value for the design axis with the given index, if this
+    /// table contains one.
+    pub fn value_for_axis(&self, axis_index: u16) -> Option<Fixed> {
+        self.axis_values()
+            .iter()
+            .find(|record| record.axis_index() == axis_index)
+            .map(|record| record.value())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use types::{Fixed, NameId};
 
-    use crate::{table_provider::TableProvider, FontRef};
+    use crate::{table_provider::TableProvider, test_helpers::BeBuffer, FontRef};
 
     use super::*;
 
@@ -33,5 +89,40 @@ mod tests {
             assert_eq!(table.value_name_id(), NameId::new(264));
             assert_eq!(table.value(), Fixed::from_f64(700.0));
         }
+        assert_eq!(table.design_axis_index(Tag::new(b"wght")), Some(0));
+        assert_eq!(table.design_axis_index(Tag::new(b"wdth")), None);
+        assert_eq!(table.axis_value_tables().count(), 3);
+    }
+
+    #[test]
+    fn multi_axis_value() {
+        let buf = BeBuffer::new()
+            .extend([1u16, 2]) // version
+            .extend([8u16, 2]) // designAxisSize, designAxisCount
+            .push(20u32) // designAxesOffset
+            .push(1u16) // axisValueCount
+            .push(36u32) // offsetToAxisValueOffsets
+            .push(2u16) // elidedFallbackNameID
+            .push(Tag::new(b"wght"))
+            .extend([256u16, 0])
+            .push(Tag::new(b"opsz"))
+            .extend([257u16, 1])
+            .push(2u16) // axisValueOffsets[0]
+            .extend([4u16, 2, 0, 261]) // format, axisCount, flags, valueNameID
+            .push(1u16)
+            .push(Fixed::from_i32(72))
+            .push(0u16)
+            .push(Fixed::from_i32(700));
+        let stat = Stat::read(buf.font_data()).unwrap();
+        let values = stat.axis_value_tables().collect::<Vec<_>>();
+        let [value @ AxisValue::Format4(format4)] = values.as_slice() else {
+            panic!("expected a single format 4 axis value");
+        };
+        assert_eq!(value.value_name_id(), NameId::new(261));
+        assert_eq!(value.axis_index(), None);
+        assert!(value.covers_axis(0) && value.covers_axis(1));
+        assert!(!value.covers_axis(2));
+        assert_eq!(format4.value_for_axis(0), Some(Fixed::from_i32(700)));
+        assert_eq!(format4.value_for_axis(1), Some(Fixed::from_i32(72)));
     }
 }
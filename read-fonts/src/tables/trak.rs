@@ -0,0 +1,147 @@
+//! The [tracking (trak)](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6trak.html) table.
+
+include!("../../generated/generated_trak.rs");
+
+impl<'a> TrackData<'a> {
+    /// Returns the point sizes for which tracking values are defined.
+    ///
+    /// The `data` argument should be retrieved from the parent table
+    /// (`Trak`) by calling its `offset_data` method.
+    pub fn sizes(&self, data: FontData<'a>) -> Result<&'a [BigEndian<Fixed>], ReadError> {
+        let start = self.size_table_offset() as usize;
+        let len = self.n_sizes() as usize * Fixed::RAW_BYTE_LEN;
+        data.read_array(start..start + len)
+    }
+
+    /// Returns the entry for the given track value, if present.
+    ///
+    /// A track value of 0 represents normal tracking.
+    pub fn entry(&self, track: Fixed) -> Option<&'a TrackTableEntry> {
+        self.track_table()
+            .iter()
+            .find(|entry| entry.track() == track)
+    }
+
+    /// Returns the tracking value, in font units, for the given track and
+    /// point size.
+    ///
+    /// Values between the sizes in the table are linearly interpolated and
+    /// values outside the range of sizes are extrapolated from the nearest
+    /// pair of sizes.
+    ///
+    /// The `data` argument should be retrieved from the parent table
+    /// (`Trak`) by calling its `offset_data` method.
+    pub fn tracking(&self, data: FontData<'a>, track: Fixed, size: f32) -> Option<f32> {
+        let entry = self.entry(track)?;
+        let sizes = self.sizes(data).ok()?;
+        let values = entry.values(data, self.n_sizes()).ok()?;
+        let value = |ix: usize| values.get(ix).map(|value| value.get() as f32);
+        match sizes.len() {
+            0 => None,
+            1 => value(0),
+            len => {
+                // Follows HarfBuzz: find the first size that is >= the
+                // requested size and interpolate with the preceding entry
+                let ix = sizes[..len - 1]
+                    .iter()
+                    .position(|s| s.get().to_f32() >= size)
+                    .unwrap_or(len - 1)
+                    .saturating_sub(1);
+                let s0 = sizes[ix].get().to_f32();
+                let s1 = sizes[ix + 1].get().to_f32();
+                let t = if s0 == s1 {
+                    0.0
+                } else {
+                    (size - s0) / (s1 - s0)
+                };
+                Some(t * value(ix + 1)? + (1.0 - t) * value(ix)?)
+            }
+        }
+    }
+}
+
+impl TrackTableEntry {
+    /// Returns the per-size tracking values for this track.
+    ///
+    /// The `data` argument should be retrieved from the parent table
+    /// (`Trak`) by calling its `offset_data` method and `n_sizes` from
+    /// the containing [`TrackData`].
+    pub fn values<'a>(
+        &self,
+        data: FontData<'a>,
+        n_sizes: u16,
+    ) -> Result<&'a [BigEndian<i16>], ReadError> {
+        let start = self.offset() as usize;
+        let len = n_sizes as usize * i16::RAW_BYTE_LEN;
+        data.read_array(start..start + len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::BeBuffer;
+
+    // Example taken from <https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6trak.html>
+    fn trak_example() -> BeBuffer {
+        let fixed = |value: i32| Fixed::from_i32(value).to_bits() as u32;
+        BeBuffer::new()
+            .push(0x00010000u32)
+            .extend([0u16, 12, 0, 0])
+            // horizontal track data: 3 tracks, 2 sizes
+            .extend([3u16, 2])
+            .push(44u32)
+            .push(fixed(-1))
+            .extend([256u16, 52])
+            .push(0u32)
+            .extend([258u16, 56])
+            .push(fixed(1))
+            .extend([257u16, 60])
+            // sizes: 12 and 24 points
+            .extend([fixed(12), fixed(24)])
+            // values for each track
+            .extend([-15i16, -7, 0, 0, 50, 20])
+    }
+
+    #[test]
+    fn parse_example() {
+        let buf = trak_example();
+        let trak = Trak::read(buf.font_data()).unwrap();
+        assert_eq!(trak.version(), MajorMinor::VERSION_1_0);
+        assert!(trak.vert().is_none());
+        let horiz = trak.horiz().unwrap().unwrap();
+        assert_eq!(horiz.n_tracks(), 3);
+        let data = trak.offset_data();
+        let sizes = horiz
+            .sizes(data)
+            .unwrap()
+            .iter()
+            .map(|size| size.get().to_i32())
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, [12, 24]);
+        let tight = horiz.entry(Fixed::from_i32(-1)).unwrap();
+        assert_eq!(tight.name_index(), NameId::new(256));
+        let values = tight.values(data, horiz.n_sizes()).unwrap();
+        assert_eq!(
+            values.iter().map(|v| v.get()).collect::<Vec<_>>(),
+            [-15, -7]
+        );
+        assert!(horiz.entry(Fixed::from_i32(2)).is_none());
+    }
+
+    #[test]
+    fn interpolate_tracking() {
+        let buf = trak_example();
+        let trak = Trak::read(buf.font_data()).unwrap();
+        let horiz = trak.horiz().unwrap().unwrap();
+        let data = trak.offset_data();
+        let loose = Fixed::from_i32(1);
+        assert_eq!(horiz.tracking(data, loose, 12.0), Some(50.0));
+        assert_eq!(horiz.tracking(data, loose, 18.0), Some(35.0));
+        assert_eq!(horiz.tracking(data, loose, 24.0), Some(20.0));
+        // extrapolated beyond the largest size
+        assert_eq!(horiz.tracking(data, loose, 36.0), Some(-10.0));
+        assert_eq!(horiz.tracking(data, Fixed::ZERO, 18.0), Some(0.0));
+        assert_eq!(horiz.tracking(data, Fixed::from_i32(3), 18.0), None);
+    }
+}
@@ -197,10 +197,10 @@ impl<'a> ValueRecord {
 
         let name = fields.iter().filter_map(|x| *x).nth(idx)?;
         let typ: FieldType = match name {
-            "x_placement" => self.x_placement().unwrap().into(),
-            "y_placement" => self.y_placement().unwrap().into(),
-            "x_advance" => self.x_advance().unwrap().into(),
-            "y_advance" => self.y_advance().unwrap().into(),
+            "x_placement" => self.x_placement()?.into(),
+            "y_placement" => self.y_placement()?.into(),
+            "x_advance" => self.x_advance()?.into(),
+            "y_advance" => self.y_advance()?.into(),
             "x_placement_device" => {
                 FieldType::offset(self.x_placement_device.get(), self.x_placement_device(data))
             }
@@ -213,7 +213,7 @@ impl<'a> ValueRecord {
             "y_advance_device" => {
                 FieldType::offset(self.y_advance_device.get(), self.y_advance_device(data))
             }
-            _ => panic!("hmm"),
+            _ => return None,
         };
 
         Some(Field::new(name, typ))
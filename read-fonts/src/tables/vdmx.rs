@@ -0,0 +1,114 @@
+//! The [VDMX (Vertical Device Metrics)](https://learn.microsoft.com/en-us/typography/opentype/spec/vdmx) table
+
+include!("../../generated/generated_vdmx.rs");
+
+impl<'a> Vdmx<'a> {
+    /// Returns the group of vertical metrics that applies to the given
+    /// aspect ratio.
+    ///
+    /// The ratio is expressed as the horizontal and vertical resolution of
+    /// the target device. The first matching ratio range is selected.
+    pub fn group_for_ratio(&self, x: u16, y: u16) -> Option<Result<VdmxGroup<'a>, ReadError>> {
+        let index = self
+            .ratio_ranges()
+            .iter()
+            .position(|range| range.matches(x, y))?;
+        Some(self.vdmx_groups().get(index))
+    }
+
+    /// Returns the maximum and minimum extents for the given size and
+    /// aspect ratio.
+    pub fn extents(&self, x: u16, y: u16, y_pel_height: u16) -> Option<VTable> {
+        self.group_for_ratio(x, y)?.ok()?.entry(y_pel_height)
+    }
+}
+
+impl RatioRange {
+    /// Returns `true` if this range applies to the given aspect ratio.
+    ///
+    /// A range with all ratio values set to zero matches any aspect ratio.
+    pub fn matches(&self, x: u16, y: u16) -> bool {
+        let (x_ratio, y_start, y_end) = (
+            self.x_ratio as u32,
+            self.y_start_ratio as u32,
+            self.y_end_ratio as u32,
+        );
+        if x_ratio == 0 && y_start == 0 && y_end == 0 {
+            return true;
+        }
+        // y / x must be within [y_start / x_ratio, y_end / x_ratio]
+        let (x, y) = (x as u32, y as u32);
+        let scaled_y = y * x_ratio;
+        (y_start * x..=y_end * x).contains(&scaled_y)
+    }
+}
+
+impl VdmxGroup<'_> {
+    /// Returns the record for the given size, if present.
+    pub fn entry(&self, y_pel_height: u16) -> Option<VTable> {
+        if !(self.startsz() as u16..=self.endsz() as u16).contains(&y_pel_height) {
+            return None;
+        }
+        let entries = self.entries();
+        entries
+            .binary_search_by_key(&y_pel_height, |entry| entry.y_pel_height())
+            .ok()
+            .map(|index| entries[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::BeBuffer;
+
+    fn vdmx_table() -> BeBuffer {
+        // two ratio ranges (1:1 and catch-all), both pointing to the
+        // same group
+        let group_offset: u16 = 6 + 2 * 4 + 2 * 2;
+        BeBuffer::new()
+            .extend([1u16, 1, 2])
+            .extend([1u8, 1, 1, 1])
+            .extend([0u8, 0, 0, 0])
+            .extend([group_offset, group_offset])
+            .push(2u16)
+            .extend([10u8, 12])
+            .extend([10u16])
+            .extend([9i16, -3])
+            .extend([12u16])
+            .extend([11i16, -4])
+    }
+
+    #[test]
+    fn lookup() {
+        let buf = vdmx_table();
+        let vdmx = Vdmx::read(buf.font_data()).unwrap();
+        assert_eq!(vdmx.num_recs(), 1);
+        assert!(vdmx.ratio_ranges()[0].matches(96, 96));
+        assert!(!vdmx.ratio_ranges()[0].matches(96, 120));
+        assert!(vdmx.ratio_ranges()[1].matches(96, 120));
+        let group = vdmx.group_for_ratio(72, 72).unwrap().unwrap();
+        assert_eq!(group.startsz(), 10);
+        assert_eq!(group.endsz(), 12);
+        let entry = vdmx.extents(1, 1, 12).unwrap();
+        assert_eq!((entry.y_max(), entry.y_min()), (11, -4));
+        assert!(vdmx.extents(1, 1, 11).is_none());
+        assert!(vdmx.extents(1, 1, 13).is_none());
+    }
+
+    #[test]
+    fn ratio_range() {
+        // covers aspect ratios from 1:0.5 to 1:2
+        let range = RatioRange {
+            b_char_set: 1,
+            x_ratio: 2,
+            y_start_ratio: 1,
+            y_end_ratio: 4,
+        };
+        assert!(range.matches(100, 50));
+        assert!(range.matches(100, 200));
+        assert!(range.matches(100, 100));
+        assert!(!range.matches(100, 49));
+        assert!(!range.matches(100, 201));
+    }
+}
@@ -0,0 +1,76 @@
+//! Checks that reading corrupted fonts never panics.
+//!
+//! Every table of each font in the corpus is truncated and overwritten at a
+//! fixed set of positions and the result is passed to
+//! [`exercise_font`]. Errors are expected; any panic fails the test.
+
+use crate::{test_helpers::exercise_font, FontRef};
+
+const CORPUS: &[&[u8]] = &[
+    font_test_data::SIMPLE_GLYF,
+    font_test_data::GLYF_COMPONENTS,
+    font_test_data::VAZIRMATN_VAR,
+    font_test_data::CANTARELL_VF_TRIMMED,
+    font_test_data::NOTO_SERIF_DISPLAY_TRIMMED,
+    font_test_data::CMAP12_FONT1,
+    font_test_data::CMAP14_FONT1,
+    font_test_data::COLRV0V1,
+    font_test_data::NAMES_ONLY,
+    font_test_data::AHEM,
+];
+
+const TABLE_RECORD_START: usize = 12;
+const TABLE_RECORD_LEN: usize = 16;
+
+/// Returns the (offset, length) of each table in the font.
+fn table_ranges(data: &[u8]) -> Vec<(usize, usize)> {
+    let font = FontRef::new(data).unwrap();
+    font.table_directory
+        .table_records()
+        .iter()
+        .map(|record| (record.offset() as usize, record.length() as usize))
+        .collect()
+}
+
+/// Positions within a table of the given length to corrupt.
+fn corrupt_positions(len: usize) -> impl Iterator<Item = usize> {
+    let header = (0..len.min(48)).step_by(2);
+    let spread = (1..8).map(move |i| i * len / 8);
+    header.chain(spread).filter(move |pos| *pos < len)
+}
+
+#[test]
+fn truncated_tables() {
+    for data in CORPUS {
+        for (ix, (_, len)) in table_ranges(data).into_iter().enumerate() {
+            for new_len in [0, 1, 4, len / 2, len.saturating_sub(1)] {
+                let mut data = data.to_vec();
+                let len_pos = TABLE_RECORD_START + ix * TABLE_RECORD_LEN + 12;
+                data[len_pos..len_pos + 4].copy_from_slice(&(new_len as u32).to_be_bytes());
+                exercise_font(&FontRef::new(&data).unwrap());
+            }
+        }
+    }
+}
+
+#[test]
+fn corrupted_tables() {
+    for data in CORPUS {
+        for (offset, len) in table_ranges(data) {
+            for pos in corrupt_positions(len) {
+                for value in [0x00, 0xFF] {
+                    let mut data = data.to_vec();
+                    data[offset + pos] = value;
+                    exercise_font(&FontRef::new(&data).unwrap());
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn malformed_cases() {
+    for case in font_test_data::malformed::CASES {
+        exercise_font(&FontRef::new(&case.font_data()).unwrap());
+    }
+}
@@ -0,0 +1,118 @@
+use font_test_data::{AUTOHINT_CMAP, NOTOSERIF_AUTOHINT_SHAPING, VAZIRMATN_VAR};
+
+use super::*;
+
+/// Returns the offset of the given slice within the font data.
+fn offset_in<T>(font_data: &[u8], slice: &[T]) -> usize {
+    slice.as_ptr() as usize - font_data.as_ptr() as usize
+}
+
+#[test]
+fn valid_fonts() {
+    for font_data in [VAZIRMATN_VAR, NOTOSERIF_AUTOHINT_SHAPING, AUTOHINT_CMAP] {
+        let font = FontRef::new(font_data).unwrap();
+        let sanitized = sanitize(&font).unwrap();
+        assert!(sanitized.dropped().is_empty(), "{:?}", sanitized.dropped());
+        assert_eq!(sanitized.tables().len(), font.tables().count());
+    }
+}
+
+#[test]
+fn bad_head_magic() {
+    let font = FontRef::new(VAZIRMATN_VAR).unwrap();
+    let head = font.table_data(Tag::new(b"head")).unwrap();
+    let mut data = VAZIRMATN_VAR.to_vec();
+    data[offset_in(VAZIRMATN_VAR, head.as_bytes()) + 12] ^= 0xFF;
+    let font = FontRef::new(&data).unwrap();
+    assert_eq!(
+        sanitize(&font).err(),
+        Some(SanitizeError::Invalid {
+            tag: Tag::new(b"head"),
+            reason: "invalid magic number"
+        })
+    );
+}
+
+#[test]
+fn unordered_loca() {
+    let font = FontRef::new(VAZIRMATN_VAR).unwrap();
+    let loca = font.table_data(Tag::new(b"loca")).unwrap();
+    let mut data = VAZIRMATN_VAR.to_vec();
+    // make the second offset larger than all others
+    let start = offset_in(VAZIRMATN_VAR, loca.as_bytes());
+    let offset_len = loca.len() / (font.maxp().unwrap().num_glyphs() as usize + 1);
+    data[start + offset_len] = 0xFF;
+    let font = FontRef::new(&data).unwrap();
+    assert_eq!(sanitize(&font).err().unwrap().tag(), Tag::new(b"loca"));
+}
+
+#[test]
+fn unordered_cmap_ranges() {
+    let font = FontRef::new(VAZIRMATN_VAR).unwrap();
+    let cmap = font.cmap().unwrap();
+    let Some(CmapSubtable::Format4(cmap4)) = cmap
+        .encoding_records()
+        .iter()
+        .find_map(|rec| rec.subtable(cmap.offset_data()).ok())
+    else {
+        panic!("expected a format 4 subtable");
+    };
+    assert!(cmap4.end_code().len() > 2);
+    let mut data = VAZIRMATN_VAR.to_vec();
+    // swap the first two end codes
+    let end_codes = offset_in(VAZIRMATN_VAR, cmap4.offset_data().as_bytes()) + 14;
+    let (first, second) = data[end_codes..end_codes + 4].split_at_mut(2);
+    first.swap_with_slice(second);
+    let font = FontRef::new(&data).unwrap();
+    assert_eq!(
+        sanitize(&font).err(),
+        Some(SanitizeError::Invalid {
+            tag: Tag::new(b"cmap"),
+            reason: "subtable ranges are unordered or overlapping"
+        })
+    );
+}
+
+#[test]
+fn drop_gsub_with_bad_lookup_index() {
+    let font = FontRef::new(NOTOSERIF_AUTOHINT_SHAPING).unwrap();
+    let feature_list = font.gsub().unwrap().feature_list().unwrap();
+    let indices = feature_list
+        .iter()
+        .map(|feature| feature.unwrap().lookup_list_indices())
+        .find(|indices| !indices.is_empty())
+        .unwrap();
+    let mut data = NOTOSERIF_AUTOHINT_SHAPING.to_vec();
+    let start = offset_in(NOTOSERIF_AUTOHINT_SHAPING, indices);
+    data[start..start + 2].copy_from_slice(&[0xFF, 0xFE]);
+    let font = FontRef::new(&data).unwrap();
+    let sanitized = sanitize(&font).unwrap();
+    assert_eq!(
+        sanitized.dropped(),
+        [SanitizeError::Invalid {
+            tag: Tag::new(b"GSUB"),
+            reason: "lookup index out of range"
+        }]
+    );
+    assert!(sanitized
+        .tables()
+        .iter()
+        .all(|(tag, _)| *tag != Tag::new(b"GSUB")));
+    let overlay = sanitized.overlay(font.clone());
+    assert!(overlay.gsub().is_err());
+    assert!(overlay.gpos().is_ok());
+}
+
+#[test]
+fn lookup_cycles() {
+    assert!(!has_cycle(&[vec![1, 2], vec![2], vec![]]));
+    assert!(has_cycle(&[vec![0]]));
+    assert!(has_cycle(&[vec![], vec![2], vec![3], vec![1]]));
+}
+
+#[test]
+fn range_ordering() {
+    assert!(ranges_are_ordered([(0, 5), (6, 6), (10, 20)].into_iter()));
+    assert!(!ranges_are_ordered([(0, 5), (5, 6)].into_iter()));
+    assert!(!ranges_are_ordered([(6, 5)].into_iter()));
+}
@@ -69,3 +69,49 @@ fn caretvalueformat3() {
             .collect::<Vec<_>>()
     );
 }
+
+#[test]
+fn lookup_glyph_filter() {
+    use crate::test_helpers::BeBuffer;
+    use types::GlyphId;
+    // glyphs 1..=5 are base, ligature, mark, mark and component; glyph 3 is
+    // in mark attachment class 1 and mark set 0, glyph 4 is in mark
+    // attachment class 2
+    let buf = BeBuffer::new()
+        .push(0x0001_0002u32)
+        .extend([14u16, 0, 0, 30, 40])
+        .extend([1u16, 1, 5, 1, 2, 3, 3, 4])
+        .extend([1u16, 3, 2, 1, 2])
+        .extend([1u16, 1])
+        .push(8u32)
+        .extend([1u16, 1, 3]);
+    let gdef = Gdef::read(buf.font_data()).unwrap();
+    let skipped = |flag: LookupFlag, mark_set: Option<u16>| {
+        let filter = LookupGlyphFilter::new(Some(&gdef), flag, mark_set);
+        (0..=6u32)
+            .filter(|gid| filter.should_skip(GlyphId::new(*gid)))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(skipped(LookupFlag::empty(), None), [0; 0]);
+    assert_eq!(skipped(LookupFlag::IGNORE_BASE_GLYPHS, None), [1]);
+    assert_eq!(skipped(LookupFlag::IGNORE_LIGATURES, None), [2]);
+    assert_eq!(skipped(LookupFlag::IGNORE_MARKS, None), [3, 4]);
+    let mut flag = LookupFlag::empty();
+    flag.set_mark_attachment_class(2);
+    assert_eq!(skipped(flag, None), [3]);
+    let flag = LookupFlag::USE_MARK_FILTERING_SET;
+    assert_eq!(skipped(flag, Some(0)), [4]);
+    // a missing mark set covers nothing
+    assert_eq!(skipped(flag, Some(1)), [3, 4]);
+    // the mark set is ignored without the flag
+    assert_eq!(skipped(LookupFlag::empty(), Some(0)), [0; 0]);
+    // without GDEF nothing is skipped
+    let filter = LookupGlyphFilter::new(None, LookupFlag::IGNORE_MARKS, None);
+    assert!(!filter.should_skip(GlyphId::new(3)));
+    let filter = LookupGlyphFilter::new(Some(&gdef), LookupFlag::empty(), None);
+    assert_eq!(
+        filter.glyph_class(GlyphId::new(5)),
+        Some(GlyphClassDef::Component)
+    );
+    assert_eq!(filter.glyph_class(GlyphId::new(6)), None);
+}
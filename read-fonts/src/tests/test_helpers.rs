@@ -1,6 +1,10 @@
 //! small utilities used in tests
 
-use crate::{FontData, Scalar};
+use crate::{
+    tables::{cmap::CmapSubtable, glyf::Glyph},
+    types::{GlyphId, GlyphId16, Tag},
+    FontData, FontRef, Scalar, TableProvider,
+};
 use std::collections::HashMap;
 
 /// A convenience type for generating a buffer of big-endian bytes.
@@ -43,6 +47,17 @@ impl BeBuffer {
         self
     }
 
+    /// Record the current location in the buffer under the given tag.
+    ///
+    /// This does not write any data; it is useful for marking the start of
+    /// a subtable or array so that offsets to it can be patched with
+    /// [`write_offset_at`](Self::write_offset_at).
+    pub fn mark(mut self, tag: &str) -> Self {
+        self.tagged_locations
+            .insert(tag.to_string(), self.data.len());
+        self
+    }
+
     /// Write multiple scalars into the buffer
     pub fn extend<T: Scalar>(mut self, iter: impl IntoIterator<Item = T>) -> Self {
         for item in iter {
@@ -75,6 +90,39 @@ impl BeBuffer {
         }
     }
 
+    /// Write the location of `target` into the offset field tagged `tag`.
+    ///
+    /// The offset is relative to the start of the buffer. Pointing several
+    /// offsets at the same target produces overlapping subtables.
+    pub fn write_offset_at<T: Scalar + TryFrom<usize>>(&mut self, tag: &str, target: &str) {
+        let offset = self.offset_for(target);
+        let Ok(offset) = T::try_from(offset) else {
+            panic!("offset {offset} does not fit in the requested type");
+        };
+        self.write_at(tag, offset);
+    }
+
+    /// Shorten the buffer to the given length in bytes.
+    ///
+    /// Tags pointing past the new end are retained, so offsets to data that
+    /// has been removed can still be written.
+    pub fn truncate(mut self, len: usize) -> Self {
+        self.data.truncate(len);
+        self
+    }
+
+    /// Shorten the buffer so that it ends at the location of the given tag.
+    pub fn truncate_at(self, tag: &str) -> Self {
+        let len = self.offset_for(tag);
+        self.truncate(len)
+    }
+
+    /// Append `len` zero bytes to the buffer.
+    pub fn pad(mut self, len: usize) -> Self {
+        self.data.resize(self.data.len() + len, 0);
+        self
+    }
+
     pub fn font_data(&self) -> FontData {
         FontData::new(&self.data)
     }
@@ -87,6 +135,117 @@ impl std::ops::Deref for BeBuffer {
     }
 }
 
+/// Touches a broad set of read paths for the given font, discarding the
+/// results.
+///
+/// This is used by robustness tests and fuzzers to check that reading
+/// malformed data returns errors rather than panicking. The number of glyphs
+/// visited is capped so that the cost is bounded for hostile inputs.
+pub fn exercise_font(font: &FontRef) {
+    const MAX_GLYPHS: u32 = 512;
+    let num_glyphs = font
+        .maxp()
+        .map(|maxp| maxp.num_glyphs() as u32)
+        .unwrap_or_default()
+        .min(MAX_GLYPHS);
+    let glyph_ids = || (0..num_glyphs).map(GlyphId::new);
+    if let Ok(head) = font.head() {
+        let _ = (head.units_per_em(), head.index_to_loc_format());
+    }
+    if let Ok(cmap) = font.cmap() {
+        let _ = cmap.map_codepoint('A');
+        for record in cmap.encoding_records() {
+            match record.subtable(cmap.offset_data()) {
+                Ok(CmapSubtable::Format4(subtable)) => {
+                    let _ = subtable.iter().take(MAX_GLYPHS as usize).count();
+                }
+                Ok(CmapSubtable::Format12(subtable)) => {
+                    let _ = subtable.iter().take(MAX_GLYPHS as usize).count();
+                }
+                Ok(CmapSubtable::Format14(subtable)) => {
+                    let _ = subtable.map_variant('A', 0xFE0F_u32);
+                    let _ = subtable.iter().take(MAX_GLYPHS as usize).count();
+                }
+                _ => {}
+            }
+        }
+    }
+    if let Ok(hmtx) = font.hmtx() {
+        for gid in glyph_ids() {
+            let _ = (hmtx.advance(gid), hmtx.side_bearing(gid));
+        }
+    }
+    if let (Ok(loca), Ok(glyf)) = (font.loca(None), font.glyf()) {
+        for gid in glyph_ids() {
+            match loca.get_glyf(gid, &glyf) {
+                Ok(Some(Glyph::Simple(glyph))) => {
+                    let _ = glyph.points().count();
+                    let _ = glyph.instructions();
+                }
+                Ok(Some(Glyph::Composite(glyph))) => {
+                    let _ = glyph.components().count();
+                }
+                _ => {}
+            }
+        }
+    }
+    if let Ok(gvar) = font.gvar() {
+        for gid in glyph_ids() {
+            if let Ok(data) = gvar.glyph_variation_data(gid) {
+                for tuple in data.tuples() {
+                    let _ = tuple.deltas().count();
+                }
+            }
+        }
+    }
+    if let Ok(post) = font.post() {
+        for gid in 0..num_glyphs {
+            let _ = post.glyph_name(GlyphId16::new(gid as u16));
+        }
+    }
+    if let Ok(name) = font.name() {
+        for record in name.name_record() {
+            if let Ok(string) = record.string(name.string_data()) {
+                let _ = string.chars().count();
+            }
+        }
+    }
+    if let Ok(gdef) = font.gdef() {
+        if let Some(Ok(classes)) = gdef.glyph_class_def() {
+            let _ = classes.iter().count();
+        }
+    }
+    if let Ok(gsub) = font.gsub() {
+        if let Ok(lookups) = gsub.lookup_list() {
+            for lookup in lookups.lookups().iter().flatten() {
+                let _ = lookup.subtables();
+            }
+        }
+        let _ = gsub.script_list().map(|list| list.script_records().len());
+        let _ = gsub.feature_list().map(|list| list.feature_records().len());
+    }
+    if let Ok(gpos) = font.gpos() {
+        if let Ok(lookups) = gpos.lookup_list() {
+            for lookup in lookups.lookups().iter().flatten() {
+                let _ = lookup.subtables();
+            }
+        }
+    }
+    if let Ok(fvar) = font.fvar() {
+        let _ = fvar.axes().map(|axes| axes.len());
+        let _ = fvar.instances().map(|instances| instances.len());
+    }
+    if let Ok(stat) = font.stat() {
+        let _ = stat.axis_value_tables().count();
+        let _ = stat.design_axis_index(Tag::new(b"wght"));
+    }
+    if let Ok(colr) = font.colr() {
+        for gid in glyph_ids() {
+            let _ = colr.v0_base_glyph(gid);
+        }
+    }
+}
+
 /// be_buffer_add!(buffer, value) - Add an item to a be_buffer.
 ///
 /// Will call the appropriate insertion method on BeBuffer depending on the values format.
@@ -170,4 +329,18 @@ mod tests {
         assert_eq!(builder.offset_for("foo"), 1);
         assert_eq!(builder.offset_for("bar"), 3);
     }
+
+    #[test]
+    fn malformation_helpers() {
+        let mut builder = BeBuffer::new()
+            .push_with_tag(0u16, "offset")
+            .pad(2)
+            .mark("subtable")
+            .extend([1u16, 2, 3]);
+        builder.write_offset_at::<u16>("offset", "subtable");
+        assert_eq!([0, 4, 0, 0, 0, 1, 0, 2, 0, 3], builder.as_slice());
+        let builder = builder.truncate_at("subtable").truncate(3);
+        assert_eq!([0, 4, 0], builder.as_slice());
+        assert_eq!(builder.offset_for("subtable"), 4);
+    }
 }
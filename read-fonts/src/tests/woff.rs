@@ -0,0 +1,145 @@
+//! Tests for WOFF decoding.
+//!
+//! These use a minimal encoder that compresses each table with zlib when
+//! doing so reduces its size.
+
+use super::*;
+use crate::{sfnt_writer::tests::assert_valid_checksums, test_helpers::BeBuffer, FontRef};
+
+const METADATA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<metadata version="1.0">
+<uniqueid id="org.example.test" />
+</metadata>"#;
+
+/// Encodes the given font as WOFF, compressing tables when doing so
+/// reduces their size.
+fn encode(font: &FontRef, metadata: Option<&str>, private_data: &[u8]) -> Vec<u8> {
+    let tables = font.tables().collect::<Vec<_>>();
+    let mut offset = HEADER_LEN + tables.len() * TABLE_ENTRY_LEN;
+    let mut directory = BeBuffer::new();
+    let mut table_data = Vec::new();
+    for table in &tables {
+        let data = table.data().unwrap().as_bytes();
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(data, 6);
+        let stored = if compressed.len() < data.len() {
+            compressed
+        } else {
+            data.to_vec()
+        };
+        directory = directory
+            .push(table.tag())
+            .extend([offset as u32, stored.len() as u32, data.len() as u32])
+            .push(table.checksum());
+        offset += stored.len().next_multiple_of(4);
+        table_data.extend(stored);
+        table_data.resize(table_data.len().next_multiple_of(4), 0);
+    }
+    let metadata = metadata.map(|text| {
+        (
+            miniz_oxide::deflate::compress_to_vec_zlib(text.as_bytes(), 6),
+            text.len(),
+        )
+    });
+    let (meta_offset, meta_length, meta_orig_length) = match &metadata {
+        Some((compressed, orig_len)) => (offset, compressed.len(), *orig_len),
+        None => (0, 0, 0),
+    };
+    offset += meta_length.next_multiple_of(4);
+    let priv_offset = if private_data.is_empty() { 0 } else { offset };
+    let mut buf = BeBuffer::new()
+        .push(WOFF_SIGNATURE)
+        .push(font.table_directory.sfnt_version())
+        .push((offset + private_data.len()) as u32)
+        .push(tables.len() as u16)
+        .push(0u16) // reserved
+        .push(0u32) // totalSfntSize
+        .extend([1u16, 0]) // majorVersion, minorVersion
+        .extend([meta_offset, meta_length, meta_orig_length].map(|x| x as u32))
+        .extend([priv_offset, private_data.len()].map(|x| x as u32))
+        .extend(directory.as_slice().iter().copied())
+        .extend(table_data);
+    if let Some((compressed, _)) = metadata {
+        buf = buf
+            .extend(compressed)
+            .pad(meta_length.next_multiple_of(4) - meta_length);
+    }
+    buf.extend(private_data.iter().copied()).as_slice().to_vec()
+}
+
+#[test]
+fn round_trip() {
+    for font_data in [
+        font_test_data::VAZIRMATN_VAR,
+        font_test_data::CANTARELL_VF_TRIMMED,
+    ] {
+        let font = FontRef::new(font_data).unwrap();
+        let woff = encode(&font, None, &[]);
+        let decoded_data = decode(&woff).unwrap();
+        assert_valid_checksums(&decoded_data);
+        let decoded = FontRef::new(&decoded_data).unwrap();
+        assert_eq!(
+            decoded.table_directory.sfnt_version(),
+            font.table_directory.sfnt_version()
+        );
+        for table in font.tables() {
+            let original = table.data().unwrap().as_bytes();
+            let decoded_table = decoded.table_data(table.tag()).unwrap().as_bytes();
+            if table.tag() == Tag::new(b"head") {
+                assert_eq!(original[..8], decoded_table[..8]);
+                assert_eq!(original[12..], decoded_table[12..]);
+            } else {
+                assert_eq!(original, decoded_table, "{}", table.tag());
+            }
+        }
+        assert!(metadata(&woff).unwrap().is_none());
+        assert!(private_data(&woff).unwrap().is_none());
+    }
+}
+
+#[test]
+fn metadata_and_private_data() {
+    let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+    let woff = encode(&font, Some(METADATA), b"private");
+    assert!(decode(&woff).is_ok());
+    assert_eq!(metadata(&woff).unwrap().unwrap(), METADATA.as_bytes());
+    assert_eq!(private_data(&woff).unwrap(), Some(b"private".as_slice()));
+}
+
+#[test]
+fn invalid_data() {
+    assert!(matches!(
+        decode(font_test_data::VAZIRMATN_VAR),
+        Err(WoffError::InvalidSignature(_))
+    ));
+    let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+    let woff = encode(&font, Some(METADATA), &[]);
+    // the first table is compressed; make its original length larger
+    // than the decompressed data
+    let orig_length_pos = HEADER_LEN + 12;
+    let mut bad_length = woff.clone();
+    bad_length[orig_length_pos + 2] += 1;
+    assert!(matches!(
+        decode(&bad_length),
+        Err(WoffError::Decompression(Some(_)))
+    ));
+    // and then smaller than the compressed length
+    let mut bad_length = woff.clone();
+    bad_length[orig_length_pos..orig_length_pos + 4].fill(0);
+    assert!(matches!(
+        decode(&bad_length),
+        Err(WoffError::InvalidTableLength(_))
+    ));
+    // corrupt the metadata
+    let meta_offset = u32::from_be_bytes(woff[24..28].try_into().unwrap()) as usize;
+    let mut bad_metadata = woff.clone();
+    bad_metadata[meta_offset + 4..meta_offset + 8].fill(0xFF);
+    assert!(matches!(
+        metadata(&bad_metadata),
+        Err(WoffError::Decompression(None))
+    ));
+    // truncated
+    assert!(matches!(
+        decode(&woff[..HEADER_LEN + 8]),
+        Err(WoffError::Read(_))
+    ));
+}
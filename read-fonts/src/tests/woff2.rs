@@ -0,0 +1,405 @@
+//! Tests for WOFF2 decoding.
+//!
+//! These use a minimal encoder that supports the glyf and hmtx transforms
+//! to produce WOFF2 data from the fonts in font-test-data.
+
+use std::io::Write;
+
+use super::*;
+use crate::{
+    sfnt_writer::tests::assert_valid_checksums,
+    tables::glyf::{Glyph, SimpleGlyph},
+    test_helpers::BeBuffer,
+    types::GlyphId16,
+    FontRef, TableProvider,
+};
+
+#[derive(Copy, Clone, Default)]
+struct EncodeOptions {
+    transform_glyf: bool,
+    transform_hmtx: bool,
+}
+
+/// Encodes the given font as WOFF2.
+fn encode(font: &FontRef, options: EncodeOptions) -> Vec<u8> {
+    let mut tags = font.tables().map(|table| table.tag()).collect::<Vec<_>>();
+    // loca must immediately follow glyf
+    tags.retain(|tag| *tag != LOCA);
+    if let Some(pos) = tags.iter().position(|tag| *tag == GLYF) {
+        tags.insert(pos + 1, LOCA);
+    }
+    let mut directory = BeBuffer::new();
+    let mut stream = Vec::new();
+    for tag in tags {
+        let data = font.table_data(tag).unwrap();
+        let orig_len = data.len() as u32;
+        let index = KNOWN_TAGS.iter().position(|known| Tag::new(known) == tag);
+        let (version, transformed) = match tag {
+            GLYF | LOCA if options.transform_glyf => (0, Some(transform_glyf(font))),
+            GLYF | LOCA => (3, None),
+            HMTX if options.transform_hmtx => (1, Some(transform_hmtx(font))),
+            _ => (0, None),
+        };
+        let transformed = transformed.map(|(glyf, hmtx)| if tag == HMTX { hmtx } else { glyf });
+        directory = match index {
+            Some(index) => directory.push((version << 6) | index as u8),
+            None => directory.push((version << 6) | 0x3F).push(tag),
+        };
+        directory = push_base128(directory, orig_len);
+        match (tag, transformed) {
+            (LOCA, Some(_)) => directory = push_base128(directory, 0),
+            (_, Some(data)) => {
+                directory = push_base128(directory, data.len() as u32);
+                stream.extend(data);
+            }
+            (_, None) => stream.extend(data.as_bytes()),
+        }
+    }
+    let mut compressed = Vec::new();
+    {
+        let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 11, 22);
+        writer.write_all(&stream).unwrap();
+    }
+    BeBuffer::new()
+        .push(WOFF2_SIGNATURE)
+        .push(font.table_directory.sfnt_version())
+        .push(0u32) // length
+        .push(font.table_directory.num_tables())
+        .push(0u16) // reserved
+        .push(0u32) // totalSfntSize
+        .push(compressed.len() as u32)
+        .extend([1u16, 0]) // majorVersion, minorVersion
+        .extend([0u32; 5]) // metadata and private data
+        .extend(directory.as_slice().iter().copied())
+        .extend(compressed)
+        .as_slice()
+        .to_vec()
+}
+
+fn push_base128(buf: BeBuffer, value: u32) -> BeBuffer {
+    let mut bytes = vec![(value & 0x7F) as u8];
+    let mut value = value >> 7;
+    while value != 0 {
+        bytes.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.reverse();
+    buf.extend(bytes)
+}
+
+/// Returns the transformed glyf table, along with a dummy value for the
+/// hmtx table to keep the call site simple.
+fn transform_glyf(font: &FontRef) -> (Vec<u8>, Vec<u8>) {
+    let glyf = font.glyf().unwrap();
+    let loca = font.loca(None).unwrap();
+    let num_glyphs = font.maxp().unwrap().num_glyphs();
+    let index_format = font.head().unwrap().index_to_loc_format() as u16;
+    let mut n_contours = BeBuffer::new();
+    let mut n_points = BeBuffer::new();
+    let mut flags = BeBuffer::new();
+    let mut glyphs = BeBuffer::new();
+    let mut composites = BeBuffer::new();
+    let mut bbox_bitmap = vec![0u8; (num_glyphs as usize).div_ceil(32) * 4];
+    let mut bboxes = BeBuffer::new();
+    let mut instructions = BeBuffer::new();
+    for gid in 0..num_glyphs {
+        let glyph = loca.get_glyf(GlyphId16::new(gid).into(), &glyf).unwrap();
+        let mut push_bbox = |bbox: [i16; 4]| {
+            bbox_bitmap[gid as usize / 8] |= 0x80 >> (gid % 8);
+            bboxes = std::mem::take(&mut bboxes).extend(bbox);
+        };
+        match glyph {
+            None => n_contours = n_contours.push(0i16),
+            Some(Glyph::Simple(simple)) => {
+                n_contours = n_contours.push(simple.number_of_contours());
+                let mut last_end = -1i32;
+                for end in simple.end_pts_of_contours() {
+                    let end = end.get() as i32;
+                    // use the one more byte encoding for larger values
+                    let count = (end - last_end) as u16;
+                    n_points = if count >= 253 {
+                        n_points.extend([255u8, (count - 253) as u8])
+                    } else {
+                        n_points.push(count as u8)
+                    };
+                    last_end = end;
+                }
+                let (mut last_x, mut last_y) = (0i32, 0i32);
+                for point in simple.points() {
+                    let (dx, dy) = (point.x as i32 - last_x, point.y as i32 - last_y);
+                    (last_x, last_y) = (point.x as i32, point.y as i32);
+                    // always use the four byte triplet encoding
+                    let flag = 124 | (dx >= 0) as u8 | ((dy >= 0) as u8) << 1;
+                    flags = flags.push(flag | if point.on_curve { 0 } else { 0x80 });
+                    glyphs = glyphs.extend([dx.unsigned_abs() as u16, dy.unsigned_abs() as u16]);
+                }
+                glyphs = push_instructions(glyphs, &mut instructions, simple.instructions());
+                // store explicit bounding boxes for odd glyphs
+                if gid % 2 == 1 {
+                    push_bbox(simple_bbox(&simple));
+                }
+            }
+            Some(Glyph::Composite(composite)) => {
+                n_contours = n_contours.push(-1i16);
+                push_bbox([
+                    composite.x_min(),
+                    composite.y_min(),
+                    composite.x_max(),
+                    composite.y_max(),
+                ]);
+                let mut len = 0;
+                for (_, flags) in composite.component_glyphs_and_flags() {
+                    len += 4 + if flags.contains(CompositeGlyphFlags::ARG_1_AND_2_ARE_WORDS) {
+                        4
+                    } else {
+                        2
+                    };
+                    if flags.contains(CompositeGlyphFlags::WE_HAVE_A_SCALE) {
+                        len += 2;
+                    } else if flags.contains(CompositeGlyphFlags::WE_HAVE_AN_X_AND_Y_SCALE) {
+                        len += 4;
+                    } else if flags.contains(CompositeGlyphFlags::WE_HAVE_A_TWO_BY_TWO) {
+                        len += 8;
+                    }
+                }
+                composites = composites.extend(composite.component_data()[..len].iter().copied());
+                if let Some(instrs) = composite.instructions() {
+                    glyphs = push_instructions(glyphs, &mut instructions, instrs);
+                }
+            }
+        }
+    }
+    let bboxes = BeBuffer::new()
+        .extend(bbox_bitmap)
+        .extend(bboxes.as_slice().iter().copied());
+    let streams = [
+        n_contours,
+        n_points,
+        flags,
+        glyphs,
+        composites,
+        bboxes,
+        instructions,
+    ];
+    let mut glyf = BeBuffer::new().extend([0u16, 0, num_glyphs, index_format]);
+    for stream in &streams {
+        glyf = glyf.push(stream.len() as u32);
+    }
+    for stream in streams {
+        glyf = glyf.extend(stream.as_slice().iter().copied());
+    }
+    (glyf.as_slice().to_vec(), vec![])
+}
+
+fn push_instructions(glyphs: BeBuffer, instructions: &mut BeBuffer, data: &[u8]) -> BeBuffer {
+    *instructions = std::mem::take(instructions).extend(data.iter().copied());
+    // use the word encoding for instruction lengths
+    glyphs.push(253u8).push(data.len() as u16)
+}
+
+fn simple_bbox(glyph: &SimpleGlyph) -> [i16; 4] {
+    [glyph.x_min(), glyph.y_min(), glyph.x_max(), glyph.y_max()]
+}
+
+/// Returns the transformed hmtx table, dropping side bearings that match
+/// the glyph bounding boxes.
+fn transform_hmtx(font: &FontRef) -> (Vec<u8>, Vec<u8>) {
+    let hmtx = font.hmtx().unwrap();
+    let glyf = font.glyf().unwrap();
+    let loca = font.loca(None).unwrap();
+    let x_min = |gid: usize| {
+        loca.get_glyf(GlyphId16::new(gid as u16).into(), &glyf)
+            .unwrap()
+            .map(|glyph| glyph.x_min())
+            .unwrap_or_default()
+    };
+    let h_metrics = hmtx.h_metrics();
+    let lsbs = h_metrics
+        .iter()
+        .map(|metric| metric.side_bearing())
+        .chain(hmtx.left_side_bearings().iter().map(|lsb| lsb.get()))
+        .collect::<Vec<_>>();
+    let matches =
+        |range: std::ops::Range<usize>| range.into_iter().all(|gid| lsbs[gid] == x_min(gid));
+    let drop_proportional = matches(0..h_metrics.len());
+    let drop_monospace = matches(h_metrics.len()..lsbs.len());
+    let mut buf = BeBuffer::new().push(drop_proportional as u8 | (drop_monospace as u8) << 1);
+    for metric in h_metrics {
+        buf = buf.push(metric.advance());
+    }
+    for (gid, lsb) in lsbs.iter().enumerate() {
+        let dropped = if gid < h_metrics.len() {
+            drop_proportional
+        } else {
+            drop_monospace
+        };
+        if !dropped {
+            buf = buf.push(*lsb);
+        }
+    }
+    (vec![], buf.as_slice().to_vec())
+}
+
+/// Checks that the decoded font matches the original.
+fn check_round_trip(font_data: &[u8], options: EncodeOptions) {
+    let font = FontRef::new(font_data).unwrap();
+    let decoded_data = decode(&encode(&font, options)).unwrap();
+    let decoded = FontRef::new(&decoded_data).unwrap();
+    assert_eq!(
+        decoded.table_directory.sfnt_version(),
+        font.table_directory.sfnt_version()
+    );
+    assert_valid_checksums(&decoded_data);
+    for table in font.tables() {
+        let tag = table.tag();
+        let original = table.data().unwrap().as_bytes();
+        let decoded_table = decoded.table_data(tag).unwrap().as_bytes();
+        match tag {
+            HEAD => {
+                assert_eq!(original[..8], decoded_table[..8]);
+                assert_eq!(original[12..], decoded_table[12..]);
+            }
+            GLYF | LOCA if options.transform_glyf => (),
+            _ => assert_eq!(original, decoded_table, "{tag}"),
+        }
+    }
+    if options.transform_glyf {
+        check_glyphs(&font, &decoded);
+    }
+}
+
+fn check_glyphs(font: &FontRef, decoded: &FontRef) {
+    let (glyf, loca) = (font.glyf().unwrap(), font.loca(None).unwrap());
+    let (decoded_glyf, decoded_loca) = (decoded.glyf().unwrap(), decoded.loca(None).unwrap());
+    assert_eq!(loca.len(), decoded_loca.len());
+    for gid in 0..loca.len() as u16 {
+        let gid = GlyphId16::new(gid).into();
+        let glyph = loca.get_glyf(gid, &glyf).unwrap();
+        let decoded_glyph = decoded_loca.get_glyf(gid, &decoded_glyf).unwrap();
+        match (glyph, decoded_glyph) {
+            (None, None) => (),
+            (Some(Glyph::Simple(a)), Some(Glyph::Simple(b))) => {
+                assert_eq!(simple_bbox(&a), simple_bbox(&b));
+                assert_eq!(a.end_pts_of_contours(), b.end_pts_of_contours());
+                assert_eq!(a.instructions(), b.instructions());
+                assert!(a.points().eq(b.points()));
+            }
+            (Some(Glyph::Composite(a)), Some(Glyph::Composite(b))) => {
+                assert_eq!(a.x_min(), b.x_min());
+                assert!(a.components().eq(b.components()));
+                assert_eq!(a.instructions(), b.instructions());
+            }
+            _ => panic!("glyph type mismatch for {gid}"),
+        }
+    }
+}
+
+#[test]
+fn null_transforms() {
+    check_round_trip(font_test_data::VAZIRMATN_VAR, EncodeOptions::default());
+    check_round_trip(
+        font_test_data::CANTARELL_VF_TRIMMED,
+        EncodeOptions::default(),
+    );
+}
+
+#[test]
+fn glyf_transform() {
+    let options = EncodeOptions {
+        transform_glyf: true,
+        ..Default::default()
+    };
+    check_round_trip(font_test_data::GLYF_COMPONENTS, options);
+    check_round_trip(font_test_data::TTHINT_SUBSET, options);
+    check_round_trip(font_test_data::VAZIRMATN_VAR, options);
+}
+
+#[test]
+fn hmtx_transform() {
+    let options = EncodeOptions {
+        transform_glyf: true,
+        transform_hmtx: true,
+    };
+    check_round_trip(font_test_data::GLYF_COMPONENTS, options);
+    check_round_trip(font_test_data::VAZIRMATN_VAR, options);
+}
+
+#[test]
+fn triplets() {
+    // (flag, data, expected)
+    let cases: &[(u8, &[u8], (i16, i16))] = &[
+        (0, &[5], (0, -5)),
+        (3, &[5], (0, 261)),
+        (11, &[5], (5, 0)),
+        (20, &[0x12], (-2, -3)),
+        (23, &[0x12], (2, 3)),
+        (84, &[1, 2], (-2, -3)),
+        (120, &[0x12, 0x34, 0x56], (-0x123, -0x456)),
+        (127, &[0x12, 0x34, 0x56, 0x78], (0x1234, 0x5678)),
+    ];
+    for (flag, data, expected) in cases {
+        let mut cursor = FontData::new(data).cursor();
+        assert_eq!(
+            read_triplet(*flag, &mut cursor).unwrap(),
+            *expected,
+            "{flag}"
+        );
+        assert!(cursor.is_empty());
+    }
+}
+
+#[test]
+fn base128() {
+    let read = |bytes: &[u8]| read_base128(&mut FontData::new(bytes).cursor());
+    assert_eq!(read(&[0x3F]).unwrap(), 63);
+    assert_eq!(read(&[0x81, 0x00]).unwrap(), 128);
+    assert_eq!(read(&[0x8F, 0xFF, 0xFF, 0xFF, 0x7F]).unwrap(), u32::MAX);
+    assert!(matches!(
+        read(&[0x80, 0x01]),
+        Err(Woff2Error::InvalidBase128)
+    ));
+    assert!(matches!(
+        read(&[0x90, 0x80, 0x80, 0x80, 0x00]),
+        Err(Woff2Error::InvalidBase128)
+    ));
+    assert!(matches!(
+        read(&[0x81, 0x80, 0x80, 0x80, 0x80, 0x00]),
+        Err(Woff2Error::InvalidBase128)
+    ));
+}
+
+#[test]
+fn invalid_data() {
+    let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+    assert!(matches!(
+        decode(font_test_data::VAZIRMATN_VAR),
+        Err(Woff2Error::InvalidSignature(_))
+    ));
+    let woff2 = encode(&font, EncodeOptions::default());
+    // truncating the compressed data
+    assert!(decode(&woff2[..woff2.len() - 10]).is_err());
+    // corrupting the compressed data
+    let mut corrupted = woff2.clone();
+    let len = corrupted.len();
+    corrupted[len - 20..].fill(0xFF);
+    assert!(decode(&corrupted).is_err());
+    // glyf transformed without loca
+    let mut woff2 = encode(
+        &font,
+        EncodeOptions {
+            transform_glyf: true,
+            ..Default::default()
+        },
+    );
+    const HEADER_LEN: usize = 48;
+    let data = FontData::new(&woff2);
+    let mut cursor = data.slice(HEADER_LEN..).unwrap().cursor();
+    let loca_flags = loop {
+        let pos = HEADER_LEN + cursor.position().unwrap();
+        if TableEntry::read(&mut cursor).unwrap().tag == LOCA {
+            break pos;
+        }
+    };
+    woff2[loca_flags] |= 3 << 6;
+    assert!(decode(&woff2).is_err());
+}
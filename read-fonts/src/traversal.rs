@@ -23,9 +23,54 @@ use types::{
 use crate::{
     array::{ComputedArray, VarLenArray},
     read::{ComputeSize, ReadArgs},
-    FontData, FontRead, FontReadWithArgs, ReadError, VarSize,
+    FontData, FontRead, FontReadWithArgs, FontRef, ReadError, TableProvider, TopLevelTable,
+    VarSize,
 };
 
+mod diff;
+mod registry;
+
+pub use diff::{diff, Diff, TableDiff};
+pub use registry::{TableParser, TableRegistry};
+
+/// Returns the table with the given tag, for tables that support traversal.
+///
+/// Returns [`ReadError::TableIsMissing`] if the table is not present in the
+/// font or is not supported. Parsers for other tables can be provided with
+/// a [`TableRegistry`].
+pub fn some_table<'a>(
+    font: &FontRef<'a>,
+    tag: Tag,
+) -> Result<Box<dyn SomeTable<'a> + 'a>, ReadError> {
+    use crate::tables;
+    match tag {
+        tables::gpos::Gpos::TAG => font.gpos().map(|x| Box::new(x) as _),
+        tables::gsub::Gsub::TAG => font.gsub().map(|x| Box::new(x) as _),
+        tables::cmap::Cmap::TAG => font.cmap().map(|x| Box::new(x) as _),
+        tables::fvar::Fvar::TAG => font.fvar().map(|x| Box::new(x) as _),
+        tables::avar::Avar::TAG => font.avar().map(|x| Box::new(x) as _),
+        tables::gdef::Gdef::TAG => font.gdef().map(|x| Box::new(x) as _),
+        tables::glyf::Glyf::TAG => font.glyf().map(|x| Box::new(x) as _),
+        tables::head::Head::TAG => font.head().map(|x| Box::new(x) as _),
+        tables::hhea::Hhea::TAG => font.hhea().map(|x| Box::new(x) as _),
+        tables::hmtx::Hmtx::TAG => font.hmtx().map(|x| Box::new(x) as _),
+        tables::loca::Loca::TAG => font.loca(None).map(|x| Box::new(x) as _),
+        tables::maxp::Maxp::TAG => font.maxp().map(|x| Box::new(x) as _),
+        tables::name::Name::TAG => font.name().map(|x| Box::new(x) as _),
+        tables::os2::Os2::TAG => font.os2().map(|x| Box::new(x) as _),
+        tables::post::Post::TAG => font.post().map(|x| Box::new(x) as _),
+        tables::colr::Colr::TAG => font.colr().map(|x| Box::new(x) as _),
+        tables::cpal::Cpal::TAG => font.cpal().map(|x| Box::new(x) as _),
+        tables::stat::Stat::TAG => font.stat().map(|x| Box::new(x) as _),
+        tables::hvar::Hvar::TAG => font.hvar().map(|x| Box::new(x) as _),
+        tables::mvar::Mvar::TAG => font.mvar().map(|x| Box::new(x) as _),
+        tables::vhea::Vhea::TAG => font.vhea().map(|x| Box::new(x) as _),
+        tables::vmtx::Vmtx::TAG => font.vmtx().map(|x| Box::new(x) as _),
+        tables::svg::Svg::TAG => font.svg().map(|x| Box::new(x) as _),
+        _ => Err(ReadError::TableIsMissing(tag)),
+    }
+}
+
 /// Types of fields in font tables.
 ///
 /// Fields can either be scalars, offsets to tables, or arrays.
@@ -283,6 +328,78 @@ impl<'a> dyn SomeTable<'a> + 'a {
             idx: 0,
         }
     }
+
+    /// Returns a JSON representation of this table.
+    ///
+    /// See `write_json` for a description of the format.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json)
+            .expect("writing to a String does not fail");
+        json
+    }
+
+    /// Returns the value at the given path within this table.
+    ///
+    /// A path is a sequence of field names separated by periods, where each
+    /// name may be followed by one or more array indices in brackets:
+    ///
+    /// ```text
+    /// lookup_list.lookups[3].subtables[0].value_format1
+    /// ```
+    ///
+    /// A name matches a field with exactly that name, or a field with that
+    /// name and an `_offset` suffix. For arrays of offsets, the plural form
+    /// is also accepted, so `lookups` matches `lookup_offsets`. Indices may
+    /// also be written as separate path elements, as in `lookups.3`.
+    ///
+    /// Offsets are resolved as they are encountered, so only the tables
+    /// along the path are parsed.
+    pub fn query(&self, path: &str) -> Result<FieldType<'a>, QueryError> {
+        let elements = parse_query_path(path)?;
+        let Some((QueryElement::Field(name), rest)) = elements.split_first() else {
+            return Err(QueryError::InvalidPath(path.to_string()));
+        };
+        let mut current = field_by_name(self, name)?;
+        for element in rest {
+            current = match (element, current) {
+                (QueryElement::Field(name), FieldType::ResolvedOffset(offset)) => {
+                    field_by_name(offset.target?.as_ref(), name)?
+                }
+                (QueryElement::Field(name), FieldType::Record(record)) => {
+                    field_by_name(&record, name)?
+                }
+                (QueryElement::Field(name), _) => {
+                    return Err(QueryError::NotATable(name.to_string()))
+                }
+                (QueryElement::Index(index), FieldType::Array(array)) => {
+                    array_item(array.as_ref(), *index)?
+                }
+                (QueryElement::Index(index), FieldType::ArrayOffset(offset)) => {
+                    array_item(offset.target?.as_ref(), *index)?
+                }
+                (QueryElement::Index(_), _) => return Err(QueryError::NotAnArray),
+            };
+        }
+        Ok(current)
+    }
+
+    /// Writes a JSON representation of this table, including all subtables
+    /// reachable through offsets.
+    ///
+    /// Tables and records are written as objects where the first member,
+    /// `"$type"`, holds the type name and the remaining members are the
+    /// fields in declaration order. Resolved offsets are replaced by their
+    /// targets, while null or unresolved offsets are written as the raw
+    /// offset value. Targets that fail to parse are written as an object with
+    /// a single `"$error"` member.
+    ///
+    /// Integers and fixed point values are written as numbers, dates as the
+    /// number of seconds since 12:00 midnight, January 1, 1904 and tags,
+    /// versions and strings as strings.
+    pub fn write_json(&self, out: &mut dyn std::fmt::Write) -> std::fmt::Result {
+        JsonWriter { out, depth: 0 }.write_table(self)
+    }
 }
 
 struct FieldIter<'a, 'b> {
@@ -611,6 +728,234 @@ impl std::fmt::Display for OffsetType {
     }
 }
 
+/// An error that occurs when resolving a path with `query`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueryError {
+    /// The path is empty or malformed.
+    InvalidPath(String),
+    /// A table or record does not contain a field with the given name.
+    NoSuchField {
+        /// Name of the table or record type.
+        type_name: String,
+        /// The requested field name.
+        name: String,
+    },
+    /// An array index is out of bounds.
+    IndexOutOfBounds {
+        /// The requested index.
+        index: usize,
+        /// The length of the array.
+        len: usize,
+    },
+    /// A field name was applied to a value that is not a table or record.
+    NotATable(String),
+    /// An index was applied to a value that is not an array.
+    NotAnArray,
+    /// The target of an offset could not be read.
+    Read(ReadError),
+}
+
+impl From<ReadError> for QueryError {
+    fn from(value: ReadError) -> Self {
+        Self::Read(value)
+    }
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidPath(path) => write!(f, "invalid query path '{path}'"),
+            Self::NoSuchField { type_name, name } => {
+                write!(f, "{type_name} contains no field '{name}'")
+            }
+            Self::IndexOutOfBounds { index, len } => {
+                write!(f, "index {index} out of bounds for array of length {len}")
+            }
+            Self::NotATable(name) => write!(f, "field '{name}' requested on a non-table value"),
+            Self::NotAnArray => write!(f, "index requested on a non-array value"),
+            Self::Read(err) => write!(f, "failed to read offset: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+enum QueryElement<'p> {
+    Field(&'p str),
+    Index(usize),
+}
+
+fn parse_query_path(path: &str) -> Result<Vec<QueryElement<'_>>, QueryError> {
+    let invalid = || QueryError::InvalidPath(path.to_string());
+    let mut elements = Vec::new();
+    for segment in path.split('.') {
+        let (name, mut indices) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        if name.is_empty() {
+            return Err(invalid());
+        } else if name.bytes().all(|b| b.is_ascii_digit()) {
+            elements.push(QueryElement::Index(name.parse().map_err(|_| invalid())?));
+        } else {
+            elements.push(QueryElement::Field(name));
+        }
+        while !indices.is_empty() {
+            let (index, rest) = indices
+                .strip_prefix('[')
+                .and_then(|indices| indices.split_once(']'))
+                .ok_or_else(invalid)?;
+            elements.push(QueryElement::Index(index.parse().map_err(|_| invalid())?));
+            indices = rest;
+        }
+    }
+    Ok(elements)
+}
+
+fn field_by_name<'a>(
+    table: &(dyn SomeTable<'a> + 'a),
+    name: &str,
+) -> Result<FieldType<'a>, QueryError> {
+    let matches = |field_name: &str| {
+        let base = field_name
+            .strip_suffix("_offsets")
+            .map(|base| (base, true))
+            .or_else(|| field_name.strip_suffix("_offset").map(|base| (base, false)));
+        field_name == name
+            || base.is_some_and(|(base, plural)| {
+                base == name || (plural && name.strip_suffix('s') == Some(base))
+            })
+    };
+    table
+        .iter()
+        .find(|field| matches(field.name))
+        .map(|field| field.value)
+        .ok_or_else(|| QueryError::NoSuchField {
+            type_name: table.type_name().to_string(),
+            name: name.to_string(),
+        })
+}
+
+fn array_item<'a>(
+    array: &(dyn SomeArray<'a> + 'a),
+    index: usize,
+) -> Result<FieldType<'a>, QueryError> {
+    array.get(index).ok_or(QueryError::IndexOutOfBounds {
+        index,
+        len: array.len(),
+    })
+}
+
+/// Writes indented JSON for tables, arrays and fields.
+struct JsonWriter<'w> {
+    out: &'w mut dyn std::fmt::Write,
+    depth: usize,
+}
+
+impl JsonWriter<'_> {
+    fn write_table<'a>(&mut self, table: &(dyn SomeTable<'a> + 'a)) -> std::fmt::Result {
+        self.out.write_char('{')?;
+        self.depth += 1;
+        self.newline()?;
+        self.out.write_str("\"$type\": ")?;
+        self.write_string(table.type_name().chars())?;
+        for field in table.iter() {
+            self.out.write_char(',')?;
+            self.newline()?;
+            self.write_string(field.name.chars())?;
+            self.out.write_str(": ")?;
+            self.write_value(&field.value)?;
+        }
+        self.depth -= 1;
+        self.newline()?;
+        self.out.write_char('}')
+    }
+
+    fn write_array<'a>(&mut self, array: &(dyn SomeArray<'a> + 'a)) -> std::fmt::Result {
+        if array.is_empty() {
+            return self.out.write_str("[]");
+        }
+        self.out.write_char('[')?;
+        self.depth += 1;
+        for (i, item) in array.iter().enumerate() {
+            if i != 0 {
+                self.out.write_char(',')?;
+            }
+            self.newline()?;
+            self.write_value(&item)?;
+        }
+        self.depth -= 1;
+        self.newline()?;
+        self.out.write_char(']')
+    }
+
+    fn write_value(&mut self, value: &FieldType) -> std::fmt::Result {
+        match value {
+            FieldType::I8(val) => write!(self.out, "{val}"),
+            FieldType::U8(val) => write!(self.out, "{val}"),
+            FieldType::I16(val) => write!(self.out, "{val}"),
+            FieldType::U16(val) => write!(self.out, "{val}"),
+            FieldType::I32(val) => write!(self.out, "{val}"),
+            FieldType::U32(val) => write!(self.out, "{val}"),
+            FieldType::I24(val) => write!(self.out, "{}", val.to_i32()),
+            FieldType::U24(val) => write!(self.out, "{}", val.to_u32()),
+            FieldType::Tag(val) => self.write_string(val.to_string().chars()),
+            FieldType::FWord(val) => write!(self.out, "{}", val.to_i16()),
+            FieldType::UfWord(val) => write!(self.out, "{}", val.to_u16()),
+            FieldType::MajorMinor(val) => self.write_string(val.to_string().chars()),
+            FieldType::Version16Dot16(val) => self.write_string(val.to_string().chars()),
+            FieldType::F2Dot14(val) => write!(self.out, "{}", val.to_f32()),
+            FieldType::Fixed(val) => write!(self.out, "{}", val.to_f64()),
+            FieldType::LongDateTime(val) => write!(self.out, "{}", val.as_secs()),
+            FieldType::GlyphId16(val) => write!(self.out, "{}", val.to_u16()),
+            FieldType::NameId(val) => write!(self.out, "{}", val.to_u16()),
+            FieldType::BareOffset(offset) => write!(self.out, "{}", offset.to_u32()),
+            FieldType::ResolvedOffset(ResolvedOffset { target, .. }) => match target {
+                Ok(table) => self.write_table(table.as_ref()),
+                Err(err) => self.write_error(err),
+            },
+            FieldType::StringOffset(StringOffset { target, .. }) => match target {
+                Ok(string) => self.write_string(string.iter_chars()),
+                Err(err) => self.write_error(err),
+            },
+            FieldType::ArrayOffset(ArrayOffset { target, .. }) => match target {
+                Ok(array) => self.write_array(array.as_ref()),
+                Err(err) => self.write_error(err),
+            },
+            FieldType::Record(record) => self.write_table(record),
+            FieldType::Array(array) => self.write_array(array.as_ref()),
+            FieldType::Unknown => self.out.write_str("null"),
+        }
+    }
+
+    fn write_error(&mut self, err: &ReadError) -> std::fmt::Result {
+        self.out.write_str("{\"$error\": ")?;
+        self.write_string(err.to_string().chars())?;
+        self.out.write_char('}')
+    }
+
+    fn write_string(&mut self, chars: impl Iterator<Item = char>) -> std::fmt::Result {
+        self.out.write_char('"')?;
+        for c in chars {
+            match c {
+                '"' => self.out.write_str("\\\"")?,
+                '\\' => self.out.write_str("\\\\")?,
+                '\n' => self.out.write_str("\\n")?,
+                '\r' => self.out.write_str("\\r")?,
+                '\t' => self.out.write_str("\\t")?,
+                c if c < ' ' => write!(self.out, "\\u{:04x}", c as u32)?,
+                c => self.out.write_char(c)?,
+            }
+        }
+        self.out.write_char('"')
+    }
+
+    fn newline(&mut self) -> std::fmt::Result {
+        self.out.write_char('\n')?;
+        for _ in 0..self.depth {
+            self.out.write_str("  ")?;
+        }
+        Ok(())
+    }
+}
+
 // used to give us an auto-impl of Debug
 impl<'a> SomeTable<'a> for RecordResolver<'a> {
     fn type_name(&self) -> &str {
@@ -783,3 +1128,130 @@ impl<T: Into<OffsetType> + Clone> From<Nullable<T>> for OffsetType {
         src.offset().clone().into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tables::{cmap::Cmap4, maxp::Maxp},
+        test_helpers::BeBuffer,
+        FontRef, TableProvider,
+    };
+
+    fn to_json<'a>(table: impl SomeTable<'a> + 'a) -> String {
+        (&table as &dyn SomeTable).to_json()
+    }
+
+    #[test]
+    fn json_scalars() {
+        let mut buf = BeBuffer::new();
+        buf = buf.push(0x00005000u32).push(5u16);
+        let maxp = Maxp::read(buf.font_data()).unwrap();
+        assert_eq!(
+            to_json(maxp),
+            r#"{
+  "$type": "Maxp",
+  "version": "0.5",
+  "num_glyphs": 5
+}"#
+        );
+    }
+
+    #[test]
+    fn json_arrays() {
+        let mut buf = BeBuffer::new();
+        // format, length, language, seg_count_x2, search_range, entry_selector,
+        // range_shift
+        buf = buf.extend([4u16, 24, 0, 2, 2, 0, 0]);
+        // end_code, reserved_pad, start_code, id_delta, id_range_offsets
+        buf = buf.extend([0xFFFFu16, 0, 0xFFFF, 1, 0]);
+        let cmap4 = Cmap4::read(buf.font_data()).unwrap();
+        let json = to_json(cmap4);
+        assert!(json.contains("\n  \"end_code\": [\n    65535\n  ],\n"));
+        assert!(json.contains("\n  \"glyph_id_array\": []\n"));
+    }
+
+    #[test]
+    fn json_offsets() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let json = to_json(font.cmap().unwrap());
+        assert!(json.starts_with("{\n  \"$type\": \"Cmap\",\n  \"version\": 0,"));
+        // the subtable offset is replaced by the subtable
+        assert!(json.contains("\"subtable_offset\": {\n        \"$type\": \"Cmap4\""));
+    }
+
+    #[test]
+    fn query_paths() {
+        let font = FontRef::new(font_test_data::NOTOSERIF_AUTOHINT_SHAPING).unwrap();
+        let gsub = font.gsub().unwrap();
+        let table = &gsub as &dyn SomeTable;
+        let lookup = gsub.lookup_list().unwrap().lookups().get(1).unwrap();
+        let FieldType::U16(lookup_type) =
+            table.query("lookup_list.lookups[1].lookup_type").unwrap()
+        else {
+            panic!("expected a u16");
+        };
+        assert_eq!(lookup_type, lookup.lookup_type());
+        // full field names and separate indices are also accepted
+        assert!(matches!(
+            table.query("lookup_list_offset.lookup_offsets.1.lookup_type"),
+            Ok(FieldType::U16(ty)) if ty == lookup_type
+        ));
+        assert!(matches!(
+            table.query("lookup_list.lookups[1].subtables[0]"),
+            Ok(FieldType::ResolvedOffset(_))
+        ));
+    }
+
+    #[test]
+    fn query_errors() {
+        let font = FontRef::new(font_test_data::NOTOSERIF_AUTOHINT_SHAPING).unwrap();
+        let gsub = font.gsub().unwrap();
+        let table = &gsub as &dyn SomeTable;
+        let lookup_count = gsub.lookup_list().unwrap().lookup_count() as usize;
+        assert_eq!(
+            table.query("lookup_list.lookups[1000]").err(),
+            Some(QueryError::IndexOutOfBounds {
+                index: 1000,
+                len: lookup_count
+            })
+        );
+        assert_eq!(
+            table.query("lookup_list.bogus").err(),
+            Some(QueryError::NoSuchField {
+                type_name: "LookupList".into(),
+                name: "bogus".into()
+            })
+        );
+        assert_eq!(
+            table.query("version.major").err(),
+            Some(QueryError::NotATable("major".into()))
+        );
+        assert_eq!(
+            table.query("version[0]").err(),
+            Some(QueryError::NotAnArray)
+        );
+        for path in [
+            "",
+            "lookup_list..lookups",
+            "lookups[1",
+            "lookups[x]",
+            "[0]",
+            "0",
+        ] {
+            assert!(matches!(table.query(path), Err(QueryError::InvalidPath(_))));
+        }
+    }
+
+    #[test]
+    fn json_string_escapes() {
+        let mut json = String::new();
+        JsonWriter {
+            out: &mut json,
+            depth: 0,
+        }
+        .write_string("a\"b\\c\n\u{1}é".chars())
+        .unwrap();
+        assert_eq!(json, r#""a\"b\\c\n\u0001é""#);
+    }
+}
@@ -0,0 +1,344 @@
+//! Structural comparison of fonts.
+
+use std::fmt::{Display, Write};
+
+use types::Tag;
+
+use super::{
+    some_table, ArrayOffset, FieldType, ResolvedOffset, SomeArray, SomeTable, StringOffset,
+};
+use crate::{FontRef, ReadError};
+
+/// A difference between two values.
+///
+/// Values are rendered as strings, so that a diff can outlive the fonts
+/// it was computed from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Diff {
+    /// A value that is present only in the second font.
+    Added(String),
+    /// A value that is present only in the first font.
+    Removed(String),
+    /// A value that differs between the two fonts.
+    Changed {
+        /// The value in the first font.
+        old: String,
+        /// The value in the second font.
+        new: String,
+    },
+    /// Differences within a table, record or array, keyed by field name or
+    /// array index.
+    Children(Vec<(String, Diff)>),
+}
+
+/// The differences in a single table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableDiff {
+    /// The table tag.
+    pub tag: Tag,
+    /// The changes to the table.
+    pub diff: Diff,
+}
+
+/// Compares two fonts table by table.
+///
+/// Tables that are byte-for-byte identical are skipped. Tables supported by
+/// [`some_table`] are compared field by field, following offsets, so that
+/// changes in subtable layout are not reported; other tables are compared
+/// as opaque data.
+///
+/// Returns the changed tables, ordered by tag.
+pub fn diff(font_a: &FontRef, font_b: &FontRef) -> Vec<TableDiff> {
+    diff_with(font_a, font_b, some_table)
+}
+
+/// Compares two fonts, using `get_table` to parse tables for comparison.
+pub(super) fn diff_with<'a, F>(
+    font_a: &FontRef<'a>,
+    font_b: &FontRef<'a>,
+    get_table: F,
+) -> Vec<TableDiff>
+where
+    F: Fn(&FontRef<'a>, Tag) -> Result<Box<dyn SomeTable<'a> + 'a>, ReadError>,
+{
+    let mut tags = font_a
+        .table_directory
+        .table_records()
+        .iter()
+        .chain(font_b.table_directory.table_records())
+        .map(|record| record.tag())
+        .collect::<Vec<_>>();
+    tags.sort_unstable();
+    tags.dedup();
+    tags.into_iter()
+        .filter_map(|tag| {
+            let diff = match (font_a.table_data(tag), font_b.table_data(tag)) {
+                (Some(a), Some(b)) if a.as_bytes() == b.as_bytes() => return None,
+                (Some(a), Some(b)) => match (get_table(font_a, tag), get_table(font_b, tag)) {
+                    (Ok(table_a), Ok(table_b)) => {
+                        diff_tables(&table_a, &table_b).unwrap_or_else(|| Diff::Changed {
+                            old: byte_len(a.len()),
+                            new: byte_len(b.len()),
+                        })
+                    }
+                    _ => Diff::Changed {
+                        old: byte_len(a.len()),
+                        new: byte_len(b.len()),
+                    },
+                },
+                (Some(a), None) => Diff::Removed(byte_len(a.len())),
+                (None, Some(b)) => Diff::Added(byte_len(b.len())),
+                (None, None) => return None,
+            };
+            Some(TableDiff { tag, diff })
+        })
+        .collect()
+}
+
+fn byte_len(len: usize) -> String {
+    format!("<{len} bytes>")
+}
+
+fn diff_tables<'a, 'b>(a: &(dyn SomeTable<'a> + 'a), b: &(dyn SomeTable<'b> + 'b)) -> Option<Diff> {
+    if a.type_name() != b.type_name() {
+        return Some(Diff::Changed {
+            old: a.type_name().to_string(),
+            new: b.type_name().to_string(),
+        });
+    }
+    let fields_b = b.iter().collect::<Vec<_>>();
+    let mut matched = vec![false; fields_b.len()];
+    let mut children = Vec::new();
+    for field_a in a.iter() {
+        let found = fields_b
+            .iter()
+            .position(|field_b| field_b.name == field_a.name);
+        match found {
+            Some(ix) => {
+                matched[ix] = true;
+                if let Some(diff) = diff_values(&field_a.value, &fields_b[ix].value) {
+                    children.push((field_a.name.to_string(), diff));
+                }
+            }
+            None => children.push((
+                field_a.name.to_string(),
+                Diff::Removed(summary(&field_a.value)),
+            )),
+        }
+    }
+    for (field_b, matched) in fields_b.iter().zip(matched) {
+        if !matched {
+            children.push((
+                field_b.name.to_string(),
+                Diff::Added(summary(&field_b.value)),
+            ));
+        }
+    }
+    (!children.is_empty()).then_some(Diff::Children(children))
+}
+
+fn diff_arrays(a: &dyn SomeArray, b: &dyn SomeArray) -> Option<Diff> {
+    let mut children = Vec::new();
+    for i in 0..a.len().max(b.len()) {
+        let diff = match (a.get(i), b.get(i)) {
+            (Some(a), Some(b)) => diff_values(&a, &b),
+            (Some(a), None) => Some(Diff::Removed(summary(&a))),
+            (None, Some(b)) => Some(Diff::Added(summary(&b))),
+            (None, None) => None,
+        };
+        if let Some(diff) = diff {
+            children.push((format!("[{i}]"), diff));
+        }
+    }
+    (!children.is_empty()).then_some(Diff::Children(children))
+}
+
+fn diff_values(a: &FieldType, b: &FieldType) -> Option<Diff> {
+    match (a, b) {
+        (
+            FieldType::ResolvedOffset(ResolvedOffset { target: Ok(a), .. }),
+            FieldType::ResolvedOffset(ResolvedOffset { target: Ok(b), .. }),
+        ) => diff_tables(a.as_ref(), b.as_ref()),
+        (FieldType::Record(a), FieldType::Record(b)) => diff_tables(a, b),
+        (
+            FieldType::ArrayOffset(ArrayOffset { target: Ok(a), .. }),
+            FieldType::ArrayOffset(ArrayOffset { target: Ok(b), .. }),
+        ) => diff_arrays(a.as_ref(), b.as_ref()),
+        (FieldType::Array(a), FieldType::Array(b)) => diff_arrays(a.as_ref(), b.as_ref()),
+        _ => {
+            let (old, new) = (summary(a), summary(b));
+            (old != new).then_some(Diff::Changed { old, new })
+        }
+    }
+}
+
+/// A short description of a value; subtables and arrays are not expanded.
+fn summary(value: &FieldType) -> String {
+    match value {
+        FieldType::ResolvedOffset(ResolvedOffset {
+            target: Ok(table), ..
+        }) => table.type_name().to_string(),
+        FieldType::Record(record) => record.type_name().to_string(),
+        FieldType::ArrayOffset(ArrayOffset {
+            target: Ok(array), ..
+        }) => array_summary(array.as_ref()),
+        FieldType::Array(array) => array_summary(array.as_ref()),
+        FieldType::StringOffset(StringOffset {
+            target: Ok(string), ..
+        }) => {
+            format!("{:?}", string.iter_chars().collect::<String>())
+        }
+        FieldType::ResolvedOffset(ResolvedOffset {
+            target: Err(err), ..
+        })
+        | FieldType::ArrayOffset(ArrayOffset {
+            target: Err(err), ..
+        })
+        | FieldType::StringOffset(StringOffset {
+            target: Err(err), ..
+        }) => {
+            format!("<error: {err}>")
+        }
+        _ => format!("{value:?}"),
+    }
+}
+
+fn array_summary(array: &dyn SomeArray) -> String {
+    format!("{}[{}]", array.type_name(), array.len())
+}
+
+impl Diff {
+    fn write_tree(
+        &self,
+        name: &str,
+        depth: usize,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        for _ in 0..depth {
+            f.write_str("  ")?;
+        }
+        match self {
+            Diff::Added(value) => writeln!(f, "+ {name}: {value}"),
+            Diff::Removed(value) => writeln!(f, "- {name}: {value}"),
+            Diff::Changed { old, new } => writeln!(f, "~ {name}: {old} -> {new}"),
+            Diff::Children(children) => {
+                f.write_str(name)?;
+                f.write_char('\n')?;
+                for (name, child) in children {
+                    child.write_tree(name, depth + 1, f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for TableDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.diff.write_tree(&self.tag.to_string(), 0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TableProvider;
+
+    /// Returns a copy of the font data with the bytes at `pos` replaced.
+    fn patch(font_data: &[u8], tag: Tag, pos: usize, bytes: &[u8]) -> Vec<u8> {
+        let font = FontRef::new(font_data).unwrap();
+        let record = font
+            .table_directory
+            .table_records()
+            .iter()
+            .find(|record| record.tag() == tag)
+            .unwrap();
+        let start = record.offset() as usize + pos;
+        let mut data = font_data.to_vec();
+        data[start..start + bytes.len()].copy_from_slice(bytes);
+        data
+    }
+
+    #[test]
+    fn identical_fonts() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        assert!(diff(&font, &font).is_empty());
+    }
+
+    #[test]
+    fn changed_scalar() {
+        let data = font_test_data::VAZIRMATN_VAR;
+        let font_a = FontRef::new(data).unwrap();
+        let upem = font_a.head().unwrap().units_per_em();
+        // unitsPerEm is at byte 18 of head
+        let patched = patch(data, Tag::new(b"head"), 18, &(upem + 1).to_be_bytes());
+        let font_b = FontRef::new(&patched).unwrap();
+        let diffs = diff(&font_a, &font_b);
+        assert_eq!(
+            diffs,
+            [TableDiff {
+                tag: Tag::new(b"head"),
+                diff: Diff::Children(vec![(
+                    "units_per_em".into(),
+                    Diff::Changed {
+                        old: upem.to_string(),
+                        new: (upem + 1).to_string(),
+                    }
+                )]),
+            }]
+        );
+        assert_eq!(
+            diffs[0].to_string(),
+            format!("head\n  ~ units_per_em: {upem} -> {}\n", upem + 1)
+        );
+    }
+
+    #[test]
+    fn changed_subtable() {
+        let data = font_test_data::VAZIRMATN_VAR;
+        let font_a = FontRef::new(data).unwrap();
+        let fvar = font_a.fvar().unwrap();
+        let axis = fvar.axes().unwrap()[0];
+        // defaultValue is at byte 8 of the first axis record
+        let axes_offset = fvar.axis_instance_arrays_offset().to_u32() as usize;
+        let new_default = axis.default_value() + types::Fixed::ONE;
+        let patched = patch(
+            data,
+            Tag::new(b"fvar"),
+            axes_offset + 8,
+            &new_default.to_be_bytes(),
+        );
+        let font_b = FontRef::new(&patched).unwrap();
+        let diffs = diff(&font_a, &font_b);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(
+            diffs[0].to_string(),
+            format!(
+                "fvar\n  axis_instance_arrays_offset\n    axes\n      [0]\n        ~ default_value: {:?} -> {:?}\n",
+                axis.default_value(),
+                new_default
+            )
+        );
+    }
+
+    #[test]
+    fn added_and_removed_tables() {
+        let font_a = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let font_b = FontRef::new(font_test_data::SIMPLE_GLYF).unwrap();
+        let diffs = diff(&font_a, &font_b);
+        let fvar = diffs
+            .iter()
+            .find(|diff| diff.tag == Tag::new(b"fvar"))
+            .unwrap();
+        assert!(matches!(fvar.diff, Diff::Removed(_)));
+        assert!(diffs.windows(2).all(|pair| pair[0].tag < pair[1].tag));
+        // the diff is symmetric
+        let reversed = diff(&font_b, &font_a);
+        assert_eq!(reversed.len(), diffs.len());
+        let fvar = reversed
+            .iter()
+            .find(|diff| diff.tag == Tag::new(b"fvar"))
+            .unwrap();
+        assert!(matches!(fvar.diff, Diff::Added(_)));
+    }
+}
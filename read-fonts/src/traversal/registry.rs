@@ -0,0 +1,195 @@
+//! Traversal support for tables not known to this crate.
+
+use std::collections::BTreeMap;
+
+use types::Tag;
+
+use super::{diff::diff_with, some_table, SomeTable, TableDiff};
+use crate::{FontData, FontRef, ReadError};
+
+/// A function that parses the data of a table for traversal.
+pub type TableParser = for<'a> fn(FontData<'a>) -> Result<Box<dyn SomeTable<'a> + 'a>, ReadError>;
+
+/// A set of parsers for tables that are not supported by [`some_table`].
+///
+/// This allows crates that define their own tables (for example, private
+/// vendor tables) to have those tables participate in traversal and
+/// [`diff()`](super::diff()) without changes to this crate. Registered parsers
+/// take precedence over the built in tables, so they may also be used to
+/// replace the parsing of a known tag.
+///
+/// ```
+/// # use read_fonts::{FontData, FontRead, ReadError, traversal::{SomeTable, TableRegistry}};
+/// # use read_fonts::tables::maxp::Maxp;
+/// # use read_fonts::types::Tag;
+/// fn parse_mine<'a>(data: FontData<'a>) -> Result<Box<dyn SomeTable<'a> + 'a>, ReadError> {
+///     // a real parser would read a type defined in the downstream crate
+///     Maxp::read(data).map(|table| Box::new(table) as _)
+/// }
+///
+/// let mut registry = TableRegistry::new();
+/// registry.register(Tag::new(b"MINE"), parse_mine);
+/// assert!(registry.contains(Tag::new(b"MINE")));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TableRegistry {
+    parsers: BTreeMap<Tag, TableParser>,
+}
+
+impl TableRegistry {
+    /// Create a registry with no custom parsers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a parser for the table with the given tag.
+    ///
+    /// Replaces any parser previously registered for `tag`.
+    pub fn register(&mut self, tag: Tag, parser: TableParser) -> &mut Self {
+        self.parsers.insert(tag, parser);
+        self
+    }
+
+    /// Returns `true` if a parser is registered for `tag`.
+    pub fn contains(&self, tag: Tag) -> bool {
+        self.parsers.contains_key(&tag)
+    }
+
+    /// Returns an iterator over the registered tags, in order.
+    pub fn tags(&self) -> impl Iterator<Item = Tag> + '_ {
+        self.parsers.keys().copied()
+    }
+
+    /// Returns the table with the given tag, using a registered parser if
+    /// one exists and otherwise falling back to [`some_table`].
+    pub fn some_table<'a>(
+        &self,
+        font: &FontRef<'a>,
+        tag: Tag,
+    ) -> Result<Box<dyn SomeTable<'a> + 'a>, ReadError> {
+        match self.parsers.get(&tag) {
+            Some(parser) => {
+                let data = font.table_data(tag).ok_or(ReadError::TableIsMissing(tag))?;
+                parser(data)
+            }
+            None => some_table(font, tag),
+        }
+    }
+
+    /// Parse each registered table that is present in `font`.
+    ///
+    /// Returns the tags of tables that failed to parse, along with the
+    /// error, ordered by tag.
+    pub fn validate(&self, font: &FontRef) -> Vec<(Tag, ReadError)> {
+        self.parsers
+            .iter()
+            .filter_map(|(tag, parser)| {
+                let data = font.table_data(*tag)?;
+                parser(data).err().map(|err| (*tag, err))
+            })
+            .collect()
+    }
+
+    /// Compares two fonts table by table, as with [`diff()`](super::diff()),
+    /// using this registry to parse tables.
+    pub fn diff(&self, font_a: &FontRef, font_b: &FontRef) -> Vec<TableDiff> {
+        diff_with(font_a, font_b, |font, tag| self.some_table(font, tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tables::maxp::Maxp,
+        test_helpers::BeBuffer,
+        traversal::{diff, Diff},
+        FontRead,
+    };
+
+    const TEST: Tag = Tag::new(b"TEST");
+
+    fn parse_maxp<'a>(data: FontData<'a>) -> Result<Box<dyn SomeTable<'a> + 'a>, ReadError> {
+        Maxp::read(data).map(|table| Box::new(table) as _)
+    }
+
+    /// Build a font containing a single table with the given tag and data.
+    fn make_font(tag: Tag, table: &[u8]) -> Vec<u8> {
+        // sfnt version, num tables, search range, entry selector, range shift
+        let buf = BeBuffer::new()
+            .push(0x00010000u32)
+            .extend([1u16, 16, 0, 0])
+            // tag, checksum, offset, length
+            .push(tag)
+            .extend([0u32, 28, table.len() as u32])
+            .extend(table.iter().copied());
+        buf.as_slice().to_vec()
+    }
+
+    fn maxp_data(num_glyphs: u16) -> Vec<u8> {
+        BeBuffer::new()
+            .push(0x00005000u32)
+            .push(num_glyphs)
+            .as_slice()
+            .to_vec()
+    }
+
+    #[test]
+    fn registered_table() {
+        let data = make_font(TEST, &maxp_data(5));
+        let font = FontRef::new(&data).unwrap();
+        assert!(matches!(
+            some_table(&font, TEST),
+            Err(ReadError::TableIsMissing(_))
+        ));
+        let mut registry = TableRegistry::new();
+        registry.register(TEST, parse_maxp);
+        assert_eq!(registry.tags().collect::<Vec<_>>(), [TEST]);
+        let table = registry.some_table(&font, TEST).unwrap();
+        assert_eq!(table.type_name(), "Maxp");
+        // unregistered tags fall back to the built in tables
+        assert!(matches!(
+            registry.some_table(&font, Tag::new(b"head")),
+            Err(ReadError::TableIsMissing(_))
+        ));
+    }
+
+    #[test]
+    fn validate_registered() {
+        let good = make_font(TEST, &maxp_data(5));
+        let bad = make_font(TEST, &[0, 0]);
+        let mut registry = TableRegistry::new();
+        registry.register(TEST, parse_maxp);
+        assert!(registry.validate(&FontRef::new(&good).unwrap()).is_empty());
+        let errors = registry.validate(&FontRef::new(&bad).unwrap());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, TEST);
+    }
+
+    #[test]
+    fn diff_registered() {
+        let data_a = make_font(TEST, &maxp_data(5));
+        let data_b = make_font(TEST, &maxp_data(6));
+        let font_a = FontRef::new(&data_a).unwrap();
+        let font_b = FontRef::new(&data_b).unwrap();
+        let mut registry = TableRegistry::new();
+        registry.register(TEST, parse_maxp);
+        let diffs = registry.diff(&font_a, &font_b);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(
+            diffs[0].diff,
+            Diff::Children(vec![(
+                "num_glyphs".into(),
+                Diff::Changed {
+                    old: "5".into(),
+                    new: "6".into(),
+                }
+            )])
+        );
+        // without the registry, the table is opaque
+        assert!(matches!(
+            diff(&font_a, &font_b)[0].diff,
+            Diff::Changed { .. }
+        ));
+    }
+}
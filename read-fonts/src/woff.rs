@@ -0,0 +1,186 @@
+//! Decoding of [WOFF] (version 1.0) compressed fonts.
+//!
+//! WOFF wraps the tables of an sfnt font, compressing each one separately
+//! with zlib, and can carry an additional block of XML metadata and a block
+//! of private data. [`decode`] produces a plain sfnt that can be loaded with
+//! [`FontRef::new`].
+//!
+//! ```no_run
+//! # fn inspect(woff_data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+//! use read_fonts::{woff, FontRef, TableProvider};
+//!
+//! let data = woff::decode(woff_data)?;
+//! let font = FontRef::new(&data)?;
+//! println!("{} glyphs", font.maxp()?.num_glyphs());
+//! if let Some(metadata) = woff::metadata(woff_data)? {
+//!     println!("{}", String::from_utf8_lossy(&metadata));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [WOFF]: https://www.w3.org/TR/WOFF/
+//! [`FontRef::new`]: crate::FontRef::new
+
+use types::Tag;
+
+use crate::{sfnt_writer::build_sfnt, FontData, ReadError};
+
+/// The signature at the start of every WOFF file.
+pub const WOFF_SIGNATURE: Tag = Tag::new(b"wOFF");
+
+const HEADER_LEN: usize = 44;
+const TABLE_ENTRY_LEN: usize = 20;
+
+/// An error that occurs when decoding a WOFF font.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum WoffError {
+    /// The data does not begin with the WOFF signature.
+    InvalidSignature(Tag),
+    /// The compressed data for a table (or the metadata, if `None`) could
+    /// not be decompressed, or did not match the declared size.
+    Decompression(Option<Tag>),
+    /// The compressed length of a table is larger than its original length.
+    InvalidTableLength(Tag),
+    /// The underlying data was truncated or otherwise malformed.
+    Read(ReadError),
+}
+
+/// Decodes a WOFF font, returning the data of the equivalent sfnt font.
+pub fn decode(data: &[u8]) -> Result<Vec<u8>, WoffError> {
+    let header = Header::read(FontData::new(data))?;
+    let tables = (0..header.num_tables as usize)
+        .map(|i| {
+            let entry_data = header
+                .data
+                .slice(HEADER_LEN + i * TABLE_ENTRY_LEN..)
+                .ok_or(ReadError::OutOfBounds)?;
+            read_table(header.data, entry_data)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(build_sfnt(header.flavor, tables))
+}
+
+/// Returns the decompressed extended metadata of a WOFF font, if present.
+///
+/// The metadata is an XML document encoded as UTF-8.
+pub fn metadata(data: &[u8]) -> Result<Option<Vec<u8>>, WoffError> {
+    let header = Header::read(FontData::new(data))?;
+    if header.meta_length == 0 {
+        return Ok(None);
+    }
+    let compressed = header.block(header.meta_offset, header.meta_length)?;
+    inflate(compressed, header.meta_orig_length as usize)
+        .map(Some)
+        .ok_or(WoffError::Decompression(None))
+}
+
+/// Returns the private data block of a WOFF font, if present.
+pub fn private_data(data: &[u8]) -> Result<Option<&[u8]>, WoffError> {
+    let header = Header::read(FontData::new(data))?;
+    if header.priv_length == 0 {
+        return Ok(None);
+    }
+    header
+        .block(header.priv_offset, header.priv_length)
+        .map(Some)
+}
+
+/// The fields of the WOFF header needed for decoding.
+///
+/// See <https://www.w3.org/TR/WOFF/#WOFFHeader>
+struct Header<'a> {
+    data: FontData<'a>,
+    flavor: u32,
+    num_tables: u16,
+    meta_offset: u32,
+    meta_length: u32,
+    meta_orig_length: u32,
+    priv_offset: u32,
+    priv_length: u32,
+}
+
+impl<'a> Header<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, WoffError> {
+        let signature: Tag = data.read_at(0)?;
+        if signature != WOFF_SIGNATURE {
+            return Err(WoffError::InvalidSignature(signature));
+        }
+        Ok(Self {
+            data,
+            flavor: data.read_at(4)?,
+            num_tables: data.read_at(12)?,
+            meta_offset: data.read_at(24)?,
+            meta_length: data.read_at(28)?,
+            meta_orig_length: data.read_at(32)?,
+            priv_offset: data.read_at(36)?,
+            priv_length: data.read_at(40)?,
+        })
+    }
+
+    fn block(&self, offset: u32, length: u32) -> Result<&'a [u8], WoffError> {
+        let start = offset as usize;
+        Ok(self
+            .data
+            .slice(start..start + length as usize)
+            .ok_or(ReadError::OutOfBounds)?
+            .as_bytes())
+    }
+}
+
+/// Reads and decompresses the table for the given table directory entry.
+///
+/// See <https://www.w3.org/TR/WOFF/#TableDirectory>
+fn read_table(data: FontData, entry: FontData) -> Result<(Tag, Vec<u8>), WoffError> {
+    let tag: Tag = entry.read_at(0)?;
+    let offset = entry.read_at::<u32>(4)? as usize;
+    let comp_length = entry.read_at::<u32>(8)? as usize;
+    let orig_length = entry.read_at::<u32>(12)? as usize;
+    let table_data = data
+        .slice(offset..offset + comp_length)
+        .ok_or(ReadError::OutOfBounds)?
+        .as_bytes();
+    let table = match comp_length.cmp(&orig_length) {
+        std::cmp::Ordering::Equal => table_data.to_vec(),
+        std::cmp::Ordering::Less => {
+            inflate(table_data, orig_length).ok_or(WoffError::Decompression(Some(tag)))?
+        }
+        std::cmp::Ordering::Greater => return Err(WoffError::InvalidTableLength(tag)),
+    };
+    Ok((tag, table))
+}
+
+/// Decompresses zlib data, returning `None` if the data is invalid or if
+/// the result is not exactly `expected_len` bytes.
+fn inflate(data: &[u8], expected_len: usize) -> Option<Vec<u8>> {
+    miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(data, expected_len)
+        .ok()
+        .filter(|result| result.len() == expected_len)
+}
+
+impl From<ReadError> for WoffError {
+    fn from(value: ReadError) -> Self {
+        Self::Read(value)
+    }
+}
+
+impl std::fmt::Display for WoffError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSignature(tag) => write!(f, "invalid WOFF signature '{tag}'"),
+            Self::Decompression(Some(tag)) => write!(f, "failed to decompress '{tag}' table"),
+            Self::Decompression(None) => write!(f, "failed to decompress metadata"),
+            Self::InvalidTableLength(tag) => {
+                write!(f, "compressed '{tag}' table is larger than the original")
+            }
+            Self::Read(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for WoffError {}
+
+#[cfg(test)]
+#[path = "tests/woff.rs"]
+mod tests;
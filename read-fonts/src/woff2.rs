@@ -0,0 +1,730 @@
+//! Decoding of [WOFF2] compressed fonts.
+//!
+//! WOFF2 is a container format for web fonts that compresses the font data
+//! with brotli and, optionally, applies transforms to the `glyf`, `loca` and
+//! `hmtx` tables to improve compression. [`decode`] reverses both steps and
+//! produces a plain sfnt that can be loaded with [`FontRef::new`].
+//!
+//! ```no_run
+//! # fn inspect(woff2_data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+//! use read_fonts::{woff2, FontRef, TableProvider};
+//!
+//! let data = woff2::decode(woff2_data)?;
+//! let font = FontRef::new(&data)?;
+//! println!("{} glyphs", font.maxp()?.num_glyphs());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Font collections are not currently supported.
+//!
+//! [WOFF2]: https://www.w3.org/TR/WOFF2/
+//! [`FontRef::new`]: crate::FontRef::new
+
+use std::io::Read;
+
+use types::{Tag, TTC_HEADER_TAG};
+
+use crate::{
+    font_data::Cursor,
+    sfnt_writer::build_sfnt,
+    tables::glyf::{CompositeGlyphFlags, SimpleGlyphFlags},
+    FontData, ReadError,
+};
+
+/// The signature at the start of every WOFF2 file.
+pub const WOFF2_SIGNATURE: Tag = Tag::new(b"wOF2");
+
+const GLYF: Tag = Tag::new(b"glyf");
+const LOCA: Tag = Tag::new(b"loca");
+const HMTX: Tag = Tag::new(b"hmtx");
+const HHEA: Tag = Tag::new(b"hhea");
+const MAXP: Tag = Tag::new(b"maxp");
+const HEAD: Tag = Tag::new(b"head");
+
+/// Tags that can be encoded with a single index in the table directory.
+///
+/// See <https://www.w3.org/TR/WOFF2/#table_dir_format>
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm",
+    b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern",
+    b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC",
+    b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty",
+    b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+    b"Gloc", b"Feat", b"Sill",
+];
+
+/// An error that occurs when decoding a WOFF2 font.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Woff2Error {
+    /// The data does not begin with the WOFF2 signature.
+    InvalidSignature(Tag),
+    /// The file contains a font collection.
+    UnsupportedCollection,
+    /// A variable length integer in the table directory is malformed.
+    InvalidBase128,
+    /// The table directory contains an unknown transform or an
+    /// inconsistent combination of transforms.
+    InvalidTransform(Tag),
+    /// The compressed data could not be decompressed, or did not match the
+    /// size declared in the table directory.
+    Decompression,
+    /// The data for a transformed table is malformed.
+    InvalidTableData(Tag),
+    /// The underlying data was truncated or otherwise malformed.
+    Read(ReadError),
+}
+
+/// Decodes a WOFF2 font, returning the data of the equivalent sfnt font.
+pub fn decode(data: &[u8]) -> Result<Vec<u8>, Woff2Error> {
+    let data = FontData::new(data);
+    let mut cursor = data.cursor();
+    let signature: Tag = cursor.read()?;
+    if signature != WOFF2_SIGNATURE {
+        return Err(Woff2Error::InvalidSignature(signature));
+    }
+    let flavor: u32 = cursor.read()?;
+    if flavor == u32::from_be_bytes(TTC_HEADER_TAG.to_be_bytes()) {
+        return Err(Woff2Error::UnsupportedCollection);
+    }
+    // length
+    cursor.advance::<u32>();
+    let num_tables: u16 = cursor.read()?;
+    // reserved, totalSfntSize
+    cursor.advance::<u16>();
+    cursor.advance::<u32>();
+    let total_compressed_size: u32 = cursor.read()?;
+    // majorVersion, minorVersion, metaOffset, metaLength, metaOrigLength,
+    // privOffset, privLength
+    cursor.advance_by(2 * 2 + 5 * 4);
+    let entries = (0..num_tables)
+        .map(|_| TableEntry::read(&mut cursor))
+        .collect::<Result<Vec<_>, _>>()?;
+    validate_transforms(&entries)?;
+    let compressed_start = cursor.position()?;
+    let compressed = data
+        .slice(compressed_start..compressed_start + total_compressed_size as usize)
+        .ok_or(ReadError::OutOfBounds)?;
+    let stream = decompress(compressed.as_bytes(), &entries)?;
+    let mut tables = Vec::with_capacity(entries.len());
+    let mut offset = 0;
+    for entry in &entries {
+        let end = offset + entry.stored_length as usize;
+        // decompress guarantees that the stream covers all tables
+        let table_data = &stream[offset..end];
+        offset = end;
+        if !entry.is_transformed {
+            tables.push((entry.tag, table_data.to_vec()));
+        } else if entry.tag == GLYF {
+            let (glyf, loca) = reconstruct_glyf(table_data)?;
+            tables.push((GLYF, glyf));
+            tables.push((LOCA, loca));
+        }
+    }
+    // hmtx depends on glyf, hhea and maxp so is handled last
+    if let Some(entry) = entries.iter().find(|e| e.tag == HMTX && e.is_transformed) {
+        let start = entries
+            .iter()
+            .take_while(|e| e.tag != HMTX)
+            .map(|e| e.stored_length as usize)
+            .sum::<usize>();
+        let hmtx_data = &stream[start..start + entry.stored_length as usize];
+        let hmtx = reconstruct_hmtx(hmtx_data, &tables)?;
+        tables.push((HMTX, hmtx));
+    }
+    Ok(build_sfnt(flavor, tables))
+}
+
+/// An entry in the WOFF2 table directory.
+struct TableEntry {
+    tag: Tag,
+    transform_version: u8,
+    is_transformed: bool,
+    /// The length of the table data in the decompressed stream.
+    stored_length: u32,
+}
+
+impl TableEntry {
+    fn read(cursor: &mut Cursor) -> Result<Self, Woff2Error> {
+        let flags: u8 = cursor.read()?;
+        let tag = match flags & 0x3F {
+            0x3F => cursor.read()?,
+            index => Tag::new(KNOWN_TAGS[index as usize]),
+        };
+        let transform_version = flags >> 6;
+        let orig_length = read_base128(cursor)?;
+        // For glyf and loca, version 0 is the transform and 3 is the null
+        // transform; for all other tables, version 0 is the null transform
+        let is_transformed = if tag == GLYF || tag == LOCA {
+            transform_version == 0
+        } else {
+            transform_version != 0
+        };
+        let stored_length = if is_transformed {
+            read_base128(cursor)?
+        } else {
+            orig_length
+        };
+        Ok(Self {
+            tag,
+            transform_version,
+            is_transformed,
+            stored_length,
+        })
+    }
+}
+
+/// Ensures that all transforms are known and that glyf and loca are
+/// transformed together.
+fn validate_transforms(entries: &[TableEntry]) -> Result<(), Woff2Error> {
+    for entry in entries {
+        let valid = match entry.tag {
+            GLYF | LOCA => matches!(entry.transform_version, 0 | 3),
+            HMTX => matches!(entry.transform_version, 0 | 1),
+            _ => entry.transform_version == 0,
+        };
+        if !valid {
+            return Err(Woff2Error::InvalidTransform(entry.tag));
+        }
+    }
+    let is_transformed = |tag| {
+        entries
+            .iter()
+            .find(|entry| entry.tag == tag)
+            .map(|entry| entry.is_transformed)
+    };
+    match (is_transformed(GLYF), is_transformed(LOCA)) {
+        (Some(true), Some(true)) => {
+            if entries
+                .iter()
+                .any(|e| e.tag == LOCA && e.stored_length != 0)
+            {
+                return Err(Woff2Error::InvalidTransform(LOCA));
+            }
+        }
+        (Some(false), Some(false)) | (None, None) => (),
+        _ => return Err(Woff2Error::InvalidTransform(LOCA)),
+    }
+    if is_transformed(HMTX) == Some(true) && is_transformed(GLYF) != Some(true) {
+        return Err(Woff2Error::InvalidTransform(HMTX));
+    }
+    Ok(())
+}
+
+/// Decompresses the table data, ensuring that the result has exactly the
+/// size required by the table directory.
+fn decompress(compressed: &[u8], entries: &[TableEntry]) -> Result<Vec<u8>, Woff2Error> {
+    let expected_len = entries
+        .iter()
+        .try_fold(0u64, |acc, entry| {
+            acc.checked_add(entry.stored_length as u64)
+        })
+        .ok_or(Woff2Error::Decompression)?;
+    let mut stream = Vec::new();
+    brotli_decompressor::Decompressor::new(compressed, 4096)
+        // read one extra byte so that we can detect excess data
+        .take(expected_len + 1)
+        .read_to_end(&mut stream)
+        .map_err(|_| Woff2Error::Decompression)?;
+    if stream.len() as u64 != expected_len {
+        return Err(Woff2Error::Decompression);
+    }
+    Ok(stream)
+}
+
+/// Reads a UIntBase128 value.
+///
+/// See <https://www.w3.org/TR/WOFF2/#DataTypes>
+fn read_base128(cursor: &mut Cursor) -> Result<u32, Woff2Error> {
+    let mut value = 0u32;
+    for i in 0..5 {
+        let byte: u8 = cursor.read()?;
+        // leading zeros are not allowed
+        if i == 0 && byte == 0x80 {
+            return Err(Woff2Error::InvalidBase128);
+        }
+        if value & 0xFE000000 != 0 {
+            return Err(Woff2Error::InvalidBase128);
+        }
+        value = (value << 7) | (byte & 0x7F) as u32;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(Woff2Error::InvalidBase128)
+}
+
+/// Reads a 255UInt16 value.
+///
+/// See <https://www.w3.org/TR/WOFF2/#DataTypes>
+fn read_255_u16(cursor: &mut Cursor) -> Result<u16, ReadError> {
+    const WORD_CODE: u8 = 253;
+    const ONE_MORE_BYTE_CODE_2: u8 = 254;
+    const ONE_MORE_BYTE_CODE_1: u8 = 255;
+    const LOWEST_U_CODE: u16 = 253;
+    let code: u8 = cursor.read()?;
+    Ok(match code {
+        WORD_CODE => cursor.read()?,
+        ONE_MORE_BYTE_CODE_1 => cursor.read::<u8>()? as u16 + LOWEST_U_CODE,
+        ONE_MORE_BYTE_CODE_2 => cursor.read::<u8>()? as u16 + LOWEST_U_CODE * 2,
+        _ => code as u16,
+    })
+}
+
+/// The separate streams of a transformed glyf table.
+///
+/// See <https://www.w3.org/TR/WOFF2/#glyf_table_format>
+struct GlyfStreams<'a> {
+    n_contours: Cursor<'a>,
+    n_points: Cursor<'a>,
+    flags: Cursor<'a>,
+    glyphs: Cursor<'a>,
+    composites: Cursor<'a>,
+    bbox_bitmap: &'a [u8],
+    bboxes: Cursor<'a>,
+    instructions: Cursor<'a>,
+    overlap_bitmap: Option<&'a [u8]>,
+}
+
+/// Reconstructs the glyf and loca tables from a transformed glyf table.
+fn reconstruct_glyf(data: &[u8]) -> Result<(Vec<u8>, Vec<u8>), Woff2Error> {
+    let err = |_| Woff2Error::InvalidTableData(GLYF);
+    let data = FontData::new(data);
+    let mut cursor = data.cursor();
+    // reserved
+    cursor.advance::<u16>();
+    let option_flags: u16 = cursor.read()?;
+    let num_glyphs: u16 = cursor.read()?;
+    let index_format: u16 = cursor.read()?;
+    let mut stream_sizes = [0u32; 7];
+    for size in &mut stream_sizes {
+        *size = cursor.read()?;
+    }
+    let mut rest = cursor.remaining().ok_or(ReadError::OutOfBounds)?;
+    let mut take = |len: usize| rest.take_up_to(len).ok_or(ReadError::OutOfBounds);
+    let [n_contours, n_points, flags, glyphs, composites, bboxes, instructions] =
+        stream_sizes.map(|size| size as usize);
+    let n_contours = take(n_contours)?.cursor();
+    let n_points = take(n_points)?.cursor();
+    let flags = take(flags)?.cursor();
+    let glyphs = take(glyphs)?.cursor();
+    let composites = take(composites)?.cursor();
+    let bboxes = take(bboxes)?;
+    let instructions = take(instructions)?.cursor();
+    let overlap_bitmap = if option_flags & 1 != 0 {
+        Some(take((num_glyphs as usize).div_ceil(8))?.as_bytes())
+    } else {
+        None
+    };
+    let bbox_bitmap_len = (num_glyphs as usize).div_ceil(32) * 4;
+    let bbox_bitmap = bboxes
+        .slice(..bbox_bitmap_len)
+        .ok_or(ReadError::OutOfBounds)?
+        .as_bytes();
+    let bboxes = bboxes
+        .slice(bbox_bitmap_len..)
+        .ok_or(ReadError::OutOfBounds)?
+        .cursor();
+    let mut streams = GlyfStreams {
+        n_contours,
+        n_points,
+        flags,
+        glyphs,
+        composites,
+        bbox_bitmap,
+        bboxes,
+        instructions,
+        overlap_bitmap,
+    };
+    let mut glyf = Vec::new();
+    let mut offsets = Vec::with_capacity(num_glyphs as usize + 1);
+    let mut points = Vec::new();
+    for gid in 0..num_glyphs as usize {
+        offsets.push(glyf.len());
+        streams
+            .reconstruct_glyph(gid, &mut glyf, &mut points)
+            .map_err(err)?;
+        glyf.resize(glyf.len().next_multiple_of(4), 0);
+    }
+    offsets.push(glyf.len());
+    let loca = match index_format {
+        0 => offsets
+            .iter()
+            .map(|offset| u16::try_from(offset / 2).map(u16::to_be_bytes))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Woff2Error::InvalidTableData(LOCA))?
+            .concat(),
+        1 => offsets
+            .iter()
+            .flat_map(|offset| (*offset as u32).to_be_bytes())
+            .collect(),
+        _ => return Err(Woff2Error::InvalidTableData(LOCA)),
+    };
+    Ok((glyf, loca))
+}
+
+impl GlyfStreams<'_> {
+    /// Appends the glyph with the given index to `glyf`.
+    fn reconstruct_glyph(
+        &mut self,
+        gid: usize,
+        glyf: &mut Vec<u8>,
+        points: &mut Vec<(i16, i16, bool)>,
+    ) -> Result<(), ReadError> {
+        let has_bbox = self
+            .bbox_bitmap
+            .get(gid / 8)
+            .is_some_and(|byte| byte & (0x80 >> (gid % 8)) != 0);
+        let n_contours: i16 = self.n_contours.read()?;
+        match n_contours {
+            0 => {
+                if has_bbox {
+                    return Err(ReadError::MalformedData("empty glyph with bounding box"));
+                }
+            }
+            -1 => {
+                if !has_bbox {
+                    return Err(ReadError::MalformedData(
+                        "composite glyph without bounding box",
+                    ));
+                }
+                glyf.extend(n_contours.to_be_bytes());
+                glyf.extend(self.bboxes.read_array::<u8>(8)?);
+                let mut have_instructions = false;
+                loop {
+                    let flags = CompositeGlyphFlags::from_bits_truncate(self.composites.read()?);
+                    let mut len = 2;
+                    len += if flags.contains(CompositeGlyphFlags::ARG_1_AND_2_ARE_WORDS) {
+                        4
+                    } else {
+                        2
+                    };
+                    if flags.contains(CompositeGlyphFlags::WE_HAVE_A_SCALE) {
+                        len += 2;
+                    } else if flags.contains(CompositeGlyphFlags::WE_HAVE_AN_X_AND_Y_SCALE) {
+                        len += 4;
+                    } else if flags.contains(CompositeGlyphFlags::WE_HAVE_A_TWO_BY_TWO) {
+                        len += 8;
+                    }
+                    have_instructions |= flags.contains(CompositeGlyphFlags::WE_HAVE_INSTRUCTIONS);
+                    glyf.extend(flags.bits().to_be_bytes());
+                    glyf.extend(self.composites.read_array::<u8>(len)?);
+                    if !flags.contains(CompositeGlyphFlags::MORE_COMPONENTS) {
+                        break;
+                    }
+                }
+                if have_instructions {
+                    self.push_instructions(glyf)?;
+                }
+            }
+            _ if n_contours > 0 => {
+                self.reconstruct_simple_glyph(gid, n_contours, has_bbox, glyf, points)?
+            }
+            _ => return Err(ReadError::MalformedData("invalid contour count")),
+        }
+        Ok(())
+    }
+
+    fn reconstruct_simple_glyph(
+        &mut self,
+        gid: usize,
+        n_contours: i16,
+        has_bbox: bool,
+        glyf: &mut Vec<u8>,
+        points: &mut Vec<(i16, i16, bool)>,
+    ) -> Result<(), ReadError> {
+        glyf.extend(n_contours.to_be_bytes());
+        let bbox_pos = glyf.len();
+        glyf.extend([0u8; 8]);
+        let mut n_points = 0u16;
+        for _ in 0..n_contours {
+            let contour_points = read_255_u16(&mut self.n_points)?;
+            n_points = n_points
+                .checked_add(contour_points)
+                .ok_or(ReadError::MalformedData("too many points in glyph"))?;
+            glyf.extend(n_points.wrapping_sub(1).to_be_bytes());
+        }
+        let flags = self.flags.read_array::<u8>(n_points as usize)?;
+        points.clear();
+        let (mut x, mut y) = (0i16, 0i16);
+        for &flag in flags {
+            let (dx, dy) = read_triplet(flag & 0x7F, &mut self.glyphs)?;
+            let overflow = || ReadError::MalformedData("point coordinate out of range");
+            x = x.checked_add(dx).ok_or_else(overflow)?;
+            y = y.checked_add(dy).ok_or_else(overflow)?;
+            points.push((x, y, flag & 0x80 == 0));
+        }
+        self.push_instructions(glyf)?;
+        let bbox = if has_bbox {
+            self.bboxes
+                .read_array::<u8>(8)?
+                .try_into()
+                .unwrap_or_default()
+        } else {
+            let mut bbox = [i16::MAX, i16::MAX, i16::MIN, i16::MIN];
+            for &(x, y, _) in points.iter() {
+                bbox = [
+                    bbox[0].min(x),
+                    bbox[1].min(y),
+                    bbox[2].max(x),
+                    bbox[3].max(y),
+                ];
+            }
+            if points.is_empty() {
+                bbox = [0; 4];
+            }
+            let mut bytes = [0u8; 8];
+            for (chunk, value) in bytes.chunks_exact_mut(2).zip(bbox) {
+                chunk.copy_from_slice(&value.to_be_bytes());
+            }
+            bytes
+        };
+        glyf[bbox_pos..bbox_pos + 8].copy_from_slice(&bbox);
+        let overlaps = self
+            .overlap_bitmap
+            .and_then(|bitmap| bitmap.get(gid / 8))
+            .is_some_and(|byte| byte & (0x80 >> (gid % 8)) != 0);
+        encode_points(points, overlaps, glyf);
+        Ok(())
+    }
+
+    /// Reads an instruction length from the glyph stream and appends it
+    /// along with the corresponding instructions to `glyf`.
+    fn push_instructions(&mut self, glyf: &mut Vec<u8>) -> Result<(), ReadError> {
+        let len = read_255_u16(&mut self.glyphs)?;
+        glyf.extend(len.to_be_bytes());
+        glyf.extend(self.instructions.read_array::<u8>(len as usize)?);
+        Ok(())
+    }
+}
+
+/// Reads the point delta for the given flag from the glyph stream.
+///
+/// See <https://www.w3.org/TR/WOFF2/#triplet_decoding>
+fn read_triplet(flag: u8, glyphs: &mut Cursor) -> Result<(i16, i16), ReadError> {
+    fn with_sign(flag: u8, value: i32) -> i32 {
+        if flag & 1 != 0 {
+            value
+        } else {
+            -value
+        }
+    }
+    let flag32 = flag as i32;
+    let (dx, dy) = match flag {
+        0..=9 => {
+            let b0 = glyphs.read::<u8>()? as i32;
+            (0, with_sign(flag, ((flag32 & 14) << 7) + b0))
+        }
+        10..=19 => {
+            let b0 = glyphs.read::<u8>()? as i32;
+            (with_sign(flag, (((flag32 - 10) & 14) << 7) + b0), 0)
+        }
+        20..=83 => {
+            let b0 = flag32 - 20;
+            let b1 = glyphs.read::<u8>()? as i32;
+            (
+                with_sign(flag, 1 + (b0 & 0x30) + (b1 >> 4)),
+                with_sign(flag >> 1, 1 + ((b0 & 0x0C) << 2) + (b1 & 0x0F)),
+            )
+        }
+        84..=119 => {
+            let b0 = flag32 - 84;
+            let [b1, b2]: [u8; 2] = glyphs.read_array::<u8>(2)?.try_into().unwrap_or_default();
+            (
+                with_sign(flag, 1 + ((b0 / 12) << 8) + b1 as i32),
+                with_sign(flag >> 1, 1 + (((b0 % 12) >> 2) << 8) + b2 as i32),
+            )
+        }
+        120..=123 => {
+            let [b1, b2, b3]: [u8; 3] = glyphs.read_array::<u8>(3)?.try_into().unwrap_or_default();
+            let (b1, b2, b3) = (b1 as i32, b2 as i32, b3 as i32);
+            (
+                with_sign(flag, (b1 << 4) + (b2 >> 4)),
+                with_sign(flag >> 1, ((b2 & 0x0F) << 8) + b3),
+            )
+        }
+        _ => {
+            let dx: u16 = glyphs.read()?;
+            let dy: u16 = glyphs.read()?;
+            (with_sign(flag, dx as i32), with_sign(flag >> 1, dy as i32))
+        }
+    };
+    let overflow = |_| ReadError::MalformedData("point coordinate out of range");
+    Ok((
+        i16::try_from(dx).map_err(overflow)?,
+        i16::try_from(dy).map_err(overflow)?,
+    ))
+}
+
+/// Appends the flags and coordinates for the given points to `glyf` using
+/// the standard (compact) simple glyph encoding.
+fn encode_points(points: &[(i16, i16, bool)], overlaps: bool, glyf: &mut Vec<u8>) {
+    let mut flags = Vec::with_capacity(points.len());
+    let mut xs = Vec::new();
+    let mut ys = Vec::new();
+    let (mut last_x, mut last_y) = (0i16, 0i16);
+    for (i, &(x, y, on_curve)) in points.iter().enumerate() {
+        let mut flag = SimpleGlyphFlags::empty();
+        if on_curve {
+            flag |= SimpleGlyphFlags::ON_CURVE_POINT;
+        }
+        if i == 0 && overlaps {
+            flag |= SimpleGlyphFlags::OVERLAP_SIMPLE;
+        }
+        // deltas always fit in i16 because wrapping arithmetic is
+        // consistent with the decoder
+        let dx = x.wrapping_sub(last_x);
+        let dy = y.wrapping_sub(last_y);
+        (last_x, last_y) = (x, y);
+        encode_delta(
+            dx,
+            SimpleGlyphFlags::X_SHORT_VECTOR,
+            SimpleGlyphFlags::X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR,
+            &mut flag,
+            &mut xs,
+        );
+        encode_delta(
+            dy,
+            SimpleGlyphFlags::Y_SHORT_VECTOR,
+            SimpleGlyphFlags::Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR,
+            &mut flag,
+            &mut ys,
+        );
+        flags.push(flag.bits());
+    }
+    let mut i = 0;
+    while i < flags.len() {
+        let flag = flags[i];
+        let repeat = flags[i + 1..]
+            .iter()
+            .take(255)
+            .take_while(|next| **next == flag)
+            .count();
+        if repeat > 0 {
+            glyf.extend([flag | SimpleGlyphFlags::REPEAT_FLAG.bits(), repeat as u8]);
+        } else {
+            glyf.push(flag);
+        }
+        i += repeat + 1;
+    }
+    glyf.extend(xs);
+    glyf.extend(ys);
+}
+
+fn encode_delta(
+    delta: i16,
+    short: SimpleGlyphFlags,
+    same_or_positive: SimpleGlyphFlags,
+    flag: &mut SimpleGlyphFlags,
+    coords: &mut Vec<u8>,
+) {
+    if delta == 0 {
+        *flag |= same_or_positive;
+    } else if delta.unsigned_abs() <= 255 {
+        *flag |= short;
+        if delta > 0 {
+            *flag |= same_or_positive;
+        }
+        coords.push(delta.unsigned_abs() as u8);
+    } else {
+        coords.extend(delta.to_be_bytes());
+    }
+}
+
+/// Reconstructs the hmtx table from a transformed hmtx table.
+///
+/// See <https://www.w3.org/TR/WOFF2/#hmtx_table_format>
+fn reconstruct_hmtx(data: &[u8], tables: &[(Tag, Vec<u8>)]) -> Result<Vec<u8>, Woff2Error> {
+    let table = |tag| {
+        tables
+            .iter()
+            .find(|(table_tag, _)| *table_tag == tag)
+            .map(|(_, data)| FontData::new(data))
+            .ok_or(Woff2Error::InvalidTransform(HMTX))
+    };
+    let num_h_metrics = table(HHEA)?.read_at::<u16>(34)? as usize;
+    let num_glyphs = table(MAXP)?.read_at::<u16>(4)? as usize;
+    let glyf = table(GLYF)?;
+    let loca = table(LOCA)?;
+    let is_long_loca = table(HEAD)?.read_at::<i16>(50)? != 0;
+    let x_min = |gid: usize| -> Result<i16, ReadError> {
+        let (start, end) = if is_long_loca {
+            (
+                loca.read_at::<u32>(gid * 4)? as usize,
+                loca.read_at::<u32>(gid * 4 + 4)? as usize,
+            )
+        } else {
+            (
+                loca.read_at::<u16>(gid * 2)? as usize * 2,
+                loca.read_at::<u16>(gid * 2 + 2)? as usize * 2,
+            )
+        };
+        if start == end {
+            Ok(0)
+        } else {
+            glyf.read_at::<i16>(start + 2)
+        }
+    };
+    if num_h_metrics == 0 || num_h_metrics > num_glyphs {
+        return Err(Woff2Error::InvalidTableData(HMTX));
+    }
+    let data = FontData::new(data);
+    let mut cursor = data.cursor();
+    let flags: u8 = cursor.read()?;
+    if flags & 0xFC != 0 {
+        return Err(Woff2Error::InvalidTableData(HMTX));
+    }
+    let has_proportional_lsbs = flags & 1 == 0;
+    let has_monospace_lsbs = flags & 2 == 0;
+    let advances = cursor.read_array::<types::BigEndian<u16>>(num_h_metrics)?;
+    let mut hmtx = Vec::with_capacity(num_h_metrics * 4 + (num_glyphs - num_h_metrics) * 2);
+    let mut lsbs = Vec::with_capacity(num_glyphs);
+    for gid in 0..num_glyphs {
+        let has_lsb = if gid < num_h_metrics {
+            has_proportional_lsbs
+        } else {
+            has_monospace_lsbs
+        };
+        lsbs.push(if has_lsb {
+            cursor.read::<i16>()?
+        } else {
+            x_min(gid)?
+        });
+    }
+    for (advance, lsb) in advances.iter().zip(&lsbs) {
+        hmtx.extend(advance.get().to_be_bytes());
+        hmtx.extend(lsb.to_be_bytes());
+    }
+    for lsb in &lsbs[num_h_metrics..] {
+        hmtx.extend(lsb.to_be_bytes());
+    }
+    Ok(hmtx)
+}
+
+impl From<ReadError> for Woff2Error {
+    fn from(value: ReadError) -> Self {
+        Self::Read(value)
+    }
+}
+
+impl std::fmt::Display for Woff2Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSignature(tag) => write!(f, "invalid WOFF2 signature '{tag}'"),
+            Self::UnsupportedCollection => write!(f, "WOFF2 font collections are not supported"),
+            Self::InvalidBase128 => write!(f, "invalid UIntBase128 value"),
+            Self::InvalidTransform(tag) => write!(f, "invalid transform for '{tag}' table"),
+            Self::Decompression => write!(f, "failed to decompress font data"),
+            Self::InvalidTableData(tag) => write!(f, "invalid transformed '{tag}' table"),
+            Self::Read(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for Woff2Error {}
+
+#[cfg(test)]
+#[path = "tests/woff2.rs"]
+mod tests;
@@ -255,6 +255,7 @@ table Cmap12 {
 }
 
 /// [cmap Format 13](https://docs.microsoft.com/en-us/typography/opentype/spec/cmap#format-13-many-to-one-range-mappings): Many-to-one range mappings
+#[validate(check_groups)]
 table Cmap13 {
     /// Subtable format; set to 13.
     #[format = 13]
@@ -264,11 +265,13 @@ table Cmap13 {
     #[compile(0)]
     reserved: u16,
     /// Byte length of this subtable (including the header)
+    #[compile(self.compute_length())]
     length: u32,
     /// For requirements on use of the language field, see “Use of
     /// the language field in 'cmap' subtables” in this document.
     language: u32,
     /// Number of groupings which follow
+    #[compile(array_len($groups))]
     num_groups: u32,
     /// Array of ConstantMapGroup records.
     #[count($num_groups)]
@@ -2,10 +2,12 @@
 
 /// [gasp](https://learn.microsoft.com/en-us/typography/opentype/spec/gasp#gasp-table-formats)
 #[tag = "gasp"]
+#[validate(check_ranges)]
 table Gasp {
     /// Version number (set to 1)
     version: u16,
     /// Number of records to follow
+    #[compile(array_len($gasp_ranges))]
     num_ranges: u16,
     /// Sorted by ppem
     #[count($num_ranges)]
@@ -40,7 +40,7 @@ table Gvar {
     /// GlyphVariationData table.
     #[count(add($glyph_count, 1))]
     #[read_with($flags)]
-    #[traverse_with(skip)]
+    #[traverse_with(traverse_glyph_variation_data)]
     #[compile_with(compile_variation_data)]
     #[compile_type(Vec<GlyphVariationData>)]
     glyph_variation_data_offsets: ComputedArray<U16Or32>,
@@ -1,28 +1,35 @@
 #![parse_module(read_fonts::tables::hdmx)]
 
 /// The [Horizontal Device Metrics](https://learn.microsoft.com/en-us/typography/opentype/spec/hdmx) table.
-#[read_args(num_glyphs: u16)]
 #[tag = "hdmx"]
+#[validate(check_records)]
 table Hdmx {
     /// Table version number (set to 0).
     version: u16,
     /// Number of device records.
+    #[compile(array_len($records))]
     num_records: u16,
     /// Size of device record, 32-bit aligned.
+    #[compile(self.compute_size_device_record())]
     size_device_record: u32,
     /// Array of device records.
     #[count($num_records)]
-    #[read_with($num_glyphs)]
+    #[read_with($size_device_record)]
     records: ComputedArray<DeviceRecord<'a>>,
 }
 
-#[read_args(num_glyphs: u16)]
+#[read_args(size_device_record: u32)]
 record DeviceRecord<'a> {
     /// Pixel size for following widths (as ppem).
     pixel_size: u8,
     /// Maximum width.
     max_width: u8,
-    /// Array of glyphs (numgGlyphs is from the 'maxp' table).
-    #[count($num_glyphs)]
+    /// Array of widths, indexed by glyph id (numGlyphs is from the
+    /// 'maxp' table).
+    ///
+    /// Records are padded to a multiple of four bytes and this array
+    /// includes any trailing padding.
+    #[count(subtract($size_device_record, 2))]
+    #[compile_with(compile_padded_widths)]
     widths: [u8],
 }
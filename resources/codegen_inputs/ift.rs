@@ -57,10 +57,14 @@ table PatchMapFormat1 {
 table GlyphMap {
   first_mapped_glyph: u16,
 
+  // The width of each entry depends on max_entry_index in the parent table, so
+  // when compiling these are stored as already encoded bytes.
   #[count(subtract($glyph_count, $first_mapped_glyph))]
   #[read_with($max_entry_index)]
   #[traverse_with(skip)]
-  #[compile(skip)] // TODO remove this once write fonts side is implemented.
+  #[compile_type(Vec<u8>)]
+  #[to_owned(convert_entry_index(obj))]
+  #[validate(skip)]
   entry_index: ComputedArray<U8Or16>,
 }
 
@@ -68,10 +72,14 @@ table GlyphMap {
 table FeatureMap {
   feature_count: u16,
 
+  // As with the glyph map entries, these are stored as already encoded bytes
+  // when compiling.
   #[count($feature_count)]
   #[read_with($max_entry_index)]
   #[traverse_with(skip)]
-  #[compile(skip)] // TODO remove this once write fonts side is implemented.
+  #[compile_type(Vec<u8>)]
+  #[to_owned(convert_feature_records(obj))]
+  #[validate(skip)]
   feature_records: ComputedArray<FeatureRecord>,
 
   // Variable sized array of EntryMapRecord's which depends on the contents of 'feature_records'
@@ -171,7 +179,9 @@ table EntryData {
   #[read_with($entry_id_string_data_offset)]
   #[if_flag($format_flags, EntryFormatFlags::ENTRY_ID_DELTA)]
   #[traverse_with(skip)]
-  #[compile(skip)]
+  // Entries are always written without ID strings, so the delta is an Int24.
+  #[compile_type(Option<Int24>)]
+  #[to_owned(obj.entry_id_delta().map(|delta| Int24::new(delta.into_inner())))]
   entry_id_delta: IdDeltaOrLength,
 
   // PATCH_FORMAT
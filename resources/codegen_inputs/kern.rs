@@ -0,0 +1,90 @@
+#![parse_module(read_fonts::tables::kern)]
+
+/// The OpenType [kerning](https://learn.microsoft.com/en-us/typography/opentype/spec/kern) table.
+table OtKern {
+    /// Table version number—set to 0.
+    version: u16,
+    /// Number of subtables in the kerning table.
+    n_tables: u16,
+    /// Data for subtables, immediately following the header.
+    #[count(..)]
+    subtable_data: [u8],
+}
+
+/// The Apple Advanced Typography [kerning](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kern.html) table.
+table AatKern {
+    /// The version number of the kerning table (0x00010000 for the
+    /// current version).
+    version: MajorMinor,
+    /// The number of subtables included in the kerning table.
+    n_tables: u32,
+    /// Data for subtables, immediately following the header.
+    #[count(..)]
+    subtable_data: [u8],
+}
+
+/// A subtable in an OpenType kerning table.
+table OtSubtable {
+    /// Kern subtable version number-set to 0.
+    version: u16,
+    /// The length of this subtable in bytes, including this header.
+    length: u16,
+    /// Circumstances under which this table is used.
+    coverage: u16,
+    /// Subtable specific data.
+    #[count(..)]
+    data: [u8],
+}
+
+/// A subtable in an AAT kerning table.
+table AatSubtable {
+    /// The length of this subtable in bytes, including this header.
+    length: u32,
+    /// Circumstances under which this table is used.
+    coverage: u16,
+    /// The tuple index (used for variations fonts). This value specifies
+    /// which tuple this subtable covers.
+    tuple_index: u16,
+    /// Subtable specific data.
+    #[count(..)]
+    data: [u8],
+}
+
+/// The type 0 `kern` subtable.
+table Subtable0 {
+    /// The number of kerning pairs in this subtable.
+    n_pairs: u16,
+    /// The largest power of two less than or equal to the value of nPairs,
+    /// multiplied by the size in bytes of an entry in the subtable.
+    search_range: u16,
+    /// This is calculated as log2 of the largest power of two less than or
+    /// equal to the value of nPairs.
+    entry_selector: u16,
+    /// The value of nPairs minus the largest power of two less than or equal
+    /// to nPairs.
+    range_shift: u16,
+    /// Kerning records.
+    #[count($n_pairs)]
+    pairs: [Subtable0Pair],
+}
+
+/// Kerning pair in a type 0 subtable.
+record Subtable0Pair {
+    /// The glyph index for the lefthand glyph in the kerning pair.
+    left: GlyphId16,
+    /// The glyph index for the righthand glyph in the kerning pair.
+    right: GlyphId16,
+    /// The kerning value in FUnits for the left and right pair.
+    value: i16,
+}
+
+/// Class table for the type 2 `kern` subtable.
+table Subtable2ClassTable {
+    /// First glyph in class range.
+    first_glyph: GlyphId16,
+    /// Number of glyph in class range.
+    n_glyphs: u16,
+    /// The offsets array for all of the glyphs in the range.
+    #[count($n_glyphs)]
+    offsets: [u16],
+}
@@ -0,0 +1,58 @@
+#![parse_module(read_fonts::tables::kerx)]
+
+/// The [extended kerning](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kerx.html) table.
+#[tag = "kerx"]
+table Kerx {
+    /// The version number of the extended kerning table (currently 2, 3,
+    /// or 4).
+    version: u16,
+    /// Unused; set to zero.
+    padding: u16,
+    /// The number of subtables included in the extended kerning table.
+    n_tables: u32,
+    /// Data for subtables, immediately following the header.
+    #[count(..)]
+    subtable_data: [u8],
+}
+
+/// A subtable in an extended kerning table.
+table Subtable {
+    /// The length of this subtable in bytes, including this header.
+    length: u32,
+    /// Circumstances under which this table is used.
+    coverage: u32,
+    /// The tuple count. This value is only used with variation fonts and
+    /// should be 0 for all other fonts.
+    tuple_count: u32,
+    /// Subtable specific data.
+    #[count(..)]
+    data: [u8],
+}
+
+/// The type 0 `kerx` subtable.
+table Subtable0 {
+    /// The number of kerning pairs in this subtable.
+    n_pairs: u32,
+    /// The largest power of two less than or equal to the value of nPairs,
+    /// multiplied by the size in bytes of an entry in the subtable.
+    search_range: u32,
+    /// This is calculated as log2 of the largest power of two less than or
+    /// equal to the value of nPairs.
+    entry_selector: u32,
+    /// The value of nPairs minus the largest power of two less than or equal
+    /// to nPairs.
+    range_shift: u32,
+    /// Kerning records.
+    #[count($n_pairs)]
+    pairs: [Subtable0Pair],
+}
+
+/// Kerning pair in a type 0 subtable.
+record Subtable0Pair {
+    /// The glyph index for the lefthand glyph in the kerning pair.
+    left: GlyphId16,
+    /// The glyph index for the righthand glyph in the kerning pair.
+    right: GlyphId16,
+    /// The kerning value in FUnits for the left and right pair.
+    value: i16,
+}
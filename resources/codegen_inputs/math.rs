@@ -0,0 +1,249 @@
+#![parse_module(read_fonts::tables::math)]
+
+/// The [Mathematical Typesetting](https://learn.microsoft.com/en-us/typography/opentype/spec/math) table
+#[tag = "MATH"]
+table Math {
+    /// Major version of the MATH table, = 1.
+    major_version: u16,
+    /// Minor version of the MATH table, = 0.
+    minor_version: u16,
+    /// Offset to MathConstants table, from the beginning of MATH table.
+    math_constants_offset: Offset16<MathConstants>,
+    /// Offset to MathGlyphInfo table, from the beginning of MATH table.
+    math_glyph_info_offset: Offset16<MathGlyphInfo>,
+    /// Offset to MathVariants table, from the beginning of MATH table.
+    math_variants_offset: Offset16<MathVariants>,
+}
+
+/// [MathValueRecord](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathvaluerecord)
+record MathValueRecord {
+    /// The X or Y value in design units.
+    value: FWord,
+    /// Offset to the device table, from the beginning of parent table.
+    /// May be NULL. Suggested format for device table is 1.
+    #[nullable]
+    device_offset: Offset16<DeviceOrVariationIndex>,
+}
+
+/// [MathConstants](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathconstants-table) table
+table MathConstants {
+    /// Percentage of scaling down for level 1 superscripts and
+    /// subscripts. Suggested value: 80%.
+    script_percent_scale_down: i16,
+    /// Percentage of scaling down for level 2 (scriptScript)
+    /// superscripts and subscripts. Suggested value: 60%.
+    script_script_percent_scale_down: i16,
+    /// Minimum height required for a delimited expression (contained
+    /// within parentheses, etc.) to be treated as a sub-formula.
+    delimited_sub_formula_min_height: UfWord,
+    /// Minimum height of n-ary operators (such as integral and
+    /// summation) for formulas in display mode (that is, appearing as
+    /// standalone page elements, not embedded inline within text).
+    display_operator_min_height: UfWord,
+    /// Values for the constants from mathLeading through
+    /// radicalKernAfterDegree, in the order given in the specification.
+    #[count(51)]
+    math_value_records: [MathValueRecord],
+    /// Height of the bottom of the radical degree, if such is present,
+    /// in proportion to the height (ascender + descender) of the
+    /// radical sign.
+    radical_degree_bottom_raise_percent: i16,
+}
+
+/// [MathGlyphInfo](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathglyphinfo-table) table
+table MathGlyphInfo {
+    /// Offset to MathItalicsCorrectionInfo table, from the beginning of
+    /// the MathGlyphInfo table.
+    #[nullable]
+    math_italics_correction_info_offset: Offset16<MathItalicsCorrectionInfo>,
+    /// Offset to MathTopAccentAttachment table, from the beginning of
+    /// the MathGlyphInfo table.
+    #[nullable]
+    math_top_accent_attachment_offset: Offset16<MathTopAccentAttachment>,
+    /// Offset to ExtendedShapes coverage table, from the beginning of
+    /// the MathGlyphInfo table. When the glyph to the left or right of
+    /// a box is an extended shape variant, the (ink) box should be
+    /// used for vertical positioning purposes, not the default
+    /// position defined by values in MathConstants table. May be NULL.
+    #[nullable]
+    extended_shape_coverage_offset: Offset16<CoverageTable>,
+    /// Offset to MathKernInfo table, from the beginning of the
+    /// MathGlyphInfo table.
+    #[nullable]
+    math_kern_info_offset: Offset16<MathKernInfo>,
+}
+
+/// [MathItalicsCorrectionInfo](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathitalicscorrectioninfo-table) table
+table MathItalicsCorrectionInfo {
+    /// Offset to Coverage table, from the beginning of
+    /// MathItalicsCorrectionInfo table.
+    italics_correction_coverage_offset: Offset16<CoverageTable>,
+    /// Number of italics correction values. Should coincide with the
+    /// number of covered glyphs.
+    italics_correction_count: u16,
+    /// Array of MathValueRecords defining italics correction values
+    /// for each covered glyph.
+    #[count($italics_correction_count)]
+    italics_correction: [MathValueRecord],
+}
+
+/// [MathTopAccentAttachment](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathtopaccentattachment-table) table
+table MathTopAccentAttachment {
+    /// Offset to Coverage table, from the beginning of
+    /// MathTopAccentAttachment table.
+    top_accent_coverage_offset: Offset16<CoverageTable>,
+    /// Number of top accent attachment point values. Must be the same
+    /// as the number of glyph IDs referenced in the Coverage table.
+    top_accent_attachment_count: u16,
+    /// Array of MathValueRecords defining top accent attachment points
+    /// for each covered glyph.
+    #[count($top_accent_attachment_count)]
+    top_accent_attachment: [MathValueRecord],
+}
+
+/// [MathKernInfo](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathkerninfo-table) table
+table MathKernInfo {
+    /// Offset to Coverage table, from the beginning of the
+    /// MathKernInfo table.
+    math_kern_coverage_offset: Offset16<CoverageTable>,
+    /// Number of MathKernInfoRecords. Must be the same as the number
+    /// of glyph IDs referenced in the Coverage table.
+    math_kern_count: u16,
+    /// Array of MathKernInfoRecords, one for each covered glyph.
+    #[count($math_kern_count)]
+    math_kern_info_records: [MathKernInfoRecord],
+}
+
+/// [MathKernInfoRecord](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathkerninforecord)
+record MathKernInfoRecord {
+    /// Offset to MathKern table for top right corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    #[nullable]
+    top_right_math_kern_offset: Offset16<MathKern>,
+    /// Offset to MathKern table for the top left corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    #[nullable]
+    top_left_math_kern_offset: Offset16<MathKern>,
+    /// Offset to MathKern table for bottom right corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    #[nullable]
+    bottom_right_math_kern_offset: Offset16<MathKern>,
+    /// Offset to MathKern table for bottom left corner, from the
+    /// beginning of the MathKernInfo table. May be NULL.
+    #[nullable]
+    bottom_left_math_kern_offset: Offset16<MathKern>,
+}
+
+/// [MathKern](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathkern-table) table
+table MathKern {
+    /// Number of heights at which the kern value changes.
+    height_count: u16,
+    /// Array of correction heights, in design units, sorted from
+    /// lowest to highest.
+    #[count($height_count)]
+    correction_height: [MathValueRecord],
+    /// Array of kerning values for different height ranges. Negative
+    /// values are used to move glyphs closer to each other.
+    #[count(add($height_count, 1))]
+    kern_values: [MathValueRecord],
+}
+
+/// [MathVariants](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathvariants-table) table
+table MathVariants {
+    /// Minimum overlap of connecting glyphs during glyph construction,
+    /// in design units.
+    min_connector_overlap: UfWord,
+    /// Offset to Coverage table, from the beginning of the
+    /// MathVariants table.
+    #[nullable]
+    vert_glyph_coverage_offset: Offset16<CoverageTable>,
+    /// Offset to Coverage table, from the beginning of the
+    /// MathVariants table.
+    #[nullable]
+    horiz_glyph_coverage_offset: Offset16<CoverageTable>,
+    /// Number of glyphs for which information is provided for
+    /// vertically growing variants. Must be the same as the number of
+    /// glyph IDs referenced in the vertical Coverage table.
+    vert_glyph_count: u16,
+    /// Number of glyphs for which information is provided for
+    /// horizontally growing variants. Must be the same as the number
+    /// of glyph IDs referenced in the horizontal Coverage table.
+    horiz_glyph_count: u16,
+    /// Array of offsets to MathGlyphConstruction tables, from the
+    /// beginning of the MathVariants table, for shapes growing in the
+    /// vertical direction.
+    #[count($vert_glyph_count)]
+    vert_glyph_construction_offsets: [Offset16<MathGlyphConstruction>],
+    /// Array of offsets to MathGlyphConstruction tables, from the
+    /// beginning of the MathVariants table, for shapes growing in the
+    /// horizontal direction.
+    #[count($horiz_glyph_count)]
+    horiz_glyph_construction_offsets: [Offset16<MathGlyphConstruction>],
+}
+
+/// [MathGlyphConstruction](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathglyphconstruction-table) table
+table MathGlyphConstruction {
+    /// Offset to the GlyphAssembly table for this shape, from the
+    /// beginning of the MathGlyphConstruction table. May be NULL.
+    #[nullable]
+    glyph_assembly_offset: Offset16<GlyphAssembly>,
+    /// Count of glyph growing variants for this glyph.
+    variant_count: u16,
+    /// MathGlyphVariantRecords for alternative variants of the glyphs.
+    #[count($variant_count)]
+    math_glyph_variant_records: [MathGlyphVariantRecord],
+}
+
+/// [MathGlyphVariantRecord](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathglyphvariantrecord)
+record MathGlyphVariantRecord {
+    /// Glyph ID for the variant.
+    variant_glyph: GlyphId16,
+    /// Advance width/height, in design units, of the variant, in the
+    /// direction of requested glyph extension.
+    advance_measurement: UfWord,
+}
+
+/// [GlyphAssembly](https://learn.microsoft.com/en-us/typography/opentype/spec/math#glyphassembly-table) table
+table GlyphAssembly {
+    /// Value of the italics correction of this GlyphAssembly. Should
+    /// not depend on the assembly size.
+    italics_correction_value: FWord,
+    /// Offset to the device table for the italics correction, from the
+    /// beginning of the GlyphAssembly table. May be NULL.
+    #[nullable]
+    italics_correction_device_offset: Offset16<DeviceOrVariationIndex>,
+    /// Number of parts in this assembly.
+    part_count: u16,
+    /// Array of GlyphPart records, from left to right (for assemblies
+    /// that extend horizontally) or bottom to top (for assemblies that
+    /// extend vertically).
+    #[count($part_count)]
+    part_records: [GlyphPart],
+}
+
+/// [GlyphPart](https://learn.microsoft.com/en-us/typography/opentype/spec/math#glyphpart-record)
+record GlyphPart {
+    /// Glyph ID for the part.
+    glyph_id: GlyphId16,
+    /// Advance width/ height, in design units, of the straight bar
+    /// connector material at the start of the glyph in the direction
+    /// of the extension (the left end for horizontal extension, the
+    /// bottom end for vertical extension).
+    start_connector_length: UfWord,
+    /// Advance width/ height, in design units, of the straight bar
+    /// connector material at the end of the glyph in the direction of
+    /// the extension (the right end for horizontal extension, the top
+    /// end for vertical extension).
+    end_connector_length: UfWord,
+    /// Full advance width/height for this part in the direction of the
+    /// extension, in design units.
+    full_advance: UfWord,
+    /// Part qualifiers.
+    part_flags: GlyphPartFlags,
+}
+
+/// [GlyphPart](https://learn.microsoft.com/en-us/typography/opentype/spec/math#glyphpart-record) flags
+flags u16 GlyphPartFlags {
+    /// If set, the part can be skipped or repeated.
+    EXTENDER_FLAG = 0x0001,
+}
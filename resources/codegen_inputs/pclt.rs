@@ -0,0 +1,39 @@
+#![parse_module(read_fonts::tables::pclt)]
+
+/// The [PCLT (PCL 5)](https://learn.microsoft.com/en-us/typography/opentype/spec/pclt) table
+#[tag = "PCLT"]
+table Pclt {
+    /// Table version number (set to 1.0)
+    version: Version16Dot16,
+    /// Unique identifier of the font
+    font_number: u32,
+    /// Width of the space in FUnits
+    pitch: u16,
+    /// Height of the optical x-height in FUnits
+    x_height: u16,
+    /// Posture, appearance width and structure of the font
+    style: u16,
+    /// Typeface family and vendor code
+    type_family: u16,
+    /// Height of the optical cap height in FUnits
+    cap_height: u16,
+    /// Symbol set of the font
+    symbol_set: u16,
+    /// Name of the font, padded with spaces
+    #[count(16)]
+    typeface: [u8],
+    /// Symbol sets that are supported by the font
+    #[count(8)]
+    character_complement: [u8],
+    /// Base name of the file for the font
+    #[count(6)]
+    file_name: [u8],
+    /// Stroke weight, from -7 (thinnest) to 7 (thickest)
+    stroke_weight: i8,
+    /// Width type, from -5 (ultra compressed) to 5 (ultra expanded)
+    width_type: i8,
+    /// Serif style of the font
+    serif_style: u8,
+    /// Reserved (set to 0)
+    reserved: u8,
+}
@@ -1,3 +1,11 @@
+//! Decoding of shared brotli (<https://datatracker.ietf.org/doc/draft-vandevenne-shared-brotli-format/>)
+//! encoded patch data.
+//!
+//! Decoding is abstracted behind the [`SharedBrotliDecoder`] trait so that alternative brotli
+//! implementations can be used. The default implementation, [`BuiltInBrotliDecoder`], wraps the C
+//! brotli library and is enabled by the `c-brotli` feature.
+
+#[cfg(feature = "c-brotli")]
 use brotlic_sys::{
     BrotliDecoderAttachDictionary, BrotliDecoderCreateInstance, BrotliDecoderDecompressStream,
     BrotliDecoderDestroyInstance, BrotliDecoderResult_BROTLI_DECODER_RESULT_ERROR,
@@ -6,6 +14,7 @@ use brotlic_sys::{
     BrotliDecoderResult_BROTLI_DECODER_RESULT_SUCCESS,
     BrotliSharedDictionaryType_BROTLI_SHARED_DICTIONARY_RAW, BROTLI_FALSE,
 };
+#[cfg(feature = "c-brotli")]
 use core::ptr;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -36,6 +45,48 @@ impl std::fmt::Display for DecodeError {
 
 impl std::error::Error for DecodeError {}
 
+/// A decoder for shared brotli encoded data.
+///
+/// Implement this to supply a brotli implementation other than the built in one, for example
+/// one backed by a system libbrotli.
+pub trait SharedBrotliDecoder {
+    /// Decodes shared brotli encoded data using the optional shared dictionary.
+    ///
+    /// The shared dictionary is a raw LZ77 style dictionary, see:
+    /// <https://datatracker.ietf.org/doc/html/draft-vandevenne-shared-brotli-format-11#section-3.2>
+    ///
+    /// Implementations must fail with [`DecodeError::MaxSizeExceeded`] if the decoded result
+    /// would be greater than max_uncompressed_length and with [`DecodeError::ExcessInputData`]
+    /// if there is data in encoded after the encoded stream finishes.
+    fn decode(
+        &self,
+        encoded: &[u8],
+        shared_dictionary: Option<&[u8]>,
+        max_uncompressed_length: usize,
+    ) -> Result<Vec<u8>, DecodeError>;
+}
+
+/// The default [`SharedBrotliDecoder`], implemented with the C brotli library.
+#[cfg(feature = "c-brotli")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BuiltInBrotliDecoder;
+
+#[cfg(feature = "c-brotli")]
+impl SharedBrotliDecoder for BuiltInBrotliDecoder {
+    fn decode(
+        &self,
+        encoded: &[u8],
+        shared_dictionary: Option<&[u8]>,
+        max_uncompressed_length: usize,
+    ) -> Result<Vec<u8>, DecodeError> {
+        shared_brotli_decode(encoded, shared_dictionary, max_uncompressed_length)
+    }
+}
+
+/// Size of the intermediate buffer that decoded data is written into before being passed on.
+#[cfg(feature = "c-brotli")]
+const CHUNK_SIZE: usize = 16 * 1024;
+
 /// Decodes shared brotli encoded data using the optional shared dictionary.
 ///
 /// The shared dictionary is a raw LZ77 style dictionary, see:
@@ -43,11 +94,38 @@ impl std::error::Error for DecodeError {}
 ///
 /// Will fail if the decoded result will be greater then max_uncompressed_length. Any excess data
 /// in encoded after the encoded stream finishes is also considered an error.
+#[cfg(feature = "c-brotli")]
 pub fn shared_brotli_decode(
     encoded: &[u8],
     shared_dictionary: Option<&[u8]>,
     max_uncompressed_length: usize,
 ) -> Result<Vec<u8>, DecodeError> {
+    let mut decoded = Vec::new();
+    shared_brotli_decode_streaming(
+        encoded,
+        shared_dictionary,
+        max_uncompressed_length,
+        |chunk| decoded.extend_from_slice(chunk),
+    )?;
+    Ok(decoded)
+}
+
+/// Decodes shared brotli encoded data using the optional shared dictionary, passing the decoded
+/// data to sink in chunks as it is produced.
+///
+/// Output space is not reserved up front, so memory use is bounded by the size of the decoded
+/// data instead of by max_uncompressed_length. Decoding stops as soon as more than
+/// max_uncompressed_length bytes would be produced.
+///
+/// Returns the total number of decoded bytes. On error some of the decoded data may have already
+/// been passed to sink.
+#[cfg(feature = "c-brotli")]
+pub fn shared_brotli_decode_streaming(
+    encoded: &[u8],
+    shared_dictionary: Option<&[u8]>,
+    max_uncompressed_length: usize,
+    mut sink: impl FnMut(&[u8]),
+) -> Result<usize, DecodeError> {
     #[cfg(fuzzing)]
     {
         // When running under a fuzzer disable brotli decoding and instead just pass through the input data.
@@ -56,7 +134,8 @@ pub fn shared_brotli_decode(
         //   implementation to be provided. This would allow fuzzing to sub in a custom impl that could return all
         //   of the possible errors that the standard impl here can generate.
         return if encoded.len() <= max_uncompressed_length {
-            Ok(encoded.to_vec())
+            sink(encoded);
+            Ok(encoded.len())
         } else {
             Err(DecodeError::MaxSizeExceeded)
         };
@@ -84,16 +163,19 @@ pub fn shared_brotli_decode(
         }
     }
 
-    let mut sink = vec![0u8; max_uncompressed_length];
+    let mut chunk = vec![0u8; CHUNK_SIZE.min(max_uncompressed_length)];
 
     let mut next_in = encoded.as_ptr();
     let mut available_in = encoded.len();
-    let mut next_out = sink.as_mut_ptr();
-    let mut available_out = sink.len();
     let mut total_out = 0;
+    let mut written = 0;
 
     let mut error: Option<DecodeError> = None;
     loop {
+        // Each call gets a fresh chunk, limited so that the total output can't exceed the max.
+        let capacity = chunk.len().min(max_uncompressed_length - written);
+        let mut next_out = chunk.as_mut_ptr();
+        let mut available_out = capacity;
         let result = unsafe {
             BrotliDecoderDecompressStream(
                 decoder,
@@ -105,6 +187,12 @@ pub fn shared_brotli_decode(
             )
         };
 
+        let produced = capacity - available_out;
+        if produced > 0 {
+            sink(&chunk[..produced]);
+            written += produced;
+        }
+
         #[allow(non_upper_case_globals)]
         match result {
             BrotliDecoderResult_BROTLI_DECODER_RESULT_SUCCESS => break,
@@ -117,8 +205,10 @@ pub fn shared_brotli_decode(
                 error = Some(DecodeError::InvalidStream);
                 break;
             }
-            BrotliDecoderResult_BROTLI_DECODER_RESULT_NEEDS_MORE_OUTPUT if available_out == 0 => {
-                // Needs more output space, but none is available.
+            BrotliDecoderResult_BROTLI_DECODER_RESULT_NEEDS_MORE_OUTPUT
+                if written == max_uncompressed_length =>
+            {
+                // Needs more output space, but the max has been reached.
                 error = Some(DecodeError::MaxSizeExceeded);
                 break;
             }
@@ -138,16 +228,10 @@ pub fn shared_brotli_decode(
         return Err(DecodeError::ExcessInputData);
     }
 
-    if total_out > sink.len() {
-        return Err(DecodeError::MaxSizeExceeded);
-    }
-
-    sink.resize(total_out, 0);
-
-    Ok(sink)
+    Ok(written)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "c-brotli"))]
 mod tests {
     use super::*;
 
@@ -238,4 +322,31 @@ mod tests {
             shared_brotli_decode(&patch, None, 10)
         );
     }
+
+    #[test]
+    fn brotli_decode_streaming() {
+        let mut decoded = vec![];
+        assert_eq!(
+            Ok(TARGET.len()),
+            shared_brotli_decode_streaming(
+                &SHARED_DICT_PATCH,
+                Some(BASE.as_bytes()),
+                TARGET.len(),
+                |chunk| decoded.extend_from_slice(chunk)
+            )
+        );
+        assert_eq!(decoded, TARGET);
+    }
+
+    #[test]
+    fn brotli_decode_streaming_too_little_output() {
+        let mut decoded = vec![];
+        assert_eq!(
+            Err(DecodeError::MaxSizeExceeded),
+            shared_brotli_decode_streaming(&NO_DICT_PATCH, None, 10, |chunk| decoded
+                .extend_from_slice(chunk))
+        );
+        // Output stops at the max.
+        assert_eq!(decoded, &TARGET[..10]);
+    }
 }
@@ -0,0 +1,462 @@
+//! Mark and cursive attachment anchors for glyphs.
+//!
+//! See the mark and cursive attachment lookups in the
+//! [GPOS](https://learn.microsoft.com/en-us/typography/opentype/spec/gpos)
+//! table.
+
+use alloc::vec::Vec;
+use read_fonts::{
+    tables::{
+        gpos::{AnchorTable, DeviceOrVariationIndex, Gpos, MarkArray, PositionSubtables},
+        layout::CoverageTable,
+        variations::{DeltaSetIndex, ItemVariationStore},
+    },
+    types::GlyphId,
+    ReadError, TableProvider,
+};
+
+use super::instance::{LocationRef, NormalizedCoord, Size};
+
+/// The role of an anchor within its attachment lookup.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AnchorKind {
+    /// Anchor on a mark glyph that attaches to a base, ligature or
+    /// another mark.
+    Mark {
+        /// Mark class of the glyph.
+        class: u16,
+    },
+    /// Anchor on a base glyph to which marks of the given class attach.
+    Base {
+        /// Mark class that attaches at this anchor.
+        class: u16,
+    },
+    /// Anchor on a ligature component to which marks of the given class
+    /// attach.
+    Ligature {
+        /// Index of the ligature component.
+        component: u16,
+        /// Mark class that attaches at this anchor.
+        class: u16,
+    },
+    /// Anchor on a mark glyph to which other marks of the given class
+    /// attach.
+    MarkBase {
+        /// Mark class that attaches at this anchor.
+        class: u16,
+    },
+    /// Entry point for cursive attachment.
+    CursiveEntry,
+    /// Exit point for cursive attachment.
+    CursiveExit,
+}
+
+/// An attachment point defined for a glyph.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Anchor {
+    /// Index of the lookup that defines the anchor.
+    pub lookup_index: u16,
+    /// Role of the anchor in the lookup.
+    pub kind: AnchorKind,
+    /// Horizontal position of the anchor.
+    pub x: f32,
+    /// Vertical position of the anchor.
+    pub y: f32,
+}
+
+/// Mark and cursive attachment anchors from the `GPOS` table, scaled to a
+/// font size.
+///
+/// If normalized coordinates are provided and the `GDEF` table contains an
+/// item variation store, anchors with variation index tables are adjusted
+/// accordingly. Device tables for hinting adjustments and contour point
+/// indices are ignored.
+#[derive(Clone)]
+pub struct GlyphAnchors<'a> {
+    gpos: Option<Gpos<'a>>,
+    var_store: Option<ItemVariationStore<'a>>,
+    coords: &'a [NormalizedCoord],
+    scale: f32,
+}
+
+impl<'a> GlyphAnchors<'a> {
+    /// Creates new glyph anchors from the given font, size, and location in
+    /// normalized variation space.
+    pub fn new(
+        font: &impl TableProvider<'a>,
+        size: Size,
+        location: impl Into<LocationRef<'a>>,
+    ) -> Self {
+        let upem = font
+            .head()
+            .map(|head| head.units_per_em())
+            .unwrap_or_default();
+        let coords = location.into().coords();
+        let var_store = (!coords.is_empty())
+            .then(|| font.gdef().ok()?.item_var_store()?.ok())
+            .flatten();
+        Self {
+            gpos: font.gpos().ok(),
+            var_store,
+            coords,
+            scale: size.linear_scale(upem),
+        }
+    }
+
+    /// Returns all anchors defined for the given glyph, in lookup order.
+    ///
+    /// Anchors from lookups that fail to parse are omitted.
+    pub fn get(&self, glyph_id: GlyphId) -> Vec<Anchor> {
+        let mut anchors = Vec::new();
+        let Some(lookups) = self.gpos.as_ref().and_then(|gpos| gpos.lookup_list().ok()) else {
+            return anchors;
+        };
+        for (lookup_index, lookup) in lookups.lookups().iter().enumerate() {
+            let Ok(subtables) = lookup.and_then(|lookup| lookup.subtables()) else {
+                continue;
+            };
+            let mut sink = AnchorSink {
+                anchors: &mut anchors,
+                lookup_index: lookup_index as u16,
+                glyphs: self,
+            };
+            // errors are ignored so that a single malformed subtable does
+            // not hide the anchors from the rest of the font
+            let _ = sink.collect(&subtables, glyph_id);
+        }
+        anchors
+    }
+
+    fn value(&self, coord: i16, device: Option<Result<DeviceOrVariationIndex, ReadError>>) -> f32 {
+        let mut value = coord as i32;
+        if let (Some(var_store), Some(Ok(DeviceOrVariationIndex::VariationIndex(ix)))) =
+            (self.var_store.as_ref(), device)
+        {
+            let index = DeltaSetIndex {
+                outer: ix.delta_set_outer_index(),
+                inner: ix.delta_set_inner_index(),
+            };
+            value += var_store.compute_delta(index, self.coords).unwrap_or(0);
+        }
+        value as f32 * self.scale
+    }
+}
+
+struct AnchorSink<'a, 'b> {
+    anchors: &'b mut Vec<Anchor>,
+    lookup_index: u16,
+    glyphs: &'b GlyphAnchors<'a>,
+}
+
+impl AnchorSink<'_, '_> {
+    fn push(&mut self, kind: AnchorKind, anchor: AnchorTable) {
+        self.anchors.push(Anchor {
+            lookup_index: self.lookup_index,
+            kind,
+            x: self.glyphs.value(anchor.x_coordinate(), anchor.x_device()),
+            y: self.glyphs.value(anchor.y_coordinate(), anchor.y_device()),
+        });
+    }
+
+    fn collect(
+        &mut self,
+        subtables: &PositionSubtables,
+        glyph_id: GlyphId,
+    ) -> Result<(), ReadError> {
+        match subtables {
+            PositionSubtables::Cursive(subtables) => {
+                for subtable in subtables.iter() {
+                    let subtable = subtable?;
+                    let Some(index) = subtable.coverage()?.get(glyph_id) else {
+                        continue;
+                    };
+                    let data = subtable.offset_data();
+                    let record = subtable
+                        .entry_exit_record()
+                        .get(index as usize)
+                        .ok_or(ReadError::OutOfBounds)?;
+                    if let Some(anchor) = record.entry_anchor(data).transpose()? {
+                        self.push(AnchorKind::CursiveEntry, anchor);
+                    }
+                    if let Some(anchor) = record.exit_anchor(data).transpose()? {
+                        self.push(AnchorKind::CursiveExit, anchor);
+                    }
+                }
+            }
+            PositionSubtables::MarkToBase(subtables) => {
+                for subtable in subtables.iter() {
+                    let subtable = subtable?;
+                    self.collect_marks(
+                        &subtable.mark_coverage()?,
+                        subtable.mark_array(),
+                        glyph_id,
+                    )?;
+                    let Some(index) = subtable.base_coverage()?.get(glyph_id) else {
+                        continue;
+                    };
+                    let base_array = subtable.base_array()?;
+                    let record = base_array.base_records().get(index as usize)?;
+                    let anchors = record.base_anchors(base_array.offset_data());
+                    for (class, anchor) in anchors.iter().enumerate() {
+                        if let Some(anchor) = anchor.transpose()? {
+                            self.push(
+                                AnchorKind::Base {
+                                    class: class as u16,
+                                },
+                                anchor,
+                            );
+                        }
+                    }
+                }
+            }
+            PositionSubtables::MarkToLig(subtables) => {
+                for subtable in subtables.iter() {
+                    let subtable = subtable?;
+                    self.collect_marks(
+                        &subtable.mark_coverage()?,
+                        subtable.mark_array(),
+                        glyph_id,
+                    )?;
+                    let Some(index) = subtable.ligature_coverage()?.get(glyph_id) else {
+                        continue;
+                    };
+                    let ligature_attach = subtable
+                        .ligature_array()?
+                        .ligature_attaches()
+                        .get(index as usize)?;
+                    let data = ligature_attach.offset_data();
+                    for (component, record) in
+                        ligature_attach.component_records().iter().enumerate()
+                    {
+                        for (class, anchor) in record?.ligature_anchors(data).iter().enumerate() {
+                            if let Some(anchor) = anchor.transpose()? {
+                                let kind = AnchorKind::Ligature {
+                                    component: component as u16,
+                                    class: class as u16,
+                                };
+                                self.push(kind, anchor);
+                            }
+                        }
+                    }
+                }
+            }
+            PositionSubtables::MarkToMark(subtables) => {
+                for subtable in subtables.iter() {
+                    let subtable = subtable?;
+                    self.collect_marks(
+                        &subtable.mark1_coverage()?,
+                        subtable.mark1_array(),
+                        glyph_id,
+                    )?;
+                    let Some(index) = subtable.mark2_coverage()?.get(glyph_id) else {
+                        continue;
+                    };
+                    let mark2_array = subtable.mark2_array()?;
+                    let record = mark2_array.mark2_records().get(index as usize)?;
+                    let anchors = record.mark2_anchors(mark2_array.offset_data());
+                    for (class, anchor) in anchors.iter().enumerate() {
+                        if let Some(anchor) = anchor.transpose()? {
+                            self.push(
+                                AnchorKind::MarkBase {
+                                    class: class as u16,
+                                },
+                                anchor,
+                            );
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn collect_marks(
+        &mut self,
+        coverage: &CoverageTable,
+        mark_array: Result<MarkArray, ReadError>,
+        glyph_id: GlyphId,
+    ) -> Result<(), ReadError> {
+        let Some(index) = coverage.get(glyph_id) else {
+            return Ok(());
+        };
+        let mark_array = mark_array?;
+        let record = mark_array
+            .mark_records()
+            .get(index as usize)
+            .ok_or(ReadError::OutOfBounds)?;
+        let anchor = record.mark_anchor(mark_array.offset_data())?;
+        self.push(
+            AnchorKind::Mark {
+                class: record.mark_class(),
+            },
+            anchor,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MetadataProvider;
+    use read_fonts::{
+        types::{F2Dot14, GlyphId16},
+        FontRef,
+    };
+    use write_fonts::{
+        tables::{
+            gdef::Gdef,
+            gpos::{
+                AnchorTable, BaseArray, BaseRecord, CursivePosFormat1, EntryExitRecord, Gpos,
+                Mark2Array, Mark2Record, MarkArray, MarkBasePosFormat1, MarkMarkPosFormat1,
+                MarkRecord, PositionLookup, PositionLookupList,
+            },
+            head::Head,
+            layout::{
+                CoverageTable, DeviceOrVariationIndex, FeatureList, Lookup, LookupFlag, ScriptList,
+            },
+            variations::{
+                ItemVariationData, ItemVariationStore, RegionAxisCoordinates, VariationRegion,
+                VariationRegionList,
+            },
+        },
+        FontBuilder,
+    };
+
+    fn coverage(gid: u16) -> CoverageTable {
+        [GlyphId16::new(gid)].into_iter().collect()
+    }
+
+    /// Glyph 1 is a base with a variable anchor and cursive entry and exit
+    /// anchors. Glyph 2 is a mark that attaches to glyph 1 and to itself.
+    fn font_data() -> Vec<u8> {
+        let mark_to_base = MarkBasePosFormat1::new(
+            coverage(2),
+            coverage(1),
+            MarkArray::new(vec![MarkRecord::new(0, AnchorTable::format_1(100, 500))]),
+            BaseArray::new(vec![BaseRecord::new(vec![Some(AnchorTable::format_3(
+                300,
+                700,
+                Some(DeviceOrVariationIndex::variation_index(0, 0)),
+                None,
+            ))])]),
+        );
+        let cursive = CursivePosFormat1::new(
+            coverage(1),
+            vec![EntryExitRecord::new(
+                Some(AnchorTable::format_1(0, 0)),
+                Some(AnchorTable::format_1(500, 10)),
+            )],
+        );
+        let mark_to_mark = MarkMarkPosFormat1::new(
+            coverage(2),
+            coverage(2),
+            MarkArray::new(vec![MarkRecord::new(0, AnchorTable::format_1(10, 20))]),
+            Mark2Array::new(vec![Mark2Record::new(vec![Some(AnchorTable::format_1(
+                30, 40,
+            ))])]),
+        );
+        let lookups = vec![
+            PositionLookup::MarkToBase(Lookup::new(LookupFlag::empty(), vec![mark_to_base])),
+            PositionLookup::Cursive(Lookup::new(LookupFlag::empty(), vec![cursive])),
+            PositionLookup::MarkToMark(Lookup::new(LookupFlag::empty(), vec![mark_to_mark])),
+        ];
+        let gpos = Gpos::new(
+            ScriptList::default(),
+            FeatureList::default(),
+            PositionLookupList::new(lookups),
+        );
+        let var_store = ItemVariationStore::new(
+            VariationRegionList::new(
+                1,
+                vec![VariationRegion::new(vec![RegionAxisCoordinates::new(
+                    F2Dot14::ZERO,
+                    F2Dot14::ONE,
+                    F2Dot14::ONE,
+                )])],
+            ),
+            vec![Some(ItemVariationData::new(1, 0, vec![0], vec![100]))],
+        );
+        let gdef = Gdef {
+            item_var_store: var_store.into(),
+            ..Gdef::new(None, None, None, None)
+        };
+        let head = Head {
+            units_per_em: 1000,
+            ..Default::default()
+        };
+        FontBuilder::new()
+            .add_table(&head)
+            .unwrap()
+            .add_table(&gdef)
+            .unwrap()
+            .add_table(&gpos)
+            .unwrap()
+            .build()
+    }
+
+    fn anchor(lookup_index: u16, kind: AnchorKind, x: f32, y: f32) -> Anchor {
+        Anchor {
+            lookup_index,
+            kind,
+            x,
+            y,
+        }
+    }
+
+    #[test]
+    fn base_anchors() {
+        let data = font_data();
+        let font = FontRef::new(&data).unwrap();
+        let anchors = font
+            .glyph_anchors(Size::unscaled(), LocationRef::default())
+            .get(GlyphId::new(1));
+        assert_eq!(
+            anchors,
+            [
+                anchor(0, AnchorKind::Base { class: 0 }, 300.0, 700.0),
+                anchor(1, AnchorKind::CursiveEntry, 0.0, 0.0),
+                anchor(1, AnchorKind::CursiveExit, 500.0, 10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn mark_anchors() {
+        let data = font_data();
+        let font = FontRef::new(&data).unwrap();
+        let anchors = font
+            .glyph_anchors(Size::unscaled(), LocationRef::default())
+            .get(GlyphId::new(2));
+        assert_eq!(
+            anchors,
+            [
+                anchor(0, AnchorKind::Mark { class: 0 }, 100.0, 500.0),
+                anchor(2, AnchorKind::Mark { class: 0 }, 10.0, 20.0),
+                anchor(2, AnchorKind::MarkBase { class: 0 }, 30.0, 40.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn scaled_variable_anchors() {
+        let data = font_data();
+        let font = FontRef::new(&data).unwrap();
+        let coords = [F2Dot14::from_f32(0.5)];
+        let anchors = font
+            .glyph_anchors(Size::new(2000.0), coords.as_slice())
+            .get(GlyphId::new(1));
+        assert_eq!(
+            anchors[0],
+            anchor(0, AnchorKind::Base { class: 0 }, 700.0, 1400.0)
+        );
+    }
+
+    #[test]
+    fn no_gpos() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let anchors = font.glyph_anchors(Size::unscaled(), LocationRef::default());
+        assert!(anchors.get(GlyphId::new(1)).is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 //! Primary attributes typically used for font classification and selection.
 
+use alloc::string::String;
 use read_fonts::{
     tables::{
         head::{Head, MacStyle},
@@ -33,8 +34,9 @@ impl Attributes {
     pub fn new<'a>(font: &impl TableProvider<'a>) -> Self {
         if let Ok(os2) = font.os2() {
             // Prefer values from the OS/2 table if it exists. We also use
-            // the post table to extract the angle for oblique styles.
-            Self::from_os2_post(os2, font.post().ok())
+            // the post table to extract the angle for oblique styles and
+            // the head table as a fallback for missing style and weight.
+            Self::from_os2_post_head(os2, font.post().ok(), font.head().ok())
         } else if let Ok(head) = font.head() {
             // Otherwise, fall back to the macStyle field of the head table.
             Self::from_head(head)
@@ -43,7 +45,8 @@ impl Attributes {
         }
     }
 
-    fn from_os2_post(os2: Os2, post: Option<Post>) -> Self {
+    fn from_os2_post_head(os2: Os2, post: Option<Post>, head: Option<Head>) -> Self {
+        let mac_style = head.map(|head| head.mac_style()).unwrap_or_default();
         let stretch = Stretch::from_width_class(os2.us_width_class());
         // Bits 1 and 9 of the fsSelection field signify italic and
         // oblique, respectively.
@@ -54,6 +57,8 @@ impl Attributes {
         } else if fs_selection.contains(SelectionFlags::OBLIQUE) {
             let angle = post.map(|post| post.italic_angle().to_f64() as f32);
             Style::Oblique(angle)
+        } else if mac_style.contains(MacStyle::ITALIC) {
+            Style::Italic
         } else {
             Style::Normal
         };
@@ -61,7 +66,17 @@ impl Attributes {
         // we don't clamp here because variable fonts could potentially
         // have a value outside of that range.
         // See <https://learn.microsoft.com/en-us/typography/opentype/spec/os2#usweightclass>
-        let weight = Weight(os2.us_weight_class() as f32);
+        let weight = match os2.us_weight_class() {
+            // A value of 0 is invalid so fall back to the bold flags
+            // in fsSelection and macStyle.
+            0 if fs_selection.contains(SelectionFlags::BOLD)
+                || mac_style.contains(MacStyle::BOLD) =>
+            {
+                Weight::BOLD
+            }
+            0 => Weight::NORMAL,
+            weight_class => Weight(weight_class as f32),
+        };
         Self {
             stretch,
             style,
@@ -69,6 +84,35 @@ impl Attributes {
         }
     }
 
+    /// Synthesizes a style name from the attributes.
+    ///
+    /// This is useful as a fallback for fonts that lack subfamily names
+    /// and produces names such as "Regular", "Bold Italic" or
+    /// "Condensed Light Oblique" where the width, weight and slope
+    /// components are omitted when they are normal.
+    pub fn style_name(&self) -> String {
+        let mut name = String::new();
+        let components = [
+            self.stretch.name(),
+            self.weight.name(),
+            match self.style {
+                Style::Normal => None,
+                Style::Italic => Some("Italic"),
+                Style::Oblique(_) => Some("Oblique"),
+            },
+        ];
+        for component in components.into_iter().flatten() {
+            if !name.is_empty() {
+                name.push(' ');
+            }
+            name.push_str(component);
+        }
+        if name.is_empty() {
+            name.push_str("Regular");
+        }
+        name
+    }
+
     fn from_head(head: Head) -> Self {
         let mac_style = head.mac_style();
         let style = mac_style
@@ -158,6 +202,22 @@ impl Stretch {
         self.0
     }
 
+    /// Returns the name of the nearest predefined width or `None` for
+    /// normal width.
+    fn name(self) -> Option<&'static str> {
+        Some(match self.0 {
+            r if r < 0.5625 => "Ultra Condensed",
+            r if r < 0.6875 => "Extra Condensed",
+            r if r < 0.8125 => "Condensed",
+            r if r < 0.9375 => "Semi Condensed",
+            r if r < 1.0625 => return None,
+            r if r < 1.1875 => "Semi Expanded",
+            r if r < 1.375 => "Expanded",
+            r if r < 1.75 => "Extra Expanded",
+            _ => "Ultra Expanded",
+        })
+    }
+
     /// Returns the stretch attribute as a percentage value.
     ///
     /// This is generally the value associated with the `wdth` axis.
@@ -244,6 +304,24 @@ impl Weight {
     pub const fn value(self) -> f32 {
         self.0
     }
+
+    /// Returns the name of the nearest predefined weight or `None` for
+    /// normal weight.
+    fn name(self) -> Option<&'static str> {
+        Some(match self.0 {
+            w if w < 150.0 => "Thin",
+            w if w < 250.0 => "Extra Light",
+            w if w < 325.0 => "Light",
+            w if w < 375.0 => "Semi Light",
+            w if w < 450.0 => return None,
+            w if w < 550.0 => "Medium",
+            w if w < 650.0 => "Semi Bold",
+            w if w < 750.0 => "Bold",
+            w if w < 850.0 => "Extra Bold",
+            w if w < 925.0 => "Black",
+            _ => "Extra Black",
+        })
+    }
 }
 
 impl Default for Weight {
@@ -274,4 +352,43 @@ mod tests {
         assert_eq!(attrs.style, Style::Oblique(Some(-14.0)));
         assert_eq!(attrs.weight, Weight::EXTRA_BOLD);
     }
+
+    #[test]
+    fn style_names() {
+        let font = FontRef::new(font_test_data::CMAP14_FONT1).unwrap();
+        assert_eq!(
+            font.attributes().style_name(),
+            "Semi Condensed Extra Bold Oblique"
+        );
+        let font = FontRef::new(font_test_data::CMAP12_FONT1).unwrap();
+        assert_eq!(font.attributes().style_name(), "Bold Italic");
+        assert_eq!(Attributes::default().style_name(), "Regular");
+        let attrs = Attributes {
+            stretch: Stretch::EXPANDED,
+            style: Style::Normal,
+            weight: Weight::new(420.0),
+        };
+        assert_eq!(attrs.style_name(), "Expanded");
+    }
+
+    #[test]
+    fn missing_weight_class() {
+        use write_fonts::{tables::os2::Os2 as WriteOs2, FontBuilder};
+        let build_font = |fs_selection| {
+            let os2 = WriteOs2 {
+                us_weight_class: 0,
+                us_width_class: 5,
+                fs_selection,
+                ..Default::default()
+            };
+            FontBuilder::new().add_table(&os2).unwrap().build()
+        };
+        let data = build_font(SelectionFlags::BOLD);
+        let attrs = FontRef::new(&data).unwrap().attributes();
+        assert_eq!(attrs.weight, Weight::BOLD);
+        let data = build_font(SelectionFlags::REGULAR);
+        let attrs = FontRef::new(&data).unwrap().attributes();
+        assert_eq!(attrs.weight, Weight::NORMAL);
+        assert_eq!(attrs.style, Style::Normal);
+    }
 }
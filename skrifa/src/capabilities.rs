@@ -0,0 +1,253 @@
+//! Summary of the functionality supported by a font.
+
+use read_fonts::{FontRef, TableProvider};
+
+use crate::{
+    charmap::Charmap, outline::OutlineGlyphFormat, variation::AxisCollection,
+    OutlineGlyphCollection,
+};
+
+/// Summary of the functionality that a font is able to provide.
+///
+/// Fonts that are missing core tables remain usable: each entry point
+/// degrades to a documented default rather than failing. This type makes
+/// those cases visible up front so that callers can decide whether a font
+/// is suitable without probing each entry point for missing data.
+///
+/// The defaults for degenerate fonts are:
+///
+/// * missing `maxp` or `maxp.numGlyphs == 0`: [`glyph_count`](Self::glyph_count)
+///   is zero and all per-glyph queries in [`GlyphMetrics`](crate::metrics::GlyphMetrics)
+///   and [`OutlineGlyphCollection`] return `None`.
+/// * missing `head`: units per em is zero and
+///   [`Metrics`](crate::metrics::Metrics) are reported in font units.
+/// * missing `hhea` or `hmtx`: advance widths and side bearings are zero
+///   and no outlines are available.
+/// * missing `cmap`: [`Charmap`] maps every character to `None`.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct Capabilities {
+    /// Number of glyphs in the font, as reported by the `maxp` table.
+    pub glyph_count: u32,
+    /// True if the font contains a `head` table with a non-zero units
+    /// per em value.
+    pub has_units_per_em: bool,
+    /// True if a supported Unicode character mapping is available.
+    pub has_charmap: bool,
+    /// True if a Unicode variation sequence mapping is available.
+    pub has_variant_charmap: bool,
+    /// True if the font contains horizontal glyph metrics.
+    pub has_horizontal_metrics: bool,
+    /// True if the font contains vertical glyph metrics.
+    pub has_vertical_metrics: bool,
+    /// Format of the scalable outlines, if any.
+    pub outline_format: Option<OutlineGlyphFormat>,
+    /// True if the font contains variation axes.
+    pub is_variable: bool,
+}
+
+impl Capabilities {
+    /// Determines the capabilities of the given font.
+    pub fn new(font: &FontRef) -> Self {
+        let charmap = Charmap::new(font);
+        Self {
+            glyph_count: font
+                .maxp()
+                .map(|maxp| maxp.num_glyphs() as u32)
+                .unwrap_or_default(),
+            has_units_per_em: font
+                .head()
+                .map(|head| head.units_per_em() != 0)
+                .unwrap_or_default(),
+            has_charmap: charmap.has_map(),
+            has_variant_charmap: charmap.has_variant_map(),
+            has_horizontal_metrics: font.hmtx().is_ok(),
+            has_vertical_metrics: font.vmtx().is_ok(),
+            outline_format: OutlineGlyphCollection::new(font).format(),
+            is_variable: !AxisCollection::new(font).is_empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instance::{LocationRef, Size},
+        outline::{pen::NullPen, DrawSettings},
+        string::StringId,
+        GlyphId, MetadataProvider,
+    };
+    use read_fonts::types::Tag;
+    use write_fonts::{
+        tables::{head::Head, hhea::Hhea, hmtx::Hmtx, maxp::Maxp},
+        FontBuilder,
+    };
+
+    fn head() -> Head {
+        Head {
+            units_per_em: 1000,
+            ..Default::default()
+        }
+    }
+
+    fn maxp(num_glyphs: u16) -> Maxp {
+        Maxp {
+            num_glyphs,
+            ..Default::default()
+        }
+    }
+
+    /// Checks that all entry points produce their documented defaults for
+    /// a font without any glyphs.
+    fn check_no_glyphs(font: &FontRef) {
+        let gid = GlyphId::new(0);
+        let glyph_metrics = font.glyph_metrics(Size::unscaled(), LocationRef::default());
+        assert_eq!(glyph_metrics.glyph_count(), 0);
+        assert_eq!(glyph_metrics.advance_width(gid), None);
+        assert_eq!(glyph_metrics.left_side_bearing(gid), None);
+        let outlines = font.outline_glyphs();
+        assert_eq!(outlines.glyph_count(), 0);
+        assert!(outlines.get(gid).is_none());
+        assert_eq!(outlines.iter().count(), 0);
+        let charmap = font.charmap();
+        assert!(!charmap.has_map());
+        assert_eq!(charmap.map('A'), None);
+        assert_eq!(charmap.mappings().count(), 0);
+    }
+
+    #[test]
+    fn empty_font() {
+        let data = FontBuilder::new().build();
+        let font = FontRef::new(&data).unwrap();
+        assert_eq!(font.capabilities(), Capabilities::default());
+        check_no_glyphs(&font);
+        let metrics = font.metrics(Size::new(16.0), LocationRef::default());
+        assert_eq!(metrics.units_per_em, 0);
+        assert_eq!(metrics.ascent, 0.0);
+    }
+
+    #[test]
+    fn zero_glyphs() {
+        let mut builder = FontBuilder::new();
+        builder.add_table(&head()).unwrap();
+        builder.add_table(&maxp(0)).unwrap();
+        builder
+            .add_table(&Hhea {
+                number_of_long_metrics: 0,
+                ..Default::default()
+            })
+            .unwrap();
+        builder.add_table(&Hmtx::default()).unwrap();
+        builder.add_raw(Tag::new(b"glyf"), vec![]);
+        builder.add_raw(Tag::new(b"loca"), vec![0u8; 2]);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let capabilities = font.capabilities();
+        assert_eq!(
+            capabilities,
+            Capabilities {
+                glyph_count: 0,
+                has_units_per_em: true,
+                has_horizontal_metrics: true,
+                outline_format: Some(OutlineGlyphFormat::Glyf),
+                ..Default::default()
+            }
+        );
+        check_no_glyphs(&font);
+    }
+
+    #[test]
+    fn missing_hmtx() {
+        let mut builder = FontBuilder::new();
+        builder.add_table(&head()).unwrap();
+        builder.add_table(&maxp(2)).unwrap();
+        builder.add_raw(Tag::new(b"glyf"), vec![]);
+        builder.add_raw(Tag::new(b"loca"), vec![0u8; 6]);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let capabilities = font.capabilities();
+        assert_eq!(capabilities.glyph_count, 2);
+        assert!(!capabilities.has_horizontal_metrics);
+        // Outlines require horizontal metrics
+        assert_eq!(capabilities.outline_format, None);
+        assert!(font.outline_glyphs().get(GlyphId::new(1)).is_none());
+        // Glyph metrics default to zero
+        let glyph_metrics = font.glyph_metrics(Size::unscaled(), LocationRef::default());
+        assert_eq!(glyph_metrics.advance_width(GlyphId::new(1)), Some(0.0));
+        assert_eq!(glyph_metrics.left_side_bearing(GlyphId::new(1)), Some(0.0));
+        assert_eq!(glyph_metrics.advance_width(GlyphId::new(2)), None);
+    }
+
+    #[test]
+    fn glyph_count_bounds_outlines() {
+        for (font_data, format) in [
+            (font_test_data::VAZIRMATN_VAR, OutlineGlyphFormat::Glyf),
+            (
+                font_test_data::NOTO_SERIF_DISPLAY_TRIMMED,
+                OutlineGlyphFormat::Cff,
+            ),
+        ] {
+            let font = FontRef::new(font_data).unwrap();
+            let capabilities = font.capabilities();
+            assert!(capabilities.glyph_count > 0);
+            assert!(capabilities.has_charmap);
+            assert_eq!(capabilities.outline_format, Some(format));
+            let outlines = font.outline_glyphs();
+            assert_eq!(outlines.glyph_count(), capabilities.glyph_count);
+            let last = GlyphId::new(capabilities.glyph_count - 1);
+            assert!(outlines.get(last).is_some());
+            assert!(outlines
+                .get(GlyphId::new(capabilities.glyph_count))
+                .is_none());
+        }
+    }
+
+    /// Runs every entry point over each of the shared malformed test cases
+    /// to ensure that bad data degrades gracefully rather than panicking.
+    #[test]
+    fn malformed_fonts() {
+        for case in font_test_data::malformed::CASES {
+            let data = case.font_data();
+            let font = FontRef::new(&data).unwrap();
+            let capabilities = font.capabilities();
+            assert_eq!(capabilities.glyph_count, 4, "{}", case.name);
+            let size = Size::new(16.0);
+            let _ = font.metrics(size, LocationRef::default());
+            let glyph_metrics = font.glyph_metrics(size, LocationRef::default());
+            let charmap = font.charmap();
+            let _ = charmap.mappings().count();
+            let outlines = font.outline_glyphs();
+            for gid in 0..=capabilities.glyph_count {
+                let gid = GlyphId::new(gid);
+                let _ = glyph_metrics.advance_width(gid);
+                let _ = glyph_metrics.bounds(gid);
+                if let Some(glyph) = outlines.get(gid) {
+                    let _ = glyph.draw(
+                        DrawSettings::unhinted(size, LocationRef::default()),
+                        &mut NullPen,
+                    );
+                }
+            }
+            for ch in ['A', 'D'] {
+                let _ = charmap.map(ch);
+            }
+            for string in font.localized_strings(StringId::FAMILY_NAME) {
+                let _ = string.to_string();
+            }
+        }
+    }
+
+    #[test]
+    fn malformed_glyf_errors() {
+        let case = font_test_data::malformed::get("glyf_loca_past_end").unwrap();
+        let data = case.font_data();
+        let font = FontRef::new(&data).unwrap();
+        let outlines = font.outline_glyphs();
+        let settings = || DrawSettings::unhinted(Size::unscaled(), LocationRef::default());
+        let mut pen = NullPen;
+        let glyph = outlines.get(GlyphId::new(0)).unwrap();
+        glyph.draw(settings(), &mut pen).unwrap();
+        // Glyph data past the end of the table is treated as missing
+        assert!(outlines.get(GlyphId::new(2)).is_none());
+    }
+}
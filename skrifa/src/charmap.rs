@@ -16,8 +16,8 @@
 
 use read_fonts::{
     tables::cmap::{
-        self, Cmap, Cmap12, Cmap12Iter, Cmap14, Cmap14Iter, Cmap4, Cmap4Iter, CmapSubtable,
-        EncodingRecord, PlatformId,
+        self, Cmap, Cmap12, Cmap12Iter, Cmap13, Cmap13Iter, Cmap14, Cmap14Iter, Cmap4, Cmap4Iter,
+        CmapSubtable, EncodingRecord, PlatformId,
     },
     types::GlyphId,
     FontData, TableProvider,
@@ -25,6 +25,8 @@ use read_fonts::{
 
 pub use read_fonts::tables::cmap::MapVariant;
 
+use alloc::vec::Vec;
+
 /// Mapping of characters to nominal glyph identifiers.
 ///
 /// The mappings are derived from the [cmap](https://learn.microsoft.com/en-us/typography/opentype/spec/cmap)
@@ -43,8 +45,9 @@ pub use read_fonts::tables::cmap::MapVariant;
 /// * Unicode characters: a symbol mapping subtable is selected if available. Otherwise, subtables supporting
 ///   the Unicode full repertoire or Basic Multilingual Plane (BMP) are preferred, in that order. Formats
 ///   [4](https://learn.microsoft.com/en-us/typography/opentype/spec/cmap#format-4-segment-mapping-to-delta-values)
-///   and [12](https://learn.microsoft.com/en-us/typography/opentype/spec/cmap#format-12-segmented-coverage) are
-///   supported.
+///   [12](https://learn.microsoft.com/en-us/typography/opentype/spec/cmap#format-12-segmented-coverage) and
+///   [13](https://learn.microsoft.com/en-us/typography/opentype/spec/cmap#format-13-many-to-one-range-mappings)
+///   are supported. The latter is generally only found in last resort fonts.
 ///
 /// * Unicode variation sequences: these are provided by a format
 ///   [14](https://learn.microsoft.com/en-us/typography/opentype/spec/cmap#format-14-unicode-variation-sequences)
@@ -58,6 +61,9 @@ pub struct Charmap<'a> {
 
 impl<'a> Charmap<'a> {
     /// Creates a new character map from the given font.
+    ///
+    /// If the font does not contain a `cmap` table, the result is an empty
+    /// map where all lookups return `None`.
     pub fn new(font: &impl TableProvider<'a>) -> Self {
         let Ok(cmap) = font.cmap() else {
             return Default::default();
@@ -108,6 +114,7 @@ impl<'a> Charmap<'a> {
                 Mappings(match &subtable.subtable {
                     SupportedSubtable::Format4(cmap4) => MappingsInner::Format4(cmap4.iter()),
                     SupportedSubtable::Format12(cmap12) => MappingsInner::Format12(cmap12.iter()),
+                    SupportedSubtable::Format13(cmap13) => MappingsInner::Format13(cmap13.iter()),
                 })
             })
             .unwrap_or(Mappings(MappingsInner::None))
@@ -125,6 +132,15 @@ impl<'a> Charmap<'a> {
     pub fn variant_mappings(&self) -> VariantMappings<'a> {
         VariantMappings(self.variant_subtable.clone().map(|cmap14| cmap14.iter()))
     }
+
+    /// Builds a reverse mapping from nominal glyph identifiers to the
+    /// characters that map to them.
+    ///
+    /// This walks the full character map so the result should be retained
+    /// when performing many lookups.
+    pub fn reverse(&self) -> ReverseCharmap {
+        ReverseCharmap::new(self.mappings())
+    }
 }
 
 /// Cacheable indices of selected mapping tables for materializing a character
@@ -199,6 +215,7 @@ impl Iterator for Mappings<'_> {
             MappingsInner::None => None,
             MappingsInner::Format4(iter) => iter.next(),
             MappingsInner::Format12(iter) => iter.next(),
+            MappingsInner::Format13(iter) => iter.next(),
         }
     }
 }
@@ -208,6 +225,7 @@ enum MappingsInner<'a> {
     None,
     Format4(Cmap4Iter<'a>),
     Format12(Cmap12Iter<'a>),
+    Format13(Cmap13Iter<'a>),
 }
 
 /// Iterator over all mappings of character and variation selector to
@@ -225,6 +243,63 @@ impl Iterator for VariantMappings<'_> {
     }
 }
 
+/// Mapping of nominal glyph identifiers to the characters that map to them.
+///
+/// Multiple characters may map to the same glyph so each glyph may have
+/// any number of associated characters.
+///
+/// This is created with the [`Charmap::reverse`] method.
+#[derive(Clone, Default, Debug)]
+pub struct ReverseCharmap {
+    /// Mappings sorted by glyph identifier and then by character.
+    mappings: Vec<(GlyphId, u32)>,
+}
+
+impl ReverseCharmap {
+    fn new(mappings: Mappings) -> Self {
+        let mut mappings: Vec<_> = mappings.map(|(ch, gid)| (gid, ch)).collect();
+        mappings.sort_unstable();
+        mappings.dedup();
+        Self { mappings }
+    }
+
+    /// Returns an iterator over all characters, in ascending order, which
+    /// map to the given glyph.
+    pub fn codepoints(&self, glyph_id: GlyphId) -> impl Iterator<Item = u32> + '_ + Clone {
+        let start = self.mappings.partition_point(|(gid, _)| *gid < glyph_id);
+        self.mappings[start..]
+            .iter()
+            .take_while(move |(gid, _)| *gid == glyph_id)
+            .map(|(_, ch)| *ch)
+    }
+
+    /// Returns the smallest character which maps to the given glyph.
+    pub fn first_codepoint(&self, glyph_id: GlyphId) -> Option<u32> {
+        self.codepoints(glyph_id).next()
+    }
+
+    /// Returns true if at least one character maps to the given glyph.
+    pub fn contains(&self, glyph_id: GlyphId) -> bool {
+        self.first_codepoint(glyph_id).is_some()
+    }
+
+    /// Returns an iterator over all mappings of glyph identifier to
+    /// character, sorted by glyph identifier and then by character.
+    pub fn mappings(&self) -> impl Iterator<Item = (GlyphId, u32)> + '_ + Clone {
+        self.mappings.iter().copied()
+    }
+
+    /// Returns the number of glyph to character mappings.
+    pub fn len(&self) -> usize {
+        self.mappings.len()
+    }
+
+    /// Returns true if there are no mappings.
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+}
+
 fn get_subtable<'a>(
     data: FontData<'a>,
     records: &[EncodingRecord],
@@ -264,6 +339,7 @@ impl CodepointSubtable<'_> {
         match &self.subtable {
             SupportedSubtable::Format4(subtable) => subtable.map_codepoint(codepoint),
             SupportedSubtable::Format12(subtable) => subtable.map_codepoint(codepoint),
+            SupportedSubtable::Format13(subtable) => subtable.map_codepoint(codepoint),
         }
     }
 }
@@ -272,6 +348,7 @@ impl CodepointSubtable<'_> {
 enum SupportedSubtable<'a> {
     Format4(Cmap4<'a>),
     Format12(Cmap12<'a>),
+    Format13(Cmap13<'a>),
 }
 
 impl<'a> SupportedSubtable<'a> {
@@ -279,6 +356,7 @@ impl<'a> SupportedSubtable<'a> {
         Some(match subtable {
             CmapSubtable::Format4(cmap4) => Self::Format4(cmap4),
             CmapSubtable::Format12(cmap12) => Self::Format12(cmap12),
+            CmapSubtable::Format13(cmap13) => Self::Format13(cmap13),
             _ => return None,
         })
     }
@@ -324,6 +402,7 @@ impl<'a> MappingSelection<'a> {
         const ENCODING_MS_UNICODE_CS: u16 = 1;
         const ENCODING_APPLE_ID_UNICODE_32: u16 = 4;
         const ENCODING_APPLE_ID_VARIANT_SELECTOR: u16 = 5;
+        const ENCODING_APPLE_ID_UNICODE_MANY_TO_ONE: u16 = 6;
         const ENCODING_MS_ID_UCS_4: u16 = 10;
         let mut mapping_index = MappingIndex::default();
         let mut mapping_kind = MappingKind::None;
@@ -363,7 +442,8 @@ impl<'a> MappingSelection<'a> {
                     }
                 }
                 (PlatformId::Windows, ENCODING_MS_ID_UCS_4)
-                | (PlatformId::Unicode, ENCODING_APPLE_ID_UNICODE_32) => {
+                | (PlatformId::Unicode, ENCODING_APPLE_ID_UNICODE_32)
+                | (PlatformId::Unicode, ENCODING_APPLE_ID_UNICODE_MANY_TO_ONE) => {
                     // Unicode full repertoire
                     if let Some(subtable) = SupportedSubtable::from_cmap_record(cmap, record) {
                         maybe_choose_subtable(MappingKind::UnicodeFull, i, subtable);
@@ -447,6 +527,27 @@ mod tests {
         assert_eq!(charmap.map(0x102527_u32), Some(GlyphId::new(10)));
     }
 
+    #[test]
+    fn map_format_13() {
+        use write_fonts::{tables::cmap::Cmap as WriteCmap, FontBuilder};
+        let cmap = WriteCmap::from_range_mappings([
+            (0x20..=0x7F, GlyphId::new(1)),
+            (0x10000..=0x1FFFF, GlyphId::new(2)),
+        ])
+        .unwrap();
+        let font_data = FontBuilder::new().add_table(&cmap).unwrap().build();
+        let font = FontRef::new(&font_data).unwrap();
+        let charmap = font.charmap();
+        assert!(matches!(
+            charmap.codepoint_subtable.as_ref().unwrap().subtable,
+            SupportedSubtable::Format13(..)
+        ));
+        assert_eq!(charmap.map('A'), Some(GlyphId::new(1)));
+        assert_eq!(charmap.map(0x1F600_u32), Some(GlyphId::new(2)));
+        assert_eq!(charmap.map(0x80_u32), None);
+        assert_eq!(charmap.mappings().count(), 0x60 + 0x10000);
+    }
+
     #[test]
     fn map_symbol_pua() {
         let font = FontRef::new(font_test_data::CMAP4_SYMBOL_PUA).unwrap();
@@ -464,6 +565,34 @@ mod tests {
         assert_eq!(charmap.map(0xFE_u32), Some(GlyphId::new(4)));
     }
 
+    #[test]
+    fn reverse_mappings() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let charmap = font.charmap();
+        let reverse = charmap.reverse();
+        assert_eq!(reverse.len(), charmap.mappings().count());
+        for (ch, gid) in charmap.mappings() {
+            assert!(reverse.codepoints(gid).any(|rev_ch| rev_ch == ch));
+        }
+        assert_eq!(reverse.first_codepoint(GlyphId::new(1)), Some('A' as u32));
+        assert_eq!(reverse.first_codepoint(GlyphId::new(2)), Some('À' as u32));
+        assert!(!reverse.contains(GlyphId::NOTDEF));
+        assert_eq!(reverse.codepoints(GlyphId::new(1000)).count(), 0);
+    }
+
+    #[test]
+    fn reverse_format_12() {
+        let font = FontRef::new(font_test_data::CMAP12_FONT1).unwrap();
+        let charmap = font.charmap();
+        let reverse = charmap.reverse();
+        let mut sorted = reverse.mappings().collect::<Vec<_>>();
+        sorted.sort();
+        assert_eq!(sorted, reverse.mappings().collect::<Vec<_>>());
+        for (gid, ch) in reverse.mappings() {
+            assert_eq!(charmap.map(ch), Some(gid));
+        }
+    }
+
     #[test]
     fn map_variants() {
         use super::MapVariant::*;
@@ -507,4 +636,16 @@ mod tests {
             assert_eq!(charmap.map_variant(codepoint, selector), Some(variant));
         }
     }
+
+    #[test]
+    fn overlay_cmap() {
+        use read_fonts::{types::Tag, TableOverlay, TableProvider};
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let other = FontRef::new(font_test_data::CMAP12_FONT1).unwrap();
+        let overrides = [(Tag::new(b"cmap"), other.data_for_tag(Tag::new(b"cmap")))];
+        let overlay = TableOverlay::new(font.clone(), &overrides);
+        let charmap = Charmap::new(&overlay);
+        assert!(charmap.mappings().eq(other.charmap().mappings()));
+        assert!(!charmap.mappings().eq(font.charmap().mappings()));
+    }
 }
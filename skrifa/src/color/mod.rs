@@ -294,6 +294,22 @@ impl<'a> ColorGlyph<'a> {
         }
     }
 
+    /// Returns an iterator over the layers of a COLRv0 glyph, from bottom
+    /// to top.
+    ///
+    /// This is a simpler alternative to [`paint`](Self::paint) for COLRv0
+    /// glyphs, which consist only of glyph outlines filled with solid
+    /// colors. Returns `None` for COLRv1 glyphs.
+    pub fn layers(&self) -> Option<ColorLayers<'a>> {
+        match &self.root_paint_ref {
+            ColorGlyphRoot::V0Range(range) => Some(ColorLayers {
+                colr: self.colr.clone(),
+                range: range.clone(),
+            }),
+            ColorGlyphRoot::V1Paint(..) => None,
+        }
+    }
+
     /// Returns the bounding box.
     ///
     /// For COLRv1 glyphs, this is the clip box of the specified COLRv1 glyph,
@@ -379,6 +395,53 @@ impl<'a> ColorGlyph<'a> {
     }
 }
 
+/// A single layer of a COLRv0 color glyph.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ColorLayer {
+    /// Identifier of the glyph providing the outline for this layer.
+    pub glyph_id: GlyphId,
+    /// Specifies a color from the `CPAL` table.
+    ///
+    /// The value `0xFFFF` is special and requests the foreground
+    /// (text) color.
+    pub palette_index: u16,
+}
+
+impl ColorLayer {
+    /// Returns true if this layer should be filled with the foreground
+    /// color rather than a palette entry.
+    pub fn is_foreground(&self) -> bool {
+        self.palette_index == 0xFFFF
+    }
+}
+
+/// Iterator over the layers of a COLRv0 color glyph.
+///
+/// See [`ColorGlyph::layers`].
+#[derive(Clone)]
+pub struct ColorLayers<'a> {
+    colr: colr::Colr<'a>,
+    range: Range<usize>,
+}
+
+impl Iterator for ColorLayers<'_> {
+    type Item = ColorLayer;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.range.next()?;
+        let Ok((glyph_id, palette_index)) = self.colr.v0_layer(index) else {
+            // Layer records are contiguous so if one is missing, the
+            // remaining records will be as well
+            self.range = 0..0;
+            return None;
+        };
+        Some(ColorLayer {
+            glyph_id: glyph_id.into(),
+            palette_index,
+        })
+    }
+}
+
 /// Collection of color glyphs.
 #[derive(Clone)]
 pub struct ColorGlyphCollection<'a> {
@@ -440,7 +503,7 @@ mod tests {
 
     use read_fonts::{types::BoundingBox, FontRef};
 
-    use super::{Brush, ColorPainter, CompositeMode, GlyphId, Transform};
+    use super::{Brush, ColorLayer, ColorPainter, CompositeMode, GlyphId, Transform};
     use crate::color::traversal_tests::test_glyph_defs::{COLORED_CIRCLES_V0, COLORED_CIRCLES_V1};
 
     #[test]
@@ -519,6 +582,54 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn colrv0_layers() {
+        let font = FontRef::new(font_test_data::COLRV0V1).unwrap();
+        let color_glyphs = font.color_glyphs();
+        let glyph_id = font.charmap().map(COLORED_CIRCLES_V0[0]).unwrap();
+        let colrv0_glyph = color_glyphs
+            .get_with_format(glyph_id, super::ColorGlyphFormat::ColrV0)
+            .unwrap();
+        // Layers should match the fills produced by painting
+        let mut painter = LayerRecorder::default();
+        colrv0_glyph
+            .paint(LocationRef::default(), &mut painter)
+            .unwrap();
+        let layers = colrv0_glyph.layers().unwrap().collect::<Vec<_>>();
+        assert!(!layers.is_empty());
+        assert_eq!(layers, painter.0);
+        let glyph_id = font.charmap().map(COLORED_CIRCLES_V1[0]).unwrap();
+        assert!(color_glyphs.get(glyph_id).unwrap().layers().is_none());
+    }
+
+    #[derive(Default)]
+    struct LayerRecorder(Vec<ColorLayer>);
+
+    impl ColorPainter for LayerRecorder {
+        fn push_transform(&mut self, _transform: Transform) {}
+        fn pop_transform(&mut self) {}
+        fn push_clip_glyph(&mut self, _glyph: GlyphId) {}
+        fn push_clip_box(&mut self, _clip_box: BoundingBox<f32>) {}
+        fn pop_clip(&mut self) {}
+        fn fill(&mut self, _brush: Brush) {}
+        fn fill_glyph(
+            &mut self,
+            glyph_id: GlyphId,
+            _brush_transform: Option<Transform>,
+            brush: Brush,
+        ) {
+            let Brush::Solid { palette_index, .. } = brush else {
+                panic!("COLRv0 layers are always solid");
+            };
+            self.0.push(ColorLayer {
+                glyph_id,
+                palette_index,
+            });
+        }
+        fn push_layer(&mut self, _composite_mode: CompositeMode) {}
+        fn pop_layer(&mut self) {}
+    }
+
     #[test]
     fn colrv0_no_bbox_test() {
         let colr_font = font_test_data::COLRV0V1;
@@ -0,0 +1,167 @@
+//! Support for accessing glyph names.
+
+use read_fonts::{
+    tables::{cff::Cff, post::Post, postscript::Charset},
+    types::{GlyphId, GlyphId16, Version16Dot16},
+    TableProvider,
+};
+
+/// The source of glyph names in a font.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GlyphNameSource {
+    /// Names are provided by a version 1.0 or 2.0
+    /// [post](https://learn.microsoft.com/en-us/typography/opentype/spec/post)
+    /// table.
+    Post,
+    /// Names are provided by the charset of a
+    /// [CFF](https://learn.microsoft.com/en-us/typography/opentype/spec/cff)
+    /// table.
+    Cff,
+    /// The font does not contain glyph names.
+    None,
+}
+
+/// Mapping from glyph identifiers to names.
+///
+/// Names are taken from the `post` table when it is version 1.0 (the
+/// standard Macintosh glyph ordering) or version 2.0. Otherwise, the
+/// charset of the `CFF` table is used if available.
+///
+/// Both sources restrict glyph names to ASCII so names are provided as
+/// string slices. Names that are not valid ASCII are treated as missing.
+#[derive(Clone)]
+pub struct GlyphNames<'a> {
+    inner: Inner<'a>,
+}
+
+#[derive(Clone)]
+enum Inner<'a> {
+    Post(Post<'a>, u32),
+    Cff(Cff<'a>, Charset<'a>),
+    None,
+}
+
+impl<'a> GlyphNames<'a> {
+    /// Creates a new glyph name mapping for the given font.
+    pub fn new(font: &impl TableProvider<'a>) -> Self {
+        let num_glyphs = font
+            .maxp()
+            .map(|maxp| maxp.num_glyphs() as u32)
+            .unwrap_or_default();
+        if let Ok(post) = font.post() {
+            if matches!(
+                post.version(),
+                Version16Dot16::VERSION_1_0 | Version16Dot16::VERSION_2_0
+            ) {
+                return Self {
+                    inner: Inner::Post(post, num_glyphs),
+                };
+            }
+        }
+        if let Some((cff, charset)) = font
+            .cff()
+            .ok()
+            .and_then(|cff| Some((cff.clone(), cff.charset(0).ok()??)))
+        {
+            return Self {
+                inner: Inner::Cff(cff, charset),
+            };
+        }
+        Self { inner: Inner::None }
+    }
+
+    /// Returns the source of the glyph names.
+    pub fn source(&self) -> GlyphNameSource {
+        match &self.inner {
+            Inner::Post(..) => GlyphNameSource::Post,
+            Inner::Cff(..) => GlyphNameSource::Cff,
+            Inner::None => GlyphNameSource::None,
+        }
+    }
+
+    /// Returns the number of glyphs for which names may be available.
+    pub fn num_glyphs(&self) -> u32 {
+        match &self.inner {
+            Inner::Post(post, num_glyphs) => (post.num_names() as u32).min(*num_glyphs),
+            Inner::Cff(_, charset) => charset.num_glyphs(),
+            Inner::None => 0,
+        }
+    }
+
+    /// Returns the name for the given glyph identifier.
+    pub fn get(&self, glyph_id: GlyphId) -> Option<&'a str> {
+        if glyph_id.to_u32() >= self.num_glyphs() {
+            return None;
+        }
+        match &self.inner {
+            Inner::Post(post, _) => {
+                let glyph_id = GlyphId16::try_from(glyph_id).ok()?;
+                post.glyph_name(glyph_id)
+            }
+            Inner::Cff(cff, charset) => {
+                let name = cff.string(charset.string_id(glyph_id).ok()?)?;
+                name.as_bytes()
+                    .is_ascii()
+                    .then(|| core::str::from_utf8(name.as_bytes()).ok())
+                    .flatten()
+            }
+            Inner::None => None,
+        }
+    }
+
+    /// Returns the identifier of the first glyph with the given name.
+    ///
+    /// This performs a linear search over all glyph names.
+    pub fn glyph_id(&self, name: &str) -> Option<GlyphId> {
+        self.iter()
+            .find(|(_, glyph_name)| *glyph_name == name)
+            .map(|(gid, _)| gid)
+    }
+
+    /// Returns an iterator over all glyph identifiers and their names.
+    ///
+    /// Glyphs without names are skipped.
+    pub fn iter(&self) -> impl Iterator<Item = (GlyphId, &'a str)> + 'a + Clone {
+        let names = self.clone();
+        (0..self.num_glyphs()).filter_map(move |gid| {
+            let gid = GlyphId::new(gid);
+            Some((gid, names.get(gid)?))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MetadataProvider;
+    use read_fonts::FontRef;
+
+    #[test]
+    fn post_names() {
+        let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
+        let names = font.glyph_names();
+        assert_eq!(names.source(), GlyphNameSource::Post);
+        let all: Vec<_> = names.iter().map(|(_, name)| name).collect();
+        assert_eq!(all.len() as u32, names.num_glyphs());
+        // standard Macintosh names
+        assert_eq!(&all[..3], [".notdef", "period", "comma"]);
+        // custom names
+        assert_eq!(all[3], "non_uniform_scale");
+        assert_eq!(all[8], "noscaled_component_offset");
+        assert_eq!(names.glyph_id("translate_only"), Some(GlyphId::new(6)));
+        assert_eq!(names.get(GlyphId::new(9)), None);
+    }
+
+    #[test]
+    fn cff_names() {
+        let font = FontRef::new(font_test_data::NOTO_SERIF_DISPLAY_TRIMMED).unwrap();
+        let names = font.glyph_names();
+        assert_eq!(names.source(), GlyphNameSource::Cff);
+        let all: Vec<_> = names.iter().map(|(_, name)| name).collect();
+        assert_eq!(all, [".notdef", "i", "j", "k", "l"]);
+        assert_eq!(names.glyph_id("k"), Some(GlyphId::new(3)));
+        assert_eq!(names.glyph_id(".notdef"), Some(GlyphId::NOTDEF));
+        assert_eq!(names.glyph_id("m"), None);
+        assert_eq!(names.get(GlyphId::new(5)), None);
+    }
+}
@@ -28,11 +28,15 @@ extern crate alloc;
 /// Expose our "raw" underlying parser crate.
 pub extern crate read_fonts as raw;
 
+pub mod anchor;
 pub mod attribute;
+pub mod capabilities;
 pub mod charmap;
 pub mod color;
 pub mod font;
+pub mod glyph_names;
 pub mod instance;
+pub mod math;
 pub mod metrics;
 pub mod outline;
 
@@ -0,0 +1,460 @@
+//! Constants and glyph information for mathematical layout.
+//!
+//! See the [MATH](https://learn.microsoft.com/en-us/typography/opentype/spec/math)
+//! table.
+
+use read_fonts::{
+    tables::{
+        gdef::DeviceOrVariationIndex,
+        math::{self, MathGlyphInfo, MathValueRecord},
+        variations::{DeltaSetIndex, ItemVariationStore},
+    },
+    types::GlyphId,
+    FontData, TableProvider,
+};
+
+use super::instance::{LocationRef, NormalizedCoord, Size};
+
+/// Identifier for a constant in the
+/// [MathConstants](https://learn.microsoft.com/en-us/typography/opentype/spec/math#mathconstants-table)
+/// table.
+///
+/// Variants are in the order defined by the specification.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[repr(u8)]
+pub enum MathConstant {
+    /// Percentage of scaling down for level 1 superscripts and subscripts.
+    ScriptPercentScaleDown,
+    /// Percentage of scaling down for level 2 (scriptScript) superscripts and
+    /// subscripts.
+    ScriptScriptPercentScaleDown,
+    /// Minimum height required for a delimited expression to be treated as a
+    /// sub-formula.
+    DelimitedSubFormulaMinHeight,
+    /// Minimum height of n-ary operators for formulas in display mode.
+    DisplayOperatorMinHeight,
+    /// White space to be left between math formulae to ensure proper line
+    /// spacing.
+    MathLeading,
+    /// Axis height of the font.
+    AxisHeight,
+    /// Maximum (ink) height of accent base that does not require raising the
+    /// accents.
+    AccentBaseHeight,
+    /// Maximum (ink) height of accent base that does not require flattening the
+    /// accents.
+    FlattenedAccentBaseHeight,
+    /// The standard shift down applied to subscript elements.
+    SubscriptShiftDown,
+    /// Maximum allowed height of the (ink) top of subscripts that does not
+    /// require moving subscripts further down.
+    SubscriptTopMax,
+    /// Minimum allowed drop of the baseline of subscripts relative to the (ink)
+    /// bottom of the base.
+    SubscriptBaselineDropMin,
+    /// Standard shift up applied to superscript elements.
+    SuperscriptShiftUp,
+    /// Standard shift of superscripts relative to the base, in cramped style.
+    SuperscriptShiftUpCramped,
+    /// Minimum allowed height of the (ink) bottom of superscripts that does not
+    /// require moving subscripts further up.
+    SuperscriptBottomMin,
+    /// Maximum allowed drop of the baseline of superscripts relative to the
+    /// (ink) top of the base.
+    SuperscriptBaselineDropMax,
+    /// Minimum gap between the superscript and subscript ink.
+    SubSuperscriptGapMin,
+    /// The maximum level to which the (ink) bottom of superscript can be pushed
+    /// to increase the gap between superscript and subscript, before subscript
+    /// starts being moved down.
+    SuperscriptBottomMaxWithSubscript,
+    /// Extra white space to be added after each subscript and superscript.
+    SpaceAfterScript,
+    /// Minimum gap between the (ink) bottom of the upper limit, and the (ink)
+    /// top of the base operator.
+    UpperLimitGapMin,
+    /// Minimum distance between baseline of upper limit and (ink) top of the
+    /// base operator.
+    UpperLimitBaselineRiseMin,
+    /// Minimum gap between (ink) top of the lower limit, and (ink) bottom of
+    /// the base operator.
+    LowerLimitGapMin,
+    /// Minimum distance between baseline of the lower limit and (ink) bottom of
+    /// the base operator.
+    LowerLimitBaselineDropMin,
+    /// Standard shift up applied to the top element of a stack.
+    StackTopShiftUp,
+    /// Standard shift up applied to the top element of a stack in display
+    /// style.
+    StackTopDisplayStyleShiftUp,
+    /// Standard shift down applied to the bottom element of a stack.
+    StackBottomShiftDown,
+    /// Standard shift down applied to the bottom element of a stack in display
+    /// style.
+    StackBottomDisplayStyleShiftDown,
+    /// Minimum gap between (ink) bottom of the top element of a stack, and the
+    /// (ink) top of the bottom element.
+    StackGapMin,
+    /// Minimum gap between (ink) bottom of the top element of a stack, and the
+    /// (ink) top of the bottom element in display style.
+    StackDisplayStyleGapMin,
+    /// Standard shift up applied to the top element of the stretch stack.
+    StretchStackTopShiftUp,
+    /// Standard shift down applied to the bottom element of the stretch stack.
+    StretchStackBottomShiftDown,
+    /// Minimum gap between the ink of the stretched element, and the (ink)
+    /// bottom of the element above.
+    StretchStackGapAboveMin,
+    /// Minimum gap between the ink of the stretched element, and the (ink) top
+    /// of the element below.
+    StretchStackGapBelowMin,
+    /// Standard shift up applied to the numerator.
+    FractionNumeratorShiftUp,
+    /// Standard shift up applied to the numerator in display style.
+    FractionNumeratorDisplayStyleShiftUp,
+    /// Standard shift down applied to the denominator.
+    FractionDenominatorShiftDown,
+    /// Standard shift down applied to the denominator in display style.
+    FractionDenominatorDisplayStyleShiftDown,
+    /// Minimum tolerated gap between the (ink) bottom of the numerator and the
+    /// ink of the fraction bar.
+    FractionNumeratorGapMin,
+    /// Minimum tolerated gap between the (ink) bottom of the numerator and the
+    /// ink of the fraction bar in display style.
+    FractionNumDisplayStyleGapMin,
+    /// Thickness of the fraction bar.
+    FractionRuleThickness,
+    /// Minimum tolerated gap between the (ink) top of the denominator and the
+    /// ink of the fraction bar.
+    FractionDenominatorGapMin,
+    /// Minimum tolerated gap between the (ink) top of the denominator and the
+    /// ink of the fraction bar in display style.
+    FractionDenomDisplayStyleGapMin,
+    /// Horizontal distance between the top and bottom elements of a skewed
+    /// fraction.
+    SkewedFractionHorizontalGap,
+    /// Vertical distance between the ink of the top and bottom elements of a
+    /// skewed fraction.
+    SkewedFractionVerticalGap,
+    /// Distance between the overbar and the (ink) top of he base.
+    OverbarVerticalGap,
+    /// Thickness of overbar.
+    OverbarRuleThickness,
+    /// Extra white space reserved above the overbar.
+    OverbarExtraAscender,
+    /// Distance between underbar and (ink) bottom of the base.
+    UnderbarVerticalGap,
+    /// Thickness of underbar.
+    UnderbarRuleThickness,
+    /// Extra white space reserved below the underbar.
+    UnderbarExtraDescender,
+    /// Space between the (ink) top of the expression and the bar over it.
+    RadicalVerticalGap,
+    /// Space between the (ink) top of the expression and the bar over it in
+    /// display style.
+    RadicalDisplayStyleVerticalGap,
+    /// Thickness of the radical rule.
+    RadicalRuleThickness,
+    /// Extra white space reserved above the radical.
+    RadicalExtraAscender,
+    /// Extra horizontal kern before the degree of a radical, if such is
+    /// present.
+    RadicalKernBeforeDegree,
+    /// Negative kern after the degree of a radical, if such is present.
+    RadicalKernAfterDegree,
+    /// Height of the bottom of the radical degree, in proportion to the height
+    /// of the radical sign.
+    RadicalDegreeBottomRaisePercent,
+}
+
+impl MathConstant {
+    /// Returns `true` if the constant is a percentage rather than a
+    /// distance in font units.
+    ///
+    /// Percentages are not scaled.
+    pub fn is_percentage(self) -> bool {
+        matches!(
+            self,
+            Self::ScriptPercentScaleDown
+                | Self::ScriptScriptPercentScaleDown
+                | Self::RadicalDegreeBottomRaisePercent
+        )
+    }
+}
+
+/// Mathematical layout constants and glyph information, scaled to a
+/// font size.
+///
+/// If normalized coordinates are provided and the `GDEF` table contains an
+/// item variation store, values with variation index tables are adjusted
+/// accordingly. Device tables for hinting adjustments are ignored.
+#[derive(Clone)]
+pub struct MathConstants<'a> {
+    constants: Option<math::MathConstants<'a>>,
+    glyph_info: Option<MathGlyphInfo<'a>>,
+    min_connector_overlap: Option<u16>,
+    var_store: Option<ItemVariationStore<'a>>,
+    coords: &'a [NormalizedCoord],
+    scale: f32,
+}
+
+impl<'a> MathConstants<'a> {
+    /// Creates new math constants from the given font, size, and location in
+    /// normalized variation space.
+    pub fn new(
+        font: &impl TableProvider<'a>,
+        size: Size,
+        location: impl Into<LocationRef<'a>>,
+    ) -> Self {
+        let upem = font
+            .head()
+            .map(|head| head.units_per_em())
+            .unwrap_or_default();
+        let math = font.math().ok();
+        let coords = location.into().coords();
+        let var_store = (!coords.is_empty())
+            .then(|| font.gdef().ok()?.item_var_store()?.ok())
+            .flatten();
+        Self {
+            constants: math.as_ref().and_then(|math| math.math_constants().ok()),
+            glyph_info: math.as_ref().and_then(|math| math.math_glyph_info().ok()),
+            min_connector_overlap: math
+                .as_ref()
+                .and_then(|math| math.math_variants().ok())
+                .map(|variants| variants.min_connector_overlap().to_u16()),
+            var_store,
+            coords,
+            scale: size.linear_scale(upem),
+        }
+    }
+
+    /// Returns `true` if the font contains math constants.
+    pub fn is_present(&self) -> bool {
+        self.constants.is_some()
+    }
+
+    /// Returns the value of the given constant.
+    ///
+    /// Percentages are returned as is. All other values are scaled to the
+    /// font size. Returns 0 if the font does not contain math constants.
+    pub fn get(&self, constant: MathConstant) -> f32 {
+        let Some(constants) = self.constants.as_ref() else {
+            return 0.0;
+        };
+        match constant {
+            MathConstant::ScriptPercentScaleDown => constants.script_percent_scale_down() as f32,
+            MathConstant::ScriptScriptPercentScaleDown => {
+                constants.script_script_percent_scale_down() as f32
+            }
+            MathConstant::DelimitedSubFormulaMinHeight => {
+                constants.delimited_sub_formula_min_height().to_u16() as f32 * self.scale
+            }
+            MathConstant::DisplayOperatorMinHeight => {
+                constants.display_operator_min_height().to_u16() as f32 * self.scale
+            }
+            MathConstant::RadicalDegreeBottomRaisePercent => {
+                constants.radical_degree_bottom_raise_percent() as f32
+            }
+            _ => {
+                // the remaining constants are stored as value records in
+                // declaration order
+                let index = constant as usize - MathConstant::MathLeading as usize;
+                constants
+                    .math_value_records()
+                    .get(index)
+                    .map(|record| self.value(record, constants.offset_data()))
+                    .unwrap_or_default()
+            }
+        }
+    }
+
+    /// Returns the italics correction for the given glyph.
+    pub fn italics_correction(&self, glyph_id: GlyphId) -> Option<f32> {
+        let info = self
+            .glyph_info
+            .as_ref()?
+            .math_italics_correction_info()?
+            .ok()?;
+        let record = info.get(glyph_id)?;
+        Some(self.value(&record, info.offset_data()))
+    }
+
+    /// Returns the horizontal position of the top accent attachment point
+    /// for the given glyph.
+    pub fn top_accent_attachment(&self, glyph_id: GlyphId) -> Option<f32> {
+        let attachment = self
+            .glyph_info
+            .as_ref()?
+            .math_top_accent_attachment()?
+            .ok()?;
+        let record = attachment.get(glyph_id)?;
+        Some(self.value(&record, attachment.offset_data()))
+    }
+
+    /// Returns `true` if the given glyph is an extended shape.
+    pub fn is_extended_shape(&self, glyph_id: GlyphId) -> bool {
+        self.glyph_info
+            .as_ref()
+            .is_some_and(|info| info.is_extended_shape(glyph_id))
+    }
+
+    /// Returns the minimum overlap of connecting glyphs during glyph
+    /// construction.
+    pub fn min_connector_overlap(&self) -> Option<f32> {
+        Some(self.min_connector_overlap? as f32 * self.scale)
+    }
+
+    fn value(&self, record: &MathValueRecord, data: FontData<'a>) -> f32 {
+        let mut value = record.value().to_i16() as i32;
+        if let (Some(var_store), Some(Ok(DeviceOrVariationIndex::VariationIndex(ix)))) =
+            (self.var_store.as_ref(), record.device(data))
+        {
+            let index = DeltaSetIndex {
+                outer: ix.delta_set_outer_index(),
+                inner: ix.delta_set_inner_index(),
+            };
+            value += var_store.compute_delta(index, self.coords).unwrap_or(0);
+        }
+        value as f32 * self.scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MetadataProvider;
+    use read_fonts::{types::F2Dot14, FontRef};
+    use write_fonts::{
+        tables::{
+            gdef::Gdef,
+            head::Head,
+            variations::{
+                ItemVariationData, ItemVariationStore, RegionAxisCoordinates, VariationRegion,
+                VariationRegionList,
+            },
+        },
+        types::Tag,
+        FontBuilder,
+    };
+
+    fn push_u16s(data: &mut Vec<u8>, values: &[i32]) {
+        for value in values {
+            data.extend((*value as u16).to_be_bytes());
+        }
+    }
+
+    /// A MATH table where the value record for each constant is
+    /// 10 * (index + 1), with a variation index on the axis height. Glyph
+    /// 3 has an italics correction of 30 and a top accent attachment
+    /// of 150.
+    fn math_table() -> Vec<u8> {
+        const CONSTANTS_LEN: i32 = 8 + 51 * 4 + 2;
+        const GLYPH_INFO_OFFSET: i32 = 10 + CONSTANTS_LEN + 6;
+        let mut data = vec![];
+        push_u16s(
+            &mut data,
+            &[1, 0, 10, GLYPH_INFO_OFFSET, GLYPH_INFO_OFFSET + 36],
+        );
+        // MathConstants
+        push_u16s(&mut data, &[80, 60, 1500, 1300]);
+        for i in 1..=51 {
+            // device table for axis height follows the constants
+            let device_offset = if i == 2 { CONSTANTS_LEN } else { 0 };
+            push_u16s(&mut data, &[i * 10, device_offset]);
+        }
+        push_u16s(&mut data, &[60]);
+        // VariationIndex table
+        push_u16s(&mut data, &[0, 0, 0x8000]);
+        // MathGlyphInfo with italics correction and top accent attachment
+        push_u16s(&mut data, &[8, 22, 0, 0]);
+        push_u16s(&mut data, &[8, 1, 30, 0, 1, 1, 3]);
+        push_u16s(&mut data, &[8, 1, 150, 0, 1, 1, 3]);
+        // MathVariants without constructions
+        push_u16s(&mut data, &[20, 0, 0, 0, 0]);
+        data
+    }
+
+    fn font_data() -> Vec<u8> {
+        let var_store = ItemVariationStore::new(
+            VariationRegionList::new(
+                1,
+                vec![VariationRegion::new(vec![RegionAxisCoordinates::new(
+                    F2Dot14::ZERO,
+                    F2Dot14::ONE,
+                    F2Dot14::ONE,
+                )])],
+            ),
+            vec![Some(ItemVariationData::new(1, 0, vec![0], vec![100]))],
+        );
+        let gdef = Gdef {
+            item_var_store: var_store.into(),
+            ..Gdef::new(None, None, None, None)
+        };
+        let head = Head {
+            units_per_em: 1000,
+            ..Default::default()
+        };
+        FontBuilder::new()
+            .add_table(&head)
+            .unwrap()
+            .add_table(&gdef)
+            .unwrap()
+            .add_raw(Tag::new(b"MATH"), math_table())
+            .build()
+    }
+
+    #[test]
+    fn scaled_constants() {
+        let data = font_data();
+        let font = FontRef::new(&data).unwrap();
+        let unscaled = font.math_constants(Size::unscaled(), LocationRef::default());
+        assert!(unscaled.is_present());
+        assert_eq!(unscaled.get(MathConstant::ScriptPercentScaleDown), 80.0);
+        assert_eq!(unscaled.get(MathConstant::DisplayOperatorMinHeight), 1300.0);
+        assert_eq!(unscaled.get(MathConstant::MathLeading), 10.0);
+        assert_eq!(unscaled.get(MathConstant::AxisHeight), 20.0);
+        assert_eq!(unscaled.get(MathConstant::RadicalKernAfterDegree), 510.0);
+        assert_eq!(
+            unscaled.get(MathConstant::RadicalDegreeBottomRaisePercent),
+            60.0
+        );
+
+        let scaled = font.math_constants(Size::new(500.0), LocationRef::default());
+        // percentages are not scaled
+        assert_eq!(scaled.get(MathConstant::ScriptPercentScaleDown), 80.0);
+        assert_eq!(scaled.get(MathConstant::DisplayOperatorMinHeight), 650.0);
+        assert_eq!(scaled.get(MathConstant::FractionRuleThickness), 175.0);
+        assert_eq!(scaled.min_connector_overlap(), Some(10.0));
+    }
+
+    #[test]
+    fn variable_constants() {
+        let data = font_data();
+        let font = FontRef::new(&data).unwrap();
+        let coords = [NormalizedCoord::from_f32(0.5)];
+        let constants = font.math_constants(Size::unscaled(), &coords[..]);
+        assert_eq!(constants.get(MathConstant::AxisHeight), 70.0);
+        // no variation index for this constant
+        assert_eq!(constants.get(MathConstant::MathLeading), 10.0);
+    }
+
+    #[test]
+    fn glyph_info() {
+        let data = font_data();
+        let font = FontRef::new(&data).unwrap();
+        let constants = font.math_constants(Size::new(500.0), LocationRef::default());
+        assert_eq!(constants.italics_correction(GlyphId::new(3)), Some(15.0));
+        assert_eq!(constants.top_accent_attachment(GlyphId::new(3)), Some(75.0));
+        assert_eq!(constants.italics_correction(GlyphId::new(4)), None);
+        assert!(!constants.is_extended_shape(GlyphId::new(3)));
+    }
+
+    #[test]
+    fn missing_table() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let constants = font.math_constants(Size::new(10.0), LocationRef::default());
+        assert!(!constants.is_present());
+        assert_eq!(constants.get(MathConstant::AxisHeight), 0.0);
+        assert_eq!(constants.min_connector_overlap(), None);
+    }
+}
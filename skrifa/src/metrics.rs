@@ -24,9 +24,18 @@
 
 use read_fonts::{
     tables::{
-        glyf::Glyf, gvar::Gvar, hmtx::LongMetric, hvar::Hvar, loca::Loca, os2::SelectionFlags,
+        base::BaseCoord,
+        gdef::{CaretValue, DeviceOrVariationIndex, LigCaretList},
+        glyf::Glyf,
+        gvar::Gvar,
+        hhea::Hhea,
+        hmtx::LongMetric,
+        hvar::Hvar,
+        loca::Loca,
+        os2::{Os2, SelectionFlags},
+        variations::{DeltaSetIndex, ItemVariationStore},
     },
-    types::{BigEndian, Fixed, GlyphId},
+    types::{BigEndian, Fixed, GlyphId, Tag},
     TableProvider,
 };
 
@@ -47,6 +56,21 @@ pub struct Decoration {
     pub thickness: f32,
 }
 
+/// Slope and offset of the text cursor for horizontal layout.
+///
+/// The slope is specified as a `rise` over `run` ratio so a vertical
+/// caret has a rise of 1 and a run of 0.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+pub struct CaretSlope {
+    /// Rise component of the slope. This is not scaled.
+    pub rise: f32,
+    /// Run component of the slope. This is not scaled.
+    pub run: f32,
+    /// Amount by which a slanted caret should be shifted horizontally
+    /// to produce the best appearance.
+    pub offset: f32,
+}
+
 /// Metrics that apply to all glyphs in a font.
 ///
 /// These are retrieved for a specific position in the design space.
@@ -58,9 +82,15 @@ pub struct Decoration {
 /// * [OS/2](https://learn.microsoft.com/en-us/typography/opentype/spec/os2): `average_width`, `cap_height`,
 ///   `x_height`, `strikeout`, as well as the line metrics: `ascent`, `descent`, `leading` if the `USE_TYPOGRAPHIC_METRICS`
 ///   flag is set or the `hhea` line metrics are zero (the Windows metrics are used as a last resort).
-/// * [hhea](https://learn.microsoft.com/en-us/typography/opentype/spec/hhea): `max_width`, as well as the line metrics:
+/// * [hhea](https://learn.microsoft.com/en-us/typography/opentype/spec/hhea): `max_width`, `caret_slope`, as well as the line metrics:
 ///   `ascent`, `descent`, `leading` if they are non-zero and the `USE_TYPOGRAPHIC_METRICS` flag is not set in the OS/2 table
 ///
+///   The choice of line metrics described above is the default and can be
+///   changed with [`Metrics::new_with_line_metrics`].
+/// * [trak](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6trak.html): `tracking` from the
+///   horizontal track data. The size in pixels per em is used as the point size and `tracking` is `None` for
+///   unscaled metrics.
+///
 /// For variable fonts, deltas are computed using the  [MVAR](https://learn.microsoft.com/en-us/typography/opentype/spec/MVAR)
 /// table.
 #[derive(Copy, Clone, PartialEq, Default, Debug)]
@@ -95,15 +125,176 @@ pub struct Metrics {
     pub strikeout: Option<Decoration>,
     /// Union of minimum and maximum extents for all glyphs in the font.
     pub bounds: Option<BoundingBox>,
+    /// Slope and offset of the caret for horizontal layout.
+    pub caret_slope: Option<CaretSlope>,
+    /// Adjustment to the advance of each glyph for normal tracking at
+    /// this size.
+    pub tracking: Option<f32>,
+    /// Source of the `ascent`, `descent` and `leading` values or `None` if
+    /// the font does not contain line metrics.
+    pub line_metrics_source: Option<LineMetricsSource>,
+}
+
+/// Strategy for choosing the source of the line metrics.
+///
+/// Fonts often contain three sets of line metrics that disagree and text
+/// stacks on different platforms choose between them differently. This
+/// allows emulating the behavior of a particular platform.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum LineMetricsStrategy {
+    /// Use the typographic metrics from the `OS/2` table when the
+    /// `USE_TYPO_METRICS` flag is set and the `hhea` metrics otherwise.
+    ///
+    /// If the chosen metrics are zero, falls back to the typographic
+    /// metrics and then the Windows metrics. This matches FreeType.
+    #[default]
+    Auto,
+    /// Use the `hhea` metrics, ignoring the `USE_TYPO_METRICS` flag.
+    ///
+    /// If the metrics are zero, falls back to the typographic metrics and
+    /// then the Windows metrics. This matches CoreText.
+    HheaFirst,
+    /// Use the typographic metrics from the `OS/2` table, ignoring the
+    /// `USE_TYPO_METRICS` flag.
+    ///
+    /// If the metrics are zero, falls back to the `hhea` metrics and then
+    /// the Windows metrics.
+    TypoFirst,
+    /// Use the Windows metrics from the `OS/2` table with the line gap
+    /// computed from the `hhea` metrics, as done by GDI.
+    ///
+    /// If the `OS/2` table is missing, falls back to the `hhea` metrics.
+    Windows,
+}
+
+/// Source of the line metrics.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LineMetricsSource {
+    /// The `ascender`, `descender` and `lineGap` fields of the `hhea`
+    /// table.
+    Hhea,
+    /// The `sTypoAscender`, `sTypoDescender` and `sTypoLineGap` fields of
+    /// the `OS/2` table.
+    Typo,
+    /// The `usWinAscent` and `usWinDescent` fields of the `OS/2` table.
+    Win,
+}
+
+/// Unscaled line metrics along with their source.
+struct LineMetrics {
+    ascent: i32,
+    descent: i32,
+    leading: i32,
+    source: LineMetricsSource,
+}
+
+impl LineMetrics {
+    fn new(hhea: Option<&Hhea>, os2: Option<&Os2>, strategy: LineMetricsStrategy) -> Option<Self> {
+        let hhea_metrics = || {
+            hhea.map(|hhea| Self {
+                ascent: hhea.ascender().to_i16() as i32,
+                descent: hhea.descender().to_i16() as i32,
+                leading: hhea.line_gap().to_i16() as i32,
+                source: LineMetricsSource::Hhea,
+            })
+        };
+        let typo_metrics = || {
+            os2.map(|os2| Self {
+                ascent: os2.s_typo_ascender() as i32,
+                descent: os2.s_typo_descender() as i32,
+                leading: os2.s_typo_line_gap() as i32,
+                source: LineMetricsSource::Typo,
+            })
+        };
+        let win_metrics = |leading| {
+            os2.map(|os2| Self {
+                ascent: os2.us_win_ascent() as i32,
+                // Win descent is always positive while other descent values
+                // are negative. Negate it to ensure we return consistent
+                // metrics.
+                descent: -(os2.us_win_descent() as i32),
+                leading,
+                source: LineMetricsSource::Win,
+            })
+        };
+        let non_zero = |metrics: &Self| metrics.ascent != 0 || metrics.descent != 0;
+        match strategy {
+            // This is the same strategy as FreeType:
+            // 1. Use the OS/2 metrics if the table exists and the USE_TYPO_METRICS
+            //    flag is set.
+            // 2. Otherwise, use the hhea metrics.
+            // 3. If hhea metrics are zero and the OS/2 table exists:
+            //    3a. Use the typo metrics if they are non-zero
+            //    3b. Otherwise, use the win metrics
+            //
+            // See: https://github.com/freetype/freetype/blob/5c37b6406258ec0d7ab64b8619c5ea2c19e3c69a/src/sfnt/sfobjs.c#L1311
+            LineMetricsStrategy::Auto => {
+                if os2.is_some_and(|os2| {
+                    os2.fs_selection()
+                        .contains(SelectionFlags::USE_TYPO_METRICS)
+                }) {
+                    typo_metrics()
+                } else {
+                    Self::new(hhea, os2, LineMetricsStrategy::HheaFirst)
+                }
+            }
+            LineMetricsStrategy::HheaFirst => hhea_metrics()
+                .filter(non_zero)
+                .or_else(|| typo_metrics().filter(non_zero))
+                .or_else(|| win_metrics(0))
+                .or_else(hhea_metrics),
+            LineMetricsStrategy::TypoFirst => typo_metrics()
+                .filter(non_zero)
+                .or_else(|| hhea_metrics().filter(non_zero))
+                .or_else(|| win_metrics(0))
+                .or_else(hhea_metrics),
+            LineMetricsStrategy::Windows => {
+                // GDI has no line gap of its own so derive one from the
+                // hhea metrics, reduced by any extra space already included
+                // in the Windows ascent and descent.
+                // See <https://learn.microsoft.com/en-us/typography/opentype/spec/recom#baseline-to-baseline-distances>
+                let leading = match (hhea, os2) {
+                    (Some(hhea), Some(os2)) => {
+                        let hhea_height =
+                            hhea.ascender().to_i16() as i32 - hhea.descender().to_i16() as i32;
+                        let win_height = os2.us_win_ascent() as i32 + os2.us_win_descent() as i32;
+                        (hhea.line_gap().to_i16() as i32 - (win_height - hhea_height)).max(0)
+                    }
+                    _ => 0,
+                };
+                win_metrics(leading).or_else(hhea_metrics)
+            }
+        }
+    }
 }
 
 impl Metrics {
     /// Creates new metrics for the given font, size, and location in
     /// normalized variation space.
+    ///
+    /// If the font does not contain a `head` table, `units_per_em` is zero
+    /// and all metrics are reported in font units.
+    ///
+    /// Line metrics are chosen with [`LineMetricsStrategy::Auto`].
     pub fn new<'a>(
         font: &impl TableProvider<'a>,
         size: Size,
         location: impl Into<LocationRef<'a>>,
+    ) -> Self {
+        Self::new_with_line_metrics(font, size, location, LineMetricsStrategy::Auto)
+    }
+
+    /// Creates new metrics for the given font, size, and location in
+    /// normalized variation space, choosing the line metrics with the
+    /// given strategy.
+    ///
+    /// The table that provided the line metrics is recorded in the
+    /// [`line_metrics_source`](Self::line_metrics_source) field.
+    pub fn new_with_line_metrics<'a>(
+        font: &impl TableProvider<'a>,
+        size: Size,
+        location: impl Into<LocationRef<'a>>,
+        strategy: LineMetricsStrategy,
     ) -> Self {
         let head = font.head();
         let mut metrics = Metrics {
@@ -134,31 +325,14 @@ impl Metrics {
         let hhea = font.hhea();
         if let Ok(hhea) = &hhea {
             metrics.max_width = Some(hhea.advance_width_max().to_u16() as f32 * scale);
+            metrics.caret_slope = Some(CaretSlope {
+                rise: hhea.caret_slope_rise() as f32,
+                run: hhea.caret_slope_run() as f32,
+                offset: hhea.caret_offset() as f32 * scale,
+            });
         }
-        // Choosing proper line metrics is a challenge due to the changing
-        // spec, backward compatibility and broken fonts.
-        //
-        // We use the same strategy as FreeType:
-        // 1. Use the OS/2 metrics if the table exists and the USE_TYPO_METRICS
-        //    flag is set.
-        // 2. Otherwise, use the hhea metrics.
-        // 3. If hhea metrics are zero and the OS/2 table exists:
-        //    3a. Use the typo metrics if they are non-zero
-        //    3b. Otherwise, use the win metrics
-        //
-        // See: https://github.com/freetype/freetype/blob/5c37b6406258ec0d7ab64b8619c5ea2c19e3c69a/src/sfnt/sfobjs.c#L1311
         let os2 = font.os2().ok();
-        let mut used_typo_metrics = false;
         if let Some(os2) = &os2 {
-            if os2
-                .fs_selection()
-                .contains(SelectionFlags::USE_TYPO_METRICS)
-            {
-                metrics.ascent = os2.s_typo_ascender() as f32 * scale;
-                metrics.descent = os2.s_typo_descender() as f32 * scale;
-                metrics.leading = os2.s_typo_line_gap() as f32 * scale;
-                used_typo_metrics = true;
-            }
             metrics.average_width = Some(os2.x_avg_char_width() as f32 * scale);
             metrics.cap_height = os2.s_cap_height().map(|v| v as f32 * scale);
             metrics.x_height = os2.sx_height().map(|v| v as f32 * scale);
@@ -167,34 +341,30 @@ impl Metrics {
                 thickness: os2.y_strikeout_size() as f32 * scale,
             });
         }
-        if !used_typo_metrics {
-            if let Ok(hhea) = font.hhea() {
-                metrics.ascent = hhea.ascender().to_i16() as f32 * scale;
-                metrics.descent = hhea.descender().to_i16() as f32 * scale;
-                metrics.leading = hhea.line_gap().to_i16() as f32 * scale;
-            }
-            if metrics.ascent == 0.0 && metrics.descent == 0.0 {
-                if let Some(os2) = &os2 {
-                    if os2.s_typo_ascender() != 0 || os2.s_typo_descender() != 0 {
-                        metrics.ascent = os2.s_typo_ascender() as f32 * scale;
-                        metrics.descent = os2.s_typo_descender() as f32 * scale;
-                        metrics.leading = os2.s_typo_line_gap() as f32 * scale;
-                    } else {
-                        metrics.ascent = os2.us_win_ascent() as f32 * scale;
-                        // Win descent is always positive while other descent values are negative. Negate it
-                        // to ensure we return consistent metrics.
-                        metrics.descent = -(os2.us_win_descent() as f32 * scale);
-                    }
-                }
-            }
+        if let Some(line_metrics) = LineMetrics::new(hhea.as_ref().ok(), os2.as_ref(), strategy) {
+            metrics.ascent = line_metrics.ascent as f32 * scale;
+            metrics.descent = line_metrics.descent as f32 * scale;
+            metrics.leading = line_metrics.leading as f32 * scale;
+            metrics.line_metrics_source = Some(line_metrics.source);
         }
         if let (Ok(mvar), true) = (font.mvar(), !coords.is_empty()) {
             use read_fonts::tables::mvar::tags::*;
             let metric_delta =
                 |tag| mvar.metric_delta(tag, coords).unwrap_or_default().to_f64() as f32 * scale;
-            metrics.ascent += metric_delta(HASC);
-            metrics.descent += metric_delta(HDSC);
-            metrics.leading += metric_delta(HLGP);
+            // MVAR uses the same tags for both the hhea and typographic
+            // line metrics
+            match metrics.line_metrics_source {
+                Some(LineMetricsSource::Win) => {
+                    metrics.ascent += metric_delta(HCLA);
+                    metrics.descent -= metric_delta(HCLD);
+                }
+                Some(_) => {
+                    metrics.ascent += metric_delta(HASC);
+                    metrics.descent += metric_delta(HDSC);
+                    metrics.leading += metric_delta(HLGP);
+                }
+                None => {}
+            }
             if let Some(cap_height) = &mut metrics.cap_height {
                 *cap_height += metric_delta(CPHT);
             }
@@ -209,11 +379,307 @@ impl Metrics {
                 strikeout.offset += metric_delta(STRO);
                 strikeout.thickness += metric_delta(STRS);
             }
+            if let Some(caret_slope) = &mut metrics.caret_slope {
+                let unscaled_delta =
+                    |tag| mvar.metric_delta(tag, coords).unwrap_or_default().to_f64() as f32;
+                caret_slope.rise += unscaled_delta(HCRS);
+                caret_slope.run += unscaled_delta(HCRN);
+                caret_slope.offset += metric_delta(HCOF);
+            }
+        }
+        metrics.tracking = tracking(font, size, false).map(|value| value * scale);
+        metrics
+    }
+
+    /// Creates new metrics for the given font, size, and location in
+    /// normalized variation space, synthesizing line metrics if the font
+    /// does not provide them.
+    ///
+    /// This is the same as [`Metrics::new`] except that when the ascent
+    /// and descent are both zero (because the `OS/2` and `hhea` tables are
+    /// missing or zeroed), they are derived from the font bounding box in
+    /// the `head` table. If that is also unavailable, an ascent of 0.8 em and
+    /// a descent of -0.2 em are used. This matches the fallback behavior of
+    /// web browsers and avoids collapsing lines of text to zero height.
+    pub fn new_with_fallback<'a>(
+        font: &impl TableProvider<'a>,
+        size: Size,
+        location: impl Into<LocationRef<'a>>,
+    ) -> Self {
+        let mut metrics = Self::new(font, size, location);
+        metrics.synthesize_line_metrics(size);
+        metrics
+    }
+
+    fn synthesize_line_metrics(&mut self, size: Size) {
+        if self.ascent != 0.0 || self.descent != 0.0 {
+            return;
+        }
+        if let Some(bounds) = self
+            .bounds
+            .filter(|bounds| bounds.y_max > bounds.y_min && bounds.y_max > 0.0)
+        {
+            self.ascent = bounds.y_max;
+            self.descent = bounds.y_min.min(0.0);
+            return;
+        }
+        let em = if self.units_per_em != 0 {
+            self.units_per_em as f32 * size.linear_scale(self.units_per_em)
+        } else {
+            size.ppem().unwrap_or_default()
+        };
+        self.ascent = em * 0.8;
+        self.descent = em * -0.2;
+    }
+}
+
+/// Metrics that apply to all glyphs in a font for vertical layout.
+///
+/// These are retrieved for a specific position in the design space.
+///
+/// The metrics here are derived from the following tables:
+/// * [head](https://learn.microsoft.com/en-us/typography/opentype/spec/head): `units_per_em`
+/// * [vhea](https://learn.microsoft.com/en-us/typography/opentype/spec/vhea): `ascent`, `descent`, `leading`,
+///   `max_height`, `caret_slope`. If the table is missing, the ascent and descent are half of the em size on
+///   either side of the vertical center line.
+/// * [VORG](https://learn.microsoft.com/en-us/typography/opentype/spec/vorg): `default_origin_y`
+/// * [BASE](https://learn.microsoft.com/en-us/typography/opentype/spec/base): `ideographic_baseline` from the
+///   `ideo` baseline on the horizontal axis of the `hani` script (or the first script if that is not
+///   present), falling back to the typographic descender from the
+///   [OS/2](https://learn.microsoft.com/en-us/typography/opentype/spec/os2) table.
+/// * [trak](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6trak.html): `tracking` from the
+///   vertical track data, as for [`Metrics`].
+///
+/// For variable fonts, deltas are computed using the  [MVAR](https://learn.microsoft.com/en-us/typography/opentype/spec/MVAR)
+/// table and the item variation store in the `BASE` table.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+pub struct VerticalMetrics {
+    /// Number of font design units per em unit.
+    pub units_per_em: u16,
+    /// Distance from the vertical center line to the right edge of the
+    /// design space.
+    pub ascent: f32,
+    /// Distance from the vertical center line to the left edge of the
+    /// design space.
+    pub descent: f32,
+    /// Recommended additional spacing between columns.
+    pub leading: f32,
+    /// Maximum advance height of all glyphs in the font.
+    pub max_height: Option<f32>,
+    /// Default y coordinate of the vertical origin for glyphs that do not
+    /// specify one.
+    pub default_origin_y: Option<f32>,
+    /// Position of the ideographic em-box bottom baseline.
+    pub ideographic_baseline: Option<f32>,
+    /// Slope and offset of the caret for vertical layout.
+    pub caret_slope: Option<CaretSlope>,
+    /// Adjustment to the advance of each glyph for normal tracking at
+    /// this size.
+    pub tracking: Option<f32>,
+}
+
+impl VerticalMetrics {
+    /// Creates new vertical metrics for the given font, size, and location
+    /// in normalized variation space.
+    pub fn new<'a>(
+        font: &impl TableProvider<'a>,
+        size: Size,
+        location: impl Into<LocationRef<'a>>,
+    ) -> Self {
+        let mut metrics = VerticalMetrics {
+            units_per_em: font
+                .head()
+                .map(|head| head.units_per_em())
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+        let coords = location.into().coords();
+        let scale = size.linear_scale(metrics.units_per_em);
+        if let Ok(vhea) = font.vhea() {
+            metrics.ascent = vhea.ascender().to_i16() as f32 * scale;
+            metrics.descent = vhea.descender().to_i16() as f32 * scale;
+            metrics.leading = vhea.line_gap().to_i16() as f32 * scale;
+            metrics.max_height = Some(vhea.advance_height_max().to_u16() as f32 * scale);
+            metrics.caret_slope = Some(CaretSlope {
+                rise: vhea.caret_slope_rise() as f32,
+                run: vhea.caret_slope_run() as f32,
+                offset: vhea.caret_offset() as f32 * scale,
+            });
+        } else {
+            // Match HarfBuzz which centers the em on the vertical line
+            // when vertical metrics are missing
+            let half_em = metrics.units_per_em as f32 * scale / 2.0;
+            metrics.ascent = half_em;
+            metrics.descent = -half_em;
+        }
+        if let Ok(vorg) = font.vorg() {
+            metrics.default_origin_y = Some(vorg.default_vert_origin_y() as f32 * scale);
+        }
+        metrics.ideographic_baseline = ideographic_baseline(font, coords)
+            .or_else(|| Some(font.os2().ok()?.s_typo_descender() as i32))
+            .map(|baseline| baseline as f32 * scale);
+        if let (Ok(mvar), true) = (font.mvar(), !coords.is_empty()) {
+            use read_fonts::tables::mvar::tags::*;
+            let metric_delta =
+                |tag| mvar.metric_delta(tag, coords).unwrap_or_default().to_f64() as f32 * scale;
+            metrics.ascent += metric_delta(VASC);
+            metrics.descent += metric_delta(VDSC);
+            metrics.leading += metric_delta(VLGP);
+            if let Some(caret_slope) = &mut metrics.caret_slope {
+                let unscaled_delta =
+                    |tag| mvar.metric_delta(tag, coords).unwrap_or_default().to_f64() as f32;
+                caret_slope.rise += unscaled_delta(VCRS);
+                caret_slope.run += unscaled_delta(VCRN);
+                caret_slope.offset += metric_delta(VCOF);
+            }
         }
+        metrics.tracking = tracking(font, size, true).map(|value| value * scale);
         metrics
     }
 }
 
+/// Returns the unscaled value for normal tracking at the given size from the
+/// `trak` table.
+fn tracking<'a>(font: &impl TableProvider<'a>, size: Size, vertical: bool) -> Option<f32> {
+    let trak = font.trak().ok()?;
+    let track_data = if vertical { trak.vert() } else { trak.horiz() }?.ok()?;
+    track_data.tracking(trak.offset_data(), Fixed::ZERO, size.ppem()?)
+}
+
+/// Returns the unscaled `ideo` baseline from the horizontal axis of the
+/// `BASE` table.
+fn ideographic_baseline<'a>(
+    font: &impl TableProvider<'a>,
+    coords: &[NormalizedCoord],
+) -> Option<i32> {
+    let base = font.base().ok()?;
+    let axis = base.horiz_axis()?.ok()?;
+    let tag_index = axis.base_tag_list()?.ok()?.index_for_tag(IDEO)?;
+    let script_list = axis.base_script_list().ok()?;
+    let records = script_list.base_script_records();
+    let record = records
+        .iter()
+        .find(|record| record.base_script_tag() == Tag::new(b"hani"))
+        .or_else(|| records.first())?;
+    let base_values = record
+        .base_script(script_list.offset_data())
+        .ok()?
+        .base_values()?
+        .ok()?;
+    let coord = base_values.base_coords().get(tag_index as usize).ok()?;
+    let var_store = base.item_var_store().and_then(|store| store.ok());
+    Some(base_coord_value(&coord, var_store.as_ref(), coords))
+}
+
+const ROMN: Tag = Tag::new(b"romn");
+const HANG: Tag = Tag::new(b"hang");
+const IDEO: Tag = Tag::new(b"ideo");
+
+/// Baseline positions for a script in horizontal layout.
+///
+/// These are read from the horizontal axis of the
+/// [BASE](https://learn.microsoft.com/en-us/typography/opentype/spec/base)
+/// table and are expressed in font units. For variable fonts, deltas are
+/// computed using the item variation store in the same table.
+///
+/// Baselines that are not defined for the script are `None`.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+pub struct Baselines {
+    /// Tag of the default baseline for the script.
+    pub default_baseline: Option<Tag>,
+    /// Position of the roman baseline (`romn`).
+    pub roman: Option<f32>,
+    /// Position of the hanging baseline (`hang`).
+    pub hanging: Option<f32>,
+    /// Position of the ideographic em-box bottom baseline (`ideo`).
+    pub ideographic: Option<f32>,
+}
+
+impl Baselines {
+    /// Creates new baselines for the given font, script and location in
+    /// normalized variation space.
+    ///
+    /// If the font does not define baselines for the script, the values
+    /// for the `DFLT` script are used.
+    pub fn new<'a>(
+        font: &impl TableProvider<'a>,
+        script: Tag,
+        location: impl Into<LocationRef<'a>>,
+    ) -> Self {
+        Self::new_inner(font, script, location.into().coords()).unwrap_or_default()
+    }
+
+    fn new_inner<'a>(
+        font: &impl TableProvider<'a>,
+        script: Tag,
+        coords: &[NormalizedCoord],
+    ) -> Option<Self> {
+        let base = font.base().ok()?;
+        let axis = base.horiz_axis()?.ok()?;
+        let tag_list = axis.base_tag_list()?.ok()?;
+        let script_list = axis.base_script_list().ok()?;
+        let base_values = script_list
+            .base_script(script)
+            .or_else(|| script_list.base_script(Tag::new(b"DFLT")))?
+            .ok()?
+            .base_values()?
+            .ok()?;
+        let var_store = base.item_var_store().and_then(|store| store.ok());
+        let baseline = |tag| {
+            let index = tag_list.index_for_tag(tag)?;
+            let coord = base_values.base_coords().get(index as usize).ok()?;
+            Some(base_coord_value(&coord, var_store.as_ref(), coords) as f32)
+        };
+        Some(Self {
+            default_baseline: tag_list
+                .baseline_tags()
+                .get(base_values.default_baseline_index() as usize)
+                .map(|tag| tag.get()),
+            roman: baseline(ROMN),
+            hanging: baseline(HANG),
+            ideographic: baseline(IDEO),
+        })
+    }
+}
+
+/// Returns the value of a base coordinate, applying a variation delta if
+/// one is available.
+fn base_coord_value(
+    coord: &BaseCoord,
+    var_store: Option<&ItemVariationStore>,
+    coords: &[NormalizedCoord],
+) -> i32 {
+    let mut value = coord.coordinate() as i32;
+    if let (BaseCoord::Format3(coord), Some(var_store), false) =
+        (coord, var_store, coords.is_empty())
+    {
+        if let Some(Ok(DeviceOrVariationIndex::VariationIndex(ix))) = coord.device() {
+            let index = DeltaSetIndex {
+                outer: ix.delta_set_outer_index(),
+                inner: ix.delta_set_inner_index(),
+            };
+            value += var_store.compute_delta(index, coords).unwrap_or(0);
+        }
+    }
+    value
+}
+
+/// Position of a caret within a ligature glyph.
+///
+/// See the ligature caret list in the
+/// [GDEF](https://learn.microsoft.com/en-us/typography/opentype/spec/gdef#ligature-caret-list-table)
+/// table.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum LigatureCaret {
+    /// Caret at the given position along the baseline.
+    Coordinate(f32),
+    /// Caret at the position of the outline point with the given index.
+    ///
+    /// Resolving this requires the scaled (and possibly hinted) outline
+    /// of the glyph.
+    ContourPoint(u16),
+}
+
 /// Glyph specific metrics.
 #[derive(Clone)]
 pub struct GlyphMetrics<'a> {
@@ -225,12 +691,18 @@ pub struct GlyphMetrics<'a> {
     hvar: Option<Hvar<'a>>,
     gvar: Option<Gvar<'a>>,
     loca_glyf: Option<(Loca<'a>, Glyf<'a>)>,
+    lig_caret_list: Option<LigCaretList<'a>>,
+    gdef_var_store: Option<ItemVariationStore<'a>>,
     coords: &'a [NormalizedCoord],
 }
 
 impl<'a> GlyphMetrics<'a> {
     /// Creates new glyph metrics from the given font, size, and location in
     /// normalized variation space.
+    ///
+    /// The glyph count is taken from the `maxp` table and is zero if that
+    /// table is missing. If the `hmtx` table is missing, advance widths and
+    /// left side bearings are zero for all glyphs.
     pub fn new(
         font: &impl TableProvider<'a>,
         size: Size,
@@ -262,6 +734,9 @@ impl<'a> GlyphMetrics<'a> {
         } else {
             None
         };
+        let gdef = font.gdef().ok();
+        let lig_caret_list = gdef.as_ref().and_then(|gdef| gdef.lig_caret_list()?.ok());
+        let gdef_var_store = gdef.as_ref().and_then(|gdef| gdef.item_var_store()?.ok());
         Self {
             glyph_count,
             fixed_scale,
@@ -271,6 +746,8 @@ impl<'a> GlyphMetrics<'a> {
             hvar,
             gvar,
             loca_glyf,
+            lig_caret_list,
+            gdef_var_store,
             coords,
         }
     }
@@ -366,6 +843,53 @@ impl<'a> GlyphMetrics<'a> {
     }
 }
 
+impl<'a> GlyphMetrics<'a> {
+    /// Returns the caret positions within the specified ligature glyph.
+    ///
+    /// If normalized coordinates were provided when constructing glyph metrics
+    /// and the `GDEF` table contains an item variation store, applies the
+    /// appropriate deltas.
+    ///
+    /// The iterator is empty if the glyph has no ligature carets.
+    pub fn ligature_carets(&self, glyph_id: GlyphId) -> impl Iterator<Item = LigatureCaret> + 'a {
+        let lig_glyph = self.lig_caret_list.as_ref().and_then(|list| {
+            let index = list.coverage().ok()?.get(glyph_id)?;
+            list.lig_glyphs().get(index as usize).ok()
+        });
+        let fixed_scale = self.fixed_scale;
+        let var_store = self.gdef_var_store.clone();
+        let coords = self.coords;
+        lig_glyph
+            .into_iter()
+            .flat_map(|lig_glyph| lig_glyph.caret_values().iter())
+            .filter_map(move |caret| {
+                Some(match caret.ok()? {
+                    CaretValue::Format1(caret) => {
+                        LigatureCaret::Coordinate(fixed_scale.apply(caret.coordinate() as i32))
+                    }
+                    CaretValue::Format2(caret) => {
+                        LigatureCaret::ContourPoint(caret.caret_value_point_index())
+                    }
+                    CaretValue::Format3(caret) => {
+                        let mut coordinate = caret.coordinate() as i32;
+                        if let (Some(var_store), Ok(DeviceOrVariationIndex::VariationIndex(ix))) =
+                            (var_store.as_ref(), caret.device())
+                        {
+                            if !coords.is_empty() {
+                                let index = DeltaSetIndex {
+                                    outer: ix.delta_set_outer_index(),
+                                    inner: ix.delta_set_inner_index(),
+                                };
+                                coordinate += var_store.compute_delta(index, coords).unwrap_or(0);
+                            }
+                        }
+                        LigatureCaret::Coordinate(fixed_scale.apply(coordinate))
+                    }
+                })
+            })
+    }
+}
+
 impl GlyphMetrics<'_> {
     fn metric_deltas_from_gvar(&self, glyph_id: GlyphId) -> Option<[i32; 2]> {
         let (loca, glyf) = self.loca_glyf.as_ref()?;
@@ -427,6 +951,9 @@ mod tests {
                 offset: 307.0,
                 thickness: 51.0,
             }),
+            caret_slope: None,
+            tracking: None,
+            line_metrics_source: Some(LineMetricsSource::Typo),
         };
         assert_eq!(metrics, expected);
     }
@@ -455,6 +982,13 @@ mod tests {
             leading: 0.0,
             underline: None,
             strikeout: None,
+            caret_slope: Some(CaretSlope {
+                rise: 1.0,
+                run: 0.0,
+                offset: 0.0,
+            }),
+            tracking: None,
+            line_metrics_source: Some(LineMetricsSource::Hhea),
         };
         assert_eq!(metrics, expected);
     }
@@ -527,6 +1061,36 @@ mod tests {
         assert_eq!(expected, &result[..]);
     }
 
+    /// Without HVAR, advances and side bearings are varied using the gvar
+    /// deltas of the phantom points.
+    #[test]
+    fn glyph_metrics_var_from_phantom_points() {
+        let font = FontRef::new(VAZIRMATN_VAR).unwrap();
+        let coords = &[NormalizedCoord::from_f32(-0.8)];
+        let mut glyph_metrics = font.glyph_metrics(Size::unscaled(), LocationRef::new(coords));
+        glyph_metrics.hvar = None;
+        let mut default_metrics = font.glyph_metrics(Size::unscaled(), LocationRef::default());
+        default_metrics.hvar = None;
+        // same values as glyph_metrics_var, which uses HVAR
+        let expected = &[
+            (908.0, 100.0),
+            (1246.0, 29.0),
+            (1246.0, 29.0),
+            (556.0, 57.0),
+        ];
+        for (i, expected) in expected.iter().enumerate() {
+            let gid = GlyphId::new(i as u32);
+            let advance_width = glyph_metrics.advance_width(gid).unwrap();
+            let lsb = glyph_metrics.left_side_bearing(gid).unwrap();
+            assert_eq!((advance_width, lsb), *expected);
+        }
+        // and the deltas are not zero
+        assert_ne!(
+            glyph_metrics.advance_width(GlyphId::new(1)),
+            default_metrics.advance_width(GlyphId::new(1))
+        );
+    }
+
     #[test]
     fn glyph_metrics_missing_hvar() {
         let font = FontRef::new(VAZIRMATN_VAR).unwrap();
@@ -581,4 +1145,481 @@ mod tests {
             assert_eq!(scaled_advance, expected_scaled_advance);
         }
     }
+
+    #[test]
+    fn ligature_carets() {
+        use read_fonts::types::{F2Dot14, GlyphId16};
+        use write_fonts::{
+            tables::{
+                gdef::{CaretValue, Gdef, LigCaretList, LigGlyph},
+                head::Head,
+                layout::{CoverageTable, DeviceOrVariationIndex, VariationIndex},
+                maxp::Maxp,
+                variations::{
+                    ItemVariationData, ItemVariationStore, RegionAxisCoordinates, VariationRegion,
+                    VariationRegionList,
+                },
+            },
+            FontBuilder,
+        };
+        // A single region peaking at 1.0 on the first axis with a delta
+        // of 50
+        let var_store = ItemVariationStore::new(
+            VariationRegionList::new(
+                1,
+                vec![VariationRegion::new(vec![RegionAxisCoordinates::new(
+                    F2Dot14::ZERO,
+                    F2Dot14::ONE,
+                    F2Dot14::ONE,
+                )])],
+            ),
+            vec![Some(ItemVariationData::new(1, 0, vec![0], vec![50]))],
+        );
+        let lig_caret_list = LigCaretList::new(
+            CoverageTable::format_1(vec![GlyphId16::new(2)]),
+            vec![LigGlyph::new(vec![
+                CaretValue::format_1(200),
+                CaretValue::format_2(7),
+                CaretValue::format_3(
+                    400,
+                    DeviceOrVariationIndex::VariationIndex(VariationIndex::new(0, 0)),
+                ),
+            ])],
+        );
+        let gdef = Gdef {
+            item_var_store: var_store.into(),
+            ..Gdef::new(None, None, Some(lig_caret_list), None)
+        };
+        let head = Head {
+            units_per_em: 1000,
+            ..Default::default()
+        };
+        let data = FontBuilder::new()
+            .add_table(&head)
+            .unwrap()
+            .add_table(&Maxp::new(3))
+            .unwrap()
+            .add_table(&gdef)
+            .unwrap()
+            .build();
+        let font = FontRef::new(&data).unwrap();
+
+        let carets = |size, coords: &[NormalizedCoord]| {
+            font.glyph_metrics(size, coords)
+                .ligature_carets(GlyphId::new(2))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            carets(Size::unscaled(), &[]),
+            [
+                LigatureCaret::Coordinate(200.0),
+                LigatureCaret::ContourPoint(7),
+                LigatureCaret::Coordinate(400.0)
+            ]
+        );
+        assert_eq!(
+            carets(Size::new(10.0), &[NormalizedCoord::from_f32(0.5)]),
+            [
+                LigatureCaret::Coordinate(2.0),
+                LigatureCaret::ContourPoint(7),
+                LigatureCaret::Coordinate(4.25)
+            ]
+        );
+        // glyph without carets
+        assert_eq!(
+            font.glyph_metrics(Size::unscaled(), LocationRef::default())
+                .ligature_carets(GlyphId::new(1))
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn vertical_metrics() {
+        let font = FontRef::new(font_test_data::VORG).unwrap();
+        let vhea = font.vhea().unwrap();
+        let metrics = font.vertical_metrics(Size::unscaled(), LocationRef::default());
+        assert_eq!(metrics.units_per_em, font.head().unwrap().units_per_em());
+        assert_eq!(metrics.ascent, vhea.ascender().to_i16() as f32);
+        assert_eq!(metrics.descent, vhea.descender().to_i16() as f32);
+        assert_eq!(metrics.leading, vhea.line_gap().to_i16() as f32);
+        assert_eq!(
+            metrics.max_height,
+            Some(vhea.advance_height_max().to_u16() as f32)
+        );
+        assert_eq!(
+            metrics.default_origin_y,
+            Some(font.vorg().unwrap().default_vert_origin_y() as f32)
+        );
+        assert_eq!(
+            metrics.caret_slope,
+            Some(CaretSlope {
+                rise: vhea.caret_slope_rise() as f32,
+                run: vhea.caret_slope_run() as f32,
+                offset: vhea.caret_offset() as f32,
+            })
+        );
+    }
+
+    #[test]
+    fn vertical_metrics_fallbacks_and_base() {
+        use read_fonts::types::F2Dot14;
+        use write_fonts::{
+            tables::{
+                base::{
+                    Axis, Base, BaseCoord, BaseScript, BaseScriptList, BaseScriptRecord,
+                    BaseTagList, BaseValues,
+                },
+                head::Head,
+                layout::{DeviceOrVariationIndex, VariationIndex},
+                variations::{
+                    ItemVariationData, ItemVariationStore, RegionAxisCoordinates, VariationRegion,
+                    VariationRegionList,
+                },
+            },
+            FontBuilder,
+        };
+        // A single region peaking at 1.0 on the first axis with a delta
+        // of 40
+        let var_store = ItemVariationStore::new(
+            VariationRegionList::new(
+                1,
+                vec![VariationRegion::new(vec![RegionAxisCoordinates::new(
+                    F2Dot14::ZERO,
+                    F2Dot14::ONE,
+                    F2Dot14::ONE,
+                )])],
+            ),
+            vec![Some(ItemVariationData::new(1, 0, vec![0], vec![40]))],
+        );
+        let base_values = BaseValues::new(
+            1,
+            vec![
+                BaseCoord::format_1(0),
+                BaseCoord::format_3(
+                    -120,
+                    Some(DeviceOrVariationIndex::VariationIndex(VariationIndex::new(
+                        0, 0,
+                    ))),
+                ),
+            ],
+        );
+        let axis = Axis::new(
+            Some(BaseTagList::new(vec![Tag::new(b"romn"), Tag::new(b"ideo")])),
+            BaseScriptList::new(vec![
+                BaseScriptRecord::new(Tag::new(b"cyrl"), BaseScript::new(None, None, vec![])),
+                BaseScriptRecord::new(
+                    Tag::new(b"hani"),
+                    BaseScript::new(Some(base_values), None, vec![]),
+                ),
+            ]),
+        );
+        let base = Base {
+            item_var_store: var_store.into(),
+            ..Base::new(Some(axis), None)
+        };
+        let head = Head {
+            units_per_em: 1000,
+            ..Default::default()
+        };
+        let data = FontBuilder::new()
+            .add_table(&head)
+            .unwrap()
+            .add_table(&base)
+            .unwrap()
+            .build();
+        let font = FontRef::new(&data).unwrap();
+        let metrics = font.vertical_metrics(Size::unscaled(), LocationRef::default());
+        // no vhea so the em is centered on the vertical line
+        assert_eq!(metrics.ascent, 500.0);
+        assert_eq!(metrics.descent, -500.0);
+        assert_eq!(metrics.max_height, None);
+        assert_eq!(metrics.caret_slope, None);
+        assert_eq!(metrics.default_origin_y, None);
+        assert_eq!(metrics.ideographic_baseline, Some(-120.0));
+        let coords = [NormalizedCoord::from_f32(0.5)];
+        let metrics = font.vertical_metrics(Size::new(10.0), &coords[..]);
+        assert_eq!(metrics.ascent, 5.0);
+        assert_eq!(metrics.ideographic_baseline, Some(-1.0));
+    }
+
+    #[test]
+    fn baselines() {
+        use read_fonts::types::F2Dot14;
+        use write_fonts::{
+            tables::{
+                base::{
+                    Axis, Base, BaseCoord, BaseScript, BaseScriptList, BaseScriptRecord,
+                    BaseTagList, BaseValues,
+                },
+                layout::{DeviceOrVariationIndex, VariationIndex},
+                variations::{
+                    ItemVariationData, ItemVariationStore, RegionAxisCoordinates, VariationRegion,
+                    VariationRegionList,
+                },
+            },
+            FontBuilder,
+        };
+        let var_store = ItemVariationStore::new(
+            VariationRegionList::new(
+                1,
+                vec![VariationRegion::new(vec![RegionAxisCoordinates::new(
+                    F2Dot14::ZERO,
+                    F2Dot14::ONE,
+                    F2Dot14::ONE,
+                )])],
+            ),
+            vec![Some(ItemVariationData::new(1, 0, vec![0], vec![100]))],
+        );
+        // baseline tags are hang, ideo, romn
+        let dflt_values = BaseValues::new(
+            2,
+            vec![
+                BaseCoord::format_1(600),
+                BaseCoord::format_1(-120),
+                BaseCoord::format_1(0),
+            ],
+        );
+        let deva_values = BaseValues::new(
+            0,
+            vec![
+                BaseCoord::format_3(
+                    650,
+                    Some(DeviceOrVariationIndex::VariationIndex(VariationIndex::new(
+                        0, 0,
+                    ))),
+                ),
+                BaseCoord::format_1(-100),
+                BaseCoord::format_1(10),
+            ],
+        );
+        let axis = Axis::new(
+            Some(BaseTagList::new(vec![
+                Tag::new(b"hang"),
+                Tag::new(b"ideo"),
+                Tag::new(b"romn"),
+            ])),
+            BaseScriptList::new(vec![
+                BaseScriptRecord::new(
+                    Tag::new(b"DFLT"),
+                    BaseScript::new(Some(dflt_values), None, vec![]),
+                ),
+                BaseScriptRecord::new(
+                    Tag::new(b"deva"),
+                    BaseScript::new(Some(deva_values), None, vec![]),
+                ),
+            ]),
+        );
+        let base = Base {
+            item_var_store: var_store.into(),
+            ..Base::new(Some(axis), None)
+        };
+        let data = FontBuilder::new().add_table(&base).unwrap().build();
+        let font = FontRef::new(&data).unwrap();
+        let deva = font.baselines(Tag::new(b"deva"), LocationRef::default());
+        assert_eq!(
+            deva,
+            Baselines {
+                default_baseline: Some(Tag::new(b"hang")),
+                roman: Some(10.0),
+                hanging: Some(650.0),
+                ideographic: Some(-100.0),
+            }
+        );
+        let coords = [NormalizedCoord::from_f32(0.5)];
+        let deva = font.baselines(Tag::new(b"deva"), &coords[..]);
+        assert_eq!(deva.hanging, Some(700.0));
+        // unknown scripts use the DFLT values
+        let latn = font.baselines(Tag::new(b"latn"), &coords[..]);
+        assert_eq!(
+            latn,
+            Baselines {
+                default_baseline: Some(Tag::new(b"romn")),
+                roman: Some(0.0),
+                hanging: Some(600.0),
+                ideographic: Some(-120.0),
+            }
+        );
+        // no BASE table
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        assert_eq!(
+            font.baselines(Tag::new(b"arab"), LocationRef::default()),
+            Baselines::default()
+        );
+    }
+
+    #[test]
+    fn fallback_line_metrics() {
+        use write_fonts::{tables::head::Head, FontBuilder};
+        let build = |head: Head| {
+            let mut builder = FontBuilder::new();
+            builder.add_table(&head).unwrap();
+            builder.build()
+        };
+        // no OS/2 or hhea: use head bounds
+        let data = build(Head {
+            units_per_em: 1000,
+            y_min: -300,
+            y_max: 900,
+            ..Default::default()
+        });
+        let font = FontRef::new(&data).unwrap();
+        let metrics = Metrics::new(&font, Size::unscaled(), LocationRef::default());
+        assert_eq!((metrics.ascent, metrics.descent), (0.0, 0.0));
+        let metrics = Metrics::new_with_fallback(&font, Size::new(20.0), LocationRef::default());
+        assert_eq!((metrics.ascent, metrics.descent), (18.0, -6.0));
+        // no usable bounds either: use typical ratios of the em
+        let data = build(Head {
+            units_per_em: 1000,
+            ..Default::default()
+        });
+        let font = FontRef::new(&data).unwrap();
+        let metrics = Metrics::new_with_fallback(&font, Size::unscaled(), LocationRef::default());
+        assert_eq!((metrics.ascent, metrics.descent), (800.0, -200.0));
+        let metrics = Metrics::new_with_fallback(&font, Size::new(10.0), LocationRef::default());
+        assert_eq!((metrics.ascent, metrics.descent), (8.0, -2.0));
+        // existing metrics are unchanged
+        let font = FontRef::new(SIMPLE_GLYF).unwrap();
+        assert_eq!(
+            Metrics::new_with_fallback(&font, Size::new(16.0), LocationRef::default()),
+            Metrics::new(&font, Size::new(16.0), LocationRef::default())
+        );
+    }
+
+    #[test]
+    fn line_metrics_strategy() {
+        use read_fonts::types::FWord;
+        use write_fonts::{
+            tables::{head::Head, hhea::Hhea, os2::Os2},
+            FontBuilder,
+        };
+        let build = |use_typo_metrics: bool, with_hhea: bool| {
+            let mut builder = FontBuilder::new();
+            builder
+                .add_table(&Head {
+                    units_per_em: 1000,
+                    ..Default::default()
+                })
+                .unwrap();
+            if with_hhea {
+                builder
+                    .add_table(&Hhea {
+                        ascender: FWord::new(900),
+                        descender: FWord::new(-300),
+                        line_gap: FWord::new(200),
+                        ..Default::default()
+                    })
+                    .unwrap();
+            }
+            builder
+                .add_table(&Os2 {
+                    fs_selection: if use_typo_metrics {
+                        SelectionFlags::USE_TYPO_METRICS
+                    } else {
+                        SelectionFlags::empty()
+                    },
+                    s_typo_ascender: 800,
+                    s_typo_descender: -200,
+                    s_typo_line_gap: 100,
+                    us_win_ascent: 1000,
+                    us_win_descent: 350,
+                    ..Default::default()
+                })
+                .unwrap();
+            builder.build()
+        };
+        let line_metrics = |data: &[u8], strategy| {
+            let font = FontRef::new(data).unwrap();
+            let metrics = Metrics::new_with_line_metrics(
+                &font,
+                Size::unscaled(),
+                LocationRef::default(),
+                strategy,
+            );
+            (
+                metrics.line_metrics_source.unwrap(),
+                [metrics.ascent, metrics.descent, metrics.leading],
+            )
+        };
+        let hhea = (LineMetricsSource::Hhea, [900.0, -300.0, 200.0]);
+        let typo = (LineMetricsSource::Typo, [800.0, -200.0, 100.0]);
+        // GDI line gap is the hhea line gap less the extra 150 units of
+        // the win metrics
+        let win = (LineMetricsSource::Win, [1000.0, -350.0, 50.0]);
+        for (use_typo_metrics, strategy, expected) in [
+            (false, LineMetricsStrategy::Auto, hhea),
+            (true, LineMetricsStrategy::Auto, typo),
+            (true, LineMetricsStrategy::HheaFirst, hhea),
+            (false, LineMetricsStrategy::TypoFirst, typo),
+            (false, LineMetricsStrategy::Windows, win),
+        ] {
+            let data = build(use_typo_metrics, true);
+            assert_eq!(line_metrics(&data, strategy), expected, "{strategy:?}");
+        }
+        // without hhea, the hhea first strategy falls back to typo metrics
+        // and the Windows metrics have no line gap
+        let data = build(false, false);
+        assert_eq!(line_metrics(&data, LineMetricsStrategy::HheaFirst), typo);
+        assert_eq!(
+            line_metrics(&data, LineMetricsStrategy::Windows),
+            (LineMetricsSource::Win, [1000.0, -350.0, 0.0])
+        );
+        // the default matches the Auto strategy
+        let font = FontRef::new(&data).unwrap();
+        assert_eq!(
+            font.metrics(Size::unscaled(), LocationRef::default()),
+            Metrics::new_with_line_metrics(
+                &font,
+                Size::unscaled(),
+                LocationRef::default(),
+                LineMetricsStrategy::Auto
+            )
+        );
+    }
+
+    #[test]
+    fn tracking() {
+        use write_fonts::{tables::head::Head, FontBuilder};
+        let fixed = |value: i32| Fixed::from_i32(value).to_bits() as u32;
+        let mut trak = Vec::new();
+        // header with horizontal track data only
+        trak.extend(0x00010000u32.to_be_bytes());
+        for value in [0u16, 12, 0, 0] {
+            trak.extend(value.to_be_bytes());
+        }
+        // one normal track with values at 12 and 24 points
+        for value in [1u16, 2] {
+            trak.extend(value.to_be_bytes());
+        }
+        trak.extend(28u32.to_be_bytes());
+        trak.extend(fixed(0).to_be_bytes());
+        for value in [256u16, 36] {
+            trak.extend(value.to_be_bytes());
+        }
+        trak.extend(fixed(12).to_be_bytes());
+        trak.extend(fixed(24).to_be_bytes());
+        for value in [50i16, 20] {
+            trak.extend(value.to_be_bytes());
+        }
+        let mut builder = FontBuilder::new();
+        builder
+            .add_table(&Head {
+                units_per_em: 1000,
+                ..Default::default()
+            })
+            .unwrap();
+        builder.add_raw(Tag::new(b"trak"), trak);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let location = LocationRef::default();
+        // 35 units at 18ppem
+        let metrics = font.metrics(Size::new(18.0), location);
+        assert_eq!(metrics.tracking, Some(35.0 * 18.0 / 1000.0));
+        assert_eq!(font.metrics(Size::new(12.0), location).tracking, Some(0.6));
+        assert_eq!(font.metrics(Size::unscaled(), location).tracking, None);
+        assert_eq!(
+            font.vertical_metrics(Size::new(18.0), location).tracking,
+            None
+        );
+    }
 }
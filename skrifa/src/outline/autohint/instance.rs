@@ -4,8 +4,8 @@ use crate::{attribute::Style, prelude::Size, MetadataProvider};
 
 use super::{
     super::{
-        pen::PathStyle, AdjustedMetrics, DrawError, OutlineGlyph, OutlineGlyphCollection,
-        OutlinePen, Target,
+        path::PathSink, pen::PathStyle, AdjustedMetrics, DrawError, OutlineGlyph,
+        OutlineGlyphCollection, Target,
     },
     metrics::{fixed_mul, pix_round, Scale, UnscaledStyleMetricsSet},
     outline::Outline,
@@ -97,7 +97,7 @@ impl Instance {
         coords: &[F2Dot14],
         glyph: &OutlineGlyph,
         path_style: PathStyle,
-        pen: &mut impl OutlinePen,
+        pen: &mut impl PathSink,
     ) -> Result<AdjustedMetrics, DrawError> {
         let common = glyph.outlines_common();
         let glyph_id = glyph.glyph_id();
@@ -177,6 +177,7 @@ impl Instance {
             has_overlaps: glyph.has_overlaps().unwrap_or_default(),
             lsb: None,
             advance_width: Some(F26Dot6::from_bits(pix_round(advance)).to_f32()),
+            recovered_error: None,
         })
     }
 }
@@ -5,7 +5,7 @@ use super::{
         path,
         pen::PathStyle,
         unscaled::{UnscaledOutlineSink, UnscaledPoint},
-        DrawError, LocationRef, OutlineGlyph, OutlinePen,
+        DrawError, LocationRef, OutlineGlyph,
     },
     metrics::Scale,
 };
@@ -198,7 +198,7 @@ impl Outline {
     pub fn to_path(
         &self,
         style: PathStyle,
-        pen: &mut impl OutlinePen,
+        pen: &mut impl path::PathSink,
     ) -> Result<(), path::ToPathError> {
         for contour in &self.contours {
             let Some(points) = self.points.get(contour.range()) else {
@@ -194,9 +194,8 @@ impl<'a> Shaper<'a> {
         // Check each requested script that is available in GSUB
         let mut gsub_handler = GsubHandler::new(&self.charmap, &lookup_list, style, glyph_styles);
         for script in script_tags.iter().filter_map(|tag| {
-            tag.and_then(|tag| script_list.index_for_tag(tag))
-                .and_then(|ix| script_list.script_records().get(ix as usize))
-                .and_then(|rec| rec.script(script_list.offset_data()).ok())
+            tag.and_then(|tag| script_list.script_by_tag(tag))
+                .and_then(|script| script.ok())
         }) {
             // And all language systems for each script
             for langsys in script
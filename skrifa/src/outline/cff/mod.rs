@@ -2,7 +2,7 @@
 
 mod hint;
 
-use super::{common::OutlinesCommon, OutlinePen};
+use super::{common::OutlinesCommon, hash::ContentHasher, path::PathSink};
 use hint::{HintParams, HintState, HintingSink};
 use read_fonts::{
     tables::{
@@ -106,6 +106,39 @@ impl<'a> Outlines<'a> {
         self.top_dict.font_dicts.count().max(1)
     }
 
+    /// Feeds the charstring and active variation region scalars for the
+    /// given glyph to the hasher.
+    ///
+    /// Subroutines are not followed so the subfont index is included to
+    /// distinguish glyphs that use different local subroutines.
+    pub(crate) fn hash_content(
+        &self,
+        glyph_id: GlyphId,
+        subfont_index: u32,
+        coords: &[F2Dot14],
+        hasher: &mut ContentHasher,
+    ) {
+        hasher.write_u32(subfont_index);
+        let charstring = self
+            .top_dict
+            .charstrings
+            .get(glyph_id.to_u32() as usize)
+            .unwrap_or_default();
+        hasher.write_u32(charstring.len() as u32);
+        hasher.write(charstring);
+        if let Some(regions) = self
+            .top_dict
+            .var_store
+            .as_ref()
+            .filter(|_| coords.iter().any(|coord| *coord != F2Dot14::ZERO))
+            .and_then(|store| store.variation_region_list().ok())
+        {
+            for region in regions.variation_regions().iter().flatten() {
+                hasher.write(&region.compute_scalar(coords).to_be_bytes());
+            }
+        }
+    }
+
     /// Returns the subfont (or Font DICT) index for the given glyph
     /// identifier.
     pub fn subfont_index(&self, glyph_id: GlyphId) -> u32 {
@@ -186,7 +219,7 @@ impl<'a> Outlines<'a> {
         glyph_id: GlyphId,
         coords: &[F2Dot14],
         hint: bool,
-        pen: &mut impl OutlinePen,
+        pen: &mut impl PathSink,
     ) -> Result<(), Error> {
         let charstring_data = self.top_dict.charstrings.get(glyph_id.to_u32() as usize)?;
         let subrs = subfont.subrs(self)?;
@@ -382,7 +415,7 @@ impl<'a> TopDict<'a> {
 }
 
 /// Command sink that sends the results of charstring evaluation to
-/// an [OutlinePen].
+/// a [PathSink].
 struct PenSink<'a, P>(&'a mut P);
 
 impl<'a, P> PenSink<'a, P> {
@@ -393,25 +426,18 @@ impl<'a, P> PenSink<'a, P> {
 
 impl<P> CommandSink for PenSink<'_, P>
 where
-    P: OutlinePen,
+    P: PathSink,
 {
     fn move_to(&mut self, x: Fixed, y: Fixed) {
-        self.0.move_to(x.to_f32(), y.to_f32());
+        self.0.move_to(x, y);
     }
 
     fn line_to(&mut self, x: Fixed, y: Fixed) {
-        self.0.line_to(x.to_f32(), y.to_f32());
+        self.0.line_to(x, y);
     }
 
     fn curve_to(&mut self, cx0: Fixed, cy0: Fixed, cx1: Fixed, cy1: Fixed, x: Fixed, y: Fixed) {
-        self.0.curve_to(
-            cx0.to_f32(),
-            cy0.to_f32(),
-            cx1.to_f32(),
-            cy1.to_f32(),
-            x.to_f32(),
-            y.to_f32(),
-        );
+        self.0.curve_to(cx0, cy0, cx1, cy1, x, y);
     }
 
     fn close(&mut self) {
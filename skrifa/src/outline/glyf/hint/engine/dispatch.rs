@@ -9,6 +9,32 @@ use super::{super::program::Program, Engine, HintError, HintErrorKind, Instructi
 /// See <https://gitlab.freedesktop.org/freetype/freetype/-/blob/57617782464411201ce7bbc93b086c1b4d7d84a5/include/freetype/config/ftoption.h#L744>
 const MAX_RUN_INSTRUCTIONS: usize = 1_000_000;
 
+/// Condition that pauses execution in [`Engine::run_until()`].
+// Breakpoints are only constructed by tests and tracing tools.
+#[allow(dead_code)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Breakpoint {
+    /// Pause before executing the instruction at the given offset in the
+    /// given program.
+    Pc { program: Program, pc: usize },
+    /// Pause before executing any instruction with the given opcode.
+    Opcode(Opcode),
+}
+
+/// Outcome of [`Engine::run_until()`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RunStatus {
+    /// All instructions were executed.
+    Completed,
+    /// Execution paused before the given instruction because it matched a
+    /// breakpoint.
+    Paused {
+        program: Program,
+        pc: usize,
+        opcode: Opcode,
+    },
+}
+
 impl<'a> Engine<'a> {
     /// Resets state for the specified program and executes all instructions.
     pub fn run_program(&mut self, program: Program, is_pedantic: bool) -> Result<(), HintError> {
@@ -53,10 +79,26 @@ impl<'a> Engine<'a> {
 
     /// Decodes and dispatches all instructions until completion or error.
     pub fn run(&mut self) -> Result<(), HintError> {
+        self.run_until(&[]).map(|_| ())
+    }
+
+    /// Decodes and dispatches instructions until completion, error or until
+    /// the next instruction matches one of the given breakpoints.
+    ///
+    /// The instruction at the current position is always executed, so
+    /// calling this again after a pause resumes execution.
+    pub fn run_until(&mut self, breakpoints: &[Breakpoint]) -> Result<RunStatus, HintError> {
         let mut count = 0;
-        while let Some(ins) = self.decode() {
+        loop {
+            if count > 0 {
+                if let Some(status) = self.check_breakpoints(breakpoints) {
+                    return Ok(status);
+                }
+            }
+            let Some(ins) = self.step() else {
+                return Ok(RunStatus::Completed);
+            };
             let ins = ins?;
-            self.dispatch(&ins)?;
             count += 1;
             if count > MAX_RUN_INSTRUCTIONS {
                 return Err(HintError {
@@ -68,7 +110,45 @@ impl<'a> Engine<'a> {
                 });
             }
         }
-        Ok(())
+    }
+
+    /// Returns the paused status if the next instruction matches any of
+    /// the given breakpoints.
+    fn check_breakpoints(&self, breakpoints: &[Breakpoint]) -> Option<RunStatus> {
+        if breakpoints.is_empty() {
+            return None;
+        }
+        let program = self.program.current;
+        let pc = self.program.decoder.pc;
+        // Decode from a copy so that the actual decoder is unaffected.
+        let mut decoder = self.program.decoder;
+        let opcode = decoder.decode()?.ok()?.opcode;
+        breakpoints
+            .iter()
+            .any(|bp| match *bp {
+                Breakpoint::Pc {
+                    program: bp_program,
+                    pc: bp_pc,
+                } => bp_program == program && bp_pc == pc,
+                Breakpoint::Opcode(bp_opcode) => bp_opcode == opcode,
+            })
+            .then_some(RunStatus::Paused {
+                program,
+                pc,
+                opcode,
+            })
+    }
+
+    /// Decodes and dispatches the next instruction from the current program.
+    ///
+    /// Returns the executed instruction or `None` when the program has
+    /// completed.
+    pub fn step(&mut self) -> Option<Result<Instruction<'a>, HintError>> {
+        let ins = match self.decode()? {
+            Ok(ins) => ins,
+            Err(e) => return Some(Err(e)),
+        };
+        Some(self.dispatch(&ins).map(|_| ins))
     }
 
     /// Decodes the next instruction from the current program.
@@ -241,3 +321,104 @@ impl<'a> Engine<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            super::{program::ProgramState, round::RoundMode, zone::ZonePointer},
+            MockEngine,
+        },
+        Breakpoint, Program, RunStatus,
+    };
+    use read_fonts::tables::glyf::bytecode::Opcode;
+
+    #[test]
+    fn step_and_snapshot() {
+        // RTHG, SVTCA[y], PUSHB[0] 0, SZP0, PUSHB[0] 5, SRP1
+        const CODE: &[u8] = &[0x19, 0x00, 0xB0, 0x00, 0x13, 0xB0, 0x05, 0x11];
+        let mut mock = MockEngine::new();
+        let mut engine = mock.engine();
+        engine.program = ProgramState::new(CODE, &[], &[], Program::Font);
+        let initial = engine.graphics_snapshot();
+        assert_eq!(initial.round_state.mode, RoundMode::Grid);
+        assert_eq!(initial.zone_pointers, [ZonePointer::Glyph; 3]);
+        let mut snapshots = vec![];
+        while let Some(ins) = engine.step() {
+            snapshots.push((ins.unwrap().opcode, engine.graphics_snapshot()));
+        }
+        let opcodes = snapshots.iter().map(|(op, _)| *op).collect::<Vec<_>>();
+        assert_eq!(
+            opcodes,
+            [
+                Opcode::RTHG,
+                Opcode::SVTCA0,
+                Opcode::PUSHB000,
+                Opcode::SZP0,
+                Opcode::PUSHB000,
+                Opcode::SRP1
+            ]
+        );
+        // Each snapshot reflects the state after its instruction
+        assert_eq!(snapshots[0].1.round_state.mode, RoundMode::HalfGrid);
+        assert_eq!(snapshots[0].1.freedom_vector, initial.freedom_vector);
+        assert_eq!(snapshots[1].1.freedom_vector.y, 0x4000);
+        assert_eq!(snapshots[2].1.zone_pointers[0], ZonePointer::Glyph);
+        assert_eq!(snapshots[3].1.zone_pointers[0], ZonePointer::Twilight);
+        assert_eq!(snapshots[4].1.reference_points, [0, 0, 0]);
+        assert_eq!(snapshots[5].1.reference_points, [0, 5, 0]);
+        // Snapshots are copies and aren't affected by later execution
+        assert_eq!(initial.round_state.mode, RoundMode::Grid);
+    }
+
+    #[test]
+    fn run_until_breakpoints() {
+        // RTHG, SVTCA[y], PUSHB[0] 0, SZP0, PUSHB[0] 5, SRP1
+        const CODE: &[u8] = &[0x19, 0x00, 0xB0, 0x00, 0x13, 0xB0, 0x05, 0x11];
+        let mut mock = MockEngine::new();
+        let mut engine = mock.engine();
+        engine.program = ProgramState::new(CODE, &[], &[], Program::Font);
+        let breakpoints = [
+            Breakpoint::Opcode(Opcode::SZP0),
+            Breakpoint::Pc {
+                program: Program::Font,
+                pc: 5,
+            },
+            // Never hit since we're running the font program
+            Breakpoint::Pc {
+                program: Program::Glyph,
+                pc: 1,
+            },
+        ];
+        assert_eq!(
+            engine.run_until(&breakpoints).unwrap(),
+            RunStatus::Paused {
+                program: Program::Font,
+                pc: 4,
+                opcode: Opcode::SZP0
+            }
+        );
+        // Paused before SZP0 so the zone pointer is unchanged
+        assert_eq!(
+            engine.graphics_snapshot().zone_pointers[0],
+            ZonePointer::Glyph
+        );
+        assert_eq!(
+            engine.run_until(&breakpoints).unwrap(),
+            RunStatus::Paused {
+                program: Program::Font,
+                pc: 5,
+                opcode: Opcode::PUSHB000
+            }
+        );
+        assert_eq!(
+            engine.graphics_snapshot().zone_pointers[0],
+            ZonePointer::Twilight
+        );
+        assert_eq!(
+            engine.run_until(&breakpoints).unwrap(),
+            RunStatus::Completed
+        );
+        assert_eq!(engine.graphics_snapshot().reference_points, [0, 5, 0]);
+    }
+}
@@ -25,7 +25,7 @@ use super::{
     cvt::Cvt,
     definition::DefinitionState,
     error::{HintError, HintErrorKind},
-    graphics::{GraphicsState, RetainedGraphicsState},
+    graphics::{GraphicsState, GraphicsStateSnapshot, RetainedGraphicsState},
     math,
     program::ProgramState,
     storage::Storage,
@@ -95,6 +95,16 @@ impl<'a> Engine<'a> {
     pub fn retained_graphics_state(&self) -> &RetainedGraphicsState {
         &self.graphics.retained
     }
+
+    /// Returns a copy of the current graphics state.
+    ///
+    /// Combined with [`Self::step`], this allows inspecting the state after
+    /// each instruction.
+    // This is used in tests and also useful for tracing.
+    #[allow(dead_code)]
+    pub fn graphics_snapshot(&self) -> GraphicsStateSnapshot {
+        (&self.graphics).into()
+    }
 }
 
 /// Tracks budgets for loops to limit execution time.
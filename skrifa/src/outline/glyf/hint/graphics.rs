@@ -301,6 +301,85 @@ impl Default for RetainedGraphicsState {
     }
 }
 
+/// Read-only copy of the graphics state at a single point during
+/// execution.
+///
+/// This captures the portions of the state that are useful when inspecting
+/// the interpreter while stepping through a program, omitting the zones
+/// which are borrowed from the outline.
+// Not all fields are read internally; this is intended for tracing.
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug)]
+pub struct GraphicsStateSnapshot {
+    /// Current freedom vector.
+    pub freedom_vector: Point<i32>,
+    /// Current axis for point movement.
+    pub freedom_axis: CoordAxis,
+    /// Current projection vector.
+    pub proj_vector: Point<i32>,
+    /// Current axis for the projection vector.
+    pub proj_axis: CoordAxis,
+    /// Current dual projection vector.
+    pub dual_proj_vector: Point<i32>,
+    /// Current rounding state.
+    pub round_state: RoundState,
+    /// Zone pointers `zp0`, `zp1` and `zp2`, in that order.
+    pub zone_pointers: [ZonePointer; 3],
+    /// Reference points `rp0`, `rp1` and `rp2`, in that order.
+    pub reference_points: [usize; 3],
+    /// Current loop counter.
+    pub loop_counter: u32,
+    /// Current value of the auto flip flag.
+    pub auto_flip: bool,
+    /// Current instruction control flags.
+    pub instruct_control: u8,
+    /// Current scan control flag.
+    pub scan_control: bool,
+    /// Type associated with `scan_control`.
+    pub scan_type: i32,
+    /// Current minimum distance.
+    pub min_distance: F26Dot6,
+    /// Current control value cut-in.
+    pub control_value_cutin: F26Dot6,
+    /// Current single width cut-in.
+    pub single_width_cutin: F26Dot6,
+    /// Current single width value.
+    pub single_width: F26Dot6,
+    /// Current delta base.
+    pub delta_base: u16,
+    /// Current delta shift.
+    pub delta_shift: u16,
+    /// True if backward compatibility heuristics are active.
+    pub backward_compatibility: bool,
+}
+
+impl From<&GraphicsState<'_>> for GraphicsStateSnapshot {
+    fn from(gs: &GraphicsState) -> Self {
+        Self {
+            freedom_vector: gs.freedom_vector,
+            freedom_axis: gs.freedom_axis,
+            proj_vector: gs.proj_vector,
+            proj_axis: gs.proj_axis,
+            dual_proj_vector: gs.dual_proj_vector,
+            round_state: gs.round_state,
+            zone_pointers: [gs.zp0, gs.zp1, gs.zp2],
+            reference_points: [gs.rp0, gs.rp1, gs.rp2],
+            loop_counter: gs.loop_counter,
+            auto_flip: gs.auto_flip,
+            instruct_control: gs.instruct_control,
+            scan_control: gs.scan_control,
+            scan_type: gs.scan_type,
+            min_distance: gs.min_distance,
+            control_value_cutin: gs.control_value_cutin,
+            single_width_cutin: gs.single_width_cutin,
+            single_width: gs.single_width,
+            delta_base: gs.delta_base,
+            delta_shift: gs.delta_shift,
+            backward_compatibility: gs.backward_compatibility,
+        }
+    }
+}
+
 impl Deref for GraphicsState<'_> {
     type Target = RetainedGraphicsState;
 
@@ -101,6 +101,30 @@ impl HintInstance {
         }
     }
 
+    /// Returns the scaled CVT values in 26.6 format as modified by the
+    /// control value program.
+    pub fn cvt(&self) -> &[i32] {
+        &self.cvt
+    }
+
+    /// Returns the storage area as modified by the control value program.
+    pub fn storage(&self) -> &[i32] {
+        &self.storage
+    }
+
+    /// Replaces the CVT and storage area with previously captured values.
+    ///
+    /// Returns false and leaves the instance unchanged if the sizes don't
+    /// match those of the current configuration.
+    pub fn restore_cvt_and_storage(&mut self, cvt: &[i32], storage: &[i32]) -> bool {
+        if cvt.len() != self.cvt.len() || storage.len() != self.storage.len() {
+            return false;
+        }
+        self.cvt.copy_from_slice(cvt);
+        self.storage.copy_from_slice(storage);
+        true
+    }
+
     pub fn hint(
         &self,
         outlines: &Outlines,
@@ -178,10 +202,20 @@ impl HintInstance {
 
     /// Captures limits, resizes buffers and scales the CVT.
     fn setup(&mut self, outlines: &Outlines, scale: i32, coords: &[F2Dot14]) {
+        // cvar does not record an axis count so take it from gvar, falling
+        // back to fvar for fonts that only vary hinting
         let axis_count = outlines
             .gvar
             .as_ref()
             .map(|gvar| gvar.axis_count())
+            .or_else(|| {
+                outlines
+                    .common
+                    .font
+                    .fvar()
+                    .ok()
+                    .map(|fvar| fvar.axis_count())
+            })
             .unwrap_or_default();
         self.functions.clear();
         self.functions
@@ -248,13 +282,27 @@ mod tests {
         let font = FontRef::new(font_test_data::CVAR).unwrap();
         let base = OutlinesCommon::new(&font).unwrap();
         let outlines = Outlines::new(&base).unwrap();
+        check_scaled_cvar_cvt(&outlines);
+    }
+
+    // The axis count for cvar should come from fvar when gvar is missing
+    #[test]
+    fn scaled_cvar_cvt_without_gvar() {
+        let font = FontRef::new(font_test_data::CVAR).unwrap();
+        let base = OutlinesCommon::new(&font).unwrap();
+        let mut outlines = Outlines::new(&base).unwrap();
+        outlines.gvar = None;
+        check_scaled_cvar_cvt(&outlines);
+    }
+
+    fn check_scaled_cvar_cvt(outlines: &Outlines) {
         let mut instance = HintInstance::default();
         let coords = [0.5, -0.5].map(F2Dot14::from_f32);
         let ppem = 16;
         // ppem * 64 / upem
         let scale = 67109;
         instance
-            .reconfigure(&outlines, scale, ppem, Default::default(), &coords)
+            .reconfigure(outlines, scale, ppem, Default::default(), &coords)
             .unwrap();
         let expected = [
             778, 10, 731, 0, 731, 10, 549, 10, 0, 0, 0, -10, 0, -10, -256, -10, 0, 0, 0, 0, 0, 0,
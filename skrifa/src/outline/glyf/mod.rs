@@ -12,7 +12,7 @@ use core_maths::CoreFloat;
 pub use hint::{HintError, HintInstance, HintOutline};
 pub use outline::{Outline, ScaledOutline};
 
-use super::{common::OutlinesCommon, DrawError, Hinting};
+use super::{common::OutlinesCommon, hash::ContentHasher, DrawError, Hinting};
 use crate::GLYF_COMPOSITE_RECURSION_LIMIT;
 use memory::{FreeTypeOutlineMemory, HarfBuzzOutlineMemory};
 
@@ -235,6 +235,52 @@ impl Outlines<'_> {
         Ok(())
     }
 
+    /// Feeds the glyph data and active variation tuples for the given glyph
+    /// (and, recursively, its components) to the hasher.
+    pub(crate) fn hash_content(
+        &self,
+        glyph_id: GlyphId,
+        coords: &[F2Dot14],
+        hasher: &mut ContentHasher,
+        recurse_depth: usize,
+    ) {
+        if recurse_depth > GLYF_COMPOSITE_RECURSION_LIMIT {
+            return;
+        }
+        let gid = glyph_id.to_u32() as usize;
+        let data = self
+            .loca
+            .get_raw(gid)
+            .zip(self.loca.get_raw(gid + 1))
+            .and_then(|(start, end)| {
+                self.glyf
+                    .offset_data()
+                    .as_bytes()
+                    .get(start as usize..end as usize)
+            })
+            .unwrap_or_default();
+        hasher.write_u32(data.len() as u32);
+        hasher.write(data);
+        if let Some(var_data) = self
+            .gvar
+            .as_ref()
+            .filter(|_| coords.iter().any(|coord| *coord != F2Dot14::ZERO))
+            .and_then(|gvar| gvar.glyph_variation_data(glyph_id).ok())
+        {
+            for (i, tuple) in var_data.tuples().enumerate() {
+                if let Some(scalar) = tuple.compute_scalar(coords) {
+                    hasher.write_u32(i as u32);
+                    hasher.write(&scalar.to_be_bytes());
+                }
+            }
+        }
+        if let Ok(Some(Glyph::Composite(composite))) = self.loca.get_glyf(glyph_id, &self.glyf) {
+            for component in composite.components() {
+                self.hash_content(component.glyph.into(), coords, hasher, recurse_depth + 1);
+            }
+        }
+    }
+
     fn hdmx_width(&self, ppem: f32, glyph_id: GlyphId) -> Option<u8> {
         let hdmx = self.hdmx.as_ref()?;
         let ppem_u8 = ppem as u8;
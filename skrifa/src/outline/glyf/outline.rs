@@ -3,9 +3,9 @@
 use std::mem::size_of;
 
 use super::super::{
-    path::{to_path, ToPathError},
+    path::{to_path, PathSink, PenCoord, ToPathError},
     pen::PathStyle,
-    Hinting, OutlinePen,
+    Hinting,
 };
 use raw::tables::glyf::PointCoord;
 use read_fonts::{
@@ -150,11 +150,10 @@ where
         }
     }
 
-    pub fn to_path(
-        &self,
-        path_style: PathStyle,
-        pen: &mut impl OutlinePen,
-    ) -> Result<(), ToPathError> {
+    pub fn to_path(&self, path_style: PathStyle, pen: &mut impl PathSink) -> Result<(), ToPathError>
+    where
+        C: PenCoord,
+    {
         to_path(self.points, self.flags, self.contours, path_style, pen)
     }
 }
@@ -0,0 +1,30 @@
+//! Support for computing stable hashes of outline data.
+
+/// 64-bit FNV-1a hasher.
+///
+/// This is used instead of the hashers in `core` or `std` because the
+/// resulting values are stable across platforms and releases.
+pub(super) struct ContentHasher(u64);
+
+impl ContentHasher {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    pub fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_be_bytes());
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
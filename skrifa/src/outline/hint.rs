@@ -3,11 +3,13 @@
 use super::{
     autohint, cff,
     glyf::{self, FreeTypeScaler},
+    path::PathSink,
     pen::PathStyle,
     AdjustedMetrics, DrawError, GlyphStyles, Hinting, LocationRef, NormalizedCoord,
-    OutlineCollectionKind, OutlineGlyph, OutlineGlyphCollection, OutlineKind, OutlinePen, Size,
+    OutlineCollectionKind, OutlineGlyph, OutlineGlyphCollection, OutlineKind, Size,
 };
 use crate::alloc::{boxed::Box, vec::Vec};
+use raw::{tables::gasp::GaspRangeBehavior, TableProvider};
 
 /// Configuration settings for a hinting instance.
 #[derive(Clone, Default, Debug)]
@@ -21,6 +23,17 @@ pub struct HintingOptions {
     /// Defaults to a target with [`SmoothMode::Normal`] which is equivalent
     /// to `FT_RENDER_MODE_NORMAL` in FreeType.
     pub target: Target,
+    /// If true, the font's
+    /// [gasp](https://learn.microsoft.com/en-us/typography/opentype/spec/gasp)
+    /// table determines the rendering behavior at the instance size.
+    ///
+    /// Specifically, hinting is disabled for sizes where the table does not
+    /// request grid-fitting and, for version 1 tables, the symmetric
+    /// rendering setting of a smooth [`Target`] is replaced by the
+    /// symmetric smoothing flag.
+    ///
+    /// FreeType ignores this table so this defaults to `false`.
+    pub use_gasp: bool,
 }
 
 impl From<Target> for HintingOptions {
@@ -28,6 +41,7 @@ impl From<Target> for HintingOptions {
         Self {
             engine: Engine::AutoFallback,
             target: value,
+            use_gasp: false,
         }
     }
 }
@@ -82,6 +96,7 @@ impl From<Engine> for HintingOptions {
         Self {
             engine: value,
             target: Default::default(),
+            use_gasp: false,
         }
     }
 }
@@ -280,6 +295,7 @@ pub struct HintingInstance {
     size: Size,
     coords: Vec<NormalizedCoord>,
     target: Target,
+    gasp: Option<GaspRangeBehavior>,
     kind: HinterKind,
 }
 
@@ -297,6 +313,7 @@ impl HintingInstance {
             size: Size::unscaled(),
             coords: vec![],
             target: options.target,
+            gasp: None,
             kind: HinterKind::None,
         };
         hinter.reconfigure(outline_glyphs, size, location, options)?;
@@ -314,10 +331,22 @@ impl HintingInstance {
     }
 
     /// Returns the currently configured hinting target.
+    ///
+    /// This reflects any adjustments made based on the `gasp` table when
+    /// [`HintingOptions::use_gasp`] is enabled.
     pub fn target(&self) -> Target {
         self.target
     }
 
+    /// Returns the rasterizer behavior requested by the `gasp` table for
+    /// the current size.
+    ///
+    /// This is only available when [`HintingOptions::use_gasp`] is
+    /// enabled and the table contains a range covering the size.
+    pub fn gasp_behavior(&self) -> Option<GaspRangeBehavior> {
+        self.gasp
+    }
+
     /// Resets the hinter state for a new font instance with the given
     /// outline collection and settings.
     pub fn reconfigure<'a>(
@@ -332,6 +361,10 @@ impl HintingInstance {
         self.coords.extend_from_slice(location.into().coords());
         let options = options.into();
         self.target = options.target;
+        self.gasp = None;
+        if options.use_gasp {
+            self.apply_gasp(outlines);
+        }
         let engine = options.engine.resolve_auto_fallback(outlines);
         // Reuse memory if the font contains the same outline format
         let current_kind = core::mem::replace(&mut self.kind, HinterKind::None);
@@ -386,11 +419,43 @@ impl HintingInstance {
         Ok(())
     }
 
+    /// Looks up the `gasp` behavior for the current size and adjusts the
+    /// target accordingly.
+    fn apply_gasp(&mut self, outlines: &OutlineGlyphCollection) {
+        let Some(ppem) = self.size.ppem() else {
+            return;
+        };
+        let Some(gasp) = outlines.common().and_then(|common| common.font.gasp().ok()) else {
+            return;
+        };
+        // Truncate to match the integral ppem value provided to the
+        // TrueType interpreter
+        let ppem = ppem as u16;
+        self.gasp = gasp.behavior(ppem);
+        if let (
+            Some(behavior),
+            Target::Smooth {
+                symmetric_rendering,
+                ..
+            },
+        ) = (self.gasp, &mut self.target)
+        {
+            if gasp.version() >= 1 {
+                *symmetric_rendering = behavior.symmetric_smoothing();
+            }
+        }
+    }
+
     /// Returns true if hinting should actually be applied for this instance.
     ///
     /// Some TrueType fonts disable hinting dynamically based on the instance
-    /// configuration.
+    /// configuration. When [`HintingOptions::use_gasp`] is enabled, hinting
+    /// is also disabled if the `gasp` table does not request grid-fitting
+    /// at the current size.
     pub fn is_enabled(&self) -> bool {
+        if self.gasp.is_some_and(|behavior| !behavior.gridfit()) {
+            return false;
+        }
         match &self.kind {
             HinterKind::Glyf(instance) => instance.is_enabled(),
             HinterKind::Cff(_) | HinterKind::Auto(_) => true,
@@ -398,12 +463,43 @@ impl HintingInstance {
         }
     }
 
+    /// Returns a copy of the TrueType interpreter state that was produced
+    /// by the control value program.
+    ///
+    /// Returns `None` if this instance does not use the TrueType
+    /// interpreter.
+    pub fn interpreter_state(&self) -> Option<InterpreterState> {
+        match &self.kind {
+            HinterKind::Glyf(instance) => Some(InterpreterState {
+                cvt: instance.cvt().to_vec(),
+                storage: instance.storage().to_vec(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Replaces the TrueType interpreter state with one previously captured
+    /// by [`Self::interpreter_state`].
+    ///
+    /// The state should come from an instance configured with the same font,
+    /// size, location and options. Returns false and leaves this instance
+    /// unchanged if it does not use the TrueType interpreter or if the
+    /// sizes of the CVT and storage area don't match.
+    pub fn set_interpreter_state(&mut self, state: &InterpreterState) -> bool {
+        match &mut self.kind {
+            HinterKind::Glyf(instance) => {
+                instance.restore_cvt_and_storage(&state.cvt, &state.storage)
+            }
+            _ => false,
+        }
+    }
+
     pub(super) fn draw(
         &self,
         glyph: &OutlineGlyph,
         memory: Option<&mut [u8]>,
         path_style: PathStyle,
-        pen: &mut impl OutlinePen,
+        pen: &mut impl PathSink,
         is_pedantic: bool,
     ) -> Result<AdjustedMetrics, DrawError> {
         let ppem = self.size.ppem();
@@ -436,6 +532,7 @@ impl HintingInstance {
                         advance_width: Some(
                             scaled_outline.adjusted_advance_width().round().to_f32(),
                         ),
+                        recovered_error: None,
                     })
                 })
             }
@@ -451,6 +548,35 @@ impl HintingInstance {
     }
 }
 
+/// Snapshot of the TrueType interpreter state that persists between glyphs.
+///
+/// This captures the control value table and storage area as modified by
+/// the control value program (`prep`). See
+/// [`HintingInstance::interpreter_state`].
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct InterpreterState {
+    cvt: Vec<i32>,
+    storage: Vec<i32>,
+}
+
+impl InterpreterState {
+    /// Creates a new state from the given CVT values (in 26.6 format) and
+    /// storage area.
+    pub fn new(cvt: Vec<i32>, storage: Vec<i32>) -> Self {
+        Self { cvt, storage }
+    }
+
+    /// Returns the scaled control value table in 26.6 format.
+    pub fn cvt(&self) -> &[i32] {
+        &self.cvt
+    }
+
+    /// Returns the storage area.
+    pub fn storage(&self) -> &[i32] {
+        &self.storage
+    }
+}
+
 #[derive(Clone)]
 enum HinterKind {
     /// Represents a hinting instance that is associated with an empty outline
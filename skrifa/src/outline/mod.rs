@@ -82,6 +82,7 @@ mod autohint;
 mod cff;
 mod common;
 mod glyf;
+mod hash;
 mod hint;
 mod path;
 mod unscaled;
@@ -96,7 +97,8 @@ use common::OutlinesCommon;
 
 pub use autohint::GlyphStyles;
 pub use hint::{
-    Engine, HintingInstance, HintingMode, HintingOptions, LcdLayout, SmoothMode, Target,
+    Engine, HintingInstance, HintingMode, HintingOptions, InterpreterState, LcdLayout, SmoothMode,
+    Target,
 };
 use raw::FontRef;
 #[doc(inline)]
@@ -107,8 +109,10 @@ use super::{
     instance::{LocationRef, NormalizedCoord, Size},
     GLYF_COMPOSITE_RECURSION_LIMIT,
 };
+use alloc::vec::Vec;
 use core::fmt::Debug;
-use pen::PathStyle;
+use path::PathSink;
+use pen::{FixedPointOutlinePen, FixedPointPen, PathStyle};
 use read_fonts::{types::GlyphId, TableProvider};
 
 /// Source format for an outline glyph.
@@ -141,7 +145,7 @@ pub enum Hinting {
 /// When applying hints to a TrueType glyph, the outline may be shifted in
 /// the horizontal direction, affecting the left side bearing and advance width
 /// of the glyph. This captures those metrics.
-#[derive(Copy, Clone, Default, Debug)]
+#[derive(Clone, Default, Debug)]
 pub struct AdjustedMetrics {
     /// True if the underlying glyph contains flags indicating the
     /// presence of overlapping contours or components.
@@ -158,6 +162,27 @@ pub struct AdjustedMetrics {
     /// This is equivalent to the `advance.x` value in
     /// [`FT_GlyphSlotRec`](https://freetype.org/freetype2/docs/reference/ft2-glyph_retrieval.html#ft_glyphslotrec).
     pub advance_width: Option<f32>,
+    /// If present, the error that occurred while drawing the glyph when
+    /// using the [`DrawErrorPolicy::EmptyOutline`] policy.
+    ///
+    /// In this case, nothing was emitted to the pen.
+    pub recovered_error: Option<DrawError>,
+}
+
+/// Determines how errors that occur while drawing a glyph are handled.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum DrawErrorPolicy {
+    /// Errors are returned to the caller and the pen may have received
+    /// a partial outline.
+    #[default]
+    Fail,
+    /// Errors are captured in [`AdjustedMetrics::recovered_error`] and the
+    /// glyph is drawn as an empty outline.
+    ///
+    /// This is useful for renderers processing untrusted fonts that want
+    /// to degrade gracefully glyph-by-glyph. Path commands are buffered
+    /// internally so that the pen only receives complete outlines.
+    EmptyOutline,
 }
 
 /// Options that define how a [glyph](OutlineGlyph) is drawn to a
@@ -166,6 +191,7 @@ pub struct DrawSettings<'a> {
     instance: DrawInstance<'a>,
     memory: Option<&'a mut [u8]>,
     path_style: PathStyle,
+    error_policy: DrawErrorPolicy,
 }
 
 impl<'a> DrawSettings<'a> {
@@ -176,6 +202,7 @@ impl<'a> DrawSettings<'a> {
             instance: DrawInstance::Unhinted(size, location.into()),
             memory: None,
             path_style: PathStyle::default(),
+            error_policy: DrawErrorPolicy::default(),
         }
     }
 
@@ -196,6 +223,7 @@ impl<'a> DrawSettings<'a> {
             },
             memory: None,
             path_style: PathStyle::default(),
+            error_policy: DrawErrorPolicy::default(),
         }
     }
 
@@ -218,6 +246,15 @@ impl<'a> DrawSettings<'a> {
         self.path_style = path_style;
         self
     }
+
+    /// Builder method to control how errors that occur while drawing are
+    /// handled.
+    ///
+    /// See [`DrawErrorPolicy`] for details.
+    pub fn with_error_policy(mut self, error_policy: DrawErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
 }
 
 enum DrawInstance<'a> {
@@ -307,6 +344,32 @@ impl<'a> OutlineGlyph<'a> {
         }
     }
 
+    /// Returns a hash of the outline data for this glyph at the given
+    /// location in variation space.
+    ///
+    /// The hash covers the raw glyph data (`glyf` bytes or charstrings)
+    /// along with the variation tuples (`gvar`) or regions (`CFF2`) that
+    /// are active at the location and their scalars. The outline is not
+    /// evaluated so this is cheap to compute and is suitable for use as a
+    /// cache key for rendered glyphs. The value is stable across platforms
+    /// and releases.
+    ///
+    /// Hashes are only comparable between glyphs of the same font and do
+    /// not account for hinting.
+    pub fn content_hash(&self, location: impl Into<LocationRef<'a>>) -> u64 {
+        let coords = location.into().coords();
+        let mut hasher = hash::ContentHasher::new();
+        match &self.kind {
+            OutlineKind::Glyf(glyf, outline) => {
+                glyf.hash_content(outline.glyph_id, coords, &mut hasher, 0)
+            }
+            OutlineKind::Cff(cff, glyph_id, subfont_index) => {
+                cff.hash_content(*glyph_id, *subfont_index, coords, &mut hasher)
+            }
+        }
+        hasher.finish()
+    }
+
     /// Returns the size (in bytes) of the temporary memory required to draw
     /// this outline.
     ///
@@ -337,6 +400,64 @@ impl<'a> OutlineGlyph<'a> {
         pen: &mut impl OutlinePen,
     ) -> Result<AdjustedMetrics, DrawError> {
         let settings: DrawSettings<'a> = settings.into();
+        match settings.error_policy {
+            DrawErrorPolicy::Fail => self.draw_with_settings(settings, pen),
+            DrawErrorPolicy::EmptyOutline => {
+                let mut path = Vec::new();
+                match self.draw_with_settings(settings, &mut path) {
+                    Ok(metrics) => {
+                        for element in path {
+                            element.apply(pen);
+                        }
+                        Ok(metrics)
+                    }
+                    Err(e) => Ok(AdjustedMetrics {
+                        has_overlaps: self.has_overlaps().unwrap_or_default(),
+                        recovered_error: Some(e),
+                        ..Default::default()
+                    }),
+                }
+            }
+        }
+    }
+
+    /// Draws the outline glyph with the given settings and emits the resulting
+    /// path commands with 26.6 fixed point coordinates to the specified pen.
+    ///
+    /// Unlike [`draw`](Self::draw), the coordinates of TrueType outlines
+    /// (hinted or not) and autohinted outlines reach the pen exactly as they
+    /// were computed, without a conversion through `f32`. See [`FixedPointPen`]
+    /// for the handling of other outline formats.
+    pub fn draw_fixed_point<'s, P: FixedPointOutlinePen>(
+        &self,
+        settings: impl Into<DrawSettings<'a>>,
+        pen: &mut FixedPointPen<'s, P>,
+    ) -> Result<AdjustedMetrics, DrawError> {
+        let settings: DrawSettings<'a> = settings.into();
+        match settings.error_policy {
+            DrawErrorPolicy::Fail => self.draw_with_settings(settings, pen),
+            DrawErrorPolicy::EmptyOutline => {
+                let mut path = Vec::new();
+                match self.draw_with_settings(settings, &mut pen.buffered(&mut path)) {
+                    Ok(metrics) => {
+                        pen.replay(path);
+                        Ok(metrics)
+                    }
+                    Err(e) => Ok(AdjustedMetrics {
+                        has_overlaps: self.has_overlaps().unwrap_or_default(),
+                        recovered_error: Some(e),
+                        ..Default::default()
+                    }),
+                }
+            }
+        }
+    }
+
+    fn draw_with_settings(
+        &self,
+        settings: DrawSettings<'a>,
+        pen: &mut impl PathSink,
+    ) -> Result<AdjustedMetrics, DrawError> {
         match (settings.instance, settings.path_style) {
             (DrawInstance::Unhinted(size, location), PathStyle::FreeType) => {
                 self.draw_unhinted(size, location, settings.memory, settings.path_style, pen)
@@ -381,7 +502,7 @@ impl<'a> OutlineGlyph<'a> {
         location: impl Into<LocationRef<'a>>,
         user_memory: Option<&mut [u8]>,
         path_style: PathStyle,
-        pen: &mut impl OutlinePen,
+        pen: &mut impl PathSink,
     ) -> Result<AdjustedMetrics, DrawError> {
         let ppem = size.ppem();
         let coords = location.into().coords();
@@ -415,6 +536,7 @@ impl<'a> OutlineGlyph<'a> {
                         has_overlaps: outline.has_overlaps,
                         lsb: Some(lsb),
                         advance_width: Some(advance_width),
+                        recovered_error: None,
                     })
                 })
             }
@@ -567,8 +689,25 @@ impl<'a> OutlineGlyphCollection<'a> {
         }
     }
 
+    /// Returns the number of glyphs in the collection.
+    ///
+    /// This is zero if the font does not contain supported outlines.
+    pub fn glyph_count(&self) -> u32 {
+        match &self.kind {
+            OutlineCollectionKind::Glyf(glyf) => glyf.glyph_count() as u32,
+            OutlineCollectionKind::Cff(cff) => cff.glyph_count() as u32,
+            OutlineCollectionKind::None => 0,
+        }
+    }
+
     /// Returns the outline for the given glyph identifier.
+    ///
+    /// Returns `None` if `glyph_id >= self.glyph_count()` or the outline
+    /// cannot be loaded.
     pub fn get(&self, glyph_id: GlyphId) -> Option<OutlineGlyph<'a>> {
+        if glyph_id.to_u32() >= self.glyph_count() {
+            return None;
+        }
         match &self.kind {
             OutlineCollectionKind::None => None,
             OutlineCollectionKind::Glyf(glyf) => Some(OutlineGlyph {
@@ -582,14 +721,10 @@ impl<'a> OutlineGlyphCollection<'a> {
 
     /// Returns an iterator over all of the outline glyphs in the collection.
     pub fn iter(&self) -> impl Iterator<Item = (GlyphId, OutlineGlyph<'a>)> + 'a + Clone {
-        let len = match &self.kind {
-            OutlineCollectionKind::Glyf(glyf) => glyf.glyph_count(),
-            OutlineCollectionKind::Cff(cff) => cff.glyph_count(),
-            _ => 0,
-        } as u16;
+        let len = self.glyph_count();
         let copy = self.clone();
         (0..len).filter_map(move |gid| {
-            let gid = GlyphId::from(gid);
+            let gid = GlyphId::new(gid);
             let glyph = copy.get(gid)?;
             Some((gid, glyph))
         })
@@ -681,7 +816,11 @@ mod tests {
     use super::*;
     use crate::{instance::Location, MetadataProvider};
     use kurbo::{Affine, BezPath, PathEl, Point};
-    use read_fonts::{types::GlyphId, FontRef, TableProvider};
+    use read_fonts::{
+        tables::gasp::GaspRangeBehavior,
+        types::{F26Dot6, GlyphId},
+        FontRef, TableProvider,
+    };
 
     use pretty_assertions::assert_eq;
 
@@ -1395,6 +1534,105 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn gasp_controls_hinting() {
+        let font = FontRef::new(font_test_data::AHEM).unwrap();
+        let outlines = font.outline_glyphs();
+        let options = |use_gasp| HintingOptions {
+            engine: Engine::Auto(None),
+            use_gasp,
+            ..Default::default()
+        };
+        // The gasp table disables grid-fitting at 8ppem and below
+        let hinter = HintingInstance::new(
+            &outlines,
+            Size::new(8.0),
+            LocationRef::default(),
+            options(true),
+        )
+        .unwrap();
+        assert_eq!(hinter.gasp_behavior(), Some(GaspRangeBehavior::GASP_DOGRAY));
+        assert!(!hinter.is_enabled());
+        let hinter = HintingInstance::new(
+            &outlines,
+            Size::new(8.0),
+            LocationRef::default(),
+            options(false),
+        )
+        .unwrap();
+        assert_eq!(hinter.gasp_behavior(), None);
+        assert!(hinter.is_enabled());
+        let hinter = HintingInstance::new(
+            &outlines,
+            Size::new(12.0),
+            LocationRef::default(),
+            options(true),
+        )
+        .unwrap();
+        assert!(hinter.gasp_behavior().unwrap().gridfit());
+        assert!(hinter.is_enabled());
+    }
+
+    #[test]
+    fn gasp_controls_symmetric_rendering() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let outlines = font.outline_glyphs();
+        let target = Target::Smooth {
+            mode: SmoothMode::Normal,
+            symmetric_rendering: false,
+            preserve_linear_metrics: false,
+        };
+        let options = HintingOptions {
+            engine: Engine::Interpreter,
+            target,
+            use_gasp: true,
+        };
+        let hinter =
+            HintingInstance::new(&outlines, Size::new(16.0), LocationRef::default(), options)
+                .unwrap();
+        assert!(hinter.target().symmetric_rendering());
+        assert!(hinter.is_enabled());
+    }
+
+    #[test]
+    fn export_import_interpreter_state() {
+        let font = FontRef::new(font_test_data::TTHINT_SUBSET).unwrap();
+        let outlines = font.outline_glyphs();
+        let options = HintingOptions {
+            engine: Engine::Interpreter,
+            ..Default::default()
+        };
+        let hinter = HintingInstance::new(
+            &outlines,
+            Size::new(16.0),
+            LocationRef::default(),
+            options.clone(),
+        )
+        .unwrap();
+        let state = hinter.interpreter_state().unwrap();
+        assert!(!state.cvt().is_empty());
+        let mut other =
+            HintingInstance::new(&outlines, Size::new(24.0), LocationRef::default(), options)
+                .unwrap();
+        assert_ne!(other.interpreter_state().unwrap(), state);
+        assert!(other.set_interpreter_state(&state));
+        assert_eq!(other.interpreter_state().unwrap(), state);
+        // Mismatched sizes are rejected
+        let bad_state = InterpreterState::new(vec![0; state.cvt().len() + 1], vec![]);
+        assert!(!other.set_interpreter_state(&bad_state));
+        assert_eq!(other.interpreter_state().unwrap(), state);
+        // Only available for the TrueType interpreter
+        let mut auto = HintingInstance::new(
+            &outlines,
+            Size::new(16.0),
+            LocationRef::default(),
+            Engine::Auto(None),
+        )
+        .unwrap();
+        assert!(auto.interpreter_state().is_none());
+        assert!(!auto.set_interpreter_state(&state));
+    }
+
     #[test]
     fn empty_glyph_advance_unhinted() {
         let font = FontRef::new(font_test_data::HVAR_WITH_TRUNCATED_ADVANCE_INDEX_MAP).unwrap();
@@ -1434,4 +1672,215 @@ mod tests {
             .unwrap();
         assert_eq!(advance, 11.0);
     }
+
+    /// Font with a simple glyph 1 that is missing most of its point data.
+    fn truncated_glyph_font() -> Vec<u8> {
+        use write_fonts::{
+            tables::{
+                head::Head,
+                hhea::Hhea,
+                hmtx::{Hmtx, LongMetric},
+                maxp::Maxp,
+            },
+            types::Tag,
+            FontBuilder,
+        };
+        let mut glyf = vec![];
+        // numberOfContours, bbox, endPtsOfContours and instructionLength
+        for value in [1i16, 0, 0, 100, 100, 9, 0] {
+            glyf.extend(value.to_be_bytes());
+        }
+        // flags for only 2 of the 10 points and no coordinates
+        glyf.extend([1u8, 1]);
+        let mut loca = vec![];
+        for offset in [0u16, 0, glyf.len() as u16 / 2] {
+            loca.extend(offset.to_be_bytes());
+        }
+        let head = Head {
+            units_per_em: 1000,
+            index_to_loc_format: 0,
+            ..Default::default()
+        };
+        FontBuilder::new()
+            .add_table(&head)
+            .unwrap()
+            .add_table(&Maxp::new(2))
+            .unwrap()
+            .add_table(&Hhea {
+                number_of_long_metrics: 2,
+                ..Default::default()
+            })
+            .unwrap()
+            .add_table(&Hmtx::new(vec![LongMetric::new(500, 0); 2], vec![]))
+            .unwrap()
+            .add_raw(Tag::new(b"glyf"), glyf)
+            .add_raw(Tag::new(b"loca"), loca)
+            .build()
+    }
+
+    #[test]
+    fn error_policy_empty_outline() {
+        let font_data = truncated_glyph_font();
+        let font = FontRef::new(&font_data).unwrap();
+        let glyph = font.outline_glyphs().get(GlyphId::new(1)).unwrap();
+        let settings = || DrawSettings::unhinted(Size::unscaled(), LocationRef::default());
+        assert!(matches!(
+            glyph.draw(settings(), &mut super::pen::NullPen),
+            Err(DrawError::Read(_))
+        ));
+        let mut path: Vec<super::pen::PathElement> = vec![];
+        let metrics = glyph
+            .draw(
+                settings().with_error_policy(DrawErrorPolicy::EmptyOutline),
+                &mut path,
+            )
+            .unwrap();
+        assert!(matches!(metrics.recovered_error, Some(DrawError::Read(_))));
+        assert!(path.is_empty());
+    }
+
+    #[test]
+    fn error_policy_empty_outline_success() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let glyph = font.outline_glyphs().get(GlyphId::new(1)).unwrap();
+        let settings = || DrawSettings::unhinted(Size::new(16.0), LocationRef::default());
+        let mut expected: Vec<super::pen::PathElement> = vec![];
+        glyph.draw(settings(), &mut expected).unwrap();
+        let mut path: Vec<super::pen::PathElement> = vec![];
+        let metrics = glyph
+            .draw(
+                settings().with_error_policy(DrawErrorPolicy::EmptyOutline),
+                &mut path,
+            )
+            .unwrap();
+        assert!(metrics.recovered_error.is_none());
+        assert!(!path.is_empty());
+        assert_eq!(path, expected);
+    }
+
+    /// Collects the coordinates emitted to a fixed point pen.
+    #[derive(Default)]
+    struct FixedPointCoords(Vec<i32>);
+
+    impl FixedPointOutlinePen for FixedPointCoords {
+        fn move_to(&mut self, x: F26Dot6, y: F26Dot6) {
+            self.0.extend([x, y].map(F26Dot6::to_bits));
+        }
+
+        fn line_to(&mut self, x: F26Dot6, y: F26Dot6) {
+            self.0.extend([x, y].map(F26Dot6::to_bits));
+        }
+
+        fn quad_to(&mut self, cx0: F26Dot6, cy0: F26Dot6, x: F26Dot6, y: F26Dot6) {
+            self.0.extend([cx0, cy0, x, y].map(F26Dot6::to_bits));
+        }
+
+        fn curve_to(
+            &mut self,
+            cx0: F26Dot6,
+            cy0: F26Dot6,
+            cx1: F26Dot6,
+            cy1: F26Dot6,
+            x: F26Dot6,
+            y: F26Dot6,
+        ) {
+            self.0
+                .extend([cx0, cy0, cx1, cy1, x, y].map(F26Dot6::to_bits));
+        }
+
+        fn close(&mut self) {}
+    }
+
+    #[test]
+    fn draw_fixed_point_hinted() {
+        use super::pen::PathElement;
+        let font = FontRef::new(font_test_data::TTHINT_SUBSET).unwrap();
+        let glyphs = font.outline_glyphs();
+        let hinting = HintingInstance::new(
+            &glyphs,
+            Size::new(16.0),
+            LocationRef::default(),
+            HintingOptions::default(),
+        )
+        .unwrap();
+        let glyph = glyphs.get(GlyphId::new(1)).unwrap();
+        let mut coords = FixedPointCoords::default();
+        glyph
+            .draw_fixed_point(&hinting, &mut FixedPointPen::new(&mut coords))
+            .unwrap();
+        let mut path: Vec<super::pen::PathElement> = vec![];
+        glyph.draw(&hinting, &mut path).unwrap();
+        let expected = path
+            .iter()
+            .flat_map(|el| match *el {
+                PathElement::MoveTo { x, y } | PathElement::LineTo { x, y } => vec![x, y],
+                PathElement::QuadTo { cx0, cy0, x, y } => vec![cx0, cy0, x, y],
+                PathElement::CurveTo {
+                    cx0,
+                    cy0,
+                    cx1,
+                    cy1,
+                    x,
+                    y,
+                } => vec![cx0, cy0, cx1, cy1, x, y],
+                PathElement::Close => vec![],
+            })
+            .map(|c| (c * 64.0) as i32)
+            .collect::<Vec<_>>();
+        assert!(!coords.0.is_empty());
+        assert_eq!(coords.0, expected);
+
+        // and the error policy applies
+        let font_data = truncated_glyph_font();
+        let font = FontRef::new(&font_data).unwrap();
+        let glyph = font.outline_glyphs().get(GlyphId::new(1)).unwrap();
+        let settings = DrawSettings::unhinted(Size::unscaled(), LocationRef::default())
+            .with_error_policy(DrawErrorPolicy::EmptyOutline);
+        let mut coords = FixedPointCoords::default();
+        let metrics = glyph
+            .draw_fixed_point(settings, &mut FixedPointPen::new(&mut coords))
+            .unwrap();
+        assert!(matches!(metrics.recovered_error, Some(DrawError::Read(_))));
+        assert!(coords.0.is_empty());
+    }
+
+    #[test]
+    fn content_hash() {
+        for font_data in [
+            font_test_data::VAZIRMATN_VAR,
+            font_test_data::CANTARELL_VF_TRIMMED,
+        ] {
+            let font = FontRef::new(font_data).unwrap();
+            let outlines = font.outline_glyphs();
+            let [glyph1, glyph2] = [2, 3].map(|gid| outlines.get(GlyphId::new(gid)).unwrap());
+            let default = LocationRef::default();
+            let coords = [NormalizedCoord::from_f32(0.5)];
+            let varied = LocationRef::new(&coords);
+            // stable for the same glyph and location
+            assert_eq!(glyph1.content_hash(default), glyph1.content_hash(default));
+            assert_eq!(glyph1.content_hash(varied), glyph1.content_hash(varied));
+            // explicit default coordinates match an empty location
+            let zeros = [NormalizedCoord::default()];
+            assert_eq!(
+                glyph1.content_hash(default),
+                glyph1.content_hash(LocationRef::new(&zeros))
+            );
+            // differs by glyph and by location
+            assert_ne!(glyph1.content_hash(default), glyph2.content_hash(default));
+            assert_ne!(glyph1.content_hash(default), glyph1.content_hash(varied));
+        }
+    }
+
+    #[test]
+    fn content_hash_static() {
+        let font = FontRef::new(font_test_data::NOTO_SERIF_DISPLAY_TRIMMED).unwrap();
+        let outlines = font.outline_glyphs();
+        let glyph = outlines.get(GlyphId::new(1)).unwrap();
+        // no variations so location is ignored
+        let coords = [NormalizedCoord::from_f32(0.5)];
+        assert_eq!(
+            glyph.content_hash(LocationRef::default()),
+            glyph.content_hash(LocationRef::new(&coords))
+        );
+    }
 }
@@ -1,10 +1,14 @@
 //! TrueType style outline to path conversion.
 
+#[cfg(feature = "libm")]
+#[allow(unused_imports)]
+use core_maths::CoreFloat;
+
 use super::pen::{OutlinePen, PathStyle};
 use core::fmt;
 use raw::{
     tables::glyf::{PointCoord, PointFlags},
-    types::Point,
+    types::{F26Dot6, Fixed, Point},
 };
 
 /// Errors that can occur when converting an outline to a path.
@@ -46,23 +50,113 @@ impl fmt::Display for ToPathError {
     }
 }
 
+/// A coordinate type in which outline sources generate path commands.
+///
+/// TrueType outlines (both scaled and hinted) and autohinted outlines are
+/// generated in 26.6 fixed point, CFF outlines in 16.16 fixed point and
+/// unhinted outlines with the HarfBuzz path style in `f32`. Coordinates are
+/// only converted to the format required by the receiving pen.
+pub(crate) trait PenCoord: Copy {
+    fn to_f32(self) -> f32;
+
+    /// Converts to 26.6, rounding to the nearest 1/64 if necessary.
+    fn to_f26dot6(self) -> F26Dot6;
+}
+
+impl PenCoord for F26Dot6 {
+    fn to_f32(self) -> f32 {
+        self.to_f32()
+    }
+
+    fn to_f26dot6(self) -> F26Dot6 {
+        self
+    }
+}
+
+impl PenCoord for Fixed {
+    fn to_f32(self) -> f32 {
+        self.to_f32()
+    }
+
+    fn to_f26dot6(self) -> F26Dot6 {
+        self.to_f26dot6()
+    }
+}
+
+impl PenCoord for f32 {
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn to_f26dot6(self) -> F26Dot6 {
+        // Scaling by a power of two is exact in f32
+        F26Dot6::from_bits((self * 64.0).round() as i32)
+    }
+}
+
+/// Receiver for the path commands generated when drawing an outline.
+///
+/// This is implemented for every [`OutlinePen`], which receive `f32`
+/// coordinates, and for [`FixedPointPen`](super::pen::FixedPointPen) which
+/// receives coordinates in the native format of the outline source without
+/// an intermediate conversion to `f32`.
+pub(crate) trait PathSink {
+    fn move_to<C: PenCoord>(&mut self, x: C, y: C);
+    fn line_to<C: PenCoord>(&mut self, x: C, y: C);
+    fn quad_to<C: PenCoord>(&mut self, cx0: C, cy0: C, x: C, y: C);
+    #[allow(clippy::too_many_arguments)]
+    fn curve_to<C: PenCoord>(&mut self, cx0: C, cy0: C, cx1: C, cy1: C, x: C, y: C);
+    fn close(&mut self);
+}
+
+impl<P: OutlinePen + ?Sized> PathSink for P {
+    fn move_to<C: PenCoord>(&mut self, x: C, y: C) {
+        OutlinePen::move_to(self, x.to_f32(), y.to_f32());
+    }
+
+    fn line_to<C: PenCoord>(&mut self, x: C, y: C) {
+        OutlinePen::line_to(self, x.to_f32(), y.to_f32());
+    }
+
+    fn quad_to<C: PenCoord>(&mut self, cx0: C, cy0: C, x: C, y: C) {
+        OutlinePen::quad_to(self, cx0.to_f32(), cy0.to_f32(), x.to_f32(), y.to_f32());
+    }
+
+    fn curve_to<C: PenCoord>(&mut self, cx0: C, cy0: C, cx1: C, cy1: C, x: C, y: C) {
+        OutlinePen::curve_to(
+            self,
+            cx0.to_f32(),
+            cy0.to_f32(),
+            cx1.to_f32(),
+            cy1.to_f32(),
+            x.to_f32(),
+            y.to_f32(),
+        );
+    }
+
+    fn close(&mut self) {
+        OutlinePen::close(self);
+    }
+}
+
 /// Converts a `glyf` outline described by points, flags and contour end points
 /// to a sequence of path elements and invokes the appropriate callback on the
 /// given pen for each.
 ///
 /// The input points can have any coordinate type that implements
-/// [`PointCoord`]. Output points are always generated in `f32`.
+/// [`PointCoord`]. Output points are passed to the pen without conversion
+/// and are converted to `f32` for an [`OutlinePen`].
 ///
 /// This is roughly equivalent to [`FT_Outline_Decompose`](https://freetype.org/freetype2/docs/reference/ft2-outline_processing.html#ft_outline_decompose).
 ///
 /// See [`contour_to_path`] for a more general function that takes an iterator
 /// if your outline data is in a different format.
-pub(crate) fn to_path<C: PointCoord>(
+pub(crate) fn to_path<C: PointCoord + PenCoord>(
     points: &[Point<C>],
     flags: &[PointFlags],
     contours: &[u16],
     path_style: PathStyle,
-    pen: &mut impl OutlinePen,
+    pen: &mut impl PathSink,
 ) -> Result<(), ToPathError> {
     for contour_ix in 0..contours.len() {
         let start_ix = (contour_ix > 0)
@@ -123,8 +217,8 @@ impl<T> ContourPoint<T>
 where
     T: PointCoord,
 {
-    fn point_f32(&self) -> Point<f32> {
-        Point::new(self.x.to_f32(), self.y.to_f32())
+    fn point(&self) -> Point<T> {
+        Point::new(self.x, self.y)
     }
 
     fn midpoint(&self, other: Self) -> ContourPoint<T> {
@@ -146,11 +240,11 @@ where
 ///
 /// This is more general than [`to_path`] and exists to support cases (such as
 /// autohinting) where the source outline data is in a different format.
-pub(crate) fn contour_to_path<C: PointCoord>(
+pub(crate) fn contour_to_path<C: PointCoord + PenCoord>(
     points: impl Iterator<Item = ContourPoint<C>>,
     last_point: ContourPoint<C>,
     style: PathStyle,
-    pen: &mut impl OutlinePen,
+    pen: &mut impl PathSink,
 ) -> Result<(), ToPathError> {
     let mut points = points.enumerate().peekable();
     let Some((_, first_point)) = points.peek().copied() else {
@@ -207,7 +301,7 @@ pub(crate) fn contour_to_path<C: PointCoord>(
         points.next();
         first_point
     };
-    let point = start_point.point_f32();
+    let point = start_point.point();
     pen.move_to(point.x, point.y);
     let mut state = PendingState::default();
     if omit_last {
@@ -244,14 +338,14 @@ enum PendingState<C> {
 
 impl<C> PendingState<C>
 where
-    C: PointCoord,
+    C: PointCoord + PenCoord,
 {
     #[inline(always)]
     fn emit(
         &mut self,
         ix: usize,
         point: ContourPoint<C>,
-        pen: &mut impl OutlinePen,
+        pen: &mut impl PathSink,
     ) -> Result<(), ToPathError> {
         let flags = point.flags;
         match *self {
@@ -261,21 +355,21 @@ where
                 } else if flags.is_off_curve_cubic() {
                     *self = Self::PendingCubic(point);
                 } else {
-                    let p = point.point_f32();
+                    let p = point.point();
                     pen.line_to(p.x, p.y);
                 }
             }
             Self::PendingQuad(quad) => {
                 if flags.is_off_curve_quad() {
-                    let c0 = quad.point_f32();
-                    let p = quad.midpoint(point).point_f32();
+                    let c0 = quad.point();
+                    let p = quad.midpoint(point).point();
                     pen.quad_to(c0.x, c0.y, p.x, p.y);
                     *self = Self::PendingQuad(point);
                 } else if flags.is_off_curve_cubic() {
                     return Err(ToPathError::ExpectedQuadOrOnCurve(ix));
                 } else {
-                    let c0 = quad.point_f32();
-                    let p = point.point_f32();
+                    let c0 = quad.point();
+                    let p = point.point();
                     pen.quad_to(c0.x, c0.y, p.x, p.y);
                     *self = Self::Empty;
                 }
@@ -291,15 +385,15 @@ where
                 if flags.is_off_curve_quad() {
                     return Err(ToPathError::ExpectedCubic(ix));
                 } else if flags.is_off_curve_cubic() {
-                    let c0 = cubic0.point_f32();
-                    let c1 = cubic1.point_f32();
-                    let p = cubic1.midpoint(point).point_f32();
+                    let c0 = cubic0.point();
+                    let c1 = cubic1.point();
+                    let p = cubic1.midpoint(point).point();
                     pen.curve_to(c0.x, c0.y, c1.x, c1.y, p.x, p.y);
                     *self = Self::PendingCubic(point);
                 } else {
-                    let c0 = cubic0.point_f32();
-                    let c1 = cubic1.point_f32();
-                    let p = point.point_f32();
+                    let c0 = cubic0.point();
+                    let c1 = cubic1.point();
+                    let p = point.point();
                     pen.curve_to(c0.x, c0.y, c1.x, c1.y, p.x, p.y);
                     *self = Self::Empty;
                 }
@@ -312,7 +406,7 @@ where
         mut self,
         start_ix: usize,
         mut start_point: ContourPoint<C>,
-        pen: &mut impl OutlinePen,
+        pen: &mut impl PathSink,
     ) -> Result<(), ToPathError> {
         match self {
             Self::Empty => {}
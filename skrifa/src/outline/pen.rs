@@ -1,7 +1,13 @@
 //! Types for collecting the output when drawing a glyph outline.
 
+#[cfg(feature = "libm")]
+#[allow(unused_imports)]
+use core_maths::CoreFloat;
+
+use super::path::{self, PenCoord};
 use alloc::{string::String, vec::Vec};
 use core::fmt::{self, Write};
+use raw::types::F26Dot6;
 
 /// Interface for accepting a sequence of path commands.
 pub trait OutlinePen {
@@ -47,6 +53,26 @@ pub enum PathElement {
     Close,
 }
 
+impl PathElement {
+    /// Emits this element to the given pen.
+    pub(crate) fn apply(self, pen: &mut impl OutlinePen) {
+        match self {
+            Self::MoveTo { x, y } => pen.move_to(x, y),
+            Self::LineTo { x, y } => pen.line_to(x, y),
+            Self::QuadTo { cx0, cy0, x, y } => pen.quad_to(cx0, cy0, x, y),
+            Self::CurveTo {
+                cx0,
+                cy0,
+                cx1,
+                cy1,
+                x,
+                y,
+            } => pen.curve_to(cx0, cy0, cx1, cy1, x, y),
+            Self::Close => pen.close(),
+        }
+    }
+}
+
 /// Style for path conversion.
 ///
 /// The order to process points in a glyf point stream is ambiguous when the
@@ -217,6 +243,184 @@ impl fmt::Display for SvgPen {
     }
 }
 
+/// Interface for accepting a sequence of path commands with coordinates
+/// in 26.6 fixed point format.
+///
+/// This matches the native precision of the TrueType hinting engine. Wrap
+/// an implementation of this trait in a [`FixedPointPen`] to draw with
+/// [`OutlineGlyph::draw_fixed_point`](super::OutlineGlyph::draw_fixed_point).
+pub trait FixedPointOutlinePen {
+    /// Emit a command to begin a new subpath at (x, y).
+    fn move_to(&mut self, x: F26Dot6, y: F26Dot6);
+
+    /// Emit a line segment from the current point to (x, y).
+    fn line_to(&mut self, x: F26Dot6, y: F26Dot6);
+
+    /// Emit a quadratic bezier segment from the current point with a control
+    /// point at (cx0, cy0) and ending at (x, y).
+    fn quad_to(&mut self, cx0: F26Dot6, cy0: F26Dot6, x: F26Dot6, y: F26Dot6);
+
+    /// Emit a cubic bezier segment from the current point with control
+    /// points at (cx0, cy0) and (cx1, cy1) and ending at (x, y).
+    #[allow(clippy::too_many_arguments)]
+    fn curve_to(
+        &mut self,
+        cx0: F26Dot6,
+        cy0: F26Dot6,
+        cx1: F26Dot6,
+        cy1: F26Dot6,
+        x: F26Dot6,
+        y: F26Dot6,
+    );
+
+    /// Emit a command to close the current subpath.
+    fn close(&mut self);
+}
+
+/// Adapter that forwards the path commands generated by
+/// [`OutlineGlyph::draw_fixed_point`] to a [`FixedPointOutlinePen`].
+///
+/// Coordinates are converted from the native format of the outline source.
+/// TrueType outlines, hinted or not, and autohinted outlines are computed in
+/// 26.6 so their coordinates are forwarded unchanged and match the output of
+/// the hinting engine. CFF outlines are rounded from 16.16 and outlines drawn
+/// with [`PathStyle::HarfBuzz`] are rounded from `f32`.
+///
+/// When created with [`snapped`](Self::snapped), all coordinates are
+/// additionally rounded to whole pixels.
+///
+/// [`OutlineGlyph::draw_fixed_point`]: super::OutlineGlyph::draw_fixed_point
+pub struct FixedPointPen<'a, P> {
+    pen: &'a mut P,
+    snap: bool,
+}
+
+impl<'a, P: FixedPointOutlinePen> FixedPointPen<'a, P> {
+    /// Creates a new adapter that forwards coordinates to the given pen
+    /// with full 26.6 precision.
+    pub fn new(pen: &'a mut P) -> Self {
+        Self { pen, snap: false }
+    }
+
+    /// Creates a new adapter that forwards coordinates to the given pen
+    /// rounded to whole pixels.
+    pub fn snapped(pen: &'a mut P) -> Self {
+        Self { pen, snap: true }
+    }
+
+    /// Creates an adapter with the same rounding that buffers the path.
+    pub(crate) fn buffered<'b>(
+        &self,
+        path: &'b mut Vec<FixedPathElement>,
+    ) -> FixedPointPen<'b, Vec<FixedPathElement>> {
+        FixedPointPen {
+            pen: path,
+            snap: self.snap,
+        }
+    }
+
+    /// Emits a path buffered by an adapter created with
+    /// [`buffered`](Self::buffered).
+    pub(crate) fn replay(&mut self, path: Vec<FixedPathElement>) {
+        for element in path {
+            element.apply(self.pen);
+        }
+    }
+
+    fn convert(&self, value: impl PenCoord) -> F26Dot6 {
+        let value = value.to_f26dot6();
+        if self.snap {
+            value.round()
+        } else {
+            value
+        }
+    }
+}
+
+impl<P: FixedPointOutlinePen> path::PathSink for FixedPointPen<'_, P> {
+    fn move_to<C: PenCoord>(&mut self, x: C, y: C) {
+        let (x, y) = (self.convert(x), self.convert(y));
+        self.pen.move_to(x, y);
+    }
+
+    fn line_to<C: PenCoord>(&mut self, x: C, y: C) {
+        let (x, y) = (self.convert(x), self.convert(y));
+        self.pen.line_to(x, y);
+    }
+
+    fn quad_to<C: PenCoord>(&mut self, cx0: C, cy0: C, x: C, y: C) {
+        let (cx0, cy0) = (self.convert(cx0), self.convert(cy0));
+        let (x, y) = (self.convert(x), self.convert(y));
+        self.pen.quad_to(cx0, cy0, x, y);
+    }
+
+    fn curve_to<C: PenCoord>(&mut self, cx0: C, cy0: C, cx1: C, cy1: C, x: C, y: C) {
+        let (cx0, cy0) = (self.convert(cx0), self.convert(cy0));
+        let (cx1, cy1) = (self.convert(cx1), self.convert(cy1));
+        let (x, y) = (self.convert(x), self.convert(y));
+        self.pen.curve_to(cx0, cy0, cx1, cy1, x, y);
+    }
+
+    fn close(&mut self) {
+        self.pen.close();
+    }
+}
+
+/// Single element of a path with 26.6 fixed point coordinates.
+///
+/// Used to buffer the path when drawing with
+/// [`DrawErrorPolicy::EmptyOutline`](super::DrawErrorPolicy::EmptyOutline).
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum FixedPathElement {
+    MoveTo([F26Dot6; 2]),
+    LineTo([F26Dot6; 2]),
+    QuadTo([F26Dot6; 4]),
+    CurveTo([F26Dot6; 6]),
+    Close,
+}
+
+impl FixedPathElement {
+    fn apply(self, pen: &mut impl FixedPointOutlinePen) {
+        match self {
+            Self::MoveTo([x, y]) => pen.move_to(x, y),
+            Self::LineTo([x, y]) => pen.line_to(x, y),
+            Self::QuadTo([cx0, cy0, x, y]) => pen.quad_to(cx0, cy0, x, y),
+            Self::CurveTo([cx0, cy0, cx1, cy1, x, y]) => pen.curve_to(cx0, cy0, cx1, cy1, x, y),
+            Self::Close => pen.close(),
+        }
+    }
+}
+
+impl FixedPointOutlinePen for Vec<FixedPathElement> {
+    fn move_to(&mut self, x: F26Dot6, y: F26Dot6) {
+        self.push(FixedPathElement::MoveTo([x, y]))
+    }
+
+    fn line_to(&mut self, x: F26Dot6, y: F26Dot6) {
+        self.push(FixedPathElement::LineTo([x, y]))
+    }
+
+    fn quad_to(&mut self, cx0: F26Dot6, cy0: F26Dot6, x: F26Dot6, y: F26Dot6) {
+        self.push(FixedPathElement::QuadTo([cx0, cy0, x, y]))
+    }
+
+    fn curve_to(
+        &mut self,
+        cx0: F26Dot6,
+        cy0: F26Dot6,
+        cx1: F26Dot6,
+        cy1: F26Dot6,
+        x: F26Dot6,
+        y: F26Dot6,
+    ) {
+        self.push(FixedPathElement::CurveTo([cx0, cy0, cx1, cy1, x, y]))
+    }
+
+    fn close(&mut self) {
+        self.push(FixedPathElement::Close)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -244,4 +448,98 @@ mod tests {
             assert_eq!(result, expected);
         }
     }
+
+    #[derive(Default)]
+    struct FixedPointCommands(Vec<(char, Vec<i32>)>);
+
+    impl FixedPointOutlinePen for FixedPointCommands {
+        fn move_to(&mut self, x: F26Dot6, y: F26Dot6) {
+            self.0.push(('M', vec![x.to_bits(), y.to_bits()]));
+        }
+
+        fn line_to(&mut self, x: F26Dot6, y: F26Dot6) {
+            self.0.push(('L', vec![x.to_bits(), y.to_bits()]));
+        }
+
+        fn quad_to(&mut self, cx0: F26Dot6, cy0: F26Dot6, x: F26Dot6, y: F26Dot6) {
+            let coords = [cx0, cy0, x, y];
+            self.0
+                .push(('Q', coords.iter().map(|c| c.to_bits()).collect()));
+        }
+
+        fn curve_to(
+            &mut self,
+            cx0: F26Dot6,
+            cy0: F26Dot6,
+            cx1: F26Dot6,
+            cy1: F26Dot6,
+            x: F26Dot6,
+            y: F26Dot6,
+        ) {
+            let coords = [cx0, cy0, cx1, cy1, x, y];
+            self.0
+                .push(('C', coords.iter().map(|c| c.to_bits()).collect()));
+        }
+
+        fn close(&mut self) {
+            self.0.push(('Z', vec![]));
+        }
+    }
+
+    fn draw_fixed_point(pen: &mut impl path::PathSink) {
+        pen.move_to(1.0, -2.5);
+        pen.line_to(1.015625, 4.0);
+        pen.quad_to(2.0345, 3.5, -0.75, -425.0);
+        pen.curve_to(-37.0, 4.5, 2.0, 1.25, -0.5, 0.49);
+        pen.close();
+    }
+
+    #[test]
+    fn fixed_point_pen() {
+        let mut commands = FixedPointCommands::default();
+        draw_fixed_point(&mut FixedPointPen::new(&mut commands));
+        let expected = [
+            ('M', vec![64, -160]),
+            ('L', vec![65, 256]),
+            ('Q', vec![130, 224, -48, -27200]),
+            ('C', vec![-2368, 288, 128, 80, -32, 31]),
+            ('Z', vec![]),
+        ];
+        assert_eq!(commands.0, expected);
+    }
+
+    #[test]
+    fn snapped_fixed_point_pen() {
+        let mut commands = FixedPointCommands::default();
+        draw_fixed_point(&mut FixedPointPen::snapped(&mut commands));
+        let expected = [
+            ('M', vec![64, -128]),
+            ('L', vec![64, 256]),
+            ('Q', vec![128, 256, -64, -27200]),
+            ('C', vec![-2368, 320, 128, 64, 0, 0]),
+            ('Z', vec![]),
+        ];
+        assert_eq!(commands.0, expected);
+    }
+
+    #[test]
+    fn fixed_point_pen_native_coords() {
+        use path::PathSink;
+        use raw::types::Fixed;
+        let mut commands = FixedPointCommands::default();
+        let mut pen = FixedPointPen::new(&mut commands);
+        // 26.6 is forwarded unchanged, even beyond the range where f32 is
+        // exact
+        let big = F26Dot6::from_bits((1 << 25) + 1);
+        pen.move_to(big, F26Dot6::from_bits(-3));
+        // 16.16 is rounded to the nearest 1/64
+        pen.line_to(Fixed::from_bits(1023), Fixed::from_bits(-1025));
+        pen.close();
+        let expected = [
+            ('M', vec![(1 << 25) + 1, -3]),
+            ('L', vec![1, -1]),
+            ('Z', vec![]),
+        ];
+        assert_eq!(commands.0, expected);
+    }
 }
@@ -1,13 +1,17 @@
 use super::{
+    anchor::GlyphAnchors,
     attribute::Attributes,
+    capabilities::Capabilities,
     charmap::Charmap,
     color::ColorGlyphCollection,
+    glyph_names::GlyphNames,
     instance::{LocationRef, Size},
-    metrics::{GlyphMetrics, Metrics},
+    math::MathConstants,
+    metrics::{Baselines, GlyphMetrics, Metrics, VerticalMetrics},
     outline::OutlineGlyphCollection,
     string::{LocalizedStrings, StringId},
     variation::{AxisCollection, NamedInstanceCollection},
-    FontRef,
+    FontRef, Tag,
 };
 
 /// Interface for types that can provide font metadata.
@@ -16,6 +20,9 @@ pub trait MetadataProvider<'a>: Sized {
     /// style and weight.
     fn attributes(&self) -> Attributes;
 
+    /// Returns a summary of the functionality supported by the font.
+    fn capabilities(&self) -> Capabilities;
+
     /// Returns the collection of variation axes.
     fn axes(&self) -> AxisCollection<'a>;
 
@@ -30,13 +37,34 @@ pub trait MetadataProvider<'a>: Sized {
     /// normalized variation space.
     fn metrics(&self, size: Size, location: impl Into<LocationRef<'a>>) -> Metrics;
 
+    /// Returns the global font metrics for vertical layout for the specified
+    /// size and location in normalized variation space.
+    fn vertical_metrics(&self, size: Size, location: impl Into<LocationRef<'a>>)
+        -> VerticalMetrics;
+
+    /// Returns the horizontal baseline positions for the specified script
+    /// and location in normalized variation space.
+    fn baselines(&self, script: Tag, location: impl Into<LocationRef<'a>>) -> Baselines;
+
+    /// Returns the constants for mathematical layout for the specified size
+    /// and location in normalized variation space.
+    fn math_constants(&self, size: Size, location: impl Into<LocationRef<'a>>)
+        -> MathConstants<'a>;
+
     /// Returns the glyph specific metrics for the specified size and location
     /// in normalized variation space.
     fn glyph_metrics(&self, size: Size, location: impl Into<LocationRef<'a>>) -> GlyphMetrics<'a>;
 
+    /// Returns the mark and cursive attachment anchors for the specified size
+    /// and location in normalized variation space.
+    fn glyph_anchors(&self, size: Size, location: impl Into<LocationRef<'a>>) -> GlyphAnchors<'a>;
+
     /// Returns the character to nominal glyph identifier mapping.
     fn charmap(&self) -> Charmap<'a>;
 
+    /// Returns the mapping from glyph identifiers to names.
+    fn glyph_names(&self) -> GlyphNames<'a>;
+
     /// Returns the collection of scalable glyph outlines.
     ///
     /// If the font contains multiple outline sources, this method prioritizes
@@ -55,6 +83,11 @@ impl<'a> MetadataProvider<'a> for FontRef<'a> {
         Attributes::new(self)
     }
 
+    /// Returns a summary of the functionality supported by the font.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(self)
+    }
+
     /// Returns the collection of variation axes.
     fn axes(&self) -> AxisCollection<'a> {
         AxisCollection::new(self)
@@ -77,17 +110,54 @@ impl<'a> MetadataProvider<'a> for FontRef<'a> {
         Metrics::new(self, size, location)
     }
 
+    /// Returns the global font metrics for vertical layout for the specified
+    /// size and location in normalized variation space.
+    fn vertical_metrics(
+        &self,
+        size: Size,
+        location: impl Into<LocationRef<'a>>,
+    ) -> VerticalMetrics {
+        VerticalMetrics::new(self, size, location)
+    }
+
+    /// Returns the horizontal baseline positions for the specified script
+    /// and location in normalized variation space.
+    fn baselines(&self, script: Tag, location: impl Into<LocationRef<'a>>) -> Baselines {
+        Baselines::new(self, script, location)
+    }
+
+    /// Returns the constants for mathematical layout for the specified size
+    /// and location in normalized variation space.
+    fn math_constants(
+        &self,
+        size: Size,
+        location: impl Into<LocationRef<'a>>,
+    ) -> MathConstants<'a> {
+        MathConstants::new(self, size, location)
+    }
+
     /// Returns the glyph specific metrics for the specified size and location
     /// in normalized variation space.
     fn glyph_metrics(&self, size: Size, location: impl Into<LocationRef<'a>>) -> GlyphMetrics<'a> {
         GlyphMetrics::new(self, size, location)
     }
 
+    /// Returns the mark and cursive attachment anchors for the specified size
+    /// and location in normalized variation space.
+    fn glyph_anchors(&self, size: Size, location: impl Into<LocationRef<'a>>) -> GlyphAnchors<'a> {
+        GlyphAnchors::new(self, size, location)
+    }
+
     /// Returns the character to nominal glyph identifier mapping.
     fn charmap(&self) -> Charmap<'a> {
         Charmap::new(self)
     }
 
+    /// Returns the mapping from glyph identifiers to names.
+    fn glyph_names(&self) -> GlyphNames<'a> {
+        GlyphNames::new(self)
+    }
+
     /// Returns the collection of scalable glyph outlines.
     ///
     /// If the font contains multiple outline sources, this method prioritizes
@@ -22,17 +22,279 @@
 //!     }
 //! }
 //! ```
+//!
+//! The [`LocalizedStrings::family_names`] and [`LocalizedStrings::subfamily_names`]
+//! functions resolve the family and style names according to a given
+//! [`FamilyNameModel`], following the precedence rules of the `name` table
+//! specification:
+//! ```
+//! use skrifa::string::{FamilyNameModel, LocalizedStrings};
+//! # fn family_and_style<'a>(font: &impl skrifa::raw::TableProvider<'a>) -> Option<(String, String)> {
+//! let family = LocalizedStrings::family_names(font, FamilyNameModel::Typographic)
+//!     .english_or_first()?;
+//! let style = LocalizedStrings::subfamily_names(font, FamilyNameModel::Typographic)
+//!     .english_or_first()?;
+//! Some((family.to_string(), style.to_string()))
+//! # }
+//! ```
 
 use read_fonts::{
-    tables::name::{CharIter, Name, NameRecord, NameString},
+    tables::{
+        name::{CharIter, Name, NameRecord, NameString},
+        stat::{AxisValue, AxisValueTableFlags},
+    },
+    types::Fixed,
     TableProvider,
 };
 
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::fmt;
 
+use crate::setting::VariationSetting;
+
 #[doc(inline)]
 pub use read_fonts::types::NameId as StringId;
 
+/// Model used to group fonts into families for selection.
+///
+/// See the notes on name identifiers 1, 2, 16, 17, 21 and 22 in the
+/// [name](https://learn.microsoft.com/en-us/typography/opentype/spec/name#name-ids)
+/// table specification.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum FamilyNameModel {
+    /// Legacy "R/I/B/BI" model where each family contains at most four
+    /// styles.
+    ///
+    /// Uses the family (1) and subfamily (2) names.
+    Legacy,
+    /// Typographic model where a family may contain any number of styles.
+    ///
+    /// Uses the typographic family (16) and subfamily (17) names, falling
+    /// back to the legacy names if not present.
+    #[default]
+    Typographic,
+    /// Model where styles in a family differ only in weight, width and
+    /// slope.
+    ///
+    /// Uses the WWS family (21) and subfamily (22) names, falling back to
+    /// the typographic and then legacy names if not present.
+    Wws,
+}
+
+impl FamilyNameModel {
+    /// Returns the family name identifiers for this model in order of
+    /// precedence.
+    pub fn family_name_ids(self) -> &'static [StringId] {
+        match self {
+            Self::Legacy => &[StringId::FAMILY_NAME],
+            Self::Typographic => &[StringId::TYPOGRAPHIC_FAMILY_NAME, StringId::FAMILY_NAME],
+            Self::Wws => &[
+                StringId::WWS_FAMILY_NAME,
+                StringId::TYPOGRAPHIC_FAMILY_NAME,
+                StringId::FAMILY_NAME,
+            ],
+        }
+    }
+
+    /// Returns the subfamily name identifiers for this model in order of
+    /// precedence.
+    pub fn subfamily_name_ids(self) -> &'static [StringId] {
+        match self {
+            Self::Legacy => &[StringId::SUBFAMILY_NAME],
+            Self::Typographic => &[
+                StringId::TYPOGRAPHIC_SUBFAMILY_NAME,
+                StringId::SUBFAMILY_NAME,
+            ],
+            Self::Wws => &[
+                StringId::WWS_SUBFAMILY_NAME,
+                StringId::TYPOGRAPHIC_SUBFAMILY_NAME,
+                StringId::SUBFAMILY_NAME,
+            ],
+        }
+    }
+}
+
+/// Resolves the style name for a position in variation space using the
+/// axis value tables of the
+/// [STAT](https://learn.microsoft.com/en-us/typography/opentype/spec/stat)
+/// table.
+///
+/// Settings are given in user space. Axes without a setting use the
+/// default value from the `fvar` table, if available.
+///
+/// Returns the name identifiers of the matching axis values ordered by
+/// their axis ordering, skipping elidable values. Multi-axis values
+/// (format 4) take precedence over single axis values for the axes they
+/// cover. If all values are elided, this returns the elided fallback name
+/// identifier.
+///
+/// Returns `None` if the font has no `STAT` table or it contains no axis
+/// values.
+pub fn stat_style_name_ids<'a, I>(
+    font: &impl TableProvider<'a>,
+    settings: I,
+) -> Option<Vec<StringId>>
+where
+    I: IntoIterator,
+    I::Item: Into<VariationSetting>,
+{
+    let stat = font.stat().ok()?;
+    let design_axes = stat.design_axes().ok()?;
+    let axis_values = stat.axis_value_tables().collect::<Vec<_>>();
+    if axis_values.is_empty() {
+        return None;
+    }
+    // Build the location in design axis order
+    let fvar_axes = font.fvar().ok().and_then(|fvar| fvar.axes().ok());
+    let mut location: Vec<Option<Fixed>> = design_axes
+        .iter()
+        .map(|axis| {
+            fvar_axes?
+                .iter()
+                .find(|fvar_axis| fvar_axis.axis_tag() == axis.axis_tag())
+                .map(|fvar_axis| fvar_axis.default_value())
+        })
+        .collect();
+    for setting in settings {
+        let setting: VariationSetting = setting.into();
+        if let Some(ix) = stat.design_axis_index(setting.selector) {
+            location[ix as usize] = Some(Fixed::from_f64(setting.value as f64));
+        }
+    }
+    let value_at = |axis_index: u16| location.get(axis_index as usize).copied().flatten();
+    let ordering = |axis_index: u16| {
+        design_axes
+            .get(axis_index as usize)
+            .map(|axis| axis.axis_ordering())
+            .unwrap_or(u16::MAX)
+    };
+    let mut covered = alloc::vec![false; design_axes.len()];
+    // (ordering, name id, is elidable)
+    let mut selected: Vec<(u16, StringId, bool)> = Vec::new();
+    // Multi-axis values first, preferring those that cover the most axes
+    let mut format4 = axis_values
+        .iter()
+        .filter_map(|value| match value {
+            AxisValue::Format4(value) => Some(value),
+            _ => None,
+        })
+        .filter(|value| {
+            value
+                .axis_values()
+                .iter()
+                .all(|record| value_at(record.axis_index()) == Some(record.value()))
+        })
+        .collect::<Vec<_>>();
+    format4.sort_by_key(|value| core::cmp::Reverse(value.axis_count()));
+    for value in format4 {
+        let records = value.axis_values();
+        if records
+            .iter()
+            .any(|record| covered.get(record.axis_index() as usize) != Some(&false))
+        {
+            continue;
+        }
+        for record in records {
+            covered[record.axis_index() as usize] = true;
+        }
+        let order = records
+            .iter()
+            .map(|record| ordering(record.axis_index()))
+            .min()
+            .unwrap_or(u16::MAX);
+        selected.push((order, value.value_name_id(), is_elidable(value.flags())));
+    }
+    // Then single axis values for the remaining axes, preferring exact
+    // matches over ranges
+    for (axis_index, is_covered) in covered.iter().enumerate() {
+        let axis_index = axis_index as u16;
+        let Some(coord) = value_at(axis_index).filter(|_| !is_covered) else {
+            continue;
+        };
+        // (rank, value) where exact matches rank higher than ranges
+        let mut best: Option<(u8, &AxisValue)> = None;
+        for value in &axis_values {
+            let rank = match value {
+                AxisValue::Format1(value)
+                    if value.axis_index() == axis_index && value.value() == coord =>
+                {
+                    2
+                }
+                AxisValue::Format3(value)
+                    if value.axis_index() == axis_index && value.value() == coord =>
+                {
+                    2
+                }
+                AxisValue::Format2(value)
+                    if value.axis_index() == axis_index
+                        && value.range_min_value() <= coord
+                        && coord <= value.range_max_value() =>
+                {
+                    if value.nominal_value() == coord {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                _ => continue,
+            };
+            if best.map(|(best_rank, _)| rank > best_rank).unwrap_or(true) {
+                best = Some((rank, value));
+            }
+        }
+        if let Some((_, value)) = best {
+            selected.push((
+                ordering(axis_index),
+                value.value_name_id(),
+                is_elidable(value.flags()),
+            ));
+        }
+    }
+    selected.sort_by_key(|(order, ..)| *order);
+    let mut names: Vec<StringId> = selected
+        .iter()
+        .filter(|(_, _, elidable)| !elidable)
+        .map(|(_, name_id, _)| *name_id)
+        .collect();
+    if names.is_empty() {
+        names.push(
+            stat.elided_fallback_name_id()
+                .unwrap_or(StringId::SUBFAMILY_NAME),
+        );
+    }
+    Some(names)
+}
+
+/// Resolves the style name for a position in variation space using the
+/// [STAT](https://learn.microsoft.com/en-us/typography/opentype/spec/stat)
+/// table.
+///
+/// This resolves the name identifiers selected by [`stat_style_name_ids`]
+/// to their English (or first available) strings and joins them with
+/// spaces. Particles without a string in the `name` table are skipped.
+///
+/// Returns `None` if the font has no `STAT` table, it contains no axis
+/// values or none of the selected names are available.
+pub fn stat_style_name<'a, I>(font: &impl TableProvider<'a>, settings: I) -> Option<String>
+where
+    I: IntoIterator,
+    I::Item: Into<VariationSetting>,
+{
+    let particles = stat_style_name_ids(font, settings)?
+        .into_iter()
+        .filter_map(|id| LocalizedStrings::new(font, id).english_or_first())
+        .map(|string| string.to_string())
+        .collect::<Vec<_>>();
+    (!particles.is_empty()).then(|| particles.join(" "))
+}
+
+fn is_elidable(flags: AxisValueTableFlags) -> bool {
+    flags.contains(AxisValueTableFlags::ELIDABLE_AXIS_VALUE_NAME)
+}
+
 /// Iterator over the characters of a string.
 #[derive(Clone)]
 pub struct Chars<'a> {
@@ -66,6 +328,33 @@ impl<'a> LocalizedStrings<'a> {
         Self { name, records, id }
     }
 
+    /// Creates a new localized string iterator for the first identifier in
+    /// `ids` that has at least one entry in the font.
+    ///
+    /// Returns an empty iterator if none of the identifiers are present.
+    pub fn first_available(font: &impl TableProvider<'a>, ids: &[StringId]) -> Self {
+        ids.iter()
+            .map(|id| Self::new(font, *id))
+            .find(|strings| strings.clone().next().is_some())
+            .unwrap_or_default()
+    }
+
+    /// Creates a new localized string iterator for the family names of the
+    /// given font according to the precedence rules of `model`.
+    pub fn family_names(font: &impl TableProvider<'a>, model: FamilyNameModel) -> Self {
+        Self::first_available(font, model.family_name_ids())
+    }
+
+    /// Creates a new localized string iterator for the subfamily (style)
+    /// names of the given font according to the precedence rules of `model`.
+    ///
+    /// If the font has no subfamily names, consider using
+    /// [`Attributes::style_name`](crate::attribute::Attributes::style_name) to
+    /// synthesize one.
+    pub fn subfamily_names(font: &impl TableProvider<'a>, model: FamilyNameModel) -> Self {
+        Self::first_available(font, model.subfamily_name_ids())
+    }
+
     /// Returns the informational string identifier for this iterator.
     pub fn id(&self) -> StringId {
         self.id
@@ -646,4 +935,175 @@ mod tests {
             "Regular"
         );
     }
+
+    fn make_names_font(names: &[(StringId, &str)]) -> Vec<u8> {
+        use write_fonts::{
+            tables::name::{Name, NameRecord},
+            FontBuilder,
+        };
+        let records = names
+            .iter()
+            .map(|(id, value)| NameRecord::new(3, 1, 0x409, *id, value.to_string().into()))
+            .collect();
+        FontBuilder::new()
+            .add_table(&Name::new(records))
+            .unwrap()
+            .build()
+    }
+
+    fn resolve(font: &FontRef, model: FamilyNameModel) -> (String, String) {
+        let family = LocalizedStrings::family_names(font, model).english_or_first();
+        let subfamily = LocalizedStrings::subfamily_names(font, model).english_or_first();
+        (family.unwrap().to_string(), subfamily.unwrap().to_string())
+    }
+
+    #[test]
+    fn family_name_precedence() {
+        let data = make_names_font(&[
+            (StringId::FAMILY_NAME, "Family Light Narrow"),
+            (StringId::SUBFAMILY_NAME, "Italic"),
+            (StringId::TYPOGRAPHIC_FAMILY_NAME, "Family"),
+            (
+                StringId::TYPOGRAPHIC_SUBFAMILY_NAME,
+                "Light Narrow Italic Caption",
+            ),
+            (StringId::WWS_FAMILY_NAME, "Family Caption"),
+            (StringId::WWS_SUBFAMILY_NAME, "Light Narrow Italic"),
+        ]);
+        let font = FontRef::new(&data).unwrap();
+        assert_eq!(
+            resolve(&font, FamilyNameModel::Legacy),
+            ("Family Light Narrow".into(), "Italic".into())
+        );
+        assert_eq!(
+            resolve(&font, FamilyNameModel::Typographic),
+            ("Family".into(), "Light Narrow Italic Caption".into())
+        );
+        assert_eq!(
+            resolve(&font, FamilyNameModel::Wws),
+            ("Family Caption".into(), "Light Narrow Italic".into())
+        );
+    }
+
+    #[test]
+    fn family_name_fallback() {
+        let data = make_names_font(&[
+            (StringId::FAMILY_NAME, "Family"),
+            (StringId::SUBFAMILY_NAME, "Bold"),
+        ]);
+        let font = FontRef::new(&data).unwrap();
+        for model in [
+            FamilyNameModel::Legacy,
+            FamilyNameModel::Typographic,
+            FamilyNameModel::Wws,
+        ] {
+            assert_eq!(resolve(&font, model), ("Family".into(), "Bold".into()));
+        }
+        let data = make_names_font(&[]);
+        let font = FontRef::new(&data).unwrap();
+        assert!(LocalizedStrings::family_names(&font, FamilyNameModel::Wws)
+            .next()
+            .is_none());
+    }
+
+    fn make_stat_font() -> Vec<u8> {
+        use read_fonts::types::Tag;
+        use write_fonts::{
+            tables::{
+                name::{Name, NameRecord},
+                stat::{AxisRecord, AxisValue, AxisValueRecord, AxisValueTableFlags, Stat},
+            },
+            FontBuilder,
+        };
+        let elidable = AxisValueTableFlags::ELIDABLE_AXIS_VALUE_NAME;
+        let none = AxisValueTableFlags::empty();
+        let stat = Stat::new(
+            vec![
+                AxisRecord::new(Tag::new(b"wght"), StringId::new(256), 0),
+                AxisRecord::new(Tag::new(b"opsz"), StringId::new(257), 1),
+            ],
+            vec![
+                AxisValue::format_1(0, elidable, StringId::new(258), Fixed::from_i32(400)),
+                AxisValue::format_1(0, none, StringId::new(259), Fixed::from_i32(700)),
+                AxisValue::format_2(
+                    1,
+                    elidable,
+                    StringId::new(260),
+                    Fixed::from_i32(12),
+                    Fixed::from_i32(6),
+                    Fixed::from_i32(18),
+                ),
+                AxisValue::format_4(
+                    none,
+                    StringId::new(261),
+                    vec![
+                        AxisValueRecord::new(1, Fixed::from_i32(72)),
+                        AxisValueRecord::new(0, Fixed::from_i32(700)),
+                    ],
+                ),
+            ],
+            StringId::SUBFAMILY_NAME,
+        );
+        let names = [
+            (2, "Regular"),
+            (258, "Regular"),
+            (259, "Bold"),
+            (260, "Text"),
+            (261, "Display Bold"),
+        ]
+        .into_iter()
+        .map(|(id, value)| {
+            NameRecord::new(3, 1, 0x409, StringId::new(id), value.to_string().into())
+        })
+        .collect();
+        FontBuilder::new()
+            .add_table(&stat)
+            .unwrap()
+            .add_table(&Name::new(names))
+            .unwrap()
+            .build()
+    }
+
+    #[test]
+    fn stat_style_names() {
+        let data = make_stat_font();
+        let font = FontRef::new(&data).unwrap();
+        let names = |settings: &[(&str, f32)]| {
+            stat_style_name_ids(&font, settings.iter().copied())
+                .unwrap()
+                .iter()
+                .map(|id| id.to_u16())
+                .collect::<Vec<_>>()
+        };
+        // multi-axis value covers both axes
+        assert_eq!(names(&[("wght", 700.0), ("opsz", 72.0)]), [261]);
+        // opsz matched by elidable range
+        assert_eq!(names(&[("wght", 700.0), ("opsz", 10.0)]), [259]);
+        // all values elided so use the fallback
+        assert_eq!(names(&[("wght", 400.0), ("opsz", 12.0)]), [2]);
+        // no match for opsz
+        assert_eq!(names(&[("wght", 700.0), ("opsz", 40.0)]), [259]);
+        assert_eq!(names(&[]), [2]);
+    }
+
+    #[test]
+    fn stat_style_name_strings() {
+        let data = make_stat_font();
+        let font = FontRef::new(&data).unwrap();
+        let name = |settings: &[(&str, f32)]| stat_style_name(&font, settings.iter().copied());
+        assert_eq!(
+            name(&[("wght", 700.0), ("opsz", 72.0)]).as_deref(),
+            Some("Display Bold")
+        );
+        assert_eq!(name(&[("wght", 700.0)]).as_deref(), Some("Bold"));
+        assert_eq!(name(&[("wght", 400.0)]).as_deref(), Some("Regular"));
+        let font = FontRef::new(font_test_data::NAMES_ONLY).unwrap();
+        assert!(stat_style_name(&font, [("wght", 400.0)]).is_none());
+    }
+
+    #[test]
+    fn stat_style_names_missing_table() {
+        let font = FontRef::new(font_test_data::NAMES_ONLY).unwrap();
+        assert!(stat_style_name_ids(&font, [("wght", 400.0)]).is_none());
+    }
 }
@@ -9,7 +9,7 @@ use read_fonts::{
 
 use crate::{
     collections::SmallVec,
-    instance::{Location, NormalizedCoord},
+    instance::{Location, LocationRef, NormalizedCoord},
     setting::VariationSetting,
     string::StringId,
 };
@@ -74,6 +74,14 @@ impl Axis {
             .normalize(Fixed::from_f64(coord as _))
             .to_f2dot14()
     }
+
+    /// Returns a user coordinate for the given normalized coordinate.
+    ///
+    /// This is the inverse of [`normalize`](Self::normalize) and does not
+    /// apply any axis variation remapping.
+    pub fn denormalize(&self, coord: NormalizedCoord) -> f32 {
+        self.record.denormalize(coord.to_fixed()).to_f64() as _
+    }
 }
 
 /// Collection of axes in a variable font.
@@ -201,6 +209,47 @@ impl<'a> AxisCollection<'a> {
         }
     }
 
+    /// Given a location in normalized variation space, computes the
+    /// associated variation settings in user space, one per axis.
+    ///
+    /// This is the inverse of [`location`](Self::location) and is useful
+    /// for reporting the effective location after clamping and
+    /// normalization.
+    ///
+    /// * Coordinates are clamped to the range [-1.0, 1.0].
+    /// * Omitted coordinates produce the default value of the associated
+    ///   axis.
+    /// * Axis variation remapping is inverted, with the exception of the
+    ///   variations added in version 2 of the `avar` table, which are
+    ///   ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use skrifa::prelude::*;
+    /// # fn wrapper(font: &FontRef) {
+    /// let axes = font.axes();
+    /// let location = axes.location([("wght", 250.0)]);
+    /// let settings: Vec<_> = axes.user_settings(&location).collect();
+    /// # }
+    /// ```
+    pub fn user_settings<'b>(
+        &self,
+        location: impl Into<LocationRef<'b>>,
+    ) -> impl Iterator<Item = VariationSetting> + 'a + Clone {
+        let mut user_coords = SmallVec::<_, 8>::with_len(self.len(), Fixed::ZERO);
+        if let Some(fvar) = self.fvar.as_ref() {
+            fvar.normalized_to_user(
+                self.avar.as_ref(),
+                location.into().coords(),
+                user_coords.as_mut_slice(),
+            );
+        }
+        self.iter()
+            .zip(user_coords)
+            .map(|(axis, value)| VariationSetting::new(axis.tag(), value.to_f64() as f32))
+    }
+
     /// Given an iterator of variation settings in user space, returns a
     /// new iterator yielding those settings that are valid for this axis
     /// collection.
@@ -412,6 +461,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn denormalize() {
+        let font = FontRef::from_index(VAZIRMATN_VAR, 0).unwrap();
+        let axis = font.axes().get(0).unwrap();
+        for (coord, value) in [(-1.0, 100.0), (-0.5, 250.0), (0.0, 400.0), (1.0, 900.0)] {
+            assert_eq!(axis.denormalize(NormalizedCoord::from_f32(coord)), value);
+        }
+    }
+
+    #[test]
+    fn user_settings() {
+        let font = FontRef::from_index(VAZIRMATN_VAR, 0).unwrap();
+        let axes = font.axes();
+        // out of range settings are clamped
+        for (value, expected) in [
+            (-1000.0, 100.0),
+            (400.0, 400.0),
+            (700.0, 700.0),
+            (2000.0, 900.0),
+        ] {
+            let location = axes.location([("wght", value)]);
+            let settings = axes.user_settings(&location).collect::<Vec<_>>();
+            assert_eq!(settings.len(), 1);
+            assert_eq!(settings[0].selector, Tag::new(b"wght"));
+            // normalized coordinates have limited precision
+            assert!((settings[0].value - expected).abs() < 0.05, "{value}");
+        }
+        // an empty location produces default values
+        let settings = axes.user_settings(&[][..]).collect::<Vec<_>>();
+        assert_eq!(settings, [("wght", 400.0).into()]);
+    }
+
     #[test]
     fn named_instances() {
         let font = FontRef::from_index(VAZIRMATN_VAR, 0).unwrap();
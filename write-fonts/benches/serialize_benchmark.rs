@@ -0,0 +1,117 @@
+//! Benchmarks for serializing layout tables.
+//!
+//! By default this uses a synthesized GPOS table. To benchmark real fonts,
+//! set `WRITE_FONTS_BENCH_FONTS` to a list of font paths separated by the
+//! platform path separator (':' on unix).
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use read_fonts::{FontRef, TableProvider};
+use write_fonts::{
+    dump_table, dump_table_with_buffers,
+    from_obj::ToOwnedTable,
+    tables::{
+        gpos::{
+            AnchorTable, BaseArray, BaseRecord, Gpos, MarkArray, MarkBasePosFormat1, MarkRecord,
+            PairPos, PairSet, PairValueRecord, PositionLookup, PositionLookupList, ValueRecord,
+        },
+        layout::{CoverageTable, FeatureList, Lookup, LookupFlag, ScriptList},
+    },
+    types::GlyphId16,
+    ScratchBuffers,
+};
+
+const N_GLYPHS: u16 = 400;
+
+fn pair_pos_lookup(seed: u16) -> PositionLookup {
+    let coverage: CoverageTable = (1..N_GLYPHS).map(GlyphId16::new).collect();
+    let pair_sets = (1..N_GLYPHS)
+        .map(|first| {
+            let records = (1..N_GLYPHS)
+                .step_by(7)
+                .map(|second| {
+                    let kern = ((first * 31 + second + seed) % 50) as i16 - 25;
+                    PairValueRecord::new(
+                        GlyphId16::new(second),
+                        ValueRecord::new().with_x_advance(kern),
+                        ValueRecord::default(),
+                    )
+                })
+                .collect();
+            PairSet::new(records)
+        })
+        .collect();
+    let subtable = PairPos::format_1(coverage, pair_sets);
+    PositionLookup::Pair(Lookup::new(LookupFlag::empty(), vec![subtable]))
+}
+
+fn mark_base_lookup(seed: u16) -> PositionLookup {
+    let marks: CoverageTable = (N_GLYPHS..N_GLYPHS + 40).map(GlyphId16::new).collect();
+    let bases: CoverageTable = (1..N_GLYPHS).map(GlyphId16::new).collect();
+    let mark_records = (0..40)
+        .map(|i| MarkRecord::new(i % 4, AnchorTable::format_1(i as i16 * 10, 500)))
+        .collect();
+    let base_records = (1..N_GLYPHS)
+        .map(|gid| {
+            let anchors = (0..4)
+                .map(|class| {
+                    let x = ((gid + class * 13 + seed) % 60) as i16 * 10;
+                    Some(AnchorTable::format_1(x, 700 - class as i16 * 400))
+                })
+                .collect();
+            BaseRecord::new(anchors)
+        })
+        .collect();
+    let subtable = MarkBasePosFormat1::new(
+        marks,
+        bases,
+        MarkArray::new(mark_records),
+        BaseArray::new(base_records),
+    );
+    PositionLookup::MarkToBase(Lookup::new(LookupFlag::empty(), vec![subtable]))
+}
+
+fn synthetic_gpos() -> Gpos {
+    let lookups = (0..8)
+        .flat_map(|i| [pair_pos_lookup(i), mark_base_lookup(i)])
+        .collect();
+    Gpos::new(
+        ScriptList::default(),
+        FeatureList::default(),
+        PositionLookupList::new(lookups),
+    )
+}
+
+fn gpos_tables() -> Vec<(String, Gpos)> {
+    let mut tables = vec![("synthetic".to_string(), synthetic_gpos())];
+    let Some(paths) = std::env::var_os("WRITE_FONTS_BENCH_FONTS") else {
+        return tables;
+    };
+    for path in std::env::split_paths(&paths) {
+        let data = std::fs::read(&path).expect("failed to read font");
+        let font = FontRef::new(&data).expect("failed to parse font");
+        let gpos = font
+            .gpos()
+            .expect("font has no GPOS table")
+            .to_owned_table();
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        tables.push((name, gpos));
+    }
+    tables
+}
+
+pub fn serialize_benchmark(c: &mut Criterion) {
+    for (name, gpos) in gpos_tables() {
+        c.bench_with_input(BenchmarkId::new("dump_table", &name), &gpos, |b, gpos| {
+            b.iter(|| dump_table(black_box(gpos)).unwrap())
+        });
+        let mut scratch = ScratchBuffers::new();
+        c.bench_with_input(
+            BenchmarkId::new("dump_table_with_buffers", &name),
+            &gpos,
+            |b, gpos| b.iter(|| dump_table_with_buffers(black_box(gpos), &mut scratch).unwrap()),
+        );
+    }
+}
+
+criterion_group!(benches, serialize_benchmark);
+criterion_main!(benches);
@@ -228,13 +228,8 @@ impl CmapSubtable {
     }
 
     /// Construct a new `Cmap13` subtable
-    pub fn format_13(
-        length: u32,
-        language: u32,
-        num_groups: u32,
-        groups: Vec<ConstantMapGroup>,
-    ) -> Self {
-        Self::Format13(Cmap13::new(length, language, num_groups, groups))
+    pub fn format_13(language: u32, groups: Vec<ConstantMapGroup>) -> Self {
+        Self::Format13(Cmap13::new(language, groups))
     }
 
     /// Construct a new `Cmap14` subtable
@@ -1014,24 +1009,18 @@ impl<'a> FontRead<'a> for Cmap12 {
 #[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cmap13 {
-    /// Byte length of this subtable (including the header)
-    pub length: u32,
     /// For requirements on use of the language field, see “Use of
     /// the language field in 'cmap' subtables” in this document.
     pub language: u32,
-    /// Number of groupings which follow
-    pub num_groups: u32,
     /// Array of ConstantMapGroup records.
     pub groups: Vec<ConstantMapGroup>,
 }
 
 impl Cmap13 {
     /// Construct a new `Cmap13`
-    pub fn new(length: u32, language: u32, num_groups: u32, groups: Vec<ConstantMapGroup>) -> Self {
+    pub fn new(language: u32, groups: Vec<ConstantMapGroup>) -> Self {
         Self {
-            length,
             language,
-            num_groups,
             groups: groups.into_iter().map(Into::into).collect(),
         }
     }
@@ -1042,9 +1031,9 @@ impl FontWrite for Cmap13 {
     fn write_into(&self, writer: &mut TableWriter) {
         (13 as u16).write_into(writer);
         (0 as u16).write_into(writer);
-        self.length.write_into(writer);
+        (self.compute_length() as u32).write_into(writer);
         self.language.write_into(writer);
-        self.num_groups.write_into(writer);
+        (u32::try_from(array_len(&self.groups)).unwrap()).write_into(writer);
         self.groups.write_into(writer);
     }
     fn table_type(&self) -> TableType {
@@ -1061,6 +1050,7 @@ impl Validate for Cmap13 {
                 }
                 self.groups.validate_impl(ctx);
             });
+            self.check_groups(ctx);
         })
     }
 }
@@ -1069,9 +1059,7 @@ impl<'a> FromObjRef<read_fonts::tables::cmap::Cmap13<'a>> for Cmap13 {
     fn from_obj_ref(obj: &read_fonts::tables::cmap::Cmap13<'a>, _: FontData) -> Self {
         let offset_data = obj.offset_data();
         Cmap13 {
-            length: obj.length(),
             language: obj.language(),
-            num_groups: obj.num_groups(),
             groups: obj.groups().to_owned_obj(offset_data),
         }
     }
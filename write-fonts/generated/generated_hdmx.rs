@@ -11,36 +11,23 @@ use crate::codegen_prelude::*;
 pub struct Hdmx {
     /// Table version number (set to 0).
     pub version: u16,
-    /// Number of device records.
-    pub num_records: u16,
-    /// Size of device record, 32-bit aligned.
-    pub size_device_record: u32,
     /// Array of device records.
     pub records: Vec<DeviceRecord>,
 }
 
 impl Hdmx {
     /// Construct a new `Hdmx`
-    pub fn new(
-        version: u16,
-        num_records: u16,
-        size_device_record: u32,
-        records: Vec<DeviceRecord>,
-    ) -> Self {
-        Self {
-            version,
-            num_records,
-            size_device_record,
-            records,
-        }
+    pub fn new(version: u16, records: Vec<DeviceRecord>) -> Self {
+        Self { version, records }
     }
 }
 
 impl FontWrite for Hdmx {
+    #[allow(clippy::unnecessary_cast)]
     fn write_into(&self, writer: &mut TableWriter) {
         self.version.write_into(writer);
-        self.num_records.write_into(writer);
-        self.size_device_record.write_into(writer);
+        (u16::try_from(array_len(&self.records)).unwrap()).write_into(writer);
+        (self.compute_size_device_record() as u32).write_into(writer);
         self.records.write_into(writer);
     }
     fn table_type(&self) -> TableType {
@@ -57,6 +44,7 @@ impl Validate for Hdmx {
                 }
                 self.records.validate_impl(ctx);
             });
+            self.check_records(ctx);
         })
     }
 }
@@ -70,8 +58,6 @@ impl<'a> FromObjRef<read_fonts::tables::hdmx::Hdmx<'a>> for Hdmx {
         let offset_data = obj.offset_data();
         Hdmx {
             version: obj.version(),
-            num_records: obj.num_records(),
-            size_device_record: obj.size_device_record(),
             records: obj
                 .records()
                 .iter()
@@ -84,6 +70,12 @@ impl<'a> FromObjRef<read_fonts::tables::hdmx::Hdmx<'a>> for Hdmx {
 #[allow(clippy::needless_lifetimes)]
 impl<'a> FromTableRef<read_fonts::tables::hdmx::Hdmx<'a>> for Hdmx {}
 
+impl<'a> FontRead<'a> for Hdmx {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        <read_fonts::tables::hdmx::Hdmx as FontRead>::read(data).map(|x| x.to_owned_table())
+    }
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceRecord {
@@ -91,7 +83,11 @@ pub struct DeviceRecord {
     pub pixel_size: u8,
     /// Maximum width.
     pub max_width: u8,
-    /// Array of glyphs (numgGlyphs is from the 'maxp' table).
+    /// Array of widths, indexed by glyph id (numGlyphs is from the
+    /// 'maxp' table).
+    ///
+    /// Records are padded to a multiple of four bytes and this array
+    /// includes any trailing padding.
     pub widths: Vec<u8>,
 }
 
@@ -110,7 +106,7 @@ impl FontWrite for DeviceRecord {
     fn write_into(&self, writer: &mut TableWriter) {
         self.pixel_size.write_into(writer);
         self.max_width.write_into(writer);
-        self.widths.write_into(writer);
+        (self.compile_padded_widths()).write_into(writer);
     }
     fn table_type(&self) -> TableType {
         TableType::Named("DeviceRecord")
@@ -118,15 +114,7 @@ impl FontWrite for DeviceRecord {
 }
 
 impl Validate for DeviceRecord {
-    fn validate_impl(&self, ctx: &mut ValidationCtx) {
-        ctx.in_table("DeviceRecord", |ctx| {
-            ctx.in_field("widths", |ctx| {
-                if self.widths.len() > (u16::MAX as usize) {
-                    ctx.report("array exceeds max length");
-                }
-            });
-        })
-    }
+    fn validate_impl(&self, _ctx: &mut ValidationCtx) {}
 }
 
 impl FromObjRef<read_fonts::tables::hdmx::DeviceRecord<'_>> for DeviceRecord {
@@ -248,19 +248,24 @@ impl<'a> FontRead<'a> for PatchMapFormat1 {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GlyphMap {
     pub first_mapped_glyph: u16,
+    pub entry_index: Vec<u8>,
 }
 
 impl GlyphMap {
     /// Construct a new `GlyphMap`
-    pub fn new(first_mapped_glyph: u16) -> Self {
-        Self { first_mapped_glyph }
+    #[allow(clippy::useless_conversion)]
+    pub fn new(first_mapped_glyph: u16, entry_index: Vec<u8>) -> Self {
+        Self {
+            first_mapped_glyph,
+            entry_index,
+        }
     }
 }
 
 impl FontWrite for GlyphMap {
-    #[allow(clippy::unnecessary_cast)]
     fn write_into(&self, writer: &mut TableWriter) {
         self.first_mapped_glyph.write_into(writer);
+        self.entry_index.write_into(writer);
     }
     fn table_type(&self) -> TableType {
         TableType::Named("GlyphMap")
@@ -273,9 +278,9 @@ impl Validate for GlyphMap {
 
 impl<'a> FromObjRef<read_fonts::tables::ift::GlyphMap<'a>> for GlyphMap {
     fn from_obj_ref(obj: &read_fonts::tables::ift::GlyphMap<'a>, _: FontData) -> Self {
-        let offset_data = obj.offset_data();
         GlyphMap {
             first_mapped_glyph: obj.first_mapped_glyph(),
+            entry_index: convert_entry_index(obj),
         }
     }
 }
@@ -287,23 +292,26 @@ impl<'a> FromTableRef<read_fonts::tables::ift::GlyphMap<'a>> for GlyphMap {}
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FeatureMap {
     pub feature_count: u16,
+    pub feature_records: Vec<u8>,
     pub entry_map_data: Vec<u8>,
 }
 
 impl FeatureMap {
     /// Construct a new `FeatureMap`
-    pub fn new(feature_count: u16, entry_map_data: Vec<u8>) -> Self {
+    #[allow(clippy::useless_conversion)]
+    pub fn new(feature_count: u16, feature_records: Vec<u8>, entry_map_data: Vec<u8>) -> Self {
         Self {
             feature_count,
+            feature_records,
             entry_map_data: entry_map_data.into_iter().map(Into::into).collect(),
         }
     }
 }
 
 impl FontWrite for FeatureMap {
-    #[allow(clippy::unnecessary_cast)]
     fn write_into(&self, writer: &mut TableWriter) {
         self.feature_count.write_into(writer);
+        self.feature_records.write_into(writer);
         self.entry_map_data.write_into(writer);
     }
     fn table_type(&self) -> TableType {
@@ -320,6 +328,7 @@ impl<'a> FromObjRef<read_fonts::tables::ift::FeatureMap<'a>> for FeatureMap {
         let offset_data = obj.offset_data();
         FeatureMap {
             feature_count: obj.feature_count(),
+            feature_records: convert_feature_records(obj),
             entry_map_data: obj.entry_map_data().to_owned_obj(offset_data),
         }
     }
@@ -548,6 +557,7 @@ pub struct EntryData {
     pub design_space_segments: Option<Vec<DesignSpaceSegment>>,
     pub copy_count: Option<u8>,
     pub copy_indices: Option<Vec<Uint24>>,
+    pub entry_id_delta: Option<Int24>,
     pub patch_format: Option<u8>,
     pub codepoint_data: Vec<u8>,
 }
@@ -564,7 +574,6 @@ impl EntryData {
 }
 
 impl FontWrite for EntryData {
-    #[allow(clippy::unnecessary_cast)]
     fn write_into(&self, writer: &mut TableWriter) {
         self.format_flags.write_into(writer);
         self.format_flags
@@ -615,6 +624,14 @@ impl FontWrite for EntryData {
                     .expect("missing conditional field should have failed validation")
                     .write_into(writer)
             });
+        self.format_flags
+            .contains(EntryFormatFlags::ENTRY_ID_DELTA)
+            .then(|| {
+                self.entry_id_delta
+                    .as_ref()
+                    .expect("missing conditional field should have failed validation")
+                    .write_into(writer)
+            });
         self.format_flags
             .contains(EntryFormatFlags::PATCH_FORMAT)
             .then(|| {
@@ -728,6 +745,18 @@ impl Validate for EntryData {
                     ctx.report("array exceeds max length");
                 }
             });
+            ctx.in_field("entry_id_delta", |ctx| {
+                if !(format_flags.contains(EntryFormatFlags::ENTRY_ID_DELTA))
+                    && self.entry_id_delta.is_some()
+                {
+                    ctx.report("'entry_id_delta' is present but ENTRY_ID_DELTA not set")
+                }
+                if (format_flags.contains(EntryFormatFlags::ENTRY_ID_DELTA))
+                    && self.entry_id_delta.is_none()
+                {
+                    ctx.report("ENTRY_ID_DELTA is set but 'entry_id_delta' is None")
+                }
+            });
             ctx.in_field("patch_format", |ctx| {
                 if !(format_flags.contains(EntryFormatFlags::PATCH_FORMAT))
                     && self.patch_format.is_some()
@@ -755,6 +784,9 @@ impl<'a> FromObjRef<read_fonts::tables::ift::EntryData<'a>> for EntryData {
             design_space_segments: obj.design_space_segments().to_owned_obj(offset_data),
             copy_count: obj.copy_count(),
             copy_indices: obj.copy_indices().to_owned_obj(offset_data),
+            entry_id_delta: obj
+                .entry_id_delta()
+                .map(|delta| Int24::new(delta.into_inner())),
             patch_format: obj.patch_format(),
             codepoint_data: obj.codepoint_data().to_owned_obj(offset_data),
         }
@@ -0,0 +1,70 @@
+// THIS FILE IS AUTOGENERATED.
+// Any changes to this file will be overwritten.
+// For more information about how codegen works, see font-codegen/README.md
+
+#[allow(unused_imports)]
+use crate::codegen_prelude::*;
+
+/// The [LTSH (Linear Threshold)](https://learn.microsoft.com/en-us/typography/opentype/spec/ltsh) table
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ltsh {
+    /// The vertical pel height at which the glyph can be assumed to scale
+    /// linearly. On a per glyph basis.
+    pub y_pels: Vec<u8>,
+}
+
+impl Ltsh {
+    /// Construct a new `Ltsh`
+    pub fn new(y_pels: Vec<u8>) -> Self {
+        Self {
+            y_pels: y_pels.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl FontWrite for Ltsh {
+    #[allow(clippy::unnecessary_cast)]
+    fn write_into(&self, writer: &mut TableWriter) {
+        (0 as u16).write_into(writer);
+        (u16::try_from(array_len(&self.y_pels)).unwrap()).write_into(writer);
+        self.y_pels.write_into(writer);
+    }
+    fn table_type(&self) -> TableType {
+        TableType::TopLevel(Ltsh::TAG)
+    }
+}
+
+impl Validate for Ltsh {
+    fn validate_impl(&self, ctx: &mut ValidationCtx) {
+        ctx.in_table("Ltsh", |ctx| {
+            ctx.in_field("y_pels", |ctx| {
+                if self.y_pels.len() > (u16::MAX as usize) {
+                    ctx.report("array exceeds max length");
+                }
+            });
+        })
+    }
+}
+
+impl TopLevelTable for Ltsh {
+    const TAG: Tag = Tag::new(b"LTSH");
+}
+
+impl<'a> FromObjRef<read_fonts::tables::ltsh::Ltsh<'a>> for Ltsh {
+    fn from_obj_ref(obj: &read_fonts::tables::ltsh::Ltsh<'a>, _: FontData) -> Self {
+        let offset_data = obj.offset_data();
+        Ltsh {
+            y_pels: obj.y_pels().to_owned_obj(offset_data),
+        }
+    }
+}
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> FromTableRef<read_fonts::tables::ltsh::Ltsh<'a>> for Ltsh {}
+
+impl<'a> FontRead<'a> for Ltsh {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        <read_fonts::tables::ltsh::Ltsh as FontRead>::read(data).map(|x| x.to_owned_table())
+    }
+}
@@ -0,0 +1,259 @@
+// THIS FILE IS AUTOGENERATED.
+// Any changes to this file will be overwritten.
+// For more information about how codegen works, see font-codegen/README.md
+
+#[allow(unused_imports)]
+use crate::codegen_prelude::*;
+
+/// The [VDMX (Vertical Device Metrics)](https://learn.microsoft.com/en-us/typography/opentype/spec/vdmx) table
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vdmx {
+    /// Version number (0 or 1).
+    pub version: u16,
+    /// Ratio record array.
+    pub ratio_ranges: Vec<RatioRange>,
+    /// Offsets from start of this table to the VDMXGroup table for a
+    /// corresponding RatioRange record.
+    pub vdmx_groups: Vec<OffsetMarker<VdmxGroup>>,
+}
+
+impl Vdmx {
+    /// Construct a new `Vdmx`
+    pub fn new(version: u16, ratio_ranges: Vec<RatioRange>, vdmx_groups: Vec<VdmxGroup>) -> Self {
+        Self {
+            version,
+            ratio_ranges: ratio_ranges.into_iter().map(Into::into).collect(),
+            vdmx_groups: vdmx_groups.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl FontWrite for Vdmx {
+    #[allow(clippy::unnecessary_cast)]
+    fn write_into(&self, writer: &mut TableWriter) {
+        self.version.write_into(writer);
+        (self.compute_num_recs() as u16).write_into(writer);
+        (u16::try_from(array_len(&self.ratio_ranges)).unwrap()).write_into(writer);
+        self.ratio_ranges.write_into(writer);
+        self.vdmx_groups.write_into(writer);
+    }
+    fn table_type(&self) -> TableType {
+        TableType::TopLevel(Vdmx::TAG)
+    }
+}
+
+impl Validate for Vdmx {
+    fn validate_impl(&self, ctx: &mut ValidationCtx) {
+        ctx.in_table("Vdmx", |ctx| {
+            ctx.in_field("ratio_ranges", |ctx| {
+                if self.ratio_ranges.len() > (u16::MAX as usize) {
+                    ctx.report("array exceeds max length");
+                }
+                self.ratio_ranges.validate_impl(ctx);
+            });
+            ctx.in_field("vdmx_groups", |ctx| {
+                if self.vdmx_groups.len() > (u16::MAX as usize) {
+                    ctx.report("array exceeds max length");
+                }
+                self.vdmx_groups.validate_impl(ctx);
+            });
+            self.check_groups(ctx);
+        })
+    }
+}
+
+impl TopLevelTable for Vdmx {
+    const TAG: Tag = Tag::new(b"VDMX");
+}
+
+impl<'a> FromObjRef<read_fonts::tables::vdmx::Vdmx<'a>> for Vdmx {
+    fn from_obj_ref(obj: &read_fonts::tables::vdmx::Vdmx<'a>, _: FontData) -> Self {
+        let offset_data = obj.offset_data();
+        Vdmx {
+            version: obj.version(),
+            ratio_ranges: obj.ratio_ranges().to_owned_obj(offset_data),
+            vdmx_groups: obj.vdmx_groups().to_owned_table(),
+        }
+    }
+}
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> FromTableRef<read_fonts::tables::vdmx::Vdmx<'a>> for Vdmx {}
+
+impl<'a> FontRead<'a> for Vdmx {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        <read_fonts::tables::vdmx::Vdmx as FontRead>::read(data).map(|x| x.to_owned_table())
+    }
+}
+
+/// Ratio ranges specify a range of aspect ratios
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RatioRange {
+    /// Character set (0 for no subset, 1 for the Windows ANSI subset).
+    pub b_char_set: u8,
+    /// Value to use for x-Ratio
+    pub x_ratio: u8,
+    /// Starting y-Ratio value.
+    pub y_start_ratio: u8,
+    /// Ending y-Ratio value.
+    pub y_end_ratio: u8,
+}
+
+impl RatioRange {
+    /// Construct a new `RatioRange`
+    pub fn new(b_char_set: u8, x_ratio: u8, y_start_ratio: u8, y_end_ratio: u8) -> Self {
+        Self {
+            b_char_set,
+            x_ratio,
+            y_start_ratio,
+            y_end_ratio,
+        }
+    }
+}
+
+impl FontWrite for RatioRange {
+    fn write_into(&self, writer: &mut TableWriter) {
+        self.b_char_set.write_into(writer);
+        self.x_ratio.write_into(writer);
+        self.y_start_ratio.write_into(writer);
+        self.y_end_ratio.write_into(writer);
+    }
+    fn table_type(&self) -> TableType {
+        TableType::Named("RatioRange")
+    }
+}
+
+impl Validate for RatioRange {
+    fn validate_impl(&self, _ctx: &mut ValidationCtx) {}
+}
+
+impl FromObjRef<read_fonts::tables::vdmx::RatioRange> for RatioRange {
+    fn from_obj_ref(obj: &read_fonts::tables::vdmx::RatioRange, _: FontData) -> Self {
+        RatioRange {
+            b_char_set: obj.b_char_set(),
+            x_ratio: obj.x_ratio(),
+            y_start_ratio: obj.y_start_ratio(),
+            y_end_ratio: obj.y_end_ratio(),
+        }
+    }
+}
+
+/// A group of vertical metrics records for a range of sizes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VdmxGroup {
+    /// Starting yPelHeight
+    pub startsz: u8,
+    /// Ending yPelHeight
+    pub endsz: u8,
+    /// The VDMX records
+    pub entries: Vec<VTable>,
+}
+
+impl VdmxGroup {
+    /// Construct a new `VdmxGroup`
+    pub fn new(startsz: u8, endsz: u8, entries: Vec<VTable>) -> Self {
+        Self {
+            startsz,
+            endsz,
+            entries: entries.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl FontWrite for VdmxGroup {
+    #[allow(clippy::unnecessary_cast)]
+    fn write_into(&self, writer: &mut TableWriter) {
+        (u16::try_from(array_len(&self.entries)).unwrap()).write_into(writer);
+        self.startsz.write_into(writer);
+        self.endsz.write_into(writer);
+        self.entries.write_into(writer);
+    }
+    fn table_type(&self) -> TableType {
+        TableType::Named("VdmxGroup")
+    }
+}
+
+impl Validate for VdmxGroup {
+    fn validate_impl(&self, ctx: &mut ValidationCtx) {
+        ctx.in_table("VdmxGroup", |ctx| {
+            ctx.in_field("entries", |ctx| {
+                if self.entries.len() > (u16::MAX as usize) {
+                    ctx.report("array exceeds max length");
+                }
+                self.entries.validate_impl(ctx);
+            });
+            self.check_entries(ctx);
+        })
+    }
+}
+
+impl<'a> FromObjRef<read_fonts::tables::vdmx::VdmxGroup<'a>> for VdmxGroup {
+    fn from_obj_ref(obj: &read_fonts::tables::vdmx::VdmxGroup<'a>, _: FontData) -> Self {
+        let offset_data = obj.offset_data();
+        VdmxGroup {
+            startsz: obj.startsz(),
+            endsz: obj.endsz(),
+            entries: obj.entries().to_owned_obj(offset_data),
+        }
+    }
+}
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a> FromTableRef<read_fonts::tables::vdmx::VdmxGroup<'a>> for VdmxGroup {}
+
+impl<'a> FontRead<'a> for VdmxGroup {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        <read_fonts::tables::vdmx::VdmxGroup as FontRead>::read(data).map(|x| x.to_owned_table())
+    }
+}
+
+/// The maximum and minimum extents for a single size.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VTable {
+    /// yPelHeight to which values apply.
+    pub y_pel_height: u16,
+    /// Maximum value (in pels) for this yPelHeight.
+    pub y_max: i16,
+    /// Minimum value (in pels) for this yPelHeight.
+    pub y_min: i16,
+}
+
+impl VTable {
+    /// Construct a new `VTable`
+    pub fn new(y_pel_height: u16, y_max: i16, y_min: i16) -> Self {
+        Self {
+            y_pel_height,
+            y_max,
+            y_min,
+        }
+    }
+}
+
+impl FontWrite for VTable {
+    fn write_into(&self, writer: &mut TableWriter) {
+        self.y_pel_height.write_into(writer);
+        self.y_max.write_into(writer);
+        self.y_min.write_into(writer);
+    }
+    fn table_type(&self) -> TableType {
+        TableType::Named("VTable")
+    }
+}
+
+impl Validate for VTable {
+    fn validate_impl(&self, _ctx: &mut ValidationCtx) {}
+}
+
+impl FromObjRef<read_fonts::tables::vdmx::VTable> for VTable {
+    fn from_obj_ref(obj: &read_fonts::tables::vdmx::VTable, _: FontData) -> Self {
+        VTable {
+            y_pel_height: obj.y_pel_height(),
+            y_max: obj.y_max(),
+            y_min: obj.y_min(),
+        }
+    }
+}
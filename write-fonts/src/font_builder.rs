@@ -6,7 +6,10 @@ use std::{borrow::Cow, fmt::Display};
 use read_fonts::{FontRef, TableProvider};
 use types::{Tag, TT_SFNT_VERSION};
 
-use crate::util::SearchRange;
+use crate::{
+    tables::{dsig::Dsig, hhea::Hhea, hmtx::Hmtx, vhea::Vhea, vmtx::Vmtx},
+    util::SearchRange,
+};
 
 include!("../generated/generated_font.rs");
 
@@ -16,6 +19,10 @@ const TABLE_RECORD_LEN: usize = 16;
 #[derive(Debug, Clone, Default)]
 pub struct FontBuilder<'a> {
     tables: BTreeMap<Tag, Cow<'a, [u8]>>,
+    /// The number of long metrics and maximum advance of 'hmtx' or 'vmtx'
+    /// tables added with [`add_hmtx`](Self::add_hmtx) or
+    /// [`add_vmtx`](Self::add_vmtx).
+    long_metrics: BTreeMap<Tag, (u16, u16)>,
 }
 
 /// An error returned when attempting to add a table to the builder.
@@ -69,10 +76,47 @@ impl<'a> FontBuilder<'a> {
 
     /// A builder method to add raw data for the provided tag
     pub fn add_raw(&mut self, tag: Tag, data: impl Into<Cow<'a, [u8]>>) -> &mut Self {
+        self.long_metrics.remove(&tag);
         self.tables.insert(tag, data.into());
         self
     }
 
+    /// Add an 'hmtx' table, and update 'hhea' to match it when building.
+    ///
+    /// When the font is built, the 'hhea' table's `numberOfHMetrics` and
+    /// `advanceWidthMax` are set from this table, which is useful with
+    /// [`Hmtx::from_glyph_metrics`]. Tables added with [`add_table`](Self::add_table)
+    /// or [`add_raw`](Self::add_raw) are never used to update 'hhea'.
+    pub fn add_hmtx(&mut self, hmtx: &Hmtx) -> Result<&mut Self, BuilderError> {
+        self.add_table(hmtx)?;
+        let advance_max = hmtx.h_metrics.iter().map(|m| m.advance).max();
+        self.long_metrics.insert(
+            Hmtx::TAG,
+            (
+                hmtx.number_of_long_metrics(),
+                advance_max.unwrap_or_default(),
+            ),
+        );
+        Ok(self)
+    }
+
+    /// Add a 'vmtx' table, and update 'vhea' to match it when building.
+    ///
+    /// See [`add_hmtx`](Self::add_hmtx); this sets the 'vhea' table's
+    /// `numOfLongVerMetrics` and `advanceHeightMax`.
+    pub fn add_vmtx(&mut self, vmtx: &Vmtx) -> Result<&mut Self, BuilderError> {
+        self.add_table(vmtx)?;
+        let advance_max = vmtx.v_metrics.iter().map(|m| m.advance).max();
+        self.long_metrics.insert(
+            Vmtx::TAG,
+            (
+                vmtx.number_of_long_metrics(),
+                advance_max.unwrap_or_default(),
+            ),
+        );
+        Ok(self)
+    }
+
     /// Copy each table from the source font if it does not already exist
     pub fn copy_missing_tables(&mut self, font: FontRef<'a>) -> &mut Self {
         for record in font.table_directory.table_records() {
@@ -93,10 +137,98 @@ impl<'a> FontBuilder<'a> {
         self.tables.contains_key(&tag)
     }
 
+    /// Returns the data of the table with this tag, if the builder contains one.
+    pub fn table_data(&self, tag: Tag) -> Option<&[u8]> {
+        self.tables.get(&tag).map(|data| data.as_ref())
+    }
+
+    /// Add an empty `DSIG` table, replacing any existing one.
+    ///
+    /// Some legacy environments require the table to be present, even
+    /// though the signatures are never checked.
+    pub fn add_empty_dsig(&mut self) -> &mut Self {
+        // an empty table always compiles
+        let bytes = crate::dump_table(&Dsig::empty()).unwrap_or_default();
+        self.add_raw(Dsig::TAG, bytes)
+    }
+
+    /// Remove the `DSIG` table, if present.
+    ///
+    /// A digital signature covers the entire font file, so it is invalidated
+    /// by any modification. Stripping it is recommended when rebuilding a
+    /// font.
+    ///
+    /// Returns `true` if a table was removed.
+    pub fn strip_dsig(&mut self) -> bool {
+        self.tables.remove(&Dsig::TAG).is_some()
+    }
+
+    /// Returns `true` if the builder contains a `DSIG` table with at least
+    /// one signature.
+    fn has_signature(&self) -> bool {
+        self.tables
+            .get(&Dsig::TAG)
+            .and_then(|data| read_fonts::tables::dsig::Dsig::read(data.as_ref().into()).ok())
+            .is_some_and(|dsig| !dsig.is_empty())
+    }
+
+    /// Update the 'hhea' and 'vhea' tables to match any 'hmtx' and 'vmtx'
+    /// tables added with [`add_hmtx`](Self::add_hmtx) or [`add_vmtx`](Self::add_vmtx).
+    fn sync_metrics_headers(&mut self) {
+        if let Some((n_long, advance_max)) = self.long_metrics.get(&Hmtx::TAG).copied() {
+            self.update_table(|hhea: &mut Hhea| {
+                hhea.number_of_long_metrics = n_long;
+                hhea.advance_width_max = advance_max.into();
+            });
+        }
+        if let Some((n_long, advance_max)) = self.long_metrics.get(&Vmtx::TAG).copied() {
+            self.update_table(|vhea: &mut Vhea| {
+                vhea.number_of_long_ver_metrics = n_long;
+                vhea.advance_height_max = advance_max.into();
+            });
+        }
+    }
+
+    /// Parse a table, apply `f`, and replace it if it has changed.
+    fn update_table<T>(&mut self, f: impl FnOnce(&mut T))
+    where
+        T: for<'b> FontRead<'b> + FontWrite + Validate + TopLevelTable + PartialEq + Clone,
+    {
+        let Some(data) = self.tables.get(&T::TAG) else {
+            return;
+        };
+        let Ok(table) = T::read(data.as_ref().into()) else {
+            log::warn!("data for '{}' is malformed", T::TAG);
+            return;
+        };
+        let mut updated = table.clone();
+        f(&mut updated);
+        if updated != table {
+            if let Ok(bytes) = crate::dump_table(&updated) {
+                self.add_raw(T::TAG, bytes);
+            }
+        }
+    }
+
     /// Assemble all the tables into a binary font file with a [Table Directory].
     ///
+    /// If the 'hmtx' or 'vmtx' table was added with [`add_hmtx`](Self::add_hmtx)
+    /// or [`add_vmtx`](Self::add_vmtx), the 'hhea' or 'vhea' table is updated
+    /// to match it.
+    ///
+    /// If the builder contains a non-empty `DSIG` table, a warning is logged
+    /// since the signature will not match the newly assembled font. Use
+    /// [`strip_dsig`](Self::strip_dsig) or [`add_empty_dsig`](Self::add_empty_dsig)
+    /// to avoid this.
+    ///
     /// [Table Directory]: https://learn.microsoft.com/en-us/typography/opentype/spec/otff#table-directory
     pub fn build(&mut self) -> Vec<u8> {
+        if self.has_signature() {
+            log::warn!(
+                "'DSIG' table contains signatures that are invalidated by rebuilding the font"
+            );
+        }
+        self.sync_metrics_headers();
         let header_len = std::mem::size_of::<u32>() // sfnt
             + std::mem::size_of::<u16>() * 4 // num_tables to range_shift
             + self.tables.len() * TABLE_RECORD_LEN;
@@ -175,10 +307,19 @@ impl std::error::Error for BuilderError {
 
 #[cfg(test)]
 mod tests {
-    use font_types::Tag;
-    use read_fonts::FontRef;
+    use font_types::{GlyphId, Tag};
+    use read_fonts::{FontRef, TableProvider, TopLevelTable};
 
-    use crate::{font_builder::checksum_and_padding, FontBuilder};
+    use crate::{
+        font_builder::checksum_and_padding,
+        tables::{
+            dsig::{Dsig, SignatureBlockFormat1, SignatureRecord},
+            hhea::Hhea,
+            hmtx::{Hmtx, LongMetric},
+            maxp::Maxp,
+        },
+        FontBuilder,
+    };
 
     #[test]
     fn sets_binary_search_assists() {
@@ -197,6 +338,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dsig() {
+        let mut builder = FontBuilder::default();
+        builder.add_empty_dsig();
+        assert!(builder.contains(Dsig::TAG));
+        assert!(!builder.has_signature());
+        let bytes = builder.build();
+        let font = FontRef::new(&bytes).unwrap();
+        assert!(font.dsig().unwrap().is_empty());
+
+        let signed = Dsig::new(
+            Default::default(),
+            vec![SignatureRecord::new(SignatureBlockFormat1::new(vec![0; 4]))],
+        );
+        builder.add_table(&signed).unwrap();
+        assert!(builder.has_signature());
+        assert!(builder.strip_dsig());
+        assert!(!builder.strip_dsig());
+        assert!(!builder.contains(Dsig::TAG));
+    }
+
+    #[test]
+    fn syncs_hhea_with_hmtx() {
+        let metrics = [(500, 10), (600, 20), (600, 30)]
+            .into_iter()
+            .map(|(advance, lsb)| LongMetric::new(advance, lsb))
+            .collect();
+        let hmtx = Hmtx::from_glyph_metrics(metrics);
+        let mut builder = FontBuilder::default();
+        builder
+            .add_table(&Maxp::new(3))
+            .unwrap()
+            .add_hmtx(&hmtx)
+            .unwrap()
+            .add_table(&Hhea::default())
+            .unwrap();
+        let bytes = builder.build();
+        let font = FontRef::new(&bytes).unwrap();
+        let hhea = font.hhea().unwrap();
+        assert_eq!(hhea.number_of_long_metrics(), 2);
+        assert_eq!(hhea.advance_width_max().to_u16(), 600);
+        let hmtx = font.hmtx().unwrap();
+        assert_eq!(hmtx.advance(GlyphId::new(2)), Some(600));
+        assert_eq!(hmtx.side_bearing(GlyphId::new(2)), Some(30));
+    }
+
+    #[test]
+    fn raw_hmtx_does_not_change_hhea() {
+        let hhea = Hhea {
+            number_of_long_metrics: 3,
+            advance_width_max: 700.into(),
+            ..Default::default()
+        };
+        let hmtx = Hmtx::from_glyph_metrics(vec![LongMetric::new(500, 10)]);
+        // three long metrics, followed by padding
+        let raw_hmtx = [0u8; 14];
+        let mut builder = FontBuilder::default();
+        builder
+            .add_table(&Maxp::new(3))
+            .unwrap()
+            .add_table(&hhea)
+            .unwrap()
+            .add_hmtx(&hmtx)
+            .unwrap()
+            .add_raw(Hmtx::TAG, raw_hmtx.as_slice());
+        let bytes = builder.build();
+        let font = FontRef::new(&bytes).unwrap();
+        let hhea = font.hhea().unwrap();
+        assert_eq!(hhea.number_of_long_metrics(), 3);
+        assert_eq!(hhea.advance_width_max().to_u16(), 700);
+    }
+
     #[test]
     fn survives_no_tables() {
         FontBuilder::default().build();
@@ -0,0 +1,311 @@
+//! Importing and exporting glyph orders.
+//!
+//! A glyph order maps glyph identifiers to glyph names. Build pipelines that
+//! pin the glyph order of a font typically store it in a plain text file
+//! using the format of the AFDKO `GlyphOrderAndAliasDB`: one glyph per line,
+//! in glyph id order, with whitespace separated columns for the final
+//! (production) name, an optional development name and an optional Unicode
+//! override. Blank lines and anything following a `#` are ignored.
+
+use std::{collections::HashMap, fmt::Display};
+
+use read_fonts::{tables::post::Post as ReadPost, types::Version16Dot16, FontRef, TableProvider};
+use types::GlyphId16;
+
+use crate::tables::post::Post;
+
+/// The names of all glyphs in a font, in glyph id order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GlyphOrder {
+    names: Vec<String>,
+    aliases: Vec<Option<String>>,
+    lookup: HashMap<String, GlyphId16>,
+}
+
+/// An error that occurs when constructing or applying a [`GlyphOrder`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GlyphOrderError {
+    /// A name appears more than once.
+    DuplicateName(String),
+    /// The order contains more glyphs than a font can hold.
+    TooManyGlyphs(usize),
+    /// A glyph does not appear in the order.
+    MissingFromOrder(String),
+    /// A glyph in the order was not provided.
+    MissingGlyph(String),
+}
+
+impl GlyphOrder {
+    /// Create a new glyph order from a sequence of names, in glyph id order.
+    pub fn new<S: Into<String>>(
+        names: impl IntoIterator<Item = S>,
+    ) -> Result<Self, GlyphOrderError> {
+        let mut order = GlyphOrder::default();
+        for name in names {
+            order.push(name.into(), None)?;
+        }
+        Ok(order)
+    }
+
+    /// Parse a glyph order in the AFDKO `GlyphOrderAndAliasDB` format.
+    ///
+    /// The first column on each line is the final glyph name. If present,
+    /// the second column is recorded as an alias that can also be used to
+    /// look up the glyph. Any further columns are ignored.
+    pub fn parse(text: &str) -> Result<Self, GlyphOrderError> {
+        let mut order = GlyphOrder::default();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut columns = line.split_whitespace();
+            let Some(name) = columns.next() else {
+                continue;
+            };
+            let alias = columns.next().filter(|alias| *alias != name);
+            order.push(name.into(), alias.map(Into::into))?;
+        }
+        Ok(order)
+    }
+
+    /// Export the glyph order of an existing font.
+    ///
+    /// Names are taken from a version 1.0 or 2.0 `post` table, falling back
+    /// to the charset of the `CFF` table. Glyphs without a name (or with a
+    /// duplicate name) are given a name of the form `glyph00042`, matching
+    /// the convention used by fontTools.
+    pub fn from_font(font: &FontRef) -> Result<Self, GlyphOrderError> {
+        let num_glyphs = font
+            .maxp()
+            .map(|maxp| maxp.num_glyphs())
+            .unwrap_or_default();
+        let source_name = font_glyph_name_fn(font);
+        let mut order = GlyphOrder::default();
+        for gid in 0..num_glyphs {
+            let gid = GlyphId16::new(gid);
+            let name = source_name(gid)
+                .filter(|name| !name.is_empty() && !order.lookup.contains_key(name))
+                .unwrap_or_else(|| format!("glyph{:05}", gid.to_u16()));
+            order.push(name, None)?;
+        }
+        Ok(order)
+    }
+
+    fn push(&mut self, name: String, alias: Option<String>) -> Result<(), GlyphOrderError> {
+        let gid = u16::try_from(self.names.len())
+            .map(GlyphId16::new)
+            .map_err(|_| GlyphOrderError::TooManyGlyphs(self.names.len() + 1))?;
+        for key in std::iter::once(&name).chain(alias.as_ref()) {
+            if self.lookup.insert(key.clone(), gid).is_some() {
+                return Err(GlyphOrderError::DuplicateName(key.clone()));
+            }
+        }
+        self.names.push(name);
+        self.aliases.push(alias);
+        Ok(())
+    }
+
+    /// The number of glyphs in the order.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns `true` if the order contains no glyphs.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns the glyph id for the given final name or alias.
+    pub fn glyph_id(&self, name: &str) -> Option<GlyphId16> {
+        self.lookup.get(name).copied()
+    }
+
+    /// Returns the final name of the given glyph.
+    pub fn name(&self, gid: GlyphId16) -> Option<&str> {
+        self.names.get(gid.to_u16() as usize).map(String::as_str)
+    }
+
+    /// Returns the alias of the given glyph, if one was provided.
+    pub fn alias(&self, gid: GlyphId16) -> Option<&str> {
+        self.aliases.get(gid.to_u16() as usize)?.as_deref()
+    }
+
+    /// Returns an iterator over the final glyph names, in glyph id order.
+    pub fn iter(&self) -> impl Iterator<Item = (GlyphId16, &str)> + '_ {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (GlyphId16::new(i as u16), name.as_str()))
+    }
+
+    /// Arrange named glyph data according to this order.
+    ///
+    /// Glyphs may be identified by their final name or by their alias. Every
+    /// glyph in the order must be provided exactly once, and every provided
+    /// glyph must be in the order; the result is indexed by glyph id and
+    /// can be passed directly to builders such as
+    /// [`GlyfLocaBuilder`](crate::tables::glyf::GlyfLocaBuilder).
+    pub fn arrange<T, S: AsRef<str>>(
+        &self,
+        glyphs: impl IntoIterator<Item = (S, T)>,
+    ) -> Result<Vec<T>, GlyphOrderError> {
+        let mut slots: Vec<Option<T>> = std::iter::repeat_with(|| None).take(self.len()).collect();
+        for (name, glyph) in glyphs {
+            let name = name.as_ref();
+            let gid = self
+                .glyph_id(name)
+                .ok_or_else(|| GlyphOrderError::MissingFromOrder(name.to_owned()))?;
+            let slot = &mut slots[gid.to_u16() as usize];
+            if slot.is_some() {
+                return Err(GlyphOrderError::DuplicateName(name.to_owned()));
+            }
+            *slot = Some(glyph);
+        }
+        slots
+            .into_iter()
+            .zip(&self.names)
+            .map(|(slot, name)| slot.ok_or_else(|| GlyphOrderError::MissingGlyph(name.clone())))
+            .collect()
+    }
+
+    /// Construct a version 2.0 `post` table storing these glyph names.
+    pub fn to_post(&self) -> Post {
+        Post::new_v2(self.names.iter().map(String::as_str))
+    }
+}
+
+/// Returns a function that looks up glyph names in the given font.
+fn font_glyph_name_fn<'a>(font: &FontRef<'a>) -> Box<dyn Fn(GlyphId16) -> Option<String> + 'a> {
+    let post = font.post().ok().filter(|post: &ReadPost| {
+        matches!(
+            post.version(),
+            Version16Dot16::VERSION_1_0 | Version16Dot16::VERSION_2_0
+        )
+    });
+    if let Some(post) = post {
+        return Box::new(move |gid| post.glyph_name(gid).map(Into::into));
+    }
+    if let Some((cff, charset)) = font
+        .cff()
+        .ok()
+        .and_then(|cff| Some((cff.clone(), cff.charset(0).ok()??)))
+    {
+        return Box::new(move |gid| {
+            let name = cff.string(charset.string_id(gid.into()).ok()?)?;
+            std::str::from_utf8(name.as_bytes()).ok().map(Into::into)
+        });
+    }
+    Box::new(|_| None)
+}
+
+/// Writes the glyph order in the AFDKO `GlyphOrderAndAliasDB` format.
+impl Display for GlyphOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, alias) in self.names.iter().zip(&self.aliases) {
+            match alias {
+                Some(alias) => writeln!(f, "{name}\t{alias}")?,
+                None => writeln!(f, "{name}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for GlyphOrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GlyphOrderError::DuplicateName(name) => write!(f, "duplicate glyph name '{name}'"),
+            GlyphOrderError::TooManyGlyphs(count) => {
+                write!(f, "glyph order contains {count} glyphs (max is 65535)")
+            }
+            GlyphOrderError::MissingFromOrder(name) => {
+                write!(f, "glyph '{name}' is not in the glyph order")
+            }
+            GlyphOrderError::MissingGlyph(name) => write!(f, "no glyph provided for '{name}'"),
+        }
+    }
+}
+
+impl std::error::Error for GlyphOrderError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tables::maxp::Maxp, FontBuilder};
+
+    const GOADB: &str = "\
+# final  dev
+.notdef
+A        A
+Adieresis Adieresis.dev uni00C4
+
+uni2010  hyphentwo # a comment
+";
+
+    #[test]
+    fn parse_goadb() {
+        let order = GlyphOrder::parse(GOADB).unwrap();
+        assert_eq!(order.len(), 4);
+        assert_eq!(order.glyph_id("A"), Some(GlyphId16::new(1)));
+        assert_eq!(order.glyph_id("Adieresis.dev"), Some(GlyphId16::new(2)));
+        assert_eq!(order.glyph_id("hyphentwo"), Some(GlyphId16::new(3)));
+        assert_eq!(order.name(GlyphId16::new(3)), Some("uni2010"));
+        assert_eq!(order.alias(GlyphId16::new(1)), None);
+        assert_eq!(order.glyph_id("uni00C4"), None);
+        let round_trip = GlyphOrder::parse(&order.to_string()).unwrap();
+        assert_eq!(round_trip, order);
+    }
+
+    #[test]
+    fn duplicate_names() {
+        assert_eq!(
+            GlyphOrder::new([".notdef", "a", "a"]),
+            Err(GlyphOrderError::DuplicateName("a".into()))
+        );
+        assert_eq!(
+            GlyphOrder::parse("a\nb a\n"),
+            Err(GlyphOrderError::DuplicateName("a".into()))
+        );
+    }
+
+    #[test]
+    fn arrange_glyphs() {
+        let order = GlyphOrder::parse(GOADB).unwrap();
+        let glyphs = [("hyphentwo", 3), ("A", 1), (".notdef", 0), ("Adieresis", 2)];
+        assert_eq!(order.arrange(glyphs).unwrap(), [0, 1, 2, 3]);
+        assert_eq!(
+            order.arrange([(".notdef", 0), ("B", 1)]),
+            Err(GlyphOrderError::MissingFromOrder("B".into()))
+        );
+        assert_eq!(
+            order.arrange([(".notdef", 0), ("A", 1)]),
+            Err(GlyphOrderError::MissingGlyph("Adieresis".into()))
+        );
+    }
+
+    #[test]
+    fn export_from_font() {
+        let order = GlyphOrder::new([".notdef", "A", "uni2010"]).unwrap();
+        let mut builder = FontBuilder::new();
+        builder
+            .add_table(&Maxp {
+                num_glyphs: 4,
+                ..Default::default()
+            })
+            .unwrap();
+        builder.add_table(&order.to_post()).unwrap();
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let exported = GlyphOrder::from_font(&font).unwrap();
+        assert_eq!(
+            exported.iter().map(|(_, name)| name).collect::<Vec<_>>(),
+            [".notdef", "A", "uni2010", "glyph00003"]
+        );
+    }
+
+    #[test]
+    fn export_cff_names() {
+        let font = FontRef::new(font_test_data::NOTO_SERIF_DISPLAY_TRIMMED).unwrap();
+        let order = GlyphOrder::from_font(&font).unwrap();
+        assert_eq!(order.to_string(), ".notdef\ni\nj\nk\nl\n");
+    }
+}
@@ -2,22 +2,31 @@
 
 use font_types::Uint24;
 
-use crate::{table_type::TableType, tables::layout::LookupType, write::TableData};
+use crate::{
+    table_type::TableType,
+    tables::layout::LookupType,
+    write::{OffsetRecord, TableData},
+};
 
 use std::{
-    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque},
-    sync::atomic::AtomicU64,
+    collections::{
+        hash_map::RandomState, BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque,
+    },
+    hash::{BuildHasher, BuildHasherDefault, Hasher},
+    ops::{Index, IndexMut, Range},
 };
 
 #[cfg(feature = "dot2")]
 mod graphviz;
 mod splitting;
 
-static OBJECT_COUNTER: AtomicU64 = AtomicU64::new(0);
-
 /// An identifier for an object in the compilation graph.
+///
+/// Ids are indices into the [`ObjectStore`] the object was written to, and
+/// objects added to a [`Graph`] after compilation are given the following
+/// indices.
 #[derive(Debug, Clone, Copy, PartialOrd, Ord, Hash, PartialEq, Eq)]
-pub struct ObjectId(u64);
+pub struct ObjectId(u32);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
@@ -68,19 +77,214 @@ impl Space {
 }
 
 impl ObjectId {
-    pub fn next() -> Self {
-        ObjectId(OBJECT_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        ObjectId(index.try_into().expect("too many objects in graph"))
     }
 }
 
+/// An arena holding the objects written while compiling a table.
+///
+/// The bytes and offsets of every object are appended to shared buffers,
+/// so adding an object does not allocate, and an object's [`ObjectId`] is
+/// its index in the arena. Identical objects are deduplicated by comparing
+/// them against the arena contents of objects with the same hash.
 #[derive(Debug, Default)]
 pub(crate) struct ObjectStore {
-    pub(crate) objects: HashMap<TableData, ObjectId>,
+    bytes: Vec<u8>,
+    offsets: Vec<OffsetRecord>,
+    pub(crate) objects: Vec<StoredObject>,
+    /// The most recently added object with a given hash.
+    by_hash: HashMap<u64, ObjectId, BuildHasherDefault<HashIdentity>>,
+    hasher: RandomState,
+}
+
+/// A hasher for keys that are already hashes.
+#[derive(Default)]
+struct HashIdentity(u64);
+
+impl Hasher for HashIdentity {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, _bytes: &[u8]) {
+        unreachable!("only used with u64 keys")
+    }
+
+    fn write_u64(&mut self, hash: u64) {
+        self.0 = hash;
+    }
+}
+
+/// The location of an object in an [`ObjectStore`].
+#[derive(Debug)]
+pub(crate) struct StoredObject {
+    type_: TableType,
+    bytes: Range<usize>,
+    offsets: Range<usize>,
+    /// The previously added object with the same hash, if any.
+    same_hash: Option<ObjectId>,
 }
 
 impl ObjectStore {
-    pub(crate) fn add(&mut self, data: TableData) -> ObjectId {
-        *self.objects.entry(data).or_insert_with(ObjectId::next)
+    /// Add a copy of `data`, unless an identical object already exists.
+    ///
+    /// As with [`TableData`]'s `PartialEq` impl, the type of the object is
+    /// not considered when looking for duplicates.
+    pub(crate) fn add(&mut self, data: &TableData) -> ObjectId {
+        let hash = self.hasher.hash_one(data);
+        let mut candidate = self.by_hash.get(&hash).copied();
+        while let Some(id) = candidate {
+            let existing = &self.objects[id.index()];
+            if self.bytes[existing.bytes.clone()] == data.bytes[..]
+                && self.offsets[existing.offsets.clone()] == data.offsets[..]
+            {
+                return id;
+            }
+            candidate = existing.same_hash;
+        }
+
+        let id = ObjectId::from_index(self.objects.len());
+        let bytes_start = self.bytes.len();
+        self.bytes.extend_from_slice(&data.bytes);
+        let offsets_start = self.offsets.len();
+        self.offsets.extend_from_slice(&data.offsets);
+        self.objects.push(StoredObject {
+            type_: data.type_,
+            bytes: bytes_start..self.bytes.len(),
+            offsets: offsets_start..self.offsets.len(),
+            same_hash: self.by_hash.insert(hash, id),
+        });
+        id
+    }
+
+    /// Copy each object out of the arena, in id order, and clear the arena.
+    ///
+    /// The arena's allocations are kept, so that it can be reused.
+    fn take_objects(&mut self) -> ObjectMap<TableData> {
+        let objects = ObjectMap {
+            items: self
+                .objects
+                .iter()
+                .map(|object| {
+                    Some(TableData {
+                        type_: object.type_,
+                        bytes: self.bytes[object.bytes.clone()].to_vec(),
+                        offsets: self.offsets[object.offsets.clone()].to_vec(),
+                    })
+                })
+                .collect(),
+            len: self.objects.len(),
+        };
+        self.bytes.clear();
+        self.offsets.clear();
+        self.objects.clear();
+        self.by_hash.clear();
+        objects
+    }
+}
+
+/// A map keyed by [`ObjectId`].
+///
+/// Since ids are dense indices this is stored as a vector, which makes
+/// lookups (such as when resolving offsets) constant time. Iteration is in
+/// id order.
+#[derive(Debug, Clone)]
+pub(crate) struct ObjectMap<T> {
+    items: Vec<Option<T>>,
+    len: usize,
+}
+
+impl<T> Default for ObjectMap<T> {
+    fn default() -> Self {
+        ObjectMap {
+            items: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> ObjectMap<T> {
+    /// Return an id that has never been used as a key in this map.
+    ///
+    /// Ids are never reused, even if the object they identified is removed.
+    fn reserve_id(&mut self) -> ObjectId {
+        self.items.push(None);
+        ObjectId::from_index(self.items.len() - 1)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn get(&self, id: &ObjectId) -> Option<&T> {
+        self.items.get(id.index()).and_then(Option::as_ref)
+    }
+
+    pub(crate) fn get_mut(&mut self, id: &ObjectId) -> Option<&mut T> {
+        self.items.get_mut(id.index()).and_then(Option::as_mut)
+    }
+
+    pub(crate) fn insert(&mut self, id: ObjectId, value: T) -> Option<T> {
+        if id.index() >= self.items.len() {
+            self.items.resize_with(id.index() + 1, || None);
+        }
+        let prev = self.items[id.index()].replace(value);
+        if prev.is_none() {
+            self.len += 1;
+        }
+        prev
+    }
+
+    pub(crate) fn remove(&mut self, id: &ObjectId) -> Option<T> {
+        let prev = self.items.get_mut(id.index()).and_then(Option::take);
+        if prev.is_some() {
+            self.len -= 1;
+        }
+        prev
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (ObjectId, &T)> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| Some((ObjectId::from_index(index), item.as_ref()?)))
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = ObjectId> + '_ {
+        self.iter().map(|(id, _)| id)
+    }
+
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.items.iter_mut().flatten()
+    }
+}
+
+impl<T> Index<&ObjectId> for ObjectMap<T> {
+    type Output = T;
+
+    fn index(&self, id: &ObjectId) -> &T {
+        self.get(id).expect("no object with id")
+    }
+}
+
+impl<T> IndexMut<&ObjectId> for ObjectMap<T> {
+    fn index_mut(&mut self, id: &ObjectId) -> &mut T {
+        self.get_mut(id).expect("no object with id")
+    }
+}
+
+impl<T> FromIterator<(ObjectId, T)> for ObjectMap<T> {
+    fn from_iter<I: IntoIterator<Item = (ObjectId, T)>>(iter: I) -> Self {
+        let mut map = ObjectMap::default();
+        for (id, value) in iter {
+            map.insert(id, value);
+        }
+        map
     }
 }
 
@@ -91,9 +295,9 @@ impl ObjectStore {
 //NOTE: we don't derive Debug because it's way too verbose to be useful
 pub struct Graph {
     /// the actual data for each table
-    objects: BTreeMap<ObjectId, TableData>,
+    objects: ObjectMap<TableData>,
     /// graph-specific state used for sorting
-    nodes: BTreeMap<ObjectId, Node>,
+    nodes: ObjectMap<Node>,
     order: Vec<ObjectId>,
     root: ObjectId,
     parents_invalid: bool,
@@ -202,16 +406,16 @@ impl Node {
 }
 
 impl Graph {
-    pub(crate) fn from_obj_store(store: ObjectStore, root: ObjectId) -> Self {
-        let objects = store.objects.into_iter().map(|(k, v)| (v, k)).collect();
-        Self::from_objects(objects, root)
+    /// Create a graph from the objects in `store`, leaving it empty.
+    pub(crate) fn from_obj_store(store: &mut ObjectStore, root: ObjectId) -> Self {
+        Self::from_objects(store.take_objects(), root)
     }
 
-    fn from_objects(objects: BTreeMap<ObjectId, TableData>, root: ObjectId) -> Self {
+    fn from_objects(objects: ObjectMap<TableData>, root: ObjectId) -> Self {
         let nodes = objects
             .iter()
             //TODO: ensure table sizes elsewhere?
-            .map(|(key, obj)| (*key, Node::new(obj.bytes.len().try_into().unwrap())))
+            .map(|(key, obj)| (key, Node::new(obj.bytes.len().try_into().unwrap())))
             .collect();
         Graph {
             objects,
@@ -255,14 +459,14 @@ impl Graph {
             !self.order.is_empty(),
             "graph must be sorted before serialization"
         );
-        let mut offsets = HashMap::new();
+        let mut positions: ObjectMap<u32> = ObjectMap::default();
         let mut out = Vec::new();
         let mut off = 0;
 
         // first pass: write out bytes, record positions of offsets
         for id in &self.order {
             let node = self.objects.get(id).unwrap();
-            offsets.insert(*id, off);
+            positions.insert(*id, off);
             off += node.bytes.len() as u32;
             out.extend_from_slice(&node.bytes);
         }
@@ -272,7 +476,7 @@ impl Graph {
         for id in &self.order {
             let node = self.objects.get(id).unwrap();
             for offset in &node.offsets {
-                let abs_off = *offsets
+                let abs_off = *positions
                     .get(&offset.object)
                     .expect("all offsets visited in first pass");
                 let rel_off = abs_off - (table_head + offset.adjustment);
@@ -359,8 +563,8 @@ impl Graph {
     }
 
     fn has_overflows(&self) -> bool {
-        for (parent_id, data) in &self.objects {
-            let parent = &self.nodes[parent_id];
+        for (parent_id, data) in self.objects.iter() {
+            let parent = &self.nodes[&parent_id];
             for link in &data.offsets {
                 let child = &self.nodes[&link.object];
                 //TODO: account for 'whence'
@@ -375,15 +579,15 @@ impl Graph {
 
     pub(crate) fn find_overflows(&self) -> Vec<Overflow> {
         let mut result = Vec::new();
-        for (parent_id, data) in &self.objects {
-            let parent = &self.nodes[parent_id];
+        for (parent_id, data) in self.objects.iter() {
+            let parent = &self.nodes[&parent_id];
             for link in &data.offsets {
                 let child = &self.nodes[&link.object];
                 //TODO: account for 'whence'
                 let rel_off = child.position - parent.position;
                 if link.len.max_value() < rel_off {
                     result.push(Overflow {
-                        parent: *parent_id,
+                        parent: parent_id,
                         child: link.object,
                         distance: rel_off,
                         offset_type: link.len,
@@ -431,13 +635,13 @@ impl Graph {
             node.parents.clear();
         }
 
-        for (id, obj) in &self.objects {
+        for (id, obj) in self.objects.iter() {
             for link in &obj.offsets {
                 self.nodes
                     .get_mut(&link.object)
                     .unwrap()
                     .parents
-                    .push((*id, link.len));
+                    .push((id, link.len));
             }
         }
         self.parents_invalid = false;
@@ -451,7 +655,6 @@ impl Graph {
             for id in self
                 .nodes
                 .keys()
-                .copied()
                 .collect::<HashSet<_>>()
                 .difference(&visited)
             {
@@ -464,7 +667,7 @@ impl Graph {
     fn sort_kahn(&mut self) {
         self.positions_invalid = true;
         if self.nodes.len() <= 1 {
-            self.order.extend(self.nodes.keys().copied());
+            self.order.extend(self.nodes.keys());
             return;
         }
 
@@ -861,15 +1064,6 @@ impl Graph {
     }
 
     fn actually_promote_subtables(&mut self, to_promote: &[ObjectId]) {
-        fn make_extension(type_: LookupType, subtable_id: ObjectId) -> TableData {
-            const EXT_FORMAT: u16 = 1;
-            let mut data = TableData::new(TableType::Named("ExtensionPosFormat1"));
-            data.write(EXT_FORMAT);
-            data.write(type_.to_raw());
-            data.add_offset(subtable_id, 4, 0);
-            data
-        }
-
         for id in to_promote {
             // 'id' is a lookup table.
             // we need to:
@@ -880,7 +1074,7 @@ impl Graph {
             let mut lookup = self.objects.remove(id).unwrap();
             let lookup_type = lookup.type_.to_lookup_type().expect("validated before now");
             for subtable_ref in &mut lookup.offsets {
-                let ext_table = make_extension(lookup_type, subtable_ref.object);
+                let ext_table = make_extension_table(lookup_type, subtable_ref.object);
                 let ext_id = self.add_object(ext_table);
                 subtable_ref.object = ext_id;
             }
@@ -903,7 +1097,7 @@ impl Graph {
         self.parents_invalid = true;
         self.distance_invalid = true;
 
-        let id = ObjectId::next();
+        let id = self.objects.reserve_id();
         self.nodes.insert(id, Node::new(data.bytes.len() as _));
         self.objects.insert(id, data);
         id
@@ -914,7 +1108,7 @@ impl Graph {
         let can_promote = self
             .objects
             .iter()
-            .filter_map(|(id, obj)| (obj.type_.is_promotable()).then_some(*id))
+            .filter_map(|(id, obj)| (obj.type_.is_promotable()).then_some(id))
             .collect::<Vec<_>>();
 
         if can_promote.is_empty() {
@@ -1043,7 +1237,7 @@ impl Graph {
         let splittable = self
             .objects
             .iter()
-            .filter_map(|(id, obj)| obj.type_.is_splittable().then_some(*id))
+            .filter_map(|(id, obj)| obj.type_.is_splittable().then_some(id))
             .collect::<Vec<_>>();
         for lookup in &splittable {
             self.split_subtables_if_needed(*lookup);
@@ -1056,16 +1250,33 @@ impl Graph {
     fn split_subtables_if_needed(&mut self, lookup: ObjectId) {
         // So You Want to Split Subtables:
         // - support PairPos and MarkBase.
-        let type_ = self.objects[&lookup].type_;
-        match type_ {
-            TableType::GposLookup(LookupType::PAIR_POS) => splitting::split_pair_pos(self, lookup),
-            TableType::GposLookup(LookupType::MARK_TO_BASE) => {
-                splitting::split_mark_to_base(self, lookup)
+        // - extension lookups are split based on the type of their subtables.
+        let type_ = match self.objects[&lookup].type_ {
+            TableType::GposLookup(LookupType::GPOS_EXT_TYPE) => {
+                match self.extension_lookup_type(lookup) {
+                    Some(type_) => type_,
+                    None => return,
+                }
             }
+            TableType::GposLookup(type_) => type_,
+            _ => return,
+        };
+        match type_ {
+            LookupType::PAIR_POS => splitting::split_pair_pos(self, lookup),
+            LookupType::MARK_TO_BASE => splitting::split_mark_to_base(self, lookup),
             _ => (),
         }
     }
 
+    /// The type of the subtables wrapped by an extension lookup.
+    ///
+    /// All subtables in a lookup must have the same type, so we just check
+    /// the first one.
+    fn extension_lookup_type(&self, lookup: ObjectId) -> Option<u16> {
+        let first_ext = self.objects[&lookup].offsets.first()?.object;
+        self.objects[&first_ext].read_at(2)
+    }
+
     /// the size only of children of this object, not the whole subgraph
     fn find_children_size(&self, id: ObjectId) -> usize {
         self.objects[&id]
@@ -1107,7 +1318,7 @@ impl Graph {
         }
         self.parents_invalid = true;
         self.distance_invalid = true;
-        let new_root = ObjectId::next();
+        let new_root = self.objects.reserve_id();
         log::trace!("duplicating node {root:?} to {new_root:?}");
 
         let mut obj = self.objects.get(&root).cloned().unwrap();
@@ -1177,6 +1388,22 @@ impl Graph {
     }
 }
 
+/// Create an extension subtable pointing to the subtable with `subtable_id`.
+///
+/// `type_` is the type of the lookup containing the (non-extension) subtable.
+fn make_extension_table(type_: LookupType, subtable_id: ObjectId) -> TableData {
+    const EXT_FORMAT: u16 = 1;
+    let name = match type_ {
+        LookupType::Gpos(_) => "ExtensionPosFormat1",
+        LookupType::Gsub(_) => "ExtensionSubstFormat1",
+    };
+    let mut data = TableData::new(TableType::Named(name));
+    data.write(EXT_FORMAT);
+    data.write(type_.to_raw());
+    data.add_offset(subtable_id, 4, 0);
+    data
+}
+
 impl Default for Priority {
     fn default() -> Self {
         Priority::ZERO
@@ -1194,11 +1421,7 @@ mod tests {
     use super::*;
 
     fn make_ids<const N: usize>() -> [ObjectId; N] {
-        let mut ids = [ObjectId::next(); N];
-        for id in ids.iter_mut().skip(1) {
-            *id = ObjectId::next();
-        }
-        ids
+        std::array::from_fn(ObjectId::from_index)
     }
 
     struct Link {
@@ -1233,7 +1456,7 @@ mod tests {
                     let table = TableData::make_mock(*size);
                     (*id, table)
                 })
-                .collect::<BTreeMap<_, _>>();
+                .collect::<ObjectMap<_>>();
 
             for link in &self.links {
                 objects
@@ -1327,6 +1550,30 @@ mod tests {
         assert_eq!(graph.find_overflows()[0].child, ids[2]);
     }
 
+    #[test]
+    fn object_store_arena() {
+        let data = |bytes: &[u8], type_| TableData {
+            type_,
+            bytes: bytes.to_vec(),
+            offsets: Vec::new(),
+        };
+        let mut store = ObjectStore::default();
+        let one = store.add(&data(&[1, 2], TableType::Named("One")));
+        let two = store.add(&data(&[3], TableType::Named("Two")));
+        // duplicates are found regardless of type
+        assert_eq!(store.add(&data(&[1, 2], TableType::Named("Other"))), one);
+        assert_eq!((one.index(), two.index()), (0, 1));
+
+        let objects = store.take_objects();
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[&one].bytes, [1, 2]);
+        assert_eq!(objects[&one].type_, TableType::Named("One"));
+        assert_eq!(objects[&two].bytes, [3]);
+
+        // the store can be reused
+        assert_eq!(store.add(&data(&[3], TableType::Named("Two"))).index(), 0);
+    }
+
     #[test]
     fn duplicate_subgraph() {
         let _ = env_logger::builder().is_test(true).try_init();
@@ -1740,6 +1987,64 @@ mod tests {
         assert_eq!(n_tables_before + 2, graph.order.len());
     }
 
+    /// A lookup that is already an extension can still have its subtables split
+    #[test]
+    fn pack_split_pairpos_in_extension_lookup() {
+        use crate::tables::{gpos, layout};
+        use read_fonts::{tables::gpos as rgpos, FontRead};
+
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let glyph_range = 1u16..500;
+        let coverage = glyph_range.clone().map(GlyphId16::new).collect();
+        let pair_sets = glyph_range
+            .map(|id| {
+                let value_rec = gpos::ValueRecord::new().with_x_advance(id as _);
+                gpos::PairSet::new(
+                    (id..id + 165)
+                        .map(|id2| {
+                            gpos::PairValueRecord::new(
+                                GlyphId16::new(id2),
+                                value_rec.clone(),
+                                gpos::ValueRecord::default(),
+                            )
+                        })
+                        .collect(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let pairpos = gpos::PairPos::format_1(coverage, pair_sets);
+        let extension = gpos::ExtensionSubtable::Pair(gpos::ExtensionPosFormat1::new(2, pairpos));
+        let lookup = gpos::PositionLookup::Extension(layout::Lookup::new(
+            layout::LookupFlag::empty(),
+            vec![extension],
+        ));
+        let table = gpos::Gpos::new(
+            Default::default(),
+            Default::default(),
+            layout::LookupList::new(vec![lookup]),
+        );
+
+        let bytes = crate::dump_table(&table).unwrap();
+        let gpos = rgpos::Gpos::read(bytes.as_slice().into()).unwrap();
+        let lookup = gpos.lookup_list().unwrap().lookups().get(0).unwrap();
+        let rgpos::PositionLookup::Extension(lookup) = lookup else {
+            panic!("lookup should still be an extension");
+        };
+        assert!(lookup.sub_table_count() > 1);
+        let mut n_pair_sets = 0;
+        for subtable in lookup.subtables().iter() {
+            let rgpos::ExtensionSubtable::Pair(ext) = subtable.unwrap() else {
+                panic!("all subtables should be PairPos");
+            };
+            let rgpos::PairPos::Format1(pairpos) = ext.extension().unwrap() else {
+                panic!("splitting should preserve the format");
+            };
+            n_pair_sets += pairpos.pair_set_count();
+        }
+        assert_eq!(n_pair_sets, 499);
+    }
+
     #[test]
     fn unpackable_graph_should_fail() {
         let _ = env_logger::builder().is_test(true).try_init();
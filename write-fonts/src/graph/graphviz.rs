@@ -17,7 +17,7 @@ impl<'a> GraphVizGraph<'a> {
         // if we are pruning it means that we remove all nodes in spaces
         // that do not include overflows.
         let nodes: BTreeSet<_> = if !prune_non_overflows {
-            graph.objects.keys().copied().collect()
+            graph.objects.keys().collect()
         } else {
             let overflows = graph.find_overflows();
             let overflow_spaces = overflows
@@ -27,15 +27,15 @@ impl<'a> GraphVizGraph<'a> {
             graph
                 .nodes
                 .iter()
-                .filter_map(|(id, node)| overflow_spaces.contains(&node.space).then_some(*id))
+                .filter_map(|(id, node)| overflow_spaces.contains(&node.space).then_some(id))
                 .collect()
         };
 
-        for (parent_id, table) in &graph.objects {
-            if !nodes.contains(parent_id) {
+        for (parent_id, table) in graph.objects.iter() {
+            if !nodes.contains(&parent_id) {
                 continue;
             }
-            let parent = &graph.nodes[parent_id];
+            let parent = &graph.nodes[&parent_id];
             for link in &table.offsets {
                 if !nodes.contains(&link.object) {
                     continue;
@@ -43,7 +43,7 @@ impl<'a> GraphVizGraph<'a> {
                 let child = &graph.nodes[&link.object];
                 let len = child.position - parent.position;
                 edges.push(GraphVizEdge {
-                    source: *parent_id,
+                    source: parent_id,
                     target: link.object,
                     len,
                     type_: link.len,
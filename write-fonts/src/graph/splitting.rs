@@ -18,7 +18,12 @@ use read_fonts::tables::{
 };
 
 use super::{Graph, ObjectId};
-use crate::{tables::layout as wlayout, write::TableData, FontWrite, TableWriter};
+use crate::{
+    table_type::TableType,
+    tables::layout::{self as wlayout, LookupType},
+    write::TableData,
+    FontWrite, TableWriter,
+};
 
 mod mark2base;
 mod pairpos;
@@ -43,10 +48,16 @@ fn split_subtables(
         "table splitting is only relevant for GPOS?"
     );
     log::debug!("trying to split subtables in '{}'", data.type_);
+    let is_extension = data.type_ == TableType::GposLookup(LookupType::GPOS_EXT_TYPE);
 
     let mut new_subtables = HashMap::new();
     for (i, subtable) in data.offsets.iter().enumerate() {
-        if let Some(split_subtables) = split_fn(graph, subtable.object) {
+        let split = if is_extension {
+            split_extension_subtable(graph, subtable.object, split_fn)
+        } else {
+            split_fn(graph, subtable.object)
+        };
+        if let Some(split_subtables) = split {
             log::trace!("produced {} splits for subtable {i}", split_subtables.len());
             new_subtables.insert(subtable.object, split_subtables);
         }
@@ -84,6 +95,27 @@ fn split_subtables(
     graph.objects.insert(lookup, new_data);
 }
 
+/// Split the subtable wrapped by an extension subtable.
+///
+/// Each of the new subtables gets its own extension subtable, and the ids
+/// of these extension subtables are returned.
+fn split_extension_subtable(
+    graph: &mut Graph,
+    extension: ObjectId,
+    split_fn: fn(&mut Graph, ObjectId) -> Option<Vec<ObjectId>>,
+) -> Option<Vec<ObjectId>> {
+    let data = &graph.objects[&extension];
+    let lookup_type = LookupType::Gpos(data.read_at(2)?);
+    let subtable = data.offsets.first()?.object;
+    let new_subtables = split_fn(graph, subtable)?;
+    Some(
+        new_subtables
+            .into_iter()
+            .map(|id| graph.add_object(super::make_extension_table(lookup_type, id)))
+            .collect(),
+    )
+}
+
 fn split_coverage(coverage: &rlayout::CoverageTable, start: u16, end: u16) -> TableData {
     assert!(start <= end);
     let len = end - start;
@@ -0,0 +1,923 @@
+//! Instancing variable fonts.
+//!
+//! An [`Instancer`] produces a new font from a variable TrueType font by
+//! pinning some of its axes to a single location, removing them from the
+//! font, and/or by restricting others to a smaller range. Pinning every axis
+//! produces a static font.
+//!
+//! This is modeled on the [instancer] in fonttools. The variations in `gvar`,
+//! `cvar`, `HVAR`, `VVAR`, `MVAR` and the `GDEF` item variation store are
+//! rebased onto the remaining design space, and any change in the default
+//! location is applied to the outlines, metrics and `GPOS` values. The `fvar`,
+//! `avar` and `STAT` tables, as well as the feature variations in `GSUB` and
+//! `GPOS`, are updated to match.
+//!
+//! Restricted ranges must contain the default location of the axis; moving
+//! the default of an axis that is not pinned is not supported. Fonts with
+//! `CFF2` outlines, `avar` version 2 or variations in `COLR` or `BASE` are
+//! also not supported.
+//!
+//! [instancer]: https://github.com/fonttools/fonttools/tree/main/Lib/fontTools/varLib/instancer
+
+mod glyf;
+mod ivs;
+mod layout;
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+};
+
+use read_fonts::{
+    tables::{avar::Avar as ReadAvar, fvar::VariationAxisRecord},
+    FontRef, ReadError, TableProvider, TopLevelTable,
+};
+use types::{F2Dot14, Fixed, GlyphId, MajorMinor, Tag};
+
+use crate::{
+    from_obj::ToOwnedTable,
+    tables::{
+        avar::{Avar, AxisValueMap, SegmentMaps},
+        cvar::{Cvar, CvarInputError},
+        fvar::Fvar,
+        gvar::{Gvar, GvarInputError},
+        hhea::Hhea,
+        hvar::Hvar,
+        mvar::Mvar,
+        os2::Os2,
+        post::Post,
+        stat::{AxisValue, Stat},
+        variations::Tuple,
+        vhea::Vhea,
+    },
+    BuilderError, FontBuilder,
+};
+
+/// The restriction applied to a single axis, in user space coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AxisLimit {
+    /// Pin the axis at a single location, removing it from the font.
+    Pin(f32),
+    /// Restrict the axis to a range, which must contain the axis default.
+    Range { min: f32, max: f32 },
+}
+
+/// A builder for instancing a variable font.
+///
+/// Axes without a limit are left unchanged.
+///
+/// # Example
+///
+/// ```no_run
+/// use read_fonts::FontRef;
+/// use write_fonts::{types::Tag, Instancer};
+/// # let bytes: Vec<u8> = Vec::new();
+/// let font = FontRef::new(&bytes).unwrap();
+/// let instance = Instancer::new()
+///     .pin(Tag::new(b"wght"), 700.0)
+///     .limit(Tag::new(b"wdth"), 75.0, 100.0)
+///     .instantiate(&font)
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Instancer {
+    limits: BTreeMap<Tag, AxisLimit>,
+}
+
+/// An error that occurs while instancing a font.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum InstancerError {
+    /// The font has no 'fvar' table.
+    NotVariable,
+    /// A limit was provided for an axis that is not in the font.
+    UnknownAxis(Tag),
+    /// A restricted range does not contain the default of its axis.
+    DefaultOutsideRange(Tag),
+    /// The font contains variations in a table that cannot be instanced.
+    UnsupportedTable(Tag),
+    /// A table could not be read.
+    Read(ReadError),
+    /// A table could not be compiled.
+    Build(BuilderError),
+    /// The instanced glyph variations are invalid.
+    Gvar(GvarInputError),
+    /// The instanced CVT variations are invalid.
+    Cvar(CvarInputError),
+    /// The points of a glyph do not match its contours or variations.
+    MalformedGlyph(GlyphId),
+}
+
+/// How a single 'fvar' axis changes, in normalized coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum AxisChange {
+    Keep,
+    Pin(f64),
+    /// The new normalized limits; `min <= 0 <= max`.
+    Limit {
+        min: f64,
+        max: f64,
+    },
+}
+
+/// The region of one axis, as normalized (start, peak, end) coordinates.
+type Tent = (f64, f64, f64);
+
+/// Tables with fields that are updated in place while instancing.
+struct MetricsTables {
+    os2: Option<Os2>,
+    post: Option<Post>,
+    hhea: Option<Hhea>,
+    vhea: Option<Vhea>,
+}
+
+/// The axis changes for a font, in 'fvar' axis order.
+#[derive(Clone, Debug)]
+struct InstancePlan {
+    changes: Vec<AxisChange>,
+    new_axis_count: u16,
+}
+
+impl Instancer {
+    /// Create a new instancer that leaves every axis unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pin the axis with the given tag at a user space value.
+    ///
+    /// The value is clamped to the range of the axis.
+    pub fn pin(&mut self, tag: Tag, value: f32) -> &mut Self {
+        self.limits.insert(tag, AxisLimit::Pin(value));
+        self
+    }
+
+    /// Restrict the axis with the given tag to a user space range.
+    ///
+    /// The range is clamped to the range of the axis, and must contain the
+    /// axis default.
+    pub fn limit(&mut self, tag: Tag, min: f32, max: f32) -> &mut Self {
+        self.limits.insert(tag, AxisLimit::Range { min, max });
+        self
+    }
+
+    /// Set the limit for the axis with the given tag.
+    pub fn set_limit(&mut self, tag: Tag, limit: AxisLimit) -> &mut Self {
+        self.limits.insert(tag, limit);
+        self
+    }
+
+    /// Instance the font, returning the compiled binary.
+    pub fn instantiate(&self, font: &FontRef) -> Result<Vec<u8>, InstancerError> {
+        let fvar = font.fvar().map_err(|_| InstancerError::NotVariable)?;
+        let axes = fvar.axes()?;
+        let avar = font.avar().ok();
+        if let Some(tag) = self
+            .limits
+            .keys()
+            .find(|tag| !axes.iter().any(|axis| axis.axis_tag() == **tag))
+        {
+            return Err(InstancerError::UnknownAxis(*tag));
+        }
+        check_supported(font, avar.as_ref())?;
+        let plan = self.plan(axes, avar.as_ref())?;
+        let is_static = plan.new_axis_count == 0;
+
+        let mut builder = FontBuilder::new();
+        let mut tables = MetricsTables {
+            os2: font.os2().ok().map(|t| t.to_owned_table()),
+            post: font.post().ok().map(|t| t.to_owned_table()),
+            hhea: font.hhea().ok().map(|t| t.to_owned_table()),
+            vhea: font.vhea().ok().map(|t| t.to_owned_table()),
+        };
+        // tables that are removed from the font, rather than copied or replaced
+        let mut dropped = HashSet::new();
+        if is_static {
+            dropped.extend([Fvar::TAG, Avar::TAG, Gvar::TAG, Cvar::TAG]);
+        }
+
+        glyf::instance_glyphs(font, &plan, &mut tables, &mut builder)?;
+        glyf::instance_cvar(font, &plan, &mut builder)?;
+
+        if let Ok(mvar) = font.mvar() {
+            let store = match mvar.item_variation_store().transpose()? {
+                Some(store) => Some(ivs::instance_store(&store, &plan)?),
+                None => None,
+            };
+            if let Some(store) = store.as_ref() {
+                ivs::apply_mvar_defaults(&mvar, store, &mut tables);
+            }
+            match store.and_then(|store| store.store) {
+                Some(store) => {
+                    let mut mvar: Mvar = mvar.to_owned_table();
+                    mvar.item_variation_store = store.into();
+                    builder.add_table(&mvar)?;
+                }
+                None => {
+                    dropped.insert(Mvar::TAG);
+                }
+            }
+        }
+        // the default advances are computed from the phantom points in 'gvar',
+        // so the default deltas of the metrics variations are discarded.
+        if let Ok(hvar) = font.hvar() {
+            match ivs::instance_store(&hvar.item_variation_store()?, &plan)?.store {
+                Some(store) => {
+                    let mut hvar: Hvar = hvar.to_owned_table();
+                    hvar.item_variation_store = store.into();
+                    builder.add_table(&hvar)?;
+                }
+                None => {
+                    dropped.insert(Hvar::TAG);
+                }
+            }
+        }
+        // the vertical metrics are also available from the phantom points, so
+        // rather than being instanced, 'VVAR' is removed.
+        dropped.insert(Tag::new(b"VVAR"));
+        layout::instance_layout(font, &plan, &mut builder)?;
+
+        for (tag, limit) in &self.limits {
+            let AxisLimit::Pin(value) = *limit else {
+                continue;
+            };
+            let axis = axes.iter().find(|axis| axis.axis_tag() == *tag).unwrap();
+            let value = Fixed::from_f64(value as f64)
+                .clamp(axis.min_value(), axis.max_value())
+                .to_f64();
+            update_default_style(*tag, value, &mut tables);
+        }
+        if let Some(os2) = tables.os2.as_ref() {
+            builder.add_table(os2)?;
+        }
+        if let Some(post) = tables.post.as_ref() {
+            builder.add_table(post)?;
+        }
+        if let Some(hhea) = tables.hhea.as_ref() {
+            builder.add_table(hhea)?;
+        }
+        if let Some(vhea) = tables.vhea.as_ref() {
+            builder.add_table(vhea)?;
+        }
+
+        if !is_static {
+            builder.add_table(&self.instance_fvar(font)?)?;
+            if let Some(avar) = avar.as_ref() {
+                match self.instance_avar(axes, avar)? {
+                    Some(avar) => {
+                        builder.add_table(&avar)?;
+                    }
+                    None => {
+                        dropped.insert(Avar::TAG);
+                    }
+                }
+            }
+        }
+        if let Ok(stat) = font.stat() {
+            builder.add_table(&self.instance_stat(&stat.to_owned_table()))?;
+        }
+
+        for record in font.table_directory.table_records() {
+            let tag = record.tag();
+            if builder.contains(tag) || dropped.contains(&tag) {
+                continue;
+            }
+            if let Some(data) = font.table_data(tag) {
+                builder.add_raw(tag, data.as_bytes());
+            }
+        }
+        // any signature is invalidated by the changes
+        builder.strip_dsig();
+        Ok(builder.build())
+    }
+
+    /// Compute the normalized change of each axis.
+    fn plan(
+        &self,
+        axes: &[VariationAxisRecord],
+        avar: Option<&ReadAvar>,
+    ) -> Result<InstancePlan, InstancerError> {
+        let mut changes = Vec::with_capacity(axes.len());
+        for (i, axis) in axes.iter().enumerate() {
+            let normalize = |value: f32| {
+                let coord = axis.normalize(Fixed::from_f64(value as f64));
+                avar.and_then(|avar| avar.axis_segment_maps().get(i)?.ok())
+                    .map(|map| map.apply(coord))
+                    .unwrap_or(coord)
+                    .to_f2dot14()
+                    .to_f32() as f64
+            };
+            let change = match self.limits.get(&axis.axis_tag()) {
+                None => AxisChange::Keep,
+                Some(AxisLimit::Pin(value)) => AxisChange::Pin(normalize(*value)),
+                Some(AxisLimit::Range { min, max }) => {
+                    let default = axis.default_value().to_f64();
+                    if (*min as f64) > default || (*max as f64) < default {
+                        return Err(InstancerError::DefaultOutsideRange(axis.axis_tag()));
+                    }
+                    match (normalize(*min), normalize(*max)) {
+                        (min, max) if min == 0.0 && max == 0.0 => AxisChange::Pin(0.0),
+                        (-1.0, 1.0) => AxisChange::Keep,
+                        (min, max) => AxisChange::Limit { min, max },
+                    }
+                }
+            };
+            changes.push(change);
+        }
+        let new_axis_count = changes
+            .iter()
+            .filter(|change| !matches!(change, AxisChange::Pin(_)))
+            .count() as u16;
+        Ok(InstancePlan {
+            changes,
+            new_axis_count,
+        })
+    }
+
+    /// The user space range of an axis after instancing, or `None` if it is pinned.
+    fn axis_range(&self, axis: &VariationAxisRecord) -> Option<(Fixed, Fixed)> {
+        let (min, max) = (axis.min_value(), axis.max_value());
+        let clamp = |value: f32| Fixed::from_f64(value as f64).clamp(min, max);
+        match self.limits.get(&axis.axis_tag()) {
+            None => Some((min, max)),
+            Some(AxisLimit::Pin(_)) => None,
+            Some(AxisLimit::Range { min, max }) => Some((clamp(*min), clamp(*max))),
+        }
+    }
+
+    /// Remove pinned axes from 'fvar', and the named instances that are no
+    /// longer reachable.
+    fn instance_fvar(&self, font: &FontRef) -> Result<Fvar, InstancerError> {
+        let fvar = font.fvar()?;
+        let read_axes = fvar.axes()?;
+        let mut owned: Fvar = fvar.to_owned_table();
+        let arrays = &mut *owned.axis_instance_arrays;
+        arrays.instances.retain_mut(|instance| {
+            let mut coords = Vec::with_capacity(instance.coordinates.len());
+            for (axis, coord) in read_axes.iter().zip(&instance.coordinates) {
+                match self.limits.get(&axis.axis_tag()) {
+                    Some(AxisLimit::Pin(value)) => {
+                        let value = Fixed::from_f64(*value as f64)
+                            .clamp(axis.min_value(), axis.max_value());
+                        if *coord != value {
+                            return false;
+                        }
+                    }
+                    _ => {
+                        let (min, max) = self.axis_range(axis).unwrap();
+                        if *coord < min || *coord > max {
+                            return false;
+                        }
+                        coords.push(*coord);
+                    }
+                }
+            }
+            instance.coordinates = coords;
+            true
+        });
+        let mut kept = Vec::with_capacity(arrays.axes.len());
+        for (axis, read_axis) in arrays.axes.drain(..).zip(read_axes) {
+            if let Some((min, max)) = self.axis_range(read_axis) {
+                kept.push(crate::tables::fvar::VariationAxisRecord {
+                    min_value: min,
+                    max_value: max,
+                    ..axis
+                });
+            }
+        }
+        arrays.axes = kept;
+        Ok(owned)
+    }
+
+    /// Remove pinned axes from 'avar' and remap the segments of restricted
+    /// axes, or return `None` if the remaining mappings are all identities.
+    fn instance_avar(
+        &self,
+        axes: &[VariationAxisRecord],
+        avar: &ReadAvar,
+    ) -> Result<Option<Avar>, InstancerError> {
+        let mut segment_maps = Vec::new();
+        for (axis, map) in axes.iter().zip(avar.axis_segment_maps().iter()) {
+            let map = map?;
+            let Some((min, max)) = self.axis_range(axis) else {
+                continue;
+            };
+            let maps = map
+                .axis_value_maps()
+                .iter()
+                .map(|m| (m.from_coordinate().to_f32(), m.to_coordinate().to_f32()));
+            if (min, max) == (axis.min_value(), axis.max_value()) {
+                segment_maps.push(SegmentMaps::new(
+                    maps.map(|(from, to)| AxisValueMap::new(f2dot14(from), f2dot14(to)))
+                        .collect(),
+                ));
+                continue;
+            }
+            // the limits before and after applying the mapping
+            let min_from = axis.normalize(min).to_f32();
+            let max_from = axis.normalize(max).to_f32();
+            let min_to = map.apply(axis.normalize(min)).to_f32();
+            let max_to = map.apply(axis.normalize(max)).to_f32();
+            let rescale = |value: f32, min: f32, max: f32| match value {
+                v if v < 0.0 => -v / min,
+                v if v > 0.0 => v / max,
+                _ => 0.0,
+            };
+            let mut new_maps: Vec<(f32, f32)> = maps
+                .filter(|(from, _)| *from >= min_from && *from <= max_from)
+                .filter(|(from, _)| (*from < 0.0 && min_from < 0.0) || *from >= 0.0)
+                .filter(|(from, _)| (*from > 0.0 && max_from > 0.0) || *from <= 0.0)
+                .map(|(from, to)| {
+                    (
+                        rescale(from, min_from, max_from),
+                        rescale(to, min_to, max_to),
+                    )
+                })
+                .collect();
+            new_maps.extend([(-1.0, -1.0), (0.0, 0.0), (1.0, 1.0)]);
+            new_maps.sort_by(|a, b| a.0.total_cmp(&b.0));
+            new_maps.dedup_by(|a, b| f2dot14(a.0) == f2dot14(b.0));
+            segment_maps.push(SegmentMaps::new(
+                new_maps
+                    .into_iter()
+                    .map(|(from, to)| AxisValueMap::new(f2dot14(from), f2dot14(to)))
+                    .collect(),
+            ));
+        }
+        if segment_maps.iter().all(SegmentMaps::is_identity) {
+            return Ok(None);
+        }
+        Ok(Some(Avar::new(segment_maps)))
+    }
+
+    /// Remove the axis values that are outside of the new axis limits.
+    fn instance_stat(&self, stat: &Stat) -> Stat {
+        let mut stat = stat.clone();
+        let tags: Vec<_> = stat.design_axes.iter().map(|axis| axis.axis_tag).collect();
+        let outside = |axis_index: u16, value: Fixed| {
+            let Some(limit) = tags
+                .get(axis_index as usize)
+                .and_then(|tag| self.limits.get(tag))
+            else {
+                return false;
+            };
+            match *limit {
+                AxisLimit::Pin(pin) => value != Fixed::from_f64(pin as f64),
+                AxisLimit::Range { min, max } => {
+                    value < Fixed::from_f64(min as f64) || value > Fixed::from_f64(max as f64)
+                }
+            }
+        };
+        if let Some(axis_values) = stat.offset_to_axis_values.as_mut() {
+            axis_values.retain(|value| match &**value {
+                AxisValue::Format1(value) => !outside(value.axis_index, value.value),
+                AxisValue::Format2(value) => !outside(value.axis_index, value.nominal_value),
+                AxisValue::Format3(value) => !outside(value.axis_index, value.value),
+                AxisValue::Format4(value) => !value
+                    .axis_values
+                    .iter()
+                    .any(|record| outside(record.axis_index, record.value)),
+            });
+            if axis_values.is_empty() {
+                stat.offset_to_axis_values = Default::default();
+            }
+        }
+        stat
+    }
+}
+
+/// Return an error if the font has variations that cannot be instanced.
+fn check_supported(font: &FontRef, avar: Option<&ReadAvar>) -> Result<(), InstancerError> {
+    if avar.is_some_and(|avar| avar.version() != MajorMinor::VERSION_1_0) {
+        return Err(InstancerError::UnsupportedTable(Avar::TAG));
+    }
+    if font.cff2().is_ok() {
+        return Err(InstancerError::UnsupportedTable(Tag::new(b"CFF2")));
+    }
+    if font
+        .colr()
+        .is_ok_and(|colr| colr.item_variation_store().is_some())
+    {
+        return Err(InstancerError::UnsupportedTable(Tag::new(b"COLR")));
+    }
+    if font
+        .base()
+        .is_ok_and(|base| base.item_var_store().is_some())
+    {
+        return Err(InstancerError::UnsupportedTable(Tag::new(b"BASE")));
+    }
+    Ok(())
+}
+
+/// Update the style fields that depend on the location of a pinned axis.
+///
+/// This follows `setDefaultWeightWidthSlant` in the fonttools instancer.
+fn update_default_style(tag: Tag, value: f64, tables: &mut MetricsTables) {
+    // the 'wdth' values corresponding to each OS/2 width class
+    const WIDTH_CLASSES: [f64; 9] = [50.0, 62.5, 75.0, 87.5, 100.0, 112.5, 125.0, 150.0, 200.0];
+    match (
+        &tag.to_be_bytes(),
+        tables.os2.as_mut(),
+        tables.post.as_mut(),
+    ) {
+        (b"wght", Some(os2), _) => os2.us_weight_class = value.clamp(1.0, 1000.0).round() as u16,
+        (b"wdth", Some(os2), _) => {
+            let value = value.clamp(50.0, 200.0);
+            let upper = WIDTH_CLASSES
+                .iter()
+                .position(|width| value <= *width)
+                .unwrap_or(WIDTH_CLASSES.len() - 1)
+                .max(1);
+            let (lower_width, upper_width) = (WIDTH_CLASSES[upper - 1], WIDTH_CLASSES[upper]);
+            let class = upper as f64 + (value - lower_width) / (upper_width - lower_width);
+            os2.us_width_class = class.round() as u16;
+        }
+        (b"slnt", _, Some(post)) => {
+            post.italic_angle = Fixed::from_f64(value.clamp(-90.0, 90.0));
+        }
+        _ => (),
+    }
+}
+
+impl InstancePlan {
+    /// Rebase a region of the original design space onto the remaining axes.
+    ///
+    /// Returns the regions that together reproduce the contribution of the
+    /// original region within the new limits, each with the scalar its deltas
+    /// must be multiplied by. A region in which every peak is zero represents
+    /// the default location. The result is empty if the region no longer
+    /// contributes.
+    fn instance_region(&self, region: &[Tent]) -> Vec<(f64, Vec<Tent>)> {
+        let mut result = vec![(1.0, Vec::with_capacity(self.new_axis_count as usize))];
+        for (i, change) in self.changes.iter().enumerate() {
+            let tent = region.get(i).copied().unwrap_or_default();
+            match *change {
+                AxisChange::Keep => result.iter_mut().for_each(|(_, r)| r.push(tent)),
+                AxisChange::Pin(coord) => {
+                    let scalar = axis_scalar(tent, coord);
+                    if scalar == 0.0 {
+                        return Vec::new();
+                    }
+                    result.iter_mut().for_each(|(s, _)| *s *= scalar);
+                }
+                AxisChange::Limit { min, max } => {
+                    let parts = limit_tent(tent, min, max);
+                    result = result
+                        .iter()
+                        .flat_map(|(scalar, r)| {
+                            parts.iter().map(move |(part_scalar, part)| {
+                                let mut r = r.clone();
+                                r.push(*part);
+                                (scalar * part_scalar, r)
+                            })
+                        })
+                        .collect();
+                }
+            }
+            if result.is_empty() {
+                break;
+            }
+        }
+        result
+    }
+}
+
+/// The scalar of a single axis of a region at the given coordinate.
+///
+/// Regions that are invalid according to the spec do not constrain the axis.
+fn axis_scalar((start, peak, end): Tent, coord: f64) -> f64 {
+    if peak == 0.0 || peak == coord || start > peak || peak > end || (start < 0.0 && end > 0.0) {
+        return 1.0;
+    }
+    if coord <= start || coord >= end {
+        0.0
+    } else if coord < peak {
+        (coord - start) / (peak - start)
+    } else {
+        (end - coord) / (end - peak)
+    }
+}
+
+/// Rebase the region of one axis onto the normalized range `min..=max`.
+fn limit_tent(tent: Tent, min: f64, max: f64) -> Vec<(f64, Tent)> {
+    let (start, peak, end) = tent;
+    if peak == 0.0 || start > peak || peak > end || (start < 0.0 && end > 0.0) {
+        return vec![(1.0, Tent::default())];
+    }
+    if peak < 0.0 {
+        return limit_positive_tent((-end, -peak, -start), -min)
+            .into_iter()
+            .map(|(scalar, (start, peak, end))| (scalar, (-end, -peak, -start)))
+            .collect();
+    }
+    limit_positive_tent(tent, max)
+}
+
+/// Rebase a region with a positive peak onto the normalized range `0..=limit`.
+fn limit_positive_tent((start, peak, end): Tent, limit: f64) -> Vec<(f64, Tent)> {
+    if start >= limit {
+        return Vec::new();
+    }
+    let (start, peak, end) = (start / limit, peak / limit, end / limit);
+    if peak > 1.0 {
+        // the peak is no longer reachable, so the region is cut off at the
+        // new maximum, where it has a scalar below one.
+        vec![((1.0 - start) / (peak - start), (start, 1.0, 1.0))]
+    } else if end <= 1.0 || peak == 1.0 {
+        vec![(1.0, (start, peak, end.min(1.0)))]
+    } else {
+        // the region still contributes at the new maximum, which needs a
+        // second region ramping up from the peak.
+        vec![
+            (1.0, (start, peak, 1.0)),
+            ((end - 1.0) / (end - peak), (peak, 1.0, 1.0)),
+        ]
+    }
+}
+
+/// Returns `true` if every axis of the region has a zero peak.
+fn is_default_region(region: &[Tent]) -> bool {
+    region.iter().all(|(_, peak, _)| *peak == 0.0)
+}
+
+/// A hashable representation of a region, as encoded in the font.
+fn region_key(region: &[Tent]) -> Vec<[i16; 3]> {
+    region
+        .iter()
+        .map(|(start, peak, end)| {
+            [
+                f2dot14(*start as f32).to_bits(),
+                f2dot14(*peak as f32).to_bits(),
+                f2dot14(*end as f32).to_bits(),
+            ]
+        })
+        .collect()
+}
+
+/// The regions of a tuple variation, with implicit intermediate regions
+/// filled in.
+fn tuple_region(
+    peak: &read_fonts::tables::variations::Tuple,
+    start: Option<read_fonts::tables::variations::Tuple>,
+    end: Option<read_fonts::tables::variations::Tuple>,
+) -> Vec<Tent> {
+    let coord = |tuple: &read_fonts::tables::variations::Tuple, i| {
+        tuple.get(i).unwrap_or_default().to_f32() as f64
+    };
+    (0..peak.len())
+        .map(|i| {
+            let p = coord(peak, i);
+            match (&start, &end) {
+                (Some(start), Some(end)) => (coord(start, i), p, coord(end, i)),
+                _ => (p.min(0.0), p, p.max(0.0)),
+            }
+        })
+        .collect()
+}
+
+/// The peak and, if needed, intermediate tuples for a region.
+fn region_tuples(region: &[Tent]) -> (Tuple, Option<(Tuple, Tuple)>) {
+    let tuple =
+        |f: fn(&Tent) -> f64| Tuple::new(region.iter().map(|t| f2dot14(f(t) as f32)).collect());
+    let peak = tuple(|t| t.1);
+    let implicit = region
+        .iter()
+        .all(|(start, peak, end)| *start == peak.min(0.0) && *end == peak.max(0.0));
+    let intermediate = (!implicit).then(|| (tuple(|t| t.0), tuple(|t| t.2)));
+    (peak, intermediate)
+}
+
+fn f2dot14(value: f32) -> F2Dot14 {
+    F2Dot14::from_f32(value)
+}
+
+impl Display for InstancerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstancerError::NotVariable => write!(f, "the font is not variable"),
+            InstancerError::UnknownAxis(tag) => write!(f, "the font has no '{tag}' axis"),
+            InstancerError::DefaultOutsideRange(tag) => {
+                write!(f, "the range for '{tag}' does not contain the axis default")
+            }
+            InstancerError::UnsupportedTable(tag) => {
+                write!(f, "variations in '{tag}' cannot be instanced")
+            }
+            InstancerError::Read(error) => write!(f, "failed to read table: {error}"),
+            InstancerError::Build(error) => {
+                write!(f, "failed to compile '{}': {}", error.tag, error.inner)
+            }
+            InstancerError::Gvar(error) => write!(f, "invalid glyph variations: {error}"),
+            InstancerError::Cvar(error) => write!(f, "invalid CVT variations: {error}"),
+            InstancerError::MalformedGlyph(gid) => write!(f, "glyph {gid} is malformed"),
+        }
+    }
+}
+
+impl std::error::Error for InstancerError {}
+
+impl From<ReadError> for InstancerError {
+    fn from(value: ReadError) -> Self {
+        InstancerError::Read(value)
+    }
+}
+
+impl From<BuilderError> for InstancerError {
+    fn from(value: BuilderError) -> Self {
+        InstancerError::Build(value)
+    }
+}
+
+impl From<GvarInputError> for InstancerError {
+    fn from(value: GvarInputError) -> Self {
+        InstancerError::Gvar(value)
+    }
+}
+
+impl From<CvarInputError> for InstancerError {
+    fn from(value: CvarInputError) -> Self {
+        InstancerError::Cvar(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OtRound;
+
+    fn plan(changes: Vec<AxisChange>) -> InstancePlan {
+        let new_axis_count = changes
+            .iter()
+            .filter(|change| !matches!(change, AxisChange::Pin(_)))
+            .count() as u16;
+        InstancePlan {
+            changes,
+            new_axis_count,
+        }
+    }
+
+    // evaluate a set of instanced regions at a location on the remaining axes
+    fn evaluate(regions: &[(f64, Vec<Tent>)], coords: &[f64]) -> f64 {
+        regions
+            .iter()
+            .map(|(scalar, region)| {
+                scalar
+                    * region
+                        .iter()
+                        .zip(coords)
+                        .map(|(tent, coord)| axis_scalar(*tent, *coord))
+                        .product::<f64>()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn pin_scales_region() {
+        let plan = plan(vec![AxisChange::Pin(0.5), AxisChange::Keep]);
+        let regions = plan.instance_region(&[(0.0, 1.0, 1.0), (0.0, 1.0, 1.0)]);
+        assert_eq!(regions, vec![(0.5, vec![(0.0, 1.0, 1.0)])]);
+
+        assert!(plan
+            .instance_region(&[(-1.0, -1.0, 0.0), (0.0, 1.0, 1.0)])
+            .is_empty());
+        let regions = plan.instance_region(&[(0.0, 0.5, 0.5), (0.0, 0.0, 0.0)]);
+        assert_eq!(regions, vec![(1.0, vec![(0.0, 0.0, 0.0)])]);
+        assert!(is_default_region(&regions[0].1));
+    }
+
+    #[test]
+    fn limit_matches_original_scalars() {
+        let tents = [
+            (0.0, 1.0, 1.0),
+            (0.0, 0.5, 1.0),
+            (0.2, 0.4, 0.6),
+            (0.2, 0.8, 1.0),
+            (0.5, 0.7, 0.9),
+            (-1.0, -1.0, 0.0),
+            (-0.8, -0.3, 0.0),
+        ];
+        for (min, max) in [(-1.0, 0.5), (-0.5, 0.3), (-0.25, 1.0), (0.0, 0.6)] {
+            let plan = plan(vec![AxisChange::Limit { min, max }]);
+            for tent in tents {
+                let regions = plan.instance_region(&[tent]);
+                for i in -20..=20 {
+                    let coord = i as f64 / 20.0;
+                    let old_coord = if coord < 0.0 {
+                        -coord * min
+                    } else {
+                        coord * max
+                    };
+                    let expected = axis_scalar(tent, old_coord);
+                    let actual = evaluate(&regions, &[coord]);
+                    assert!(
+                        (expected - actual).abs() < 1e-9,
+                        "{tent:?} in {min}..{max} at {coord}: {expected} != {actual}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pin_all_axes() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let data = Instancer::new()
+            .pin(Tag::new(b"wght"), 700.0)
+            .instantiate(&font)
+            .unwrap();
+        let instance = FontRef::new(&data).unwrap();
+        for tag in [b"fvar", b"avar", b"gvar", b"HVAR", b"MVAR"] {
+            assert!(instance.table_data(Tag::new(tag)).is_none());
+        }
+        assert!(instance.table_data(Tag::new(b"glyf")).is_some());
+    }
+
+    #[test]
+    fn limit_axis() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let data = Instancer::new()
+            .limit(Tag::new(b"wght"), 300.0, 600.0)
+            .instantiate(&font)
+            .unwrap();
+        let instance = FontRef::new(&data).unwrap();
+        let fvar = instance.fvar().unwrap();
+        let axis = fvar.axes().unwrap()[0];
+        assert_eq!(axis.min_value().to_f32(), 300.0);
+        assert_eq!(axis.default_value().to_f32(), 400.0);
+        assert_eq!(axis.max_value().to_f32(), 600.0);
+        assert!(instance.gvar().is_ok());
+        assert_eq!(
+            instance.maxp().unwrap().num_glyphs(),
+            font.maxp().unwrap().num_glyphs()
+        );
+    }
+
+    #[test]
+    fn pin_updates_cvt() {
+        let font = FontRef::new(font_test_data::CVAR).unwrap();
+        let fvar = font.fvar().unwrap();
+        let axes = fvar.axes().unwrap();
+        let mut instancer = Instancer::new();
+        for axis in axes {
+            instancer.pin(axis.axis_tag(), axis.max_value().to_f32());
+        }
+        let data = instancer.instantiate(&font).unwrap();
+        let instance = FontRef::new(&data).unwrap();
+        assert!(instance.table_data(Tag::new(b"cvar")).is_none());
+
+        let cvt = |font: &FontRef| -> Vec<i16> {
+            font.table_data(Tag::new(b"cvt "))
+                .unwrap()
+                .as_bytes()
+                .chunks_exact(2)
+                .map(|chunk| i16::from_be_bytes([chunk[0], chunk[1]]))
+                .collect()
+        };
+        let old_cvt = cvt(&font);
+        let coords = vec![F2Dot14::ONE; axes.len()];
+        let mut deltas = vec![0; old_cvt.len()];
+        font.cvar()
+            .unwrap()
+            .deltas(axes.len() as u16, &coords, &mut deltas)
+            .unwrap();
+        let expected: Vec<i16> = old_cvt
+            .iter()
+            .zip(&deltas)
+            .map(|(value, delta)| (*value as f64 + Fixed::from_bits(*delta).to_f64()).ot_round())
+            .collect();
+        assert_ne!(old_cvt, expected);
+        assert_eq!(cvt(&instance), expected);
+    }
+
+    #[test]
+    fn invalid_limits() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let result = Instancer::new()
+            .pin(Tag::new(b"wdth"), 100.0)
+            .instantiate(&font);
+        assert!(
+            matches!(result, Err(InstancerError::UnknownAxis(tag)) if tag == Tag::new(b"wdth"))
+        );
+        let result = Instancer::new()
+            .limit(Tag::new(b"wght"), 500.0, 700.0)
+            .instantiate(&font);
+        assert!(matches!(
+            result,
+            Err(InstancerError::DefaultOutsideRange(_))
+        ));
+        let font = FontRef::new(font_test_data::SIMPLE_GLYF).unwrap();
+        let result = Instancer::new().instantiate(&font);
+        assert!(matches!(result, Err(InstancerError::NotVariable)));
+    }
+
+    #[test]
+    fn implicit_intermediate_regions() {
+        let (peak, intermediate) = region_tuples(&[(0.0, 1.0, 1.0), (-0.5, -0.5, 0.0)]);
+        assert_eq!(peak.values, [f2dot14(1.0), f2dot14(-0.5)]);
+        assert!(intermediate.is_none());
+
+        let (_, intermediate) = region_tuples(&[(0.0, 0.5, 1.0), (0.0, 0.0, 0.0)]);
+        let (start, end) = intermediate.unwrap();
+        assert_eq!(start.values, [f2dot14(0.0), f2dot14(0.0)]);
+        assert_eq!(end.values, [f2dot14(1.0), f2dot14(0.0)]);
+    }
+}
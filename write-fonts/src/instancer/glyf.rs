@@ -0,0 +1,550 @@
+//! Instancing glyph outlines, metrics and CVT values.
+
+use std::collections::HashMap;
+
+use kurbo::{Point, Vec2};
+use read_fonts::{
+    tables::{cvar::CvtVariationData, gvar::Gvar as ReadGvar},
+    types::GlyphId,
+    FontRef, TableProvider,
+};
+use types::Tag;
+
+use super::{
+    is_default_region, region_key, region_tuples, tuple_region, InstancePlan, InstancerError,
+    MetricsTables, Tent,
+};
+use crate::{
+    from_obj::ToOwnedTable,
+    tables::{
+        cvar::{Cvar, CvtDeltas},
+        glyf::{Anchor, Bbox, GlyfLocaBuilder, Glyph},
+        gvar::{iup::iup_delta_interpolate, GlyphDeltas, GlyphVariations, Gvar},
+        head::Head,
+        hmtx::{Hmtx, LongMetric},
+        vmtx::Vmtx,
+    },
+    FontBuilder, OtRound,
+};
+
+/// The tolerance used when optimizing the remaining glyph deltas with IUP.
+const IUP_TOLERANCE: f64 = 0.5;
+
+/// The maximum nesting of composite glyphs whose bounds are recomputed.
+const MAX_COMPONENT_DEPTH: usize = 64;
+
+/// The dense deltas for a single region.
+type Variation = (Vec<Tent>, Vec<Vec2>);
+
+/// A glyph with its default coordinates and variations.
+struct GlyphVars {
+    glyph: Glyph,
+    /// The points of a simple glyph or the offsets of the components of a
+    /// composite, followed by the four phantom points.
+    coords: Vec<Point>,
+    /// The deltas for each of the coordinates, for each region.
+    variations: Vec<Variation>,
+}
+
+/// Apply the default deltas to the outlines and metrics of a 'glyf' font
+/// and rebase its remaining glyph variations.
+pub(super) fn instance_glyphs(
+    font: &FontRef,
+    plan: &InstancePlan,
+    tables: &mut MetricsTables,
+    builder: &mut FontBuilder,
+) -> Result<(), InstancerError> {
+    let (Ok(gvar), Ok(glyf), Ok(loca)) = (font.gvar(), font.glyf(), font.loca(None)) else {
+        return Ok(());
+    };
+    let num_glyphs = font.maxp()?.num_glyphs();
+    let hmtx = font.hmtx()?;
+    let vmtx = font.vmtx().ok();
+
+    let mut glyphs = Vec::with_capacity(num_glyphs as usize);
+    for gid in 0..num_glyphs {
+        let gid = GlyphId::from(gid);
+        let glyph: Glyph = match loca.get_glyf(gid, &glyf)? {
+            Some(glyph) => glyph.to_owned_table(),
+            None => Glyph::Empty,
+        };
+        let mut coords = glyph_coords(&glyph);
+        let bbox = glyph.bbox().unwrap_or_default();
+        let lsb = hmtx.side_bearing(gid).unwrap_or_default();
+        let advance = hmtx.advance(gid).unwrap_or_default();
+        let x_min = (bbox.x_min - lsb) as f64;
+        coords.push(Point::new(x_min, 0.0));
+        coords.push(Point::new(x_min + advance as f64, 0.0));
+        let (top, advance) = vmtx
+            .as_ref()
+            .map(|vmtx| {
+                let tsb = vmtx.side_bearing(gid).unwrap_or_default();
+                let advance = vmtx.advance(gid).unwrap_or_default();
+                ((bbox.y_max + tsb) as f64, advance as f64)
+            })
+            .unwrap_or_default();
+        coords.push(Point::new(0.0, top));
+        coords.push(Point::new(0.0, top - advance));
+        let variations = glyph_variations(&gvar, gid, &glyph, &coords)?;
+        glyphs.push(GlyphVars {
+            glyph,
+            coords,
+            variations,
+        });
+    }
+
+    let mut new_variations = Vec::with_capacity(glyphs.len());
+    for (gid, glyph) in glyphs.iter_mut().enumerate() {
+        let gid = GlyphId::new(gid as u32);
+        let deltas = instance_glyph(gid, glyph, plan)?;
+        new_variations.push(GlyphVariations::new(gid, deltas));
+    }
+    let bounds = compute_bounds(&mut glyphs);
+
+    let mut loca_builder = GlyfLocaBuilder::new();
+    loca_builder.trust_provided_bounds(true);
+    for glyph in &glyphs {
+        loca_builder
+            .add_glyph(&glyph.glyph)
+            .map_err(|inner| crate::BuilderError {
+                tag: Tag::new(b"glyf"),
+                inner,
+            })?;
+    }
+    let mut head: Head = font.head()?.to_owned_table();
+    let (glyf, loca) = loca_builder.build_with_head(&mut head);
+    builder
+        .add_table(&glyf)?
+        .add_table(&loca)?
+        .add_table(&head)?;
+
+    if let Some(hhea) = tables.hhea.as_mut() {
+        let metrics = glyphs
+            .iter()
+            .zip(&bounds)
+            .enumerate()
+            .map(|(gid, (glyph, bbox))| {
+                let [pp1, pp2, ..] = phantom_points(GlyphId::new(gid as u32), &glyph.coords)?;
+                let advance = (pp2.x - pp1.x).max(0.0) as u16;
+                let x_min = bbox.map(|bbox| bbox.x_min).unwrap_or_default();
+                Ok(LongMetric::new(advance, x_min - pp1.x as i16))
+            })
+            .collect::<Result<Vec<_>, InstancerError>>()?;
+        let (mut min_lsb, mut min_rsb, mut max_extent) = (i16::MAX, i16::MAX, i16::MIN);
+        for (metric, bbox) in metrics.iter().zip(&bounds) {
+            let Some(bbox) = bbox else {
+                continue;
+            };
+            let extent = metric.side_bearing + (bbox.x_max - bbox.x_min);
+            min_lsb = min_lsb.min(metric.side_bearing);
+            min_rsb = min_rsb.min(metric.advance as i16 - extent);
+            max_extent = max_extent.max(extent);
+        }
+        if max_extent != i16::MIN {
+            hhea.min_left_side_bearing = min_lsb.into();
+            hhea.min_right_side_bearing = min_rsb.into();
+            hhea.x_max_extent = max_extent.into();
+        }
+        hhea.advance_width_max = metrics
+            .iter()
+            .map(|metric| metric.advance)
+            .max()
+            .unwrap_or_default()
+            .into();
+        let hmtx = Hmtx::from_glyph_metrics(metrics);
+        hhea.number_of_long_metrics = hmtx.number_of_long_metrics();
+        builder.add_table(&hmtx)?;
+    }
+    if let (Some(vhea), Some(_)) = (tables.vhea.as_mut(), vmtx) {
+        let metrics = glyphs
+            .iter()
+            .zip(&bounds)
+            .enumerate()
+            .map(|(gid, (glyph, bbox))| {
+                let [.., pp3, pp4] = phantom_points(GlyphId::new(gid as u32), &glyph.coords)?;
+                let advance = (pp3.y - pp4.y).max(0.0) as u16;
+                let y_max = bbox.map(|bbox| bbox.y_max).unwrap_or_default();
+                Ok(LongMetric::new(advance, pp3.y as i16 - y_max))
+            })
+            .collect::<Result<Vec<_>, InstancerError>>()?;
+        let (mut min_tsb, mut min_bsb, mut max_extent) = (i16::MAX, i16::MAX, i16::MIN);
+        for (metric, bbox) in metrics.iter().zip(&bounds) {
+            let Some(bbox) = bbox else {
+                continue;
+            };
+            let extent = metric.side_bearing + (bbox.y_max - bbox.y_min);
+            min_tsb = min_tsb.min(metric.side_bearing);
+            min_bsb = min_bsb.min(metric.advance as i16 - extent);
+            max_extent = max_extent.max(extent);
+        }
+        if max_extent != i16::MIN {
+            vhea.min_top_side_bearing = min_tsb.into();
+            vhea.min_bottom_side_bearing = min_bsb.into();
+            vhea.y_max_extent = max_extent.into();
+        }
+        vhea.advance_height_max = metrics
+            .iter()
+            .map(|metric| metric.advance)
+            .max()
+            .unwrap_or_default()
+            .into();
+        let vmtx = Vmtx::from_glyph_metrics(metrics);
+        vhea.number_of_long_ver_metrics = vmtx.number_of_long_metrics();
+        builder.add_table(&vmtx)?;
+    }
+
+    if plan.new_axis_count > 0 {
+        builder.add_table(&Gvar::new(new_variations, plan.new_axis_count)?)?;
+    }
+    Ok(())
+}
+
+/// Apply the default deltas to the 'cvt ' table and rebase the remaining
+/// CVT variations.
+pub(super) fn instance_cvar(
+    font: &FontRef,
+    plan: &InstancePlan,
+    builder: &mut FontBuilder,
+) -> Result<(), InstancerError> {
+    let (Ok(cvar), Some(cvt)) = (font.cvar(), font.table_data(Tag::new(b"cvt "))) else {
+        return Ok(());
+    };
+    let mut values: Vec<f64> = cvt
+        .as_bytes()
+        .chunks_exact(2)
+        .map(|bytes| i16::from_be_bytes([bytes[0], bytes[1]]) as f64)
+        .collect();
+    let axis_count = plan.changes.len() as u16;
+    let variations: CvtVariationData = cvar.variation_data(axis_count)?;
+
+    let mut defaults = vec![0.0; values.len()];
+    let mut new_variations: Vec<(Vec<Tent>, Vec<f64>)> = Vec::new();
+    let mut region_indices = HashMap::new();
+    for tuple in variations.tuples() {
+        let region = tuple_region(
+            &tuple.peak(),
+            tuple.intermediate_start(),
+            tuple.intermediate_end(),
+        );
+        let mut deltas = vec![0.0; values.len()];
+        for delta in tuple.deltas() {
+            if let Some(value) = deltas.get_mut(delta.position as usize) {
+                *value = delta.value as f64;
+            }
+        }
+        for (scalar, part) in plan.instance_region(&region) {
+            let target = if is_default_region(&part) {
+                &mut defaults
+            } else {
+                let index = *region_indices.entry(region_key(&part)).or_insert_with(|| {
+                    new_variations.push((part.clone(), vec![0.0; values.len()]));
+                    new_variations.len() - 1
+                });
+                &mut new_variations[index].1
+            };
+            for (target, delta) in target.iter_mut().zip(&deltas) {
+                *target += delta * scalar;
+            }
+        }
+    }
+
+    for (value, delta) in values.iter_mut().zip(&defaults) {
+        *value += delta;
+    }
+    let cvt: Vec<u8> = values
+        .into_iter()
+        .flat_map(|value| OtRound::<i16>::ot_round(value).to_be_bytes())
+        .collect();
+    builder.add_raw(Tag::new(b"cvt "), cvt);
+
+    let variations: Vec<_> = new_variations
+        .into_iter()
+        .filter_map(|(region, deltas)| {
+            let deltas: Vec<_> = deltas
+                .into_iter()
+                .map(|delta| Some(delta.ot_round()).filter(|delta: &i16| *delta != 0))
+                .collect();
+            if deltas.iter().all(Option::is_none) {
+                return None;
+            }
+            let (peak, intermediate) = region_tuples(&region);
+            Some(CvtDeltas::new(peak, deltas, intermediate))
+        })
+        .collect();
+    if plan.new_axis_count > 0 && !variations.is_empty() {
+        builder.add_table(&Cvar::new(variations)?)?;
+    }
+    Ok(())
+}
+
+/// The points of a simple glyph, or the component offsets of a composite.
+fn glyph_coords(glyph: &Glyph) -> Vec<Point> {
+    match glyph {
+        Glyph::Empty => Vec::new(),
+        Glyph::Simple(glyph) => glyph
+            .contours
+            .iter()
+            .flat_map(|contour| contour.iter())
+            .map(|point| Point::new(point.x as f64, point.y as f64))
+            .collect(),
+        Glyph::Composite(glyph) => glyph
+            .components()
+            .iter()
+            .map(|component| match component.anchor {
+                Anchor::Offset { x, y } => Point::new(x as f64, y as f64),
+                Anchor::Point { .. } => Point::ZERO,
+            })
+            .collect(),
+    }
+}
+
+/// The end point of each contour of a simple glyph.
+///
+/// Empty contours have no end point, and are skipped.
+fn contour_ends(glyph: &Glyph) -> Vec<usize> {
+    let Glyph::Simple(glyph) = glyph else {
+        return Vec::new();
+    };
+    glyph
+        .contours
+        .iter()
+        .filter(|contour| !contour.is_empty())
+        .scan(0, |end, contour| {
+            *end += contour.len();
+            Some(*end - 1)
+        })
+        .collect()
+}
+
+/// The four phantom points at the end of the coordinates of a glyph.
+fn phantom_points(gid: GlyphId, coords: &[Point]) -> Result<[Point; 4], InstancerError> {
+    coords
+        .len()
+        .checked_sub(4)
+        .and_then(|start| coords[start..].try_into().ok())
+        .ok_or(InstancerError::MalformedGlyph(gid))
+}
+
+/// Read the variations of a glyph, inferring any deltas that are not encoded.
+fn glyph_variations(
+    gvar: &ReadGvar,
+    gid: GlyphId,
+    glyph: &Glyph,
+    coords: &[Point],
+) -> Result<Vec<Variation>, InstancerError> {
+    if gvar.data_for_gid(gid)?.is_empty() {
+        return Ok(Vec::new());
+    }
+    let ends = contour_ends(glyph);
+    let mut result = Vec::new();
+    for tuple in gvar.glyph_variation_data(gid)?.tuples() {
+        let region = tuple_region(
+            &tuple.peak(),
+            tuple.intermediate_start(),
+            tuple.intermediate_end(),
+        );
+        let mut deltas = vec![None; coords.len()];
+        for delta in tuple.deltas() {
+            if let Some(target) = deltas.get_mut(delta.position as usize) {
+                *target = Some(Vec2::new(delta.x_delta as f64, delta.y_delta as f64));
+            }
+        }
+        let deltas = match glyph {
+            Glyph::Simple(_) if !tuple.has_deltas_for_all_points() => {
+                iup_delta_interpolate(&deltas, coords, &ends)
+                    .map_err(|_| InstancerError::MalformedGlyph(gid))?
+            }
+            _ => deltas.into_iter().map(Option::unwrap_or_default).collect(),
+        };
+        result.push((region, deltas));
+    }
+    Ok(result)
+}
+
+/// Apply the default deltas to a glyph and return its remaining variations.
+fn instance_glyph(
+    gid: GlyphId,
+    glyph: &mut GlyphVars,
+    plan: &InstancePlan,
+) -> Result<Vec<GlyphDeltas>, InstancerError> {
+    let mut defaults = vec![Vec2::ZERO; glyph.coords.len()];
+    let mut new_variations: Vec<Variation> = Vec::new();
+    let mut region_indices = HashMap::new();
+    for (region, deltas) in &glyph.variations {
+        for (scalar, part) in plan.instance_region(region) {
+            let target = if is_default_region(&part) {
+                &mut defaults
+            } else {
+                let index = *region_indices.entry(region_key(&part)).or_insert_with(|| {
+                    new_variations.push((part.clone(), vec![Vec2::ZERO; deltas.len()]));
+                    new_variations.len() - 1
+                });
+                &mut new_variations[index].1
+            };
+            for (target, delta) in target.iter_mut().zip(deltas) {
+                *target += *delta * scalar;
+            }
+        }
+    }
+
+    for (coord, delta) in glyph.coords.iter_mut().zip(&defaults) {
+        *coord = Point::new(
+            (coord.x + delta.x).ot_round(),
+            (coord.y + delta.y).ot_round(),
+        );
+    }
+    set_glyph_coords(&mut glyph.glyph, &glyph.coords);
+
+    let ends = contour_ends(&glyph.glyph);
+    new_variations
+        .into_iter()
+        .map(|(region, deltas)| {
+            let (peak, intermediate) = region_tuples(&region);
+            match glyph.glyph {
+                Glyph::Simple(_) => GlyphDeltas::from_simple_glyph_deltas(
+                    peak,
+                    deltas,
+                    intermediate,
+                    &glyph.coords,
+                    &ends,
+                    IUP_TOLERANCE,
+                )
+                .map_err(|_| InstancerError::MalformedGlyph(gid)),
+                _ => Ok(GlyphDeltas::from_required_deltas(
+                    peak,
+                    deltas,
+                    intermediate,
+                )),
+            }
+        })
+        .filter(|deltas| !deltas.as_ref().is_ok_and(GlyphDeltas::is_noop))
+        .collect()
+}
+
+/// Update the points or component offsets of a glyph.
+fn set_glyph_coords(glyph: &mut Glyph, coords: &[Point]) {
+    match glyph {
+        Glyph::Empty => (),
+        Glyph::Simple(glyph) => {
+            let mut coords = coords.iter();
+            for contour in glyph.contours.iter_mut() {
+                let mut points: Vec<_> = std::mem::take(contour).into();
+                for (point, coord) in points.iter_mut().zip(coords.by_ref()) {
+                    point.x = coord.x as i16;
+                    point.y = coord.y as i16;
+                }
+                *contour = points.into();
+            }
+        }
+        Glyph::Composite(glyph) => {
+            for (component, coord) in glyph.components_mut().iter_mut().zip(coords) {
+                if let Anchor::Offset { x, y } = &mut component.anchor {
+                    *x = coord.x as i16;
+                    *y = coord.y as i16;
+                }
+            }
+        }
+    }
+}
+
+/// Recompute the bounding boxes of all glyphs, returning the bounds of each.
+fn compute_bounds(glyphs: &mut [GlyphVars]) -> Vec<Option<Bbox>> {
+    let mut bounds = Vec::with_capacity(glyphs.len());
+    for glyph in glyphs.iter_mut() {
+        if let Glyph::Simple(simple) = &mut glyph.glyph {
+            simple.recompute_bounding_box();
+        }
+        bounds.push(glyph.glyph.bbox());
+    }
+    // composites may reference composites with a higher glyph id, so repeat
+    // until the bounds stop changing, which happens after at most the maximum
+    // component depth.
+    for _ in 0..MAX_COMPONENT_DEPTH {
+        let mut changed = false;
+        for (gid, glyph) in glyphs.iter_mut().enumerate() {
+            if let Glyph::Composite(composite) = &mut glyph.glyph {
+                composite.recompute_bounding_box(&bounds);
+                if bounds[gid] != Some(composite.bbox) {
+                    bounds[gid] = Some(composite.bbox);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    bounds
+}
+
+#[cfg(test)]
+mod tests {
+    use read_fonts::tables::glyf::CurvePoint;
+
+    use super::*;
+    use crate::{instancer::AxisChange, tables::glyf::SimpleGlyph};
+
+    fn simple_glyph(contours: &[&[(i16, i16)]]) -> Glyph {
+        let mut glyph = SimpleGlyph::default();
+        glyph.contours = contours
+            .iter()
+            .map(|points| {
+                points
+                    .iter()
+                    .map(|(x, y)| CurvePoint::on_curve(*x, *y))
+                    .collect::<Vec<_>>()
+                    .into()
+            })
+            .collect();
+        Glyph::Simple(glyph)
+    }
+
+    fn glyph_vars(glyph: Glyph, num_coords: usize) -> GlyphVars {
+        let coords = (0..num_coords)
+            .map(|i| Point::new(i as f64, i as f64))
+            .collect::<Vec<_>>();
+        let deltas = vec![Vec2::new(1.0, 0.0); num_coords];
+        GlyphVars {
+            glyph,
+            coords,
+            variations: vec![(vec![(0.0, 1.0, 1.0)], deltas)],
+        }
+    }
+
+    fn keep_plan() -> InstancePlan {
+        InstancePlan {
+            changes: vec![AxisChange::Keep],
+            new_axis_count: 1,
+        }
+    }
+
+    #[test]
+    fn contour_ends_skip_empty_contours() {
+        let glyph = simple_glyph(&[&[], &[(0, 0), (10, 0), (10, 10)], &[], &[(5, 5)]]);
+        assert_eq!(contour_ends(&glyph), vec![2, 3]);
+    }
+
+    #[test]
+    fn instance_empty_contour_glyph() {
+        let glyph = simple_glyph(&[&[(0, 0), (10, 0), (10, 10)], &[]]);
+        let mut glyph = glyph_vars(glyph, 3 + 4);
+        let deltas = instance_glyph(GlyphId::new(1), &mut glyph, &keep_plan()).unwrap();
+        assert_eq!(deltas.len(), 1);
+    }
+
+    #[test]
+    fn malformed_glyph_is_an_error() {
+        let gid = GlyphId::new(5);
+        // three points in the contours, but coordinates for only two
+        let glyph = simple_glyph(&[&[(0, 0), (10, 0), (10, 10)]]);
+        let mut glyph = glyph_vars(glyph, 2 + 4);
+        assert!(matches!(
+            instance_glyph(gid, &mut glyph, &keep_plan()),
+            Err(InstancerError::MalformedGlyph(err_gid)) if err_gid == gid
+        ));
+        assert!(matches!(
+            phantom_points(gid, &glyph.coords[..3]),
+            Err(InstancerError::MalformedGlyph(_))
+        ));
+    }
+}
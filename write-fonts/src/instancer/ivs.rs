@@ -0,0 +1,296 @@
+//! Instancing item variation stores.
+
+use std::collections::HashMap;
+
+use read_fonts::tables::{mvar::Mvar, variations::ItemVariationStore as ReadItemVariationStore};
+
+use super::{
+    f2dot14, is_default_region, region_key, InstancePlan, InstancerError, MetricsTables, Tent,
+};
+use crate::{
+    tables::{
+        os2::Os2,
+        variations::{
+            ItemVariationData, ItemVariationStore, RegionAxisCoordinates, VariationRegion,
+            VariationRegionList,
+        },
+    },
+    OtRound,
+};
+use types::FWord;
+
+/// An item variation store rebased onto the remaining design space.
+///
+/// The outer and inner indices of every delta set are unchanged, so that
+/// anything referencing the original store can reference the new one.
+pub(super) struct InstancedStore {
+    /// The new store, or `None` if no variations remain.
+    pub(super) store: Option<ItemVariationStore>,
+    /// The change at the default location, indexed by outer then inner index.
+    defaults: Vec<Vec<f64>>,
+}
+
+impl InstancedStore {
+    /// The change in the default value of the given delta set.
+    pub(super) fn default_delta(&self, outer: u16, inner: u16) -> f64 {
+        self.defaults
+            .get(outer as usize)
+            .and_then(|deltas| deltas.get(inner as usize))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+pub(super) fn instance_store(
+    store: &ReadItemVariationStore,
+    plan: &InstancePlan,
+) -> Result<InstancedStore, InstancerError> {
+    let old_regions = store
+        .variation_region_list()?
+        .variation_regions()
+        .iter()
+        .map(|region| {
+            region.map(|region| {
+                let region: Vec<Tent> = region
+                    .region_axes()
+                    .iter()
+                    .map(|axis| {
+                        (
+                            axis.start_coord().to_f32() as f64,
+                            axis.peak_coord().to_f32() as f64,
+                            axis.end_coord().to_f32() as f64,
+                        )
+                    })
+                    .collect();
+                plan.instance_region(&region)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut new_regions: Vec<Vec<Tent>> = Vec::new();
+    let mut region_indices = HashMap::new();
+    let mut defaults = Vec::new();
+    let mut subtables = Vec::new();
+    for data in store.item_variation_data().iter() {
+        let Some(data) = data.transpose()? else {
+            defaults.push(Vec::new());
+            subtables.push(None);
+            continue;
+        };
+        let old_indices: Vec<_> = data.region_indexes().iter().map(|i| i.get()).collect();
+        let mut rows = Vec::with_capacity(data.item_count() as usize);
+        let mut row_defaults = Vec::with_capacity(data.item_count() as usize);
+        for inner in 0..data.item_count() {
+            let mut row: HashMap<u16, f64> = HashMap::new();
+            let mut default = 0.0;
+            for (delta, region) in data.delta_set(inner).zip(&old_indices) {
+                let Some(parts) = old_regions.get(*region as usize) else {
+                    continue;
+                };
+                for (scalar, part) in parts {
+                    let delta = delta as f64 * scalar;
+                    if is_default_region(part) {
+                        default += delta;
+                        continue;
+                    }
+                    let index = *region_indices.entry(region_key(part)).or_insert_with(|| {
+                        new_regions.push(part.clone());
+                        new_regions.len() as u16 - 1
+                    });
+                    *row.entry(index).or_default() += delta;
+                }
+            }
+            rows.push(row);
+            row_defaults.push(default);
+        }
+        defaults.push(row_defaults);
+        subtables.push(Some(encode_subtable(rows)));
+    }
+
+    if new_regions.is_empty() {
+        return Ok(InstancedStore {
+            store: None,
+            defaults,
+        });
+    }
+    let regions = new_regions
+        .iter()
+        .map(|region| {
+            VariationRegion::new(
+                region
+                    .iter()
+                    .map(|(start, peak, end)| {
+                        RegionAxisCoordinates::new(
+                            f2dot14(*start as f32),
+                            f2dot14(*peak as f32),
+                            f2dot14(*end as f32),
+                        )
+                    })
+                    .collect(),
+            )
+        })
+        .collect();
+    let store = ItemVariationStore::new(
+        VariationRegionList::new(plan.new_axis_count, regions),
+        subtables,
+    );
+    Ok(InstancedStore {
+        store: Some(store),
+        defaults,
+    })
+}
+
+/// Apply the change at the default location to the values referenced by 'MVAR'.
+pub(super) fn apply_mvar_defaults(mvar: &Mvar, store: &InstancedStore, tables: &mut MetricsTables) {
+    fn apply<T: Copy + Into<f64>>(value: &mut T, delta: f64)
+    where
+        f64: OtRound<T>,
+    {
+        *value = ((*value).into() + delta).ot_round();
+    }
+    fn apply_fword(value: &mut FWord, delta: f64) {
+        let mut raw = value.to_i16();
+        apply(&mut raw, delta);
+        *value = raw.into();
+    }
+
+    let MetricsTables {
+        os2,
+        post,
+        hhea,
+        vhea,
+    } = tables;
+    for record in mvar.value_records() {
+        let delta = store.default_delta(
+            record.delta_set_outer_index(),
+            record.delta_set_inner_index(),
+        );
+        if delta == 0.0 {
+            continue;
+        }
+        // the mapping of tags to fields, from `MVAR_ENTRIES` in fonttools
+        match (
+            &record.value_tag().to_be_bytes(),
+            os2.as_mut(),
+            post.as_mut(),
+            hhea.as_mut(),
+            vhea.as_mut(),
+        ) {
+            (b"hasc", Some(os2), ..) => apply(&mut os2.s_typo_ascender, delta),
+            (b"hdsc", Some(os2), ..) => apply(&mut os2.s_typo_descender, delta),
+            (b"hlgp", Some(os2), ..) => apply(&mut os2.s_typo_line_gap, delta),
+            (b"hcla", Some(os2), ..) => apply(&mut os2.us_win_ascent, delta),
+            (b"hcld", Some(os2), ..) => apply(&mut os2.us_win_descent, delta),
+            (
+                b"xhgt",
+                Some(Os2 {
+                    sx_height: Some(value),
+                    ..
+                }),
+                ..,
+            ) => apply(value, delta),
+            (
+                b"cpht",
+                Some(Os2 {
+                    s_cap_height: Some(value),
+                    ..
+                }),
+                ..,
+            ) => apply(value, delta),
+            (b"sbxs", Some(os2), ..) => apply(&mut os2.y_subscript_x_size, delta),
+            (b"sbys", Some(os2), ..) => apply(&mut os2.y_subscript_y_size, delta),
+            (b"sbxo", Some(os2), ..) => apply(&mut os2.y_subscript_x_offset, delta),
+            (b"sbyo", Some(os2), ..) => apply(&mut os2.y_subscript_y_offset, delta),
+            (b"spxs", Some(os2), ..) => apply(&mut os2.y_superscript_x_size, delta),
+            (b"spys", Some(os2), ..) => apply(&mut os2.y_superscript_y_size, delta),
+            (b"spxo", Some(os2), ..) => apply(&mut os2.y_superscript_x_offset, delta),
+            (b"spyo", Some(os2), ..) => apply(&mut os2.y_superscript_y_offset, delta),
+            (b"strs", Some(os2), ..) => apply(&mut os2.y_strikeout_size, delta),
+            (b"stro", Some(os2), ..) => apply(&mut os2.y_strikeout_position, delta),
+            (b"unds", _, Some(post), ..) => apply_fword(&mut post.underline_thickness, delta),
+            (b"undo", _, Some(post), ..) => apply_fword(&mut post.underline_position, delta),
+            (b"hcrs", _, _, Some(hhea), _) => apply(&mut hhea.caret_slope_rise, delta),
+            (b"hcrn", _, _, Some(hhea), _) => apply(&mut hhea.caret_slope_run, delta),
+            (b"hcof", _, _, Some(hhea), _) => apply(&mut hhea.caret_offset, delta),
+            (b"vasc", .., Some(vhea)) => apply_fword(&mut vhea.ascender, delta),
+            (b"vdsc", .., Some(vhea)) => apply_fword(&mut vhea.descender, delta),
+            (b"vlgp", .., Some(vhea)) => apply_fword(&mut vhea.line_gap, delta),
+            (b"vcrs", .., Some(vhea)) => apply(&mut vhea.caret_slope_rise, delta),
+            (b"vcrn", .., Some(vhea)) => apply(&mut vhea.caret_slope_run, delta),
+            (b"vcof", .., Some(vhea)) => apply(&mut vhea.caret_offset, delta),
+            _ => (),
+        }
+    }
+}
+
+/// Encode rounded delta rows, keyed by region index, as a subtable.
+fn encode_subtable(rows: Vec<HashMap<u16, f64>>) -> ItemVariationData {
+    let rows: Vec<HashMap<u16, i32>> = rows
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|(region, delta)| (region, delta.round() as i32))
+                .filter(|(_, delta)| *delta != 0)
+                .collect()
+        })
+        .collect();
+    let mut columns: Vec<u16> = rows.iter().flat_map(|row| row.keys().copied()).collect();
+    columns.sort_unstable();
+    columns.dedup();
+    let column_max = |region: &u16| {
+        rows.iter()
+            .filter_map(|row| row.get(region))
+            .map(|delta| delta.unsigned_abs())
+            .max()
+            .unwrap_or_default()
+    };
+    let long_words = columns
+        .iter()
+        .any(|region| column_max(region) > i16::MAX as u32);
+    let is_word = |region: &u16| match long_words {
+        true => column_max(region) > i16::MAX as u32,
+        false => column_max(region) > i8::MAX as u32,
+    };
+    // word columns must come first
+    columns.sort_by_key(|region| !is_word(region));
+    let word_count = columns.iter().filter(|region| is_word(region)).count() as u16;
+
+    let mut delta_sets = Vec::new();
+    for row in &rows {
+        for (i, region) in columns.iter().enumerate() {
+            let delta = row.get(region).copied().unwrap_or_default();
+            match (long_words, i < word_count as usize) {
+                (true, true) => delta_sets.extend(delta.to_be_bytes()),
+                (true, false) | (false, true) => delta_sets.extend((delta as i16).to_be_bytes()),
+                (false, false) => delta_sets.extend((delta as i8).to_be_bytes()),
+            }
+        }
+    }
+    let word_delta_count = word_count | if long_words { 0x8000 } else { 0 };
+    ItemVariationData::new(rows.len() as u16, word_delta_count, columns, delta_sets)
+}
+
+#[cfg(test)]
+mod tests {
+    use read_fonts::{FontData, FontRead};
+
+    use super::*;
+    use crate::dump_table;
+
+    #[test]
+    fn encode_mixed_widths() {
+        let rows = vec![
+            HashMap::from([(0, 1.0), (1, 300.0), (2, -2.4)]),
+            HashMap::from([(1, -5.0), (3, 0.2)]),
+        ];
+        let data = encode_subtable(rows);
+        assert_eq!(data.region_indexes, [1, 0, 2]);
+        assert_eq!(data.word_delta_count, 1);
+
+        let bytes = dump_table(&data).unwrap();
+        let data =
+            read_fonts::tables::variations::ItemVariationData::read(FontData::new(&bytes)).unwrap();
+        assert_eq!(data.delta_set(0).collect::<Vec<_>>(), [300, 1, -2]);
+        assert_eq!(data.delta_set(1).collect::<Vec<_>>(), [-5, 0, 0]);
+    }
+}
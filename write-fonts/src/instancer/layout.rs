@@ -0,0 +1,438 @@
+//! Instancing the variations in 'GDEF', 'GPOS' and 'GSUB'.
+
+use read_fonts::{FontRef, TableProvider, TopLevelTable};
+
+use super::{f2dot14, ivs, ivs::InstancedStore, AxisChange, InstancePlan, InstancerError};
+use crate::{
+    from_obj::ToOwnedTable,
+    offsets::NullableOffsetMarker,
+    tables::{
+        gdef::{CaretValue, Gdef},
+        gpos::{
+            AnchorTable, CursivePosFormat1, ExtensionSubtable, Gpos, MarkArray, MarkBasePosFormat1,
+            MarkLigPosFormat1, MarkMarkPosFormat1, PairPos, PositionLookup, SinglePos, ValueFormat,
+            ValueRecord,
+        },
+        gsub::Gsub,
+        layout::{Condition, DeviceOrVariationIndex, FeatureList, FeatureVariations, Lookup},
+    },
+    FontBuilder, OtRound,
+};
+
+/// The format flags for each of the device offsets in a value record.
+const DEVICE_FLAGS: ValueFormat = ValueFormat::X_PLACEMENT_DEVICE
+    .union(ValueFormat::Y_PLACEMENT_DEVICE)
+    .union(ValueFormat::X_ADVANCE_DEVICE)
+    .union(ValueFormat::Y_ADVANCE_DEVICE);
+
+/// Instance the 'GDEF' item variation store, applying the default deltas to
+/// the values that reference it, and the feature variations in 'GSUB' and
+/// 'GPOS'.
+///
+/// Tables without variations are left to be copied unchanged.
+pub(super) fn instance_layout(
+    font: &FontRef,
+    plan: &InstancePlan,
+    builder: &mut FontBuilder,
+) -> Result<(), InstancerError> {
+    let mut store = None;
+    if let Ok(gdef) = font.gdef() {
+        if let Some(var_store) = gdef.item_var_store().transpose()? {
+            let instanced = ivs::instance_store(&var_store, plan)?;
+            let mut gdef: Gdef = gdef.to_owned_table();
+            let deltas = DefaultDeltas {
+                store: &instanced,
+                keep_devices: instanced.store.is_some(),
+            };
+            if let Some(lig_caret_list) = gdef.lig_caret_list.as_mut() {
+                for caret in lig_caret_list
+                    .lig_glyphs
+                    .iter_mut()
+                    .flat_map(|lig_glyph| lig_glyph.caret_values.iter_mut())
+                {
+                    deltas.apply_caret(caret);
+                }
+            }
+            gdef.item_var_store = instanced.store.clone().into();
+            builder.add_table(&gdef)?;
+            store = Some(instanced);
+        }
+    }
+
+    if let Ok(gpos) = font.gpos() {
+        if store.is_some()
+            || gpos
+                .feature_variations_offset()
+                .is_some_and(|o| !o.is_null())
+        {
+            let mut gpos: Gpos = gpos.to_owned_table();
+            if let Some(store) = store.as_ref() {
+                let deltas = DefaultDeltas {
+                    store,
+                    keep_devices: store.store.is_some(),
+                };
+                for lookup in gpos.lookup_list.lookups.iter_mut() {
+                    deltas.apply_lookup(lookup);
+                }
+            }
+            instance_feature_variations(
+                &mut gpos.feature_list,
+                &mut gpos.feature_variations,
+                plan,
+                Gpos::TAG,
+            )?;
+            builder.add_table(&gpos)?;
+        }
+    }
+    if let Ok(gsub) = font.gsub() {
+        if gsub
+            .feature_variations_offset()
+            .is_some_and(|o| !o.is_null())
+        {
+            let mut gsub: Gsub = gsub.to_owned_table();
+            instance_feature_variations(
+                &mut gsub.feature_list,
+                &mut gsub.feature_variations,
+                plan,
+                Gsub::TAG,
+            )?;
+            builder.add_table(&gsub)?;
+        }
+    }
+    Ok(())
+}
+
+/// Rebase the conditions of the feature variations onto the remaining axes.
+///
+/// Records that can no longer match are removed. The first record that
+/// matches everywhere in the remaining design space ends the list, and if no
+/// records precede it, its substitutions are applied to the feature list.
+fn instance_feature_variations(
+    feature_list: &mut FeatureList,
+    feature_variations: &mut NullableOffsetMarker<FeatureVariations, 4>,
+    plan: &InstancePlan,
+    tag: types::Tag,
+) -> Result<(), InstancerError> {
+    let Some(variations) = feature_variations.as_mut() else {
+        return Ok(());
+    };
+    let records = std::mem::take(&mut variations.feature_variation_records);
+    'records: for mut record in records {
+        let mut matches_everywhere = true;
+        if let Some(condition_set) = record.condition_set.as_mut() {
+            let mut conditions = Vec::new();
+            for mut condition in std::mem::take(&mut condition_set.conditions) {
+                let Condition::Format1AxisRange(range) = &mut *condition else {
+                    return Err(InstancerError::UnsupportedTable(tag));
+                };
+                let axis_index = range.axis_index as usize;
+                let (min, max) = (
+                    range.filter_range_min_value.to_f32() as f64,
+                    range.filter_range_max_value.to_f32() as f64,
+                );
+                match plan.changes.get(axis_index) {
+                    None | Some(AxisChange::Keep) => (),
+                    Some(AxisChange::Pin(coord)) => {
+                        if *coord < min || *coord > max {
+                            continue 'records;
+                        }
+                        continue;
+                    }
+                    Some(AxisChange::Limit {
+                        min: limit_min,
+                        max: limit_max,
+                    }) => {
+                        if max < *limit_min || min > *limit_max {
+                            continue 'records;
+                        }
+                        if min <= *limit_min && max >= *limit_max {
+                            continue;
+                        }
+                        let rescale = |value: f64| match value {
+                            v if v < 0.0 => v / -limit_min,
+                            v if v > 0.0 => v / limit_max,
+                            _ => 0.0,
+                        };
+                        range.filter_range_min_value = f2dot14(rescale(min.max(*limit_min)) as f32);
+                        range.filter_range_max_value = f2dot14(rescale(max.min(*limit_max)) as f32);
+                    }
+                }
+                range.axis_index = plan.changes[..axis_index.min(plan.changes.len())]
+                    .iter()
+                    .filter(|change| !matches!(change, AxisChange::Pin(_)))
+                    .count() as u16;
+                conditions.push(condition);
+            }
+            matches_everywhere = conditions.is_empty();
+            condition_set.conditions = conditions;
+        }
+        if !matches_everywhere {
+            variations.feature_variation_records.push(record);
+            continue;
+        }
+        // this record matches everywhere, so later records are unreachable
+        if variations.feature_variation_records.is_empty() {
+            if let Some(substitution) = record.feature_table_substitution.as_mut() {
+                for substitution in substitution.substitutions.iter_mut() {
+                    if let Some(feature_record) = feature_list
+                        .feature_records
+                        .get_mut(substitution.feature_index as usize)
+                    {
+                        *feature_record.feature =
+                            std::mem::take(&mut *substitution.alternate_feature);
+                    }
+                }
+            }
+        } else {
+            variations.feature_variation_records.push(record);
+        }
+        break;
+    }
+    if variations.feature_variation_records.is_empty() {
+        feature_variations.clear();
+    }
+    Ok(())
+}
+
+/// Applies the default deltas of the 'GDEF' item variation store.
+struct DefaultDeltas<'a> {
+    store: &'a InstancedStore,
+    /// If `false`, no variations remain and variation index devices are
+    /// removed.
+    keep_devices: bool,
+}
+
+impl DefaultDeltas<'_> {
+    /// Add the default delta of a variation index device to a value.
+    ///
+    /// Returns `true` if the device is a variation index.
+    fn apply(&self, value: &mut i16, device: Option<&DeviceOrVariationIndex>) -> bool {
+        let Some(DeviceOrVariationIndex::VariationIndex(index)) = device else {
+            return false;
+        };
+        let delta = self
+            .store
+            .default_delta(index.delta_set_outer_index, index.delta_set_inner_index);
+        *value = (*value as f64 + delta).ot_round();
+        true
+    }
+
+    fn apply_device(
+        &self,
+        value: &mut i16,
+        device: &mut NullableOffsetMarker<DeviceOrVariationIndex>,
+    ) {
+        if self.apply(value, device.as_ref()) && !self.keep_devices {
+            device.clear();
+        }
+    }
+
+    fn apply_caret(&self, caret: &mut CaretValue) {
+        let CaretValue::Format3(format3) = caret else {
+            return;
+        };
+        if self.apply(&mut format3.coordinate, Some(&*format3.device)) && !self.keep_devices {
+            *caret = CaretValue::format_1(format3.coordinate);
+        }
+    }
+
+    fn apply_anchor(&self, anchor: &mut AnchorTable) {
+        let AnchorTable::Format3(format3) = anchor else {
+            return;
+        };
+        self.apply_device(&mut format3.x_coordinate, &mut format3.x_device);
+        self.apply_device(&mut format3.y_coordinate, &mut format3.y_device);
+        if format3.x_device.is_none() && format3.y_device.is_none() {
+            *anchor = AnchorTable::format_1(format3.x_coordinate, format3.y_coordinate);
+        }
+    }
+
+    fn apply_anchors<'b>(&self, anchors: impl Iterator<Item = &'b mut AnchorTable>) {
+        anchors.for_each(|anchor| self.apply_anchor(anchor));
+    }
+
+    fn apply_mark_array(&self, marks: &mut MarkArray) {
+        self.apply_anchors(
+            marks
+                .mark_records
+                .iter_mut()
+                .map(|record| &mut *record.mark_anchor),
+        );
+    }
+
+    /// Apply the deltas to the value records of a subtable, which must all
+    /// share a format.
+    fn apply_value_records<'b>(&self, records: impl Iterator<Item = &'b mut ValueRecord>) {
+        let mut records: Vec<_> = records.collect();
+        let mut format = ValueFormat::empty();
+        for record in records.iter_mut() {
+            let ValueRecord {
+                x_placement,
+                y_placement,
+                x_advance,
+                y_advance,
+                x_placement_device,
+                y_placement_device,
+                x_advance_device,
+                y_advance_device,
+                ..
+            } = record;
+            let fields = [
+                (x_placement, x_placement_device, ValueFormat::X_PLACEMENT),
+                (y_placement, y_placement_device, ValueFormat::Y_PLACEMENT),
+                (x_advance, x_advance_device, ValueFormat::X_ADVANCE),
+                (y_advance, y_advance_device, ValueFormat::Y_ADVANCE),
+            ];
+            for (value, device, flag) in fields {
+                let mut new_value = value.unwrap_or_default();
+                self.apply_device(&mut new_value, device);
+                if value.is_some() || new_value != 0 {
+                    *value = Some(new_value);
+                    format |= flag;
+                }
+            }
+            format |= record.format() - DEVICE_FLAGS;
+            if self.keep_devices {
+                format |= record.format() & DEVICE_FLAGS;
+            } else {
+                for (device, flag) in [
+                    (&record.x_placement_device, ValueFormat::X_PLACEMENT_DEVICE),
+                    (&record.y_placement_device, ValueFormat::Y_PLACEMENT_DEVICE),
+                    (&record.x_advance_device, ValueFormat::X_ADVANCE_DEVICE),
+                    (&record.y_advance_device, ValueFormat::Y_ADVANCE_DEVICE),
+                ] {
+                    if device.is_some() {
+                        format |= flag;
+                    }
+                }
+            }
+        }
+        for record in records {
+            record.set_explicit_value_format(format);
+        }
+    }
+
+    fn apply_lookup(&self, lookup: &mut PositionLookup) {
+        match lookup {
+            PositionLookup::Single(lookup) => self.apply_subtables(lookup, Self::apply_single),
+            PositionLookup::Pair(lookup) => self.apply_subtables(lookup, Self::apply_pair),
+            PositionLookup::Cursive(lookup) => self.apply_subtables(lookup, Self::apply_cursive),
+            PositionLookup::MarkToBase(lookup) => {
+                self.apply_subtables(lookup, Self::apply_mark_base)
+            }
+            PositionLookup::MarkToLig(lookup) => self.apply_subtables(lookup, Self::apply_mark_lig),
+            PositionLookup::MarkToMark(lookup) => {
+                self.apply_subtables(lookup, Self::apply_mark_mark)
+            }
+            PositionLookup::Extension(lookup) => {
+                self.apply_subtables(lookup, Self::apply_extension)
+            }
+            PositionLookup::Contextual(_) | PositionLookup::ChainContextual(_) => (),
+        }
+    }
+
+    fn apply_subtables<T>(&self, lookup: &mut Lookup<T>, apply: fn(&Self, &mut T)) {
+        for subtable in lookup.subtables.iter_mut() {
+            apply(self, subtable);
+        }
+    }
+
+    fn apply_extension(&self, subtable: &mut ExtensionSubtable) {
+        match subtable {
+            ExtensionSubtable::Single(ext) => self.apply_single(&mut ext.extension),
+            ExtensionSubtable::Pair(ext) => self.apply_pair(&mut ext.extension),
+            ExtensionSubtable::Cursive(ext) => self.apply_cursive(&mut ext.extension),
+            ExtensionSubtable::MarkToBase(ext) => self.apply_mark_base(&mut ext.extension),
+            ExtensionSubtable::MarkToLig(ext) => self.apply_mark_lig(&mut ext.extension),
+            ExtensionSubtable::MarkToMark(ext) => self.apply_mark_mark(&mut ext.extension),
+            ExtensionSubtable::Contextual(_) | ExtensionSubtable::ChainContextual(_) => (),
+        }
+    }
+
+    fn apply_single(&self, subtable: &mut SinglePos) {
+        match subtable {
+            SinglePos::Format1(table) => {
+                self.apply_value_records(std::iter::once(&mut table.value_record))
+            }
+            SinglePos::Format2(table) => self.apply_value_records(table.value_records.iter_mut()),
+        }
+    }
+
+    fn apply_pair(&self, subtable: &mut PairPos) {
+        match subtable {
+            PairPos::Format1(table) => {
+                for second in [false, true] {
+                    self.apply_value_records(
+                        table
+                            .pair_sets
+                            .iter_mut()
+                            .flat_map(|set| set.pair_value_records.iter_mut())
+                            .map(|record| match second {
+                                false => &mut record.value_record1,
+                                true => &mut record.value_record2,
+                            }),
+                    );
+                }
+            }
+            PairPos::Format2(table) => {
+                for second in [false, true] {
+                    self.apply_value_records(
+                        table
+                            .class1_records
+                            .iter_mut()
+                            .flat_map(|record| record.class2_records.iter_mut())
+                            .map(|record| match second {
+                                false => &mut record.value_record1,
+                                true => &mut record.value_record2,
+                            }),
+                    );
+                }
+            }
+        }
+    }
+
+    fn apply_cursive(&self, subtable: &mut CursivePosFormat1) {
+        self.apply_anchors(subtable.entry_exit_record.iter_mut().flat_map(|record| {
+            [record.entry_anchor.as_mut(), record.exit_anchor.as_mut()]
+                .into_iter()
+                .flatten()
+        }));
+    }
+
+    fn apply_mark_base(&self, subtable: &mut MarkBasePosFormat1) {
+        self.apply_mark_array(&mut subtable.mark_array);
+        self.apply_anchors(
+            subtable
+                .base_array
+                .base_records
+                .iter_mut()
+                .flat_map(|record| record.base_anchors.iter_mut())
+                .filter_map(|anchor| anchor.as_mut()),
+        );
+    }
+
+    fn apply_mark_lig(&self, subtable: &mut MarkLigPosFormat1) {
+        self.apply_mark_array(&mut subtable.mark_array);
+        self.apply_anchors(
+            subtable
+                .ligature_array
+                .ligature_attaches
+                .iter_mut()
+                .flat_map(|attach| attach.component_records.iter_mut())
+                .flat_map(|record| record.ligature_anchors.iter_mut())
+                .filter_map(|anchor| anchor.as_mut()),
+        );
+    }
+
+    fn apply_mark_mark(&self, subtable: &mut MarkMarkPosFormat1) {
+        self.apply_mark_array(&mut subtable.mark1_array);
+        self.apply_anchors(
+            subtable
+                .mark2_array
+                .mark2_records
+                .iter_mut()
+                .flat_map(|record| record.mark2_anchors.iter_mut())
+                .filter_map(|anchor| anchor.as_mut()),
+        );
+    }
+}
@@ -140,8 +140,11 @@ mod collections;
 pub mod error;
 mod font_builder;
 pub mod from_obj;
+mod glyph_order;
 mod graph;
+mod instancer;
 mod offsets;
+mod read_view;
 mod round;
 mod table_type;
 pub mod tables;
@@ -155,9 +158,12 @@ mod codegen_test;
 mod hex_diff;
 
 pub use font_builder::{BuilderError, FontBuilder};
+pub use glyph_order::{GlyphOrder, GlyphOrderError};
+pub use instancer::{AxisLimit, Instancer, InstancerError};
 pub use offsets::{NullableOffsetMarker, OffsetMarker};
+pub use read_view::ReadView;
 pub use round::OtRound;
-pub use write::{dump_table, FontWrite, TableWriter};
+pub use write::{dump_table, dump_table_with_buffers, FontWrite, ScratchBuffers, TableWriter};
 
 /// Rexport of the common font types
 pub extern crate font_types as types;
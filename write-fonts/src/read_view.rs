@@ -0,0 +1,99 @@
+//! Inspecting compiled tables with the read-fonts API.
+
+use std::marker::PhantomData;
+
+use read_fonts::{FontData, FontRead, FontReadWithArgs, ReadError};
+
+use crate::{from_obj::FromTableRef, validate::Validate, write::FontWrite};
+
+/// A compiled table which can be viewed as its `read-fonts` equivalent.
+///
+/// This is primarily useful in tests and tools that build a table and then
+/// want to inspect it using the parsing API:
+///
+/// ```
+/// # use write_fonts::{tables::maxp::Maxp, ReadView};
+/// let maxp = Maxp::new(42);
+/// let view = ReadView::new(&maxp).unwrap();
+/// assert_eq!(view.read().unwrap().num_glyphs(), 42);
+/// ```
+///
+/// The read type is inferred from the write type, since each write type
+/// implements [`FromTableRef`] for exactly one read type.
+#[derive(Clone, Debug)]
+pub struct ReadView<T> {
+    data: Vec<u8>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: FontWrite + Validate> ReadView<T> {
+    /// Compile `table` into a buffer which can then be read.
+    pub fn new(table: &T) -> Result<Self, crate::error::Error> {
+        Ok(Self {
+            data: crate::dump_table(table)?,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<T> ReadView<T> {
+    /// The compiled bytes of the table.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Parse the compiled bytes as the read equivalent of `T`.
+    pub fn read<'a, R>(&'a self) -> Result<R, ReadError>
+    where
+        R: FontRead<'a>,
+        T: FromTableRef<R>,
+    {
+        R::read(FontData::new(&self.data))
+    }
+
+    /// Parse the compiled bytes as the read equivalent of `T`, for tables
+    /// which require arguments to parse.
+    pub fn read_with_args<'a, R>(&'a self, args: &R::Args) -> Result<R, ReadError>
+    where
+        R: FontReadWithArgs<'a>,
+        T: FromTableRef<R>,
+    {
+        R::read_with_args(FontData::new(&self.data), args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::{
+        hmtx::{Hmtx, LongMetric},
+        layout::{ClassDef, ClassDefBuilder},
+    };
+
+    #[test]
+    fn view_class_def() {
+        let class_def: ClassDef = [(1u16, 2u16), (2, 2), (7, 4)]
+            .into_iter()
+            .map(|(gid, class)| (types::GlyphId16::new(gid), class))
+            .collect::<ClassDefBuilder>()
+            .build();
+        let view = ReadView::new(&class_def).unwrap();
+        let read = view.read().unwrap();
+        assert_eq!(read.get(types::GlyphId16::new(2)), 2);
+        assert_eq!(read.get(types::GlyphId16::new(7)), 4);
+        assert_eq!(read.get(types::GlyphId16::new(3)), 0);
+    }
+
+    #[test]
+    fn view_with_args() {
+        let hmtx = Hmtx::new(
+            vec![LongMetric::new(500, 10), LongMetric::new(600, 20)],
+            vec![30],
+        );
+        let view = ReadView::new(&hmtx).unwrap();
+        let read = view.read_with_args(&(2, 3)).unwrap();
+        assert_eq!(read.h_metrics()[1].advance(), 600);
+        assert_eq!(read.left_side_bearings()[0].get(), 30);
+        assert_eq!(view.data().len(), 2 * 4 + 2);
+    }
+}
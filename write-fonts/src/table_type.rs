@@ -49,6 +49,8 @@ impl TableType {
             self,
             TableType::GposLookup(LookupType::PAIR_POS)
                 | TableType::GposLookup(LookupType::MARK_TO_BASE)
+                // may contain splittable subtables
+                | TableType::GposLookup(LookupType::GPOS_EXT_TYPE)
         )
     }
 
@@ -5,13 +5,18 @@
 
 pub mod avar;
 pub mod base;
+pub mod cff2;
 pub mod cmap;
+pub mod cvar;
+pub mod dsig;
 pub mod fvar;
+pub mod gasp;
 pub mod gdef;
 pub mod glyf;
 pub mod gpos;
 pub mod gsub;
 pub mod gvar;
+pub mod hdmx;
 pub mod head;
 pub mod hhea;
 pub mod hmtx;
@@ -19,6 +24,7 @@ pub mod hvar;
 pub mod ift;
 pub mod layout;
 pub mod loca;
+pub mod ltsh;
 pub mod maxp;
 pub mod meta;
 pub mod mvar;
@@ -28,8 +34,10 @@ pub mod post;
 pub mod sbix;
 pub mod stat;
 pub mod variations;
+pub mod vdmx;
 pub mod vhea;
 pub mod vmtx;
+pub mod vvar;
 
 // ensure that all of our types implement the serde traits
 #[cfg(feature = "serde")]
@@ -39,18 +47,24 @@ fn do_we_even_serde() {
     struct AllTables {
         avar: avar::Avar,
         base: base::Base,
+        cff2: cff2::Cff2,
         cmap: cmap::Cmap,
+        cvar: cvar::Cvar,
+        dsig: dsig::Dsig,
         fvar: fvar::Fvar,
+        gasp: gasp::Gasp,
         gdef: gdef::Gdef,
         glyf: glyf::Glyf,
         gpos: gpos::Gpos,
         gsub: gsub::Gsub,
         gvar: gvar::Gvar,
+        hdmx: hdmx::Hdmx,
         head: head::Head,
         hhea: hhea::Hhea,
         hmtx: hmtx::Hmtx,
         hvar: hvar::Hvar,
         loca: loca::Loca,
+        ltsh: ltsh::Ltsh,
         maxp: maxp::Maxp,
         meta: meta::Meta,
         name: name::Name,
@@ -58,8 +72,10 @@ fn do_we_even_serde() {
         post: post::Post,
         sbix: sbix::Sbix,
         stat: stat::Stat,
+        vdmx: vdmx::Vdmx,
         vhea: vhea::Vhea,
         vmtx: vmtx::Vmtx,
+        vvar: vvar::Vvar,
         ift: ift::Ift,
     }
     let tables = AllTables::default();
@@ -4,6 +4,158 @@ use super::variations::{DeltaSetIndexMap, ItemVariationStore};
 
 include!("../../generated/generated_avar.rs");
 
+use std::fmt::Display;
+
+/// A builder for the [`Avar`] table.
+///
+/// Axis mappings are given in user coordinates, as a list of pairs mapping
+/// user values to design values, in the manner of a designspace file. They
+/// are normalized and converted to segment maps when added.
+#[derive(Clone, Debug, Default)]
+pub struct AvarBuilder {
+    axis_segment_maps: Vec<SegmentMaps>,
+}
+
+/// An error that occurs when adding an axis to an [`AvarBuilder`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AvarBuilderError {
+    /// The axis does not satisfy `min <= default <= max`.
+    InvalidAxisRange(Tag),
+    /// The mapping is not strictly increasing, either before or after
+    /// normalization.
+    NotMonotonic(Tag),
+}
+
+impl AvarBuilder {
+    /// Create a new, empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the mapping for the next axis.
+    ///
+    /// This must be called once for each axis in `fvar`, in the same order.
+    /// `min`, `default` and `max` are the axis range in user coordinates, and
+    /// `mapping` is a list of `(user, design)` pairs; an empty mapping is
+    /// the identity.
+    pub fn add_axis(
+        &mut self,
+        tag: Tag,
+        min: f64,
+        default: f64,
+        max: f64,
+        mapping: &[(f64, f64)],
+    ) -> Result<&mut Self, AvarBuilderError> {
+        if !(min <= default && default <= max) {
+            return Err(AvarBuilderError::InvalidAxisRange(tag));
+        }
+        let mut mapping = mapping.to_vec();
+        mapping.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if mapping
+            .windows(2)
+            .any(|pair| pair[0].0 == pair[1].0 || pair[0].1 >= pair[1].1)
+        {
+            return Err(AvarBuilderError::NotMonotonic(tag));
+        }
+        if mapping.is_empty() {
+            self.axis_segment_maps.push(SegmentMaps::identity());
+            return Ok(self);
+        }
+        let map = |value| piecewise_linear_map(value, &mapping);
+        let design = (map(min), map(default), map(max));
+        let mut value_maps = vec![(-1.0, -1.0), (0.0, 0.0), (1.0, 1.0)];
+        value_maps.extend(
+            mapping
+                .iter()
+                .filter(|(user, _)| (min..=max).contains(user))
+                .map(|(user, design_value)| {
+                    (
+                        normalize(*user, (min, default, max)),
+                        normalize(*design_value, design),
+                    )
+                }),
+        );
+        let mut value_maps = value_maps
+            .into_iter()
+            .map(|(from, to)| {
+                AxisValueMap::new(F2Dot14::from_f32(from as f32), F2Dot14::from_f32(to as f32))
+            })
+            .collect::<Vec<_>>();
+        value_maps.sort_by_key(|map| map.from_coordinate);
+        value_maps.dedup();
+        // values that were distinct may collide once rounded
+        if value_maps.windows(2).any(|pair| {
+            pair[0].from_coordinate == pair[1].from_coordinate
+                || pair[0].to_coordinate > pair[1].to_coordinate
+        }) {
+            return Err(AvarBuilderError::NotMonotonic(tag));
+        }
+        self.axis_segment_maps.push(SegmentMaps::new(value_maps));
+        Ok(self)
+    }
+
+    /// Build the table, or return `None` if every mapping is the identity.
+    pub fn build(self) -> Option<Avar> {
+        self.axis_segment_maps
+            .iter()
+            .any(|maps| !maps.is_identity())
+            .then(|| Avar::new(self.axis_segment_maps))
+    }
+}
+
+/// Normalize a value to the range `-1..=1` given an axis' min, default and max.
+fn normalize(value: f64, (min, default, max): (f64, f64, f64)) -> f64 {
+    let value = value.clamp(min, max);
+    if value < default {
+        (value - default) / (default - min)
+    } else if value > default {
+        (value - default) / (max - default)
+    } else {
+        0.0
+    }
+}
+
+/// Map a value through a sorted list of `(input, output)` pairs, interpolating
+/// between them and extrapolating by the nearest offset outside of them.
+fn piecewise_linear_map(value: f64, mapping: &[(f64, f64)]) -> f64 {
+    let (first, last) = (mapping[0], mapping[mapping.len() - 1]);
+    if value <= first.0 {
+        return value + first.1 - first.0;
+    }
+    if value >= last.0 {
+        return value + last.1 - last.0;
+    }
+    let i = mapping.partition_point(|(input, _)| *input <= value);
+    let ((a, va), (b, vb)) = (mapping[i - 1], mapping[i]);
+    va + (vb - va) * (value - a) / (b - a)
+}
+
+impl SegmentMaps {
+    /// The segment maps for an axis with no mapping.
+    fn identity() -> Self {
+        SegmentMaps::new(
+            [-1.0, 0.0, 1.0]
+                .map(|value| AxisValueMap::new(F2Dot14::from_f32(value), F2Dot14::from_f32(value)))
+                .to_vec(),
+        )
+    }
+}
+
+impl Display for AvarBuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AvarBuilderError::InvalidAxisRange(tag) => {
+                write!(f, "axis '{tag}' must satisfy min <= default <= max")
+            }
+            AvarBuilderError::NotMonotonic(tag) => {
+                write!(f, "mapping for axis '{tag}' is not strictly increasing")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AvarBuilderError {}
+
 impl SegmentMaps {
     /// Returns true if all the axis value maps are identity maps.
     pub fn is_identity(&self) -> bool {
@@ -57,4 +209,63 @@ mod tests {
 
         assert!(!segment_maps.is_identity());
     }
+
+    #[test]
+    fn builder_normalizes_mappings() {
+        let mut builder = AvarBuilder::new();
+        builder
+            .add_axis(Tag::new(b"wght"), 100.0, 400.0, 900.0, &[])
+            .unwrap()
+            .add_axis(
+                Tag::new(b"wdth"),
+                50.0,
+                100.0,
+                200.0,
+                &[(50.0, 10.0), (75.0, 40.0), (100.0, 50.0), (200.0, 150.0)],
+            )
+            .unwrap();
+        let avar = builder.build().unwrap();
+        assert!(avar.axis_segment_maps[0].is_identity());
+        let maps = avar.axis_segment_maps[1]
+            .axis_value_maps
+            .iter()
+            .map(|map| (map.from_coordinate.to_f32(), map.to_coordinate.to_f32()))
+            .collect::<Vec<_>>();
+        assert_eq!(maps, [(-1.0, -1.0), (-0.5, -0.25), (0.0, 0.0), (1.0, 1.0)]);
+
+        let bytes = crate::dump_table(&avar).unwrap();
+        let read = read_fonts::tables::avar::Avar::read(FontData::new(&bytes)).unwrap();
+        assert_eq!(read.axis_count(), 2);
+    }
+
+    #[test]
+    fn builder_identity_is_none() {
+        let mut builder = AvarBuilder::new();
+        builder
+            .add_axis(
+                Tag::new(b"wght"),
+                100.0,
+                400.0,
+                900.0,
+                &[(100.0, 100.0), (900.0, 900.0)],
+            )
+            .unwrap();
+        assert!(builder.build().is_none());
+    }
+
+    #[test]
+    fn builder_rejects_non_monotonic() {
+        let wdth = Tag::new(b"wdth");
+        let mut builder = AvarBuilder::new();
+        assert_eq!(
+            builder
+                .add_axis(wdth, 50.0, 100.0, 200.0, &[(50.0, 60.0), (100.0, 50.0)])
+                .err(),
+            Some(AvarBuilderError::NotMonotonic(wdth))
+        );
+        assert_eq!(
+            builder.add_axis(wdth, 100.0, 50.0, 200.0, &[]).err(),
+            Some(AvarBuilderError::InvalidAxisRange(wdth))
+        );
+    }
 }
@@ -0,0 +1,899 @@
+//! The [CFF2](https://learn.microsoft.com/en-us/typography/opentype/spec/cff2) table
+
+include!("../../generated/generated_cff2.rs");
+
+mod charstring;
+
+pub use charstring::{BlendPoint, CharString, CharStringBuilder, CharStringError};
+
+use super::variations::ItemVariationStore;
+use read_fonts::tables::postscript::{
+    dict::{self, Operator, Token},
+    FdSelect, Index, Number,
+};
+
+/// The maximum number of operands on the stack, used when splitting blends.
+const MAX_STACK: usize = 513;
+
+/// The [CFF2](https://learn.microsoft.com/en-us/typography/opentype/spec/cff2) table
+///
+/// Unlike most tables, this is not written field by field; offsets in the
+/// top DICT are relative to the start of the table, and so the whole table
+/// is laid out when it is compiled.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cff2 {
+    /// The FontMatrix, if it is not the default of `[0.001, 0, 0, 0.001, 0, 0]`.
+    pub font_matrix: Option<[f64; 6]>,
+    /// Global subroutines, as compiled charstring data.
+    pub global_subrs: Vec<Vec<u8>>,
+    /// One charstring per glyph.
+    pub char_strings: Vec<CharString>,
+    /// The private dicts referenced by the font dicts in the FDArray.
+    ///
+    /// If this is empty, a single empty private dict is written.
+    pub private_dicts: Vec<PrivateDict>,
+    /// The index of the font dict used by each glyph.
+    ///
+    /// This may be empty if there is only a single font dict.
+    pub fd_select: Vec<u16>,
+    /// The variation store used by blended values in charstrings and
+    /// private dicts.
+    pub variation_store: Option<ItemVariationStore>,
+}
+
+/// A [Private DICT](https://learn.microsoft.com/en-us/typography/opentype/spec/cff2#private-dict-data)
+///
+/// Values in arrays are stored as absolute values; they are delta-encoded
+/// when compiled.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrivateDict {
+    pub blue_values: Vec<BlendValue>,
+    pub other_blues: Vec<BlendValue>,
+    pub family_blues: Vec<BlendValue>,
+    pub family_other_blues: Vec<BlendValue>,
+    pub blue_scale: Option<BlendValue>,
+    pub blue_shift: Option<BlendValue>,
+    pub blue_fuzz: Option<BlendValue>,
+    pub std_hw: Option<BlendValue>,
+    pub std_vw: Option<BlendValue>,
+    pub stem_snap_h: Vec<BlendValue>,
+    pub stem_snap_v: Vec<BlendValue>,
+    pub language_group: Option<i32>,
+    pub expansion_factor: Option<f64>,
+    /// The `ItemVariationData` used by blends in this dict and in the
+    /// charstrings that use it, if not the first.
+    pub vs_index: Option<u16>,
+    /// Local subroutines, as compiled charstring data.
+    pub subrs: Vec<Vec<u8>>,
+}
+
+/// A value that may vary across the designspace.
+///
+/// The deltas correspond to the regions of the `ItemVariationData` in use,
+/// and are written with the `blend` operator. A value with no deltas is
+/// not variable.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlendValue {
+    pub default: f64,
+    pub deltas: Vec<f64>,
+}
+
+impl TopLevelTable for Cff2 {
+    const TAG: Tag = Tag::new(b"CFF2");
+}
+
+impl BlendValue {
+    /// Create a new value with the given deltas.
+    pub fn new(default: f64, deltas: Vec<f64>) -> Self {
+        Self { default, deltas }
+    }
+
+    /// Returns `true` if any delta is non-zero.
+    pub fn is_variable(&self) -> bool {
+        self.deltas.iter().any(|delta| *delta != 0.0)
+    }
+
+    /// `self - other`, treating missing deltas as zero.
+    fn sub(&self, other: &BlendValue, region_count: u16) -> BlendValue {
+        let get = |deltas: &[f64], i| deltas.get(i).copied().unwrap_or_default();
+        let deltas = if self.is_variable() || other.is_variable() {
+            (0..region_count as usize)
+                .map(|i| get(&self.deltas, i) - get(&other.deltas, i))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        BlendValue::new(self.default - other.default, deltas)
+    }
+}
+
+impl From<f64> for BlendValue {
+    fn from(value: f64) -> Self {
+        BlendValue::new(value, Vec::new())
+    }
+}
+
+impl Cff2 {
+    /// The number of regions in the `ItemVariationData` at `vs_index`.
+    fn region_count(&self, vs_index: u16) -> Option<u16> {
+        let data = self
+            .variation_store
+            .as_ref()?
+            .item_variation_data
+            .get(vs_index as usize)?;
+        Some(
+            data.as_ref()
+                .map_or(0, |data| data.region_indexes.len() as u16),
+        )
+    }
+
+    fn compile(&self) -> Vec<u8> {
+        let default_private_dict = [PrivateDict::default()];
+        let private_dicts = match self.private_dicts.as_slice() {
+            [] => default_private_dict.as_slice(),
+            dicts => dicts,
+        };
+        let var_store = self
+            .variation_store
+            .as_ref()
+            .and_then(|store| crate::dump_table(store).ok());
+        let fd_select = (!self.fd_select.is_empty()).then(|| compile_fd_select(&self.fd_select));
+
+        // offsets in dicts are always written with the five byte encoding, so
+        // we can compute the size of the top dict before we know their values.
+        let top_dict_len = self.compile_top_dict(&mut TopDictOffsets::default()).len();
+        let mut offsets = TopDictOffsets::default();
+        let mut tail = Vec::new();
+        let start = 5 + top_dict_len;
+        write_index(&mut tail, &self.global_subrs);
+        if let Some(var_store) = &var_store {
+            offsets.var_store = Some(start + tail.len());
+            tail.extend((var_store.len() as u16).to_be_bytes());
+            tail.extend(var_store);
+        }
+        if let Some(fd_select) = &fd_select {
+            offsets.fd_select = Some(start + tail.len());
+            tail.extend(fd_select);
+        }
+        offsets.char_strings = start + tail.len();
+        write_index(&mut tail, &self.char_strings);
+
+        let compiled_private_dicts = private_dicts
+            .iter()
+            .map(|dict| dict.compile(self.region_count(dict.vs_index.unwrap_or_default())))
+            .collect::<Vec<_>>();
+        // each font dict contains only the size and offset of its private dict
+        const FONT_DICT_LEN: usize = 11;
+        offsets.fd_array = start + tail.len();
+        let fd_array_len = index_len(private_dicts.len(), FONT_DICT_LEN * private_dicts.len());
+        let mut private_offset = offsets.fd_array + fd_array_len;
+        let mut font_dicts = Vec::with_capacity(private_dicts.len());
+        let mut private_data = Vec::new();
+        for (dict, subrs) in compiled_private_dicts.iter().zip(private_dicts) {
+            let mut font_dict = Vec::with_capacity(FONT_DICT_LEN);
+            encode_offset(dict.len(), &mut font_dict);
+            encode_offset(private_offset, &mut font_dict);
+            font_dict.push(18);
+            font_dicts.push(font_dict);
+            private_data.extend(dict);
+            write_index(&mut private_data, &subrs.subrs);
+            private_offset = offsets.fd_array + fd_array_len + private_data.len();
+        }
+        write_index(&mut tail, &font_dicts);
+        tail.extend(private_data);
+
+        let top_dict = self.compile_top_dict(&mut offsets);
+        debug_assert_eq!(top_dict.len(), top_dict_len);
+        let mut data = Vec::with_capacity(start + tail.len());
+        data.extend([2, 0, 5]);
+        data.extend((top_dict.len() as u16).to_be_bytes());
+        data.extend(top_dict);
+        data.extend(tail);
+        data
+    }
+
+    fn compile_top_dict(&self, offsets: &mut TopDictOffsets) -> Vec<u8> {
+        let mut data = Vec::new();
+        if let Some(matrix) = self.font_matrix {
+            for value in matrix {
+                encode_dict_number(value, &mut data);
+            }
+            data.extend([12, 7]);
+        }
+        encode_offset(offsets.char_strings, &mut data);
+        data.push(17);
+        if self.variation_store.is_some() {
+            encode_offset(offsets.var_store.unwrap_or_default(), &mut data);
+            data.push(24);
+        }
+        encode_offset(offsets.fd_array, &mut data);
+        data.extend([12, 36]);
+        if !self.fd_select.is_empty() {
+            encode_offset(offsets.fd_select.unwrap_or_default(), &mut data);
+            data.extend([12, 37]);
+        }
+        data
+    }
+}
+
+/// The offsets written to the top dict.
+#[derive(Default)]
+struct TopDictOffsets {
+    char_strings: usize,
+    var_store: Option<usize>,
+    fd_array: usize,
+    fd_select: Option<usize>,
+}
+
+impl PrivateDict {
+    fn compile(&self, region_count: Option<u16>) -> Vec<u8> {
+        let region_count = region_count.unwrap_or_default();
+        let mut data = Vec::new();
+        if let Some(vs_index) = self.vs_index {
+            encode_dict_number(vs_index as f64, &mut data);
+            data.push(22);
+        }
+        let arrays = [
+            (&self.blue_values, &[6][..]),
+            (&self.other_blues, &[7]),
+            (&self.family_blues, &[8]),
+            (&self.family_other_blues, &[9]),
+        ];
+        for (values, op) in arrays {
+            encode_delta_array(values, op, region_count, &mut data);
+        }
+        let values = [
+            (&self.std_hw, &[10][..]),
+            (&self.std_vw, &[11]),
+            (&self.blue_scale, &[12, 9]),
+            (&self.blue_shift, &[12, 10]),
+            (&self.blue_fuzz, &[12, 11]),
+        ];
+        for (value, op) in values {
+            if let Some(value) = value {
+                encode_blended(std::slice::from_ref(value), op, region_count, &mut data);
+            }
+        }
+        encode_delta_array(&self.stem_snap_h, &[12, 12], region_count, &mut data);
+        encode_delta_array(&self.stem_snap_v, &[12, 13], region_count, &mut data);
+        if let Some(language_group) = self.language_group {
+            encode_dict_number(language_group as f64, &mut data);
+            data.extend([12, 17]);
+        }
+        if let Some(expansion_factor) = self.expansion_factor {
+            encode_dict_number(expansion_factor, &mut data);
+            data.extend([12, 18]);
+        }
+        if !self.subrs.is_empty() {
+            // local subrs immediately follow the private dict
+            let len = data.len() + 6;
+            encode_offset(len, &mut data);
+            data.push(19);
+        }
+        data
+    }
+
+    /// Return any value that has the wrong number of deltas.
+    fn bad_delta_count(&self, region_count: u16) -> Option<usize> {
+        [
+            &self.blue_values,
+            &self.other_blues,
+            &self.family_blues,
+            &self.family_other_blues,
+            &self.stem_snap_h,
+            &self.stem_snap_v,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(
+            [
+                &self.blue_scale,
+                &self.blue_shift,
+                &self.blue_fuzz,
+                &self.std_hw,
+                &self.std_vw,
+            ]
+            .into_iter()
+            .flatten(),
+        )
+        .map(|value| value.deltas.len())
+        .find(|len| *len != 0 && *len != region_count as usize)
+    }
+}
+
+fn encode_delta_array(values: &[BlendValue], op: &[u8], region_count: u16, data: &mut Vec<u8>) {
+    if values.is_empty() {
+        return;
+    }
+    let mut prev = BlendValue::default();
+    let deltas = values
+        .iter()
+        .map(|value| {
+            let delta = value.sub(&prev, region_count);
+            prev = value.clone();
+            delta
+        })
+        .collect::<Vec<_>>();
+    encode_blended(&deltas, op, region_count, data);
+}
+
+/// Encode the operands for a dict operator, blending them if required.
+fn encode_blended(values: &[BlendValue], op: &[u8], region_count: u16, data: &mut Vec<u8>) {
+    if values.iter().any(BlendValue::is_variable) {
+        // split the operands so that each blend fits on the stack
+        let chunk_len = (MAX_STACK - 1) / (region_count as usize + 1);
+        for chunk in values.chunks(chunk_len.max(1)) {
+            for value in chunk {
+                encode_dict_number(value.default, data);
+            }
+            for value in chunk {
+                for i in 0..region_count as usize {
+                    encode_dict_number(value.deltas.get(i).copied().unwrap_or_default(), data);
+                }
+            }
+            encode_dict_number(chunk.len() as f64, data);
+            data.push(23);
+        }
+    } else {
+        for value in values {
+            encode_dict_number(value.default, data);
+        }
+    }
+    data.extend(op);
+}
+
+/// Encode a dict operand, as an integer if possible or else as a real number.
+///
+/// See <https://learn.microsoft.com/en-us/typography/opentype/spec/cff2#table-3-operand-encoding>
+fn encode_dict_number(value: f64, data: &mut Vec<u8>) {
+    if value.fract() != 0.0 || !(i32::MIN as f64..=i32::MAX as f64).contains(&value) {
+        return encode_real(value, data);
+    }
+    let value = value as i32;
+    match value {
+        -107..=107 => data.push((value + 139) as u8),
+        108..=1131 => {
+            let value = value - 108;
+            data.extend([((value >> 8) + 247) as u8, value as u8]);
+        }
+        -1131..=-108 => {
+            let value = -value - 108;
+            data.extend([((value >> 8) + 251) as u8, value as u8]);
+        }
+        -32768..=32767 => {
+            data.push(28);
+            data.extend((value as i16).to_be_bytes());
+        }
+        _ => {
+            data.push(29);
+            data.extend(value.to_be_bytes());
+        }
+    }
+}
+
+/// Encode an offset using the fixed-size five byte integer encoding.
+fn encode_offset(offset: usize, data: &mut Vec<u8>) {
+    data.push(29);
+    data.extend((offset as i32).to_be_bytes());
+}
+
+/// Encode a real number as packed binary coded decimal.
+fn encode_real(value: f64, data: &mut Vec<u8>) {
+    let repr = value.to_string();
+    let mut nibbles = Vec::with_capacity(repr.len() + 1);
+    for c in repr.bytes() {
+        match c {
+            b'0'..=b'9' => nibbles.push(c - b'0'),
+            b'.' => nibbles.push(0xa),
+            b'-' => nibbles.push(0xe),
+            _ => (),
+        }
+    }
+    nibbles.push(0xf);
+    if nibbles.len() % 2 == 1 {
+        nibbles.push(0xf);
+    }
+    data.push(30);
+    data.extend(nibbles.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+}
+
+/// The size of an INDEX with `count` items totalling `data_len` bytes.
+fn index_len(count: usize, data_len: usize) -> usize {
+    if count == 0 {
+        return 4;
+    }
+    4 + 1 + (count + 1) * offset_size(data_len + 1) + data_len
+}
+
+fn offset_size(max_offset: usize) -> usize {
+    match max_offset {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        0x10000..=0xFFFFFF => 3,
+        _ => 4,
+    }
+}
+
+/// Write a CFF2 INDEX, which has a 32-bit count.
+///
+/// See <https://learn.microsoft.com/en-us/typography/opentype/spec/cff2#index-data>
+fn write_index<T: AsRef<[u8]>>(data: &mut Vec<u8>, items: &[T]) {
+    data.extend((items.len() as u32).to_be_bytes());
+    if items.is_empty() {
+        return;
+    }
+    let data_len: usize = items.iter().map(|item| item.as_ref().len()).sum();
+    let off_size = offset_size(data_len + 1);
+    data.push(off_size as u8);
+    let mut offset = 1u32;
+    for item in items.iter().map(AsRef::as_ref).chain([&[][..]]) {
+        data.extend(&offset.to_be_bytes()[4 - off_size..]);
+        offset += item.len() as u32;
+    }
+    for item in items {
+        data.extend(item.as_ref());
+    }
+}
+
+/// Compile the FDSelect using whichever of formats 0 and 3 is smaller, or
+/// format 4 if required.
+fn compile_fd_select(fds: &[u16]) -> Vec<u8> {
+    let mut ranges: Vec<(usize, u16)> = Vec::new();
+    for (gid, fd) in fds.iter().copied().enumerate() {
+        if ranges.last().map(|(_, last)| *last) != Some(fd) {
+            ranges.push((gid, fd));
+        }
+    }
+    let mut data = Vec::new();
+    if fds.len() > u16::MAX as usize || fds.iter().any(|fd| *fd > u8::MAX as u16) {
+        data.push(4);
+        data.extend((ranges.len() as u32).to_be_bytes());
+        for (first, fd) in ranges {
+            data.extend((first as u32).to_be_bytes());
+            data.extend(fd.to_be_bytes());
+        }
+        data.extend((fds.len() as u32).to_be_bytes());
+    } else if fds.len() <= 4 + ranges.len() * 3 {
+        data.push(0);
+        data.extend(fds.iter().map(|fd| *fd as u8));
+    } else {
+        data.push(3);
+        data.extend((ranges.len() as u16).to_be_bytes());
+        for (first, fd) in ranges {
+            data.extend((first as u16).to_be_bytes());
+            data.push(fd as u8);
+        }
+        data.extend((fds.len() as u16).to_be_bytes());
+    }
+    data
+}
+
+impl AsRef<[u8]> for CharString {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl FontWrite for Cff2 {
+    fn write_into(&self, writer: &mut TableWriter) {
+        writer.write_slice(&self.compile())
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::TopLevel(Cff2::TAG)
+    }
+}
+
+impl Validate for Cff2 {
+    fn validate_impl(&self, ctx: &mut ValidationCtx) {
+        ctx.in_table("CFF2", |ctx| {
+            let dict_count = self.private_dicts.len().max(1);
+            ctx.in_field("fd_select", |ctx| {
+                if self.fd_select.is_empty() && dict_count > 1 {
+                    ctx.report("fd_select is required when there are multiple private dicts");
+                }
+                if !self.fd_select.is_empty() && self.fd_select.len() != self.char_strings.len() {
+                    ctx.report("fd_select must have one entry per glyph");
+                }
+                if self.fd_select.iter().any(|fd| *fd as usize >= dict_count) {
+                    ctx.report("fd_select references a missing private dict");
+                }
+            });
+            ctx.in_field("private_dicts", |ctx| {
+                for dict in &self.private_dicts {
+                    let vs_index = dict.vs_index.unwrap_or_default();
+                    let region_count = self.region_count(vs_index);
+                    if region_count.is_none()
+                        && (dict.vs_index.is_some() && self.variation_store.is_some()
+                            || dict.bad_delta_count(0).is_some())
+                    {
+                        ctx.report(format!("missing ItemVariationData {vs_index}"));
+                    } else if let Some(len) = dict.bad_delta_count(region_count.unwrap_or_default())
+                    {
+                        ctx.report(format!(
+                            "blended value has {len} deltas, expected {}",
+                            region_count.unwrap_or_default()
+                        ));
+                    }
+                }
+            });
+            ctx.in_field("variation_store", |ctx| {
+                if let Some(store) = &self.variation_store {
+                    match crate::dump_table(store) {
+                        Ok(data) if data.len() > u16::MAX as usize => {
+                            ctx.report("variation store is too large")
+                        }
+                        Err(_) => ctx.report("failed to compile variation store"),
+                        _ => (),
+                    }
+                }
+            });
+        })
+    }
+}
+
+impl<'a> FromObjRef<read_fonts::tables::cff2::Cff2<'a>> for Cff2 {
+    fn from_obj_ref(obj: &read_fonts::tables::cff2::Cff2<'a>, _: FontData) -> Self {
+        let table_data = obj.offset_data().as_bytes();
+        let index_items = |offset: usize| {
+            table_data
+                .get(offset..)
+                .and_then(|data| Index::new(data, true).ok())
+                .map(|index| {
+                    (0..index.count() as usize)
+                        .filter_map(|i| index.get(i).ok())
+                        .map(<[u8]>::to_vec)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        };
+        let mut cff2 = Cff2 {
+            global_subrs: (0..obj.global_subrs().count() as usize)
+                .filter_map(|i| obj.global_subrs().get(i).ok())
+                .map(<[u8]>::to_vec)
+                .collect(),
+            ..Default::default()
+        };
+        let mut read_store = None;
+        let mut fd_array = Vec::new();
+        let mut fd_select = None;
+        for entry in dict::entries(obj.top_dict_data(), None).flatten() {
+            match entry {
+                dict::Entry::FontMatrix(matrix) => {
+                    cff2.font_matrix = Some(matrix.map(|value| value.to_f64()))
+                }
+                dict::Entry::CharstringsOffset(offset) => {
+                    cff2.char_strings = index_items(offset)
+                        .into_iter()
+                        .map(CharString::from_raw)
+                        .collect()
+                }
+                dict::Entry::VariationStoreOffset(offset) => {
+                    read_store = table_data.get(offset + 2..).and_then(|data| {
+                        read_fonts::tables::variations::ItemVariationStore::read(FontData::new(
+                            data,
+                        ))
+                        .ok()
+                    });
+                }
+                dict::Entry::FdArrayOffset(offset) => fd_array = index_items(offset),
+                dict::Entry::FdSelectOffset(offset) => {
+                    fd_select = table_data
+                        .get(offset..)
+                        .and_then(|data| FdSelect::read(FontData::new(data)).ok());
+                }
+                _ => (),
+            }
+        }
+        let region_count = |vs_index: u16| {
+            read_store
+                .as_ref()
+                .and_then(|store| store.item_variation_data().get(vs_index as usize))
+                .and_then(Result::ok)
+                .map(|data| data.region_index_count())
+                .unwrap_or_default()
+        };
+        for font_dict in &fd_array {
+            let range = dict::entries(font_dict, None)
+                .flatten()
+                .find_map(|entry| match entry {
+                    dict::Entry::PrivateDictRange(range) => Some(range),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            let private_data = table_data.get(range.clone()).unwrap_or_default();
+            let (mut private_dict, subrs_offset) = read_private_dict(private_data, region_count);
+            if let Some(offset) = subrs_offset {
+                private_dict.subrs = index_items(range.start + offset);
+            }
+            cff2.private_dicts.push(private_dict);
+        }
+        if let Some(fd_select) = fd_select {
+            cff2.fd_select = (0..cff2.char_strings.len() as u32)
+                .map(|gid| fd_select.font_index(gid.into()).unwrap_or_default())
+                .collect();
+        }
+        cff2.variation_store = read_store.map(|store| store.to_owned_table());
+        cff2
+    }
+}
+
+impl FromTableRef<read_fonts::tables::cff2::Cff2<'_>> for Cff2 {}
+
+impl<'a> FontRead<'a> for Cff2 {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        read_fonts::tables::cff2::Cff2::read(data).map(|x| x.to_owned_table())
+    }
+}
+
+/// Parse the raw tokens of a private dict, preserving any blends.
+///
+/// Returns the dict and the offset of the local subrs, if present.
+fn read_private_dict(
+    data: &[u8],
+    region_count: impl Fn(u16) -> u16,
+) -> (PrivateDict, Option<usize>) {
+    let mut dict = PrivateDict::default();
+    let mut subrs_offset = None;
+    let mut stack: Vec<BlendValue> = Vec::new();
+    let mut vs_index = 0;
+    for token in dict::tokens(data).flatten() {
+        let op = match token {
+            Token::Operand(number) => {
+                stack.push(number_to_f64(number).into());
+                continue;
+            }
+            Token::Operator(op) => op,
+        };
+        let single = || stack.last().cloned();
+        let array = || {
+            let mut prev = BlendValue::default();
+            stack
+                .iter()
+                .map(|delta| {
+                    prev = BlendValue::new(
+                        prev.default + delta.default,
+                        (0..delta.deltas.len().max(prev.deltas.len()))
+                            .map(|i| {
+                                prev.deltas.get(i).copied().unwrap_or_default()
+                                    + delta.deltas.get(i).copied().unwrap_or_default()
+                            })
+                            .collect(),
+                    );
+                    let mut value = prev.clone();
+                    if !value.is_variable() {
+                        value.deltas.clear();
+                    }
+                    value
+                })
+                .collect::<Vec<_>>()
+        };
+        match op {
+            Operator::Blend => {
+                let count = stack.pop().map(|n| n.default as usize).unwrap_or_default();
+                let regions = region_count(vs_index) as usize;
+                let Some(start) = stack.len().checked_sub(count * (regions + 1)) else {
+                    break;
+                };
+                let operands = stack.split_off(start);
+                let (defaults, deltas) = operands.split_at(count);
+                stack.extend(defaults.iter().enumerate().map(|(i, value)| {
+                    BlendValue::new(
+                        value.default,
+                        deltas[i * regions..(i + 1) * regions]
+                            .iter()
+                            .map(|delta| delta.default)
+                            .collect(),
+                    )
+                }));
+                continue;
+            }
+            Operator::VariationStoreIndex => {
+                vs_index = single()
+                    .map(|value| value.default as u16)
+                    .unwrap_or_default();
+                dict.vs_index = Some(vs_index);
+            }
+            Operator::BlueValues => dict.blue_values = array(),
+            Operator::OtherBlues => dict.other_blues = array(),
+            Operator::FamilyBlues => dict.family_blues = array(),
+            Operator::FamilyOtherBlues => dict.family_other_blues = array(),
+            Operator::StemSnapH => dict.stem_snap_h = array(),
+            Operator::StemSnapV => dict.stem_snap_v = array(),
+            Operator::BlueScale => dict.blue_scale = single(),
+            Operator::BlueShift => dict.blue_shift = single(),
+            Operator::BlueFuzz => dict.blue_fuzz = single(),
+            Operator::StdHw => dict.std_hw = single(),
+            Operator::StdVw => dict.std_vw = single(),
+            Operator::LanguageGroup => {
+                dict.language_group = single().map(|value| value.default as i32)
+            }
+            Operator::ExpansionFactor => {
+                dict.expansion_factor = single().map(|value| value.default)
+            }
+            Operator::SubrsOffset => subrs_offset = single().map(|value| value.default as usize),
+            _ => (),
+        }
+        stack.clear();
+    }
+    (dict, subrs_offset)
+}
+
+fn number_to_f64(number: Number) -> f64 {
+    match number {
+        Number::I32(value) => value as f64,
+        Number::Fixed(value) => value.to_f64(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use font_types::F2Dot14;
+    use read_fonts::{
+        tables::postscript::{charstring, BlendState},
+        FontRef, TableProvider,
+    };
+
+    use super::*;
+    use crate::tables::variations::{
+        ivs_builder::VariationStoreBuilder, RegionAxisCoordinates, VariationRegion,
+    };
+
+    #[derive(Default)]
+    struct CommandCollector(Vec<String>);
+
+    impl charstring::CommandSink for CommandCollector {
+        fn move_to(&mut self, x: Fixed, y: Fixed) {
+            self.0.push(format!("M{x},{y}"));
+        }
+        fn line_to(&mut self, x: Fixed, y: Fixed) {
+            self.0.push(format!("L{x},{y}"));
+        }
+        fn curve_to(&mut self, cx0: Fixed, cy0: Fixed, cx1: Fixed, cy1: Fixed, x: Fixed, y: Fixed) {
+            self.0.push(format!("C{cx0},{cy0} {cx1},{cy1} {x},{y}"));
+        }
+        fn close(&mut self) {
+            self.0.push("Z".into());
+        }
+    }
+
+    fn one_region_store() -> ItemVariationStore {
+        let region = VariationRegion::new(vec![RegionAxisCoordinates {
+            start_coord: F2Dot14::from_f32(0.0),
+            peak_coord: F2Dot14::from_f32(1.0),
+            end_coord: F2Dot14::from_f32(1.0),
+        }]);
+        let mut builder = VariationStoreBuilder::new(1);
+        builder.add_deltas(vec![(region, 1)]);
+        builder.build().0
+    }
+
+    /// Evaluate the charstring for `gid` at the normalized `coord`.
+    fn draw(data: &[u8], gid: usize, coord: f32) -> Vec<String> {
+        let cff2 = read_fonts::tables::cff2::Cff2::read(FontData::new(data)).unwrap();
+        let mut char_strings = None;
+        let mut store = None;
+        for entry in dict::entries(cff2.top_dict_data(), None) {
+            match entry.unwrap() {
+                dict::Entry::CharstringsOffset(offset) => {
+                    char_strings = Some(Index::new(&data[offset..], true).unwrap())
+                }
+                dict::Entry::VariationStoreOffset(offset) => {
+                    store = Some(
+                        read_fonts::tables::variations::ItemVariationStore::read(FontData::new(
+                            &data[offset + 2..],
+                        ))
+                        .unwrap(),
+                    )
+                }
+                _ => (),
+            }
+        }
+        let coords = [F2Dot14::from_f32(coord)];
+        let blend_state = store.map(|store| BlendState::new(store, &coords, 0).unwrap());
+        let mut sink = CommandCollector::default();
+        charstring::evaluate(
+            char_strings.unwrap().get(gid).unwrap(),
+            Index::Empty,
+            None,
+            blend_state,
+            &mut sink,
+        )
+        .unwrap();
+        sink.0
+    }
+
+    #[test]
+    fn compile_variable_glyph() {
+        let mut notdef = kurbo::BezPath::new();
+        notdef.move_to((0.0, 0.0));
+        notdef.line_to((100.0, 0.0));
+        notdef.line_to((100.0, 100.0));
+
+        let mut builder = CharStringBuilder::new(1);
+        builder
+            .move_to(BlendPoint::new(BlendValue::new(10.0, vec![20.0]), 0.0))
+            .curve_to(
+                kurbo::Point::new(20.0, 10.0),
+                kurbo::Point::new(30.0, 10.0),
+                BlendPoint::new(40.0, BlendValue::new(0.0, vec![-5.0])),
+            );
+        let cff2 = Cff2 {
+            char_strings: vec![CharString::from_bezpath(&notdef), builder.build().unwrap()],
+            variation_store: Some(one_region_store()),
+            ..Default::default()
+        };
+        let data = crate::dump_table(&cff2).unwrap();
+
+        assert_eq!(draw(&data, 0, 1.0), ["M0,0", "L100,0", "L100,100"]);
+        assert_eq!(draw(&data, 1, 0.0), ["M10,0", "C20,10 30,10 40,0"]);
+        assert_eq!(draw(&data, 1, 1.0), ["M30,0", "C20,10 30,10 40,-5"]);
+        assert_eq!(draw(&data, 1, 0.5), ["M20,0", "C20,10 30,10 40,-2.5"]);
+    }
+
+    #[test]
+    fn private_dicts_round_trip() {
+        let dict = PrivateDict {
+            blue_values: vec![
+                BlendValue::new(-10.0, vec![-2.0]),
+                0.0.into(),
+                BlendValue::new(500.0, vec![10.0]),
+                510.0.into(),
+            ],
+            blue_scale: Some(0.03125.into()),
+            std_vw: Some(BlendValue::new(80.0, vec![40.0])),
+            stem_snap_h: vec![70.0.into(), 90.0.into()],
+            subrs: vec![vec![139, 11]],
+            ..Default::default()
+        };
+        let cff2 = Cff2 {
+            font_matrix: Some([0.0009765625, 0.0, 0.0, 0.0009765625, 0.0, 0.0]),
+            global_subrs: vec![vec![139, 139, 11]],
+            char_strings: vec![CharString::default(); 4],
+            private_dicts: vec![dict.clone(), PrivateDict::default()],
+            fd_select: vec![0, 1, 1, 0],
+            variation_store: Some(one_region_store()),
+        };
+        let data = crate::dump_table(&cff2).unwrap();
+        let read = Cff2::read(FontData::new(&data)).unwrap();
+        assert_eq!(read, cff2);
+    }
+
+    #[test]
+    fn round_trip_cantarell() {
+        let font = FontRef::new(font_test_data::CANTARELL_VF_TRIMMED).unwrap();
+        let cff2: Cff2 = font.cff2().unwrap().to_owned_table();
+        assert!(!cff2.char_strings.is_empty());
+        assert!(cff2.variation_store.is_some());
+        let data = crate::dump_table(&cff2).unwrap();
+        let read = Cff2::read(FontData::new(&data)).unwrap();
+        assert_eq!(read, cff2);
+    }
+
+    #[test]
+    fn validate_fd_select() {
+        let cff2 = Cff2 {
+            char_strings: vec![CharString::default(); 2],
+            private_dicts: vec![PrivateDict::default(), PrivateDict::default()],
+            ..Default::default()
+        };
+        assert!(crate::dump_table(&cff2).is_err());
+    }
+
+    #[test]
+    fn fd_select_formats() {
+        assert_eq!(compile_fd_select(&[0, 1, 0]), [0, 0, 1, 0]);
+        let fds = [0; 10].into_iter().chain([1; 10]).collect::<Vec<_>>();
+        assert_eq!(compile_fd_select(&fds), [3, 0, 2, 0, 0, 0, 0, 10, 1, 0, 20]);
+    }
+
+    #[test]
+    fn real_encoding() {
+        let mut data = Vec::new();
+        encode_dict_number(-2.25, &mut data);
+        assert_eq!(data, [30, 0xe2, 0xa2, 0x5f]);
+    }
+}
@@ -0,0 +1,368 @@
+//! Compiling CFF2 charstrings
+
+use kurbo::{BezPath, PathEl, Point};
+
+use super::BlendValue;
+
+/// The default maximum stack depth for CFF2 charstrings.
+///
+/// See <https://learn.microsoft.com/en-us/typography/opentype/spec/cff2#maxstack>
+const MAX_STACK: usize = 513;
+
+// charstring operators
+const RLINETO: u8 = 5;
+const RRCURVETO: u8 = 8;
+const VSINDEX: u8 = 15;
+const BLEND: u8 = 16;
+const RMOVETO: u8 = 21;
+
+/// A compiled CFF2 charstring.
+///
+/// Charstrings are constructed with a [`CharStringBuilder`], or from a
+/// static [`BezPath`] with [`CharString::from_bezpath`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CharString(Vec<u8>);
+
+/// A point whose coordinates may vary across the designspace.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlendPoint {
+    pub x: BlendValue,
+    pub y: BlendValue,
+}
+
+/// A builder for variable [`CharString`]s.
+///
+/// Points are given in absolute coordinates; the builder takes care of
+/// converting them to the relative operands used in the charstring, and of
+/// inserting `blend` operators for any values that vary.
+#[derive(Clone, Debug)]
+pub struct CharStringBuilder {
+    region_count: u16,
+    vs_index: Option<u16>,
+    commands: Vec<(u8, Vec<BlendPoint>)>,
+}
+
+/// An error that occurs when building a [`CharString`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CharStringError {
+    /// A value does not have one delta per region.
+    DeltaCountMismatch { expected: u16, actual: usize },
+    /// A line or curve was added before the first move.
+    MissingMove,
+}
+
+impl CharString {
+    /// Create a charstring from already compiled data.
+    pub fn from_raw(data: Vec<u8>) -> Self {
+        Self(data)
+    }
+
+    /// Create a non-variable charstring from a kurbo `BezPath`.
+    ///
+    /// Quadratic segments are converted to cubics; contours are closed
+    /// implicitly, so `ClosePath` elements are ignored.
+    pub fn from_bezpath(path: &BezPath) -> Self {
+        let mut builder = CharStringBuilder::new(0);
+        let mut last = Point::ZERO;
+        for el in path.elements() {
+            match *el {
+                PathEl::MoveTo(pt) => {
+                    builder.move_to(pt);
+                    last = pt;
+                }
+                PathEl::LineTo(pt) => {
+                    builder.line_to(pt);
+                    last = pt;
+                }
+                PathEl::QuadTo(p1, p2) => {
+                    let c1 = last + (p1 - last) * (2.0 / 3.0);
+                    let c2 = p2 + (p1 - p2) * (2.0 / 3.0);
+                    builder.curve_to(c1, c2, p2);
+                    last = p2;
+                }
+                PathEl::CurveTo(p1, p2, p3) => {
+                    builder.curve_to(p1, p2, p3);
+                    last = p3;
+                }
+                PathEl::ClosePath => (),
+            }
+        }
+        // without deltas the only possible error is a missing move, in which
+        // case the path has no contours worth drawing
+        builder.build().unwrap_or_default()
+    }
+
+    /// The compiled charstring data.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl BlendPoint {
+    /// Create a new point from its coordinates.
+    pub fn new(x: impl Into<BlendValue>, y: impl Into<BlendValue>) -> Self {
+        Self {
+            x: x.into(),
+            y: y.into(),
+        }
+    }
+
+    fn delta_from(&self, prev: &BlendPoint, region_count: u16) -> [BlendValue; 2] {
+        [
+            self.x.sub(&prev.x, region_count),
+            self.y.sub(&prev.y, region_count),
+        ]
+    }
+}
+
+impl From<Point> for BlendPoint {
+    fn from(value: Point) -> Self {
+        BlendPoint::new(value.x, value.y)
+    }
+}
+
+impl CharStringBuilder {
+    /// Create a new builder.
+    ///
+    /// `region_count` is the number of regions referenced by the
+    /// `ItemVariationData` that this charstring's deltas apply to; every
+    /// variable value must have exactly this many deltas.
+    pub fn new(region_count: u16) -> Self {
+        Self {
+            region_count,
+            vs_index: None,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Select the `ItemVariationData` used for this charstring.
+    ///
+    /// If this is not set, the charstring uses the index set in its private
+    /// dict (or zero.)
+    pub fn vs_index(&mut self, vs_index: u16) -> &mut Self {
+        self.vs_index = Some(vs_index);
+        self
+    }
+
+    /// Start a new contour at `pt`.
+    pub fn move_to(&mut self, pt: impl Into<BlendPoint>) -> &mut Self {
+        self.commands.push((RMOVETO, vec![pt.into()]));
+        self
+    }
+
+    /// Add a line to `pt`.
+    pub fn line_to(&mut self, pt: impl Into<BlendPoint>) -> &mut Self {
+        self.commands.push((RLINETO, vec![pt.into()]));
+        self
+    }
+
+    /// Add a cubic bezier curve ending at `pt`.
+    pub fn curve_to(
+        &mut self,
+        c1: impl Into<BlendPoint>,
+        c2: impl Into<BlendPoint>,
+        pt: impl Into<BlendPoint>,
+    ) -> &mut Self {
+        self.commands
+            .push((RRCURVETO, vec![c1.into(), c2.into(), pt.into()]));
+        self
+    }
+
+    /// Compile the charstring.
+    pub fn build(mut self) -> Result<CharString, CharStringError> {
+        if self.commands.first().is_some_and(|(op, _)| *op != RMOVETO) {
+            return Err(CharStringError::MissingMove);
+        }
+        if let Some(value) = self
+            .commands
+            .iter()
+            .flat_map(|(_, points)| points)
+            .flat_map(|pt| [&pt.x, &pt.y])
+            .find(|value| {
+                !value.deltas.is_empty() && value.deltas.len() != self.region_count as usize
+            })
+        {
+            return Err(CharStringError::DeltaCountMismatch {
+                expected: self.region_count,
+                actual: value.deltas.len(),
+            });
+        }
+
+        let mut data = Vec::new();
+        if let Some(vs_index) = self.vs_index {
+            encode_number(vs_index as f64, &mut data);
+            data.push(VSINDEX);
+        }
+        let mut prev = BlendPoint::default();
+        let mut pending_op = None;
+        let mut operands: Vec<BlendValue> = Vec::new();
+        for (op, points) in std::mem::take(&mut self.commands) {
+            let mut args = Vec::with_capacity(points.len() * 2);
+            for pt in points {
+                args.extend(pt.delta_from(&prev, self.region_count));
+                prev = pt;
+            }
+            // moves can't be combined, and the stack has a fixed size
+            let can_extend = pending_op == Some(op)
+                && op != RMOVETO
+                && self.stack_use(operands.iter().chain(&args)) <= MAX_STACK;
+            if !can_extend {
+                if let Some(op) = pending_op {
+                    self.encode_operator(op, &operands, &mut data);
+                }
+                operands.clear();
+            }
+            pending_op = Some(op);
+            operands.extend(args);
+        }
+        if let Some(op) = pending_op {
+            self.encode_operator(op, &operands, &mut data);
+        }
+        Ok(CharString(data))
+    }
+
+    /// The number of stack slots needed to push these operands.
+    fn stack_use<'a>(&self, operands: impl Iterator<Item = &'a BlendValue> + Clone) -> usize {
+        let count = operands.clone().count();
+        if operands.into_iter().any(BlendValue::is_variable) {
+            count * (self.region_count as usize + 1) + 1
+        } else {
+            count
+        }
+    }
+
+    fn encode_operator(&self, op: u8, operands: &[BlendValue], data: &mut Vec<u8>) {
+        for value in operands {
+            encode_number(value.default, data);
+        }
+        if operands.iter().any(BlendValue::is_variable) {
+            for value in operands {
+                for i in 0..self.region_count as usize {
+                    encode_number(value.deltas.get(i).copied().unwrap_or_default(), data);
+                }
+            }
+            encode_number(operands.len() as f64, data);
+            data.push(BLEND);
+        }
+        data.push(op);
+    }
+}
+
+/// Encode a charstring operand.
+///
+/// Integers are encoded in the most compact form available; other values
+/// are encoded as 16.16 fixed-point numbers.
+///
+/// See <https://learn.microsoft.com/en-us/typography/opentype/spec/cff2charstr#3-charstring-number-encoding>
+fn encode_number(value: f64, data: &mut Vec<u8>) {
+    if value.fract() == 0.0 && (i16::MIN as f64..=i16::MAX as f64).contains(&value) {
+        let value = value as i32;
+        match value {
+            -107..=107 => data.push((value + 139) as u8),
+            108..=1131 => {
+                let value = value - 108;
+                data.extend([((value >> 8) + 247) as u8, value as u8]);
+            }
+            -1131..=-108 => {
+                let value = -value - 108;
+                data.extend([((value >> 8) + 251) as u8, value as u8]);
+            }
+            _ => {
+                data.push(28);
+                data.extend((value as i16).to_be_bytes());
+            }
+        }
+    } else {
+        data.push(255);
+        data.extend(font_types::Fixed::from_f64(value).to_bits().to_be_bytes());
+    }
+}
+
+impl std::fmt::Display for CharStringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CharStringError::DeltaCountMismatch { expected, actual } => {
+                write!(f, "value has {actual} deltas, expected {expected}")
+            }
+            CharStringError::MissingMove => write!(f, "path does not start with a move"),
+        }
+    }
+}
+
+impl std::error::Error for CharStringError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_encoding() {
+        let encode = |value| {
+            let mut data = Vec::new();
+            encode_number(value, &mut data);
+            data
+        };
+        assert_eq!(encode(0.0), [139]);
+        assert_eq!(encode(-107.0), [32]);
+        assert_eq!(encode(108.0), [247, 0]);
+        assert_eq!(encode(1131.0), [250, 255]);
+        assert_eq!(encode(-1131.0), [254, 255]);
+        assert_eq!(encode(10000.0), [28, 0x27, 0x10]);
+        assert_eq!(encode(0.5), [255, 0, 0, 0x80, 0]);
+    }
+
+    #[test]
+    fn static_path() {
+        let mut path = BezPath::new();
+        path.move_to((10.0, 10.0));
+        path.line_to((20.0, 10.0));
+        path.line_to((20.0, 20.0));
+        path.close_path();
+        let charstring = CharString::from_bezpath(&path);
+        assert_eq!(
+            charstring.as_bytes(),
+            [
+                149, 149, RMOVETO, // move by (10, 10)
+                149, 139, 139, 149, RLINETO, // two lines share an operator
+            ]
+        );
+    }
+
+    #[test]
+    fn blended_operands() {
+        let mut builder = CharStringBuilder::new(1);
+        builder
+            .move_to(BlendPoint::new(BlendValue::new(10.0, vec![5.0]), 0.0))
+            .line_to(BlendPoint::new(20.0, 0.0));
+        let charstring = builder.build().unwrap();
+        assert_eq!(
+            charstring.as_bytes(),
+            [
+                149, 139, // defaults
+                144, 139, // deltas
+                141, BLEND, RMOVETO, // 2 blended values
+                149, 139, // defaults
+                134, 139, // (the x delta is relative to the previous point)
+                141, BLEND, RLINETO,
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_errors() {
+        let mut builder = CharStringBuilder::new(2);
+        builder.move_to(BlendPoint::new(BlendValue::new(10.0, vec![5.0]), 0.0));
+        assert_eq!(
+            builder.build(),
+            Err(CharStringError::DeltaCountMismatch {
+                expected: 2,
+                actual: 1
+            })
+        );
+        let mut builder = CharStringBuilder::new(0);
+        builder.line_to(Point::new(1.0, 1.0));
+        assert_eq!(builder.build(), Err(CharStringError::MissingMove));
+    }
+}
@@ -4,7 +4,10 @@
 
 include!("../../generated/generated_cmap.rs");
 
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    ops::RangeInclusive,
+};
 
 use crate::util::SearchRange;
 
@@ -15,6 +18,8 @@ const WINDOWS_FULL_REPERTOIRE_ENCODING: u16 = 10;
 // https://learn.microsoft.com/en-us/typography/opentype/spec/cmap#unicode-platform-platform-id--0
 const UNICODE_BMP_ENCODING: u16 = 3;
 const UNICODE_FULL_REPERTOIRE_ENCODING: u16 = 4;
+const UNICODE_VARIATION_SEQUENCE_ENCODING: u16 = 5;
+const UNICODE_MANY_TO_ONE_ENCODING: u16 = 6;
 
 impl CmapSubtable {
     /// Create a new format 4 subtable
@@ -130,6 +135,82 @@ impl CmapSubtable {
             seq_map_groups,
         )
     }
+
+    /// Create a new format 14 `CmapSubtable` from a list of
+    /// `(base char, variation selector, GlyphId)` triples.
+    ///
+    /// Sequences whose glyph matches the default mapping of the base char
+    /// are encoded in the Default UVS tables, all others in the Non-Default
+    /// UVS tables.
+    ///
+    /// Invariants:
+    ///
+    /// - Inputs must be sorted by selector and then by base char, and
+    ///   deduplicated.
+    /// - All `GlyphId`s must be 16-bit
+    fn create_format_14(
+        sequences: &[(char, char, GlyphId)],
+        default_glyph: impl Fn(char) -> Option<GlyphId>,
+    ) -> Self {
+        assert!(sequences.iter().all(|(_, _, g)| g.to_u32() <= 0xFFFF));
+        let mut records = Vec::new();
+        for group in sequences.chunk_by(|(_, vs1, _), (_, vs2, _)| vs1 == vs2) {
+            let selector = group[0].1;
+            let mut ranges: Vec<UnicodeRange> = Vec::new();
+            let mut mappings = Vec::new();
+            for (base, _, gid) in group {
+                let base = *base as u32;
+                if default_glyph(char::from_u32(base).unwrap()) != Some(*gid) {
+                    mappings.push(UvsMapping::new(
+                        Uint24::new(base),
+                        gid.to_u32().try_into().expect("checked before now"),
+                    ));
+                    continue;
+                }
+                match ranges.last_mut() {
+                    Some(range)
+                        if range.additional_count < u8::MAX
+                            && range.start_unicode_value.to_u32()
+                                + range.additional_count as u32
+                                + 1
+                                == base =>
+                    {
+                        range.additional_count += 1
+                    }
+                    _ => ranges.push(UnicodeRange::new(Uint24::new(base), 0)),
+                }
+            }
+            let default_uvs =
+                (!ranges.is_empty()).then(|| DefaultUvs::new(ranges.len() as u32, ranges));
+            let non_default_uvs =
+                (!mappings.is_empty()).then(|| NonDefaultUvs::new(mappings.len() as u32, mappings));
+            records.push(VariationSelector::new(
+                Uint24::new(selector as u32),
+                default_uvs,
+                non_default_uvs,
+            ));
+        }
+
+        // identical UVS tables are shared when the table is written, so they
+        // only contribute to the length once
+        let mut seen_default = HashSet::new();
+        let mut seen_non_default = HashSet::new();
+        let mut length = 10 + 11 * records.len();
+        for record in &records {
+            if let Some(uvs) = record.default_uvs.as_ref() {
+                if seen_default.insert(uvs) {
+                    length += 4 + 4 * uvs.ranges.len();
+                }
+            }
+            if let Some(uvs) = record.non_default_uvs.as_ref() {
+                if seen_non_default.insert(uvs) {
+                    length += 4 + 5 * uvs.uvs_mapping.len();
+                }
+            }
+        }
+        let length = length.try_into().unwrap();
+        CmapSubtable::format_14(length, records.len() as u32, records)
+    }
 }
 
 /// A conflicting Cmap definition, one char is mapped to multiple distinct GlyphIds.
@@ -139,6 +220,8 @@ impl CmapSubtable {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CmapConflict {
     ch: char,
+    /// The variation selector, if the conflict is in a variation sequence.
+    selector: Option<char>,
     gid1: GlyphId,
     gid2: GlyphId,
 }
@@ -146,16 +229,45 @@ pub struct CmapConflict {
 impl std::fmt::Display for CmapConflict {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let ch32 = self.ch as u32;
+        write!(f, "Cannot map {:?} (U+{ch32:04X}) ", self.ch)?;
+        if let Some(selector) = self.selector {
+            write!(f, "with selector U+{:04X} ", selector as u32)?;
+        }
         write!(
             f,
-            "Cannot map {:?} (U+{ch32:04X}) to two different glyph ids: {} and {}",
-            self.ch, self.gid1, self.gid2
+            "to two different glyph ids: {} and {}",
+            self.gid1, self.gid2
         )
     }
 }
 
 impl std::error::Error for CmapConflict {}
 
+/// Two overlapping ranges in a many-to-one cmap definition.
+///
+/// If there are multiple overlapping ranges, the first pair in codepoint
+/// order is reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CmapRangeOverlap {
+    range1: RangeInclusive<u32>,
+    range2: RangeInclusive<u32>,
+}
+
+impl std::fmt::Display for CmapRangeOverlap {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Cannot map overlapping ranges U+{:04X}..=U+{:04X} and U+{:04X}..=U+{:04X}",
+            self.range1.start(),
+            self.range1.end(),
+            self.range2.start(),
+            self.range2.end()
+        )
+    }
+}
+
+impl std::error::Error for CmapRangeOverlap {}
+
 impl Cmap {
     /// Generates a ['cmap'] that is expected to work in most modern environments.
     ///
@@ -183,7 +295,12 @@ impl Cmap {
                     (c1 == c2 && g1 != g2).then(|| (*c1, *g1.min(g2), *g1.max(g2)))
                 })
         {
-            return Err(CmapConflict { ch, gid1, gid2 });
+            return Err(CmapConflict {
+                ch,
+                selector: None,
+                gid1,
+                gid2,
+            });
         }
 
         let mut uni_records = Vec::new(); // platform 0
@@ -237,6 +354,99 @@ impl Cmap {
     }
 }
 
+impl Cmap {
+    /// Generates a [`cmap`] as in [`Cmap::from_mappings`], along with a
+    /// [format 14] subtable for the given Unicode variation sequences.
+    ///
+    /// Each sequence is a `(base char, variation selector, GlyphId)` triple.
+    /// Sequences that map to the same glyph as the base char alone are
+    /// encoded as default sequences. If no sequences are provided, this is
+    /// equivalent to [`Cmap::from_mappings`].
+    ///
+    /// The inputs are not required to be sorted.
+    ///
+    /// [`cmap`]: https://learn.microsoft.com/en-us/typography/opentype/spec/cmap
+    /// [format 14]: https://learn.microsoft.com/en-us/typography/opentype/spec/cmap#format-14-unicode-variation-sequences
+    pub fn from_mappings_and_variation_sequences(
+        mappings: impl IntoIterator<Item = (char, GlyphId)>,
+        sequences: impl IntoIterator<Item = (char, char, GlyphId)>,
+    ) -> Result<Cmap, CmapConflict> {
+        let mappings: Vec<_> = mappings.into_iter().collect();
+        let mut cmap = Cmap::from_mappings(mappings.iter().copied())?;
+
+        let mut sequences: Vec<_> = sequences
+            .into_iter()
+            .map(|(ch, selector, gid)| (selector, ch, gid))
+            .collect();
+        sequences.sort();
+        sequences.dedup();
+        if let Some(((selector, ch, gid1), (_, _, gid2))) = sequences
+            .iter()
+            .zip(sequences.iter().skip(1))
+            .find(|((vs1, c1, _), (vs2, c2, _))| vs1 == vs2 && c1 == c2)
+        {
+            return Err(CmapConflict {
+                ch: *ch,
+                selector: Some(*selector),
+                gid1: *gid1,
+                gid2: *gid2,
+            });
+        }
+        if sequences.is_empty() {
+            return Ok(cmap);
+        }
+
+        let sequences: Vec<_> = sequences
+            .into_iter()
+            .map(|(selector, ch, gid)| (ch, selector, gid))
+            .collect();
+        let defaults: HashMap<_, _> = mappings.into_iter().collect();
+        let subtable = CmapSubtable::create_format_14(&sequences, |ch| defaults.get(&ch).copied());
+        // the Unicode (0), variation sequences (5) record goes after the
+        // other Unicode platform records
+        let pos = cmap
+            .encoding_records
+            .iter()
+            .position(|record| record.platform_id != PlatformId::Unicode)
+            .unwrap_or(cmap.encoding_records.len());
+        cmap.encoding_records.insert(
+            pos,
+            EncodingRecord::new(
+                PlatformId::Unicode,
+                UNICODE_VARIATION_SEQUENCE_ENCODING,
+                subtable,
+            ),
+        );
+        Ok(cmap)
+    }
+}
+
+impl Cmap {
+    /// Generates a [`cmap`] that maps each range of codepoints to a single
+    /// glyph.
+    ///
+    /// This is intended for last resort and other fallback fonts, where
+    /// large ranges of characters share a glyph. The input is not required
+    /// to be sorted, but the ranges must not overlap. Empty ranges are
+    /// ignored.
+    ///
+    /// This emits a single [format 13] subtable with the Unicode platform
+    /// encoding reserved for that format.
+    ///
+    /// [`cmap`]: https://learn.microsoft.com/en-us/typography/opentype/spec/cmap
+    /// [format 13]: https://learn.microsoft.com/en-us/typography/opentype/spec/cmap#format-13-many-to-one-range-mappings
+    pub fn from_range_mappings(
+        ranges: impl IntoIterator<Item = (RangeInclusive<u32>, GlyphId)>,
+    ) -> Result<Cmap, CmapRangeOverlap> {
+        let subtable = Cmap13::from_ranges(ranges)?;
+        Ok(Cmap::new(vec![EncodingRecord::new(
+            PlatformId::Unicode,
+            UNICODE_MANY_TO_ONE_ENCODING,
+            subtable.into(),
+        )]))
+    }
+}
+
 // a helper for computing efficient segments for cmap format 4
 struct Format4SegmentComputer<'a> {
     mappings: &'a [(char, GlyphId)],
@@ -502,8 +712,69 @@ impl<'a> Format4SegmentComputer<'a> {
     }
 }
 
+/// A comparison between the segmentation of an existing format 4 subtable
+/// and the one produced by [`Cmap4::resegment`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cmap4SegmentationReport {
+    /// The number of segments in the existing subtable, including the
+    /// final `0xFFFF` segment.
+    pub seg_count: usize,
+    /// The number of segments after resegmentation.
+    pub optimized_seg_count: usize,
+    /// The length in bytes of the existing subtable.
+    pub length: usize,
+    /// The length in bytes of the subtable after resegmentation.
+    pub optimized_length: usize,
+}
+
+impl Cmap4SegmentationReport {
+    /// The number of bytes that would be saved by resegmenting.
+    pub fn excess_bytes(&self) -> usize {
+        self.length.saturating_sub(self.optimized_length)
+    }
+
+    /// Returns `true` if the existing subtable is at most `tolerance` bytes
+    /// larger than the resegmented one.
+    pub fn is_near_optimal(&self, tolerance: usize) -> bool {
+        self.excess_bytes() <= tolerance
+    }
+}
+
 impl Cmap4 {
-    fn compute_length(&self) -> u16 {
+    /// Rebuild an existing format 4 subtable using our segmentation.
+    ///
+    /// The resulting subtable has the same language and maps the same
+    /// characters to the same glyphs, but is generally smaller for subtables
+    /// produced by tools with less careful segmentation, or after subsetting.
+    ///
+    /// Mappings to glyph 0 and mappings for surrogate code points are dropped.
+    pub fn resegment(table: &read_fonts::tables::cmap::Cmap4) -> Self {
+        let mappings = table
+            .iter()
+            .filter_map(|(cp, gid)| Some((char::from_u32(cp)?, gid)))
+            .collect::<Vec<_>>();
+        let mut result = match CmapSubtable::create_format_4(&mappings) {
+            Some(CmapSubtable::Format4(cmap4)) => cmap4,
+            // nothing mapped, so all we need is the final segment
+            _ => Cmap4::new(0, vec![0xFFFF], vec![0xFFFF], vec![1], vec![0], vec![]),
+        };
+        result.language = table.language();
+        result
+    }
+
+    /// Compare the segmentation of an existing format 4 subtable with the
+    /// one produced by [`Cmap4::resegment`].
+    pub fn check_segmentation(table: &read_fonts::tables::cmap::Cmap4) -> Cmap4SegmentationReport {
+        let optimized = Self::resegment(table);
+        Cmap4SegmentationReport {
+            seg_count: table.seg_count_x2() as usize / 2,
+            optimized_seg_count: optimized.end_code.len(),
+            length: table.length() as usize,
+            optimized_length: optimized.byte_len(),
+        }
+    }
+
+    fn byte_len(&self) -> usize {
         // https://learn.microsoft.com/en-us/typography/opentype/spec/cmap#format-4-segment-mapping-to-delta-values
         // there are always 8 u16 fields
         const FIXED_SIZE: usize = 8 * u16::RAW_BYTE_LEN;
@@ -512,9 +783,11 @@ impl Cmap4 {
         let segment_len = self.end_code.len() * PER_SEGMENT_LEN;
         let gid_len = self.glyph_id_array.len() * u16::RAW_BYTE_LEN;
 
-        (FIXED_SIZE + segment_len + gid_len)
-            .try_into()
-            .expect("cmap4 overflow")
+        FIXED_SIZE + segment_len + gid_len
+    }
+
+    fn compute_length(&self) -> u16 {
+        self.byte_len().try_into().expect("cmap4 overflow")
     }
 
     fn compute_search_range(&self) -> u16 {
@@ -542,13 +815,79 @@ impl Cmap12 {
     }
 }
 
+impl Cmap13 {
+    /// Create a new format 13 subtable mapping each range of codepoints to
+    /// a single glyph.
+    ///
+    /// The input is not required to be sorted, but the ranges must not
+    /// overlap. Empty ranges are ignored and adjacent ranges that map to the
+    /// same glyph are merged.
+    pub fn from_ranges(
+        ranges: impl IntoIterator<Item = (RangeInclusive<u32>, GlyphId)>,
+    ) -> Result<Self, CmapRangeOverlap> {
+        let mut ranges = ranges
+            .into_iter()
+            .filter(|(range, _)| !range.is_empty())
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|(range, _)| (*range.start(), *range.end()));
+        let mut groups: Vec<ConstantMapGroup> = Vec::with_capacity(ranges.len());
+        for (i, (range, gid)) in ranges.iter().enumerate() {
+            if let Some(prev) = groups.last_mut() {
+                if *range.start() <= prev.end_char_code {
+                    return Err(CmapRangeOverlap {
+                        range1: ranges[i - 1].0.clone(),
+                        range2: range.clone(),
+                    });
+                }
+                if *range.start() == prev.end_char_code + 1 && gid.to_u32() == prev.glyph_id {
+                    prev.end_char_code = *range.end();
+                    continue;
+                }
+            }
+            groups.push(ConstantMapGroup::new(
+                *range.start(),
+                *range.end(),
+                gid.to_u32(),
+            ));
+        }
+        Ok(Cmap13::new(0, groups))
+    }
+
+    fn compute_length(&self) -> u32 {
+        // https://learn.microsoft.com/en-us/typography/opentype/spec/cmap#format-13-many-to-one-range-mappings
+        const FIXED_SIZE: usize = 2 * u16::RAW_BYTE_LEN + 3 * u32::RAW_BYTE_LEN;
+        const PER_SEGMENT_LEN: usize = 3 * u32::RAW_BYTE_LEN;
+
+        (FIXED_SIZE + PER_SEGMENT_LEN * self.groups.len())
+            .try_into()
+            .unwrap()
+    }
+
+    fn check_groups(&self, ctx: &mut ValidationCtx) {
+        if self
+            .groups
+            .iter()
+            .any(|group| group.start_char_code > group.end_char_code)
+        {
+            ctx.report("group start char code must not exceed end char code");
+        }
+        if self
+            .groups
+            .windows(2)
+            .any(|pair| pair[0].end_char_code >= pair[1].start_char_code)
+        {
+            ctx.report("groups must be sorted and must not overlap");
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::ops::RangeInclusive;
 
     use font_types::GlyphId;
     use read_fonts::{
-        tables::cmap::{Cmap, CmapSubtable, PlatformId},
+        tables::cmap::{Cmap, CmapSubtable, MapVariant, PlatformId},
         FontData, FontRead,
     };
 
@@ -556,7 +895,8 @@ mod tests {
         dump_table,
         tables::cmap::{
             self as write, CmapConflict, UNICODE_BMP_ENCODING, UNICODE_FULL_REPERTOIRE_ENCODING,
-            WINDOWS_BMP_ENCODING, WINDOWS_FULL_REPERTOIRE_ENCODING,
+            UNICODE_VARIATION_SEQUENCE_ENCODING, WINDOWS_BMP_ENCODING,
+            WINDOWS_FULL_REPERTOIRE_ENCODING,
         },
     };
 
@@ -805,7 +1145,15 @@ mod tests {
 
         let result = write::Cmap::from_mappings(mappings);
 
-        assert_eq!(result, Err(CmapConflict { ch, gid1, gid2 }))
+        assert_eq!(
+            result,
+            Err(CmapConflict {
+                ch,
+                selector: None,
+                gid1,
+                gid2
+            })
+        )
     }
 
     struct MappingBuilder {
@@ -957,6 +1305,134 @@ mod tests {
         assert!(mapping == read_mapping);
     }
 
+    #[test]
+    fn f4_resegment_split_segments() {
+        // map A-Z to contiguous gids, but with one segment per char
+        let chars = ('A'..='Z').map(|c| c as u32 as u16).collect::<Vec<_>>();
+        let n_chars = chars.len();
+        let end_code = chars.iter().copied().chain([0xFFFF]).collect::<Vec<_>>();
+        let id_delta = std::iter::repeat_n(-64i16, n_chars)
+            .chain([1])
+            .collect::<Vec<_>>();
+        let unoptimized = super::Cmap4::new(
+            0,
+            end_code.clone(),
+            end_code,
+            id_delta,
+            vec![0; n_chars + 1],
+            vec![],
+        );
+        let bytes = dump_table(&unoptimized).unwrap();
+        let table = read_fonts::tables::cmap::Cmap4::read(bytes.as_slice().into()).unwrap();
+
+        let report = super::Cmap4::check_segmentation(&table);
+        assert_eq!(report.seg_count, 27);
+        assert_eq!(report.optimized_seg_count, 2);
+        assert_eq!(report.length, 232);
+        assert_eq!(report.optimized_length, 32);
+        assert_eq!(report.excess_bytes(), 200);
+        assert!(!report.is_near_optimal(16));
+
+        let resegmented = super::Cmap4::resegment(&table);
+        assert_eq!(
+            get_read_mapping(&resegmented),
+            get_read_mapping(&unoptimized)
+        );
+        assert_eq!(resegmented.start_code, ['A' as u32 as u16, 0xFFFF]);
+    }
+
+    #[test]
+    fn f4_check_segmentation_of_generated() {
+        let mapping = MappingBuilder::default()
+            .extend('A'..='Z')
+            .extend(('a'..='z').rev())
+            .build();
+        let format4 = expect_f4(&mapping);
+        let bytes = dump_table(&format4).unwrap();
+        let table = read_fonts::tables::cmap::Cmap4::read(bytes.as_slice().into()).unwrap();
+        let report = super::Cmap4::check_segmentation(&table);
+        assert_eq!(report.length, bytes.len());
+        assert_eq!(report.excess_bytes(), 0);
+        assert!(report.is_near_optimal(0));
+    }
+
+    #[test]
+    fn f13_from_range_mappings() {
+        let cmap = write::Cmap::from_range_mappings([
+            (0x10000..=0x1FFFF, GlyphId::new(2)),
+            (0..=0x7F, GlyphId::new(1)),
+            (0x80..=0xFF, GlyphId::new(1)),
+            #[allow(clippy::reversed_empty_ranges)]
+            (0x200..=0x100, GlyphId::new(3)),
+        ])
+        .unwrap();
+        let bytes = dump_table(&cmap).unwrap();
+        let cmap = Cmap::read(FontData::new(&bytes)).unwrap();
+        let [record] = cmap.encoding_records() else {
+            panic!("expected a single encoding record");
+        };
+        assert_eq!(record.platform_id(), PlatformId::Unicode);
+        assert_eq!(record.encoding_id(), super::UNICODE_MANY_TO_ONE_ENCODING);
+        let CmapSubtable::Format13(subtable) = record.subtable(cmap.offset_data()).unwrap() else {
+            panic!("expected a format 13 subtable");
+        };
+        let groups = subtable
+            .groups()
+            .iter()
+            .map(|group| {
+                (
+                    group.start_char_code(),
+                    group.end_char_code(),
+                    group.glyph_id(),
+                )
+            })
+            .collect::<Vec<_>>();
+        // adjacent ranges with the same glyph are merged
+        assert_eq!(groups, [(0, 0xFF, 1), (0x10000, 0x1FFFF, 2)]);
+        assert_eq!(subtable.length() as usize, 16 + 2 * 12);
+        assert_eq!(cmap.map_codepoint(0x41u32), Some(GlyphId::new(1)));
+        assert_eq!(cmap.map_codepoint(0x12345u32), Some(GlyphId::new(2)));
+        assert_eq!(cmap.map_codepoint(0x150u32), None);
+    }
+
+    #[test]
+    fn f13_overlapping_ranges() {
+        let err = write::Cmap::from_range_mappings([
+            (0x100..=0x1FF, GlyphId::new(2)),
+            (0..=0x7F, GlyphId::new(1)),
+            (0x180..=0x2FF, GlyphId::new(3)),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot map overlapping ranges U+0100..=U+01FF and U+0180..=U+02FF"
+        );
+    }
+
+    #[test]
+    fn f13_validate_groups() {
+        let unsorted = write::Cmap13::new(
+            0,
+            vec![
+                write::ConstantMapGroup::new(0x100, 0x1FF, 1),
+                write::ConstantMapGroup::new(0, 0x7F, 2),
+            ],
+        );
+        assert!(dump_table(&unsorted).is_err());
+        let inverted = write::Cmap13::new(0, vec![write::ConstantMapGroup::new(0x100, 0x7F, 1)]);
+        assert!(dump_table(&inverted).is_err());
+    }
+
+    #[test]
+    fn f4_resegment_empty() {
+        let empty = super::Cmap4::new(3, vec![0xFFFF], vec![0xFFFF], vec![1], vec![0], vec![]);
+        let bytes = dump_table(&empty).unwrap();
+        let table = read_fonts::tables::cmap::Cmap4::read(bytes.as_slice().into()).unwrap();
+        let resegmented = super::Cmap4::resegment(&table);
+        assert_eq!(resegmented, empty);
+        assert!(super::Cmap4::check_segmentation(&table).is_near_optimal(0));
+    }
+
     #[test]
     // a small ordered segment between two larger unordered segments;
     // merging this correctly requires us to consider the next segment as well
@@ -987,4 +1463,83 @@ mod tests {
         let read_it_back = Cmap12::read(bytes.as_slice().into()).unwrap();
         assert_eq!(read_it_back.groups.len() as u32, more_than_16_bits);
     }
+
+    #[test]
+    fn variation_sequences() {
+        let mappings = [
+            ('\u{4E08}', GlyphId::new(1)),
+            ('\u{4E09}', GlyphId::new(2)),
+            ('\u{4E0A}', GlyphId::new(3)),
+            ('\u{20000}', GlyphId::new(4)),
+        ];
+        let vs1 = '\u{FE00}';
+        let vs17 = '\u{E0100}';
+        let sequences = [
+            ('\u{4E09}', vs17, GlyphId::new(2)),
+            ('\u{4E08}', vs17, GlyphId::new(1)),
+            ('\u{4E0A}', vs17, GlyphId::new(5)),
+            ('\u{20000}', vs1, GlyphId::new(6)),
+        ];
+        let cmap = write::Cmap::from_mappings_and_variation_sequences(mappings, sequences).unwrap();
+
+        let bytes = dump_table(&cmap).unwrap();
+        let cmap = Cmap::read(FontData::new(&bytes)).unwrap();
+        assert_eq!(
+            vec![
+                (PlatformId::Unicode, UNICODE_BMP_ENCODING),
+                (PlatformId::Unicode, UNICODE_FULL_REPERTOIRE_ENCODING),
+                (PlatformId::Unicode, UNICODE_VARIATION_SEQUENCE_ENCODING),
+                (PlatformId::Windows, WINDOWS_BMP_ENCODING),
+                (PlatformId::Windows, WINDOWS_FULL_REPERTOIRE_ENCODING),
+            ],
+            cmap.encoding_records()
+                .iter()
+                .map(|er| (er.platform_id(), er.encoding_id()))
+                .collect::<Vec<_>>()
+        );
+        let Ok(CmapSubtable::Format14(format14)) =
+            cmap.encoding_records()[2].subtable(cmap.offset_data())
+        else {
+            panic!("expected format 14 subtable");
+        };
+        assert_eq!(format14.length() as usize, format14.offset_data().len());
+        assert_eq!(
+            format14.map_variant('\u{4E08}', vs17),
+            Some(MapVariant::UseDefault)
+        );
+        assert_eq!(
+            format14.map_variant('\u{4E09}', vs17),
+            Some(MapVariant::UseDefault)
+        );
+        assert_eq!(
+            format14.map_variant('\u{4E0A}', vs17),
+            Some(MapVariant::Variant(GlyphId::new(5)))
+        );
+        assert_eq!(
+            format14.map_variant('\u{20000}', vs1),
+            Some(MapVariant::Variant(GlyphId::new(6)))
+        );
+        assert_eq!(format14.map_variant('\u{20000}', vs17), None);
+        let record = &format14.var_selector()[1];
+        let default_uvs = record.default_uvs(format14.offset_data()).unwrap().unwrap();
+        assert_eq!(default_uvs.ranges().len(), 1);
+    }
+
+    #[test]
+    fn conflicting_variation_sequences_fail() {
+        let vs1 = '\u{FE00}';
+        let result = write::Cmap::from_mappings_and_variation_sequences(
+            [('a', GlyphId::new(1))],
+            [('a', vs1, GlyphId::new(3)), ('a', vs1, GlyphId::new(2))],
+        );
+        assert_eq!(
+            result,
+            Err(CmapConflict {
+                ch: 'a',
+                selector: Some(vs1),
+                gid1: GlyphId::new(2),
+                gid2: GlyphId::new(3)
+            })
+        );
+    }
 }
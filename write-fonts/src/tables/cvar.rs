@@ -0,0 +1,257 @@
+//! The [cvar (CVT Variations)](https://learn.microsoft.com/en-us/typography/opentype/spec/cvar) table
+
+use read_fonts::TopLevelTable;
+use types::{FixedSize, MajorMinor, Tag};
+
+use crate::{
+    validate::{Validate, ValidationCtx},
+    FontWrite, TableWriter,
+};
+
+use super::variations::{
+    PackedDeltas, PackedPointNumbers, Tuple, TupleVariationCount, TupleVariationHeader,
+};
+
+/// The [cvar] table.
+///
+/// [cvar]: https://learn.microsoft.com/en-us/typography/opentype/spec/cvar
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cvar {
+    tuple_variation_headers: Vec<TupleVariationHeader>,
+    per_tuple_data: Vec<CvtTupleVariationData>,
+}
+
+/// Deltas for the control value table at one region of the design space.
+#[derive(Clone, Debug)]
+pub struct CvtDeltas {
+    peak_tuple: Tuple,
+    // start and end tuples of optional intermediate region
+    intermediate_region: Option<(Tuple, Tuple)>,
+    // one entry per CVT value, or None if the value is unchanged
+    deltas: Vec<Option<i16>>,
+}
+
+/// An error representing invalid input when building a cvar table
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CvarInputError {
+    /// Variations do not all have the same axis count
+    InconsistentAxisCount,
+    /// A variation contains an intermediate region with a different length
+    /// than the peak.
+    InconsistentTupleLengths,
+    /// There are more than 4095 variations
+    TooManyVariations(usize),
+}
+
+/// The serializable representation of a single tuple variation data
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct CvtTupleVariationData {
+    point_numbers: PackedPointNumbers,
+    deltas: PackedDeltas,
+}
+
+impl TopLevelTable for Cvar {
+    const TAG: Tag = Tag::new(b"cvar");
+}
+
+impl Cvar {
+    /// Construct a cvar table from a set of per-region CVT deltas.
+    pub fn new(variations: Vec<CvtDeltas>) -> Result<Self, CvarInputError> {
+        const MAX_TUPLE_VARIATIONS: usize = 4095;
+        if variations.len() > MAX_TUPLE_VARIATIONS {
+            return Err(CvarInputError::TooManyVariations(variations.len()));
+        }
+        let axis_count = variations.first().map(|var| var.peak_tuple.len());
+        let mut tuple_variation_headers = Vec::with_capacity(variations.len());
+        let mut per_tuple_data = Vec::with_capacity(variations.len());
+        for variation in variations {
+            if Some(variation.peak_tuple.len()) != axis_count {
+                return Err(CvarInputError::InconsistentAxisCount);
+            }
+            if let Some((start, end)) = &variation.intermediate_region {
+                if start.len() != variation.peak_tuple.len()
+                    || end.len() != variation.peak_tuple.len()
+                {
+                    return Err(CvarInputError::InconsistentTupleLengths);
+                }
+            }
+            let (header, data) = variation.build();
+            tuple_variation_headers.push(header);
+            per_tuple_data.push(data);
+        }
+        Ok(Self {
+            tuple_variation_headers,
+            per_tuple_data,
+        })
+    }
+
+    fn compute_data_offset(&self) -> u16 {
+        let header_len = self
+            .tuple_variation_headers
+            .iter()
+            .fold(0usize, |acc, header| {
+                acc.checked_add(header.compute_size() as usize).unwrap()
+            });
+        (MajorMinor::RAW_BYTE_LEN
+            + TupleVariationCount::RAW_BYTE_LEN
+            + u16::RAW_BYTE_LEN
+            + header_len)
+            .try_into()
+            .unwrap()
+    }
+}
+
+impl CvtDeltas {
+    /// Create a new set of deltas.
+    ///
+    /// The `deltas` contain one entry per value in the `cvt` table; a `None`
+    /// entry means the value is not modified in this region and is omitted
+    /// from the encoded data.
+    pub fn new(
+        peak_tuple: Tuple,
+        deltas: Vec<Option<i16>>,
+        intermediate_region: Option<(Tuple, Tuple)>,
+    ) -> Self {
+        Self {
+            peak_tuple,
+            intermediate_region,
+            deltas,
+        }
+    }
+
+    fn build(self) -> (TupleVariationHeader, CvtTupleVariationData) {
+        let point_numbers = if self.deltas.iter().all(Option::is_some) {
+            PackedPointNumbers::All
+        } else {
+            PackedPointNumbers::Some(
+                self.deltas
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, delta)| delta.map(|_| i as u16))
+                    .collect(),
+            )
+        };
+        let deltas = self.deltas.iter().flatten().map(|d| *d as i32).collect();
+        let data = CvtTupleVariationData {
+            point_numbers,
+            deltas: PackedDeltas::new(deltas),
+        };
+        // cvar has no shared tuples so every peak is embedded and, as we
+        // never emit shared point numbers, every tuple has private points
+        let header = TupleVariationHeader::new(
+            data.compute_size(),
+            None,
+            Some(self.peak_tuple),
+            self.intermediate_region,
+            true,
+        );
+        (header, data)
+    }
+}
+
+impl CvtTupleVariationData {
+    fn compute_size(&self) -> u16 {
+        self.point_numbers
+            .compute_size()
+            .checked_add(self.deltas.compute_size())
+            .unwrap()
+    }
+}
+
+impl FontWrite for CvtTupleVariationData {
+    fn write_into(&self, writer: &mut TableWriter) {
+        self.point_numbers.write_into(writer);
+        self.deltas.write_into(writer);
+    }
+}
+
+impl FontWrite for Cvar {
+    fn write_into(&self, writer: &mut TableWriter) {
+        MajorMinor::VERSION_1_0.write_into(writer);
+        TupleVariationCount::from_bits(self.tuple_variation_headers.len() as u16)
+            .write_into(writer);
+        self.compute_data_offset().write_into(writer);
+        self.tuple_variation_headers.write_into(writer);
+        self.per_tuple_data.write_into(writer);
+    }
+}
+
+impl Validate for Cvar {
+    fn validate_impl(&self, _ctx: &mut ValidationCtx) {}
+}
+
+impl std::fmt::Display for CvarInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CvarInputError::InconsistentAxisCount => {
+                write!(f, "variations have inconsistent axis counts")
+            }
+            CvarInputError::InconsistentTupleLengths => {
+                write!(f, "intermediate region tuples have unexpected length")
+            }
+            CvarInputError::TooManyVariations(count) => {
+                write!(f, "{count} variations exceeds the maximum of 4095")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CvarInputError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use read_fonts::{tables::cvar as read_cvar, FontData, FontRead};
+    use types::F2Dot14;
+
+    fn tuple(values: &[f32]) -> Tuple {
+        Tuple::new(values.iter().copied().map(F2Dot14::from_f32).collect())
+    }
+
+    #[test]
+    fn round_trip() {
+        let cvar = Cvar::new(vec![
+            CvtDeltas::new(tuple(&[1.0, 0.0]), vec![Some(10), None, Some(-200)], None),
+            CvtDeltas::new(
+                tuple(&[0.5, 0.5]),
+                vec![Some(4), Some(6), Some(8)],
+                Some((tuple(&[0.0, 0.0]), tuple(&[1.0, 1.0]))),
+            ),
+        ])
+        .unwrap();
+        let bytes = crate::dump_table(&cvar).unwrap();
+        let read = read_cvar::Cvar::read(FontData::new(&bytes)).unwrap();
+        assert_eq!(read.tuple_variation_count().count(), 2);
+        let mut deltas = [0i32; 3];
+        read.deltas(2, &[F2Dot14::ONE, F2Dot14::ZERO], &mut deltas)
+            .unwrap();
+        // deltas are returned in 16.16
+        assert_eq!(deltas.map(|d| d >> 16), [10, 0, -200]);
+        let mut deltas = [0i32; 3];
+        let half = F2Dot14::from_f32(0.5);
+        read.deltas(2, &[half, half], &mut deltas).unwrap();
+        // the first region contributes at half strength
+        assert_eq!(deltas.map(|d| d >> 16), [4 + 5, 6, 8 - 100]);
+    }
+
+    #[test]
+    fn inconsistent_input() {
+        assert_eq!(
+            Cvar::new(vec![
+                CvtDeltas::new(tuple(&[1.0]), vec![Some(1)], None),
+                CvtDeltas::new(tuple(&[1.0, 0.0]), vec![Some(1)], None),
+            ]),
+            Err(CvarInputError::InconsistentAxisCount)
+        );
+        assert_eq!(
+            Cvar::new(vec![CvtDeltas::new(
+                tuple(&[1.0]),
+                vec![Some(1)],
+                Some((tuple(&[0.0, 0.0]), tuple(&[1.0]))),
+            )]),
+            Err(CvarInputError::InconsistentTupleLengths)
+        );
+    }
+}
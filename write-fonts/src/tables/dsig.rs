@@ -0,0 +1,51 @@
+//! The [DSIG (Digital Signature Table)](https://docs.microsoft.com/en-us/typography/opentype/spec/dsig) table
+
+include!("../../generated/generated_dsig.rs");
+
+impl Dsig {
+    /// Construct an empty table without any signatures.
+    ///
+    /// Some legacy environments require the table to be present, even
+    /// though the signatures are never checked.
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if the table does not contain any signatures.
+    pub fn is_empty(&self) -> bool {
+        self.signature_records.is_empty()
+    }
+}
+
+impl SignatureRecord {
+    fn compute_length(&self) -> u32 {
+        // reserved1, reserved2 and signatureLength precede the signature
+        const HEADER_LEN: usize = u16::RAW_BYTE_LEN * 2 + u32::RAW_BYTE_LEN;
+        (HEADER_LEN + self.signature_block.signature.len()) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let bytes = crate::dump_table(&Dsig::empty()).unwrap();
+        assert_eq!(bytes, [0, 0, 0, 1, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let table = Dsig::new(
+            PermissionFlags::CANNOT_BE_RESIGNED,
+            vec![SignatureRecord::new(SignatureBlockFormat1::new(vec![
+                1, 2, 3,
+            ]))],
+        );
+        let bytes = crate::dump_table(&table).unwrap();
+        let read = read_fonts::tables::dsig::Dsig::read(bytes.as_slice().into()).unwrap();
+        assert_eq!(read.signature_records()[0].length(), 11);
+        assert_eq!(Dsig::read(bytes.as_slice().into()).unwrap(), table);
+    }
+}
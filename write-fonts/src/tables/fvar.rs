@@ -7,6 +7,193 @@ pub use instance_record::InstanceRecord;
 
 include!("../../generated/generated_fvar.rs");
 
+use super::name::{check_postscript_name, NameBuilder, NameBuilderError};
+use std::fmt::Display;
+
+/// The flag set on axes that should not be exposed in user interfaces.
+const HIDDEN_AXIS: u16 = 0x0001;
+
+/// A builder for the [`Fvar`] table.
+///
+/// Axes and instances are defined in user coordinates, with names given as
+/// strings. When the table is built the names are added to a
+/// [`NameBuilder`], and the resulting name ids are used in the table.
+#[derive(Clone, Debug, Default)]
+pub struct FvarBuilder {
+    axes: Vec<(VariationAxisRecord, String)>,
+    instances: Vec<InstanceDef>,
+}
+
+/// A named instance, before it is resolved against the axes.
+#[derive(Clone, Debug)]
+struct InstanceDef {
+    subfamily_name: String,
+    postscript_name: Option<String>,
+    location: Vec<(Tag, Fixed)>,
+}
+
+/// An error that occurs when building an [`Fvar`] table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FvarBuilderError {
+    /// An axis does not satisfy `min <= default <= max`.
+    InvalidAxisRange(Tag),
+    /// The same axis tag was added more than once.
+    DuplicateAxis(Tag),
+    /// An instance location references an axis that was not added.
+    UnknownAxis(Tag),
+    /// An instance coordinate is outside of the axis range.
+    CoordinateOutOfRange { tag: Tag, value: Fixed },
+    /// A name could not be added to the name table.
+    Name(NameBuilderError),
+}
+
+impl FvarBuilder {
+    /// Create a new, empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an axis with the given name and range, in user coordinates.
+    ///
+    /// Axes are written in the order they are added.
+    pub fn add_axis(
+        &mut self,
+        tag: Tag,
+        name: impl Into<String>,
+        min: f64,
+        default: f64,
+        max: f64,
+    ) -> Result<&mut Self, FvarBuilderError> {
+        if !(min <= default && default <= max) {
+            return Err(FvarBuilderError::InvalidAxisRange(tag));
+        }
+        if self.axes.iter().any(|(axis, _)| axis.axis_tag == tag) {
+            return Err(FvarBuilderError::DuplicateAxis(tag));
+        }
+        let axis = VariationAxisRecord {
+            axis_tag: tag,
+            min_value: Fixed::from_f64(min),
+            default_value: Fixed::from_f64(default),
+            max_value: Fixed::from_f64(max),
+            ..Default::default()
+        };
+        self.axes.push((axis, name.into()));
+        Ok(self)
+    }
+
+    /// Mark a previously added axis as hidden from user interfaces.
+    pub fn hide_axis(&mut self, tag: Tag) -> Result<&mut Self, FvarBuilderError> {
+        let (axis, _) = self
+            .axes
+            .iter_mut()
+            .find(|(axis, _)| axis.axis_tag == tag)
+            .ok_or(FvarBuilderError::UnknownAxis(tag))?;
+        axis.flags |= HIDDEN_AXIS;
+        Ok(self)
+    }
+
+    /// Add a named instance at a location in user coordinates.
+    ///
+    /// Axes that are not in `location` use their default value. If any
+    /// instance has a PostScript name, the PostScript name id is written for
+    /// every instance, with `0xFFFF` used for instances without one.
+    pub fn add_instance(
+        &mut self,
+        subfamily_name: impl Into<String>,
+        postscript_name: Option<&str>,
+        location: &[(Tag, f64)],
+    ) -> &mut Self {
+        self.instances.push(InstanceDef {
+            subfamily_name: subfamily_name.into(),
+            postscript_name: postscript_name.map(str::to_owned),
+            location: location
+                .iter()
+                .map(|(tag, value)| (*tag, Fixed::from_f64(*value)))
+                .collect(),
+        });
+        self
+    }
+
+    /// Build the table, adding the axis and instance names to `names`.
+    pub fn build(&self, names: &mut NameBuilder) -> Result<Fvar, FvarBuilderError> {
+        let mut axes = Vec::with_capacity(self.axes.len());
+        for (axis, name) in &self.axes {
+            let mut axis = axis.clone();
+            axis.axis_name_id = names.find_or_add_name(name)?;
+            axes.push(axis);
+        }
+        let has_postscript_names = self
+            .instances
+            .iter()
+            .any(|instance| instance.postscript_name.is_some());
+        let mut instances = Vec::with_capacity(self.instances.len());
+        for instance in &self.instances {
+            if let Some((tag, _)) = instance
+                .location
+                .iter()
+                .find(|(tag, _)| !axes.iter().any(|axis| axis.axis_tag == *tag))
+            {
+                return Err(FvarBuilderError::UnknownAxis(*tag));
+            }
+            let coordinates = axes
+                .iter()
+                .map(|axis| {
+                    let value = instance
+                        .location
+                        .iter()
+                        .find_map(|(tag, value)| (*tag == axis.axis_tag).then_some(*value))
+                        .unwrap_or(axis.default_value);
+                    if value < axis.min_value || value > axis.max_value {
+                        return Err(FvarBuilderError::CoordinateOutOfRange {
+                            tag: axis.axis_tag,
+                            value,
+                        });
+                    }
+                    Ok(value)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let post_script_name_id = match &instance.postscript_name {
+                Some(name) => {
+                    check_postscript_name(name)?;
+                    Some(names.find_or_add_name(name)?)
+                }
+                None => has_postscript_names.then_some(NameId::new(0xFFFF)),
+            };
+            instances.push(InstanceRecord {
+                subfamily_name_id: names.find_or_add_name(&instance.subfamily_name)?,
+                coordinates,
+                post_script_name_id,
+                ..Default::default()
+            });
+        }
+        Ok(Fvar::new(AxisInstanceArrays::new(axes, instances)))
+    }
+}
+
+impl From<NameBuilderError> for FvarBuilderError {
+    fn from(value: NameBuilderError) -> Self {
+        FvarBuilderError::Name(value)
+    }
+}
+
+impl Display for FvarBuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FvarBuilderError::InvalidAxisRange(tag) => {
+                write!(f, "axis '{tag}' must satisfy min <= default <= max")
+            }
+            FvarBuilderError::DuplicateAxis(tag) => write!(f, "axis '{tag}' was added twice"),
+            FvarBuilderError::UnknownAxis(tag) => write!(f, "unknown axis '{tag}'"),
+            FvarBuilderError::CoordinateOutOfRange { tag, value } => {
+                write!(f, "coordinate {value} is outside the range of axis '{tag}'")
+            }
+            FvarBuilderError::Name(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for FvarBuilderError {}
+
 impl Fvar {
     /// We need everyone to have, or not have, post_script name so we can have a single record size.
     fn check_instances(&self, ctx: &mut ValidationCtx) {
@@ -227,4 +414,74 @@ mod tests {
             .push(nameless_instance_record(coordinates));
         assert!(fvar.validate().is_err());
     }
+
+    #[test]
+    fn builder_adds_names() {
+        let mut names = NameBuilder::new();
+        let mut builder = FvarBuilder::new();
+        builder
+            .add_axis(Tag::new(b"wght"), "Weight", 100.0, 400.0, 900.0)
+            .unwrap()
+            .add_axis(Tag::new(b"wdth"), "Width", 75.0, 100.0, 100.0)
+            .unwrap()
+            .hide_axis(Tag::new(b"wdth"))
+            .unwrap();
+        builder
+            .add_instance("Bold", Some("Test-Bold"), &[(Tag::new(b"wght"), 700.0)])
+            .add_instance("Condensed", None, &[(Tag::new(b"wdth"), 75.0)]);
+        let fvar = builder.build(&mut names).unwrap();
+
+        let bytes = crate::dump_table(&fvar).unwrap();
+        let loaded = read_fonts::tables::fvar::Fvar::read(FontData::new(&bytes)).unwrap();
+        let arrays = loaded.axis_instance_arrays().unwrap();
+        let axes = arrays.axes();
+        assert_eq!(axes[0].axis_name_id(), NameId::new(256));
+        assert_eq!(axes[1].axis_name_id(), NameId::new(257));
+        assert_eq!(axes[1].flags(), HIDDEN_AXIS);
+
+        let bold = arrays.instances().get(0).unwrap();
+        assert_eq!(bold.subfamily_name_id, NameId::new(259));
+        assert_eq!(bold.post_script_name_id, Some(NameId::new(258)));
+        assert_eq!(
+            bold.coordinates.iter().map(|v| v.get()).collect::<Vec<_>>(),
+            [Fixed::from_i32(700), Fixed::from_i32(100)]
+        );
+        // every instance has a PostScript name id, even if it is 0xFFFF
+        assert_eq!(loaded.instance_size(), 2 * 4 + 6);
+
+        let name = names.build();
+        let name_string = |id: u16| {
+            name.name_record
+                .iter()
+                .find(|rec| rec.name_id == NameId::new(id))
+                .map(|rec| rec.string.as_str().to_owned())
+        };
+        assert_eq!(name_string(256).as_deref(), Some("Weight"));
+        assert_eq!(name_string(260).as_deref(), Some("Condensed"));
+    }
+
+    #[test]
+    fn builder_errors() {
+        let wght = Tag::new(b"wght");
+        let mut builder = FvarBuilder::new();
+        assert_eq!(
+            builder.add_axis(wght, "Weight", 400.0, 100.0, 900.0).err(),
+            Some(FvarBuilderError::InvalidAxisRange(wght))
+        );
+        builder
+            .add_axis(wght, "Weight", 100.0, 400.0, 900.0)
+            .unwrap();
+        assert_eq!(
+            builder.add_axis(wght, "Weight", 100.0, 400.0, 900.0).err(),
+            Some(FvarBuilderError::DuplicateAxis(wght))
+        );
+        builder.add_instance("Black", None, &[(wght, 1000.0)]);
+        assert_eq!(
+            builder.build(&mut NameBuilder::new()).err(),
+            Some(FvarBuilderError::CoordinateOutOfRange {
+                tag: wght,
+                value: Fixed::from_i32(1000)
+            })
+        );
+    }
 }
@@ -0,0 +1,88 @@
+//! The [gasp](https://learn.microsoft.com/en-us/typography/opentype/spec/gasp) table
+
+include!("../../generated/generated_gasp.rs");
+
+impl Gasp {
+    /// Returns the desired rasterizer behavior for the given size in pixels
+    /// per em.
+    ///
+    /// Returns `None` if no range covers the given size.
+    pub fn behavior(&self, ppem: u16) -> Option<GaspRangeBehavior> {
+        self.gasp_ranges
+            .iter()
+            .find(|range| ppem <= range.range_max_ppem)
+            .map(|range| range.range_gasp_behavior)
+    }
+
+    fn check_ranges(&self, ctx: &mut ValidationCtx) {
+        if self.version > 1 {
+            ctx.report("version must be 0 or 1");
+        }
+        if self
+            .gasp_ranges
+            .windows(2)
+            .any(|pair| pair[0].range_max_ppem >= pair[1].range_max_ppem)
+        {
+            ctx.report("ranges must be sorted by ppem");
+        }
+        if self
+            .gasp_ranges
+            .last()
+            .is_some_and(|range| range.range_max_ppem != u16::MAX)
+        {
+            ctx.report("last range must have a max ppem of 0xFFFF");
+        }
+        let symmetric =
+            GaspRangeBehavior::GASP_SYMMETRIC_GRIDFIT | GaspRangeBehavior::GASP_SYMMETRIC_SMOOTHING;
+        if self.version == 0
+            && self
+                .gasp_ranges
+                .iter()
+                .any(|range| range.range_gasp_behavior.intersects(symmetric))
+        {
+            ctx.report("symmetric flags require version 1");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let table = Gasp::new(
+            1,
+            vec![
+                GaspRange::new(8, GaspRangeBehavior::GASP_DOGRAY),
+                GaspRange::new(u16::MAX, GaspRangeBehavior::all()),
+            ],
+        );
+        let bytes = crate::dump_table(&table).unwrap();
+        let read = read_fonts::tables::gasp::Gasp::read(bytes.as_slice().into()).unwrap();
+        assert_eq!(read.num_ranges(), 2);
+        assert!(read.behavior(9).unwrap().symmetric_smoothing());
+        assert_eq!(Gasp::read(bytes.as_slice().into()).unwrap(), table);
+        assert_eq!(table.behavior(4), Some(GaspRangeBehavior::GASP_DOGRAY));
+    }
+
+    #[test]
+    fn validation() {
+        let unsorted = Gasp::new(
+            1,
+            vec![
+                GaspRange::new(16, GaspRangeBehavior::GASP_GRIDFIT),
+                GaspRange::new(8, GaspRangeBehavior::GASP_DOGRAY),
+            ],
+        );
+        assert!(crate::dump_table(&unsorted).is_err());
+        let symmetric_v0 = Gasp::new(
+            0,
+            vec![GaspRange::new(
+                u16::MAX,
+                GaspRangeBehavior::GASP_SYMMETRIC_SMOOTHING,
+            )],
+        );
+        assert!(crate::dump_table(&symmetric_v0).is_err());
+    }
+}
@@ -16,7 +16,7 @@ mod simple;
 
 pub use composite::{Anchor, Component, ComponentFlags, CompositeGlyph, Transform};
 pub use glyf_loca_builder::{GlyfLocaBuilder, SomeGlyph};
-pub use simple::{Contour, MalformedPath, SimpleGlyph};
+pub use simple::{Contour, CubicConversion, MalformedPath, SimpleGlyph};
 
 /// The [glyf (Glyph Data)](https://docs.microsoft.com/en-us/typography/opentype/spec/glyf) table
 ///
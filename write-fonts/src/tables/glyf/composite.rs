@@ -5,6 +5,7 @@ use crate::{
     FontWrite,
 };
 
+use kurbo::{Affine, Rect, Vec2};
 use read_fonts::{tables::glyf::CompositeGlyphFlags, types::GlyphId16, FontRead};
 
 use super::Bbox;
@@ -173,7 +174,7 @@ impl CompositeGlyph {
 
         for (component, bbox) in source {
             components.push(component);
-            union_box.get_or_insert(bbox).union(bbox);
+            union_box = Some(union_box.map_or(bbox, |union| union.union(bbox)));
         }
 
         if components.is_empty() {
@@ -190,6 +191,84 @@ impl CompositeGlyph {
     pub fn components(&self) -> &[Component] {
         &self.components
     }
+
+    /// Returns `true` if any component is marked as overlapping.
+    pub fn has_overlaps(&self) -> bool {
+        self.components.iter().any(|c| c.flags.overlap_compound)
+    }
+
+    /// Mark whether the components of this glyph overlap.
+    ///
+    /// The spec requires the `OVERLAP_COMPOUND` flag to be set only on the
+    /// first component; this sets it there and clears it everywhere else.
+    pub fn set_overlaps(&mut self, overlap: bool) {
+        for (i, component) in self.components.iter_mut().enumerate() {
+            component.flags.overlap_compound = overlap && i == 0;
+        }
+    }
+
+    /// The components of this glyph, for modification in place.
+    ///
+    /// The bounding box is not updated; see
+    /// [`recompute_bounding_box`](Self::recompute_bounding_box).
+    pub fn components_mut(&mut self) -> &mut [Component] {
+        &mut self.components
+    }
+
+    /// Recompute the glyph's bounding box from the bounds of its components.
+    ///
+    /// `glyph_bounds` contains the bounding box of each glyph in the font,
+    /// indexed by glyph id, or `None` for empty glyphs. The bounds of each
+    /// component are transformed before being combined, which is exact for
+    /// scales and translations and conservative for rotations and skews.
+    ///
+    /// The existing bounding box is kept and `false` is returned if any
+    /// component refers to a glyph outside of `glyph_bounds`, or is
+    /// positioned by matching points, or if all components are empty.
+    pub fn recompute_bounding_box(&mut self, glyph_bounds: &[Option<Bbox>]) -> bool {
+        let mut union_box: Option<Bbox> = None;
+        for component in &self.components {
+            let Some(bounds) = glyph_bounds.get(component.glyph.to_u16() as usize) else {
+                return false;
+            };
+            let Anchor::Offset { x, y } = component.anchor else {
+                return false;
+            };
+            let Some(bounds) = bounds else {
+                continue;
+            };
+            let Transform { xx, yx, xy, yy } = component.transform;
+            let transform = Affine::new([
+                xx.to_f32() as f64,
+                yx.to_f32() as f64,
+                xy.to_f32() as f64,
+                yy.to_f32() as f64,
+                0.0,
+                0.0,
+            ]);
+            let offset = Vec2::new(x as f64, y as f64);
+            let offset = if component.flags.scaled_component_offset {
+                (transform * offset.to_point()).to_vec2()
+            } else {
+                offset
+            };
+            let rect = Rect::new(
+                bounds.x_min as f64,
+                bounds.y_min as f64,
+                bounds.x_max as f64,
+                bounds.y_max as f64,
+            );
+            let bbox = Bbox::from(transform.then_translate(offset).transform_rect_bbox(rect));
+            union_box = Some(union_box.map_or(bbox, |union| union.union(bbox)));
+        }
+        match union_box {
+            Some(bbox) => {
+                self.bbox = bbox;
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 impl FontWrite for CompositeGlyph {
@@ -343,4 +422,71 @@ mod tests {
 
         assert_eq!(orig.offset_data().as_ref(), bytes);
     }
+
+    #[test]
+    fn overlap_flag_only_on_first_component() {
+        let make_component = |gid, overlap_compound| {
+            Component::new(
+                GlyphId16::new(gid),
+                Anchor::Offset { x: 0, y: 0 },
+                Transform::default(),
+                ComponentFlags {
+                    overlap_compound,
+                    ..Default::default()
+                },
+            )
+        };
+        let mut composite = CompositeGlyph::new(make_component(1, false), Bbox::default());
+        composite.add_component(make_component(2, true), Bbox::default());
+        composite.add_component(make_component(3, false), Bbox::default());
+        assert!(composite.has_overlaps());
+        composite.set_overlaps(true);
+
+        let bytes = crate::dump_table(&composite).unwrap();
+        let read = read_glyf::CompositeGlyph::read(FontData::new(&bytes)).unwrap();
+        let flags = read.components().map(|c| c.flags).collect::<Vec<_>>();
+        assert_eq!(flags.len(), 3);
+        assert!(flags[0].contains(CompositeGlyphFlags::OVERLAP_COMPOUND));
+        assert!(flags[0].contains(CompositeGlyphFlags::ARGS_ARE_XY_VALUES));
+        assert!(!flags[1].contains(CompositeGlyphFlags::OVERLAP_COMPOUND));
+        assert!(!flags[2].contains(CompositeGlyphFlags::OVERLAP_COMPOUND));
+    }
+
+    #[test]
+    fn recompute_bounding_box() {
+        let half = font_types::F2Dot14::from_f32(0.5);
+        let component = Component::new(
+            GlyphId16::new(1),
+            Anchor::Offset { x: 10, y: 20 },
+            Transform {
+                xx: half,
+                yy: half,
+                ..Default::default()
+            },
+            ComponentFlags::default(),
+        );
+        let mut composite = CompositeGlyph::new(component, Bbox::default());
+        let glyph_bounds = [
+            None,
+            Some(Bbox {
+                x_min: 5,
+                y_min: 5,
+                x_max: 100,
+                y_max: 100,
+            }),
+        ];
+        assert!(composite.recompute_bounding_box(&glyph_bounds));
+        assert_eq!(
+            composite.bbox,
+            Bbox {
+                x_min: 13,
+                y_min: 23,
+                x_max: 60,
+                y_max: 70,
+            }
+        );
+        // unknown glyphs keep the existing box
+        assert!(!composite.recompute_bounding_box(&glyph_bounds[..1]));
+        assert_eq!(composite.bbox.x_min, 13);
+    }
 }
@@ -2,12 +2,15 @@
 
 use crate::{
     error::Error,
-    tables::loca::{Loca, LocaFormat},
+    tables::{
+        head::Head,
+        loca::{Loca, LocaFormat},
+    },
     validate::Validate,
     FontWrite, TableWriter,
 };
 
-use super::{CompositeGlyph, Glyf, Glyph, SimpleGlyph};
+use super::{Bbox, CompositeGlyph, Glyf, Glyph, SimpleGlyph};
 
 /// A builder for constructing the 'glyf' & 'loca' tables.
 ///
@@ -36,12 +39,23 @@ use super::{CompositeGlyph, Glyf, Glyph, SimpleGlyph};
 pub struct GlyfLocaBuilder {
     glyph_writer: TableWriter,
     raw_loca: Vec<u32>,
+    glyph_bounds: Vec<Option<Bbox>>,
+    trust_bounds: bool,
 }
 
 /// A trait encompassing [`Glyph`], [`SimpleGlyph`] and [`CompositeGlyph`]
 ///
-/// This is a marker trait to ensure that only glyphs are passed to [`GlyfLocaBuilder`].
-pub trait SomeGlyph: Validate + FontWrite {}
+/// This trait ensures that only glyphs are passed to [`GlyfLocaBuilder`].
+pub trait SomeGlyph: Validate + FontWrite + Clone {
+    /// The bounding box of the glyph, or `None` if it is empty.
+    fn bounds(&self) -> Option<Bbox>;
+
+    /// Recompute the bounding box of the glyph.
+    ///
+    /// `glyph_bounds` contains the bounding boxes of the preceding glyphs,
+    /// indexed by glyph id, and is used to compute the bounds of composites.
+    fn recompute_bounds(&mut self, glyph_bounds: &[Option<Bbox>]);
+}
 
 impl GlyfLocaBuilder {
     /// Construct a new builder for the 'glyf' and 'loca' tables.
@@ -49,9 +63,23 @@ impl GlyfLocaBuilder {
         Self {
             glyph_writer: TableWriter::default(),
             raw_loca: vec![0],
+            glyph_bounds: Vec::new(),
+            trust_bounds: false,
         }
     }
 
+    /// Set whether the bounding boxes of added glyphs should be used as is.
+    ///
+    /// By default, the bounding box of each glyph is recomputed from its
+    /// points (for simple glyphs) or from the bounds of its components (for
+    /// composite glyphs) when it is added. Composites are only recomputed
+    /// when all of their components have already been added and are
+    /// positioned by offset; otherwise the provided bounding box is used.
+    pub fn trust_provided_bounds(&mut self, trust: bool) -> &mut Self {
+        self.trust_bounds = trust;
+        self
+    }
+
     /// Add a glyph to the table.
     ///
     /// The argument can be any of [`Glyph`], [`SimpleGlyph`] or [`CompositeGlyph`].
@@ -60,12 +88,35 @@ impl GlyfLocaBuilder {
     /// associate any errors with a particular glyph.
     pub fn add_glyph(&mut self, glyph: &impl SomeGlyph) -> Result<&mut Self, Error> {
         glyph.validate()?;
-        glyph.write_into(&mut self.glyph_writer);
+        let bounds = if self.trust_bounds {
+            glyph.write_into(&mut self.glyph_writer);
+            glyph.bounds()
+        } else {
+            let mut glyph = glyph.clone();
+            glyph.recompute_bounds(&self.glyph_bounds);
+            glyph.write_into(&mut self.glyph_writer);
+            glyph.bounds()
+        };
+        self.glyph_bounds.push(bounds);
         let pos = self.glyph_writer.current_data().bytes.len();
         self.raw_loca.push(pos as u32);
         Ok(self)
     }
 
+    /// Returns the union of the bounding boxes of all glyphs added so far.
+    ///
+    /// This is the font-wide bounding box that is stored in the ['head']
+    /// table, or `None` if all glyphs are empty.
+    ///
+    /// [`head`]: crate::tables::head::Head::set_bounds
+    pub fn bounds(&self) -> Option<Bbox> {
+        self.glyph_bounds
+            .iter()
+            .flatten()
+            .copied()
+            .reduce(Bbox::union)
+    }
+
     /// Construct the final glyf and loca tables.
     ///
     /// This method also returns the loca format; the caller is responsible for
@@ -79,13 +130,57 @@ impl GlyfLocaBuilder {
         let format = loca.format();
         (Glyf(glyph_data), loca, format)
     }
+
+    /// Construct the final glyf and loca tables, updating the [`head`] table.
+    ///
+    /// The font-wide bounding box and `indexToLocFormat` of `head` are set
+    /// to match the compiled glyphs, so that the three tables stay consistent.
+    ///
+    /// [`head`]: crate::tables::head::Head
+    #[must_use]
+    pub fn build_with_head(self, head: &mut Head) -> (Glyf, Loca) {
+        if let Some(bbox) = self.bounds() {
+            head.set_bounds(bbox);
+        }
+        let (glyf, loca, format) = self.build();
+        head.set_loca_format(format);
+        (glyf, loca)
+    }
+}
+
+impl SomeGlyph for SimpleGlyph {
+    fn bounds(&self) -> Option<Bbox> {
+        (!self.contours.is_empty()).then_some(self.bbox)
+    }
+
+    fn recompute_bounds(&mut self, _glyph_bounds: &[Option<Bbox>]) {
+        self.recompute_bounding_box();
+    }
 }
 
-impl SomeGlyph for SimpleGlyph {}
+impl SomeGlyph for CompositeGlyph {
+    fn bounds(&self) -> Option<Bbox> {
+        Some(self.bbox)
+    }
 
-impl SomeGlyph for CompositeGlyph {}
+    fn recompute_bounds(&mut self, glyph_bounds: &[Option<Bbox>]) {
+        self.recompute_bounding_box(glyph_bounds);
+    }
+}
 
-impl SomeGlyph for Glyph {}
+impl SomeGlyph for Glyph {
+    fn bounds(&self) -> Option<Bbox> {
+        self.bbox()
+    }
+
+    fn recompute_bounds(&mut self, glyph_bounds: &[Option<Bbox>]) {
+        match self {
+            Glyph::Empty => (),
+            Glyph::Simple(glyph) => glyph.recompute_bounds(glyph_bounds),
+            Glyph::Composite(glyph) => glyph.recompute_bounds(glyph_bounds),
+        }
+    }
+}
 
 impl Default for GlyfLocaBuilder {
     fn default() -> Self {
@@ -97,7 +192,7 @@ impl Default for GlyfLocaBuilder {
 mod tests {
     use super::super::{Anchor, Component, ComponentFlags, Transform};
     use crate::from_obj::FromTableRef;
-    use font_types::GlyphId;
+    use font_types::{F2Dot14, GlyphId, GlyphId16};
     use kurbo::{BezPath, Shape};
     use read_fonts::FontRead;
 
@@ -176,4 +271,121 @@ mod tests {
         assert_eq!(rglyph2, glyph2.into());
         assert_eq!(rglyph3, glyph3.into());
     }
+
+    #[test]
+    fn recompute_bounds() {
+        let square = kurbo::Rect::from_points((5., 5.), (100., 100.)).into_path(0.1);
+        let mut glyph1 = SimpleGlyph::from_bezpath(&square).unwrap();
+        let expected = glyph1.bbox;
+        glyph1.bbox = Bbox::default();
+        let gid1 = GlyphId16::new(1);
+        // scaled by 0.5 and offset by (10, 20)
+        let half = F2Dot14::from_f32(0.5);
+        let transform = Transform {
+            xx: half,
+            yy: half,
+            ..Default::default()
+        };
+        let composite = CompositeGlyph::try_from_iter([
+            (
+                Component::new(
+                    gid1,
+                    Anchor::Offset { x: 10, y: 20 },
+                    transform,
+                    ComponentFlags::default(),
+                ),
+                Bbox::default(),
+            ),
+            (
+                Component::new(
+                    gid1,
+                    Anchor::Offset { x: 0, y: 0 },
+                    Transform::default(),
+                    ComponentFlags::default(),
+                ),
+                Bbox::default(),
+            ),
+        ])
+        .unwrap();
+        let glyphs = [Glyph::Empty, glyph1.into(), composite.into()];
+
+        let mut builder = GlyfLocaBuilder::new();
+        for glyph in &glyphs {
+            builder.add_glyph(glyph).unwrap();
+        }
+        assert_eq!(builder.bounds(), Some(expected));
+        let (glyf, loca, format) = builder.build();
+        let rglyf = read_fonts::tables::glyf::Glyf::read(glyf.0.as_slice().into()).unwrap();
+        let loca_bytes = crate::dump_table(&loca).unwrap();
+        let rloca = read_fonts::tables::loca::Loca::read(
+            loca_bytes.as_slice().into(),
+            format == LocaFormat::Long,
+        )
+        .unwrap();
+        let read_bbox = |gid| {
+            Glyph::from_table_ref(&rloca.get_glyf(GlyphId::new(gid), &rglyf).unwrap().unwrap())
+                .bbox()
+                .unwrap()
+        };
+        assert_eq!(read_bbox(1), expected);
+        assert_eq!(
+            read_bbox(2),
+            Bbox {
+                x_min: 5,
+                y_min: 5,
+                x_max: 100,
+                y_max: 100,
+            }
+        );
+
+        // trusted bounds are written as is
+        let mut builder = GlyfLocaBuilder::new();
+        builder.trust_provided_bounds(true);
+        for glyph in &glyphs {
+            builder.add_glyph(glyph).unwrap();
+        }
+        assert_eq!(builder.bounds(), Some(Bbox::default()));
+    }
+
+    #[test]
+    fn build_updates_head() {
+        let square = kurbo::Rect::from_points((5., 5.), (100., 100.)).into_path(0.1);
+        let glyph = SimpleGlyph::from_bezpath(&square).unwrap();
+        let mut builder = GlyfLocaBuilder::new();
+        builder.add_glyph(&Glyph::Empty).unwrap();
+        // enough data to require long offsets
+        for _ in 0..0x2000 {
+            builder.add_glyph(&glyph).unwrap();
+        }
+        let mut head = Head::default();
+        let (_glyf, loca) = builder.build_with_head(&mut head);
+        assert_eq!(loca.format(), LocaFormat::Long);
+        assert_eq!(head.index_to_loc_format, LocaFormat::Long as i16);
+        assert_eq!(
+            (head.x_min, head.y_min, head.x_max, head.y_max),
+            (5, 5, 100, 100)
+        );
+    }
+
+    #[test]
+    fn composite_with_unknown_component_keeps_bounds() {
+        let provided = Bbox {
+            x_min: 1,
+            y_min: 2,
+            x_max: 3,
+            y_max: 4,
+        };
+        let composite = CompositeGlyph::new(
+            Component::new(
+                GlyphId16::new(5),
+                Anchor::Offset { x: 0, y: 0 },
+                Transform::default(),
+                ComponentFlags::default(),
+            ),
+            provided,
+        );
+        let mut builder = GlyfLocaBuilder::new();
+        builder.add_glyph(&composite).unwrap();
+        assert_eq!(builder.bounds(), Some(provided));
+    }
 }
@@ -6,7 +6,7 @@ use crate::{
     FontWrite, OtRound,
 };
 
-use kurbo::BezPath;
+use kurbo::{BezPath, CubicBez, ParamCurve, ParamCurveNearest, PathEl, Point, QuadBez};
 use read_fonts::{
     tables::glyf::{CurvePoint, SimpleGlyphFlags},
     FontRead,
@@ -20,12 +20,25 @@ pub struct SimpleGlyph {
     pub bbox: Bbox,
     pub contours: Vec<Contour>,
     pub instructions: Vec<u8>,
+    overlap: bool,
 }
 
 /// A single contour, comprising only line and quadratic bezier segments
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Contour(Vec<CurvePoint>);
 
+/// A simple glyph converted from a path with cubic segments.
+///
+/// See [`SimpleGlyph::from_cubic_bezpath`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CubicConversion {
+    /// The converted glyph.
+    pub glyph: SimpleGlyph,
+    /// The maximum distance, in font units, between the input cubic segments
+    /// and their quadratic approximations.
+    pub max_deviation: f64,
+}
+
 /// An error if an input curve is malformed
 #[derive(Clone, Debug)]
 #[non_exhaustive]
@@ -62,6 +75,70 @@ impl SimpleGlyph {
             .map(|mut x| x.pop().unwrap())
     }
 
+    /// Attempt to create a simple glyph from a path that may contain cubic
+    /// bezier segments.
+    ///
+    /// Each cubic segment is replaced by a quadratic spline that approximates
+    /// it to within `tolerance` font units. Smaller tolerances produce more
+    /// faithful, but larger, glyphs. The resulting points are rounded to
+    /// integer coordinates, which may add up to half a unit of error in each
+    /// direction.
+    ///
+    /// The returned [`CubicConversion`] includes the maximum distance between
+    /// the original cubic segments and the final (rounded) quadratic
+    /// segments, measured at evenly spaced samples along each cubic.
+    ///
+    /// Returns an error if the input path is malformed, as in
+    /// [`from_bezpath`](Self::from_bezpath).
+    pub fn from_cubic_bezpath(
+        path: &BezPath,
+        tolerance: f64,
+    ) -> Result<CubicConversion, MalformedPath> {
+        let mut quad_path = BezPath::new();
+        let mut max_deviation = 0f64;
+        let mut start = Point::ZERO;
+        let mut last = Point::ZERO;
+        for el in path.elements() {
+            match *el {
+                PathEl::MoveTo(pt) => {
+                    start = pt;
+                    last = pt;
+                    quad_path.move_to(pt);
+                }
+                PathEl::LineTo(pt) => {
+                    last = pt;
+                    quad_path.line_to(pt);
+                }
+                PathEl::QuadTo(p1, pt) => {
+                    last = pt;
+                    quad_path.quad_to(p1, pt);
+                }
+                PathEl::ClosePath => {
+                    last = start;
+                    quad_path.close_path();
+                }
+                PathEl::CurveTo(p1, p2, p3) => {
+                    let cubic = CubicBez::new(last, p1, p2, p3);
+                    let mut quads = approximate_cubic(&cubic, tolerance);
+                    let mut quad_start = round_point(last);
+                    for quad in &mut quads {
+                        quad.p0 = quad_start;
+                        quad.p1 = round_point(quad.p1);
+                        quad.p2 = round_point(quad.p2);
+                        quad_start = quad.p2;
+                        quad_path.quad_to(quad.p1, quad.p2);
+                    }
+                    max_deviation = max_deviation.max(deviation(&cubic, &quads));
+                    last = p3;
+                }
+            }
+        }
+        Ok(CubicConversion {
+            glyph: Self::from_bezpath(&quad_path)?,
+            max_deviation,
+        })
+    }
+
     /// Attempt to create a set of interpolation-compatible glyphs from a set
     /// of paths.
     ///
@@ -107,10 +184,15 @@ impl SimpleGlyph {
         }
 
         let (mut last_x, mut last_y) = (0, 0);
+        let mut first_flag = if self.overlap {
+            SimpleGlyphFlags::OVERLAP_SIMPLE
+        } else {
+            SimpleGlyphFlags::empty()
+        };
         let mut iter = self.contours.iter().flat_map(|c| c.iter());
         std::iter::from_fn(move || {
             let point = iter.next()?;
-            let mut flag = SimpleGlyphFlags::empty();
+            let mut flag = std::mem::take(&mut first_flag);
             let d_x = point.x - last_x;
             let d_y = point.y - last_y;
             last_x = point.x;
@@ -135,6 +217,19 @@ impl SimpleGlyph {
         })
     }
 
+    /// Returns `true` if the contours of this glyph are marked as overlapping.
+    pub fn has_overlaps(&self) -> bool {
+        self.overlap
+    }
+
+    /// Mark whether the contours of this glyph overlap.
+    ///
+    /// This is written as the `OVERLAP_SIMPLE` flag, which the spec requires
+    /// to be set only on the first point.
+    pub fn set_overlaps(&mut self, overlap: bool) {
+        self.overlap = overlap;
+    }
+
     /// Recompute the Glyph's bounding box based on the current contours
     pub fn recompute_bounding_box(&mut self) {
         let mut points = self
@@ -244,14 +339,16 @@ impl FromObjRef<read_fonts::tables::glyf::SimpleGlyph<'_>> for SimpleGlyph {
         let mut contours = vec![];
         for end_pt in from.end_pts_of_contours() {
             let end = end_pt.get() as usize + 1;
-            let count = end - last_end;
-            last_end = end;
+            // end points should increase, treat any that don't as empty contours
+            let count = end.saturating_sub(last_end);
+            last_end = last_end.max(end);
             contours.push(Contour(points.by_ref().take(count).collect()));
         }
         Self {
             bbox,
             contours,
             instructions: from.instructions().to_owned(),
+            overlap: from.has_overlapping_contours(),
         }
     }
 }
@@ -520,6 +617,38 @@ fn is_implicit_on_curve(points: &[ContourPoint], idx: usize) -> bool {
 }
 
 // impl for SimpleGlyph::interpolatable_glyphs_from_paths
+/// Number of points sampled along each cubic when measuring deviation.
+const DEVIATION_SAMPLES: usize = 32;
+
+/// Approximate a cubic with a quadratic spline, falling back to independent
+/// quadratic segments if no spline fits within the tolerance.
+fn approximate_cubic(cubic: &CubicBez, tolerance: f64) -> Vec<QuadBez> {
+    match cubic.approx_spline(tolerance) {
+        Some(spline) => spline.to_quads().collect(),
+        None => cubic.to_quads(tolerance).map(|(_, _, quad)| quad).collect(),
+    }
+}
+
+/// The maximum distance from points sampled along `cubic` to the nearest
+/// point on `quads`.
+fn deviation(cubic: &CubicBez, quads: &[QuadBez]) -> f64 {
+    (0..=DEVIATION_SAMPLES)
+        .map(|i| {
+            let pt = cubic.eval(i as f64 / DEVIATION_SAMPLES as f64);
+            quads
+                .iter()
+                .map(|quad| quad.nearest(pt, 1e-6).distance_sq)
+                .fold(f64::INFINITY, f64::min)
+                .sqrt()
+        })
+        .fold(0.0, f64::max)
+}
+
+fn round_point(pt: Point) -> Point {
+    let (x, y): (i16, i16) = pt.ot_round();
+    Point::new(x as f64, y as f64)
+}
+
 fn simple_glyphs_from_kurbo(paths: &[BezPath]) -> Result<Vec<SimpleGlyph>, MalformedPath> {
     // check that all paths have the same number of elements so we can zip them together
     let num_elements: Vec<usize> = paths.iter().map(|path| path.elements().len()).collect();
@@ -613,6 +742,7 @@ fn simple_glyphs_from_kurbo(paths: &[BezPath]) -> Result<Vec<SimpleGlyph>, Malfo
             bbox: path.control_box().into(),
             contours,
             instructions: Default::default(),
+            overlap: false,
         })
     }
 
@@ -636,6 +766,41 @@ mod tests {
         bytes
     }
 
+    fn cubic_circle(radius: f64) -> BezPath {
+        kurbo::Shape::to_path(&kurbo::Circle::new((500.0, 500.0), radius), 0.1)
+    }
+
+    #[test]
+    fn from_cubic_bezpath_tolerance() {
+        let path = cubic_circle(400.0);
+        assert!(path
+            .elements()
+            .iter()
+            .any(|el| matches!(el, kurbo::PathEl::CurveTo(..))));
+        let coarse = SimpleGlyph::from_cubic_bezpath(&path, 4.0).unwrap();
+        let fine = SimpleGlyph::from_cubic_bezpath(&path, 0.1).unwrap();
+        // rounding to integer coordinates adds up to sqrt(0.5) units of error
+        assert!(coarse.max_deviation <= 4.0 + 0.71);
+        assert!(fine.max_deviation <= 0.1 + 0.71);
+        assert!(fine.max_deviation > 0.0);
+        let num_points =
+            |glyph: &SimpleGlyph| glyph.contours.iter().map(|c| c.len()).sum::<usize>();
+        assert!(num_points(&fine.glyph) > num_points(&coarse.glyph));
+        assert_eq!(fine.glyph.contours.len(), 1);
+    }
+
+    #[test]
+    fn from_cubic_bezpath_without_cubics() {
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((100.0, 0.0));
+        path.quad_to((100.0, 100.0), (0.0, 100.0));
+        path.close_path();
+        let converted = SimpleGlyph::from_cubic_bezpath(&path, 1.0).unwrap();
+        assert_eq!(converted.glyph, SimpleGlyph::from_bezpath(&path).unwrap());
+        assert_eq!(converted.max_deviation, 0.0);
+    }
+
     #[test]
     fn bad_path_input() {
         let mut path = BezPath::new();
@@ -727,6 +892,25 @@ mod tests {
         assert_eq!(orig_bytes.as_ref(), bytes);
     }
 
+    #[test]
+    fn overlap_flag_on_first_point() {
+        let bezpath =
+            BezPath::from_svg("M0,0 L0,100 L100,100 Z M50,50 L50,150 L150,150 Z").unwrap();
+        let mut ours = SimpleGlyph::from_bezpath(&bezpath).unwrap();
+        ours.set_overlaps(true);
+        let bytes = crate::dump_table(&ours).unwrap();
+        let read = read_glyf::SimpleGlyph::read(bytes.as_slice().into()).unwrap();
+        assert!(read.has_overlapping_contours());
+        assert_eq!(SimpleGlyph::from_table_ref(&read), ours);
+        assert!(SimpleGlyph::from_table_ref(&read).has_overlaps());
+        // the flag only affects the first point
+        ours.set_overlaps(false);
+        let without = crate::dump_table(&ours).unwrap();
+        assert_eq!(without.len(), bytes.len());
+        let diff = bytes.iter().zip(&without).filter(|(a, b)| a != b).count();
+        assert_eq!(diff, 1);
+    }
+
     #[test]
     fn simple_glyph_open_path() {
         let mut path = BezPath::new();
@@ -820,6 +1004,27 @@ mod tests {
         assert!(points[0].on_curve);
     }
 
+    #[test]
+    fn decreasing_end_points_read_as_empty_contours() {
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((10.0, 0.0));
+        path.line_to((10.0, 10.0));
+        path.close_path();
+        path.move_to((20.0, 0.0));
+        path.line_to((30.0, 0.0));
+        path.close_path();
+        let glyph = SimpleGlyph::from_bezpath(&path).unwrap();
+        let mut bytes = crate::dump_table(&glyph).unwrap();
+        // swap the end points of the contours, from [2, 4] to [4, 2]
+        bytes[10..14].copy_from_slice(&[0, 4, 0, 2]);
+        let read = read_fonts::tables::glyf::SimpleGlyph::read(bytes.as_slice().into()).unwrap();
+        assert_eq!(read.end_pts_of_contours(), &[4, 2]);
+        let ours: SimpleGlyph = read.to_owned_table();
+        let lens = ours.contours.iter().map(Contour::len).collect::<Vec<_>>();
+        assert_eq!(lens, [read.num_points(), 0]);
+    }
+
     #[test]
     fn compile_repeatable_flags() {
         let mut path = BezPath::new();
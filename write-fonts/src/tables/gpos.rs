@@ -16,6 +16,7 @@ use super::{
     variations::ivs_builder::{RemapVariationIndices, VariationIndexRemapping},
 };
 
+pub mod builders;
 #[cfg(test)]
 #[path = "../tests/test_gpos.rs"]
 mod spec_tests;
@@ -0,0 +1,877 @@
+//! Builders for GPOS subtables.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::{
+    tables::layout::{
+        builders::{device_len, split_by_size, Builder, MAX_TABLE_SIZE},
+        ClassDef, CoverageTable,
+    },
+    types::GlyphId16,
+};
+
+use super::{
+    AnchorTable, BaseArray, BaseRecord, Class1Record, Class2Record, Mark2Array, Mark2Record,
+    MarkArray, MarkBasePosFormat1, MarkMarkPosFormat1, MarkRecord, PairPos, PairPosFormat1,
+    PairPosFormat2, PairSet, PairValueRecord, SinglePos, SinglePosFormat1, SinglePosFormat2,
+    ValueFormat, ValueRecord,
+};
+
+/// A set of glyphs, used as a class in class-based lookups.
+pub type GlyphSet = BTreeSet<GlyphId16>;
+
+/// A builder for [`SinglePos`] subtables.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SinglePosBuilder {
+    items: BTreeMap<GlyphId16, ValueRecord>,
+}
+
+/// A builder for [`PairPos`] subtables.
+///
+/// Pairs can be added either for individual glyphs, which are compiled into
+/// format 1 subtables, or for classes of glyphs, which are compiled into
+/// format 2 subtables. Glyph pairs are always ordered before class pairs, so
+/// they can be used to specify exceptions to class-based kerning.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PairPosBuilder {
+    pairs: BTreeMap<GlyphId16, BTreeMap<GlyphId16, (ValueRecord, ValueRecord)>>,
+    classes: BTreeMap<(GlyphSet, GlyphSet), (ValueRecord, ValueRecord)>,
+}
+
+/// A builder for [`MarkBasePosFormat1`] subtables.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MarkToBaseBuilder {
+    marks: MarkList,
+    bases: BTreeMap<GlyphId16, BTreeMap<u16, AnchorTable>>,
+}
+
+/// A builder for [`MarkMarkPosFormat1`] subtables.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MarkToMarkBuilder {
+    marks: MarkList,
+    mark2s: BTreeMap<GlyphId16, BTreeMap<u16, AnchorTable>>,
+}
+
+/// An error indicating that a mark glyph was added to two different classes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreviouslyAssignedClass {
+    pub glyph_id: GlyphId16,
+    /// The class the glyph was previously assigned to.
+    pub class: String,
+}
+
+impl SinglePosBuilder {
+    /// Set the adjustment for `glyph`, replacing any existing adjustment.
+    pub fn insert(&mut self, glyph: GlyphId16, record: ValueRecord) {
+        self.items.insert(glyph, record);
+    }
+
+    /// Returns `true` if no glyphs have been added.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The number of glyphs in the builder.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+}
+
+impl Builder for SinglePosBuilder {
+    type Output = Vec<SinglePos>;
+
+    fn build(self) -> Self::Output {
+        let format = union_format(self.items.values());
+        // each glyph needs a coverage entry and a value record
+        let item_len = format.record_byte_len() + 2;
+        split_by_size(self.items, 8, |(_, rec)| {
+            item_len + value_record_devices_len(rec)
+        })
+        .into_iter()
+        .map(|items| {
+            let coverage = items.iter().map(|(gid, _)| *gid).collect::<CoverageTable>();
+            let records = items
+                .into_iter()
+                .map(|(_, rec)| rec.with_explicit_value_format(format))
+                .collect::<Vec<_>>();
+            if records.iter().all(|rec| rec == &records[0]) {
+                let record = records.into_iter().next().unwrap();
+                SinglePos::Format1(SinglePosFormat1::new(coverage, record))
+            } else {
+                SinglePos::Format2(SinglePosFormat2::new(coverage, records))
+            }
+        })
+        .collect()
+    }
+}
+
+impl PairPosBuilder {
+    /// Set the adjustment for a pair of glyphs, replacing any existing value.
+    pub fn insert_pair(
+        &mut self,
+        glyph1: GlyphId16,
+        record1: ValueRecord,
+        glyph2: GlyphId16,
+        record2: ValueRecord,
+    ) {
+        self.pairs
+            .entry(glyph1)
+            .or_default()
+            .insert(glyph2, (record1, record2));
+    }
+
+    /// Set the adjustment for a pair of glyph classes, replacing any existing value.
+    ///
+    /// Classes that overlap without being identical cannot share a subtable;
+    /// in this case additional subtables are created as needed.
+    pub fn insert_classes(
+        &mut self,
+        class1: GlyphSet,
+        record1: ValueRecord,
+        class2: GlyphSet,
+        record2: ValueRecord,
+    ) {
+        self.classes.insert((class1, class2), (record1, record2));
+    }
+
+    /// Returns `true` if no pairs have been added.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty() && self.classes.is_empty()
+    }
+
+    fn build_glyph_pairs(
+        pairs: BTreeMap<GlyphId16, BTreeMap<GlyphId16, (ValueRecord, ValueRecord)>>,
+    ) -> impl Iterator<Item = PairPos> {
+        let records = pairs.values().flat_map(BTreeMap::values);
+        let format1 = union_format(records.clone().map(|(rec, _)| rec));
+        let format2 = union_format(records.map(|(_, rec)| rec));
+        let record_len = 2 + format1.record_byte_len() + format2.record_byte_len();
+        // each pair set needs an offset, a coverage entry, and a count
+        split_by_size(pairs, 10, move |(_, seconds)| {
+            6 + seconds
+                .values()
+                .map(|(rec1, rec2)| {
+                    record_len + value_record_devices_len(rec1) + value_record_devices_len(rec2)
+                })
+                .sum::<usize>()
+        })
+        .into_iter()
+        .map(move |items| {
+            let coverage = items.iter().map(|(gid, _)| *gid).collect::<CoverageTable>();
+            let pair_sets = items
+                .into_iter()
+                .map(|(_, seconds)| {
+                    PairSet::new(
+                        seconds
+                            .into_iter()
+                            .map(|(glyph2, (rec1, rec2))| {
+                                PairValueRecord::new(
+                                    glyph2,
+                                    rec1.with_explicit_value_format(format1),
+                                    rec2.with_explicit_value_format(format2),
+                                )
+                            })
+                            .collect(),
+                    )
+                })
+                .collect();
+            PairPos::Format1(PairPosFormat1::new(coverage, pair_sets))
+        })
+    }
+
+    fn build_class_pairs(
+        classes: BTreeMap<(GlyphSet, GlyphSet), (ValueRecord, ValueRecord)>,
+    ) -> impl Iterator<Item = PairPos> {
+        let mut subtables = vec![ClassPairSubtable::default()];
+        for ((class1, class2), (rec1, rec2)) in classes {
+            if !subtables
+                .last_mut()
+                .unwrap()
+                .try_add(&class1, &rec1, &class2, &rec2)
+            {
+                let mut next = ClassPairSubtable::default();
+                // an empty subtable can always accept a pair
+                next.try_add(&class1, &rec1, &class2, &rec2);
+                subtables.push(next);
+            }
+        }
+        subtables
+            .into_iter()
+            .filter(|subtable| !subtable.records.is_empty())
+            .map(ClassPairSubtable::build)
+    }
+}
+
+impl Builder for PairPosBuilder {
+    type Output = Vec<PairPos>;
+
+    fn build(self) -> Self::Output {
+        Self::build_glyph_pairs(self.pairs)
+            .chain(Self::build_class_pairs(self.classes))
+            .collect()
+    }
+}
+
+/// Glyph classes being assigned for one side of a class-based subtable.
+#[derive(Clone, Debug)]
+struct ClassAssignment {
+    first_class: u16,
+    classes: HashMap<GlyphSet, u16>,
+    glyphs: BTreeMap<GlyphId16, u16>,
+}
+
+impl ClassAssignment {
+    fn new(first_class: u16) -> Self {
+        Self {
+            first_class,
+            classes: Default::default(),
+            glyphs: Default::default(),
+        }
+    }
+
+    /// The class of this set, if it has already been assigned.
+    fn get(&self, set: &GlyphSet) -> Option<u16> {
+        self.classes.get(set).copied()
+    }
+
+    /// `true` if this set is already a class, or shares no glyphs with any class.
+    fn can_add(&self, set: &GlyphSet) -> bool {
+        self.classes.contains_key(set) || set.iter().all(|gid| !self.glyphs.contains_key(gid))
+    }
+
+    fn add(&mut self, set: &GlyphSet) -> u16 {
+        if let Some(class) = self.get(set) {
+            return class;
+        }
+        let class = self.first_class + self.classes.len() as u16;
+        self.classes.insert(set.clone(), class);
+        self.glyphs.extend(set.iter().map(|gid| (*gid, class)));
+        class
+    }
+
+    /// The number of classes, including any implicit class 0.
+    fn class_count(&self) -> usize {
+        self.first_class as usize + self.classes.len()
+    }
+
+    /// An upper bound on the size of the compiled ClassDef, after adding `set`.
+    fn classdef_len_with(&self, set: &GlyphSet) -> usize {
+        let (mut count, mut first, mut last) = (self.glyphs.len(), None, None);
+        if self.get(set).is_none() {
+            count += set.len();
+            first = set.first();
+            last = set.last();
+        }
+        let first = self.glyphs.keys().next().into_iter().chain(first).min();
+        let last = self.glyphs.keys().next_back().into_iter().chain(last).max();
+        let Some((first, last)) = first.zip(last) else {
+            return 4;
+        };
+        let format1_len = 6 + (last.to_u16() - first.to_u16()) as usize * 2 + 2;
+        let format2_len = 4 + count * 6;
+        format1_len.min(format2_len)
+    }
+}
+
+/// The pairs assigned to a single format 2 subtable.
+#[derive(Clone, Debug)]
+struct ClassPairSubtable {
+    // class 0 of the first classdef is the glyphs in the coverage table that
+    // are not in the classdef, so we can use it for a real class.
+    class1: ClassAssignment,
+    // class 0 of the second classdef is 'all other glyphs', and must be empty.
+    class2: ClassAssignment,
+    records: BTreeMap<(u16, u16), (ValueRecord, ValueRecord)>,
+    format1: ValueFormat,
+    format2: ValueFormat,
+    devices_len: usize,
+}
+
+impl Default for ClassPairSubtable {
+    fn default() -> Self {
+        Self {
+            class1: ClassAssignment::new(0),
+            class2: ClassAssignment::new(1),
+            records: Default::default(),
+            format1: ValueFormat::empty(),
+            format2: ValueFormat::empty(),
+            devices_len: 0,
+        }
+    }
+}
+
+impl ClassPairSubtable {
+    /// Attempt to add a pair, returning `false` if it does not fit.
+    fn try_add(
+        &mut self,
+        class1: &GlyphSet,
+        rec1: &ValueRecord,
+        class2: &GlyphSet,
+        rec2: &ValueRecord,
+    ) -> bool {
+        if !self.class1.can_add(class1) || !self.class2.can_add(class2) {
+            return false;
+        }
+        let format1 = self.format1 | rec1.format();
+        let format2 = self.format2 | rec2.format();
+        let devices_len =
+            self.devices_len + value_record_devices_len(rec1) + value_record_devices_len(rec2);
+        let new_class1 = self.class1.get(class1).is_none();
+        let class1_count = self.class1.class_count() + new_class1 as usize;
+        let class2_count = self.class2.class_count() + self.class2.get(class2).is_none() as usize;
+        let coverage_len =
+            4 + (self.class1.glyphs.len() + if new_class1 { class1.len() } else { 0 }) * 2;
+        let len = 16
+            + coverage_len
+            + self.class1.classdef_len_with(class1)
+            + self.class2.classdef_len_with(class2)
+            + class1_count * class2_count * (format1.record_byte_len() + format2.record_byte_len())
+            + devices_len;
+        if len > MAX_TABLE_SIZE && !self.records.is_empty() {
+            return false;
+        }
+
+        let c1 = self.class1.add(class1);
+        let c2 = self.class2.add(class2);
+        self.records.insert((c1, c2), (rec1.clone(), rec2.clone()));
+        self.format1 = format1;
+        self.format2 = format2;
+        self.devices_len = devices_len;
+        true
+    }
+
+    fn build(mut self) -> PairPos {
+        let coverage = self
+            .class1
+            .glyphs
+            .keys()
+            .copied()
+            .collect::<CoverageTable>();
+        let class_def1 = self
+            .class1
+            .glyphs
+            .iter()
+            .map(|(gid, cls)| (*gid, *cls))
+            .collect::<ClassDef>();
+        let class_def2 = self
+            .class2
+            .glyphs
+            .iter()
+            .map(|(gid, cls)| (*gid, *cls))
+            .collect::<ClassDef>();
+        let empty1 = ValueRecord::new().with_explicit_value_format(self.format1);
+        let empty2 = ValueRecord::new().with_explicit_value_format(self.format2);
+        let class1_records = (0..self.class1.class_count() as u16)
+            .map(|c1| {
+                Class1Record::new(
+                    (0..self.class2.class_count() as u16)
+                        .map(|c2| match self.records.remove(&(c1, c2)) {
+                            Some((rec1, rec2)) => Class2Record::new(
+                                rec1.with_explicit_value_format(self.format1),
+                                rec2.with_explicit_value_format(self.format2),
+                            ),
+                            None => Class2Record::new(empty1.clone(), empty2.clone()),
+                        })
+                        .collect(),
+                )
+            })
+            .collect();
+        PairPos::Format2(PairPosFormat2::new(
+            coverage,
+            class_def1,
+            class_def2,
+            class1_records,
+        ))
+    }
+}
+
+impl MarkToBaseBuilder {
+    /// Add a mark glyph, with its anchor, to the named mark class.
+    ///
+    /// Returns the id of the class, or an error if the glyph has already
+    /// been added to a different class.
+    pub fn insert_mark(
+        &mut self,
+        glyph: GlyphId16,
+        class: &str,
+        anchor: AnchorTable,
+    ) -> Result<u16, PreviouslyAssignedClass> {
+        self.marks.insert(glyph, class, anchor)
+    }
+
+    /// Add the anchor where marks of the named class attach to a base glyph.
+    pub fn insert_base(&mut self, glyph: GlyphId16, class: &str, anchor: AnchorTable) {
+        let class = self.marks.class_id(class);
+        self.bases.entry(glyph).or_default().insert(class, anchor);
+    }
+
+    /// Returns `true` if no glyphs have been added.
+    pub fn is_empty(&self) -> bool {
+        self.marks.glyphs.is_empty() && self.bases.is_empty()
+    }
+}
+
+impl Builder for MarkToBaseBuilder {
+    type Output = Vec<MarkBasePosFormat1>;
+
+    fn build(self) -> Self::Output {
+        build_mark_attachments(self.marks, self.bases)
+            .into_iter()
+            .map(|subtable| {
+                let base_array =
+                    BaseArray::new(subtable.anchors.into_iter().map(BaseRecord::new).collect());
+                MarkBasePosFormat1::new(
+                    subtable.mark_coverage,
+                    subtable.base_coverage,
+                    subtable.mark_array,
+                    base_array,
+                )
+            })
+            .collect()
+    }
+}
+
+impl MarkToMarkBuilder {
+    /// Add an attaching mark glyph, with its anchor, to the named mark class.
+    ///
+    /// Returns the id of the class, or an error if the glyph has already
+    /// been added to a different class.
+    pub fn insert_mark1(
+        &mut self,
+        glyph: GlyphId16,
+        class: &str,
+        anchor: AnchorTable,
+    ) -> Result<u16, PreviouslyAssignedClass> {
+        self.marks.insert(glyph, class, anchor)
+    }
+
+    /// Add the anchor where marks of the named class attach to a base mark glyph.
+    pub fn insert_mark2(&mut self, glyph: GlyphId16, class: &str, anchor: AnchorTable) {
+        let class = self.marks.class_id(class);
+        self.mark2s.entry(glyph).or_default().insert(class, anchor);
+    }
+
+    /// Returns `true` if no glyphs have been added.
+    pub fn is_empty(&self) -> bool {
+        self.marks.glyphs.is_empty() && self.mark2s.is_empty()
+    }
+}
+
+impl Builder for MarkToMarkBuilder {
+    type Output = Vec<MarkMarkPosFormat1>;
+
+    fn build(self) -> Self::Output {
+        build_mark_attachments(self.marks, self.mark2s)
+            .into_iter()
+            .map(|subtable| {
+                let mark2_array =
+                    Mark2Array::new(subtable.anchors.into_iter().map(Mark2Record::new).collect());
+                MarkMarkPosFormat1::new(
+                    subtable.mark_coverage,
+                    subtable.base_coverage,
+                    subtable.mark_array,
+                    mark2_array,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Marks, and the named classes they belong to.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct MarkList {
+    class_names: Vec<String>,
+    glyphs: BTreeMap<GlyphId16, (u16, AnchorTable)>,
+}
+
+impl MarkList {
+    fn class_id(&mut self, class: &str) -> u16 {
+        match self.class_names.iter().position(|name| name == class) {
+            Some(idx) => idx as u16,
+            None => {
+                self.class_names.push(class.to_owned());
+                (self.class_names.len() - 1) as u16
+            }
+        }
+    }
+
+    fn insert(
+        &mut self,
+        glyph: GlyphId16,
+        class: &str,
+        anchor: AnchorTable,
+    ) -> Result<u16, PreviouslyAssignedClass> {
+        let class_id = self.class_id(class);
+        match self.glyphs.get(&glyph) {
+            Some((prev, _)) if *prev != class_id => Err(PreviouslyAssignedClass {
+                glyph_id: glyph,
+                class: self.class_names[*prev as usize].clone(),
+            }),
+            _ => {
+                self.glyphs.insert(glyph, (class_id, anchor));
+                Ok(class_id)
+            }
+        }
+    }
+}
+
+/// The contents of a mark-to-base or mark-to-mark subtable.
+struct MarkAttachment {
+    mark_coverage: CoverageTable,
+    mark_array: MarkArray,
+    base_coverage: CoverageTable,
+    anchors: Vec<Vec<Option<AnchorTable>>>,
+}
+
+/// Assign mark classes to subtables, and compile each subtable.
+///
+/// If all the classes will not fit in a single subtable, they are split
+/// across multiple subtables, each with its own (renumbered) set of classes.
+fn build_mark_attachments(
+    marks: MarkList,
+    bases: BTreeMap<GlyphId16, BTreeMap<u16, AnchorTable>>,
+) -> Vec<MarkAttachment> {
+    // classes without marks can't be used
+    let used_classes = marks
+        .glyphs
+        .values()
+        .map(|(class, _)| *class)
+        .collect::<BTreeSet<_>>();
+    let estimate_len = |classes: &[u16]| {
+        let (mut mark_count, mut mark_anchors_len) = (0, 0);
+        for (class, anchor) in marks.glyphs.values() {
+            if classes.contains(class) {
+                mark_count += 1;
+                mark_anchors_len += anchor_len(anchor);
+            }
+        }
+        let (mut base_count, mut base_anchors_len) = (0, 0);
+        for anchors in bases.values() {
+            let mut anchors = anchors
+                .iter()
+                .filter(|(class, _)| classes.contains(class))
+                .peekable();
+            if anchors.peek().is_some() {
+                base_count += 1;
+                base_anchors_len += anchors.map(|(_, anchor)| anchor_len(anchor)).sum::<usize>();
+            }
+        }
+        12 + (4 + mark_count * 2)
+            + (4 + base_count * 2)
+            + (2 + mark_count * 4 + mark_anchors_len)
+            + (2 + base_count * classes.len() * 2 + base_anchors_len)
+    };
+
+    let mut groups: Vec<Vec<u16>> = Vec::new();
+    let mut current = Vec::new();
+    for class in used_classes {
+        current.push(class);
+        if current.len() > 1 && estimate_len(&current) > MAX_TABLE_SIZE {
+            current.pop();
+            groups.push(std::mem::replace(&mut current, vec![class]));
+        }
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+
+    groups
+        .into_iter()
+        .map(|classes| {
+            let local_class = |class: u16| classes.iter().position(|c| *c == class);
+            let mark_glyphs = marks
+                .glyphs
+                .iter()
+                .filter_map(|(gid, (class, anchor))| {
+                    local_class(*class).map(|class| (*gid, class as u16, anchor.clone()))
+                })
+                .collect::<Vec<_>>();
+            let mark_coverage = mark_glyphs
+                .iter()
+                .map(|(gid, ..)| *gid)
+                .collect::<CoverageTable>();
+            let mark_array = MarkArray::new(
+                mark_glyphs
+                    .into_iter()
+                    .map(|(_, class, anchor)| MarkRecord::new(class, anchor))
+                    .collect(),
+            );
+            let mut base_glyphs = Vec::new();
+            let mut anchors = Vec::new();
+            for (gid, base_anchors) in &bases {
+                let mut record = vec![None; classes.len()];
+                for (class, anchor) in base_anchors {
+                    if let Some(class) = local_class(*class) {
+                        record[class] = Some(anchor.clone());
+                    }
+                }
+                if record.iter().any(Option::is_some) {
+                    base_glyphs.push(*gid);
+                    anchors.push(record);
+                }
+            }
+            MarkAttachment {
+                mark_coverage,
+                mark_array,
+                base_coverage: base_glyphs.into_iter().collect(),
+                anchors,
+            }
+        })
+        .collect()
+}
+
+fn union_format<'a>(records: impl IntoIterator<Item = &'a ValueRecord>) -> ValueFormat {
+    records
+        .into_iter()
+        .fold(ValueFormat::empty(), |format, rec| format | rec.format())
+}
+
+/// The total size of any device tables referenced by this record.
+fn value_record_devices_len(record: &ValueRecord) -> usize {
+    [
+        &record.x_placement_device,
+        &record.y_placement_device,
+        &record.x_advance_device,
+        &record.y_advance_device,
+    ]
+    .into_iter()
+    .filter_map(|device| device.as_ref())
+    .map(device_len)
+    .sum()
+}
+
+/// The size of an anchor table, including any device tables.
+fn anchor_len(anchor: &AnchorTable) -> usize {
+    match anchor {
+        AnchorTable::Format1(_) => 6,
+        AnchorTable::Format2(_) => 8,
+        AnchorTable::Format3(table) => {
+            10 + table.x_device.as_ref().map(device_len).unwrap_or(0)
+                + table.y_device.as_ref().map(device_len).unwrap_or(0)
+        }
+    }
+}
+
+impl std::fmt::Display for PreviouslyAssignedClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "glyph {} was already assigned to mark class '{}'",
+            self.glyph_id, self.class
+        )
+    }
+}
+
+impl std::error::Error for PreviouslyAssignedClass {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::layout::{builders::LookupBuilder, DeviceOrVariationIndex, LookupFlag};
+
+    fn gid(raw: u16) -> GlyphId16 {
+        GlyphId16::new(raw)
+    }
+
+    fn glyph_set(raw: impl IntoIterator<Item = u16>) -> GlyphSet {
+        raw.into_iter().map(GlyphId16::new).collect()
+    }
+
+    #[test]
+    fn single_pos_formats() {
+        let mut builder = SinglePosBuilder::default();
+        builder.insert(gid(5), ValueRecord::new().with_x_advance(10));
+        builder.insert(gid(6), ValueRecord::new().with_x_advance(10));
+        let subtables = builder.clone().build();
+        assert!(matches!(subtables.as_slice(), [SinglePos::Format1(_)]));
+
+        // formats are unified across the subtable
+        builder.insert(gid(7), ValueRecord::new().with_y_placement(-5));
+        let subtables = builder.build();
+        let [SinglePos::Format2(table)] = subtables.as_slice() else {
+            panic!("expected a single format 2 subtable");
+        };
+        assert!(table
+            .value_records
+            .iter()
+            .all(|rec| rec.format() == ValueFormat::X_ADVANCE | ValueFormat::Y_PLACEMENT));
+        assert_eq!(table.value_records[2].x_advance, None);
+    }
+
+    #[test]
+    fn pair_pos_glyphs_and_classes() {
+        let kern = |val| ValueRecord::new().with_x_advance(val);
+        let mut builder = PairPosBuilder::default();
+        builder.insert_pair(gid(1), kern(-10), gid(2), ValueRecord::new());
+        builder.insert_classes(
+            glyph_set([1, 3]),
+            kern(-20),
+            glyph_set([2, 4]),
+            ValueRecord::new(),
+        );
+        builder.insert_classes(
+            glyph_set([5]),
+            kern(-30),
+            glyph_set([2, 4]),
+            ValueRecord::new(),
+        );
+
+        let subtables = builder.build();
+        let [PairPos::Format1(pairs), PairPos::Format2(classes)] = subtables.as_slice() else {
+            panic!("unexpected subtables {subtables:?}");
+        };
+        assert_eq!(pairs.pair_sets.len(), 1);
+        assert_eq!(classes.coverage.iter().count(), 3);
+        // class 0 is used for the first class1
+        assert_eq!(classes.class_def1.get(gid(1)), 0);
+        assert_eq!(classes.class_def1.get(gid(5)), 1);
+        assert_eq!(classes.class1_records.len(), 2);
+        assert_eq!(classes.class1_records[0].class2_records.len(), 2);
+        let rec = &classes.class1_records[1].class2_records[1];
+        assert_eq!(rec.value_record1.x_advance, Some(-30));
+        // empty class 0 records still have the right format
+        let rec = &classes.class1_records[1].class2_records[0];
+        assert_eq!(rec.value_record1.format(), ValueFormat::X_ADVANCE);
+        assert!(crate::dump_table(classes).is_ok());
+    }
+
+    #[test]
+    fn pair_pos_overlapping_classes() {
+        let kern = |val| ValueRecord::new().with_x_advance(val);
+        let mut builder = PairPosBuilder::default();
+        builder.insert_classes(glyph_set([1, 2]), kern(-5), glyph_set([3]), kern(0));
+        builder.insert_classes(glyph_set([2, 4]), kern(-5), glyph_set([3]), kern(0));
+        assert_eq!(builder.build().len(), 2);
+    }
+
+    #[test]
+    fn pair_pos_with_device() {
+        let device = DeviceOrVariationIndex::device(11, 13, &[1, 0, -1]);
+        let mut builder = PairPosBuilder::default();
+        builder.insert_pair(
+            gid(1),
+            ValueRecord::new()
+                .with_x_advance(-10)
+                .with_x_advance_device(device),
+            gid(2),
+            ValueRecord::new(),
+        );
+        builder.insert_pair(
+            gid(3),
+            ValueRecord::new().with_x_advance(5),
+            gid(4),
+            ValueRecord::new(),
+        );
+        let subtables = builder.build();
+        let [PairPos::Format1(table)] = subtables.as_slice() else {
+            panic!("expected a single format 1 subtable");
+        };
+        let rec = &table.pair_sets[1].pair_value_records[0].value_record1;
+        assert_eq!(
+            rec.format(),
+            ValueFormat::X_ADVANCE | ValueFormat::X_ADVANCE_DEVICE
+        );
+        assert!(rec.x_advance_device.is_none());
+        assert!(crate::dump_table(table).is_ok());
+    }
+
+    #[test]
+    fn pair_pos_split() {
+        let mut builder = PairPosBuilder::default();
+        for first in 0..200 {
+            for second in 0..100 {
+                builder.insert_pair(
+                    gid(first),
+                    ValueRecord::new().with_x_advance(first as i16 - second as i16),
+                    gid(second),
+                    ValueRecord::new(),
+                );
+            }
+        }
+        let mut lookup = LookupBuilder::new(LookupFlag::empty(), None);
+        *lookup.last_mut() = builder;
+        let lookup = lookup.build();
+        assert_eq!(lookup.subtables.len(), 2);
+        for subtable in &lookup.subtables {
+            assert!(crate::dump_table(&**subtable).is_ok());
+        }
+    }
+
+    #[test]
+    fn mark_to_base() {
+        let mut builder = MarkToBaseBuilder::default();
+        assert_eq!(
+            builder.insert_mark(gid(10), "top", AnchorTable::format_1(0, 500)),
+            Ok(0)
+        );
+        assert_eq!(
+            builder.insert_mark(gid(11), "bottom", AnchorTable::format_1(0, -10)),
+            Ok(1)
+        );
+        assert_eq!(
+            builder.insert_mark(gid(10), "bottom", AnchorTable::format_1(0, 0)),
+            Err(PreviouslyAssignedClass {
+                glyph_id: gid(10),
+                class: "top".into()
+            })
+        );
+        builder.insert_base(gid(1), "top", AnchorTable::format_1(250, 700));
+        builder.insert_base(gid(2), "bottom", AnchorTable::format_1(300, 0));
+        // no marks in this class, so it is dropped
+        builder.insert_base(gid(2), "ogonek", AnchorTable::format_1(300, 0));
+
+        let subtables = builder.build();
+        let [subtable] = subtables.as_slice() else {
+            panic!("expected one subtable");
+        };
+        assert_eq!(subtable.mark_array.mark_records.len(), 2);
+        let records = &subtable.base_array.base_records;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].base_anchors.len(), 2);
+        assert!(records[0].base_anchors[1].is_none());
+        assert!(crate::dump_table(subtable).is_ok());
+    }
+
+    #[test]
+    fn mark_to_base_split() {
+        let mut builder = MarkToBaseBuilder::default();
+        let classes = (0..8).map(|i| format!("class{i}")).collect::<Vec<_>>();
+        for (i, class) in classes.iter().enumerate() {
+            builder
+                .insert_mark(gid(2000 + i as u16), class, AnchorTable::format_1(0, 0))
+                .unwrap();
+            for base in 0..1000u16 {
+                // distinct anchors, so they can't be shared
+                let anchor = AnchorTable::format_1(base as i16, i as i16);
+                builder.insert_base(gid(base), class, anchor);
+            }
+        }
+        let subtables = builder.build();
+        assert!(subtables.len() > 1);
+        assert_eq!(
+            subtables
+                .iter()
+                .map(|sub| sub.mark_array.mark_records.len())
+                .sum::<usize>(),
+            8
+        );
+        for subtable in &subtables {
+            assert!(crate::dump_table(subtable).is_ok());
+        }
+    }
+
+    #[test]
+    fn mark_to_mark() {
+        let mut builder = MarkToMarkBuilder::default();
+        builder
+            .insert_mark1(gid(10), "top", AnchorTable::format_1(0, 500))
+            .unwrap();
+        builder.insert_mark2(gid(11), "top", AnchorTable::format_1(0, 700));
+        let subtables = builder.build();
+        let [subtable] = subtables.as_slice() else {
+            panic!("expected one subtable");
+        };
+        assert_eq!(subtable.mark2_array.mark2_records.len(), 1);
+        assert!(crate::dump_table(subtable).is_ok());
+    }
+}
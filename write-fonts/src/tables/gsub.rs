@@ -9,6 +9,7 @@ use super::layout::{
     LookupSubtable, LookupType, ScriptList, SequenceContext,
 };
 
+pub mod builders;
 #[cfg(test)]
 #[path = "../tests/test_gsub.rs"]
 mod tests;
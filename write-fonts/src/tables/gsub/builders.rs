@@ -0,0 +1,198 @@
+//! Builders for GSUB subtables.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    tables::layout::{
+        builders::{split_by_size, Builder},
+        CoverageTable,
+    },
+    types::GlyphId16,
+};
+
+use super::{Ligature, LigatureSet, LigatureSubstFormat1, SingleSubst};
+
+/// A builder for [`SingleSubst`] subtables.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SingleSubBuilder {
+    items: BTreeMap<GlyphId16, GlyphId16>,
+}
+
+/// A builder for [`LigatureSubstFormat1`] subtables.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LigatureSubBuilder {
+    items: BTreeMap<GlyphId16, Vec<(Vec<GlyphId16>, GlyphId16)>>,
+}
+
+impl SingleSubBuilder {
+    /// Substitute `target` with `replacement`, replacing any existing rule.
+    pub fn insert(&mut self, target: GlyphId16, replacement: GlyphId16) {
+        self.items.insert(target, replacement);
+    }
+
+    /// Returns `true` if no substitutions have been added.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The number of substitutions in the builder.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+}
+
+impl Builder for SingleSubBuilder {
+    type Output = Vec<SingleSubst>;
+
+    fn build(self) -> Self::Output {
+        // each glyph needs a coverage entry and (in format 2) a substitute
+        split_by_size(self.items, 6, |_| 4)
+            .into_iter()
+            .map(|items| {
+                let coverage = items.iter().map(|(gid, _)| *gid).collect::<CoverageTable>();
+                let delta = |(target, replacement): &(GlyphId16, GlyphId16)| {
+                    replacement.to_u16().wrapping_sub(target.to_u16()) as i16
+                };
+                let first_delta = delta(&items[0]);
+                if items.iter().all(|item| delta(item) == first_delta) {
+                    SingleSubst::format_1(coverage, first_delta)
+                } else {
+                    SingleSubst::format_2(coverage, items.into_iter().map(|(_, g)| g).collect())
+                }
+            })
+            .collect()
+    }
+}
+
+impl LigatureSubBuilder {
+    /// Substitute the sequence `components` with the glyph `replacement`.
+    ///
+    /// Where multiple ligatures begin with the same glyph, longer ligatures
+    /// take precedence. If a ligature with these components already exists,
+    /// its replacement is updated.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `components` is empty.
+    pub fn insert(&mut self, components: Vec<GlyphId16>, replacement: GlyphId16) {
+        let (first, rest) = components
+            .split_first()
+            .expect("ligature must have at least one component");
+        let ligatures = self.items.entry(*first).or_default();
+        match ligatures.iter_mut().find(|(existing, _)| existing == rest) {
+            Some((_, existing)) => *existing = replacement,
+            None => ligatures.push((rest.to_vec(), replacement)),
+        }
+    }
+
+    /// Returns `true` if no ligatures have been added.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl Builder for LigatureSubBuilder {
+    type Output = Vec<LigatureSubstFormat1>;
+
+    fn build(self) -> Self::Output {
+        // each ligature set needs an offset, a coverage entry and a count,
+        // and each ligature an offset, a glyph, a count, and its components
+        let set_len = |(_, ligatures): &(GlyphId16, Vec<(Vec<GlyphId16>, GlyphId16)>)| {
+            6 + ligatures
+                .iter()
+                .map(|(rest, _)| 6 + rest.len() * 2)
+                .sum::<usize>()
+        };
+        split_by_size(self.items, 6, set_len)
+            .into_iter()
+            .map(|items| {
+                let coverage = items.iter().map(|(gid, _)| *gid).collect::<CoverageTable>();
+                let ligature_sets = items
+                    .into_iter()
+                    .map(|(_, mut ligatures)| {
+                        // stable, so equal-length ligatures keep their order
+                        ligatures.sort_by_key(|(rest, _)| std::cmp::Reverse(rest.len()));
+                        LigatureSet::new(
+                            ligatures
+                                .into_iter()
+                                .map(|(rest, replacement)| Ligature::new(replacement, rest))
+                                .collect(),
+                        )
+                    })
+                    .collect();
+                LigatureSubstFormat1::new(coverage, ligature_sets)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::layout::{builders::LookupBuilder, LookupFlag};
+
+    fn gids<const N: usize>(raw: [u16; N]) -> Vec<GlyphId16> {
+        raw.into_iter().map(GlyphId16::new).collect()
+    }
+
+    #[test]
+    fn single_sub_format() {
+        let mut builder = SingleSubBuilder::default();
+        builder.insert(GlyphId16::new(10), GlyphId16::new(20));
+        builder.insert(GlyphId16::new(11), GlyphId16::new(21));
+        let subtables = builder.clone().build();
+        assert_eq!(subtables.len(), 1);
+        let SingleSubst::Format1(table) = &subtables[0] else {
+            panic!("expected format 1");
+        };
+        assert_eq!(table.delta_glyph_id, 10);
+
+        builder.insert(GlyphId16::new(5), GlyphId16::new(2));
+        let subtables = builder.build();
+        let SingleSubst::Format2(table) = &subtables[0] else {
+            panic!("expected format 2");
+        };
+        assert_eq!(table.substitute_glyph_ids, gids([2, 20, 21]));
+    }
+
+    #[test]
+    fn single_sub_split() {
+        let mut builder = SingleSubBuilder::default();
+        for gid in 0..20_000 {
+            // alternate deltas so we need format 2
+            builder.insert(GlyphId16::new(gid), GlyphId16::new(gid / 2));
+        }
+        let subtables = builder.build();
+        assert_eq!(subtables.len(), 2);
+        for subtable in &subtables {
+            assert!(crate::dump_table(subtable).is_ok());
+        }
+    }
+
+    #[test]
+    fn ligature_order() {
+        let mut builder = LigatureSubBuilder::default();
+        builder.insert(gids([1, 2]), GlyphId16::new(10));
+        builder.insert(gids([1, 2, 3]), GlyphId16::new(11));
+        builder.insert(gids([1, 3]), GlyphId16::new(12));
+        builder.insert(gids([4, 5]), GlyphId16::new(13));
+        // replaces the earlier rule
+        builder.insert(gids([1, 2]), GlyphId16::new(14));
+
+        let mut lookup = LookupBuilder::new(LookupFlag::IGNORE_MARKS, None);
+        *lookup.last_mut() = builder;
+        let lookup = lookup.build();
+        assert_eq!(lookup.subtables.len(), 1);
+        let subtable = &lookup.subtables[0];
+        assert_eq!(subtable.coverage.iter().collect::<Vec<_>>(), gids([1, 4]));
+        let ligatures = subtable.ligature_sets[0]
+            .ligatures
+            .iter()
+            .map(|lig| (lig.component_glyph_ids.clone(), lig.ligature_glyph.to_u16()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ligatures,
+            [(gids([2, 3]), 11), (gids([2]), 14), (gids([3]), 12)]
+        );
+    }
+}
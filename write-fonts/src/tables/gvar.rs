@@ -5,8 +5,9 @@ include!("../../generated/generated_gvar.rs");
 use std::collections::HashMap;
 
 use indexmap::IndexMap;
+use kurbo::{Point, Vec2};
 
-use crate::{collections::HasLen, OffsetMarker};
+use crate::{collections::HasLen, OffsetMarker, OtRound};
 
 use super::variations::{
     PackedDeltas, PackedPointNumbers, Tuple, TupleVariationCount, TupleVariationHeader,
@@ -354,6 +355,50 @@ impl GlyphDeltas {
         }
     }
 
+    /// Create deltas for a simple glyph from unrounded point deltas.
+    ///
+    /// `coords` are the default positions of the glyph's points, followed by
+    /// its four phantom points, and `contour_ends` is the index of the last
+    /// point of each contour. Deltas that can be inferred from their
+    /// neighbours within `tolerance` are marked as optional, so that they can
+    /// be omitted if this saves space.
+    pub fn from_simple_glyph_deltas(
+        peak_tuple: Tuple,
+        deltas: Vec<Vec2>,
+        intermediate_region: Option<(Tuple, Tuple)>,
+        coords: &[Point],
+        contour_ends: &[usize],
+        tolerance: f64,
+    ) -> Result<Self, iup::IupError> {
+        let deltas = iup::iup_delta_optimize(deltas, coords.to_vec(), tolerance, contour_ends)?;
+        Ok(Self::new(peak_tuple, deltas, intermediate_region))
+    }
+
+    /// Create deltas from unrounded deltas that are all required.
+    ///
+    /// This is appropriate for composite glyphs, where the deltas apply to the
+    /// component offsets and cannot be inferred.
+    pub fn from_required_deltas(
+        peak_tuple: Tuple,
+        deltas: Vec<Vec2>,
+        intermediate_region: Option<(Tuple, Tuple)>,
+    ) -> Self {
+        let deltas = deltas
+            .into_iter()
+            .map(|delta| GlyphDelta::required(delta.x.ot_round(), delta.y.ot_round()))
+            .collect();
+        Self::new(peak_tuple, deltas, intermediate_region)
+    }
+
+    /// Returns `true` if these deltas do not move any point.
+    ///
+    /// Such variations can be dropped without changing the glyph.
+    pub fn is_noop(&self) -> bool {
+        self.deltas
+            .iter()
+            .all(|delta| !delta.required || (delta.x == 0 && delta.y == 0))
+    }
+
     // this is a type method just to expose it for testing, we call it before
     // we finish instantiating self.
     //
@@ -792,6 +837,65 @@ mod tests {
         assert_eq!(points, vec![(30, 31), (101, 102), (10, 11)]);
     }
 
+    #[test]
+    fn optimize_simple_glyph_deltas() {
+        // a square, with all points moving by roughly the same amount; the
+        // midpoints of each side can be inferred.
+        let coords = [
+            (0., 0.),
+            (0., 50.),
+            (0., 100.),
+            (50., 100.),
+            (100., 100.),
+            (100., 50.),
+            (100., 0.),
+            (50., 0.),
+            (0., 0.),
+            (100., 0.),
+            (0., 0.),
+            (0., 0.),
+        ]
+        .map(Point::from);
+        let mut deltas = [(10., 5.); 12].map(Vec2::from).to_vec();
+        deltas[1] = Vec2::new(10.2, 5.);
+        deltas[8..].fill(Vec2::ZERO);
+        // the advance width changes
+        deltas[9] = Vec2::new(20., 0.);
+        let peak = Tuple::new(vec![F2Dot14::ONE]);
+        let glyph_deltas = GlyphDeltas::from_simple_glyph_deltas(
+            peak.clone(),
+            deltas.clone(),
+            None,
+            &coords,
+            &[7],
+            0.5,
+        )
+        .unwrap();
+        assert!(!glyph_deltas.is_noop());
+        assert!(glyph_deltas.deltas[..8].iter().any(|delta| !delta.required));
+        assert_eq!(glyph_deltas.deltas[1], GlyphDelta::optional(10, 5));
+        assert_eq!(glyph_deltas.deltas[9], GlyphDelta::required(20, 0));
+
+        let table = Gvar::new(
+            vec![GlyphVariations::new(GlyphId::new(0), vec![glyph_deltas])],
+            1,
+        )
+        .unwrap();
+        let bytes = crate::dump_table(&table).unwrap();
+        let gvar = read_fonts::tables::gvar::Gvar::read(FontData::new(&bytes)).unwrap();
+        let data = gvar.glyph_variation_data(GlyphId::new(0)).unwrap();
+        let tuple = data.tuples().next().unwrap();
+        assert!(!tuple.has_deltas_for_all_points());
+
+        // composite deltas are all kept, and rounded
+        let composite = GlyphDeltas::from_required_deltas(peak.clone(), deltas, None);
+        assert!(composite.deltas.iter().all(|delta| delta.required));
+        assert_eq!(composite.deltas[1], GlyphDelta::required(10, 5));
+
+        let zeros = GlyphDeltas::from_required_deltas(peak, vec![Vec2::new(0.2, -0.3); 5], None);
+        assert!(zeros.is_noop());
+    }
+
     #[test]
     fn disregard_iup_when_appropriate() {
         // if the cost of encoding the list of points is greater than the savings
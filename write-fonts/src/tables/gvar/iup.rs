@@ -79,6 +79,106 @@ pub fn iup_delta_optimize(
     Ok(result)
 }
 
+/// For the outline given in `coords`, with contour endpoints given
+/// `contour_ends`, infer the deltas for points that have no explicit delta.
+///
+/// `deltas` contains one entry per coordinate, including the four phantom
+/// points, with `None` for points whose delta is not encoded. Within a
+/// contour, a missing delta is interpolated from the nearest encoded points
+/// before and after it; a contour without any encoded point (including each
+/// phantom point) gets zero deltas.
+///
+/// See:
+/// * <https://github.com/fonttools/fonttools/blob/6a13bdc2e668334b04466b288d31179df1cff7be/Lib/fontTools/varLib/iup.py#L77>
+/// * <https://learn.microsoft.com/en-us/typography/opentype/spec/gvar#inferred-deltas-for-un-referenced-point-numbers>
+pub fn iup_delta_interpolate(
+    deltas: &[Option<Vec2>],
+    coords: &[Point],
+    contour_ends: &[usize],
+) -> Result<Vec<Vec2>, IupError> {
+    let num_coords = coords.len();
+    if num_coords < NUM_PHANTOM_POINTS {
+        return Err(IupError::NotEnoughCoords(num_coords));
+    }
+    if deltas.len() != num_coords {
+        return Err(IupError::DeltaCoordLengthMismatch {
+            num_deltas: deltas.len(),
+            num_coords,
+        });
+    }
+    let mut contour_ends = contour_ends.to_vec();
+    contour_ends.sort();
+    let expected_num_coords = contour_ends
+        .last()
+        .copied()
+        .map(|v| v + 1)
+        .unwrap_or_default()
+        + NUM_PHANTOM_POINTS;
+    if num_coords != expected_num_coords {
+        return Err(IupError::CoordEndsMismatch {
+            num_coords,
+            expected_num_coords,
+        });
+    }
+    for offset in (1..=4).rev() {
+        contour_ends.push(num_coords - offset);
+    }
+
+    let mut result = Vec::with_capacity(num_coords);
+    let mut start = 0;
+    for end in contour_ends {
+        result.extend(iup_contour(&deltas[start..=end], &coords[start..=end]));
+        start = end + 1;
+    }
+    Ok(result)
+}
+
+/// Infer the missing deltas of a single contour.
+///
+/// <https://github.com/fonttools/fonttools/blob/6a13bdc2e668334b04466b288d31179df1cff7be/Lib/fontTools/varLib/iup.py#L77>
+fn iup_contour(deltas: &[Option<Vec2>], coords: &[Point]) -> Vec<Vec2> {
+    let n = deltas.len();
+    let indices: Vec<_> = (0..n).filter(|i| deltas[*i].is_some()).collect();
+    let (Some(&first), Some(&last)) = (indices.first(), indices.last()) else {
+        return vec![Vec2::ZERO; n];
+    };
+    let delta = |i: usize| deltas[i].unwrap_or_default();
+    let mut result = Vec::with_capacity(n);
+    if first != 0 {
+        result.extend(iup_segment(
+            &coords[..first],
+            coords[first],
+            delta(first),
+            coords[last],
+            delta(last),
+        ));
+    }
+    result.push(delta(first));
+    for pair in indices.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        if end - start > 1 {
+            result.extend(iup_segment(
+                &coords[start + 1..end],
+                coords[start],
+                delta(start),
+                coords[end],
+                delta(end),
+            ));
+        }
+        result.push(delta(end));
+    }
+    if last != n - 1 {
+        result.extend(iup_segment(
+            &coords[last + 1..],
+            coords[last],
+            delta(last),
+            coords[first],
+            delta(first),
+        ));
+    }
+    result
+}
+
 #[derive(Clone, Debug)]
 pub enum IupError {
     DeltaCoordLengthMismatch {
@@ -1313,4 +1413,62 @@ mod tests {
 
         iup_delta_optimize(deltas, coords, 0.5, &contour_ends).unwrap();
     }
+
+    #[test]
+    fn interpolate_missing_deltas() {
+        let coords = [(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)]
+            .into_iter()
+            .chain([(0.0, 0.0); 4])
+            .map(|(x, y)| Point::new(x, y))
+            .collect::<Vec<_>>();
+        let deltas = [
+            Some(Vec2::new(10.0, 0.0)),
+            None,
+            Some(Vec2::new(20.0, 10.0)),
+            None,
+            Some(Vec2::new(5.0, 0.0)),
+            None,
+            None,
+            None,
+        ];
+
+        let result = iup_delta_interpolate(&deltas, &coords, &[3]).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                Vec2::new(10.0, 0.0),
+                Vec2::new(20.0, 0.0),
+                Vec2::new(20.0, 10.0),
+                Vec2::new(10.0, 10.0),
+                Vec2::new(5.0, 0.0),
+                Vec2::ZERO,
+                Vec2::ZERO,
+                Vec2::ZERO,
+            ]
+        );
+    }
+
+    #[test]
+    fn interpolate_optimized_deltas() {
+        let scenario = iup_scenario4();
+        let mut deltas = scenario.deltas.clone();
+        let mut coords = scenario.coords.clone();
+        let contour_ends = [deltas.len() - 1];
+        deltas.extend([Vec2::ZERO; NUM_PHANTOM_POINTS]);
+        coords.extend([Point::ZERO; NUM_PHANTOM_POINTS]);
+
+        let optimized =
+            iup_delta_optimize(deltas.clone(), coords.clone(), 0.5, &contour_ends).unwrap();
+        let encoded = optimized
+            .iter()
+            .map(|d| d.required.then(|| Vec2::new(d.x as f64, d.y as f64)))
+            .collect::<Vec<_>>();
+        let result = iup_delta_interpolate(&encoded, &coords, &contour_ends).unwrap();
+        for (expected, actual) in deltas.iter().zip(&result) {
+            assert!(
+                (*expected - *actual).hypot() <= 1.0,
+                "{expected:?} {actual:?}"
+            );
+        }
+    }
 }
@@ -0,0 +1,101 @@
+//! The [Horizontal Device Metrics](https://learn.microsoft.com/en-us/typography/opentype/spec/hdmx) table.
+
+include!("../../generated/generated_hdmx.rs");
+
+impl Hdmx {
+    /// The size of each record, including padding to a 32-bit boundary.
+    fn compute_size_device_record(&self) -> u32 {
+        let max_widths = self
+            .records
+            .iter()
+            .map(|record| record.widths.len())
+            .max()
+            .unwrap_or_default();
+        padded_record_len(max_widths) as u32
+    }
+
+    fn check_records(&self, ctx: &mut ValidationCtx) {
+        if self
+            .records
+            .windows(2)
+            .any(|pair| pair[0].pixel_size >= pair[1].pixel_size)
+        {
+            ctx.report("records must be sorted by pixel size");
+        }
+        if self.records.windows(2).any(|pair| {
+            padded_record_len(pair[0].widths.len()) != padded_record_len(pair[1].widths.len())
+        }) {
+            ctx.report("all records must have the same number of widths");
+        }
+    }
+}
+
+impl DeviceRecord {
+    /// Construct a new record for the given size from per-glyph advance
+    /// widths, in pixels.
+    ///
+    /// The maximum width is computed from the provided widths.
+    pub fn from_widths(pixel_size: u8, widths: Vec<u8>) -> Self {
+        let max_width = widths.iter().copied().max().unwrap_or_default();
+        Self::new(pixel_size, max_width, widths)
+    }
+
+    fn compile_padded_widths(&self) -> Vec<u8> {
+        let mut widths = self.widths.clone();
+        widths.resize(padded_record_len(widths.len()) - 2, 0);
+        widths
+    }
+}
+
+/// The length of a device record with the given number of widths, including
+/// the pixel size and max width fields and padding to a 32-bit boundary.
+fn padded_record_len(num_widths: usize) -> usize {
+    (num_widths + 2 + 3) & !3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn padded_round_trip() {
+        let table = Hdmx::new(
+            0,
+            vec![
+                DeviceRecord::from_widths(9, vec![9, 10, 11]),
+                DeviceRecord::from_widths(12, vec![12, 13, 14]),
+            ],
+        );
+        assert_eq!(table.records[1].max_width, 14);
+        let bytes = crate::dump_table(&table).unwrap();
+        // header plus two records of 8 bytes
+        assert_eq!(bytes.len(), 8 + 2 * 8);
+        let read = read_fonts::tables::hdmx::Hdmx::read(bytes.as_slice().into()).unwrap();
+        assert_eq!(read.size_device_record(), 8);
+        let record = read.record_for_size(12).unwrap();
+        assert_eq!(&record.widths[..3], &[12, 13, 14]);
+        // padding is preserved when converting, but not duplicated
+        let owned = Hdmx::read(bytes.as_slice().into()).unwrap();
+        assert_eq!(crate::dump_table(&owned).unwrap(), bytes);
+    }
+
+    #[test]
+    fn validation() {
+        let unsorted = Hdmx::new(
+            0,
+            vec![
+                DeviceRecord::from_widths(12, vec![1, 2]),
+                DeviceRecord::from_widths(9, vec![1, 2]),
+            ],
+        );
+        assert!(crate::dump_table(&unsorted).is_err());
+        let mismatched = Hdmx::new(
+            0,
+            vec![
+                DeviceRecord::from_widths(9, vec![1, 2]),
+                DeviceRecord::from_widths(12, vec![1, 2, 3]),
+            ],
+        );
+        assert!(crate::dump_table(&mismatched).is_err());
+    }
+}
@@ -1,3 +1,24 @@
 //! The head table
 
 include!("../../generated/generated_head.rs");
+
+use super::{glyf::Bbox, loca::LocaFormat};
+
+impl Head {
+    /// Set the font-wide bounding box from the union of all glyph bounds.
+    ///
+    /// See [`GlyfLocaBuilder::bounds`](super::glyf::GlyfLocaBuilder::bounds).
+    pub fn set_bounds(&mut self, bbox: Bbox) {
+        self.x_min = bbox.x_min;
+        self.y_min = bbox.y_min;
+        self.x_max = bbox.x_max;
+        self.y_max = bbox.y_max;
+    }
+
+    /// Set the `indexToLocFormat` field to match the format of the 'loca' table.
+    ///
+    /// See [`GlyfLocaBuilder::build`](super::glyf::GlyfLocaBuilder::build).
+    pub fn set_loca_format(&mut self, format: LocaFormat) {
+        self.index_to_loc_format = format as i16;
+    }
+}
@@ -2,6 +2,39 @@
 
 include!("../../generated/generated_hmtx.rs");
 
+impl Hmtx {
+    /// Create a new table from the metrics of every glyph, in glyph order.
+    ///
+    /// Glyphs at the end of the font that share the advance of the last
+    /// long metric are stored as side bearings only. The caller must set
+    /// the 'hhea' table's `numberOfHMetrics` to [`Hmtx::number_of_long_metrics`],
+    /// or add the table with [`FontBuilder::add_hmtx`](crate::FontBuilder::add_hmtx).
+    pub fn from_glyph_metrics(metrics: Vec<LongMetric>) -> Self {
+        let (h_metrics, left_side_bearings) = compact_metrics(metrics);
+        Hmtx::new(h_metrics, left_side_bearings)
+    }
+
+    /// The number of long metrics, as stored in `hhea.numberOfHMetrics`.
+    pub fn number_of_long_metrics(&self) -> u16 {
+        self.h_metrics.len() as u16
+    }
+}
+
+/// Split metrics into long metrics and trailing side bearings, removing
+/// repeated advances at the end.
+pub(crate) fn compact_metrics(mut metrics: Vec<LongMetric>) -> (Vec<LongMetric>, Vec<i16>) {
+    let mut count = metrics.len();
+    while count > 1 && metrics[count - 1].advance == metrics[count - 2].advance {
+        count -= 1;
+    }
+    let side_bearings = metrics
+        .split_off(count)
+        .into_iter()
+        .map(|metric| metric.side_bearing)
+        .collect();
+    (metrics, side_bearings)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -24,4 +57,28 @@ mod tests {
         assert_eq!(loaded.h_metrics()[0].side_bearing(), -214);
         assert_eq!(loaded.left_side_bearings(), &hmtx.left_side_bearings);
     }
+
+    #[test]
+    fn compact_trailing_advances() {
+        let metrics = [(500, 10), (600, 20), (600, 30), (600, 40)]
+            .into_iter()
+            .map(|(advance, lsb)| LongMetric::new(advance, lsb))
+            .collect();
+        let hmtx = Hmtx::from_glyph_metrics(metrics);
+        assert_eq!(hmtx.number_of_long_metrics(), 2);
+        assert_eq!(
+            hmtx.h_metrics,
+            [LongMetric::new(500, 10), LongMetric::new(600, 20)]
+        );
+        assert_eq!(hmtx.left_side_bearings, [30, 40]);
+
+        // a single glyph, or all distinct advances, stay long
+        let hmtx = Hmtx::from_glyph_metrics(vec![LongMetric::new(500, 10)]);
+        assert_eq!(hmtx.number_of_long_metrics(), 1);
+        let hmtx =
+            Hmtx::from_glyph_metrics(vec![LongMetric::new(500, 10), LongMetric::new(600, 20)]);
+        assert_eq!(hmtx.number_of_long_metrics(), 2);
+        assert!(hmtx.left_side_bearings.is_empty());
+        assert_eq!(Hmtx::from_glyph_metrics(Vec::new()), Hmtx::default());
+    }
 }
@@ -2,4 +2,275 @@
 
 include!("../../generated/generated_hvar.rs");
 
-use super::variations::{DeltaSetIndexMap, ItemVariationStore};
+use super::variations::{
+    ivs_builder::VariationStoreBuilder, DeltaSetIndexMap, ItemVariationStore, VariationRegion,
+};
+use std::fmt::Display;
+
+/// A builder for the [`Hvar`] table.
+///
+/// Advance width deltas are collected per glyph. When the table is built the
+/// deltas are stored both directly (using glyph ids as implicit indices) and
+/// in an optimized store with an advance width mapping; whichever is smaller
+/// is kept.
+#[derive(Clone, Debug)]
+pub struct HvarBuilder(AdvanceDeltas);
+
+/// An error that occurs when adding advance deltas to an [`HvarBuilder`] or
+/// a [`VvarBuilder`](super::vvar::VvarBuilder).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AdvanceDeltasError {
+    /// The glyph id is not less than the number of glyphs in the font.
+    GlyphOutOfRange { glyph_id: GlyphId, glyph_count: u16 },
+    /// A region does not have one set of coordinates per axis.
+    AxisCountMismatch {
+        glyph_id: GlyphId,
+        expected: u16,
+        actual: usize,
+    },
+}
+
+/// Per-glyph advance deltas, shared by the HVAR and VVAR builders.
+#[derive(Clone, Debug)]
+pub(crate) struct AdvanceDeltas {
+    axis_count: u16,
+    deltas: Vec<Vec<(VariationRegion, i32)>>,
+}
+
+impl HvarBuilder {
+    /// Create a new builder for a font with the given number of axes and glyphs.
+    pub fn new(axis_count: u16, glyph_count: u16) -> Self {
+        Self(AdvanceDeltas::new(axis_count, glyph_count))
+    }
+
+    /// Add the advance width deltas for the glyph with the given id.
+    ///
+    /// This replaces any deltas previously added for the same glyph.
+    pub fn add_advance_deltas<T: Into<i32>>(
+        &mut self,
+        glyph_id: GlyphId,
+        deltas: Vec<(VariationRegion, T)>,
+    ) -> Result<&mut Self, AdvanceDeltasError> {
+        self.0.add(glyph_id, deltas)?;
+        Ok(self)
+    }
+
+    /// Build the table.
+    pub fn build(self) -> Hvar {
+        let (store, mapping) = self.0.build();
+        Hvar::new(MajorMinor::VERSION_1_0, store, mapping, None, None)
+    }
+}
+
+impl AdvanceDeltas {
+    pub(crate) fn new(axis_count: u16, glyph_count: u16) -> Self {
+        Self {
+            axis_count,
+            deltas: vec![Vec::new(); glyph_count as usize],
+        }
+    }
+
+    pub(crate) fn add<T: Into<i32>>(
+        &mut self,
+        glyph_id: GlyphId,
+        deltas: Vec<(VariationRegion, T)>,
+    ) -> Result<(), AdvanceDeltasError> {
+        let Some(slot) = self.deltas.get_mut(glyph_id.to_u32() as usize) else {
+            return Err(AdvanceDeltasError::GlyphOutOfRange {
+                glyph_id,
+                glyph_count: self.deltas.len() as u16,
+            });
+        };
+        if let Some(region) = deltas
+            .iter()
+            .map(|(region, _)| region)
+            .find(|region| region.region_axes.len() != self.axis_count as usize)
+        {
+            return Err(AdvanceDeltasError::AxisCountMismatch {
+                glyph_id,
+                expected: self.axis_count,
+                actual: region.region_axes.len(),
+            });
+        }
+        *slot = deltas
+            .into_iter()
+            .map(|(region, delta)| (region, delta.into()))
+            .collect();
+        Ok(())
+    }
+
+    /// Build the variation store and, if required, the advance mapping.
+    ///
+    /// The mapping is omitted when the deltas are stored directly, in glyph
+    /// order, in a single subtable.
+    pub(crate) fn build(self) -> (ItemVariationStore, Option<DeltaSetIndexMap>) {
+        let mut direct_builder = VariationStoreBuilder::new_with_implicit_indices(self.axis_count);
+        let mut mapped_builder = VariationStoreBuilder::new(self.axis_count);
+        let mut temp_ids = Vec::with_capacity(self.deltas.len());
+        for deltas in self.deltas {
+            direct_builder.add_deltas(deltas.clone());
+            temp_ids.push(mapped_builder.add_deltas(deltas));
+        }
+        let (direct_store, _) = direct_builder.build();
+        let (mapped_store, remap) = mapped_builder.build();
+
+        let mapping = temp_ids
+            .into_iter()
+            .map(|temp_id| {
+                let index = remap.get(temp_id).unwrap();
+                ((index.delta_set_outer_index as u32) << 16) | index.delta_set_inner_index as u32
+            })
+            .collect::<Vec<_>>();
+        // the optimized store may still happen to be in glyph order
+        if mapping
+            .iter()
+            .enumerate()
+            .all(|(gid, idx)| gid as u32 == *idx)
+        {
+            return (mapped_store, None);
+        }
+        let mapping = mapping.into_iter().collect::<DeltaSetIndexMap>();
+
+        let direct_size = encoded_len(&direct_store);
+        let mapped_size = encoded_len(&mapped_store) + encoded_len(&mapping);
+        if direct_size <= mapped_size {
+            (direct_store, None)
+        } else {
+            (mapped_store, Some(mapping))
+        }
+    }
+}
+
+fn encoded_len<T: FontWrite + Validate>(table: &T) -> usize {
+    crate::dump_table(table)
+        .map(|bytes| bytes.len())
+        .unwrap_or(usize::MAX)
+}
+
+impl Display for AdvanceDeltasError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdvanceDeltasError::GlyphOutOfRange {
+                glyph_id,
+                glyph_count,
+            } => write!(
+                f,
+                "glyph {glyph_id} is out of range for a font with {glyph_count} glyphs"
+            ),
+            AdvanceDeltasError::AxisCountMismatch {
+                glyph_id,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "region for glyph {glyph_id} has {actual} axes, expected {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AdvanceDeltasError {}
+
+#[cfg(test)]
+mod tests {
+    use font_types::F2Dot14;
+    use read_fonts::{tables::hvar as read_hvar, FontRead};
+
+    use crate::dump_table;
+    use crate::tables::variations::RegionAxisCoordinates;
+
+    use super::*;
+
+    fn region(min: f32, peak: f32, max: f32) -> VariationRegion {
+        VariationRegion::new(vec![RegionAxisCoordinates {
+            start_coord: F2Dot14::from_f32(min),
+            peak_coord: F2Dot14::from_f32(peak),
+            end_coord: F2Dot14::from_f32(max),
+        }])
+    }
+
+    fn read_advance_delta(hvar: &read_hvar::Hvar, gid: u16, coord: f32) -> i32 {
+        hvar.advance_width_delta(GlyphId::from(gid), &[F2Dot14::from_f32(coord)])
+            .unwrap()
+            .to_i32()
+    }
+
+    #[test]
+    fn direct_mapping_elides_index_map() {
+        let mut builder = HvarBuilder::new(1, 3);
+        builder
+            .add_advance_deltas(GlyphId::new(0), vec![(region(0.0, 1.0, 1.0), 10)])
+            .unwrap()
+            .add_advance_deltas(GlyphId::new(1), vec![(region(0.0, 1.0, 1.0), 20)])
+            .unwrap()
+            .add_advance_deltas(GlyphId::new(2), vec![(region(0.0, 1.0, 1.0), 30)])
+            .unwrap();
+        let hvar = builder.build();
+        assert!(hvar.advance_width_mapping.is_none());
+
+        let bytes = dump_table(&hvar).unwrap();
+        let read = read_hvar::Hvar::read(FontData::new(&bytes)).unwrap();
+        assert_eq!(read_advance_delta(&read, 0, 1.0), 10);
+        assert_eq!(read_advance_delta(&read, 1, 1.0), 20);
+        assert_eq!(read_advance_delta(&read, 2, 0.5), 15);
+    }
+
+    #[test]
+    fn shared_deltas_use_index_map() {
+        let glyph_count = 200;
+        let mut builder = HvarBuilder::new(1, glyph_count);
+        for gid in 0..glyph_count {
+            let deltas = if gid % 2 == 0 {
+                vec![
+                    (region(0.0, 1.0, 1.0), 1000),
+                    (region(-1.0, -1.0, 0.0), -500),
+                ]
+            } else {
+                vec![(region(0.0, 1.0, 1.0), 10)]
+            };
+            builder
+                .add_advance_deltas(GlyphId::from(gid), deltas)
+                .unwrap();
+        }
+        let hvar = builder.build();
+        assert!(hvar.advance_width_mapping.is_some());
+        // identical regions are shared across glyphs
+        let store = &hvar.item_variation_store;
+        assert_eq!(store.variation_region_list.variation_regions.len(), 2);
+
+        let bytes = dump_table(&hvar).unwrap();
+        let read = read_hvar::Hvar::read(FontData::new(&bytes)).unwrap();
+        assert_eq!(read_advance_delta(&read, 0, 1.0), 1000);
+        assert_eq!(read_advance_delta(&read, 0, -1.0), -500);
+        assert_eq!(read_advance_delta(&read, 199, 1.0), 10);
+        assert_eq!(read_advance_delta(&read, 199, -1.0), 0);
+    }
+
+    #[test]
+    fn builder_errors() {
+        let mut builder = HvarBuilder::new(1, 2);
+        assert_eq!(
+            builder
+                .add_advance_deltas(GlyphId::new(2), vec![(region(0.0, 1.0, 1.0), 1)])
+                .unwrap_err(),
+            AdvanceDeltasError::GlyphOutOfRange {
+                glyph_id: GlyphId::new(2),
+                glyph_count: 2
+            }
+        );
+        let two_axes = VariationRegion::new(vec![
+            region(0.0, 1.0, 1.0).region_axes[0].clone(),
+            region(0.0, 1.0, 1.0).region_axes[0].clone(),
+        ]);
+        assert_eq!(
+            builder
+                .add_advance_deltas(GlyphId::new(0), vec![(two_axes, 1)])
+                .unwrap_err(),
+            AdvanceDeltasError::AxisCountMismatch {
+                glyph_id: GlyphId::new(0),
+                expected: 1,
+                actual: 2
+            }
+        );
+    }
+}
@@ -3,11 +3,532 @@
 #![allow(unused_variables)]
 include!("../../generated/generated_ift.rs");
 
-use crate::FontWrite;
-use read_fonts::tables::ift::CompatibilityId;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeInclusive,
+};
+
+use crate::{FontWrite, TableWriter};
+use read_fonts::{
+    collections::IntSet,
+    tables::ift::{CompatibilityId, IFTX_TAG, IFT_TAG},
+};
 
 impl FontWrite for CompatibilityId {
     fn write_into(&self, writer: &mut TableWriter) {
         writer.write_slice(self.as_slice());
     }
 }
+
+impl TopLevelTable for Ift {
+    const TAG: Tag = IFT_TAG;
+}
+
+/// A patch map stored in the 'IFTX' table.
+///
+/// This has the same structure as [`Ift`], and exists so that a patch map
+/// can be added to a [`FontBuilder`](crate::FontBuilder) under the 'IFTX' tag.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Iftx(pub Ift);
+
+impl TopLevelTable for Iftx {
+    const TAG: Tag = IFTX_TAG;
+}
+
+impl FontWrite for Iftx {
+    fn write_into(&self, writer: &mut TableWriter) {
+        self.0.write_into(writer)
+    }
+
+    fn table_type(&self) -> TableType {
+        self.0.table_type()
+    }
+}
+
+impl Validate for Iftx {
+    fn validate_impl(&self, ctx: &mut ValidationCtx) {
+        self.0.validate_impl(ctx)
+    }
+}
+
+impl From<Ift> for Iftx {
+    fn from(src: Ift) -> Iftx {
+        Iftx(src)
+    }
+}
+
+fn convert_entry_index(obj: &read_fonts::tables::ift::GlyphMap) -> Vec<u8> {
+    obj.offset_data()
+        .as_bytes()
+        .get(obj.shape().entry_index_byte_range())
+        .unwrap_or_default()
+        .to_vec()
+}
+
+fn convert_feature_records(obj: &read_fonts::tables::ift::FeatureMap) -> Vec<u8> {
+    obj.offset_data()
+        .as_bytes()
+        .get(obj.shape().feature_records_byte_range())
+        .unwrap_or_default()
+        .to_vec()
+}
+
+/// Writes an entry index, which is a u8 if `max_entry_index` is less than 256
+/// and a u16 otherwise.
+fn write_entry_index(index: u16, max_entry_index: u16, data: &mut Vec<u8>) {
+    if max_entry_index < 256 {
+        data.push(index as u8);
+    } else {
+        data.extend_from_slice(&index.to_be_bytes());
+    }
+}
+
+/// A builder for [format 1 patch maps](PatchMapFormat1).
+///
+/// Entries are identified by their index. Glyphs are mapped to the entry
+/// that should be loaded when the glyph is needed, and feature mappings add
+/// entries that are loaded when a feature is needed along with any glyph
+/// mapped to one of the given entry ranges.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PatchMapFormat1Builder {
+    glyph_count: u32,
+    glyph_entries: BTreeMap<GlyphId, u16>,
+    // tag -> (first new entry index, entry ranges)
+    feature_entries: BTreeMap<Tag, (u16, Vec<RangeInclusive<u16>>)>,
+    applied_entries: BTreeSet<u16>,
+}
+
+impl PatchMapFormat1Builder {
+    /// Create a builder for a font with `glyph_count` glyphs.
+    pub fn new(glyph_count: u32) -> Self {
+        Self {
+            glyph_count,
+            ..Default::default()
+        }
+    }
+
+    /// Map `gid` to the entry at `entry_index`.
+    ///
+    /// Glyphs which are not mapped are assigned entry index 0, which
+    /// signifies that no entry needs to be loaded.
+    pub fn map_glyph(&mut self, gid: GlyphId, entry_index: u16) -> &mut Self {
+        self.glyph_entries.insert(gid, entry_index);
+        self
+    }
+
+    /// Add a feature mapping for `tag`.
+    ///
+    /// The i-th range in `entry_ranges` maps to the new entry index
+    /// `first_new_entry_index + i`. To be valid for clients, the new entry
+    /// indices should be larger than any entry index used in the glyph map.
+    ///
+    /// Replaces any mapping previously added for `tag`.
+    pub fn map_feature(
+        &mut self,
+        tag: Tag,
+        first_new_entry_index: u16,
+        entry_ranges: Vec<RangeInclusive<u16>>,
+    ) -> &mut Self {
+        self.feature_entries
+            .insert(tag, (first_new_entry_index, entry_ranges));
+        self
+    }
+
+    /// Mark the entry at `entry_index` as having already been applied.
+    ///
+    /// Entries larger than the largest entry index in the map are ignored.
+    pub fn mark_applied(&mut self, entry_index: u16) -> &mut Self {
+        self.applied_entries.insert(entry_index);
+        self
+    }
+
+    fn max_glyph_map_entry_index(&self) -> u16 {
+        self.glyph_entries
+            .iter()
+            .filter(|(gid, _)| gid.to_u32() < self.glyph_count)
+            .map(|(_, index)| *index)
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn max_entry_index(&self) -> u16 {
+        self.feature_entries
+            .values()
+            .filter(|(_, ranges)| !ranges.is_empty())
+            .map(|(first, ranges)| first.saturating_add(ranges.len() as u16 - 1))
+            .fold(self.max_glyph_map_entry_index(), u16::max)
+    }
+
+    fn build_glyph_map(&self, max_entry_index: u16) -> GlyphMap {
+        let first_mapped_glyph = self
+            .glyph_entries
+            .iter()
+            .find(|(_, index)| **index != 0)
+            .map(|(gid, _)| gid.to_u32())
+            .unwrap_or(self.glyph_count)
+            .min(self.glyph_count)
+            .min(u16::MAX as u32);
+        let mut entry_index = Vec::new();
+        for gid in first_mapped_glyph..self.glyph_count {
+            let index = self
+                .glyph_entries
+                .get(&GlyphId::new(gid))
+                .copied()
+                .unwrap_or_default();
+            write_entry_index(index, max_entry_index, &mut entry_index);
+        }
+        GlyphMap::new(first_mapped_glyph as u16, entry_index)
+    }
+
+    fn build_feature_map(&self, max_entry_index: u16) -> Option<FeatureMap> {
+        if self.feature_entries.is_empty() {
+            return None;
+        }
+        let mut feature_records = Vec::new();
+        let mut entry_map_data = Vec::new();
+        for (tag, (first_new_entry_index, ranges)) in &self.feature_entries {
+            feature_records.extend_from_slice(&tag.to_be_bytes());
+            write_entry_index(
+                *first_new_entry_index,
+                max_entry_index,
+                &mut feature_records,
+            );
+            write_entry_index(ranges.len() as u16, max_entry_index, &mut feature_records);
+            for range in ranges {
+                write_entry_index(*range.start(), max_entry_index, &mut entry_map_data);
+                write_entry_index(*range.end(), max_entry_index, &mut entry_map_data);
+            }
+        }
+        Some(FeatureMap::new(
+            self.feature_entries.len() as u16,
+            feature_records,
+            entry_map_data,
+        ))
+    }
+
+    /// Build the patch map.
+    pub fn build(
+        &self,
+        compatibility_id: CompatibilityId,
+        uri_template: &str,
+        patch_format: u8,
+    ) -> PatchMapFormat1 {
+        let max_entry_index = self.max_entry_index();
+        // one bit for each entry index in 0..=max_entry_index
+        let mut applied_entries_bitmap = vec![0u8; max_entry_index as usize / 8 + 1];
+        for index in self.applied_entries.range(..=max_entry_index) {
+            applied_entries_bitmap[*index as usize / 8] |= 1 << (index % 8);
+        }
+        PatchMapFormat1::new(
+            compatibility_id,
+            max_entry_index,
+            self.max_glyph_map_entry_index(),
+            Uint24::new(self.glyph_count),
+            self.build_glyph_map(max_entry_index),
+            self.build_feature_map(max_entry_index),
+            applied_entries_bitmap,
+            uri_template.len() as u16,
+            uri_template.as_bytes().to_vec(),
+            patch_format,
+        )
+    }
+}
+
+impl PatchMapFormat2 {
+    /// Construct a format 2 patch map from a list of entries.
+    ///
+    /// Entries are assigned consecutive entry ids, starting from 1.
+    ///
+    /// Returns an error if any of the entries fail validation.
+    pub fn from_entries(
+        compatibility_id: CompatibilityId,
+        default_patch_format: u8,
+        uri_template: &str,
+        entries: &[EntryData],
+    ) -> Result<Self, crate::error::Error> {
+        let mut entry_data = Vec::new();
+        for entry in entries {
+            entry_data.extend(crate::dump_table(entry)?);
+        }
+        Ok(PatchMapFormat2::new(
+            compatibility_id,
+            default_patch_format,
+            Uint24::new(entries.len() as u32),
+            MappingEntries::new(entry_data),
+            None,
+            uri_template.len() as u16,
+            uri_template.as_bytes().to_vec(),
+        ))
+    }
+}
+
+impl EntryData {
+    /// Set the codepoints which this entry matches.
+    ///
+    /// The codepoints are encoded as a sparse bit set, biased by the smallest
+    /// codepoint, and the format flags are updated to match. Passing an empty
+    /// set removes any codepoints from this entry.
+    ///
+    /// See: <https://w3c.github.io/IFT/Overview.html#mapping-entry-codepoints>
+    pub fn set_codepoints(&mut self, codepoints: &IntSet<u32>) {
+        self.format_flags
+            .remove(EntryFormatFlags::CODEPOINTS_BIT_1 | EntryFormatFlags::CODEPOINTS_BIT_2);
+        self.codepoint_data.clear();
+        let Some(bias) = codepoints.first() else {
+            return;
+        };
+        if bias == 0 {
+            self.format_flags |= EntryFormatFlags::CODEPOINTS_BIT_1;
+        } else if bias <= 0xFFFF {
+            self.format_flags |= EntryFormatFlags::CODEPOINTS_BIT_2;
+            self.codepoint_data
+                .extend_from_slice(&(bias as u16).to_be_bytes());
+        } else {
+            self.format_flags |=
+                EntryFormatFlags::CODEPOINTS_BIT_1 | EntryFormatFlags::CODEPOINTS_BIT_2;
+            self.codepoint_data
+                .extend_from_slice(&Uint24::new(bias).to_be_bytes());
+        }
+        let biased: IntSet<u32> = codepoints.iter().map(|cp| cp - bias).collect();
+        self.codepoint_data.extend(biased.to_sparse_bit_set());
+    }
+
+    /// Set the features and design space segments which this entry matches.
+    ///
+    /// This also sets the corresponding counts and format flag.
+    pub fn set_features_and_design_space(
+        &mut self,
+        feature_tags: Vec<Tag>,
+        design_space_segments: Vec<DesignSpaceSegment>,
+    ) {
+        self.format_flags |= EntryFormatFlags::FEATURES_AND_DESIGN_SPACE;
+        // overly long arrays are reported during validation
+        self.feature_count = Some(feature_tags.len() as u8);
+        self.feature_tags = Some(feature_tags);
+        self.design_space_count = Some(design_space_segments.len() as u16);
+        self.design_space_segments = Some(design_space_segments);
+    }
+
+    /// Set the indices of previous entries whose conditions are copied into
+    /// this entry.
+    pub fn set_copy_indices(&mut self, copy_indices: Vec<Uint24>) {
+        self.format_flags |= EntryFormatFlags::COPY_INDICES;
+        self.copy_count = Some(copy_indices.len() as u8);
+        self.copy_indices = Some(copy_indices);
+    }
+
+    /// Set the difference between this entry's id and the id it would
+    /// otherwise be assigned (one more than the previous entry's id).
+    ///
+    /// Entries are written without id strings, so the delta is stored as an
+    /// Int24.
+    ///
+    /// See: <https://w3c.github.io/IFT/Overview.html#mapping-entry-entryiddelta>
+    pub fn set_entry_id_delta(&mut self, delta: Int24) {
+        self.format_flags |= EntryFormatFlags::ENTRY_ID_DELTA;
+        self.entry_id_delta = Some(delta);
+    }
+
+    /// Set the format of the patch referenced by this entry, overriding the
+    /// default format of the patch map.
+    pub fn set_patch_format(&mut self, patch_format: u8) {
+        self.format_flags |= EntryFormatFlags::PATCH_FORMAT;
+        self.patch_format = Some(patch_format);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use read_fonts::{FontData, FontRead, FontReadWithArgs};
+
+    fn compat_id() -> CompatibilityId {
+        CompatibilityId::from_u32s([1, 2, 3, 4])
+    }
+
+    #[test]
+    fn format1_glyph_map() {
+        let mut builder = PatchMapFormat1Builder::new(6);
+        builder
+            .map_glyph(GlyphId::new(2), 1)
+            .map_glyph(GlyphId::new(4), 2)
+            .mark_applied(1);
+        let table = Ift::Format1(builder.build(compat_id(), "{id}", 1));
+        let bytes = crate::dump_table(&table).unwrap();
+
+        let read_fonts::tables::ift::Ift::Format1(map) =
+            read_fonts::tables::ift::Ift::read(FontData::new(&bytes)).unwrap()
+        else {
+            panic!("expected format 1");
+        };
+        assert_eq!(map.compatibility_id(), compat_id());
+        assert_eq!(map.max_entry_index(), 2);
+        assert_eq!(map.max_glyph_map_entry_index(), 2);
+        assert_eq!(map.glyph_count().to_u32(), 6);
+        assert_eq!(map.glyph_map().unwrap().first_mapped_glyph(), 2);
+        assert_eq!(
+            map.gid_to_entry_iter().collect::<Vec<_>>(),
+            vec![(GlyphId::new(2), 1), (GlyphId::new(4), 2)]
+        );
+        assert!(map.feature_map().is_none());
+        assert!(!map.is_entry_applied(0));
+        assert!(map.is_entry_applied(1));
+        assert!(!map.is_entry_applied(2));
+        assert_eq!(map.uri_template_as_string().unwrap(), "{id}");
+        assert_eq!(map.patch_format(), 1);
+
+        // round trips through the owned type
+        let owned = Ift::read(FontData::new(&bytes)).unwrap();
+        assert_eq!(owned, table);
+    }
+
+    #[test]
+    fn format1_feature_map_u16_entries() {
+        let mut builder = PatchMapFormat1Builder::new(3);
+        builder
+            .map_glyph(GlyphId::new(1), 300)
+            .map_feature(Tag::new(b"liga"), 400, vec![1..=300, 5..=5])
+            .map_feature(Tag::new(b"dlig"), 302, vec![300..=300]);
+        let bytes = crate::dump_table(&Ift::Format1(builder.build(compat_id(), "", 2))).unwrap();
+
+        let read_fonts::tables::ift::Ift::Format1(map) =
+            read_fonts::tables::ift::Ift::read(FontData::new(&bytes)).unwrap()
+        else {
+            panic!("expected format 1");
+        };
+        assert_eq!(map.max_entry_index(), 401);
+        assert_eq!(map.max_glyph_map_entry_index(), 300);
+        assert_eq!(
+            map.gid_to_entry_iter().collect::<Vec<_>>(),
+            vec![(GlyphId::new(1), 300)]
+        );
+
+        let feature_map = map.feature_map().unwrap().unwrap();
+        assert_eq!(feature_map.feature_count(), 2);
+        let records = feature_map
+            .feature_records()
+            .iter()
+            .map(|record| {
+                let record = record.unwrap();
+                (
+                    record.feature_tag(),
+                    record.first_new_entry_index().get(),
+                    record.entry_map_count().get(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            records,
+            vec![(Tag::new(b"dlig"), 302, 1), (Tag::new(b"liga"), 400, 2)]
+        );
+        assert_eq!(
+            feature_map.entry_map_data(),
+            &[1, 44, 1, 44, 0, 1, 1, 44, 0, 5, 0, 5]
+        );
+    }
+
+    #[test]
+    fn format2_entries() {
+        let mut first = EntryData::default();
+        first.set_codepoints(&[0x20, 0x21, 0x30].into_iter().collect());
+        let mut second = EntryData::default();
+        second.set_features_and_design_space(
+            vec![Tag::new(b"liga")],
+            vec![DesignSpaceSegment::new(
+                Tag::new(b"wght"),
+                Fixed::from_i32(100),
+                Fixed::from_i32(200),
+            )],
+        );
+        second.set_codepoints(&[0x10FFFF].into_iter().collect());
+        second.set_patch_format(1);
+        let mut third = EntryData::default();
+        third.set_copy_indices(vec![Uint24::new(0), Uint24::new(1)]);
+        third.set_codepoints(&[0, 5].into_iter().collect());
+
+        let entries = [first, second, third];
+        let map = PatchMapFormat2::from_entries(compat_id(), 2, "{id}", &entries).unwrap();
+        let bytes = crate::dump_table(&Iftx(Ift::Format2(map))).unwrap();
+
+        let read_fonts::tables::ift::Ift::Format2(map) =
+            read_fonts::tables::ift::Ift::read(FontData::new(&bytes)).unwrap()
+        else {
+            panic!("expected format 2");
+        };
+        assert_eq!(map.default_patch_format(), 2);
+        assert_eq!(map.entry_count().to_u32(), 3);
+        assert_eq!(map.uri_template_as_string().unwrap(), "{id}");
+
+        let entry_data = entries
+            .iter()
+            .flat_map(|entry| crate::dump_table(entry).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(map.entries().unwrap().entry_data(), entry_data);
+    }
+
+    #[test]
+    fn entry_data_round_trip() {
+        let mut entry = EntryData::default();
+        entry.set_features_and_design_space(
+            vec![Tag::new(b"liga"), Tag::new(b"smcp")],
+            vec![DesignSpaceSegment::new(
+                Tag::new(b"wght"),
+                Fixed::from_i32(100),
+                Fixed::from_i32(200),
+            )],
+        );
+        entry.set_copy_indices(vec![Uint24::new(3)]);
+        entry.set_entry_id_delta(Int24::new(-2));
+        entry.set_patch_format(3);
+        entry.set_codepoints(&[0x30, 0x31, 0x40].into_iter().collect());
+        let bytes = crate::dump_table(&entry).unwrap();
+
+        let read = read_fonts::tables::ift::EntryData::read_with_args(
+            FontData::new(&bytes),
+            &Offset32::new(0),
+        )
+        .unwrap();
+        assert_eq!(read.format_flags(), entry.format_flags);
+        assert_eq!(
+            read.feature_tags()
+                .unwrap()
+                .iter()
+                .map(|tag| tag.get())
+                .collect::<Vec<_>>(),
+            [Tag::new(b"liga"), Tag::new(b"smcp")]
+        );
+        assert_eq!(read.design_space_segments().unwrap().len(), 1);
+        assert_eq!(read.copy_indices().unwrap()[0].get(), Uint24::new(3));
+        assert_eq!(
+            read.entry_id_delta().map(|delta| delta.into_inner()),
+            Some(-2)
+        );
+        assert_eq!(read.patch_format(), Some(3));
+
+        let (bias, data) = read.codepoint_data().split_at(2);
+        assert_eq!(bias, &[0, 0x30]);
+        let expected: IntSet<u32> = [0, 1, 0x10].into_iter().collect();
+        assert_eq!(data, expected.to_sparse_bit_set());
+    }
+
+    #[test]
+    fn set_codepoints_replaces_previous() {
+        let mut entry = EntryData::default();
+        entry.set_codepoints(&[0x10000, 0x10001].into_iter().collect());
+        assert_eq!(
+            entry.format_flags,
+            EntryFormatFlags::CODEPOINTS_BIT_1 | EntryFormatFlags::CODEPOINTS_BIT_2
+        );
+        assert_eq!(&entry.codepoint_data[..3], &[1, 0, 0]);
+
+        entry.set_codepoints(&[0, 1].into_iter().collect());
+        assert_eq!(entry.format_flags, EntryFormatFlags::CODEPOINTS_BIT_1);
+        let expected: IntSet<u32> = [0, 1].into_iter().collect();
+        assert_eq!(entry.codepoint_data, expected.to_sparse_bit_set());
+
+        entry.set_codepoints(&IntSet::empty());
+        assert!(entry.format_flags.is_empty());
+        assert!(entry.codepoint_data.is_empty());
+    }
+}
@@ -8,6 +8,7 @@ use std::{
 pub use read_fonts::tables::layout::LookupFlag;
 use read_fonts::FontRead;
 
+pub mod builders;
 #[cfg(test)]
 #[path = "../tests/layout.rs"]
 mod spec_tests;
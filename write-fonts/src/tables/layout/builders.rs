@@ -0,0 +1,133 @@
+//! Common utilities for building layout lookups.
+
+use super::{Device, DeviceOrVariationIndex, Lookup, LookupFlag};
+
+/// The largest size a table can have if every offset into it must fit in 16 bits.
+///
+/// Builders use this as a (conservative) limit when deciding whether to
+/// split their contents across multiple subtables.
+pub(crate) const MAX_TABLE_SIZE: usize = u16::MAX as usize;
+
+/// A trait for types that build layout subtables.
+///
+/// A single builder may produce multiple subtables, for instance if its
+/// contents would not fit in a single subtable.
+pub trait Builder {
+    type Output;
+
+    /// Finalize the builder, producing the output.
+    fn build(self) -> Self::Output;
+}
+
+/// A builder for a [`Lookup`] containing any number of subtable builders.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LookupBuilder<T> {
+    pub flags: LookupFlag,
+    pub mark_set: Option<u16>,
+    pub subtables: Vec<T>,
+}
+
+impl<T: Default> LookupBuilder<T> {
+    /// Create a new builder with the provided flags and mark filtering set.
+    ///
+    /// If `mark_set` is provided, the `USE_MARK_FILTERING_SET` flag is set
+    /// automatically.
+    pub fn new(flags: LookupFlag, mark_set: Option<u16>) -> Self {
+        Self {
+            flags,
+            mark_set,
+            subtables: vec![Default::default()],
+        }
+    }
+
+    /// The current (last) subtable.
+    pub fn last_mut(&mut self) -> &mut T {
+        if self.subtables.is_empty() {
+            self.subtables.push(Default::default());
+        }
+        self.subtables.last_mut().unwrap()
+    }
+
+    /// Force subsequent additions to go into a new subtable.
+    pub fn force_subtable_break(&mut self) {
+        self.subtables.push(Default::default());
+    }
+}
+
+impl<T, U> Builder for LookupBuilder<T>
+where
+    T: Builder<Output = Vec<U>>,
+    U: Default,
+{
+    type Output = Lookup<U>;
+
+    fn build(self) -> Self::Output {
+        let mut flags = LookupFlag::from_bits_truncate(
+            self.flags.to_bits() & !LookupFlag::USE_MARK_FILTERING_SET.to_bits(),
+        );
+        if self.mark_set.is_some() {
+            flags |= LookupFlag::USE_MARK_FILTERING_SET;
+        }
+        let subtables = self
+            .subtables
+            .into_iter()
+            .flat_map(Builder::build)
+            .collect::<Vec<_>>();
+        let mut lookup = Lookup::new(flags, subtables);
+        lookup.mark_filtering_set = self.mark_set;
+        lookup
+    }
+}
+
+/// Group items so that the estimated size of each group fits in a table.
+///
+/// `header_len` is the fixed size of each table and `item_len` returns the
+/// number of bytes contributed by each item. A group always contains at
+/// least one item, even if that item alone exceeds the limit.
+pub(crate) fn split_by_size<T>(
+    items: impl IntoIterator<Item = T>,
+    header_len: usize,
+    item_len: impl Fn(&T) -> usize,
+) -> Vec<Vec<T>> {
+    let mut groups = Vec::new();
+    let mut current = Vec::new();
+    let mut current_len = header_len;
+    for item in items {
+        let len = item_len(&item);
+        if !current.is_empty() && current_len + len > MAX_TABLE_SIZE {
+            groups.push(std::mem::take(&mut current));
+            current_len = header_len;
+        }
+        current_len += len;
+        current.push(item);
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+/// The encoded size of a device or variation index table.
+pub(crate) fn device_len(device: &DeviceOrVariationIndex) -> usize {
+    match device {
+        DeviceOrVariationIndex::Device(Device { delta_value, .. }) => 6 + delta_value.len() * 2,
+        DeviceOrVariationIndex::VariationIndex(_)
+        | DeviceOrVariationIndex::PendingVariationIndex(_) => 6,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_groups() {
+        let items = vec![30_000usize, 30_000, 10_000, 1];
+        let groups = split_by_size(items, 10, |len| *len);
+        assert_eq!(groups, vec![vec![30_000, 30_000], vec![10_000, 1]]);
+
+        // an oversized item still gets its own group
+        let groups = split_by_size(vec![1usize, 70_000, 1], 0, |len| *len);
+        assert_eq!(groups, vec![vec![1], vec![70_000], vec![1]]);
+    }
+}
@@ -0,0 +1,18 @@
+//! The [LTSH (Linear Threshold)](https://learn.microsoft.com/en-us/typography/opentype/spec/ltsh) table
+
+include!("../../generated/generated_ltsh.rs");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let table = Ltsh::new(vec![1, 12, 255]);
+        let bytes = crate::dump_table(&table).unwrap();
+        assert_eq!(bytes, [0, 0, 0, 3, 1, 12, 255]);
+        let read = read_fonts::tables::ltsh::Ltsh::read(bytes.as_slice().into()).unwrap();
+        assert_eq!(read.y_pel(GlyphId::new(1)), Some(12));
+        assert_eq!(Ltsh::read(bytes.as_slice().into()).unwrap(), table);
+    }
+}
@@ -2,7 +2,30 @@
 
 include!("../../generated/generated_maxp.rs");
 
+use read_fonts::tables::{
+    glyf::{Glyf, InstructionStats},
+    loca::Loca,
+};
+
 impl Maxp {
+    /// Recompute `max_size_of_instructions` from the glyphs in the given
+    /// glyf and loca tables.
+    ///
+    /// This should be called after glyph instructions have been edited (for
+    /// example by patching or subsetting). Since this sets a version 1.0 field,
+    /// it should only be used for fonts with TrueType outlines.
+    ///
+    /// Returns the computed instruction statistics.
+    pub fn recalc_max_size_of_instructions(
+        &mut self,
+        loca: &Loca,
+        glyf: &Glyf,
+    ) -> Result<InstructionStats, ReadError> {
+        let stats = InstructionStats::new(loca, glyf)?;
+        self.max_size_of_instructions = Some(stats.max_size);
+        Ok(stats)
+    }
+
     fn compute_version(&self) -> Version16Dot16 {
         if self.max_points.is_some()
             || self.max_contours.is_some()
@@ -28,6 +51,7 @@ impl Maxp {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use read_fonts::{FontRef, TableProvider};
 
     #[test]
     fn maxp_05() {
@@ -72,4 +96,18 @@ mod tests {
         assert_eq!(loaded.max_zones(), Some(10));
         assert_eq!(loaded.max_component_depth(), Some(18));
     }
+
+    #[test]
+    fn recalc_max_size_of_instructions() {
+        let font = FontRef::new(font_test_data::TTHINT_SUBSET).unwrap();
+        let loca = font.loca(None).unwrap();
+        let glyf = font.glyf().unwrap();
+        let mut maxp: Maxp = font.maxp().unwrap().to_owned_table();
+        // the font was subset without updating maxp
+        assert_eq!(maxp.max_size_of_instructions, Some(19419));
+
+        let stats = maxp.recalc_max_size_of_instructions(&loca, &glyf).unwrap();
+        assert_eq!(maxp.max_size_of_instructions, Some(12233));
+        assert_eq!(stats.total_size, 12233 + 11941);
+    }
 }
@@ -50,6 +50,51 @@ impl Display for InvalidScriptLangTag {
 
 impl std::error::Error for InvalidScriptLangTag {}
 
+impl Meta {
+    /// The languages and scripts that the font was primarily designed for,
+    /// from the 'dlng' metadata.
+    pub fn design_languages(&self) -> &[ScriptLangTag] {
+        self.script_lang_tags(DLNG)
+    }
+
+    /// The languages and scripts that the font is capable of supporting,
+    /// from the 'slng' metadata.
+    pub fn supported_languages(&self) -> &[ScriptLangTag] {
+        self.script_lang_tags(SLNG)
+    }
+
+    /// Set the 'dlng' metadata, replacing any existing value.
+    pub fn set_design_languages(&mut self, langs: Vec<ScriptLangTag>) {
+        self.set_metadata(DLNG, Metadata::ScriptLangTags(langs));
+    }
+
+    /// Set the 'slng' metadata, replacing any existing value.
+    pub fn set_supported_languages(&mut self, langs: Vec<ScriptLangTag>) {
+        self.set_metadata(SLNG, Metadata::ScriptLangTags(langs));
+    }
+
+    /// Set the metadata for the given tag, replacing any existing value.
+    ///
+    /// Records are kept sorted by tag.
+    pub fn set_metadata(&mut self, tag: Tag, data: Metadata) {
+        match self.data_maps.binary_search_by_key(&tag, |rec| rec.tag) {
+            Ok(idx) => self.data_maps[idx].data = OffsetMarker::new(data),
+            Err(idx) => self.data_maps.insert(idx, DataMapRecord::new(tag, data)),
+        }
+    }
+
+    fn script_lang_tags(&self, tag: Tag) -> &[ScriptLangTag] {
+        self.data_maps
+            .iter()
+            .find(|rec| rec.tag == tag)
+            .and_then(|rec| match rec.data.as_ref() {
+                Metadata::ScriptLangTags(langs) => Some(langs.as_slice()),
+                Metadata::Other(_) => None,
+            })
+            .unwrap_or_default()
+    }
+}
+
 impl DataMapRecord {
     fn validate_data_type(&self, ctx: &mut ValidationCtx) {
         if matches!(
@@ -168,4 +213,33 @@ mod tests {
 
         assert_eq!(table, read_back);
     }
+
+    #[test]
+    fn language_accessors() {
+        let lang = |s: &str| ScriptLangTag::new(s.into()).unwrap();
+        let mut table = Meta::default();
+        assert!(table.design_languages().is_empty());
+        table.set_supported_languages(vec![lang("latn"), lang("cyrl")]);
+        table.set_design_languages(vec![lang("en-latn")]);
+        table.set_design_languages(vec![lang("en-latn"), lang("ja")]);
+        assert_eq!(table.data_maps.len(), 2);
+        assert_eq!(table.data_maps[0].tag, DLNG);
+
+        let bytes = crate::dump_table(&table).unwrap();
+        let read = read_fonts::tables::meta::Meta::read(bytes.as_slice().into()).unwrap();
+        let design = read
+            .design_languages()
+            .map(|x| x.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(design, ["en-latn", "ja"]);
+        let supported = read
+            .supported_languages()
+            .map(|x| x.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(supported, ["latn", "cyrl"]);
+
+        let read_back = Meta::read(bytes.as_slice().into()).unwrap();
+        assert_eq!(read_back.design_languages(), table.design_languages());
+        assert_eq!(read_back.supported_languages(), table.supported_languages());
+    }
 }
@@ -2,8 +2,174 @@
 
 include!("../../generated/generated_mvar.rs");
 
-use super::variations::ItemVariationStore;
-use std::mem::size_of;
+use super::variations::{ivs_builder::VariationStoreBuilder, ItemVariationStore, VariationRegion};
+use std::{collections::BTreeMap, fmt::Display, mem::size_of};
+
+pub use read_fonts::tables::mvar::tags;
+
+/// The value tags defined by the specification.
+///
+/// See <https://learn.microsoft.com/en-us/typography/opentype/spec/mvar#value-tags>
+const KNOWN_VALUE_TAGS: &[Tag] = &[
+    tags::HASC,
+    tags::HDSC,
+    tags::HLGP,
+    tags::HCLA,
+    tags::HCLD,
+    tags::VASC,
+    tags::VDSC,
+    tags::VLGP,
+    tags::HCRS,
+    tags::HCRN,
+    tags::HCOF,
+    tags::VCRS,
+    tags::VCRN,
+    tags::VCOF,
+    tags::XHGT,
+    tags::CPHT,
+    tags::SBXO,
+    tags::SBYO,
+    tags::SBXS,
+    tags::SBYS,
+    tags::SPXO,
+    tags::SPYO,
+    tags::SPXS,
+    tags::SPYS,
+    tags::STRS,
+    tags::STRO,
+    tags::UNDS,
+    tags::UNDO,
+    tags::GSP0,
+    tags::GSP1,
+    tags::GSP2,
+    tags::GSP3,
+    tags::GSP4,
+    tags::GSP5,
+    tags::GSP6,
+    tags::GSP7,
+    tags::GSP8,
+    tags::GSP9,
+];
+
+/// A builder for the [`Mvar`] table.
+///
+/// Deltas are collected per value tag and stored in a shared
+/// [`ItemVariationStore`] when the table is built.
+#[derive(Clone, Debug)]
+pub struct MvarBuilder {
+    axis_count: u16,
+    deltas: BTreeMap<Tag, Vec<(VariationRegion, i32)>>,
+}
+
+/// An error that occurs when adding deltas to an [`MvarBuilder`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MvarBuilderError {
+    /// The tag is not one of the value tags defined by the specification.
+    UnknownTag(Tag),
+    /// A region does not have one set of coordinates per axis.
+    AxisCountMismatch {
+        tag: Tag,
+        expected: u16,
+        actual: usize,
+    },
+}
+
+impl MvarBuilder {
+    /// Create a new builder for a font with the given number of axes.
+    pub fn new(axis_count: u16) -> Self {
+        Self {
+            axis_count,
+            deltas: Default::default(),
+        }
+    }
+
+    /// Returns `true` if the tag is one of the value tags defined by the
+    /// specification.
+    pub fn is_known_tag(tag: Tag) -> bool {
+        KNOWN_VALUE_TAGS.contains(&tag)
+    }
+
+    /// Add the deltas for the value with the given tag.
+    ///
+    /// This replaces any deltas previously added for the same tag.
+    pub fn add_deltas<T: Into<i32>>(
+        &mut self,
+        tag: Tag,
+        deltas: Vec<(VariationRegion, T)>,
+    ) -> Result<&mut Self, MvarBuilderError> {
+        if !Self::is_known_tag(tag) {
+            return Err(MvarBuilderError::UnknownTag(tag));
+        }
+        if let Some(region) = deltas
+            .iter()
+            .map(|(region, _)| region)
+            .find(|region| region.region_axes.len() != self.axis_count as usize)
+        {
+            return Err(MvarBuilderError::AxisCountMismatch {
+                tag,
+                expected: self.axis_count,
+                actual: region.region_axes.len(),
+            });
+        }
+        let deltas = deltas
+            .into_iter()
+            .map(|(region, delta)| (region, delta.into()))
+            .collect();
+        self.deltas.insert(tag, deltas);
+        Ok(self)
+    }
+
+    /// Returns `true` if no deltas have been added.
+    pub fn is_empty(&self) -> bool {
+        self.deltas.is_empty()
+    }
+
+    /// Build the table.
+    ///
+    /// Value records are sorted by tag, as required by the specification.
+    pub fn build(self) -> Mvar {
+        if self.deltas.is_empty() {
+            return Mvar::new(MajorMinor::VERSION_1_0, None, Vec::new());
+        }
+        let mut store_builder = VariationStoreBuilder::new(self.axis_count);
+        let temp_ids = self
+            .deltas
+            .into_iter()
+            .map(|(tag, deltas)| (tag, store_builder.add_deltas(deltas)))
+            .collect::<Vec<_>>();
+        let (store, remap) = store_builder.build();
+        let value_records = temp_ids
+            .into_iter()
+            .map(|(tag, temp_id)| {
+                let index = remap.get(temp_id).unwrap();
+                ValueRecord::new(
+                    tag,
+                    index.delta_set_outer_index,
+                    index.delta_set_inner_index,
+                )
+            })
+            .collect();
+        Mvar::new(MajorMinor::VERSION_1_0, Some(store), value_records)
+    }
+}
+
+impl Display for MvarBuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MvarBuilderError::UnknownTag(tag) => write!(f, "unknown MVAR value tag '{tag}'"),
+            MvarBuilderError::AxisCountMismatch {
+                tag,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "region for '{tag}' has {actual} axes, expected {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MvarBuilderError {}
 
 impl Mvar {
     /// Construct a new `MVAR` table.
@@ -135,4 +301,60 @@ mod tests {
         assert_eq!(read_metric_delta(&read, b"hdsc", &[0.5]), -20.0);
         assert_eq!(read_metric_delta(&read, b"hdsc", &[1.0]), -30.0);
     }
+
+    #[test]
+    fn builder() {
+        let [r1, r2, r3] = test_regions();
+        let mut builder = MvarBuilder::new(1);
+        builder
+            .add_deltas(tags::XHGT, vec![(r1.clone(), 8i16)])
+            .unwrap()
+            .add_deltas(tags::HASC, vec![(r2, -20), (r3, -30)])
+            .unwrap()
+            .add_deltas(tags::CPHT, vec![(r1, 8)])
+            .unwrap();
+        let table = builder.build();
+
+        let bytes = dump_table(&table).unwrap();
+        let read = read_mvar::Mvar::read(FontData::new(&bytes)).unwrap();
+        let value_tags = read
+            .value_records()
+            .iter()
+            .map(|rec| rec.value_tag())
+            .collect::<Vec<_>>();
+        assert_eq!(value_tags, [tags::CPHT, tags::HASC, tags::XHGT]);
+        // identical deltas are shared
+        assert_value_record(
+            &read.value_records()[0],
+            ValueRecord::new(
+                tags::CPHT,
+                read.value_records()[2].delta_set_outer_index(),
+                read.value_records()[2].delta_set_inner_index(),
+            ),
+        );
+        assert_eq!(read_metric_delta(&read, b"xhgt", &[0.5]), 4.0);
+        assert_eq!(read_metric_delta(&read, b"hasc", &[1.0]), -30.0);
+    }
+
+    #[test]
+    fn builder_validation() {
+        let [r1, ..] = test_regions();
+        let mut builder = MvarBuilder::new(2);
+        assert!(MvarBuilder::new(2).build().item_variation_store.is_none());
+        assert_eq!(
+            builder
+                .add_deltas(Tag::new(b"asc "), vec![(r1.clone(), 1)])
+                .err(),
+            Some(MvarBuilderError::UnknownTag(Tag::new(b"asc ")))
+        );
+        assert_eq!(
+            builder.add_deltas(tags::HASC, vec![(r1, 1)]).err(),
+            Some(MvarBuilderError::AxisCountMismatch {
+                tag: tags::HASC,
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert!(builder.is_empty());
+    }
 }
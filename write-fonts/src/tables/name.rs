@@ -1,8 +1,294 @@
 //! The name table
 
 include!("../../generated/generated_name.rs");
+use std::{collections::BTreeMap, fmt::Display};
+
 use read_fonts::tables::name::{Encoding, MacRomanMapping};
 
+const MAC_PLATFORM_ID: u16 = 1;
+const MAC_ROMAN_ENCODING_ID: u16 = 0;
+const MAC_ENGLISH_LANGUAGE_ID: u16 = 0;
+const WINDOWS_PLATFORM_ID: u16 = 3;
+const WINDOWS_UNICODE_BMP_ENCODING_ID: u16 = 1;
+const WINDOWS_ENGLISH_US_LANGUAGE_ID: u16 = 0x409;
+
+/// The maximum length of a PostScript name, in characters.
+const MAX_POSTSCRIPT_NAME_LEN: usize = 63;
+
+/// The range of name ids available for font-specific names.
+const FONT_SPECIFIC_NAME_IDS: std::ops::RangeInclusive<u16> = 256..=32767;
+
+/// The styles that can be expressed with the legacy family and subfamily
+/// names (ids 1 and 2).
+const RIBBI_STYLES: [&str; 4] = ["Regular", "Italic", "Bold", "Bold Italic"];
+
+/// A builder for the [`Name`] table.
+///
+/// Strings added with [`add_name`](Self::add_name) generate a Windows
+/// (Unicode BMP, US English) record and, unless disabled, a Macintosh
+/// (Roman, English) record when the string is representable in Mac Roman.
+/// Each record key can only have one string; adding it again replaces the
+/// previous value. Records whose encoded strings are identical share
+/// storage in the compiled table.
+#[derive(Clone, Debug)]
+pub struct NameBuilder {
+    names: BTreeMap<NameId, String>,
+    records: BTreeMap<(u16, u16, u16, NameId), String>,
+    mac_names: bool,
+}
+
+/// An error that occurs when adding names to a [`NameBuilder`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NameBuilderError {
+    /// The encoded string does not fit in the 16-bit length field.
+    TooLong { name_id: NameId, length: usize },
+    /// The string contains characters that cannot be represented in the
+    /// record's encoding.
+    Unencodable {
+        platform_id: u16,
+        encoding_id: u16,
+        name_id: NameId,
+    },
+    /// The PostScript name is too long or contains disallowed characters.
+    InvalidPostScriptName(String),
+    /// All of the font-specific name ids (256 to 32767) are in use.
+    NoFreeNameIds,
+}
+
+impl NameBuilder {
+    /// Create a new, empty builder.
+    pub fn new() -> Self {
+        Self {
+            names: Default::default(),
+            records: Default::default(),
+            mac_names: true,
+        }
+    }
+
+    /// Set whether Macintosh records are generated for names added with
+    /// [`add_name`](Self::add_name).
+    ///
+    /// These are enabled by default.
+    pub fn include_mac_names(&mut self, include: bool) -> &mut Self {
+        self.mac_names = include;
+        self
+    }
+
+    /// Add an English name for the Windows and Macintosh platforms.
+    ///
+    /// An explicit record added with [`add_record`](Self::add_record) takes
+    /// precedence over the record generated here.
+    pub fn add_name(
+        &mut self,
+        name_id: NameId,
+        string: impl Into<String>,
+    ) -> Result<&mut Self, NameBuilderError> {
+        let string = string.into();
+        check_string(Encoding::Utf16Be, name_id, &string)?;
+        self.names.insert(name_id, string);
+        Ok(self)
+    }
+
+    /// Return the id of a font-specific name with this string, adding it if
+    /// it does not already exist.
+    ///
+    /// New names are assigned the id following the highest existing
+    /// font-specific name (or 256, if there are none.) This is intended for
+    /// names that are referenced by other tables, such as axis and instance
+    /// names in `fvar`.
+    pub fn find_or_add_name(&mut self, string: &str) -> Result<NameId, NameBuilderError> {
+        let font_specific = self
+            .names
+            .range(NameId::new(*FONT_SPECIFIC_NAME_IDS.start())..);
+        if let Some(name_id) = font_specific
+            .clone()
+            .find_map(|(name_id, existing)| (existing == string).then_some(*name_id))
+        {
+            return Ok(name_id);
+        }
+        let next_id = font_specific
+            .last()
+            .map(|(name_id, _)| name_id.to_u16().saturating_add(1))
+            .unwrap_or(*FONT_SPECIFIC_NAME_IDS.start());
+        if !FONT_SPECIFIC_NAME_IDS.contains(&next_id) {
+            return Err(NameBuilderError::NoFreeNameIds);
+        }
+        let name_id = NameId::new(next_id);
+        self.add_name(name_id, string)?;
+        Ok(name_id)
+    }
+
+    /// Add a record for a specific platform, encoding and language.
+    pub fn add_record(
+        &mut self,
+        platform_id: u16,
+        encoding_id: u16,
+        language_id: u16,
+        name_id: NameId,
+        string: impl Into<String>,
+    ) -> Result<&mut Self, NameBuilderError> {
+        let string = string.into();
+        let encoding = Encoding::new(platform_id, encoding_id);
+        let unencodable = match encoding {
+            Encoding::Unknown => true,
+            Encoding::MacRoman => string.chars().any(|c| MacRomanMapping.encode(c).is_none()),
+            Encoding::Utf16Be => false,
+        };
+        if unencodable {
+            return Err(NameBuilderError::Unencodable {
+                platform_id,
+                encoding_id,
+                name_id,
+            });
+        }
+        check_string(encoding, name_id, &string)?;
+        self.records
+            .insert((platform_id, encoding_id, language_id, name_id), string);
+        Ok(self)
+    }
+
+    /// Add the family, subfamily, full and PostScript names (ids 1, 2, 4
+    /// and 6) for a font with the given family and style.
+    ///
+    /// If the style is not one of "Regular", "Italic", "Bold" or
+    /// "Bold Italic", the words of the style other than "Bold" and "Italic"
+    /// are moved to the legacy family name, and the typographic family and
+    /// subfamily names (ids 16 and 17) are added as well.
+    pub fn add_family_and_style(
+        &mut self,
+        family: &str,
+        style: &str,
+    ) -> Result<&mut Self, NameBuilderError> {
+        if RIBBI_STYLES.contains(&style) {
+            self.add_name(NameId::FAMILY_NAME, family)?
+                .add_name(NameId::SUBFAMILY_NAME, style)?;
+        } else {
+            let words = style.split_whitespace().collect::<Vec<_>>();
+            let bold = words.contains(&"Bold");
+            let italic = words.contains(&"Italic");
+            let legacy_style = match (bold, italic) {
+                (false, false) => "Regular",
+                (false, true) => "Italic",
+                (true, false) => "Bold",
+                (true, true) => "Bold Italic",
+            };
+            let legacy_family = words
+                .iter()
+                .filter(|word| !matches!(**word, "Bold" | "Italic"))
+                .fold(family.to_string(), |family, word| family + " " + word);
+            self.add_name(NameId::FAMILY_NAME, legacy_family)?
+                .add_name(NameId::SUBFAMILY_NAME, legacy_style)?
+                .add_name(NameId::TYPOGRAPHIC_FAMILY_NAME, family)?
+                .add_name(NameId::TYPOGRAPHIC_SUBFAMILY_NAME, style)?;
+        }
+        let postscript_name = format!("{family}-{style}")
+            .chars()
+            .filter(|c| is_postscript_char(*c))
+            .collect::<String>();
+        self.add_name(NameId::FULL_NAME, format!("{family} {style}"))?
+            .add_name(NameId::POSTSCRIPT_NAME, postscript_name)
+    }
+
+    /// Build the table.
+    ///
+    /// Records are sorted, as required by the specification.
+    pub fn build(&self) -> Name {
+        let mut records = self.records.clone();
+        for (name_id, string) in &self.names {
+            records
+                .entry((
+                    WINDOWS_PLATFORM_ID,
+                    WINDOWS_UNICODE_BMP_ENCODING_ID,
+                    WINDOWS_ENGLISH_US_LANGUAGE_ID,
+                    *name_id,
+                ))
+                .or_insert_with(|| string.clone());
+            if self.mac_names && string.chars().all(|c| MacRomanMapping.encode(c).is_some()) {
+                records
+                    .entry((
+                        MAC_PLATFORM_ID,
+                        MAC_ROMAN_ENCODING_ID,
+                        MAC_ENGLISH_LANGUAGE_ID,
+                        *name_id,
+                    ))
+                    .or_insert_with(|| string.clone());
+            }
+        }
+        Name::new(
+            records
+                .into_iter()
+                .map(
+                    |((platform_id, encoding_id, language_id, name_id), string)| {
+                        NameRecord::new(
+                            platform_id,
+                            encoding_id,
+                            language_id,
+                            name_id,
+                            string.into(),
+                        )
+                    },
+                )
+                .collect(),
+        )
+    }
+}
+
+impl Default for NameBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Check that a string fits in a name record, and that PostScript names
+/// follow the restrictions in the spec.
+fn check_string(encoding: Encoding, name_id: NameId, string: &str) -> Result<(), NameBuilderError> {
+    let length = NameStringWriter { encoding, string }.encoded_len();
+    if length > u16::MAX as usize {
+        return Err(NameBuilderError::TooLong { name_id, length });
+    }
+    if name_id == NameId::POSTSCRIPT_NAME {
+        check_postscript_name(string)?;
+    }
+    Ok(())
+}
+
+/// Check that a string is a valid PostScript name.
+pub(crate) fn check_postscript_name(string: &str) -> Result<(), NameBuilderError> {
+    if string.chars().count() > MAX_POSTSCRIPT_NAME_LEN || !string.chars().all(is_postscript_char) {
+        return Err(NameBuilderError::InvalidPostScriptName(string.to_owned()));
+    }
+    Ok(())
+}
+
+/// <https://learn.microsoft.com/en-us/typography/opentype/spec/name#name-ids>
+fn is_postscript_char(c: char) -> bool {
+    matches!(c, '!'..='~') && !"[](){}<>/%".contains(c)
+}
+
+impl Display for NameBuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NameBuilderError::TooLong { name_id, length } => {
+                write!(f, "name {name_id} is too long ({length} bytes)")
+            }
+            NameBuilderError::Unencodable {
+                platform_id,
+                encoding_id,
+                name_id,
+            } => write!(
+                f,
+                "name {name_id} cannot be encoded for platform {platform_id}, encoding {encoding_id}"
+            ),
+            NameBuilderError::InvalidPostScriptName(name) => {
+                write!(f, "invalid PostScript name '{name}'")
+            }
+            NameBuilderError::NoFreeNameIds => write!(f, "no font-specific name ids are free"),
+        }
+    }
+}
+
+impl std::error::Error for NameBuilderError {}
+
 impl Name {
     ///// Sort the name records in the table.
     /////
@@ -121,6 +407,15 @@ impl NameStringWriter<'_> {
             Encoding::Unknown => 0,
         }
     }
+
+    /// The length of the encoded string, which may not fit in 16 bits.
+    fn encoded_len(&self) -> usize {
+        match self.encoding {
+            Encoding::Utf16Be => self.string.chars().map(|c| c.len_utf16() * 2).sum(),
+            Encoding::MacRoman => self.string.chars().count(),
+            Encoding::Unknown => 0,
+        }
+    }
 }
 
 impl FontWrite for NameStringAndLenWriter<'_> {
@@ -227,6 +522,124 @@ mod tests {
         assert_eq!(loaded.name_record()[2].name_id, NameId::new(1030));
     }
 
+    #[test]
+    fn builder_generates_windows_and_mac_records() {
+        let mut builder = NameBuilder::new();
+        builder
+            .add_name(NameId::new(9), "Designer")
+            .unwrap()
+            .add_name(NameId::new(10), "Ω ≈ ★")
+            .unwrap()
+            .add_record(0, 4, 0, NameId::new(9), "Designer")
+            .unwrap();
+        let table = builder.build();
+        let keys = table
+            .name_record
+            .iter()
+            .map(|rec| (rec.platform_id, rec.encoding_id, rec.name_id.to_u16()))
+            .collect::<Vec<_>>();
+        // ★ is not in Mac Roman, so name 10 is Windows only
+        assert_eq!(keys, [(0, 4, 9), (1, 0, 9), (3, 1, 9), (3, 1, 10)]);
+
+        // the identical UTF-16 strings for platforms 0 and 3 share storage
+        let bytes = crate::dump_table(&table).unwrap();
+        let loaded = read_fonts::tables::name::Name::read(FontData::new(&bytes)).unwrap();
+        let records = loaded.name_record();
+        assert_eq!(records[0].string_offset(), records[2].string_offset());
+        assert_eq!(
+            records[3]
+                .string(loaded.string_data())
+                .unwrap()
+                .chars()
+                .collect::<String>(),
+            "Ω ≈ ★"
+        );
+
+        builder.include_mac_names(false);
+        assert_eq!(builder.build().name_record.len(), 3);
+    }
+
+    #[test]
+    fn builder_rejects_bad_strings() {
+        let mut builder = NameBuilder::new();
+        assert_eq!(
+            builder
+                .add_record(1, 0, 0, NameId::new(9), "★")
+                .unwrap_err(),
+            NameBuilderError::Unencodable {
+                platform_id: 1,
+                encoding_id: 0,
+                name_id: NameId::new(9),
+            }
+        );
+        assert!(matches!(
+            builder.add_name(NameId::new(10), "x".repeat(40_000)),
+            Err(NameBuilderError::TooLong { length: 80_000, .. })
+        ));
+        assert!(matches!(
+            builder.add_name(NameId::POSTSCRIPT_NAME, "Has Space"),
+            Err(NameBuilderError::InvalidPostScriptName(_))
+        ));
+        assert!(matches!(
+            builder.add_name(NameId::POSTSCRIPT_NAME, "A".repeat(64)),
+            Err(NameBuilderError::InvalidPostScriptName(_))
+        ));
+        assert!(builder.build().name_record.is_empty());
+    }
+
+    #[test]
+    fn builder_family_and_style() {
+        let windows_names = |builder: &NameBuilder| {
+            builder
+                .build()
+                .name_record
+                .iter()
+                .filter(|rec| rec.platform_id == WINDOWS_PLATFORM_ID)
+                .map(|rec| (rec.name_id.to_u16(), rec.string.as_str().to_owned()))
+                .collect::<Vec<_>>()
+        };
+        let mut builder = NameBuilder::new();
+        builder
+            .add_family_and_style("Noto Sans", "Bold Italic")
+            .unwrap();
+        assert_eq!(
+            windows_names(&builder),
+            [
+                (1, "Noto Sans".into()),
+                (2, "Bold Italic".into()),
+                (4, "Noto Sans Bold Italic".into()),
+                (6, "NotoSans-BoldItalic".into()),
+            ]
+        );
+
+        let mut builder = NameBuilder::new();
+        builder
+            .add_family_and_style("Noto Sans", "Condensed Bold")
+            .unwrap();
+        assert_eq!(
+            windows_names(&builder),
+            [
+                (1, "Noto Sans Condensed".into()),
+                (2, "Bold".into()),
+                (4, "Noto Sans Condensed Bold".into()),
+                (6, "NotoSans-CondensedBold".into()),
+                (16, "Noto Sans".into()),
+                (17, "Condensed Bold".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_find_or_add_name() {
+        let mut builder = NameBuilder::new();
+        builder.add_name(NameId::SUBFAMILY_NAME, "Regular").unwrap();
+        let weight = builder.find_or_add_name("Weight").unwrap();
+        let regular = builder.find_or_add_name("Regular").unwrap();
+        assert_eq!(weight, NameId::new(256));
+        assert_eq!(regular, NameId::new(257));
+        assert_eq!(builder.find_or_add_name("Weight").unwrap(), weight);
+    }
+
     /// ensure we are counting characters and not bytes
     #[test]
     fn mac_str_length() {
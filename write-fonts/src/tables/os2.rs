@@ -2,7 +2,63 @@
 
 include!("../../generated/generated_os2.rs");
 
+use std::collections::BTreeSet;
+
+use super::hmtx::Hmtx;
+use crate::OtRound;
+
+mod ranges;
+
 impl Os2 {
+    /// Set the `ulUnicodeRange1..4` fields from the codepoints in the font.
+    ///
+    /// The codepoints should be those mapped by the font's 'cmap' table.
+    /// A bit is set if any codepoint is in one of the Unicode blocks it
+    /// covers, and the non-plane 0 bit (57) is set if any codepoint is
+    /// outside the Basic Multilingual Plane.
+    pub fn set_unicode_ranges(&mut self, codepoints: impl IntoIterator<Item = u32>) {
+        let codepoints = codepoints.into_iter().collect::<BTreeSet<_>>();
+        [
+            self.ul_unicode_range_1,
+            self.ul_unicode_range_2,
+            self.ul_unicode_range_3,
+            self.ul_unicode_range_4,
+        ] = ranges::unicode_ranges(&codepoints);
+    }
+
+    /// Set the `ulCodePageRange1..2` fields from the codepoints in the font.
+    ///
+    /// A code page is considered supported if the font contains the
+    /// characters that are characteristic of it, matching fontTools. If no
+    /// code page is detected, the Latin 1 bit is set.
+    pub fn set_code_page_ranges(&mut self, codepoints: impl IntoIterator<Item = u32>) {
+        let codepoints = codepoints.into_iter().collect::<BTreeSet<_>>();
+        let [range_1, range_2] = ranges::code_page_ranges(&codepoints);
+        self.ul_code_page_range_1 = Some(range_1);
+        self.ul_code_page_range_2 = Some(range_2);
+    }
+
+    /// Set `xAvgCharWidth` to the average advance width of all glyphs with
+    /// a non-zero advance, as is required for version 3 and later.
+    pub fn set_avg_char_width(&mut self, hmtx: &Hmtx) {
+        let (mut count, mut total) = (0u32, 0u64);
+        for metric in hmtx.h_metrics.iter().filter(|metric| metric.advance > 0) {
+            count += 1;
+            total += metric.advance as u64;
+        }
+        // glyphs after the long metrics share the last advance
+        if let Some(last) = hmtx.h_metrics.last().filter(|metric| metric.advance > 0) {
+            let n_short = hmtx.left_side_bearings.len();
+            count += n_short as u32;
+            total += last.advance as u64 * n_short as u64;
+        }
+        self.x_avg_char_width = if count == 0 {
+            0
+        } else {
+            (total as f64 / count as f64).ot_round()
+        };
+    }
+
     fn compute_version(&self) -> u16 {
         if self.us_lower_optical_point_size.is_some() || self.us_upper_optical_point_size.is_some()
         {
@@ -31,3 +87,39 @@ impl Os2 {
 fn convert_panose(raw: &[u8]) -> [u8; 10] {
     raw.try_into().unwrap_or_default()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::hmtx::LongMetric;
+
+    #[test]
+    fn avg_char_width() {
+        let hmtx = Hmtx::new(
+            vec![
+                LongMetric::new(0, 0),
+                LongMetric::new(500, 0),
+                LongMetric::new(600, 0),
+            ],
+            vec![0, 0],
+        );
+        let mut os2 = Os2::default();
+        os2.set_avg_char_width(&hmtx);
+        // (500 + 600 * 3) / 4
+        assert_eq!(os2.x_avg_char_width, 575);
+
+        os2.set_avg_char_width(&Hmtx::default());
+        assert_eq!(os2.x_avg_char_width, 0);
+    }
+
+    #[test]
+    fn ranges_from_codepoints() {
+        let mut os2 = Os2::default();
+        os2.set_unicode_ranges("aБ".chars().map(u32::from));
+        os2.set_code_page_ranges("aБ".chars().map(u32::from));
+        assert_eq!(os2.ul_unicode_range_1, 1 | 1 << 9);
+        assert_eq!(os2.ul_code_page_range_1, Some(1 << 2));
+        assert_eq!(os2.ul_code_page_range_2, Some(0));
+        assert_eq!(os2.compute_version(), 1);
+    }
+}
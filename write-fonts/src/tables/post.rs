@@ -52,6 +52,35 @@ impl Post {
     }
 }
 
+impl Post {
+    /// Construct a new version 3.0 table.
+    ///
+    /// Version 3.0 tables do not contain glyph names.
+    pub fn new_v3() -> Self {
+        Post {
+            version: Version16Dot16::VERSION_3_0,
+            ..Default::default()
+        }
+    }
+
+    /// Remove all glyph names, downgrading the table to version 3.0.
+    ///
+    /// This is a common size optimization for web fonts, where glyph names
+    /// are not needed. All other fields are preserved.
+    ///
+    /// Returns `true` if the table was modified.
+    pub fn strip_glyph_names(&mut self) -> bool {
+        if self.version == Version16Dot16::VERSION_3_0 {
+            return false;
+        }
+        self.version = Version16Dot16::VERSION_3_0;
+        self.num_glyphs = None;
+        self.glyph_name_index = None;
+        self.string_data = None;
+        true
+    }
+}
+
 impl std::ops::Deref for PString {
     type Target = str;
     fn deref(&self) -> &Self::Target {
@@ -133,4 +162,31 @@ mod tests {
         assert_eq!(loaded.glyph_name(GlyphId16::new(4)), Some("A"));
         assert_eq!(loaded.glyph_name(GlyphId16::new(5)), Some("flarb"));
     }
+
+    #[test]
+    fn compilev3() {
+        let post = Post::new_v3();
+        let dumped = crate::dump_table(&post).unwrap();
+        assert_eq!(dumped.len(), 32);
+        let loaded = read_fonts::tables::post::Post::read(FontData::new(&dumped)).unwrap();
+        assert_eq!(loaded.version(), Version16Dot16::VERSION_3_0);
+        assert_eq!(loaded.glyph_name(GlyphId16::new(0)), None);
+    }
+
+    #[test]
+    fn strip_glyph_names() {
+        let mut post = Post::new_v2([".notdef", "A", "flarb"]);
+        post.italic_angle = Fixed::from_i32(-12);
+        post.is_fixed_pitch = 1;
+        assert!(post.strip_glyph_names());
+        assert!(!post.strip_glyph_names());
+
+        let dumped = crate::dump_table(&post).unwrap();
+        assert_eq!(dumped.len(), 32);
+        let loaded = read_fonts::tables::post::Post::read(FontData::new(&dumped)).unwrap();
+        assert_eq!(loaded.version(), Version16Dot16::VERSION_3_0);
+        assert_eq!(loaded.italic_angle(), Fixed::from_i32(-12));
+        assert_eq!(loaded.is_fixed_pitch(), 1);
+        assert_eq!(loaded.num_glyphs(), None);
+    }
 }
@@ -2,6 +2,249 @@
 
 include!("../../generated/generated_stat.rs");
 
+use super::name::Name;
+use std::fmt::Display;
+
+/// A builder for the [`Stat`] table.
+///
+/// Axis values refer to axes by tag; these are resolved to axis indices when
+/// the table is built, at which point all names are also checked against the
+/// name table.
+#[derive(Clone, Debug)]
+pub struct StatBuilder {
+    axes: Vec<AxisRecord>,
+    axis_values: Vec<(AxisValueTableFlags, NameId, AxisValueDef)>,
+    elided_fallback_name_id: NameId,
+}
+
+/// An axis value, before axis tags are resolved.
+#[derive(Clone, Debug)]
+enum AxisValueDef {
+    Value(Tag, Fixed),
+    Range {
+        tag: Tag,
+        nominal: Fixed,
+        min: Fixed,
+        max: Fixed,
+    },
+    Linked {
+        tag: Tag,
+        value: Fixed,
+        linked_value: Fixed,
+    },
+    Location(Vec<(Tag, Fixed)>),
+}
+
+/// An error that occurs when building a [`Stat`] table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StatBuilderError {
+    /// The same axis tag was added more than once.
+    DuplicateAxis(Tag),
+    /// An axis value references an axis that was not added.
+    UnknownAxis(Tag),
+    /// A range axis value does not satisfy `min <= nominal <= max`.
+    InvalidRange { name_id: NameId },
+    /// A name id does not exist in the name table.
+    MissingName(NameId),
+}
+
+impl StatBuilder {
+    /// Create a new, empty builder.
+    ///
+    /// The elided fallback name defaults to the subfamily name (id 2).
+    pub fn new() -> Self {
+        Self {
+            axes: Vec::new(),
+            axis_values: Vec::new(),
+            elided_fallback_name_id: NameId::SUBFAMILY_NAME,
+        }
+    }
+
+    /// Add a design axis.
+    pub fn add_axis(&mut self, tag: Tag, name_id: NameId, ordering: u16) -> &mut Self {
+        self.axes.push(AxisRecord::new(tag, name_id, ordering));
+        self
+    }
+
+    /// Add a value for a single axis (format 1).
+    pub fn add_value(
+        &mut self,
+        tag: Tag,
+        name_id: NameId,
+        value: f64,
+        flags: AxisValueTableFlags,
+    ) -> &mut Self {
+        self.axis_values.push((
+            flags,
+            name_id,
+            AxisValueDef::Value(tag, Fixed::from_f64(value)),
+        ));
+        self
+    }
+
+    /// Add a value covering a range of a single axis (format 2).
+    pub fn add_range_value(
+        &mut self,
+        tag: Tag,
+        name_id: NameId,
+        nominal: f64,
+        min: f64,
+        max: f64,
+        flags: AxisValueTableFlags,
+    ) -> &mut Self {
+        self.axis_values.push((
+            flags,
+            name_id,
+            AxisValueDef::Range {
+                tag,
+                nominal: Fixed::from_f64(nominal),
+                min: Fixed::from_f64(min),
+                max: Fixed::from_f64(max),
+            },
+        ));
+        self
+    }
+
+    /// Add a value for a single axis that is linked to another value, such
+    /// as Regular and Bold (format 3).
+    pub fn add_linked_value(
+        &mut self,
+        tag: Tag,
+        name_id: NameId,
+        value: f64,
+        linked_value: f64,
+        flags: AxisValueTableFlags,
+    ) -> &mut Self {
+        self.axis_values.push((
+            flags,
+            name_id,
+            AxisValueDef::Linked {
+                tag,
+                value: Fixed::from_f64(value),
+                linked_value: Fixed::from_f64(linked_value),
+            },
+        ));
+        self
+    }
+
+    /// Add a value for a location on multiple axes (format 4).
+    pub fn add_location_value(
+        &mut self,
+        name_id: NameId,
+        location: &[(Tag, f64)],
+        flags: AxisValueTableFlags,
+    ) -> &mut Self {
+        let location = location
+            .iter()
+            .map(|(tag, value)| (*tag, Fixed::from_f64(*value)))
+            .collect();
+        self.axis_values
+            .push((flags, name_id, AxisValueDef::Location(location)));
+        self
+    }
+
+    /// Set the name used when all of the axis values of a font are elided.
+    pub fn elided_fallback_name_id(&mut self, name_id: NameId) -> &mut Self {
+        self.elided_fallback_name_id = name_id;
+        self
+    }
+
+    /// Build the table, checking that every name id exists in `name`.
+    pub fn build(&self, name: &Name) -> Result<Stat, StatBuilderError> {
+        let check_name = |name_id: NameId| {
+            if name.name_record.iter().any(|rec| rec.name_id == name_id) {
+                Ok(())
+            } else {
+                Err(StatBuilderError::MissingName(name_id))
+            }
+        };
+        for (i, axis) in self.axes.iter().enumerate() {
+            if self.axes[..i]
+                .iter()
+                .any(|prev| prev.axis_tag == axis.axis_tag)
+            {
+                return Err(StatBuilderError::DuplicateAxis(axis.axis_tag));
+            }
+            check_name(axis.axis_name_id)?;
+        }
+        check_name(self.elided_fallback_name_id)?;
+        let axis_index = |tag: Tag| {
+            self.axes
+                .iter()
+                .position(|axis| axis.axis_tag == tag)
+                .map(|i| i as u16)
+                .ok_or(StatBuilderError::UnknownAxis(tag))
+        };
+
+        let mut axis_values = Vec::with_capacity(self.axis_values.len());
+        for (flags, name_id, def) in &self.axis_values {
+            let (flags, name_id) = (*flags, *name_id);
+            check_name(name_id)?;
+            axis_values.push(match def {
+                AxisValueDef::Value(tag, value) => {
+                    AxisValue::format_1(axis_index(*tag)?, flags, name_id, *value)
+                }
+                AxisValueDef::Range {
+                    tag,
+                    nominal,
+                    min,
+                    max,
+                } => {
+                    if !(min <= nominal && nominal <= max) {
+                        return Err(StatBuilderError::InvalidRange { name_id });
+                    }
+                    AxisValue::format_2(axis_index(*tag)?, flags, name_id, *nominal, *min, *max)
+                }
+                AxisValueDef::Linked {
+                    tag,
+                    value,
+                    linked_value,
+                } => AxisValue::format_3(axis_index(*tag)?, flags, name_id, *value, *linked_value),
+                AxisValueDef::Location(location) => {
+                    let records = location
+                        .iter()
+                        .map(|(tag, value)| Ok(AxisValueRecord::new(axis_index(*tag)?, *value)))
+                        .collect::<Result<_, _>>()?;
+                    AxisValue::format_4(flags, name_id, records)
+                }
+            });
+        }
+        Ok(Stat::new(
+            self.axes.clone(),
+            axis_values,
+            self.elided_fallback_name_id,
+        ))
+    }
+}
+
+impl Default for StatBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for StatBuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatBuilderError::DuplicateAxis(tag) => write!(f, "axis '{tag}' was added twice"),
+            StatBuilderError::UnknownAxis(tag) => {
+                write!(f, "axis value references undeclared axis '{tag}'")
+            }
+            StatBuilderError::InvalidRange { name_id } => {
+                write!(
+                    f,
+                    "range for axis value {name_id} must satisfy min <= nominal <= max"
+                )
+            }
+            StatBuilderError::MissingName(name_id) => {
+                write!(f, "name {name_id} is not in the name table")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StatBuilderError {}
+
 impl Stat {
     /// Create a new STAT 1.2 table
     pub fn new(
@@ -77,4 +320,146 @@ mod tests {
         };
         assert_eq!(value2.value_name_id(), NameId::new(261));
     }
+
+    #[test]
+    fn multi_axis_value() {
+        let table = Stat::new(
+            vec![
+                AxisRecord::new(Tag::new(b"wght"), NameId::new(256), 0),
+                AxisRecord::new(Tag::new(b"opsz"), NameId::new(257), 1),
+            ],
+            vec![AxisValue::format_4(
+                AxisValueTableFlags::empty(),
+                NameId::new(258),
+                vec![
+                    AxisValueRecord::new(1, Fixed::from_f64(72.)),
+                    AxisValueRecord::new(0, Fixed::from_f64(700.)),
+                ],
+            )],
+            NameId::new(2),
+        );
+
+        let bytes = dump_table(&table).unwrap();
+        let read = read_stat::Stat::read(FontData::new(&bytes)).unwrap();
+        let axis_values = read.offset_to_axis_values().unwrap().unwrap();
+        let read_stat::AxisValue::Format4(value) = axis_values.axis_values().get(0).unwrap() else {
+            panic!("wrong format");
+        };
+        assert_eq!(value.axis_count(), 2);
+        assert_eq!(value.value_name_id(), NameId::new(258));
+        let records = value
+            .axis_values()
+            .iter()
+            .map(|record| (record.axis_index(), record.value()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            records,
+            [(1, Fixed::from_f64(72.)), (0, Fixed::from_f64(700.))]
+        );
+
+        // and round trip back to the write type
+        let owned: Stat = read.to_owned_table();
+        assert_eq!(owned, table);
+    }
+
+    fn test_names() -> Name {
+        let mut builder = crate::tables::name::NameBuilder::new();
+        builder.add_family_and_style("Test", "Regular").unwrap();
+        for name in ["Weight", "Width", "Bold", "Regular", "Condensed Bold"] {
+            builder.find_or_add_name(name).unwrap();
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn builder_resolves_axes() {
+        let (wght, wdth) = (Tag::new(b"wght"), Tag::new(b"wdth"));
+        let mut builder = StatBuilder::new();
+        builder
+            .add_axis(wght, NameId::new(256), 0)
+            .add_axis(wdth, NameId::new(257), 1)
+            .add_linked_value(
+                wght,
+                NameId::new(259),
+                400.0,
+                700.0,
+                AxisValueTableFlags::ELIDABLE_AXIS_VALUE_NAME,
+            )
+            .add_range_value(
+                wght,
+                NameId::new(258),
+                700.0,
+                600.0,
+                900.0,
+                AxisValueTableFlags::empty(),
+            )
+            .add_location_value(
+                NameId::new(260),
+                &[(wdth, 75.0), (wght, 700.0)],
+                AxisValueTableFlags::empty(),
+            );
+        let table = builder.build(&test_names()).unwrap();
+
+        let bytes = dump_table(&table).unwrap();
+        let read = read_stat::Stat::read(FontData::new(&bytes)).unwrap();
+        assert_eq!(read.elided_fallback_name_id(), Some(NameId::SUBFAMILY_NAME));
+        let axis_values = read.offset_to_axis_values().unwrap().unwrap();
+        let values = axis_values.axis_values();
+        assert!(matches!(
+            values.get(0).unwrap(),
+            read_stat::AxisValue::Format3(_)
+        ));
+        assert!(matches!(
+            values.get(1).unwrap(),
+            read_stat::AxisValue::Format2(_)
+        ));
+        let read_stat::AxisValue::Format4(location) = values.get(2).unwrap() else {
+            panic!("wrong format");
+        };
+        let indices = location
+            .axis_values()
+            .iter()
+            .map(|rec| rec.axis_index())
+            .collect::<Vec<_>>();
+        assert_eq!(indices, [1, 0]);
+    }
+
+    #[test]
+    fn builder_errors() {
+        let names = test_names();
+        let wght = Tag::new(b"wght");
+        let mut builder = StatBuilder::new();
+        builder
+            .add_axis(wght, NameId::new(256), 0)
+            .add_location_value(
+                NameId::new(258),
+                &[(Tag::new(b"ital"), 1.0)],
+                AxisValueTableFlags::empty(),
+            );
+        assert_eq!(
+            builder.build(&names).err(),
+            Some(StatBuilderError::UnknownAxis(Tag::new(b"ital")))
+        );
+
+        let mut builder = StatBuilder::new();
+        builder.add_axis(wght, NameId::new(256), 0).add_value(
+            wght,
+            NameId::new(300),
+            400.0,
+            AxisValueTableFlags::empty(),
+        );
+        assert_eq!(
+            builder.build(&names).err(),
+            Some(StatBuilderError::MissingName(NameId::new(300)))
+        );
+
+        let mut builder = StatBuilder::new();
+        builder
+            .add_axis(wght, NameId::new(256), 0)
+            .elided_fallback_name_id(NameId::new(17));
+        assert_eq!(
+            builder.build(&names).err(),
+            Some(StatBuilderError::MissingName(NameId::new(17)))
+        );
+    }
 }
@@ -0,0 +1,104 @@
+//! The [VDMX (Vertical Device Metrics)](https://learn.microsoft.com/en-us/typography/opentype/spec/vdmx) table
+
+include!("../../generated/generated_vdmx.rs");
+
+impl Vdmx {
+    /// The number of distinct groups.
+    ///
+    /// Identical groups are shared when the table is compiled.
+    fn compute_num_recs(&self) -> u16 {
+        let mut groups: Vec<&VdmxGroup> = Vec::new();
+        for group in &self.vdmx_groups {
+            if !groups.contains(&&**group) {
+                groups.push(group);
+            }
+        }
+        groups.len() as u16
+    }
+
+    fn check_groups(&self, ctx: &mut ValidationCtx) {
+        if self.version > 1 {
+            ctx.report("version must be 0 or 1");
+        }
+        if self.ratio_ranges.len() != self.vdmx_groups.len() {
+            ctx.report("each ratio range must have exactly one group");
+        }
+    }
+}
+
+impl RatioRange {
+    /// A ratio range that matches any aspect ratio.
+    ///
+    /// This is typically used as the final record in the table.
+    pub fn any() -> Self {
+        Self::new(0, 0, 0, 0)
+    }
+}
+
+impl VdmxGroup {
+    /// Construct a new group from a set of entries.
+    ///
+    /// The entries are sorted by size, and the starting and ending sizes are
+    /// computed from the entries. Sizes that do not fit in the range of a
+    /// `u8` are clamped.
+    pub fn from_entries(mut entries: Vec<VTable>) -> Self {
+        entries.sort_by_key(|entry| entry.y_pel_height);
+        let clamp = |size: u16| size.min(u8::MAX as u16) as u8;
+        let startsz = entries
+            .first()
+            .map(|entry| clamp(entry.y_pel_height))
+            .unwrap_or_default();
+        let endsz = entries
+            .last()
+            .map(|entry| clamp(entry.y_pel_height))
+            .unwrap_or_default();
+        Self::new(startsz, endsz, entries)
+    }
+
+    fn check_entries(&self, ctx: &mut ValidationCtx) {
+        if self
+            .entries
+            .windows(2)
+            .any(|pair| pair[0].y_pel_height >= pair[1].y_pel_height)
+        {
+            ctx.report("entries must be sorted by yPelHeight");
+        }
+        if self.startsz > self.endsz {
+            ctx.report("startsz must not be greater than endsz");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_groups() {
+        let group = VdmxGroup::from_entries(vec![VTable::new(12, 11, -4), VTable::new(10, 9, -3)]);
+        assert_eq!((group.startsz, group.endsz), (10, 12));
+        let table = Vdmx::new(
+            1,
+            vec![RatioRange::new(1, 1, 1, 1), RatioRange::any()],
+            vec![group.clone(), group],
+        );
+        let bytes = crate::dump_table(&table).unwrap();
+        let read = read_fonts::tables::vdmx::Vdmx::read(bytes.as_slice().into()).unwrap();
+        assert_eq!(read.num_recs(), 1);
+        assert_eq!(read.num_ratios(), 2);
+        let offsets = read.vdmx_group_offsets();
+        assert_eq!(offsets[0].get(), offsets[1].get());
+        let entry = read.extents(96, 120, 10).unwrap();
+        assert_eq!((entry.y_max(), entry.y_min()), (9, -3));
+        assert_eq!(Vdmx::read(bytes.as_slice().into()).unwrap(), table);
+    }
+
+    #[test]
+    fn validation() {
+        let table = Vdmx::new(1, vec![RatioRange::any()], vec![]);
+        assert!(crate::dump_table(&table).is_err());
+        let unsorted = VdmxGroup::new(10, 12, vec![VTable::new(12, 0, 0), VTable::new(10, 0, 0)]);
+        let table = Vdmx::new(1, vec![RatioRange::any()], vec![unsorted]);
+        assert!(crate::dump_table(&table).is_err());
+    }
+}
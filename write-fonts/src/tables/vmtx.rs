@@ -3,3 +3,21 @@
 pub use super::hmtx::LongMetric;
 
 include!("../../generated/generated_vmtx.rs");
+
+impl Vmtx {
+    /// Create a new table from the metrics of every glyph, in glyph order.
+    ///
+    /// See [`Hmtx::from_glyph_metrics`](super::hmtx::Hmtx::from_glyph_metrics);
+    /// the number of long metrics must be set as the 'vhea' table's
+    /// `numOfLongVerMetrics`, or the table added with
+    /// [`FontBuilder::add_vmtx`](crate::FontBuilder::add_vmtx).
+    pub fn from_glyph_metrics(metrics: Vec<LongMetric>) -> Self {
+        let (v_metrics, top_side_bearings) = super::hmtx::compact_metrics(metrics);
+        Vmtx::new(v_metrics, top_side_bearings)
+    }
+
+    /// The number of long metrics, as stored in `vhea.numOfLongVerMetrics`.
+    pub fn number_of_long_metrics(&self) -> u16 {
+        self.v_metrics.len() as u16
+    }
+}
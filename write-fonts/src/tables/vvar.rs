@@ -0,0 +1,75 @@
+//! The [vvar](https://learn.microsoft.com/en-us/typography/opentype/spec/vvar) table
+
+include!("../../generated/generated_vvar.rs");
+
+use super::hvar::{AdvanceDeltas, AdvanceDeltasError};
+use super::variations::{DeltaSetIndexMap, ItemVariationStore, VariationRegion};
+
+/// A builder for the [`Vvar`] table.
+///
+/// This works like the [`HvarBuilder`](super::hvar::HvarBuilder), collecting
+/// advance height deltas per glyph.
+#[derive(Clone, Debug)]
+pub struct VvarBuilder(AdvanceDeltas);
+
+impl VvarBuilder {
+    /// Create a new builder for a font with the given number of axes and glyphs.
+    pub fn new(axis_count: u16, glyph_count: u16) -> Self {
+        Self(AdvanceDeltas::new(axis_count, glyph_count))
+    }
+
+    /// Add the advance height deltas for the glyph with the given id.
+    ///
+    /// This replaces any deltas previously added for the same glyph.
+    pub fn add_advance_deltas<T: Into<i32>>(
+        &mut self,
+        glyph_id: GlyphId,
+        deltas: Vec<(VariationRegion, T)>,
+    ) -> Result<&mut Self, AdvanceDeltasError> {
+        self.0.add(glyph_id, deltas)?;
+        Ok(self)
+    }
+
+    /// Build the table.
+    pub fn build(self) -> Vvar {
+        let (store, mapping) = self.0.build();
+        Vvar::new(MajorMinor::VERSION_1_0, store, mapping, None, None, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use font_types::F2Dot14;
+    use read_fonts::{tables::vvar as read_vvar, FontRead};
+
+    use crate::dump_table;
+    use crate::tables::variations::RegionAxisCoordinates;
+
+    use super::*;
+
+    #[test]
+    fn advance_height_deltas() {
+        let region = VariationRegion::new(vec![RegionAxisCoordinates {
+            start_coord: F2Dot14::from_f32(0.0),
+            peak_coord: F2Dot14::from_f32(1.0),
+            end_coord: F2Dot14::from_f32(1.0),
+        }]);
+        let mut builder = VvarBuilder::new(1, 2);
+        builder
+            .add_advance_deltas(GlyphId::new(1), vec![(region, -40)])
+            .unwrap();
+        let vvar = builder.build();
+        assert!(vvar.advance_height_mapping.is_none());
+
+        let bytes = dump_table(&vvar).unwrap();
+        let read = read_vvar::Vvar::read(FontData::new(&bytes)).unwrap();
+        let coords = [F2Dot14::from_f32(1.0)];
+        let delta = |gid| {
+            read.advance_height_delta(GlyphId::new(gid), &coords)
+                .unwrap()
+                .to_i32()
+        };
+        assert_eq!(delta(0), 0);
+        assert_eq!(delta(1), -40);
+    }
+}
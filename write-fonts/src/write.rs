@@ -43,6 +43,32 @@ pub struct TableWriter {
     /// This should only ever be non-zero in the body of a closure passed to
     /// [adjust_offsets](Self::adjust_offsets)
     offset_adjustment: u32,
+    /// Cleared buffers that are reused when writing subtables.
+    scratch: Vec<TableData>,
+}
+
+/// Reusable scratch storage for serializing tables.
+///
+/// Each subtable is first written into a scratch buffer, which is reused for
+/// the next subtable. Unless the subtable is a duplicate of one already
+/// written, its contents are then appended to an arena shared by all of the
+/// subtables of the table, and subtables refer to each other by their index
+/// in that arena.
+///
+/// A single table always reuses its scratch buffers; passing the same
+/// `ScratchBuffers` to [`dump_table_with_buffers`] when compiling many tables
+/// also avoids re-growing the buffers and the arena for each table.
+#[derive(Debug, Default)]
+pub struct ScratchBuffers {
+    buffers: Vec<TableData>,
+    store: ObjectStore,
+}
+
+impl ScratchBuffers {
+    /// Create a new, empty set of buffers.
+    pub fn new() -> Self {
+        Self::default()
+    }
 }
 
 /// Attempt to serialize a table.
@@ -50,9 +76,20 @@ pub struct TableWriter {
 /// Returns an error if the table is malformed or cannot otherwise be serialized,
 /// otherwise it will return the bytes encoding the table.
 pub fn dump_table<T: FontWrite + Validate>(table: &T) -> Result<Vec<u8>, Error> {
+    dump_table_with_buffers(table, &mut ScratchBuffers::default())
+}
+
+/// Attempt to serialize a table, reusing the provided scratch buffers.
+///
+/// This is equivalent to [`dump_table`], but avoids repeated allocation when
+/// compiling many tables in sequence.
+pub fn dump_table_with_buffers<T: FontWrite + Validate>(
+    table: &T,
+    scratch: &mut ScratchBuffers,
+) -> Result<Vec<u8>, Error> {
     log::trace!("writing table '{}'", table.table_type());
     table.validate()?;
-    let mut graph = TableWriter::make_graph(table);
+    let mut graph = TableWriter::make_graph_with_buffers(table, scratch);
 
     if !graph.pack_objects() {
         return Err(Error::PackingFailed(PackingError {
@@ -64,18 +101,35 @@ pub fn dump_table<T: FontWrite + Validate>(table: &T) -> Result<Vec<u8>, Error>
 
 impl TableWriter {
     /// A convenience method for generating a graph with the provided root object.
+    #[cfg(test)]
     pub(crate) fn make_graph(root: &impl FontWrite) -> Graph {
-        let mut writer = TableWriter::default();
+        Self::make_graph_with_buffers(root, &mut ScratchBuffers::default())
+    }
+
+    /// Generate a graph with the provided root object, reusing `scratch` buffers.
+    fn make_graph_with_buffers(root: &impl FontWrite, scratch: &mut ScratchBuffers) -> Graph {
+        let mut writer = TableWriter {
+            tables: std::mem::take(&mut scratch.store),
+            scratch: std::mem::take(&mut scratch.buffers),
+            ..Default::default()
+        };
         let root_id = writer.add_table(root);
-        Graph::from_obj_store(writer.tables, root_id)
+        let graph = Graph::from_obj_store(&mut writer.tables, root_id);
+        scratch.buffers = writer.scratch;
+        scratch.store = writer.tables;
+        graph
     }
 
     fn add_table(&mut self, table: &dyn FontWrite) -> ObjectId {
-        self.stack.push(TableData::default());
+        self.stack.push(self.scratch.pop().unwrap_or_default());
         table.write_into(self);
         let mut table_data = self.stack.pop().unwrap();
         table_data.type_ = table.table_type();
-        self.tables.add(table_data)
+        let id = self.tables.add(&table_data);
+        table_data.bytes.clear();
+        table_data.offsets.clear();
+        self.scratch.push(table_data);
+        id
     }
 
     /// Call the provided closure, adjusting any written offsets by `adjustment`.
@@ -142,6 +196,7 @@ impl Default for TableWriter {
             tables: ObjectStore::default(),
             stack: vec![TableData::default()],
             offset_adjustment: 0,
+            scratch: Vec::new(),
         }
     }
 }
@@ -335,3 +390,80 @@ impl<T: FontWrite> FontWrite for Option<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::{
+        gpos::{
+            AnchorTable, BaseArray, BaseRecord, Gpos, MarkArray, MarkBasePosFormat1, MarkRecord,
+            PositionLookup, PositionLookupList,
+        },
+        layout::{CoverageTable, FeatureList, Lookup, LookupFlag, ScriptList},
+    };
+    use types::GlyphId16;
+
+    fn make_gpos(n_lookups: u16) -> Gpos {
+        let lookups = (0..n_lookups)
+            .map(|i| {
+                let marks: CoverageTable = (100..110).map(GlyphId16::new).collect();
+                let bases: CoverageTable = (1..20).map(GlyphId16::new).collect();
+                let mark_records = (0..10)
+                    .map(|j| MarkRecord::new(j % 2, AnchorTable::format_1(j as i16, 500)))
+                    .collect();
+                let base_records = (0..19)
+                    .map(|_| {
+                        BaseRecord::new(vec![
+                            Some(AnchorTable::format_1(i as i16, 700)),
+                            Some(AnchorTable::format_1(200, -100)),
+                        ])
+                    })
+                    .collect();
+                let subtable = MarkBasePosFormat1::new(
+                    marks,
+                    bases,
+                    MarkArray::new(mark_records),
+                    BaseArray::new(base_records),
+                );
+                PositionLookup::MarkToBase(Lookup::new(LookupFlag::empty(), vec![subtable]))
+            })
+            .collect();
+        Gpos::new(
+            ScriptList::default(),
+            FeatureList::default(),
+            PositionLookupList::new(lookups),
+        )
+    }
+
+    #[test]
+    fn reused_scratch_buffers_match_dump_table() {
+        let mut scratch = ScratchBuffers::new();
+        for n_lookups in [4, 1, 8] {
+            let gpos = make_gpos(n_lookups);
+            let expected = dump_table(&gpos).unwrap();
+            assert_eq!(
+                dump_table_with_buffers(&gpos, &mut scratch).unwrap(),
+                expected
+            );
+        }
+        // buffers are retained between tables
+        assert!(!scratch.buffers.is_empty());
+        assert!(scratch
+            .buffers
+            .iter()
+            .all(|buf| buf.bytes.is_empty() && buf.offsets.is_empty()));
+    }
+
+    #[test]
+    fn duplicate_subtables_share_ids() {
+        let mut writer = TableWriter::default();
+        writer.add_table(&make_gpos(3));
+        // the second base anchor is shared by every base record in every
+        // lookup, so it is only stored once
+        let n_objects = writer.tables.objects.len();
+        let anchor = AnchorTable::format_1(200, -100);
+        let anchor_id = writer.add_table(&anchor);
+        assert_eq!(writer.tables.objects.len(), n_objects);
+        assert_eq!(writer.add_table(&anchor), anchor_id);
+    }
+}